@@ -0,0 +1,77 @@
+# English messages of the goose CLI. This catalog is built in and used for any message a
+# translation leaves out. Translations use the same keys in a file named after the locale, such
+# as de.ftl or pt-BR.ftl; see i18n.rs for where they are looked up.
+#
+# { $name } is replaced with the value of the variable `name`. Some values arrive styled, e.g.
+# in color, and should be kept whole.
+
+## Session
+
+greeting = ready — type a message to get started
+session-closed = session closed · { $session }
+files-touched = files touched ({ $count })
+files-touched-read = read { $count }
+files-touched-written = written { $count }
+files-touched-deleted = deleted { $count }
+
+## Thinking indicator
+
+thinking = Thinking...
+interrupt-hint = (Ctrl+C to interrupt)
+
+## Extensions
+
+status-added = added
+status-failed = failed
+extension-added = { $status } extension `{ $name }`
+extension-failed = { $status } to add extension { $name }
+extension-exit-code = exited with code { $code }
+extension-exit-unknown = exit code unknown
+extension-stderr = stderr:
+extension-no-stderr = nothing on stderr
+extension-stderr-hidden = { $count } earlier lines hidden, run with --debug to see them
+builtin-added = { $status } builtin: { $names }
+builtins-added = { $status } builtins: { $names }
+builtin-failed = { $status } to add builtin: { $names }
+builtins-failed = { $status } to add builtins: { $names }
+
+## Tool approval
+
+tool-confirm-prompt = Goose would like to call the above tool, do you allow?
+tool-confirm-security-prompt = Do you allow this tool call?
+tool-confirm-batch-prompt = Goose would like to call { $count } tools. Select the ones to allow (space toggles, esc cancels)
+tool-allow = Allow
+tool-allow-hint = Allow the tool call once
+tool-always-allow = Always Allow
+tool-always-allow-hint = Always allow this tool, in this and future sessions
+extension-always-allow = Always Allow { $extension }
+extension-always-allow-hint = Always allow every tool from the { $extension } extension
+tool-deny = Deny
+tool-deny-hint = Deny the tool call
+tool-always-deny = Always Deny
+tool-always-deny-hint = Never allow this tool, in this and future sessions
+tool-cancel = Cancel
+tool-cancel-hint = Cancel the AI response and tool call
+tool-stale-file-prompt = The file changed since goose read it. Apply the edit anyway?
+tool-stale-file-apply = Apply
+tool-stale-file-apply-hint = Make the edit to the file as it is now
+tool-stale-file-reread = Re-read and retry
+tool-stale-file-reread-hint = Have goose read the file again and redo the edit
+tool-stale-file-abort = Abort
+tool-stale-file-abort-hint = Cancel the AI response and the edit
+tool-network-prompt = Let the tool connect to these hosts?
+tool-network-allow-hint = Allow this call only
+tool-network-always-allow = Always allow in this project
+tool-network-always-allow-hint = Don't ask again about these hosts in this project
+tool-network-allow-failed = Could not save the allowed hosts: { $error }
+
+## Requests for information from extensions
+
+elicitation-notification = goose is asking for information
+elicitation-cancelled = Information request cancelled.
+
+## Requests from extensions to use the model
+
+sampling-notification = An extension wants to use the model
+sampling-prompt = Let the extension use the model?
+sampling-declined = Declined the extension's request to use the model.
@@ -10,7 +10,9 @@ use goose_mcp::{
     AutoVisualiserRouter, ComputerControllerServer, DeveloperServer, MemoryServer, TutorialServer,
 };
 
+use crate::commands::ask::handle_ask;
 use crate::commands::configure::{configure_telemetry_consent_dialog, handle_configure};
+use crate::commands::extension::handle_extension_add;
 use crate::commands::info::handle_info;
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
 use crate::commands::recipe::{handle_deeplink, handle_list, handle_open, handle_validate};
@@ -20,13 +22,14 @@ use crate::commands::term::{
 
 use crate::commands::schedule::{
     handle_schedule_add, handle_schedule_cron_help, handle_schedule_list, handle_schedule_remove,
-    handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
-    handle_schedule_sessions,
+    handle_schedule_run_now, handle_schedule_serve, handle_schedule_services_status,
+    handle_schedule_services_stop, handle_schedule_sessions,
 };
 use crate::commands::session::{handle_session_list, handle_session_remove};
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
-use crate::session::{build_session, SessionBuilderConfig};
+use crate::session::{add_context_from, build_session, SessionBuilderConfig, Verbosity};
+use crate::watch::{self, Watcher};
 use goose::agents::Container;
 use goose::session::session_manager::SessionType;
 use goose::session::SessionManager;
@@ -82,6 +85,28 @@ pub struct SessionOptions {
     )]
     pub debug: bool,
 
+    #[arg(
+        long = "verbosity",
+        value_name = "LEVEL",
+        help = "How much besides the response to print (silent, minimal, normal, verbose, debug)",
+        long_help = "Controls whether tool calls, tool parameters and output, thinking and notifications are shown. Defaults to GOOSE_CLI_VERBOSITY, or normal."
+    )]
+    pub verbosity: Option<Verbosity>,
+
+    #[arg(
+        long = "show-tool-ids",
+        help = "Number tool calls and their responses",
+        long_help = "Labels each tool call with a number and marks its response with the same number, so they can be matched up in long sessions. Use /tool <number> to show a call in full."
+    )]
+    pub show_tool_ids: bool,
+
+    #[arg(
+        long = "no-redact",
+        help = "Show secrets in tool calls and output instead of masking them",
+        long_help = "Tool parameters and output are shown with API keys, tokens, passwords and the values of goose's secrets and the working directory's .env masked as [REDACTED]. Add more patterns with GOOSE_CLI_REDACT_PATTERNS. This turns masking off, for debugging."
+    )]
+    pub no_redact: bool,
+
     #[arg(
         long = "max-tool-repetitions",
         value_name = "NUMBER",
@@ -105,6 +130,28 @@ pub struct SessionOptions {
         long_help = "Run extensions (stdio and built-in) inside the specified container. The extension must exist in the container. For built-in extensions, goose must be installed inside the container."
     )]
     pub container: Option<String>,
+
+    #[arg(
+        long = "record",
+        value_name = "FILE",
+        help = "Record the session as an asciinema cast (e.g. session.cast)",
+        long_help = "Write everything the session renders, with timing, to an asciinema v2 cast file that can be replayed with `asciinema play` or embedded in docs."
+    )]
+    pub record: Option<PathBuf>,
+
+    #[arg(
+        long = "sandbox",
+        help = "Work in a git worktree or temporary copy, then review the changes before applying them",
+        long_help = "Runs the session in a detached git worktree of the current repository (or a temporary copy of the directory outside git), so shell commands and edits can't touch your checkout. When the session ends, the combined diff is shown and applied to your checkout only once you confirm."
+    )]
+    pub sandbox: bool,
+
+    #[arg(
+        long = "tui",
+        help = "Use a full screen interface with a scrollable message pane, input box and status bar",
+        long_help = "Shows the interactive session in the terminal's alternate screen: a message pane that scrolls with PgUp/PgDn, an input box (Alt-Enter for a new line) and a status bar with the model, context use and cost. Your shell's scrollback is left untouched. Can also be turned on with GOOSE_CLI_TUI=true."
+    )]
+    pub tui: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -172,7 +219,7 @@ pub struct ExtensionOptions {
 
     #[arg(
         long = "no-profile",
-        help = "Don't load your default extensions, only use CLI-specified extensions"
+        help = "Don't load your default extensions or the project profile, only use CLI-specified extensions"
     )]
     pub no_profile: bool,
 }
@@ -185,6 +232,7 @@ pub struct InputOptions {
         short,
         long,
         value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
         help = "Path to instruction file containing commands. Use - for stdin.",
         conflicts_with = "input_text",
         conflicts_with = "recipe"
@@ -208,6 +256,7 @@ pub struct InputOptions {
         short = None,
         long = "recipe",
         value_name = "RECIPE_NAME or FULL_PATH_TO_RECIPE_FILE",
+        value_hint = clap::ValueHint::FilePath,
         help = "Recipe name to get recipe file or the full path of the recipe file (use --explain to see recipe details)",
         long_help = "Recipe name to get recipe file or the full path of the recipe file that defines a custom agent configuration. Use --explain to see the recipe's title, description, and parameters.",
         conflicts_with = "instructions",
@@ -215,6 +264,50 @@ pub struct InputOptions {
     )]
     pub recipe: Option<String>,
 
+    /// Name of an extension prompt to run
+    #[arg(
+        long = "prompt",
+        value_name = "NAME",
+        help = "Run a prompt provided by an extension (see /prompts in a session)",
+        long_help = "Run a prompt provided by one of the session's extensions. Supply its arguments with --arg; missing required arguments are asked for when running in a terminal and are an error otherwise.",
+        conflicts_with = "instructions",
+        conflicts_with = "input_text",
+        conflicts_with = "recipe"
+    )]
+    pub prompt: Option<String>,
+
+    #[arg(
+        long = "arg",
+        value_name = "KEY=VALUE",
+        help = "Argument for --prompt (can be specified multiple times)",
+        action = clap::ArgAction::Append,
+        value_parser = parse_key_val,
+        requires = "prompt"
+    )]
+    pub prompt_args: Vec<(String, String)>,
+
+    /// Files to send with the first message
+    #[arg(
+        long = "file",
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        help = "Attach a file to the message (can be specified multiple times)",
+        long_help = "Attach a local file to the message. Text files are included in full, images are sent as images, and other or large files are referenced by path. Can be specified multiple times.",
+        action = clap::ArgAction::Append
+    )]
+    pub files: Vec<String>,
+
+    /// Images to send with the first message
+    #[arg(
+        long = "image",
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        help = "Attach an image to the message (can be specified multiple times)",
+        long_help = "Attach a local image (PNG, JPEG, GIF, WebP, ...) to the message, for models that accept images. Can be specified multiple times.",
+        action = clap::ArgAction::Append
+    )]
+    pub images: Vec<String>,
+
     /// Additional system prompt to customize agent behavior
     #[arg(
         long = "system",
@@ -225,6 +318,15 @@ pub struct InputOptions {
     )]
     pub system: Option<String>,
 
+    /// Earlier session to give the run as context
+    #[arg(
+        long = "context-from",
+        value_name = "SESSION_ID",
+        help = "Give the run a summary of an earlier session as context",
+        long_help = "Summarize an earlier session with the provider's fast model and add the summary to the system prompt, so this run can pick up where that one left off. The tokens the summary took are reported with the session info."
+    )]
+    pub context_from: Option<String>,
+
     #[arg(
         long,
         value_name = "KEY=VALUE",
@@ -258,6 +360,15 @@ pub struct InputOptions {
         help = "Print the rendered recipe instead of running it."
     )]
     pub render_recipe: bool,
+
+    /// Show what running the recipe would use without running it
+    #[arg(
+        long = "dry-run",
+        help = "Show the model, extensions, prompts and cost ceiling of the recipe without running it",
+        long_help = "Resolve the recipe and show the provider and model it would run with, the extensions it would load, its instructions and prompt, and the most the run could cost. Exits without starting extensions or calling the provider.",
+        requires = "recipe"
+    )]
+    pub dry_run: bool,
 }
 
 /// Output configuration options for the run command
@@ -267,19 +378,28 @@ pub struct OutputOptions {
     #[arg(
         short = 'q',
         long = "quiet",
-        help = "Quiet mode. Suppress non-response output, printing only the model response to stdout"
+        help = "Quiet mode. Suppress non-response output, printing only the model response to stdout (same as --verbosity silent)"
     )]
     pub quiet: bool,
 
-    /// Output format (text, json, stream-json)
+    /// Output format (text, json, stream-json, jsonl)
     #[arg(
         long = "output-format",
         value_name = "FORMAT",
-        help = "Output format (text, json, stream-json)",
+        help = "Output format (text, json, stream-json, jsonl)",
+        long_help = "Output format. 'stream-json' emits each agent message as a JSON line; 'jsonl' emits one JSON line per text chunk, tool request, tool response, error, cost and context usage update.",
         default_value = "text",
-        value_parser = clap::builder::PossibleValuesParser::new(["text", "json", "stream-json"])
+        value_parser = clap::builder::PossibleValuesParser::new(["text", "json", "stream-json", "jsonl"])
     )]
     pub output_format: String,
+
+    /// Write response text as it streams in
+    #[arg(
+        long = "stream-raw",
+        help = "Write response text as soon as it arrives, without markdown rendering",
+        long_help = "Write each chunk of the response as soon as it arrives, without markdown rendering or waiting for a paragraph to be complete. This is the default when stdout is not a terminal, so piping to tee shows progress live."
+    )]
+    pub stream_raw: bool,
 }
 
 impl Default for OutputOptions {
@@ -287,6 +407,7 @@ impl Default for OutputOptions {
         Self {
             quiet: false,
             output_format: "text".to_string(),
+            stream_raw: false,
         }
     }
 }
@@ -324,6 +445,15 @@ pub struct RunBehavior {
     )]
     pub interactive: bool,
 
+    /// Never prompt, for CI and other unattended runs
+    #[arg(
+        long = "non-interactive",
+        help = "Never prompt; fail fast with a distinct exit code instead",
+        long_help = "Never wait for input. Tool calls that need approval are denied, or allowed when GOOSE_CLI_NON_INTERACTIVE_APPROVAL=approve; extension requests for input fail the run. Text output is quiet; use --output-format jsonl for machine-readable output. Exit codes: 0 success, 1 other error, 3 provider authentication, 4 rate limit, 5 context overflow, 6 extension startup, 7 tool failure, 8 permission denied, 9 turn budget (--max-turns) exceeded.",
+        conflicts_with = "interactive"
+    )]
+    pub non_interactive: bool,
+
     /// Run without storing a session file
     #[arg(
         long = "no-session",
@@ -343,6 +473,16 @@ pub struct RunBehavior {
     )]
     pub resume: bool,
 
+    /// Re-run whenever files matching these globs change
+    #[arg(
+        long = "watch",
+        value_name = "GLOB",
+        help = "Run again whenever files matching the glob change; can be given more than once",
+        long_help = "Keep watching the current directory and run the instructions, recipe or prompt again, in a new session, whenever a file matching the glob is added, changed or removed. Files ignored by .gitignore are left out, and changes made during a run do not trigger another one. Stop with Ctrl+C.",
+        conflicts_with_all = ["interactive", "resume"]
+    )]
+    pub watch: Vec<String>,
+
     /// Scheduled job ID (used internally for scheduled executions)
     #[arg(
         long = "scheduled-job-id",
@@ -471,6 +611,13 @@ enum SessionCommand {
         )]
         format: String,
 
+        #[arg(
+            long,
+            conflicts_with = "format",
+            help = "Print the sessions as JSON, the same as --format json"
+        )]
+        json: bool,
+
         #[arg(
             long = "ascending",
             help = "Sort by date in ascending order (oldest first)",
@@ -482,12 +629,48 @@ enum SessionCommand {
             short = 'w',
             short_alias = 'p',
             long = "working_dir",
+            alias = "cwd",
             help = "Filter sessions by working directory"
         )]
         working_dir: Option<PathBuf>,
 
         #[arg(short = 'l', long = "limit", help = "Limit the number of results")]
         limit: Option<usize>,
+
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Only list sessions with this tag (can be specified multiple times)",
+            action = clap::ArgAction::Append
+        )]
+        tags: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "WHEN",
+            value_parser = crate::commands::session::parse_since,
+            help = "Only list sessions updated since then, e.g. 7d, 12h or 2025-06-01"
+        )]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    #[command(about = "Show a session's details, the latest session's without an ID or name")]
+    Info {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(long, help = "Print the details as JSON")]
+        json: bool,
+    },
+    #[command(about = "Add tags to a session, or remove them with --remove")]
+    Tag {
+        #[arg(help = "Session ID or name")]
+        session: String,
+
+        #[arg(required = true, help = "Tags to add")]
+        tags: Vec<String>,
+
+        #[arg(short = 'r', long, help = "Remove the tags instead of adding them")]
+        remove: bool,
     },
     #[command(about = "Remove sessions. Runs interactively if no ID, name, or regex is provided.")]
     Remove {
@@ -520,6 +703,95 @@ enum SessionCommand {
             default_value = "markdown"
         )]
         format: String,
+
+        #[arg(
+            long = "no-redact",
+            help = "Export secrets in tool calls and output instead of masking them"
+        )]
+        no_redact: bool,
+    },
+    #[command(
+        about = "Bundle a session into an archive that can be imported on another machine",
+        long_about = "Bundle a session with its metadata, subagent sessions, a transcript and the \
+                      changes its text editor calls made into a zip archive. Import it elsewhere \
+                      with `goose session import`."
+    )]
+    Bundle {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(
+            short,
+            long,
+            help = "Output file path (default: session_<id>.goose.zip in the current directory)"
+        )]
+        output: Option<PathBuf>,
+
+        #[arg(
+            long = "no-redact",
+            help = "Bundle secrets in tool calls and output instead of masking them"
+        )]
+        no_redact: bool,
+    },
+    #[command(about = "Import a session from a bundle made with `goose session bundle`")]
+    Import {
+        /// Path to the bundle
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    #[command(about = "Search the messages, tool calls and tool output of stored sessions")]
+    Grep {
+        #[arg(
+            help = "Text to search for; matches case insensitively unless it contains uppercase letters"
+        )]
+        pattern: String,
+
+        #[arg(short = 'e', long, help = "Treat the pattern as a regular expression")]
+        regex: bool,
+
+        #[arg(
+            short = 'w',
+            long = "working_dir",
+            help = "Only search sessions from this working directory"
+        )]
+        working_dir: Option<PathBuf>,
+
+        #[arg(
+            short = 'l',
+            long = "limit",
+            default_value_t = 50,
+            help = "Stop after this many matches"
+        )]
+        limit: usize,
+    },
+    #[command(about = "Replay a session with its original timing")]
+    Replay {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(
+            long,
+            default_value_t = 1.0,
+            help = "Playback speed multiplier (e.g. 2 for twice as fast, 0 for no pauses)"
+        )]
+        speed: f64,
+
+        #[arg(
+            long = "max-pause",
+            value_name = "SECONDS",
+            default_value_t = 5.0,
+            help = "Longest pause between messages, before applying --speed"
+        )]
+        max_pause: f64,
+    },
+    #[command(about = "Connect to a session started with `goose session --serve`")]
+    Attach {
+        /// ID of the served session
+        #[arg(value_name = "SESSION_ID")]
+        session_id: String,
+
+        #[arg(long, help = "Show complete tool responses without truncation")]
+        debug: bool,
     },
     #[command(name = "diagnostics")]
     Diagnostics {
@@ -537,20 +809,28 @@ enum SessionCommand {
 enum SchedulerCommand {
     #[command(about = "Add a new scheduled job")]
     Add {
+        #[arg(
+            value_name = "CRON",
+            required_unless_present = "cron",
+            help = "Cron expression for when to run the recipe, e.g. \"0 9 * * 1\" for Mondays at 9:00"
+        )]
+        schedule: Option<String>,
         #[arg(
             long = "schedule-id",
             alias = "id",
-            help = "Unique ID for the recurring scheduled job"
+            help = "Unique ID for the recurring scheduled job (defaults to the recipe's file name)"
         )]
-        schedule_id: String,
+        schedule_id: Option<String>,
         #[arg(
             long,
+            conflicts_with = "schedule",
             help = "Cron expression for the schedule",
             long_help = "Cron expression for when to run the job. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight"
         )]
-        cron: String,
+        cron: Option<String>,
         #[arg(
-            long,
+            long = "recipe-source",
+            visible_alias = "recipe",
             help = "Recipe source (path to file, or base64 encoded recipe string)"
         )]
         recipe_source: String,
@@ -575,6 +855,8 @@ enum SchedulerCommand {
         #[arg(short = 'l', long, help = "Maximum number of sessions to return")]
         limit: Option<usize>,
     },
+    #[command(about = "Keep running and run scheduled jobs when they are due")]
+    Serve {},
     #[command(about = "Run a scheduled job immediately")]
     RunNow {
         /// ID of the schedule to run
@@ -658,18 +940,52 @@ enum RecipeCommand {
     },
 }
 
+#[derive(Subcommand)]
+enum ExtensionCommand {
+    /// Add an MCP extension
+    #[command(
+        about = "Add an extension",
+        long_about = "Adds an MCP extension to the configuration.\n\n\
+                      With --interactive, walks through the transport, the command or URL and the\n\
+                      rest of the settings, then connects to the extension and lists its tools and\n\
+                      prompts before saving it."
+    )]
+    Add {
+        /// Test the connection and show what the extension provides before saving it
+        #[arg(
+            short,
+            long,
+            help = "Guided setup that tests the connection before saving"
+        )]
+        interactive: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Configure goose settings
     #[command(about = "Configure goose settings")]
     Configure {},
 
+    /// Manage extensions
+    #[command(about = "Manage extensions", visible_alias = "ext")]
+    Extension {
+        #[command(subcommand)]
+        command: ExtensionCommand,
+    },
+
     /// Display goose configuration information
     #[command(about = "Display goose information")]
     Info {
         /// Show verbose information including current configuration
         #[arg(short, long, help = "Show verbose information including config.yaml")]
         verbose: bool,
+
+        #[arg(
+            long,
+            help = "Print the information, the provider and the extensions as JSON"
+        )]
+        json: bool,
     },
 
     /// Manage system prompts and behaviors
@@ -731,6 +1047,15 @@ enum Command {
         )]
         history: bool,
 
+        /// Serve the session over a local socket instead of using the terminal
+        #[arg(
+            long,
+            conflicts_with = "tui",
+            help = "Run the session without a terminal, for `goose session attach` to connect to",
+            long_help = "Runs the session in the background of this process, listening on a local socket, until it is stopped. Clients attached with `goose session attach <id>` see the conversation live and can send messages; tool calls that need approval are answered by GOOSE_CLI_NON_INTERACTIVE_APPROVAL. Start it under nohup, tmux or a service manager to keep it running after logging out."
+        )]
+        serve: bool,
+
         #[command(flatten)]
         session_opts: SessionOptions,
 
@@ -771,6 +1096,32 @@ enum Command {
         model_opts: ModelOptions,
     },
 
+    /// Ask a single question and print only the answer
+    #[command(
+        about = "Ask a one-off question and print only the answer",
+        long_about = "Runs a single exchange without keeping a session and prints only the final answer,\n\
+                      for use in shell pipelines. Input piped on stdin is added after the question.\n\n\
+                      Examples:\n  \
+                        goose ask \"what does this regex match: ^a+b?$\"\n  \
+                        git diff | goose ask \"write a commit message for this\"\n  \
+                        goose ask --json \"capital of France?\" | jq -r .answer"
+    )]
+    Ask {
+        /// The question (multiple words allowed without quotes); read from stdin when omitted
+        #[arg(value_name = "QUESTION", num_args = 0..)]
+        question: Vec<String>,
+
+        /// Print the answer and token usage as a JSON object
+        #[arg(long, help = "Print the answer and token usage as a JSON object")]
+        json: bool,
+
+        #[command(flatten)]
+        extension_opts: ExtensionOptions,
+
+        #[command(flatten)]
+        model_opts: ModelOptions,
+    },
+
     /// Recipe utilities for validation and deeplinking
     #[command(about = "Recipe utilities for validation and deeplinking")]
     Recipe {
@@ -942,6 +1293,7 @@ pub struct InputConfig {
 fn get_command_name(command: &Option<Command>) -> &'static str {
     match command {
         Some(Command::Configure {}) => "configure",
+        Some(Command::Extension { .. }) => "extension",
         Some(Command::Info { .. }) => "info",
         Some(Command::Mcp { .. }) => "mcp",
         Some(Command::Acp { .. }) => "acp",
@@ -949,6 +1301,7 @@ fn get_command_name(command: &Option<Command>) -> &'static str {
         Some(Command::Project {}) => "project",
         Some(Command::Projects) => "projects",
         Some(Command::Run { .. }) => "run",
+        Some(Command::Ask { .. }) => "ask",
         Some(Command::Schedule { .. }) => "schedule",
         Some(Command::Update { .. }) => "update",
         Some(Command::Recipe { .. }) => "recipe",
@@ -976,11 +1329,35 @@ async fn handle_session_subcommand(command: SessionCommand) -> Result<()> {
     match command {
         SessionCommand::List {
             format,
+            json,
             ascending,
             working_dir,
             limit,
+            tags,
+            since,
         } => {
-            handle_session_list(format, ascending, working_dir, limit).await?;
+            let format = if json { "json".to_string() } else { format };
+            handle_session_list(format, ascending, working_dir, limit, tags, since).await?;
+        }
+        SessionCommand::Info { identifier, json } => {
+            let session_id = match identifier {
+                Some(id) => Some(lookup_session_id(id).await?),
+                None => None,
+            };
+            crate::commands::session::handle_session_info(session_id, json).await?;
+        }
+        SessionCommand::Tag {
+            session,
+            tags,
+            remove,
+        } => {
+            let session_id = lookup_session_id(Identifier {
+                name: Some(session),
+                session_id: None,
+                path: None,
+            })
+            .await?;
+            crate::commands::session::handle_session_tag(session_id, tags, remove).await?;
         }
         SessionCommand::Remove { identifier, regex } => {
             let (session_id, name) = if let Some(id) = identifier {
@@ -994,6 +1371,7 @@ async fn handle_session_subcommand(command: SessionCommand) -> Result<()> {
             identifier,
             output,
             format,
+            no_redact,
         } => {
             let session_manager = SessionManager::instance();
             let session_identifier = if let Some(id) = identifier {
@@ -1011,9 +1389,76 @@ async fn handle_session_subcommand(command: SessionCommand) -> Result<()> {
                     }
                 }
             };
-            crate::commands::session::handle_session_export(session_identifier, output, format)
+            crate::commands::session::handle_session_export(
+                session_identifier,
+                output,
+                format,
+                !no_redact,
+            )
+            .await?;
+        }
+        SessionCommand::Bundle {
+            identifier,
+            output,
+            no_redact,
+        } => {
+            let session_manager = SessionManager::instance();
+            let session_id = if let Some(id) = identifier {
+                lookup_session_id(id).await?
+            } else {
+                match crate::commands::session::prompt_interactive_session_selection(
+                    &session_manager,
+                )
+                .await
+                {
+                    Ok(id) => id,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        return Ok(());
+                    }
+                }
+            };
+            crate::commands::session::handle_session_bundle(&session_id, output, !no_redact)
+                .await?;
+        }
+        SessionCommand::Import { path } => {
+            crate::commands::session::handle_session_import(&path).await?;
+        }
+        SessionCommand::Grep {
+            pattern,
+            regex,
+            working_dir,
+            limit,
+        } => {
+            crate::commands::session_grep::handle_session_grep(pattern, regex, working_dir, limit)
                 .await?;
         }
+        SessionCommand::Replay {
+            identifier,
+            speed,
+            max_pause,
+        } => {
+            let session_manager = SessionManager::instance();
+            let session_id = if let Some(id) = identifier {
+                lookup_session_id(id).await?
+            } else {
+                match crate::commands::session::prompt_interactive_session_selection(
+                    &session_manager,
+                )
+                .await
+                {
+                    Ok(id) => id,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        return Ok(());
+                    }
+                }
+            };
+            crate::commands::session::handle_session_replay(&session_id, speed, max_pause).await?;
+        }
+        SessionCommand::Attach { session_id, debug } => {
+            crate::session::attach_to_session(&session_id, debug).await?;
+        }
         SessionCommand::Diagnostics { identifier, output } => {
             let session_manager = SessionManager::instance();
             let session_id = if let Some(id) = identifier {
@@ -1042,6 +1487,7 @@ async fn handle_interactive_session(
     resume: bool,
     fork: bool,
     history: bool,
+    serve: bool,
     session_opts: SessionOptions,
     extension_opts: ExtensionOptions,
 ) -> Result<()> {
@@ -1104,22 +1550,40 @@ async fn handle_interactive_session(
         max_tool_repetitions: session_opts.max_tool_repetitions,
         max_turns: session_opts.max_turns,
         scheduled_job_id: None,
-        interactive: true,
-        quiet: false,
+        interactive: !serve,
+        non_interactive: serve,
+        verbosity: session_opts.verbosity,
+        show_tool_ids: session_opts.show_tool_ids,
+        no_redact: session_opts.no_redact,
         output_format: "text".to_string(),
+        stream_raw: false,
         container: session_opts.container.map(Container::new),
+        record: session_opts.record,
+        sandbox: session_opts.sandbox,
+        tui: session_opts.tui,
     })
     .await;
 
-    if (resume || fork) && history {
+    if (resume || fork) && history && !serve {
         session.render_message_history();
     }
 
-    let result = session.interactive(None).await;
+    let result = if serve {
+        session.serve().await
+    } else {
+        session.interactive(None).await
+    };
     log_session_completion(&session, session_start, session_type, result.is_ok()).await;
+    finish_sandbox(&mut session).await;
     result
 }
 
+async fn finish_sandbox(session: &mut crate::CliSession) {
+    if let Err(e) = session.close_sandbox().await {
+        eprintln!("Failed to close the sandbox: {}", e);
+    }
+}
+
 async fn log_session_completion(
     session: &crate::CliSession,
     session_start: std::time::Instant,
@@ -1164,6 +1628,16 @@ fn parse_run_input(
     input_opts: &InputOptions,
     quiet: bool,
 ) -> Result<Option<(InputConfig, Option<Recipe>)>> {
+    if input_opts.prompt.is_some() {
+        return Ok(Some((
+            InputConfig {
+                contents: None,
+                additional_system_prompt: input_opts.system.clone(),
+            },
+            None,
+        )));
+    }
+
     match (
         &input_opts.instructions,
         &input_opts.input_text,
@@ -1253,7 +1727,7 @@ fn parse_run_input(
             Ok(Some((input_config, Some(recipe))))
         }
         (None, None, None) => {
-            eprintln!("Error: Must provide either --instructions (-i), --text (-t), or --recipe. Use -i - for stdin.");
+            eprintln!("Error: Must provide either --instructions (-i), --text (-t), --recipe, or --prompt. Use -i - for stdin.");
             std::process::exit(1);
         }
     }
@@ -1272,7 +1746,10 @@ async fn handle_run_command(
         configure_telemetry_consent_dialog()?;
     }
 
-    let parsed = parse_run_input(&input_opts, output_opts.quiet)?;
+    let parsed = parse_run_input(
+        &input_opts,
+        output_opts.quiet || run_behavior.non_interactive,
+    )?;
 
     let Some((input_config, recipe)) = parsed else {
         return Ok(());
@@ -1289,10 +1766,19 @@ async fn handle_run_command(
         }
     }
 
-    let session_id =
-        get_or_create_session_id(identifier, run_behavior.resume, run_behavior.no_session).await?;
+    // a dry run doesn't create a session to run in
+    let session_id = if input_opts.dry_run {
+        None
+    } else {
+        get_or_create_session_id(
+            identifier.clone(),
+            run_behavior.resume,
+            run_behavior.no_session,
+        )
+        .await?
+    };
 
-    let mut session = build_session(SessionBuilderConfig {
+    let session_config = SessionBuilderConfig {
         session_id,
         resume: run_behavior.resume,
         fork: false,
@@ -1310,13 +1796,52 @@ async fn handle_run_command(
         max_turns: session_opts.max_turns,
         scheduled_job_id: run_behavior.scheduled_job_id,
         interactive: run_behavior.interactive,
-        quiet: output_opts.quiet,
+        non_interactive: run_behavior.non_interactive,
+        verbosity: session_opts
+            .verbosity
+            .or((output_opts.quiet || run_behavior.non_interactive).then_some(Verbosity::Silent)),
+        show_tool_ids: session_opts.show_tool_ids,
+        no_redact: session_opts.no_redact,
         output_format: output_opts.output_format,
+        stream_raw: output_opts.stream_raw,
         container: session_opts.container.map(Container::new),
-    })
-    .await;
+        record: session_opts.record,
+        sandbox: session_opts.sandbox,
+        tui: session_opts.tui,
+    };
 
-    if run_behavior.interactive {
+    if input_opts.dry_run {
+        crate::session::dry_run(&session_config);
+        return Ok(());
+    }
+
+    if !run_behavior.watch.is_empty() {
+        return handle_watch_run(
+            &run_behavior,
+            identifier,
+            session_config,
+            &input_opts,
+            input_config.contents,
+            recipe.is_some(),
+        )
+        .await;
+    }
+
+    let mut session = build_session(session_config).await;
+    use_context_from(&mut session, &input_opts).await?;
+    attach_files(&mut session, &input_opts)?;
+
+    let result = if let Some(prompt) = &input_opts.prompt {
+        let arguments = input_opts.prompt_args.into_iter().collect();
+        let session_start = std::time::Instant::now();
+        let result = session.headless_prompt(prompt, arguments).await;
+        log_session_completion(&session, session_start, "prompt", result.is_ok()).await;
+        if run_behavior.interactive && result.is_ok() {
+            session.interactive(None).await
+        } else {
+            result
+        }
+    } else if run_behavior.interactive {
         session.interactive(input_config.contents).await
     } else if let Some(contents) = input_config.contents {
         let session_start = std::time::Instant::now();
@@ -1336,21 +1861,110 @@ async fn handle_run_command(
         Err(anyhow::anyhow!(
             "no text provided for prompt in headless mode"
         ))
+    };
+    finish_sandbox(&mut session).await;
+    result
+}
+
+/// Gives the agent the summary of the session named with `--context-from`, dropping the sandbox
+/// when that fails as the run doesn't go ahead without it
+async fn use_context_from(
+    session: &mut crate::CliSession,
+    input_opts: &InputOptions,
+) -> Result<()> {
+    let Some(source_id) = &input_opts.context_from else {
+        return Ok(());
+    };
+    if let Err(e) = add_context_from(session, source_id).await {
+        session.discard_sandbox();
+        return Err(e.context("Failed to use --context-from"));
+    }
+    Ok(())
+}
+
+/// Adds the files given with `--file` and `--image` to the first message, failing if one can't
+/// be attached rather than sending the message without it
+fn attach_files(session: &mut crate::CliSession, input_opts: &InputOptions) -> Result<()> {
+    let files = input_opts.files.iter().map(|path| (path, false));
+    let images = input_opts.images.iter().map(|path| (path, true));
+    for (path, image) in files.chain(images) {
+        if let Err(e) = session.attach(path, image) {
+            session.discard_sandbox();
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Runs once, then again in a new session each time watched files change, until Ctrl+C.
+async fn handle_watch_run(
+    run_behavior: &RunBehavior,
+    identifier: Option<Identifier>,
+    mut session_config: SessionBuilderConfig,
+    input_opts: &InputOptions,
+    contents: Option<String>,
+    is_recipe: bool,
+) -> Result<()> {
+    let mut watcher = Watcher::new(&std::env::current_dir()?, &run_behavior.watch)?;
+    let session_type = if is_recipe { "recipe" } else { "run" };
+    let mut run = 1;
+    loop {
+        if run > 1 {
+            session_config.session_id =
+                get_or_create_session_id(identifier.clone(), false, run_behavior.no_session)
+                    .await?;
+        }
+        let mut session = build_session(session_config.clone()).await;
+        use_context_from(&mut session, input_opts).await?;
+        attach_files(&mut session, input_opts)?;
+        let session_start = std::time::Instant::now();
+        let result = if let Some(prompt) = &input_opts.prompt {
+            let arguments = input_opts.prompt_args.iter().cloned().collect();
+            session.headless_prompt(prompt, arguments).await
+        } else if let Some(contents) = &contents {
+            session.headless(contents.clone()).await
+        } else {
+            Err(anyhow::anyhow!(
+                "no text provided for prompt in headless mode"
+            ))
+        };
+        log_session_completion(&session, session_start, session_type, result.is_ok()).await;
+        finish_sandbox(&mut session).await;
+        // keep watching, the next change may well fix it
+        if let Err(e) = result {
+            eprintln!("Error: {:#}", e);
+        }
+
+        // what the run itself changed shouldn't start another
+        watcher.poll();
+        watch::render_waiting(&run_behavior.watch);
+        let changes = tokio::select! {
+            changes = watcher.wait_for_changes() => changes,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        run += 1;
+        watch::render_separator(run, &changes);
     }
 }
 
 async fn handle_schedule_command(command: SchedulerCommand) -> Result<()> {
     match command {
         SchedulerCommand::Add {
+            schedule,
             schedule_id,
             cron,
             recipe_source,
-        } => handle_schedule_add(schedule_id, cron, recipe_source).await,
+        } => {
+            // clap makes sure exactly one of them is given
+            let cron = schedule.or(cron).unwrap_or_default();
+            handle_schedule_add(schedule_id, cron, recipe_source).await
+        }
         SchedulerCommand::List {} => handle_schedule_list().await,
         SchedulerCommand::Remove { schedule_id } => handle_schedule_remove(schedule_id).await,
         SchedulerCommand::Sessions { schedule_id, limit } => {
             handle_schedule_sessions(schedule_id, limit).await
         }
+        SchedulerCommand::Serve {} => handle_schedule_serve().await,
         SchedulerCommand::RunNow { schedule_id } => handle_schedule_run_now(schedule_id).await,
         SchedulerCommand::ServicesStatus {} => handle_schedule_services_status().await,
         SchedulerCommand::ServicesStop {} => handle_schedule_services_stop().await,
@@ -1418,9 +2032,16 @@ async fn handle_default_session() -> Result<()> {
         max_turns: None,
         scheduled_job_id: None,
         interactive: true,
-        quiet: false,
+        non_interactive: false,
+        verbosity: None,
+        show_tool_ids: false,
+        no_redact: false,
         output_format: "text".to_string(),
+        stream_raw: false,
         container: None,
+        record: None,
+        sandbox: false,
+        tui: false,
     })
     .await;
     session.interactive(None).await
@@ -1449,7 +2070,10 @@ pub async fn cli() -> anyhow::Result<()> {
             Ok(())
         }
         Some(Command::Configure {}) => handle_configure().await,
-        Some(Command::Info { verbose }) => handle_info(verbose),
+        Some(Command::Extension {
+            command: ExtensionCommand::Add { interactive },
+        }) => handle_extension_add(interactive).await,
+        Some(Command::Info { verbose, json }) => handle_info(verbose, json),
         Some(Command::Mcp { server }) => handle_mcp_command(server).await,
         Some(Command::Acp { builtins }) => goose_acp::server::run(builtins).await,
         Some(Command::Session {
@@ -1461,6 +2085,7 @@ pub async fn cli() -> anyhow::Result<()> {
             resume,
             fork,
             history,
+            serve,
             session_opts,
             extension_opts,
         }) => {
@@ -1469,6 +2094,7 @@ pub async fn cli() -> anyhow::Result<()> {
                 resume,
                 fork,
                 history,
+                serve,
                 session_opts,
                 extension_opts,
             )
@@ -1502,6 +2128,27 @@ pub async fn cli() -> anyhow::Result<()> {
             )
             .await
         }
+        Some(Command::Ask {
+            question,
+            json,
+            extension_opts,
+            model_opts,
+        }) => {
+            handle_ask(
+                question,
+                json,
+                SessionBuilderConfig {
+                    extensions: extension_opts.extensions,
+                    streamable_http_extensions: extension_opts.streamable_http_extensions,
+                    builtins: extension_opts.builtins,
+                    no_profile: extension_opts.no_profile,
+                    provider: model_opts.provider,
+                    model: model_opts.model,
+                    ..Default::default()
+                },
+            )
+            .await
+        }
         Some(Command::Schedule { command }) => handle_schedule_command(command).await,
         Some(Command::Update {
             canary,
@@ -0,0 +1,73 @@
+use anyhow::{bail, Result};
+use std::io::{IsTerminal, Read};
+
+use crate::session::renderer::AnswerRenderer;
+use crate::session::term_writer::StderrWriter;
+use crate::session::{build_session, set_term_writer, SessionBuilderConfig, Verbosity};
+
+/// The question from the arguments, followed by whatever was piped in, so both
+/// `goose ask "why?" < log` and `echo "why?" | goose ask` work.
+fn question_text(args: &[String], piped: Option<String>) -> Option<String> {
+    let args = args.join(" ");
+    let args = args.trim();
+    let piped = piped.as_deref().map(str::trim).unwrap_or_default();
+    match (args.is_empty() || args == "-", piped.is_empty()) {
+        (true, true) => None,
+        (true, false) => Some(piped.to_string()),
+        (false, true) => Some(args.to_string()),
+        (false, false) => Some(format!("{}\n\n{}", args, piped)),
+    }
+}
+
+/// Handle `goose ask`: one exchange with no session file, printing only the answer.
+pub async fn handle_ask(
+    question: Vec<String>,
+    json: bool,
+    config: SessionBuilderConfig,
+) -> Result<()> {
+    let stdin = std::io::stdin();
+    let piped = if stdin.is_terminal() {
+        None
+    } else {
+        let mut input = String::new();
+        stdin.lock().read_to_string(&mut input)?;
+        Some(input)
+    };
+    let Some(question) = question_text(&question, piped) else {
+        bail!("Nothing to ask: pass a question or pipe one in on stdin");
+    };
+
+    // anything besides the answer, such as errors, goes to stderr
+    set_term_writer(Box::new(StderrWriter));
+    let mut session = build_session(SessionBuilderConfig {
+        session_id: None,
+        no_session: true,
+        interactive: false,
+        non_interactive: true,
+        verbosity: Some(Verbosity::Silent),
+        output_format: "answer".to_string(),
+        ..config
+    })
+    .await;
+    session.set_renderer(Box::new(AnswerRenderer::new(std::io::stdout(), json)));
+    session.headless(question).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(&["why", "is", "it", "red?"], None, Some("why is it red?") ; "arguments")]
+    #[test_case(&[], Some("summarize this\n"), Some("summarize this") ; "stdin")]
+    #[test_case(&["-"], Some("from stdin"), Some("from stdin") ; "dash reads stdin")]
+    #[test_case(&["what failed?"], Some("error: boom\n"), Some("what failed?\n\nerror: boom") ; "both")]
+    #[test_case(&[], Some("  \n"), None ; "nothing")]
+    fn test_question_text(args: &[&str], piped: Option<&str>, expected: Option<&str>) {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            question_text(&args, piped.map(str::to_string)).as_deref(),
+            expected
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::project_profile::{self, profile_path, ProjectProfile};
 use crate::recipes::github_recipe::GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY;
 use cliclack::spinner;
 use console::style;
@@ -306,6 +307,11 @@ async fn handle_existing_config() -> anyhow::Result<()> {
             "goose settings",
             "Set the goose mode, Tool Output, Tool Permissions, Experiment, goose recipe github repo and more",
         )
+        .item(
+            "project",
+            "Project Profile",
+            "Pin the model, extensions, permissions and theme for this repository",
+        )
         .interact()?;
 
     match action {
@@ -315,6 +321,7 @@ async fn handle_existing_config() -> anyhow::Result<()> {
         "settings" => configure_settings_dialog().await,
         "providers" => configure_provider_dialog().await.map(|_| ()),
         "custom_providers" => configure_custom_provider_dialog(),
+        "project" => configure_project_profile_dialog(),
         _ => unreachable!(),
     }
 }
@@ -805,7 +812,7 @@ pub fn toggle_extensions_dialog() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn prompt_extension_timeout() -> anyhow::Result<u64> {
+pub fn prompt_extension_timeout() -> anyhow::Result<u64> {
     Ok(
         cliclack::input("Please set the timeout for this tool (in secs):")
             .placeholder(&goose::config::DEFAULT_EXTENSION_TIMEOUT.to_string())
@@ -817,7 +824,7 @@ fn prompt_extension_timeout() -> anyhow::Result<u64> {
     )
 }
 
-fn prompt_extension_description() -> anyhow::Result<String> {
+pub fn prompt_extension_description() -> anyhow::Result<String> {
     Ok(cliclack::input("Enter a description for this extension:")
         .placeholder("Description")
         .validate(|input: &String| {
@@ -830,7 +837,7 @@ fn prompt_extension_description() -> anyhow::Result<String> {
         .interact()?)
 }
 
-fn prompt_extension_name(placeholder: &str) -> anyhow::Result<String> {
+pub fn prompt_extension_name(placeholder: &str) -> anyhow::Result<String> {
     let extensions = get_all_extension_names();
     Ok(
         cliclack::input("What would you like to call this extension?")
@@ -848,13 +855,17 @@ fn prompt_extension_name(placeholder: &str) -> anyhow::Result<String> {
     )
 }
 
-fn collect_env_vars() -> anyhow::Result<(HashMap<String, String>, Vec<String>)> {
-    let envs = HashMap::new();
-    let mut env_keys = Vec::new();
-    let config = Config::global();
+pub fn collect_env_vars() -> anyhow::Result<(HashMap<String, String>, Vec<String>)> {
+    let env_keys = store_env_vars(prompt_env_vars()?)?;
+    Ok((HashMap::new(), env_keys))
+}
+
+/// Asks for environment variables and their values, without storing them
+pub fn prompt_env_vars() -> anyhow::Result<HashMap<String, String>> {
+    let mut envs = HashMap::new();
 
     if !cliclack::confirm("Would you like to add environment variables?").interact()? {
-        return Ok((envs, env_keys));
+        return Ok(envs);
     }
 
     loop {
@@ -866,20 +877,30 @@ fn collect_env_vars() -> anyhow::Result<(HashMap<String, String>, Vec<String>)>
             .mask('▪')
             .interact()?;
 
-        if !try_store_secret(config, &key, value)? {
-            return Err(anyhow::anyhow!("Failed to store secret"));
-        }
-        env_keys.push(key);
+        envs.insert(key, value);
 
         if !cliclack::confirm("Add another environment variable?").interact()? {
             break;
         }
     }
 
-    Ok((envs, env_keys))
+    Ok(envs)
+}
+
+/// Stores environment variable values as secrets, returning their names
+pub fn store_env_vars(envs: HashMap<String, String>) -> anyhow::Result<Vec<String>> {
+    let config = Config::global();
+    let mut env_keys = Vec::with_capacity(envs.len());
+    for (key, value) in envs {
+        if !try_store_secret(config, &key, value)? {
+            return Err(anyhow::anyhow!("Failed to store secret"));
+        }
+        env_keys.push(key);
+    }
+    Ok(env_keys)
 }
 
-fn collect_headers() -> anyhow::Result<HashMap<String, String>> {
+pub fn collect_headers() -> anyhow::Result<HashMap<String, String>> {
     let mut headers = HashMap::new();
 
     if !cliclack::confirm("Would you like to add custom headers?").interact()? {
@@ -1157,6 +1178,11 @@ pub async fn configure_settings_dialog() -> anyhow::Result<()> {
             "Tool Output",
             "Show more or less tool output",
         )
+        .item(
+            "edit_mode",
+            "Input Editing",
+            "Emacs-style or vi-style editing of the prompt",
+        )
         .item(
             "max_turns",
             "Max Turns",
@@ -1196,6 +1222,9 @@ pub async fn configure_settings_dialog() -> anyhow::Result<()> {
         "tool_output" => {
             configure_tool_output_dialog()?;
         }
+        "edit_mode" => {
+            configure_edit_mode_dialog()?;
+        }
         "max_turns" => {
             configure_max_turns_dialog()?;
         }
@@ -1218,6 +1247,124 @@ pub async fn configure_settings_dialog() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Edits `.goose/profile.toml` at the root of the repository the current directory is in.
+pub fn configure_project_profile_dialog() -> anyhow::Result<()> {
+    let path = profile_path(&std::env::current_dir()?);
+    let mut profile = if path.is_file() {
+        ProjectProfile::load(&path)?
+    } else {
+        ProjectProfile::default()
+    };
+    let _ = cliclack::log::info(format!("Project profile: {}", path.display()));
+
+    let setting = cliclack::select("What should sessions in this project use?")
+        .item("model", "Model", "Provider and model")
+        .item("mode", "goose mode", "Auto, approve, smart approve or chat")
+        .item("theme", "Theme", "Light, dark or ansi")
+        .item(
+            "extensions",
+            "Extensions",
+            "Which configured extensions to load",
+        )
+        .item(
+            "tool_permission",
+            "Tool Permission",
+            "Permission for a tool, or every tool of an extension",
+        )
+        .interact()?;
+
+    let optional = |text: String| Some(text.trim().to_string()).filter(|t| !t.is_empty());
+    match setting {
+        "model" => {
+            profile.provider = optional(
+                cliclack::input("Provider (leave empty for the global one):")
+                    .default_input(profile.provider.as_deref().unwrap_or_default())
+                    .required(false)
+                    .interact()?,
+            );
+            profile.model = optional(
+                cliclack::input("Model (leave empty for the global one):")
+                    .default_input(profile.model.as_deref().unwrap_or_default())
+                    .required(false)
+                    .interact()?,
+            );
+        }
+        "mode" => {
+            profile.mode = cliclack::select("Which goose mode should this project use?")
+                .item(None, "Global", "Use the goose mode from the global config")
+                .item(Some(GooseMode::Auto), "Auto Mode", "")
+                .item(Some(GooseMode::Approve), "Approve Mode", "")
+                .item(Some(GooseMode::SmartApprove), "Smart Approve Mode", "")
+                .item(Some(GooseMode::Chat), "Chat Mode", "")
+                .initial_value(profile.mode)
+                .interact()?;
+        }
+        "theme" => {
+            let theme = cliclack::select("Which theme should this project use?")
+                .item("", "Global", "Use the theme from the global config")
+                .item("light", "Light", "")
+                .item("dark", "Dark", "")
+                .item("ansi", "ANSI", "")
+                .interact()?;
+            profile.theme = optional(theme.to_string());
+        }
+        "extensions" => {
+            let mut extensions: Vec<(String, bool)> = get_all_extensions()
+                .into_iter()
+                .map(|entry| (entry.config.name(), entry.enabled))
+                .collect();
+            extensions.sort();
+            let names: Vec<&String> = extensions.iter().map(|(name, _)| name).collect();
+            let current: Vec<&String> = extensions
+                .iter()
+                .filter(|(name, enabled)| match &profile.extensions {
+                    Some(selected) => selected.contains(name),
+                    None => *enabled,
+                })
+                .map(|(name, _)| name)
+                .collect();
+            let selected = cliclack::multiselect(
+                "extensions for this project: (use \"space\" to toggle and \"enter\" to submit)",
+            )
+            .required(false)
+            .items(
+                &names
+                    .iter()
+                    .map(|name| (*name, name.as_str(), MULTISELECT_VISIBILITY_HINT))
+                    .collect::<Vec<_>>(),
+            )
+            .initial_values(current)
+            .filter_mode()
+            .interact()?;
+            profile.extensions = Some(selected.into_iter().cloned().collect());
+        }
+        "tool_permission" => {
+            let principal: String = cliclack::input("Tool name:")
+                .placeholder("e.g. developer__shell, or github__* for every tool of an extension")
+                .interact()?;
+            let level = cliclack::select("Permission for this project")
+                .item(Some(PermissionLevel::AlwaysAllow), "Always Allow", "")
+                .item(Some(PermissionLevel::AskBefore), "Ask Before", "")
+                .item(Some(PermissionLevel::NeverAllow), "Never Allow", "")
+                .item(None, "Global", "Remove it from the project profile")
+                .interact()?;
+            match level {
+                Some(level) => profile
+                    .permissions
+                    .insert(principal.trim().to_string(), level),
+                None => profile.permissions.remove(principal.trim()),
+            };
+        }
+        _ => unreachable!(),
+    }
+
+    profile.save(&path)?;
+    // the user just wrote it, so it doesn't need to be confirmed when the next session starts
+    project_profile::trust(&path)?;
+    cliclack::outro(format!("Project profile saved to {}", path.display()))?;
+    Ok(())
+}
+
 pub fn configure_goose_mode_dialog() -> anyhow::Result<()> {
     let config = Config::global();
 
@@ -1321,6 +1468,31 @@ pub fn configure_tool_output_dialog() -> anyhow::Result<()> {
     Ok(())
 }
 
+pub fn configure_edit_mode_dialog() -> anyhow::Result<()> {
+    let config = Config::global();
+
+    let edit_mode = cliclack::select("How would you like to edit the prompt?")
+        .item(
+            "emacs",
+            "Emacs (default)",
+            "Ctrl-A, Ctrl-E, Ctrl-K and friends",
+        )
+        .item(
+            "vi",
+            "Vi",
+            "Normal and insert modes, motions, and registers with \"ayy and \"ap",
+        )
+        .interact()?;
+    config.set_param("EDIT_MODE", edit_mode)?;
+
+    match edit_mode {
+        "vi" => cliclack::outro("Prompt editing set to vi. Press Esc for normal mode.")?,
+        _ => cliclack::outro("Prompt editing set to emacs.")?,
+    };
+
+    Ok(())
+}
+
 pub fn configure_keyring_dialog() -> anyhow::Result<()> {
     let config = Config::global();
 
@@ -1984,7 +2156,7 @@ pub fn configure_custom_provider_dialog() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn print_config_file_saved() -> anyhow::Result<()> {
+pub fn print_config_file_saved() -> anyhow::Result<()> {
     let config = Config::global();
     cliclack::outro(format!(
         "Configuration saved successfully to {}",
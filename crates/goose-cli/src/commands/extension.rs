@@ -0,0 +1,337 @@
+use crate::commands::configure::{
+    collect_headers, configure_extensions_dialog, print_config_file_saved, prompt_env_vars,
+    prompt_extension_description, prompt_extension_name, prompt_extension_timeout, store_env_vars,
+};
+use anyhow::Result;
+use console::style;
+use goose::agents::extension::Envs;
+use goose::agents::{Agent, ExtensionConfig};
+use goose::config::extensions::set_extension;
+use goose::config::ExtensionEntry;
+use goose::session::SessionType;
+use goose::utils::safe_truncate;
+use rmcp::model::{Prompt, Tool};
+use std::collections::HashMap;
+
+/// How much of a tool's or prompt's description is shown after the test connection
+const MAX_DESCRIPTION_CHARS: usize = 60;
+
+/// What the test connection found on the server
+struct Discovered {
+    tools: Vec<Tool>,
+    prompts: Vec<Prompt>,
+}
+
+/// Adds an extension, with `--interactive` through a guided flow that tries the connection
+/// before saving it
+pub async fn handle_extension_add(interactive: bool) -> Result<()> {
+    if !interactive {
+        return configure_extensions_dialog();
+    }
+
+    cliclack::intro(style(" add-extension ").on_cyan().black())?;
+    let mut config = prompt_extension_config()?;
+    loop {
+        let discovered = test_connection(&config).await;
+        let next = match &discovered {
+            Ok(discovered) => {
+                cliclack::note(
+                    format!("Found on {}", config.name()),
+                    discovered_lines(&config.key(), discovered).join("\n"),
+                )?;
+                break;
+            }
+            Err(e) => {
+                cliclack::log::error(format!("{}", e))?;
+                cliclack::select("What would you like to do?")
+                    .item("retry", "Try again", "Connect with the same settings")
+                    .item("edit", "Change the settings", "Start over with new answers")
+                    .item("save", "Save anyway", "Add it without a working connection")
+                    .item("cancel", "Cancel", "Leave the configuration unchanged")
+                    .interact()?
+            }
+        };
+        match next {
+            "retry" => continue,
+            "edit" => config = prompt_extension_config()?,
+            "save" => break,
+            _ => {
+                cliclack::outro("No extension added")?;
+                return Ok(());
+            }
+        }
+    }
+
+    let config = store_envs(config)?;
+    set_extension(ExtensionEntry {
+        enabled: true,
+        config: config.clone(),
+    });
+    cliclack::log::success(format!("Added {} extension", style(config.name()).green()))?;
+    print_config_file_saved()
+}
+
+fn prompt_extension_config() -> Result<ExtensionConfig> {
+    let transport = cliclack::select("How does goose connect to the extension?")
+        .item("stdio", "Standard I/O", "Run a local command or script")
+        .item(
+            "streamable_http",
+            "Streamable HTTP",
+            "Connect to a remote server at a URL",
+        )
+        .item(
+            "sse",
+            "SSE",
+            "Server-sent events, now served over Streamable HTTP",
+        )
+        .interact()?;
+
+    match transport {
+        "stdio" => prompt_stdio_config(),
+        "sse" => {
+            cliclack::log::warning(
+                "SSE is no longer supported. Most servers that offered it also speak Streamable \
+                 HTTP, usually at /mcp instead of /sse.",
+            )?;
+            prompt_streamable_http_config()
+        }
+        _ => prompt_streamable_http_config(),
+    }
+}
+
+fn prompt_stdio_config() -> Result<ExtensionConfig> {
+    let name = prompt_extension_name("my-extension")?;
+    let command: String = cliclack::input("What command should be run?")
+        .placeholder("npx -y @modelcontextprotocol/server-everything")
+        .validate(|input: &String| match shlex::split(input) {
+            Some(parts) if !parts.is_empty() => Ok(()),
+            Some(_) => Err("Please enter a command"),
+            None => Err("Please close the quotes in the command"),
+        })
+        .interact()?;
+    let timeout = prompt_extension_timeout()?;
+    let description = prompt_extension_description()?;
+    // kept with the config until it is saved, see `store_envs`
+    let envs = prompt_env_vars()?;
+
+    let mut args = shlex::split(&command).unwrap_or_default().into_iter();
+    Ok(ExtensionConfig::Stdio {
+        name,
+        cmd: args.next().unwrap_or_default(),
+        args: args.collect(),
+        envs: Envs::new(envs),
+        env_keys: Vec::new(),
+        description,
+        timeout: Some(timeout),
+        bundled: None,
+        available_tools: Vec::new(),
+    })
+}
+
+/// Moves the environment variables entered in the flow to the secret store, so the saved
+/// configuration only names them. Until then they stay in memory, so nothing is left behind
+/// when the extension isn't added.
+fn store_envs(config: ExtensionConfig) -> Result<ExtensionConfig> {
+    let ExtensionConfig::Stdio {
+        name,
+        cmd,
+        args,
+        envs,
+        mut env_keys,
+        description,
+        timeout,
+        bundled,
+        available_tools,
+    } = config
+    else {
+        return Ok(config);
+    };
+    env_keys.extend(store_env_vars(envs.get_env())?);
+    Ok(ExtensionConfig::Stdio {
+        name,
+        cmd,
+        args,
+        envs: Envs::default(),
+        env_keys,
+        description,
+        timeout,
+        bundled,
+        available_tools,
+    })
+}
+
+fn prompt_streamable_http_config() -> Result<ExtensionConfig> {
+    let name = prompt_extension_name("my-remote-extension")?;
+    let uri: String = cliclack::input("What is the server's URL?")
+        .placeholder("http://localhost:8000/mcp")
+        .validate(|input: &String| {
+            if input.is_empty() {
+                Err("Please enter a URL")
+            } else if !(input.starts_with("http://") || input.starts_with("https://")) {
+                Err("URL should start with http:// or https://")
+            } else {
+                Ok(())
+            }
+        })
+        .interact()?;
+    let timeout = prompt_extension_timeout()?;
+    let description = prompt_extension_description()?;
+    let headers = collect_headers()?;
+
+    Ok(ExtensionConfig::StreamableHttp {
+        name,
+        uri,
+        envs: Envs::new(HashMap::new()),
+        env_keys: Vec::new(),
+        headers,
+        description,
+        timeout: Some(timeout),
+        bundled: None,
+        available_tools: Vec::new(),
+    })
+}
+
+/// Starts the extension in a throwaway session and asks it for its tools and prompts
+async fn test_connection(config: &ExtensionConfig) -> Result<Discovered> {
+    let spinner = cliclack::spinner();
+    spinner.start(format!("Connecting to {}...", config.name()));
+
+    let agent = Agent::new();
+    let session = agent
+        .config
+        .session_manager
+        .create_session(
+            std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from(".")),
+            "Extension Connection Test".to_string(),
+            SessionType::Hidden,
+        )
+        .await?;
+
+    let result = match agent.add_extension(config.clone(), &session.id).await {
+        Ok(()) => {
+            spinner.set_message(format!("Listing what {} provides...", config.name()));
+            let tools = agent.list_tools(&session.id, Some(config.key())).await;
+            let prompts = agent
+                .list_extension_prompts(&session.id)
+                .await
+                .remove(&config.key())
+                .unwrap_or_default();
+            let _ = agent.remove_extension(&config.key(), &session.id).await;
+            Ok(Discovered { tools, prompts })
+        }
+        Err(e) => Err(anyhow::anyhow!(
+            "Could not connect to {}: {}",
+            config.name(),
+            e
+        )),
+    };
+    let _ = agent
+        .config
+        .session_manager
+        .delete_session(&session.id)
+        .await;
+
+    match &result {
+        Ok(discovered) => spinner.stop(format!(
+            "Connected to {}: {} {}, {} {}",
+            config.name(),
+            discovered.tools.len(),
+            if discovered.tools.len() == 1 {
+                "tool"
+            } else {
+                "tools"
+            },
+            discovered.prompts.len(),
+            if discovered.prompts.len() == 1 {
+                "prompt"
+            } else {
+                "prompts"
+            },
+        )),
+        Err(_) => spinner.error(format!("Connection to {} failed", config.name())),
+    }
+    result
+}
+
+/// The tools and prompts the test connection found, one per line with their description
+fn discovered_lines(key: &str, discovered: &Discovered) -> Vec<String> {
+    let prefix = format!("{}__", key);
+    let describe = |name: &str, description: Option<&str>| match description
+        .map(|d| d.lines().next().unwrap_or_default().trim())
+        .filter(|d| !d.is_empty())
+    {
+        Some(description) => format!(
+            "  {} {}",
+            name,
+            style(safe_truncate(description, MAX_DESCRIPTION_CHARS)).dim()
+        ),
+        None => format!("  {}", name),
+    };
+
+    let mut lines = vec![style("Tools").bold().to_string()];
+    if discovered.tools.is_empty() {
+        lines.push(format!("  {}", style("none").dim()));
+    }
+    lines.extend(discovered.tools.iter().map(|tool| {
+        describe(
+            tool.name
+                .strip_prefix(&prefix)
+                .unwrap_or(tool.name.as_ref()),
+            tool.description.as_deref(),
+        )
+    }));
+
+    lines.push(style("Prompts").bold().to_string());
+    if discovered.prompts.is_empty() {
+        lines.push(format!("  {}", style("none").dim()));
+    }
+    lines.extend(
+        discovered
+            .prompts
+            .iter()
+            .map(|prompt| describe(&prompt.name, prompt.description.as_deref())),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn tool(name: &str, description: &str) -> Tool {
+        Tool::new(
+            name.to_string(),
+            description.to_string(),
+            Arc::new(serde_json::Map::new()),
+        )
+    }
+
+    #[test]
+    fn test_discovered_lines() {
+        let discovered = Discovered {
+            tools: vec![
+                tool(
+                    "everything__echo",
+                    "Echoes back the input\nwith more detail",
+                ),
+                tool("everything__add", ""),
+            ],
+            prompts: vec![],
+        };
+        let lines: Vec<String> = discovered_lines("everything", &discovered)
+            .iter()
+            .map(|line| console::strip_ansi_codes(line).into_owned())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Tools",
+                "  echo Echoes back the input",
+                "  add",
+                "Prompts",
+                "  none",
+            ]
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use anyhow::Result;
 use console::style;
 use goose::config::paths::Paths;
-use goose::config::Config;
+use goose::config::{get_all_extensions, Config};
 use goose::session::session_manager::{DB_NAME, SESSIONS_FOLDER};
+use serde::Serialize;
 use serde_yaml;
 
 fn print_aligned(label: &str, value: &str, width: usize) {
@@ -10,8 +11,9 @@ fn print_aligned(label: &str, value: &str, width: usize) {
 }
 
 use goose::config::base::CONFIG_YAML_NAME;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 fn check_path_status(path: &Path) -> String {
     if path.exists() {
@@ -32,13 +34,73 @@ fn check_path_status(path: &Path) -> String {
     }
 }
 
-pub fn handle_info(verbose: bool) -> Result<()> {
+/// An extension as `goose info --json` lists it, leaving out its settings, which can hold
+/// secrets
+#[derive(Serialize)]
+struct ExtensionInfo {
+    name: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    enabled: bool,
+}
+
+#[derive(Serialize)]
+struct Info {
+    version: &'static str,
+    paths: BTreeMap<&'static str, PathBuf>,
+    provider: Option<String>,
+    model: Option<String>,
+    extensions: Vec<ExtensionInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+fn extension_infos() -> Vec<ExtensionInfo> {
+    let mut extensions: Vec<ExtensionInfo> = get_all_extensions()
+        .into_iter()
+        .map(|entry| ExtensionInfo {
+            name: entry.config.name(),
+            kind: serde_json::to_value(&entry.config)
+                .ok()
+                .and_then(|value| value.get("type")?.as_str().map(str::to_string)),
+            enabled: entry.enabled,
+        })
+        .collect();
+    extensions.sort_by(|a, b| a.name.cmp(&b.name));
+    extensions
+}
+
+pub fn handle_info(verbose: bool, json: bool) -> Result<()> {
     let logs_dir = Paths::in_state_dir("logs");
     let sessions_dir = Paths::in_data_dir(SESSIONS_FOLDER);
     let sessions_db = sessions_dir.join(DB_NAME);
     let config = Config::global();
     let config_dir = Paths::config_dir();
     let config_yaml_file = config_dir.join(CONFIG_YAML_NAME);
+    let provider = config.get_goose_provider().ok();
+    let model = config.get_goose_model().ok();
+    let extensions = extension_infos();
+
+    if json {
+        let info = Info {
+            version: env!("CARGO_PKG_VERSION"),
+            paths: BTreeMap::from([
+                ("config_dir", config_dir),
+                ("config_yaml", config_yaml_file),
+                ("sessions_db", sessions_db),
+                ("logs_dir", logs_dir),
+            ]),
+            provider,
+            model,
+            extensions,
+            config: verbose
+                .then(|| config.all_values())
+                .transpose()?
+                .map(|values| values.into_iter().collect()),
+        };
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
 
     let paths = [
         ("Config dir:", &config_dir),
@@ -69,6 +131,35 @@ pub fn handle_info(verbose: bool) -> Result<()> {
         );
     }
 
+    println!("\n{}", style("Provider:").cyan().bold());
+    print_aligned(
+        "Provider:",
+        provider.as_deref().unwrap_or("not configured"),
+        label_padding,
+    );
+    print_aligned(
+        "Model:",
+        model.as_deref().unwrap_or("not configured"),
+        label_padding,
+    );
+
+    println!("\n{}", style("Extensions:").cyan().bold());
+    if extensions.is_empty() {
+        println!("  No extensions configured");
+    }
+    for extension in &extensions {
+        let kind = extension.kind.as_deref().unwrap_or_default();
+        if extension.enabled {
+            print_aligned(&extension.name, kind, label_padding);
+        } else {
+            print_aligned(
+                &extension.name,
+                &format!("{} {}", kind, style("(disabled)").dim()),
+                label_padding,
+            );
+        }
+    }
+
     if verbose {
         println!("\n{}", style("goose Configuration:").cyan().bold());
         let values = config.all_values()?;
@@ -92,3 +183,47 @@ pub fn handle_info(verbose: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_info_json_shape() {
+        let mut info = Info {
+            version: "1.2.3",
+            paths: BTreeMap::from([
+                ("config_dir", PathBuf::from("/config")),
+                ("sessions_db", PathBuf::from("/data/sessions/sessions.db")),
+            ]),
+            provider: Some("openai".to_string()),
+            model: None,
+            extensions: vec![ExtensionInfo {
+                name: "developer".to_string(),
+                kind: Some("builtin".to_string()),
+                enabled: true,
+            }],
+            config: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            json!({
+                "version": "1.2.3",
+                "paths": {
+                    "config_dir": "/config",
+                    "sessions_db": "/data/sessions/sessions.db",
+                },
+                "provider": "openai",
+                "model": null,
+                "extensions": [{ "name": "developer", "type": "builtin", "enabled": true }],
+            })
+        );
+
+        info.config = Some(BTreeMap::from([("GOOSE_MODE".to_string(), json!("auto"))]));
+        assert_eq!(
+            serde_json::to_value(&info).unwrap()["config"],
+            json!({ "GOOSE_MODE": "auto" })
+        );
+    }
+}
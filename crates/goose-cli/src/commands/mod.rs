@@ -1,9 +1,12 @@
+pub mod ask;
 pub mod configure;
+pub mod extension;
 pub mod info;
 pub mod project;
 pub mod recipe;
 pub mod schedule;
 pub mod session;
+pub mod session_grep;
 pub mod term;
 pub mod update;
 pub mod web;
@@ -1,11 +1,14 @@
 use anyhow::{bail, Context, Result};
 use goose::scheduler::{
-    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, ScheduledJob, Scheduler,
-    SchedulerError,
+    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, JobRun, ScheduledJob,
+    Scheduler, SchedulerError,
 };
 use goose::session::SessionManager;
 use std::path::Path;
 use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::session::run_status_hook;
 
 fn validate_cron_expression(cron: &str) -> Result<()> {
     // Basic validation and helpful suggestions
@@ -65,11 +68,28 @@ fn validate_cron_expression(cron: &str) -> Result<()> {
     Ok(())
 }
 
+/// The ID a schedule gets when none is given: the recipe's file name without its extension
+fn default_schedule_id(recipe_source: &str) -> Option<String> {
+    let path = Path::new(recipe_source);
+    if !path.is_file() {
+        return None;
+    }
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string)
+}
+
 pub async fn handle_schedule_add(
-    schedule_id: String,
+    schedule_id: Option<String>,
     cron: String,
     recipe_source_arg: String, // This is expected to be a file path by the Scheduler
 ) -> Result<()> {
+    let schedule_id = match schedule_id.or_else(|| default_schedule_id(&recipe_source_arg)) {
+        Some(id) => id,
+        None => bail!(
+            "Give the job an ID with --schedule-id; the recipe is not a file to name it after"
+        ),
+    };
     println!(
         "[CLI Debug] Scheduling job ID: {}, Cron: {}, Recipe Source Path: {}",
         schedule_id, cron, recipe_source_arg
@@ -237,6 +257,68 @@ pub async fn handle_schedule_sessions(schedule_id: String, limit: Option<usize>)
     Ok(())
 }
 
+/// What the status hook is called with when a scheduled run finishes, e.g.
+/// `scheduled standup completed 20250106_4`. The session holds the run's transcript.
+fn run_status(run: &JobRun) -> String {
+    let outcome = if run.error.is_some() {
+        "failed"
+    } else {
+        "completed"
+    };
+    let mut status = format!(
+        "scheduled {} {}",
+        shlex::try_quote(&run.job_id).unwrap_or_default(),
+        outcome
+    );
+    if let Some(session_id) = &run.session_id {
+        status.push(' ');
+        status.push_str(session_id);
+    }
+    status
+}
+
+/// Keeps the scheduler running in this process, so jobs run when they are due without the
+/// desktop app, and reports each run through GOOSE_STATUS_HOOK
+pub async fn handle_schedule_serve() -> Result<()> {
+    let scheduler_storage_path =
+        get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
+    let session_manager = Arc::new(SessionManager::instance());
+    let scheduler = Scheduler::new(scheduler_storage_path, session_manager)
+        .await
+        .context("Failed to initialize scheduler")?;
+    let mut runs = scheduler.subscribe_runs();
+
+    let jobs = scheduler.list_scheduled_jobs().await;
+    println!(
+        "Running {} scheduled job{}; stop with Ctrl+C.",
+        jobs.len(),
+        if jobs.len() == 1 { "" } else { "s" }
+    );
+
+    let mut shutdown = crate::signal::shutdown_signal();
+    loop {
+        let run = tokio::select! {
+            run = runs.recv() => run,
+            _ = &mut shutdown => return Ok(()),
+        };
+        match run {
+            Ok(run) => {
+                match &run.error {
+                    Some(error) => println!("Job '{}' failed: {}", run.job_id, error),
+                    None => println!(
+                        "Job '{}' completed; transcript in session {}",
+                        run.job_id,
+                        run.session_id.as_deref().unwrap_or("?")
+                    ),
+                }
+                run_status_hook(&run_status(&run));
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
 pub async fn handle_schedule_run_now(schedule_id: String) -> Result<()> {
     let scheduler_storage_path =
         get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
@@ -340,3 +422,33 @@ pub async fn handle_schedule_cron_help() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(None, None, "scheduled standup completed" ; "no session")]
+    #[test_case(Some("20250106_4"), None, "scheduled standup completed 20250106_4" ; "completed")]
+    #[test_case(Some("20250106_4"), Some("no provider"), "scheduled standup failed 20250106_4" ; "failed")]
+    fn test_run_status(session_id: Option<&str>, error: Option<&str>, expected: &str) {
+        let run = JobRun {
+            job_id: "standup".to_string(),
+            session_id: session_id.map(str::to_string),
+            error: error.map(str::to_string),
+        };
+        assert_eq!(run_status(&run), expected);
+    }
+
+    #[test]
+    fn test_default_schedule_id_is_the_recipe_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let recipe = dir.path().join("standup.yaml");
+        std::fs::write(&recipe, "title: standup").unwrap();
+        assert_eq!(
+            default_schedule_id(recipe.to_str().unwrap()),
+            Some("standup".to_string())
+        );
+        assert_eq!(default_schedule_id("dGl0bGU6IHN0YW5kdXA="), None);
+    }
+}
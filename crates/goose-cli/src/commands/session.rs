@@ -1,13 +1,23 @@
-use crate::session::message_to_markdown;
+use crate::session::{
+    changes_patch, files_touched_markdown, message_to_markdown, replay_conversation, Redactor,
+    SessionMetadata, SessionTags,
+};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, Utc};
 
 use cliclack::{confirm, multiselect, select};
-use goose::session::{generate_diagnostics, Session, SessionManager};
+use goose::config::paths::Paths;
+use goose::conversation::Conversation;
+use goose::session::{
+    generate_diagnostics, ExtensionState, Session, SessionBundle, SessionManager, SubagentRecord,
+    SubagentsState,
+};
 use goose::utils::safe_truncate;
 use regex::Regex;
+use serde::Serialize;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const TRUNCATED_DESC_LENGTH: usize = 60;
 
@@ -46,12 +56,7 @@ fn prompt_interactive_session_removal(sessions: &[Session]) -> Result<Vec<Sessio
     let display_map: std::collections::HashMap<String, Session> = sessions
         .iter()
         .map(|s| {
-            let desc = if s.name.is_empty() {
-                "(no name)"
-            } else {
-                &s.name
-            };
-            let truncated_desc = safe_truncate(desc, TRUNCATED_DESC_LENGTH);
+            let truncated_desc = safe_truncate(&session_title(s), TRUNCATED_DESC_LENGTH);
             let display_text = format!("{} - {} ({})", s.updated_at, truncated_desc, s.id);
             (display_text, s.clone())
         })
@@ -129,11 +134,50 @@ pub async fn handle_session_remove(
     remove_sessions(&session_manager, matched_sessions).await
 }
 
+/// Parses `--since`: a duration back from now such as `30m`, `12h`, `7d` or `2w`, or a date
+/// (`2025-06-01`) or RFC 3339 timestamp.
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_since_from(value, Utc::now())
+}
+
+fn parse_since_from(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("invalid time '{}', expected e.g. 7d or 2025-06-01", value))?;
+    let duration = match unit {
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        _ => {
+            return Err(format!(
+                "invalid unit '{}' in '{}', expected m, h, d or w",
+                unit, value
+            ))
+        }
+    };
+    Ok(now - duration)
+}
+
 pub async fn handle_session_list(
     format: String,
     ascending: bool,
     working_dir: Option<PathBuf>,
     limit: Option<usize>,
+    tags: Vec<String>,
+    since: Option<DateTime<Utc>>,
 ) -> Result<()> {
     let session_manager = SessionManager::instance();
     let mut sessions = session_manager.list_sessions().await?;
@@ -148,6 +192,18 @@ pub async fn handle_session_list(
         });
     }
 
+    if !tags.is_empty() {
+        sessions.retain(|s| {
+            let session_tags =
+                SessionTags::from_extension_data(&s.extension_data).unwrap_or_default();
+            tags.iter().all(|tag| session_tags.contains(tag))
+        });
+    }
+
+    if let Some(since) = since {
+        sessions.retain(|s| s.updated_at >= since);
+    }
+
     if ascending {
         sessions.sort_by(|a, b| a.updated_at.cmp(&b.updated_at));
     } else {
@@ -169,12 +225,221 @@ pub async fn handle_session_list(
             }
 
             println!("Available sessions:");
-            for session in sessions {
-                let output = format!("{} - {} - {}", session.id, session.name, session.updated_at);
-                println!("{}", output);
-            }
+            println!("{}", session_table(&sessions));
+        }
+    }
+    Ok(())
+}
+
+/// What a session is listed as: the name the session store generates from its first messages,
+/// or the one the user gave it
+fn session_title(session: &Session) -> String {
+    if session.name.is_empty() {
+        "(no name)".to_string()
+    } else {
+        session.name.clone()
+    }
+}
+
+fn session_table(sessions: &[Session]) -> comfy_table::Table {
+    use comfy_table::{presets, Cell, CellAlignment, ContentArrangement, Table};
+
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Title",
+            "Updated",
+            "Directory",
+            "Model",
+            "Cost",
+            "Msgs",
+            "Tags",
+            "ID",
+        ]);
+
+    for session in sessions {
+        let metadata = SessionMetadata::from_extension_data(&session.extension_data);
+        let model = metadata
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .or_else(|| session.model_config.as_ref().map(|c| c.model_name.clone()))
+            .unwrap_or_default();
+        let cost = metadata
+            .and_then(|m| m.cost)
+            .map(|c| format!("${:.2}", c))
+            .unwrap_or_default();
+        let tags = SessionTags::from_extension_data(&session.extension_data)
+            .map(|t| t.tags.join(", "))
+            .unwrap_or_default();
+
+        table.add_row(vec![
+            Cell::new(safe_truncate(
+                &session_title(session),
+                TRUNCATED_DESC_LENGTH,
+            )),
+            Cell::new(
+                session
+                    .updated_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M"),
+            ),
+            Cell::new(session.working_dir.display()),
+            Cell::new(model),
+            Cell::new(cost).set_alignment(CellAlignment::Right),
+            Cell::new(session.message_count).set_alignment(CellAlignment::Right),
+            Cell::new(tags),
+            Cell::new(&session.id),
+        ]);
+    }
+    table
+}
+
+/// Adds tags to a session, or removes them with `remove`, and prints the tags it ends up with.
+pub async fn handle_session_tag(session_id: String, tags: Vec<String>, remove: bool) -> Result<()> {
+    let session_manager = SessionManager::instance();
+    let session = session_manager
+        .get_session(&session_id, false)
+        .await
+        .with_context(|| format!("Session '{}' not found", session_id))?;
+
+    let mut session_tags =
+        SessionTags::from_extension_data(&session.extension_data).unwrap_or_default();
+    for tag in &tags {
+        if remove {
+            session_tags.remove(tag);
+        } else {
+            session_tags.add(tag);
         }
     }
+
+    session_manager
+        .update_extension_data(&session_id, |extension_data| {
+            session_tags.to_extension_data(extension_data)
+        })
+        .await?;
+
+    if session_tags.tags.is_empty() {
+        println!("Session `{}` has no tags.", session_id);
+    } else {
+        println!(
+            "Session `{}` tags: {}",
+            session_id,
+            session_tags.tags.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// What `goose session info` shows about a session
+#[derive(Serialize)]
+struct SessionInfo {
+    id: String,
+    title: String,
+    working_dir: PathBuf,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    provider: Option<String>,
+    model: Option<String>,
+    message_count: usize,
+    input_tokens: Option<i32>,
+    output_tokens: Option<i32>,
+    total_tokens: Option<i32>,
+    /// Estimated USD, when price data is known for every model the session used
+    cost: Option<f64>,
+    tags: Vec<String>,
+    recipe: Option<String>,
+    schedule_id: Option<String>,
+}
+
+impl SessionInfo {
+    fn new(session: &Session) -> Self {
+        let metadata = SessionMetadata::from_extension_data(&session.extension_data);
+        Self {
+            id: session.id.clone(),
+            title: session_title(session),
+            working_dir: session.working_dir.clone(),
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+            provider: session.provider_name.clone(),
+            model: metadata
+                .as_ref()
+                .and_then(|m| m.model.clone())
+                .or_else(|| session.model_config.as_ref().map(|c| c.model_name.clone())),
+            message_count: session.message_count,
+            input_tokens: session.accumulated_input_tokens,
+            output_tokens: session.accumulated_output_tokens,
+            total_tokens: session.accumulated_total_tokens,
+            cost: metadata.and_then(|m| m.cost),
+            tags: SessionTags::from_extension_data(&session.extension_data)
+                .map(|t| t.tags)
+                .unwrap_or_default(),
+            recipe: session.recipe.as_ref().map(|r| r.title.clone()),
+            schedule_id: session.schedule_id.clone(),
+        }
+    }
+}
+
+/// Shows one session, the latest one without `session_id`, as text or as JSON.
+pub async fn handle_session_info(session_id: Option<String>, json: bool) -> Result<()> {
+    let session_manager = SessionManager::instance();
+    let session_id = match session_id {
+        Some(id) => id,
+        None => session_manager
+            .list_sessions()
+            .await?
+            .into_iter()
+            .max_by_key(|s| s.updated_at)
+            .map(|s| s.id)
+            .context("No sessions found")?,
+    };
+    let session = session_manager
+        .get_session(&session_id, false)
+        .await
+        .with_context(|| format!("Session '{}' not found", session_id))?;
+    let info = SessionInfo::new(&session);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    let tokens = |count: Option<i32>| count.map(|c| c.to_string()).unwrap_or_default();
+    let rows = [
+        ("ID", info.id.clone()),
+        ("Title", info.title.clone()),
+        ("Directory", info.working_dir.display().to_string()),
+        (
+            "Created",
+            info.created_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+        ),
+        (
+            "Updated",
+            info.updated_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+        ),
+        ("Provider", info.provider.clone().unwrap_or_default()),
+        ("Model", info.model.clone().unwrap_or_default()),
+        ("Messages", info.message_count.to_string()),
+        ("Input tokens", tokens(info.input_tokens)),
+        ("Output tokens", tokens(info.output_tokens)),
+        (
+            "Cost",
+            info.cost.map(|c| format!("${:.2}", c)).unwrap_or_default(),
+        ),
+        ("Tags", info.tags.join(", ")),
+        ("Recipe", info.recipe.clone().unwrap_or_default()),
+        ("Schedule", info.schedule_id.clone().unwrap_or_default()),
+    ];
+    for (label, value) in rows.iter().filter(|(_, value)| !value.is_empty()) {
+        println!("  {:<14} {}", format!("{}:", label), value);
+    }
     Ok(())
 }
 
@@ -182,9 +447,10 @@ pub async fn handle_session_export(
     session_id: String,
     output_path: Option<PathBuf>,
     format: String,
+    redact: bool,
 ) -> Result<()> {
     let session_manager = SessionManager::instance();
-    let session = match session_manager.get_session(&session_id, true).await {
+    let mut session = match session_manager.get_session(&session_id, true).await {
         Ok(session) => session,
         Err(e) => {
             return Err(anyhow::anyhow!(
@@ -195,15 +461,18 @@ pub async fn handle_session_export(
         }
     };
 
-    let output = match format.as_str() {
-        "json" => serde_json::to_string_pretty(&session)?,
-        "yaml" => serde_yaml::to_string(&session)?,
-        "markdown" => {
-            let conversation = session
-                .conversation
-                .ok_or_else(|| anyhow::anyhow!("Session has no messages"))?;
-            export_session_to_markdown(conversation.messages().to_vec(), &session.name)
+    let mut subagents = subagent_sessions(&session_manager, &session).await;
+    if redact {
+        let redactor = Redactor::from_config(&session.working_dir);
+        redact_session(&redactor, &mut session);
+        for (_, subagent_session) in &mut subagents {
+            redact_session(&redactor, subagent_session);
         }
+    }
+    let output = match format.as_str() {
+        "json" => serde_json::to_string_pretty(&with_subagents(&session, &subagents)?)?,
+        "yaml" => serde_yaml::to_string(&with_subagents(&session, &subagents)?)?,
+        "markdown" => session_markdown(&session, &subagents)?,
         _ => return Err(anyhow::anyhow!("Unsupported format: {}", format)),
     };
 
@@ -219,6 +488,108 @@ pub async fn handle_session_export(
     Ok(())
 }
 
+pub async fn handle_session_bundle(
+    session_id: &str,
+    output_path: Option<PathBuf>,
+    redact: bool,
+) -> Result<()> {
+    let session_manager = SessionManager::instance();
+    let mut session = session_manager
+        .get_session(session_id, true)
+        .await
+        .with_context(|| format!("Session '{}' not found or failed to read", session_id))?;
+
+    let mut subagents = subagent_sessions(&session_manager, &session).await;
+    if redact {
+        let redactor = Redactor::from_config(&session.working_dir);
+        redact_session(&redactor, &mut session);
+        for (_, subagent_session) in &mut subagents {
+            redact_session(&redactor, subagent_session);
+        }
+    }
+
+    let mut files = vec![(
+        "transcript.md".to_string(),
+        session_markdown(&session, &subagents)?,
+    )];
+    let patch = session
+        .conversation
+        .as_ref()
+        .map(|c| changes_patch(c.messages()))
+        .unwrap_or_default();
+    if !patch.is_empty() {
+        files.push(("changes.patch".to_string(), patch));
+    }
+
+    let subagent_count = subagents.len();
+    let bundle = SessionBundle::new(
+        session,
+        subagents.into_iter().map(|(_, s)| s).collect(),
+        files,
+        redact,
+    );
+    let output_file =
+        output_path.unwrap_or_else(|| PathBuf::from(format!("session_{}.goose.zip", session_id)));
+    fs::write(&output_file, bundle.to_zip()?)
+        .with_context(|| format!("Failed to write to output file: {}", output_file.display()))?;
+
+    println!(
+        "Session bundled to {} ({} subagent session{}{})",
+        output_file.display(),
+        subagent_count,
+        if subagent_count == 1 { "" } else { "s" },
+        if redact { ", secrets masked" } else { "" }
+    );
+    Ok(())
+}
+
+pub async fn handle_session_import(path: &Path) -> Result<()> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let bundle = SessionBundle::from_zip(&data)
+        .with_context(|| format!("Failed to read bundle {}", path.display()))?;
+    let session = bundle.import(&SessionManager::instance()).await?;
+    let files_dir = Paths::in_data_dir("imports").join(&session.id);
+    let files = bundle.write_files(&files_dir)?;
+
+    println!(
+        "Imported session '{}' from goose {} on {} as {}",
+        bundle.manifest.session_name,
+        bundle.manifest.system.app_version,
+        bundle.manifest.system.os,
+        session.id
+    );
+    if !files.is_empty() {
+        println!(
+            "The bundle's files ({}) are in {}",
+            files
+                .iter()
+                .filter_map(|path| path.file_name())
+                .map(|name| name.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", "),
+            files_dir.display()
+        );
+    }
+    println!(
+        "Resume it with: goose session --resume --session-id {}",
+        session.id
+    );
+    Ok(())
+}
+
+pub async fn handle_session_replay(session_id: &str, speed: f64, max_pause: f64) -> Result<()> {
+    let session = SessionManager::instance()
+        .get_session(session_id, true)
+        .await
+        .with_context(|| format!("Session '{}' not found or failed to read", session_id))?;
+    let conversation = session
+        .conversation
+        .ok_or_else(|| anyhow::anyhow!("Session has no messages"))?;
+
+    replay_conversation(conversation.messages(), speed, max_pause).await;
+    Ok(())
+}
+
 pub async fn handle_diagnostics(session_id: &str, output_path: Option<PathBuf>) -> Result<()> {
     println!(
         "Generating diagnostics bundle for session '{}'...",
@@ -254,6 +625,79 @@ pub async fn handle_diagnostics(session_id: &str, output_path: Option<PathBuf>)
     Ok(())
 }
 
+/// The sessions of the subagents `session` delegated to, which hold their conversations.
+async fn subagent_sessions(
+    session_manager: &SessionManager,
+    session: &Session,
+) -> Vec<(SubagentRecord, Session)> {
+    let state = SubagentsState::from_extension_data(&session.extension_data).unwrap_or_default();
+    let mut sessions = Vec::new();
+    for subagent in state.subagents {
+        match session_manager
+            .get_session(&subagent.session_id, true)
+            .await
+        {
+            Ok(subagent_session) => sessions.push((subagent, subagent_session)),
+            Err(e) => eprintln!(
+                "Warning: leaving out subagent {}: {}",
+                subagent.session_id, e
+            ),
+        }
+    }
+    sessions
+}
+
+/// Masks secrets in the session's tool calls and tool output.
+fn redact_session(redactor: &Redactor, session: &mut Session) {
+    if let Some(conversation) = &session.conversation {
+        let messages = conversation.messages().iter().cloned().map(|mut message| {
+            redactor.redact_message(&mut message);
+            message
+        });
+        session.conversation = Some(Conversation::new_unvalidated(messages));
+    }
+}
+
+/// The session as JSON, with the subagents' sessions under `subagent_sessions`.
+fn with_subagents(
+    session: &Session,
+    subagents: &[(SubagentRecord, Session)],
+) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(session)?;
+    if !subagents.is_empty() {
+        value["subagent_sessions"] = serde_json::to_value(
+            subagents
+                .iter()
+                .map(|(_, subagent_session)| subagent_session)
+                .collect::<Vec<_>>(),
+        )?;
+    }
+    Ok(value)
+}
+
+/// The session's transcript followed by its subagents' and the files it touched.
+fn session_markdown(session: &Session, subagents: &[(SubagentRecord, Session)]) -> Result<String> {
+    let conversation = session
+        .conversation
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Session has no messages"))?;
+    let mut markdown = export_session_to_markdown(conversation.messages().to_vec(), &session.name);
+    for (subagent, subagent_session) in subagents {
+        markdown.push_str(&format!(
+            "## Subagent {}: {}\n\n",
+            subagent.session_id, subagent.description
+        ));
+        let messages = subagent_session
+            .conversation
+            .as_ref()
+            .map(|c| c.messages().as_slice())
+            .unwrap_or_default();
+        markdown.push_str(&messages_to_markdown(messages));
+    }
+    markdown.push_str(&files_touched_markdown(conversation.messages()));
+    Ok(markdown)
+}
+
 fn export_session_to_markdown(
     messages: Vec<goose::conversation::message::Message>,
     session_name: &String,
@@ -268,11 +712,17 @@ fn export_session_to_markdown(
     }
 
     markdown_output.push_str(&format!("*Total messages: {}*\n\n---\n\n", messages.len()));
+    markdown_output.push_str(&messages_to_markdown(&messages));
+    markdown_output
+}
+
+fn messages_to_markdown(messages: &[goose::conversation::message::Message]) -> String {
+    let mut markdown_output = String::new();
 
     // Track if the last message had tool requests to properly handle tool responses
     let mut skip_next_if_tool_response = false;
 
-    for message in &messages {
+    for message in messages {
         // Check if this is a User message containing only ToolResponses
         let is_only_tool_response = message.role == rmcp::model::Role::User
             && message.content.iter().all(|content| {
@@ -341,12 +791,7 @@ pub async fn prompt_interactive_session_selection(
     let display_map: std::collections::HashMap<String, Session> = sessions
         .iter()
         .map(|s| {
-            let desc = if s.name.is_empty() {
-                "(no name)"
-            } else {
-                &s.name
-            };
-            let truncated_desc = safe_truncate(desc, TRUNCATED_DESC_LENGTH);
+            let truncated_desc = safe_truncate(&session_title(s), TRUNCATED_DESC_LENGTH);
 
             let display_text = format!("{} - {} ({})", s.updated_at, truncated_desc, s.id);
             (display_text, s.clone())
@@ -376,3 +821,111 @@ pub async fn prompt_interactive_session_selection(
         Err(anyhow::anyhow!("Invalid selection"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use test_case::test_case;
+
+    fn sample_session() -> Session {
+        let mut session = Session {
+            id: "20250610_1".to_string(),
+            name: "Speed up CI".to_string(),
+            working_dir: PathBuf::from("/work/repo"),
+            created_at: DateTime::parse_from_rfc3339("2025-06-10T09:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339("2025-06-10T10:30:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            message_count: 4,
+            accumulated_input_tokens: Some(1000),
+            accumulated_output_tokens: Some(200),
+            accumulated_total_tokens: Some(1200),
+            provider_name: Some("openai".to_string()),
+            ..Default::default()
+        };
+        SessionMetadata {
+            model: Some("gpt-4o".to_string()),
+            cost: Some(0.42),
+        }
+        .to_extension_data(&mut session.extension_data)
+        .unwrap();
+        SessionTags {
+            tags: vec!["ci".to_string()],
+        }
+        .to_extension_data(&mut session.extension_data)
+        .unwrap();
+        session
+    }
+
+    #[test]
+    fn test_session_list_json_shape() {
+        let session = sample_session();
+        let value = serde_json::to_value([&session]).unwrap();
+        let listed = &value[0];
+        assert_eq!(listed["id"], "20250610_1");
+        assert_eq!(listed["name"], "Speed up CI");
+        assert_eq!(listed["working_dir"], "/work/repo");
+        assert_eq!(listed["updated_at"], json!(session.updated_at));
+        assert_eq!(listed["message_count"], 4);
+        assert_eq!(listed["accumulated_total_tokens"], 1200);
+        assert_eq!(listed["provider_name"], "openai");
+        assert_eq!(
+            listed["extension_data"]["cli_metadata.v0"]["model"],
+            "gpt-4o"
+        );
+        assert_eq!(
+            listed["extension_data"]["cli_tags.v0"]["tags"],
+            json!(["ci"])
+        );
+    }
+
+    #[test]
+    fn test_session_info_json_shape() {
+        let session = sample_session();
+        assert_eq!(
+            serde_json::to_value(SessionInfo::new(&session)).unwrap(),
+            json!({
+                "id": "20250610_1",
+                "title": "Speed up CI",
+                "working_dir": "/work/repo",
+                "created_at": session.created_at,
+                "updated_at": session.updated_at,
+                "provider": "openai",
+                "model": "gpt-4o",
+                "message_count": 4,
+                "input_tokens": 1000,
+                "output_tokens": 200,
+                "total_tokens": 1200,
+                "cost": 0.42,
+                "tags": ["ci"],
+                "recipe": null,
+                "schedule_id": null,
+            })
+        );
+    }
+
+    #[test_case("90m", "2025-06-10T10:30:00Z" ; "minutes")]
+    #[test_case("12h", "2025-06-10T00:00:00Z" ; "hours")]
+    #[test_case("2w", "2025-05-27T12:00:00Z" ; "weeks")]
+    #[test_case("2025-06-01", "2025-06-01T00:00:00Z" ; "date")]
+    #[test_case("2025-06-01T08:00:00+02:00", "2025-06-01T06:00:00Z" ; "timestamp")]
+    fn test_parse_since(value: &str, expected: &str) {
+        let now = DateTime::parse_from_rfc3339("2025-06-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_since_from(value, now).unwrap(),
+            DateTime::parse_from_rfc3339(expected).unwrap()
+        );
+    }
+
+    #[test_case("7y" ; "unknown unit")]
+    #[test_case("d" ; "no amount")]
+    #[test_case("yesterday" ; "words")]
+    fn test_parse_since_rejects(value: &str) {
+        assert!(parse_since_from(value, Utc::now()).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone};
+use console::style;
+use goose::conversation::message::{Message, MessageContent};
+use goose::session::SessionManager;
+use regex::Regex;
+use rmcp::model::Role;
+use std::path::PathBuf;
+
+/// Characters of context shown on each side of a match.
+const SNIPPET_CONTEXT: usize = 60;
+
+/// A piece of a message that can be searched, labelled with where it came from.
+#[derive(Debug, PartialEq)]
+struct Searchable {
+    source: String,
+    text: String,
+}
+
+/// Searches the messages, tool calls (including their arguments, so file paths are found) and
+/// tool output of all stored sessions, newest session first. Lowercase patterns match case
+/// insensitively.
+pub async fn handle_session_grep(
+    pattern: String,
+    regex: bool,
+    working_dir: Option<PathBuf>,
+    limit: usize,
+) -> Result<()> {
+    let matcher = build_matcher(&pattern, regex)?;
+    let session_manager = SessionManager::instance();
+    let mut sessions = session_manager.list_sessions().await?;
+    if let Some(ref dir) = working_dir {
+        let dir_lower = dir.to_string_lossy().to_lowercase();
+        sessions.retain(|s| {
+            s.working_dir
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(&dir_lower)
+        });
+    }
+    sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+    let mut matches = 0;
+    'sessions: for summary in sessions {
+        let session = session_manager
+            .get_session(&summary.id, true)
+            .await
+            .with_context(|| format!("Failed to read session '{}'", summary.id))?;
+        let Some(conversation) = session.conversation else {
+            continue;
+        };
+
+        let mut printed_header = false;
+        for message in conversation.messages() {
+            for part in searchable_parts(message) {
+                let Some(found) = matcher.find(&part.text) else {
+                    continue;
+                };
+                if !printed_header {
+                    println!(
+                        "\n{} {}",
+                        style(&session.id).cyan().bold(),
+                        style(&session.name).dim()
+                    );
+                    printed_header = true;
+                }
+                let time = Local
+                    .timestamp_opt(message.created, 0)
+                    .single()
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                println!(
+                    "  {} {} {}",
+                    style(time).dim(),
+                    style(format!("[{}]", part.source)).dim(),
+                    highlighted_snippet(&part.text, found.start(), found.end())
+                );
+
+                matches += 1;
+                if matches >= limit {
+                    println!(
+                        "\n{}",
+                        style(format!("Stopped after {} matches (--limit)", limit)).dim()
+                    );
+                    break 'sessions;
+                }
+            }
+        }
+    }
+
+    if matches == 0 {
+        println!("No matches for '{}'", pattern);
+    }
+    Ok(())
+}
+
+fn build_matcher(pattern: &str, regex: bool) -> Result<Regex> {
+    let expr = if regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    let smart_case = !pattern.chars().any(char::is_uppercase);
+    let expr = if smart_case {
+        format!("(?i){}", expr)
+    } else {
+        expr
+    };
+    Regex::new(&expr).with_context(|| format!("Invalid pattern '{}'", pattern))
+}
+
+fn searchable_parts(message: &Message) -> Vec<Searchable> {
+    let role = match message.role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+    };
+    message
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            MessageContent::Text(text) => Some(Searchable {
+                source: role.to_string(),
+                text: text.text.clone(),
+            }),
+            MessageContent::ToolRequest(request) => {
+                let call = request.tool_call.as_ref().ok()?;
+                Some(Searchable {
+                    source: format!("tool {}", call.name),
+                    text: call
+                        .arguments
+                        .as_ref()
+                        .map(|args| serde_json::Value::Object(args.clone()).to_string())
+                        .unwrap_or_default(),
+                })
+            }
+            MessageContent::ToolResponse(response) => {
+                let result = response.tool_result.as_ref().ok()?;
+                let text: Vec<&str> = result
+                    .content
+                    .iter()
+                    .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
+                    .collect();
+                Some(Searchable {
+                    source: "tool output".to_string(),
+                    text: text.join("\n"),
+                })
+            }
+            _ => None,
+        })
+        .filter(|part| !part.text.is_empty())
+        .collect()
+}
+
+/// The match with some context on each side, on one line and with the match highlighted.
+fn highlighted_snippet(text: &str, start: usize, end: usize) -> String {
+    let before = text.get(..start).unwrap_or("");
+    let context_start = before
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT - 1)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let after = text.get(end..).unwrap_or("");
+    let context_end = after
+        .char_indices()
+        .nth(SNIPPET_CONTEXT)
+        .map(|(i, _)| end + i)
+        .unwrap_or(text.len());
+
+    let one_line = |range: std::ops::Range<usize>| {
+        text.get(range)
+            .unwrap_or("")
+            .replace(['\n', '\r', '\t'], " ")
+    };
+    format!(
+        "{}{}{}{}{}",
+        if context_start > 0 { "…" } else { "" },
+        one_line(context_start..start),
+        style(one_line(start..end)).yellow().bold(),
+        one_line(end..context_end),
+        if context_end < text.len() { "…" } else { "" },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{CallToolRequestParams, CallToolResult, Content};
+    use test_case::test_case;
+
+    #[test_case("nginx", "/etc/NGINX/nginx.conf", true ; "lowercase is case insensitive")]
+    #[test_case("NGINX", "/etc/nginx/nginx.conf", false ; "uppercase is case sensitive")]
+    #[test_case("a.b", "axb", false ; "literal by default")]
+    fn test_build_matcher(pattern: &str, text: &str, expected: bool) {
+        assert_eq!(
+            build_matcher(pattern, false).unwrap().is_match(text),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_regex_pattern() {
+        let matcher = build_matcher(r"config\.(ya?ml|toml)", true).unwrap();
+        assert!(matcher.is_match("edited config.yml"));
+        assert!(build_matcher("(", true).is_err());
+    }
+
+    #[test]
+    fn test_searchable_parts_include_tool_arguments_and_output() {
+        let args = serde_json::json!({"path": "/etc/nginx/nginx.conf"});
+        let message = Message::assistant()
+            .with_text("Updating the config")
+            .with_tool_request(
+                "1",
+                Ok(CallToolRequestParams {
+                    meta: None,
+                    task: None,
+                    name: "developer__text_editor".into(),
+                    arguments: args.as_object().cloned(),
+                }),
+            );
+        let response = Message::user().with_tool_response(
+            "1",
+            Ok(CallToolResult::success(vec![Content::text("File written")])),
+        );
+
+        let parts = searchable_parts(&message);
+        assert_eq!(parts[0].source, "assistant");
+        assert_eq!(parts[1].source, "tool developer__text_editor");
+        assert!(parts[1].text.contains("/etc/nginx/nginx.conf"));
+        assert_eq!(
+            searchable_parts(&response),
+            vec![Searchable {
+                source: "tool output".to_string(),
+                text: "File written".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_highlighted_snippet_trims_context() {
+        let text = format!("{}needle\n{}", "a".repeat(100), "b".repeat(100));
+        let start = 100;
+        let snippet =
+            console::strip_ansi_codes(&highlighted_snippet(&text, start, start + 6)).to_string();
+        assert_eq!(
+            snippet,
+            format!("…{}needle {}…", "a".repeat(60), "b".repeat(59))
+        );
+    }
+}
@@ -4,7 +4,7 @@ use goose::conversation::message::{Message, MessageContent, MessageMetadata};
 use goose::session::{SessionManager, SessionType};
 use rmcp::model::Role;
 
-use crate::session::{build_session, SessionBuilderConfig};
+use crate::session::{build_session, SessionBuilderConfig, Verbosity};
 
 use clap::ValueEnum;
 
@@ -253,7 +253,7 @@ pub async fn handle_term_run(prompt: Vec<String>) -> Result<()> {
         session_id: Some(session_id),
         resume: true,
         interactive: false,
-        quiet: true,
+        verbosity: Some(Verbosity::Silent),
         ..Default::default()
     };
 
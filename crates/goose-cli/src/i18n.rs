@@ -0,0 +1,245 @@
+use anyhow::{bail, Result};
+use goose::config::paths::Paths;
+use goose::config::Config;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// The built-in messages, and the fallback for anything a translation leaves out
+const ENGLISH: &str = include_str!("../locales/en.ftl");
+
+/// Where a distribution installs its translations, set when building, e.g.
+/// `GOOSE_LOCALE_DIR=/usr/share/goose/locales cargo build`
+const DISTRIBUTION_LOCALE_DIR: Option<&str> = option_env!("GOOSE_LOCALE_DIR");
+
+static CATALOG: LazyLock<Catalog> = LazyLock::new(|| {
+    let locale = locale_from(|name| {
+        Config::global()
+            .get_param::<String>(name)
+            .ok()
+            .or_else(|| std::env::var(name).ok())
+    });
+    Catalog::for_locale(locale.as_deref(), &locale_dirs())
+});
+
+/// Messages by key, read from a catalog in the simple form of Fluent used by `locales/en.ftl`:
+/// `key = value` lines, indented lines continuing the value above, `#` comments, and
+/// `{ $name }` for variables.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut messages: HashMap<String, String> = HashMap::new();
+        let mut last_key: Option<String> = None;
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if line.starts_with([' ', '\t']) {
+                match last_key.as_ref().and_then(|key| messages.get_mut(key)) {
+                    Some(value) => {
+                        if !value.is_empty() {
+                            value.push('\n');
+                        }
+                        value.push_str(line.trim());
+                        continue;
+                    }
+                    None => bail!("line {}: continues no message", number + 1),
+                }
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("line {}: expected `key = value`", number + 1);
+            };
+            let key = key.trim();
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                bail!("line {}: invalid key `{}`", number + 1, key);
+            }
+            messages.insert(key.to_string(), value.trim().to_string());
+            last_key = Some(key.to_string());
+        }
+        Ok(Self { messages })
+    }
+
+    /// The built-in messages with those of the most specific translation of `locale` found in
+    /// `dirs` on top, e.g. `pt-BR.ftl` over `pt.ftl` over English. Catalogs that can't be read
+    /// are reported and skipped.
+    pub fn for_locale(locale: Option<&str>, dirs: &[PathBuf]) -> Self {
+        let mut catalog = Self::parse(ENGLISH).expect("the built-in catalog is valid");
+        let Some(locale) = locale else {
+            return catalog;
+        };
+        for name in fallback_chain(locale).iter().rev() {
+            let Some(path) = dirs
+                .iter()
+                .map(|dir| dir.join(format!("{}.ftl", name)))
+                .find(|path| path.is_file())
+            else {
+                continue;
+            };
+            match Self::load(&path) {
+                Ok(translation) => catalog.messages.extend(translation.messages),
+                Err(e) => eprintln!("Warning: ignoring translation {}: {:#}", path.display(), e),
+            }
+        }
+        catalog
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// The message for `key` with its variables filled in, or `None` if there is no such
+    /// message. Variables without a value are left as they are.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> Option<String> {
+        let message = self.messages.get(key)?;
+        let mut result = String::with_capacity(message.len());
+        let mut rest = message.as_str();
+        while let Some((before, after)) = rest.split_once('{') {
+            let Some((placeable, after)) = after.split_once('}') else {
+                break;
+            };
+            result.push_str(before);
+            let value = placeable
+                .trim()
+                .strip_prefix('$')
+                .and_then(|name| args.iter().find(|(arg, _)| *arg == name))
+                .map(|(_, value)| *value);
+            match value {
+                Some(value) => result.push_str(value),
+                None => {
+                    result.push('{');
+                    result.push_str(placeable);
+                    result.push('}');
+                }
+            }
+            rest = after;
+        }
+        result.push_str(rest);
+        Some(result)
+    }
+}
+
+/// The locale to show messages in: `GOOSE_CLI_LOCALE`, else the usual `LC_ALL`, `LC_MESSAGES`
+/// and `LANG`, in the form `de-DE`. `None` for the `C` and `POSIX` locales.
+fn locale_from(get: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let value = ["GOOSE_CLI_LOCALE", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| get(name))
+        .find(|value| !value.trim().is_empty())?;
+    // de_DE.UTF-8@euro
+    let name = value
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .replace('_', "-");
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    Some(name)
+}
+
+/// `pt-BR` then `pt`
+fn fallback_chain(locale: &str) -> Vec<String> {
+    let mut chain = vec![locale.to_string()];
+    if let Some((language, _)) = locale.split_once('-') {
+        chain.push(language.to_string());
+    }
+    chain
+}
+
+/// Directories searched for translations, first match wins: `GOOSE_CLI_LOCALE_DIR`, the
+/// `locales` directory in goose's config directory, and the distribution's directory.
+fn locale_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(dir) = Config::global().get_param::<String>("GOOSE_CLI_LOCALE_DIR") {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs.push(Paths::config_dir().join("locales"));
+    dirs.extend(DISTRIBUTION_LOCALE_DIR.map(PathBuf::from));
+    dirs
+}
+
+/// The message for `key` in the user's language
+pub fn tr(key: &str) -> String {
+    tr_with(key, &[])
+}
+
+/// The message for `key` in the user's language, with the variables in `args` filled in.
+/// Unknown keys come back as they are, so a typo shows up instead of an empty line.
+pub fn tr_with(key: &str, args: &[(&str, &str)]) -> String {
+    CATALOG.format(key, args).unwrap_or_else(|| key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use test_case::test_case;
+
+    #[test]
+    fn test_english_catalog_parses() {
+        let catalog = Catalog::parse(ENGLISH).unwrap();
+        assert_eq!(
+            catalog.format("extension-always-allow", &[("extension", "github")]),
+            Some("Always Allow github".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        let catalog = Catalog::parse(
+            "# comment\ngreeting = Hallo { $name }, {$count} neue\nlong =\n    first\n    second\n",
+        )
+        .unwrap();
+        assert_eq!(
+            catalog.format("greeting", &[("name", "Ada"), ("count", "3")]),
+            Some("Hallo Ada, 3 neue".to_string())
+        );
+        assert_eq!(
+            catalog.format("greeting", &[]),
+            Some("Hallo { $name }, {$count} neue".to_string())
+        );
+        assert_eq!(
+            catalog.format("long", &[]),
+            Some("first\nsecond".to_string())
+        );
+        assert_eq!(catalog.format("missing", &[]), None);
+
+        assert!(Catalog::parse("no equals sign").is_err());
+        assert!(Catalog::parse("  orphan continuation").is_err());
+    }
+
+    #[test_case(&[("LANG", "de_DE.UTF-8")], Some("de-DE") ; "lang")]
+    #[test_case(&[("LC_ALL", "pt_BR@euro"), ("LANG", "de_DE.UTF-8")], Some("pt-BR") ; "lc all first")]
+    #[test_case(&[("GOOSE_CLI_LOCALE", "fr"), ("LC_ALL", "de_DE")], Some("fr") ; "goose setting first")]
+    #[test_case(&[("LANG", "C.UTF-8")], None ; "c locale")]
+    #[test_case(&[], None ; "unset")]
+    fn test_locale_from(vars: &[(&str, &str)], expected: Option<&str>) {
+        let locale = locale_from(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        });
+        assert_eq!(locale.as_deref(), expected);
+    }
+
+    #[test]
+    fn test_more_specific_translations_win() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("pt.ftl"),
+            "tool-allow = Permitir\ntool-deny = Negar\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("pt-BR.ftl"), "tool-deny = Recusar\n").unwrap();
+
+        let catalog = Catalog::for_locale(Some("pt-BR"), &[dir.path().to_path_buf()]);
+        assert_eq!(catalog.format("tool-allow", &[]).unwrap(), "Permitir");
+        assert_eq!(catalog.format("tool-deny", &[]).unwrap(), "Recusar");
+        assert_eq!(catalog.format("tool-cancel", &[]).unwrap(), "Cancel");
+    }
+}
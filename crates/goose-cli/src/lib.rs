@@ -1,11 +1,15 @@
 pub mod cli;
 pub mod commands;
+pub mod i18n;
 pub mod logging;
+pub mod project_memory;
+pub mod project_profile;
 pub mod project_tracker;
 pub mod recipes;
 pub mod scenario_tests;
 pub mod session;
 pub mod signal;
+pub mod watch;
 
 // Re-export commonly used types
 pub use cli::Cli;
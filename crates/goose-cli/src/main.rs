@@ -1,5 +1,6 @@
 use anyhow::Result;
 use goose_cli::cli::cli;
+use goose_cli::session::SessionError;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -14,5 +15,14 @@ async fn main() -> Result<()> {
         goose::otel::otlp::shutdown_otlp();
     }
 
+    // Session errors were already shown with a hint; only the exit code is left to report
+    if let Some(error) = result
+        .as_ref()
+        .err()
+        .and_then(|e| e.downcast_ref::<SessionError>())
+    {
+        std::process::exit(error.exit_code());
+    }
+
     result
 }
@@ -0,0 +1,240 @@
+use crate::project_profile::{project_key, project_root};
+use anyhow::{Context, Result};
+use console::style;
+use goose::config::paths::Paths;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Key of the memories in the agent's system prompt
+pub const SYSTEM_PROMPT_KEY: &str = "project_memory";
+
+/// What a memory records, given as a `decision:`, `convention:` or `gotcha:` prefix when
+/// it is added
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryKind {
+    Decision,
+    Convention,
+    Gotcha,
+    #[default]
+    Note,
+}
+
+impl MemoryKind {
+    const ALL: [MemoryKind; 4] = [
+        MemoryKind::Decision,
+        MemoryKind::Convention,
+        MemoryKind::Gotcha,
+        MemoryKind::Note,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MemoryKind::Decision => "decision",
+            MemoryKind::Convention => "convention",
+            MemoryKind::Gotcha => "gotcha",
+            MemoryKind::Note => "note",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Memory {
+    #[serde(default)]
+    pub kind: MemoryKind,
+    pub text: String,
+}
+
+impl Memory {
+    /// A memory from what the user typed after `/memory`, e.g. `gotcha: tests need docker`
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (kind, text) = input
+            .split_once(':')
+            .and_then(|(prefix, rest)| {
+                let prefix = prefix.trim().to_ascii_lowercase();
+                MemoryKind::ALL
+                    .into_iter()
+                    .find(|kind| kind.label() == prefix)
+                    .map(|kind| (kind, rest.trim()))
+            })
+            .unwrap_or((MemoryKind::Note, input));
+        (!text.is_empty()).then(|| Self {
+            kind,
+            text: text.to_string(),
+        })
+    }
+}
+
+/// Durable notes about one project, such as decisions, conventions and gotchas, added with
+/// `/memory` and given to the agent at the start of every session in the repository. They are
+/// kept in goose's data directory rather than the checkout, as whatever is in the file becomes
+/// instructions to the agent:
+///
+/// ```toml
+/// [[memories]]
+/// kind = "convention"
+/// text = "Errors are reported with anyhow, never unwrap in library code"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectMemory {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memories: Vec<Memory>,
+}
+
+/// Where the memories for the project containing `dir` are, whether or not they exist
+pub fn memory_path(dir: &Path) -> PathBuf {
+    Paths::data_dir()
+        .join("memories")
+        .join(format!("{}.toml", project_key(&project_root(dir))))
+}
+
+impl ProjectMemory {
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// The memories at `path`, or none when the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid project memories {}", path.display()))
+    }
+
+    /// The memories of the current directory's project, if it has any. Memories that can't be
+    /// read are reported and skipped rather than stopping the session.
+    pub fn for_current_dir() -> Option<Self> {
+        let path = memory_path(&std::env::current_dir().ok()?);
+        match Self::load(&path) {
+            Ok(memory) if !memory.memories.is_empty() => Some(memory),
+            Ok(_) => None,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    style(format!("Warning: {:#}; ignoring them", e)).yellow()
+                );
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Adds a memory unless the same one is already kept. Returns whether it was added.
+    pub fn add(&mut self, memory: Memory) -> bool {
+        if self.memories.contains(&memory) {
+            return false;
+        }
+        self.memories.push(memory);
+        true
+    }
+
+    /// Removes a memory by its 1-based number in the `/memory` list
+    pub fn remove(&mut self, number: usize) -> Option<Memory> {
+        (1..=self.memories.len())
+            .contains(&number)
+            .then(|| self.memories.remove(number - 1))
+    }
+
+    /// The memories as instructions for the system prompt, empty when there are none
+    pub fn system_prompt(&self) -> String {
+        if self.memories.is_empty() {
+            return String::new();
+        }
+        let notes: Vec<String> = self
+            .memories
+            .iter()
+            .map(|memory| format!("- {}: {}", memory.kind.label(), memory.text))
+            .collect();
+        format!(
+            "Notes the user asked you to remember about this project from earlier sessions. \
+             Follow them unless the user says otherwise:\n{}",
+            notes.join("\n")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use test_case::test_case;
+
+    #[test_case("gotcha: tests need docker", MemoryKind::Gotcha, "tests need docker" ; "kind prefix")]
+    #[test_case("Decision:use sqlx", MemoryKind::Decision, "use sqlx" ; "any case without a space")]
+    #[test_case("  deploy on fridays  ", MemoryKind::Note, "deploy on fridays" ; "plain note")]
+    #[test_case("see: the wiki", MemoryKind::Note, "see: the wiki" ; "unknown prefix is kept")]
+    fn test_parse_memory(input: &str, kind: MemoryKind, text: &str) {
+        assert_eq!(
+            Memory::parse(input),
+            Some(Memory {
+                kind,
+                text: text.to_string()
+            })
+        );
+    }
+
+    #[test_case("" ; "empty")]
+    #[test_case("gotcha:  " ; "kind only")]
+    fn test_parse_empty_memory(input: &str) {
+        assert_eq!(Memory::parse(input), None);
+    }
+
+    #[test]
+    fn test_memories_are_kept_per_repository_outside_it() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let path = memory_path(&dir.path().join("src"));
+        assert_eq!(path, memory_path(dir.path()));
+        assert!(path.starts_with(Paths::data_dir()));
+        assert!(!path.starts_with(dir.path()));
+    }
+
+    #[test]
+    fn test_memories_round_trip_and_are_numbered_from_one() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("memories").join("project.toml");
+        assert_eq!(
+            ProjectMemory::load(&path).unwrap(),
+            ProjectMemory::default()
+        );
+
+        let mut memory = ProjectMemory::default();
+        assert!(memory.add(Memory::parse("convention: anyhow for errors").unwrap()));
+        assert!(memory.add(Memory::parse("gotcha: tests need docker").unwrap()));
+        assert!(!memory.add(Memory::parse("gotcha: tests need docker").unwrap()));
+        memory.save(&path).unwrap();
+        let mut loaded = ProjectMemory::load(&path).unwrap();
+        assert_eq!(loaded, memory);
+
+        assert_eq!(loaded.remove(0), None);
+        assert_eq!(loaded.remove(3), None);
+        assert_eq!(
+            loaded.remove(1).map(|m| m.text),
+            Some("anyhow for errors".to_string())
+        );
+        assert_eq!(
+            loaded.system_prompt(),
+            "Notes the user asked you to remember about this project from earlier sessions. \
+             Follow them unless the user says otherwise:\n- gotcha: tests need docker"
+        );
+    }
+
+    #[test]
+    fn test_kind_defaults_to_note() {
+        let memory = ProjectMemory::parse("[[memories]]\ntext = \"hand written\"").unwrap();
+        assert_eq!(memory.memories[0].kind, MemoryKind::Note);
+        assert!(ProjectMemory::parse("[[memories]]\ntxt = \"typo\"").is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use anyhow::{Context, Result};
+use console::style;
+use goose::config::extensions::get_extension_by_name;
+use goose::config::permission::PermissionLevel;
+use goose::config::{Config, ExtensionConfig, GooseMode, PermissionManager};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+/// Where the profile lives, relative to the repository root
+pub const PROFILE_PATH: &str = ".goose/profile.toml";
+
+/// Config key of the profiles the user has trusted: each profile's path with the SHA-256 of
+/// the contents they agreed to
+const TRUSTED_PROFILES_KEY: &str = "GOOSE_TRUSTED_PROJECT_PROFILES";
+
+/// Settings pinned for one project in `.goose/profile.toml`, loaded when a session starts
+/// anywhere in the repository once the user has trusted it:
+///
+/// ```toml
+/// provider = "anthropic"
+/// model = "claude-sonnet-4-5"
+/// mode = "smart_approve"
+/// theme = "dark"
+/// extensions = ["developer", "github"]
+///
+/// [permissions]
+/// developer__shell = "ask_before"
+/// "github__*" = "always_allow"
+/// ```
+///
+/// Command line flags and environment variables still win over the profile, and the profile
+/// wins over the global config. A profile comes with the checkout and can loosen the mode and
+/// permissions, so it is only used after the user has seen and trusted it, and again after
+/// every change to it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<GooseMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Names of configured extensions to use instead of the globally enabled ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<String>>,
+    /// Tool or `extension__*` names and their permission level
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub permissions: BTreeMap<String, PermissionLevel>,
+}
+
+pub use goose::utils::project_root;
+
+/// A name for the project at `root` to keep its files by in goose's own directories: its path
+/// with everything but letters and digits replaced, e.g. `-home-me-src-goose`
+pub fn project_key(root: &Path) -> String {
+    root.to_string_lossy()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Where the profile for the project containing `dir` is, whether or not it exists
+pub fn profile_path(dir: &Path) -> PathBuf {
+    project_root(dir).join(PROFILE_PATH)
+}
+
+impl ProjectProfile {
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid project profile {}", path.display()))
+    }
+
+    /// The profile for the current directory's project, if it has one and the user trusts it.
+    /// A profile that can't be read is reported and skipped rather than stopping the session.
+    pub fn for_current_dir() -> Option<(PathBuf, Self)> {
+        let path = profile_path(&std::env::current_dir().ok()?);
+        if !path.is_file() {
+            return None;
+        }
+        let loaded = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))
+            .and_then(|text| {
+                let profile = Self::parse(&text)
+                    .with_context(|| format!("Invalid project profile {}", path.display()))?;
+                Ok((text, profile))
+            });
+        match loaded {
+            Ok((text, profile)) => confirm_trust(&path, &text).then_some((path, profile)),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    style(format!("Warning: {:#}; ignoring it", e)).yellow()
+                );
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Applies the settings that are read from the config on demand. They are set as
+    /// environment variables, which the config already prefers over its file, unless the
+    /// variable is set already. Permissions apply to this process only.
+    pub fn apply(&self) {
+        let mode = self.mode.and_then(|mode| {
+            serde_json::to_value(mode)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+        });
+        for (key, value) in [
+            ("GOOSE_PROVIDER", self.provider.clone()),
+            ("GOOSE_MODEL", self.model.clone()),
+            ("GOOSE_MODE", mode),
+            ("GOOSE_CLI_THEME", self.theme.clone()),
+        ] {
+            if let Some(value) = value {
+                if std::env::var_os(key).is_none() {
+                    std::env::set_var(key, value);
+                }
+            }
+        }
+        if !self.permissions.is_empty() {
+            PermissionManager::instance()
+                .set_project_permissions(self.permissions.clone().into_iter().collect());
+        }
+    }
+
+    /// The configured extensions the profile names, or `None` to use the enabled ones
+    pub fn extension_configs(&self) -> Option<Vec<ExtensionConfig>> {
+        let names = self.extensions.as_ref()?;
+        Some(
+            names
+                .iter()
+                .filter_map(|name| {
+                    let config = get_extension_by_name(name);
+                    if config.is_none() {
+                        eprintln!(
+                            "{}",
+                            style(format!(
+                                "Warning: extension '{}' from the project profile is not configured; \
+                                 add it with 'goose configure'",
+                                name
+                            ))
+                            .yellow()
+                        );
+                    }
+                    config
+                })
+                .collect(),
+        )
+    }
+
+    /// What the profile sets, for the session header, e.g. `model, extensions, permissions`
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.provider.is_some() || self.model.is_some() {
+            parts.push("model");
+        }
+        if self.mode.is_some() {
+            parts.push("mode");
+        }
+        if self.theme.is_some() {
+            parts.push("theme");
+        }
+        if self.extensions.is_some() {
+            parts.push("extensions");
+        }
+        if !self.permissions.is_empty() {
+            parts.push("permissions");
+        }
+        if parts.is_empty() {
+            "empty".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+fn digest(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Whether `trusted` holds the profile at `path` with exactly these contents
+fn is_trusted_in(trusted: &HashMap<String, String>, path: &Path, text: &str) -> bool {
+    trusted
+        .get(&path.display().to_string())
+        .is_some_and(|hash| *hash == digest(text))
+}
+
+fn trusted_profiles() -> HashMap<String, String> {
+    Config::global()
+        .get_param(TRUSTED_PROFILES_KEY)
+        .unwrap_or_default()
+}
+
+/// Records the profile at `path`, as it is now, as trusted, e.g. after the user edited it
+/// with `goose configure`
+pub fn trust(path: &Path) -> Result<()> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut trusted = trusted_profiles();
+    trusted.insert(path.display().to_string(), digest(&text));
+    Config::global().set_param(TRUSTED_PROFILES_KEY, trusted)?;
+    Ok(())
+}
+
+/// Whether to use the profile at `path`: yes if the user trusted these contents before,
+/// otherwise after showing it and asking. Sessions that can't ask leave it out.
+fn confirm_trust(path: &Path, text: &str) -> bool {
+    if is_trusted_in(&trusted_profiles(), path, text) {
+        return true;
+    }
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        eprintln!(
+            "{}",
+            style(format!(
+                "Warning: ignoring the project profile {} until it is trusted; \
+                 start goose interactively in the project to review it",
+                path.display()
+            ))
+            .yellow()
+        );
+        return false;
+    }
+
+    let _ = cliclack::note(format!("Project profile {}", path.display()), text.trim());
+    let confirmed = cliclack::confirm(
+        "This project's profile is new or has changed. It can set the model, goose mode, \
+         extensions and tool permissions. Trust it?",
+    )
+    .initial_value(false)
+    .interact()
+    .unwrap_or(false);
+    if !confirmed {
+        let _ = cliclack::log::info("Ignoring the project profile for this session");
+        return false;
+    }
+    if let Err(e) = trust(path) {
+        eprintln!(
+            "{}",
+            style(format!(
+                "Warning: couldn't remember the trusted profile: {:#}",
+                e
+            ))
+            .yellow()
+        );
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_profile() {
+        let profile = ProjectProfile::parse(
+            r#"
+model = "gpt-4o"
+mode = "smart_approve"
+extensions = ["developer"]
+
+[permissions]
+developer__shell = "ask_before"
+"github__*" = "never_allow"
+"#,
+        )
+        .unwrap();
+        assert_eq!(profile.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(profile.mode, Some(GooseMode::SmartApprove));
+        assert_eq!(
+            profile.permissions.get("github__*"),
+            Some(&PermissionLevel::NeverAllow)
+        );
+        assert_eq!(profile.summary(), "model, mode, extensions, permissions");
+
+        assert!(ProjectProfile::parse("modle = \"typo\"").is_err());
+        assert!(ProjectProfile::parse("mode = \"yolo\"").is_err());
+    }
+
+    #[test]
+    fn test_profile_is_found_at_repo_root_and_round_trips() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let nested = dir.path().join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        let path = profile_path(&nested);
+        assert_eq!(path, dir.path().join(".goose").join("profile.toml"));
+
+        let profile = ProjectProfile {
+            theme: Some("dark".to_string()),
+            permissions: BTreeMap::from([(
+                "developer__shell".to_string(),
+                PermissionLevel::AlwaysAllow,
+            )]),
+            ..Default::default()
+        };
+        profile.save(&path).unwrap();
+        assert_eq!(ProjectProfile::load(&path).unwrap(), profile);
+    }
+
+    #[test]
+    fn test_trust_is_for_the_contents_that_were_trusted() {
+        let path = Path::new("/work/app/.goose/profile.toml");
+        let text = "mode = \"smart_approve\"\n";
+        let trusted = HashMap::from([(path.display().to_string(), digest(text))]);
+
+        assert!(is_trusted_in(&trusted, path, text));
+        assert!(!is_trusted_in(&trusted, path, "mode = \"auto\"\n"));
+        assert!(!is_trusted_in(
+            &trusted,
+            Path::new("/work/other/.goose/profile.toml"),
+            text
+        ));
+        assert!(!is_trusted_in(&HashMap::new(), path, text));
+    }
+}
@@ -0,0 +1,106 @@
+use goose::config::Config;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+static ENABLED: LazyLock<bool> = LazyLock::new(|| {
+    Config::global()
+        .get_param::<bool>("GOOSE_CLI_ACCESSIBLE")
+        .unwrap_or(false)
+});
+
+/// Output for screen readers, turned on with `GOOSE_CLI_ACCESSIBLE=1`. Spinners, progress bars,
+/// the status line and box drawing are left out, and whatever they or colors alone would have
+/// signaled is printed as a plain line in brackets instead.
+pub fn enabled() -> bool {
+    *ENABLED
+}
+
+pub const THINKING: &str = "[thinking]";
+
+pub fn tool_started(tool: &str) -> String {
+    format!("[tool: {} started]", tool)
+}
+
+/// Opens the output of one of several tool calls that ran at the same time
+pub fn tool_batch_started(
+    index: usize,
+    total: usize,
+    tool: &str,
+    elapsed: Option<Duration>,
+) -> String {
+    match elapsed {
+        Some(elapsed) => format!(
+            "[tool {} of {}: {}, ran {:.1}s]",
+            index,
+            total,
+            tool,
+            elapsed.as_secs_f64()
+        ),
+        None => format!("[tool {} of {}: {}]", index, total, tool),
+    }
+}
+
+/// `lines` is `None` when the call failed
+pub fn tool_finished(lines: Option<usize>) -> String {
+    match lines {
+        None => "[tool failed]".to_string(),
+        Some(0) => "[tool finished, no output]".to_string(),
+        Some(1) => "[tool finished, 1 line output]".to_string(),
+        Some(n) => format!("[tool finished, {} lines output]", n),
+    }
+}
+
+/// A progress report from an extension, in place of its progress bar. `percent` is rounded down
+/// to a multiple of ten so the line only changes, and is only printed again, every tenth.
+pub fn progress(percent: Option<f64>, message: Option<&str>) -> String {
+    let percent = percent.map(|p| ((p.clamp(0.0, 100.0) / 10.0).floor() * 10.0) as u32);
+    match (percent, message) {
+        (Some(percent), Some(message)) => format!("[progress: {}%, {}]", percent, message),
+        (Some(percent), None) => format!("[progress: {}%]", percent),
+        (None, Some(message)) => format!("[progress: {}]", message),
+        (None, None) => "[progress]".to_string(),
+    }
+}
+
+pub fn context_usage(percentage: usize, used: &str, limit: &str) -> String {
+    let level = if percentage < 50 {
+        "low"
+    } else if percentage < 85 {
+        "moderate"
+    } else {
+        "high"
+    };
+    format!(
+        "[context: {}% used, {} of {} tokens, {}]",
+        percentage, used, limit, level
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(None, "[tool failed]" ; "failed")]
+    #[test_case(Some(0), "[tool finished, no output]" ; "no output")]
+    #[test_case(Some(1), "[tool finished, 1 line output]" ; "one line")]
+    #[test_case(Some(3), "[tool finished, 3 lines output]" ; "lines")]
+    fn test_tool_finished(lines: Option<usize>, expected: &str) {
+        assert_eq!(tool_finished(lines), expected);
+    }
+
+    #[test_case(Some(47.5), Some("indexing"), "[progress: 40%, indexing]" ; "rounds down")]
+    #[test_case(Some(100.0), None, "[progress: 100%]" ; "done")]
+    #[test_case(None, Some("waiting"), "[progress: waiting]" ; "no total")]
+    fn test_progress(percent: Option<f64>, message: Option<&str>, expected: &str) {
+        assert_eq!(progress(percent, message), expected);
+    }
+
+    #[test]
+    fn test_tool_batch_started() {
+        assert_eq!(
+            tool_batch_started(2, 3, "shell", Some(Duration::from_millis(2340))),
+            "[tool 2 of 3: shell, ran 2.3s]"
+        );
+    }
+}
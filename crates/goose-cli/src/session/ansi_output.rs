@@ -0,0 +1,175 @@
+use console::measure_text_width;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+/// Ends sanitized output so its colors don't carry over into what is printed next
+const RESET: &str = "\x1b[0m";
+
+/// A run of text, or one escape sequence
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Text(&'a str),
+    /// Select Graphic Rendition: colors, bold, underline and the like
+    Style(&'a str),
+    /// Anything else, such as cursor movement, clearing the screen or setting the title
+    Control,
+}
+
+fn segments(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut chars = text.char_indices();
+    let mut text_start = 0;
+    while let Some((start, c)) = chars.next() {
+        if c != ESC {
+            continue;
+        }
+        if let Some(run) = text.get(text_start..start).filter(|run| !run.is_empty()) {
+            segments.push(Segment::Text(run));
+        }
+        let end = match chars.next() {
+            // CSI: parameters, then a final byte in @..~
+            Some((_, '[')) => loop {
+                match chars.next() {
+                    Some((i, c)) if ('@'..='~').contains(&c) => break Some((i + 1, c == 'm')),
+                    Some(_) => {}
+                    None => break None,
+                }
+            },
+            // OSC: up to BEL or ESC \
+            Some((_, ']')) => loop {
+                match chars.next() {
+                    Some((i, BEL)) => break Some((i + 1, false)),
+                    Some((_, ESC)) => {
+                        let end = chars.next().map(|(i, c)| i + c.len_utf8());
+                        break end.map(|end| (end, false));
+                    }
+                    Some(_) => {}
+                    None => break None,
+                }
+            },
+            Some((i, c)) => Some((i + c.len_utf8(), false)),
+            None => None,
+        };
+        // an unfinished sequence at the end, e.g. where the output was cut short, is dropped
+        let Some((end, is_style)) = end else {
+            return segments;
+        };
+        segments.push(match text.get(start..end) {
+            Some(sequence) if is_style => Segment::Style(sequence),
+            _ => Segment::Control,
+        });
+        text_start = end;
+    }
+    if let Some(run) = text.get(text_start..).filter(|run| !run.is_empty()) {
+        segments.push(Segment::Text(run));
+    }
+    segments
+}
+
+/// Whether tool output carries terminal styling, like the colors of `cargo` or `pytest` run
+/// with `--color=always`
+pub fn has_ansi(text: &str) -> bool {
+    text.contains(ESC)
+        && segments(text)
+            .iter()
+            .any(|segment| matches!(segment, Segment::Style(_)))
+}
+
+/// Tool output that is safe to print as it is: colors and styles are kept, while sequences
+/// that move the cursor or clear the screen are dropped, and lines redrawn with carriage
+/// returns, as progress bars do, keep only what was drawn last.
+pub fn sanitize(text: &str) -> String {
+    let mut kept = String::with_capacity(text.len());
+    for segment in segments(text) {
+        match segment {
+            Segment::Text(text) | Segment::Style(text) => kept.push_str(text),
+            Segment::Control => {}
+        }
+    }
+    let lines: Vec<&str> = kept
+        .split('\n')
+        .map(|line| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            line.rsplit('\r').next().unwrap_or(line)
+        })
+        .collect();
+    let mut sanitized = lines.join("\n").trim_end_matches('\n').to_string();
+    sanitized.push_str(RESET);
+    sanitized
+}
+
+/// A line of styled output broken so no piece is wider than `width` columns. Escape
+/// sequences take no room, and the terminal keeps the style across the breaks.
+pub fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    let mut used = 0;
+    for segment in segments(line) {
+        match segment {
+            Segment::Style(sequence) => pieces.last_mut().unwrap().push_str(sequence),
+            Segment::Text(text) => {
+                for c in text.chars() {
+                    let c_width = measure_text_width(c.encode_utf8(&mut [0; 4]));
+                    if used + c_width > width && used > 0 {
+                        pieces.push(String::new());
+                        used = 0;
+                    }
+                    pieces.last_mut().unwrap().push(c);
+                    used += c_width;
+                }
+            }
+            Segment::Control => {}
+        }
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const RED: &str = "\x1b[31m";
+    const BOLD_GREEN: &str = "\x1b[1;32m";
+
+    #[test_case("plain output", false ; "plain")]
+    #[test_case("\x1b[31merror\x1b[0m: failed", true ; "colored")]
+    #[test_case("\x1b[2Kclearing only", false ; "control sequences only")]
+    fn test_has_ansi(text: &str, expected: bool) {
+        assert_eq!(has_ansi(text), expected);
+    }
+
+    #[test]
+    fn test_sanitize_keeps_colors_and_drops_cursor_control() {
+        let output = format!(
+            "{}Compiling{} goose\n\x1b]0;title\x07\x1b[2K\x1b[1Gtest ok",
+            BOLD_GREEN, RESET
+        );
+        assert_eq!(
+            sanitize(&output),
+            format!("{}Compiling{} goose\ntest ok{}", BOLD_GREEN, RESET, RESET)
+        );
+    }
+
+    #[test]
+    fn test_sanitize_keeps_the_last_progress_update() {
+        assert_eq!(
+            sanitize("[=>  ] 10%\r[===>] 90%\r[====] done\r\nnext\n"),
+            format!("[====] done\nnext{}", RESET)
+        );
+    }
+
+    #[test]
+    fn test_sanitize_drops_an_unfinished_sequence() {
+        assert_eq!(sanitize("ok\x1b[3"), format!("ok{}", RESET));
+    }
+
+    #[test]
+    fn test_wrap_does_not_count_escape_sequences() {
+        let line = format!("{}abcdef{}gh", RED, RESET);
+        assert_eq!(
+            wrap(&line, 4),
+            vec![format!("{}abcd", RED), format!("ef{}gh", RESET)]
+        );
+        assert_eq!(wrap("", 4), vec![String::new()]);
+    }
+}
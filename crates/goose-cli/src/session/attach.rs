@@ -0,0 +1,539 @@
+//! Serving a session over a local socket, and attaching a terminal to it.
+//!
+//! `goose session --serve` runs a session without a terminal: whatever its renderer is asked to
+//! show is sent to every client attached with `goose session attach <id>`, and the messages
+//! those clients type are answered one at a time. Clients detach with Ctrl-D while the session
+//! keeps running, so it can be started on a server and picked up again over ssh from anywhere.
+//!
+//! The protocol is one JSON document per line in each direction.
+
+use anyhow::{anyhow, bail, Context, Result};
+use console::{style, Color};
+use goose::config::paths::Paths;
+use goose::conversation::message::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+use tokio_util::sync::CancellationToken;
+
+use super::mentions;
+use super::renderer::{AnsiRenderer, RenderEvent, Renderer};
+use super::turn_stats::TurnStats;
+
+/// Events kept for clients that attach later, so they see the conversation so far
+const MAX_BACKLOG_EVENTS: usize = 1000;
+/// Events waiting to be written to a slow client before it misses some
+const CLIENT_QUEUE: usize = 256;
+
+/// Where a served session listens
+pub fn socket_path(session_id: &str) -> PathBuf {
+    Paths::in_state_dir("sessions").join(format!("{}.sock", session_id))
+}
+
+/// Sent by the session to attached clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    /// The first line a client gets: the conversation so far, and whether a turn is running
+    Attached {
+        session_id: String,
+        busy: bool,
+        backlog: Vec<RenderEvent>,
+    },
+    Render {
+        event: RenderEvent,
+    },
+    /// The session finished a turn and is waiting for the next message
+    Idle,
+}
+
+/// Sent by an attached client to the session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Message {
+        text: String,
+    },
+    /// Cancel the running turn, like Ctrl-C in a terminal session
+    Interrupt,
+}
+
+#[derive(Default)]
+struct HubState {
+    backlog: VecDeque<RenderEvent>,
+    /// Cancels the running turn, if there is one
+    turn: Option<CancellationToken>,
+}
+
+/// What the session shows, shared with the tasks serving each client
+struct Hub {
+    session_id: String,
+    state: Mutex<HubState>,
+    sender: broadcast::Sender<ServerMessage>,
+}
+
+impl Hub {
+    fn new(session_id: &str) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            state: Mutex::new(HubState::default()),
+            sender: broadcast::channel(CLIENT_QUEUE).0,
+        }
+    }
+
+    fn publish(&self, event: RenderEvent) {
+        let mut state = self.state.lock().unwrap();
+        if state.backlog.len() == MAX_BACKLOG_EVENTS {
+            state.backlog.pop_front();
+        }
+        state.backlog.push_back(event.clone());
+        // nobody may be attached
+        let _ = self.sender.send(ServerMessage::Render { event });
+    }
+
+    /// The greeting for a new client and the events after it. Taken under the lock so no
+    /// event is both in the backlog and sent live, or in neither.
+    fn subscribe(&self) -> (ServerMessage, broadcast::Receiver<ServerMessage>) {
+        let state = self.state.lock().unwrap();
+        let attached = ServerMessage::Attached {
+            session_id: self.session_id.clone(),
+            busy: state.turn.is_some(),
+            backlog: state.backlog.iter().cloned().collect(),
+        };
+        (attached, self.sender.subscribe())
+    }
+
+    fn start_turn(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.state.lock().unwrap().turn = Some(token.clone());
+        token
+    }
+
+    fn end_turn(&self) {
+        self.state.lock().unwrap().turn = None;
+        let _ = self.sender.send(ServerMessage::Idle);
+    }
+
+    fn interrupt(&self) {
+        if let Some(turn) = &self.state.lock().unwrap().turn {
+            turn.cancel();
+        }
+    }
+}
+
+/// Passes everything the session shows on to the attached clients
+pub struct HubRenderer {
+    hub: Arc<Hub>,
+}
+
+impl Renderer for HubRenderer {
+    fn render_user_message(&mut self, message: &Message) {
+        self.hub.publish(RenderEvent::UserMessage(message.clone()));
+    }
+
+    fn render_message(&mut self, message: &Message) {
+        self.hub.publish(RenderEvent::Message(message.clone()));
+    }
+
+    fn render_text(&mut self, text: &str, _color: Option<Color>) {
+        self.hub.publish(RenderEvent::Text(text.to_string()));
+    }
+
+    fn render_error(&mut self, error: &str) {
+        self.hub.publish(RenderEvent::Error(error.to_string()));
+    }
+
+    fn render_model_change(&mut self, model: &str, mode: &str) {
+        self.hub.publish(RenderEvent::ModelChange {
+            model: model.to_string(),
+            mode: mode.to_string(),
+        });
+    }
+
+    fn render_turn_stats(&mut self, stats: &TurnStats) {
+        self.hub.publish(RenderEvent::TurnStats(stats.clone()));
+    }
+
+    fn finish(&mut self, total_tokens: Option<i32>) {
+        self.hub.publish(RenderEvent::Finish(total_tokens));
+    }
+}
+
+/// The listening end of a served session. The socket is removed when it is dropped.
+pub struct Server {
+    hub: Arc<Hub>,
+    path: PathBuf,
+    messages: mpsc::Receiver<String>,
+    _listener: tokio_util::task::AbortOnDropHandle<()>,
+}
+
+impl Server {
+    pub fn bind(session_id: &str) -> Result<Self> {
+        let path = socket_path(session_id);
+        let hub = Arc::new(Hub::new(session_id));
+        let (sender, messages) = mpsc::channel(CLIENT_QUEUE);
+        let listener = transport::listen(&path, hub.clone(), sender)?;
+        Ok(Self {
+            hub,
+            path,
+            messages,
+            _listener: tokio_util::task::AbortOnDropHandle::new(listener),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn renderer(&self) -> HubRenderer {
+        HubRenderer {
+            hub: self.hub.clone(),
+        }
+    }
+
+    /// Adds a resumed session's messages to the backlog, so clients see what came before
+    pub fn add_history(&self, messages: &[Message]) {
+        for message in messages.iter().filter(|m| m.metadata.user_visible) {
+            self.hub.publish(match message.role {
+                rmcp::model::Role::User => RenderEvent::UserMessage(message.clone()),
+                rmcp::model::Role::Assistant => RenderEvent::Message(message.clone()),
+            });
+        }
+    }
+
+    /// The next message a client typed, in the order they arrived
+    pub async fn next_message(&mut self) -> Option<String> {
+        self.messages.recv().await
+    }
+
+    /// Marks a turn as running; the token is cancelled when a client interrupts it
+    pub fn start_turn(&self) -> CancellationToken {
+        self.hub.start_turn()
+    }
+
+    pub fn end_turn(&self) {
+        self.hub.end_turn();
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Shows one event from the session. Messages typed by this client are already on screen.
+fn show(renderer: &mut dyn Renderer, event: RenderEvent, sent: Option<&str>) {
+    match event {
+        RenderEvent::UserMessage(message) => {
+            let typed = mentions::typed_text(&message);
+            if sent != Some(typed.as_str()) {
+                println!("\n{} {}", style("( O)>").cyan().bold(), typed);
+            }
+            renderer.render_user_message(&message);
+        }
+        RenderEvent::Message(message) => renderer.render_message(&message),
+        RenderEvent::Text(text) => renderer.render_text(&text, None),
+        RenderEvent::Error(error) => renderer.render_error(&error),
+        RenderEvent::ModelChange { model, mode } => renderer.render_model_change(&model, &mode),
+        RenderEvent::TurnStats(stats) => renderer.render_turn_stats(&stats),
+        RenderEvent::Finish(total_tokens) => renderer.finish(total_tokens),
+    }
+}
+
+/// Shows what the session does until the running turn ends
+async fn wait_for_turn(
+    connection: &mut transport::Connection,
+    renderer: &mut dyn Renderer,
+    sent: Option<&str>,
+) -> Result<()> {
+    loop {
+        tokio::select! {
+            message = connection.receive() => match message? {
+                Some(ServerMessage::Render { event }) => show(renderer, event, sent),
+                Some(ServerMessage::Idle) => return Ok(()),
+                Some(ServerMessage::Attached { .. }) => {}
+                None => return Err(anyhow!("The session has stopped")),
+            },
+            _ = tokio::signal::ctrl_c() => connection.send(&ClientMessage::Interrupt).await?,
+        }
+    }
+}
+
+/// Connects this terminal to a served session: shows the conversation so far and what happens
+/// from now on, and sends what is typed at the prompt. Ctrl-C interrupts the running turn;
+/// Ctrl-D detaches and leaves the session running.
+pub async fn attach_to_session(session_id: &str, debug: bool) -> Result<()> {
+    let mut connection = transport::connect(&socket_path(session_id))
+        .await
+        .with_context(|| {
+            format!(
+                "Session {} is not being served; start it with `goose session --serve`",
+                session_id
+            )
+        })?;
+    let Some(ServerMessage::Attached {
+        session_id,
+        busy,
+        backlog,
+    }) = connection.receive().await?
+    else {
+        bail!("The session did not answer as expected");
+    };
+
+    let mut renderer = AnsiRenderer::new(debug);
+    println!(
+        "  {} {}",
+        style("⇄").cyan(),
+        style(format!(
+            "attached to session {} · Ctrl-D detaches, the session keeps running",
+            session_id
+        ))
+        .dim()
+    );
+    for event in backlog {
+        show(&mut renderer, event, None);
+    }
+    if busy {
+        wait_for_turn(&mut connection, &mut renderer, None).await?;
+    }
+
+    let mut editor = rustyline::DefaultEditor::new()?;
+    let prompt = format!("{} ", style("( O)>").cyan().bold());
+    loop {
+        let line = tokio::task::block_in_place(|| editor.readline(&prompt));
+        let text = match line {
+            Ok(text) if text.trim().is_empty() => continue,
+            Ok(text) => text,
+            Err(rustyline::error::ReadlineError::Interrupted)
+            | Err(rustyline::error::ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let _ = editor.add_history_entry(text.as_str());
+        connection
+            .send(&ClientMessage::Message { text: text.clone() })
+            .await?;
+        wait_for_turn(&mut connection, &mut renderer, Some(&text)).await?;
+    }
+
+    println!(
+        "  {} {}",
+        style("⇄").cyan(),
+        style(format!(
+            "detached; attach again with `goose session attach {}`",
+            session_id
+        ))
+        .dim()
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+mod transport {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+    use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+    use tokio::net::{UnixListener, UnixStream};
+
+    pub struct Connection {
+        lines: Lines<BufReader<OwnedReadHalf>>,
+        writer: OwnedWriteHalf,
+    }
+
+    impl Connection {
+        fn new(stream: UnixStream) -> Self {
+            let (reader, writer) = stream.into_split();
+            Self {
+                lines: BufReader::new(reader).lines(),
+                writer,
+            }
+        }
+
+        async fn write_line<T: Serialize>(&mut self, value: &T) -> Result<()> {
+            let mut line = serde_json::to_string(value)?;
+            line.push('\n');
+            self.writer.write_all(line.as_bytes()).await?;
+            Ok(())
+        }
+
+        pub async fn send(&mut self, message: &ClientMessage) -> Result<()> {
+            self.write_line(message)
+                .await
+                .context("Lost the connection to the session")
+        }
+
+        pub async fn receive(&mut self) -> Result<Option<ServerMessage>> {
+            match self.lines.next_line().await? {
+                Some(line) => Ok(Some(serde_json::from_str(&line)?)),
+                None => Ok(None),
+            }
+        }
+    }
+
+    pub async fn connect(path: &Path) -> Result<Connection> {
+        Ok(Connection::new(UnixStream::connect(path).await?))
+    }
+
+    pub fn listen(
+        path: &Path,
+        hub: Arc<Hub>,
+        messages: mpsc::Sender<String>,
+    ) -> Result<tokio::task::JoinHandle<()>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                bail!("The session is already being served at {}", path.display());
+            }
+            // left behind by a session that didn't shut down cleanly
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let hub = hub.clone();
+                let messages = messages.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_client(Connection::new(stream), hub, messages).await {
+                        tracing::debug!("Attached client disconnected: {}", e);
+                    }
+                });
+            }
+        }))
+    }
+
+    async fn serve_client(
+        mut connection: Connection,
+        hub: Arc<Hub>,
+        messages: mpsc::Sender<String>,
+    ) -> Result<()> {
+        let (attached, mut events) = hub.subscribe();
+        connection.write_line(&attached).await?;
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) => connection.write_line(&event).await?,
+                    // a client that fell behind misses some output rather than holding up the session
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                },
+                line = connection.lines.next_line() => {
+                    let Some(line) = line? else {
+                        return Ok(());
+                    };
+                    match serde_json::from_str(&line)? {
+                        ClientMessage::Message { text } => messages.send(text).await?,
+                        ClientMessage::Interrupt => hub.interrupt(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod transport {
+    use super::*;
+
+    const UNSUPPORTED: &str = "Serving and attaching to sessions needs Unix domain sockets";
+
+    pub struct Connection;
+
+    impl Connection {
+        pub async fn send(&mut self, _message: &ClientMessage) -> Result<()> {
+            bail!(UNSUPPORTED)
+        }
+
+        pub async fn receive(&mut self) -> Result<Option<ServerMessage>> {
+            bail!(UNSUPPORTED)
+        }
+    }
+
+    pub async fn connect(_path: &Path) -> Result<Connection> {
+        bail!(UNSUPPORTED)
+    }
+
+    pub fn listen(
+        _path: &Path,
+        _hub: Arc<Hub>,
+        _messages: mpsc::Sender<String>,
+    ) -> Result<tokio::task::JoinHandle<()>> {
+        bail!(UNSUPPORTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_round_trips() {
+        let message = ServerMessage::Render {
+            event: RenderEvent::Text("hi".to_string()),
+        };
+        let line = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            line,
+            r#"{"type":"render","event":{"type":"text","data":"hi"}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(&line).unwrap(),
+            message
+        );
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"interrupt"}"#).unwrap(),
+            ClientMessage::Interrupt
+        );
+    }
+
+    #[test]
+    fn test_new_clients_get_the_backlog_then_live_events() {
+        let hub = Hub::new("20250101_1");
+        hub.publish(RenderEvent::Text("before".to_string()));
+        let (attached, mut events) = hub.subscribe();
+        assert_eq!(
+            attached,
+            ServerMessage::Attached {
+                session_id: "20250101_1".to_string(),
+                busy: false,
+                backlog: vec![RenderEvent::Text("before".to_string())],
+            }
+        );
+
+        let turn = hub.start_turn();
+        hub.publish(RenderEvent::Text("after".to_string()));
+        hub.interrupt();
+        assert!(turn.is_cancelled());
+        hub.end_turn();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ServerMessage::Render {
+                event: RenderEvent::Text("after".to_string())
+            }
+        );
+        assert_eq!(events.try_recv().unwrap(), ServerMessage::Idle);
+    }
+
+    #[test]
+    fn test_backlog_is_bounded() {
+        let hub = Hub::new("s");
+        for i in 0..MAX_BACKLOG_EVENTS + 5 {
+            hub.publish(RenderEvent::Text(i.to_string()));
+        }
+        let state = hub.state.lock().unwrap();
+        assert_eq!(state.backlog.len(), MAX_BACKLOG_EVENTS);
+        assert_eq!(
+            state.backlog.front(),
+            Some(&RenderEvent::Text("5".to_string()))
+        );
+    }
+}
@@ -1,23 +1,34 @@
 use crate::cli::StreamableHttpOptions;
-
-use super::output;
+use crate::project_memory::{self, ProjectMemory};
+use crate::project_profile::ProjectProfile;
+
+use super::accessible;
+use super::context_from::{self, ContextFromState};
+use super::non_interactive::ApprovalPolicy;
+use super::output::{self, Verbosity};
+use super::sandbox::Sandbox;
+use super::vi;
 use super::CliSession;
 use console::style;
 use goose::agents::{Agent, Container, ExtensionError};
 use goose::config::resolve_extensions_for_new_session;
 use goose::config::{get_all_extensions, Config, ExtensionConfig};
+use goose::context_mgmt::{ToolOutputSummarizer, DEFAULT_TOOL_OUTPUT_THRESHOLD};
 use goose::providers::create;
 use goose::recipe::Recipe;
 use goose::session::session_manager::SessionType;
-use goose::session::EnabledExtensionsState;
-use rustyline::EditMode;
+use goose::session::{EnabledExtensionsState, ExtensionState};
 use std::collections::BTreeSet;
+use std::path::PathBuf;
 use std::process;
 use std::sync::Arc;
 use tokio::task::JoinSet;
 
 const EXTENSION_HINT_MAX_LEN: usize = 5;
 
+/// Turns the agent takes without user input when `--max-turns` isn't given
+const DEFAULT_MAX_TURNS: u32 = 1000;
+
 fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
     let truncated: String = s.chars().take(max_len).collect();
     if s.chars().count() > max_len {
@@ -110,12 +121,26 @@ pub struct SessionBuilderConfig {
     pub scheduled_job_id: Option<String>,
     /// Whether this session will be used interactively (affects debugging prompts)
     pub interactive: bool,
-    /// Quiet mode - suppress non-response output
-    pub quiet: bool,
+    /// Never prompt; approvals follow GOOSE_CLI_NON_INTERACTIVE_APPROVAL
+    pub non_interactive: bool,
+    /// How much non-response output to print; falls back to GOOSE_CLI_VERBOSITY when unset
+    pub verbosity: Option<Verbosity>,
+    /// Number tool calls and their responses on screen
+    pub show_tool_ids: bool,
+    /// Show secrets in tool calls and their output instead of masking them
+    pub no_redact: bool,
     /// Output format (text, json)
     pub output_format: String,
+    /// Write response text as it arrives, without markdown rendering
+    pub stream_raw: bool,
     /// Docker container to run stdio extensions inside
     pub container: Option<Container>,
+    /// Also record the session as an asciinema cast to this file
+    pub record: Option<PathBuf>,
+    /// Work in a git worktree or copy of the directory and offer to apply the changes at the end
+    pub sandbox: bool,
+    /// Show interactive sessions in the full screen interface
+    pub tui: bool,
 }
 
 /// Manual implementation of Default to ensure proper initialization of output_format
@@ -140,9 +165,16 @@ impl Default for SessionBuilderConfig {
             max_turns: None,
             scheduled_job_id: None,
             interactive: false,
-            quiet: false,
+            non_interactive: false,
+            verbosity: None,
+            show_tool_ids: false,
+            no_redact: false,
             output_format: "text".to_string(),
+            stream_raw: false,
             container: None,
+            record: None,
+            sandbox: false,
+            tui: false,
         }
     }
 }
@@ -290,22 +322,33 @@ async fn load_extensions(
         )
     };
 
-    let spinner = cliclack::spinner();
-    spinner.start(get_message(&waiting_ids));
+    // a spinner would be read out on every tick
+    let spinner = if accessible::enabled() {
+        println!("[{}]", get_message(&waiting_ids));
+        None
+    } else {
+        let spinner = cliclack::spinner();
+        spinner.start(get_message(&waiting_ids));
+        Some(spinner)
+    };
 
     let mut offer_debug: Vec<(usize, anyhow::Error)> = Vec::new();
     while let Some(result) = set.join_next().await {
         match result {
             Ok((id, Ok(_))) => {
                 waiting_ids.remove(&id);
-                spinner.set_message(get_message(&waiting_ids));
+                if let Some(spinner) = &spinner {
+                    spinner.set_message(get_message(&waiting_ids));
+                }
             }
             Ok((id, Err(e))) => offer_debug.push((id, e.into())),
             Err(e) => tracing::error!("failed to add extension: {}", e),
         }
     }
 
-    spinner.clear();
+    if let Some(spinner) = spinner {
+        spinner.clear();
+    }
 
     for (id, err) in offer_debug {
         let label = extensions_to_load
@@ -434,6 +477,31 @@ async fn resolve_session_id(
     }
 }
 
+/// Exits after setting the session up failed. The sandbox is removed first, as `process::exit`
+/// skips the cleanup at the end of the session.
+fn exit_discarding_sandbox(sandbox: Option<Sandbox>) -> ! {
+    if let Some(sandbox) = sandbox {
+        sandbox.discard();
+    }
+    process::exit(1);
+}
+
+/// Moves the session into a new sandbox of the current directory, where its extensions start.
+async fn enter_sandbox(agent: &Agent, session_id: &str) -> anyhow::Result<Sandbox> {
+    let origin = std::env::current_dir()?;
+    let sandbox = Sandbox::create(&origin, session_id)?;
+    std::env::set_current_dir(sandbox.working_dir())?;
+    agent
+        .config
+        .session_manager
+        .update(session_id)
+        .working_dir(sandbox.working_dir().to_path_buf())
+        .apply()
+        .await?;
+    output::render_sandbox_entered(&origin, sandbox.working_dir());
+    Ok(sandbox)
+}
+
 async fn handle_resumed_session_workdir(agent: &Agent, session_id: &str, interactive: bool) {
     let session = agent
         .config
@@ -494,6 +562,7 @@ async fn collect_extension_configs(
     agent: &Agent,
     session_config: &SessionBuilderConfig,
     recipe: Option<&Recipe>,
+    project_profile: Option<&ProjectProfile>,
     session_id: &str,
 ) -> Result<Vec<ExtensionConfig>, ExtensionError> {
     let configured_extensions: Vec<ExtensionConfig> = if session_config.resume {
@@ -506,7 +575,10 @@ async fn collect_extension_configs(
     } else if session_config.no_profile {
         Vec::new()
     } else {
-        resolve_extensions_for_new_session(recipe.and_then(|r| r.extensions.as_deref()), None)
+        resolve_extensions_for_new_session(
+            recipe.and_then(|r| r.extensions.as_deref()),
+            project_profile.and_then(ProjectProfile::extension_configs),
+        )
     };
 
     let cli_flag_extensions = parse_cli_flag_extensions(
@@ -521,6 +593,54 @@ async fn collect_extension_configs(
     Ok(all)
 }
 
+/// Routes large tool outputs through the cheaper model set with `GOOSE_TOOL_SUMMARIZER_MODEL`,
+/// from `GOOSE_TOOL_SUMMARIZER_PROVIDER` or else the session's provider. Outputs over
+/// `GOOSE_TOOL_SUMMARIZER_THRESHOLD` tokens are summarized before the main model sees them.
+async fn configure_tool_summarizer(
+    agent: &Agent,
+    config: &Config,
+    session_provider: &str,
+    extensions: &[ExtensionConfig],
+) {
+    let Ok(model) = config.get_param::<String>("GOOSE_TOOL_SUMMARIZER_MODEL") else {
+        return;
+    };
+    let provider_name = config
+        .get_param::<String>("GOOSE_TOOL_SUMMARIZER_PROVIDER")
+        .unwrap_or_else(|_| session_provider.to_string());
+    let threshold = config
+        .get_param::<usize>("GOOSE_TOOL_SUMMARIZER_THRESHOLD")
+        .unwrap_or(DEFAULT_TOOL_OUTPUT_THRESHOLD);
+
+    let provider = match goose::model::ModelConfig::new(&model) {
+        Ok(model_config) => {
+            create(
+                &provider_name,
+                model_config.with_canonical_limits(&provider_name),
+                extensions.to_vec(),
+            )
+            .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    match provider {
+        Ok(provider) => {
+            agent
+                .set_tool_output_summarizer(Some(ToolOutputSummarizer::new(provider, threshold)))
+                .await
+        }
+        Err(e) => eprintln!(
+            "{}",
+            style(format!(
+                "Warning: Failed to set up the tool output summarizer {}/{} ({}), \
+                 continuing without it",
+                provider_name, model, e
+            ))
+            .yellow()
+        ),
+    }
+}
+
 async fn resolve_and_load_extensions(
     agent: Agent,
     extensions: Vec<ExtensionConfig>,
@@ -572,8 +692,71 @@ async fn configure_session_prompts(
     }
 }
 
+/// What a run with this configuration would use, for `goose run --dry-run`: the provider and
+/// model, the extensions it would load and what the recipe sends, without starting the
+/// extensions or calling the provider
+pub fn dry_run(session_config: &SessionBuilderConfig) {
+    let project_profile = if session_config.no_profile {
+        None
+    } else {
+        ProjectProfile::for_current_dir()
+    };
+    if let Some((_, profile)) = &project_profile {
+        profile.apply();
+    }
+
+    let resolved = resolve_provider_and_model(session_config, Config::global(), None, None);
+    let mut extensions = if session_config.no_profile {
+        Vec::new()
+    } else {
+        resolve_extensions_for_new_session(
+            session_config
+                .recipe
+                .as_ref()
+                .and_then(|r| r.extensions.as_deref()),
+            project_profile
+                .as_ref()
+                .and_then(|(_, profile)| profile.extension_configs()),
+        )
+    };
+    extensions.extend(
+        parse_cli_flag_extensions(
+            &session_config.extensions,
+            &session_config.streamable_http_extensions,
+            &session_config.builtins,
+        )
+        .into_iter()
+        .map(|(_, config)| config),
+    );
+
+    output::display_dry_run(
+        &resolved.provider_name,
+        &resolved.model_config,
+        &extensions,
+        session_config.recipe.as_ref(),
+        session_config.max_turns.unwrap_or(DEFAULT_MAX_TURNS),
+    );
+}
+
 pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
     goose::posthog::set_session_context("cli", session_config.resume);
+    // enable escape sequences on Windows consoles before anything styled is printed
+    super::terminal::supports_ansi();
+
+    // applied before anything reads the provider, model, mode or theme from the config
+    let project_profile = if session_config.no_profile {
+        None
+    } else {
+        ProjectProfile::for_current_dir()
+    };
+    if let Some((_, profile)) = &project_profile {
+        profile.apply();
+    }
+    let project_memory = if session_config.no_profile {
+        None
+    } else {
+        ProjectMemory::for_current_dir()
+    };
 
     let config = Config::global();
     let agent: Agent = Agent::new();
@@ -612,14 +795,33 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         handle_resumed_session_workdir(&agent, &session_id, session_config.interactive).await;
     }
 
-    let extensions_for_provider =
-        match collect_extension_configs(&agent, &session_config, recipe, &session_id).await {
-            Ok(exts) => exts,
+    let sandbox = if session_config.sandbox {
+        match enter_sandbox(&agent, &session_id).await {
+            Ok(sandbox) => Some(sandbox),
             Err(e) => {
-                output::render_error(&format!("Failed to collect extensions: {}", e));
+                output::render_error(&format!("Failed to set up the sandbox: {}", e));
                 process::exit(1);
             }
-        };
+        }
+    } else {
+        None
+    };
+
+    let extensions_for_provider = match collect_extension_configs(
+        &agent,
+        &session_config,
+        recipe,
+        project_profile.as_ref().map(|(_, profile)| profile),
+        &session_id,
+    )
+    .await
+    {
+        Ok(exts) => exts,
+        Err(e) => {
+            output::render_error(&format!("Failed to collect extensions: {}", e));
+            exit_discarding_sandbox(sandbox);
+        }
+    };
 
     let new_provider = match create(
         &resolved.provider_name,
@@ -637,7 +839,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
                 For more info, see: https://block.github.io/goose/docs/troubleshooting/#keychainkeyring-errors",
                 e
             ));
-            process::exit(1);
+            exit_discarding_sandbox(sandbox);
         }
     };
     let provider_for_display = Arc::clone(&new_provider);
@@ -653,13 +855,17 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         tracing::info!("🤖 Using model: {}", resolved.model_name);
     }
 
-    agent
-        .update_provider(new_provider, &session_id)
-        .await
-        .unwrap_or_else(|e| {
-            output::render_error(&format!("Failed to initialize agent: {}", e));
-            process::exit(1);
-        });
+    if let Err(e) = agent.update_provider(new_provider, &session_id).await {
+        output::render_error(&format!("Failed to initialize agent: {}", e));
+        exit_discarding_sandbox(sandbox);
+    }
+    configure_tool_summarizer(
+        &agent,
+        config,
+        &resolved.provider_name,
+        &extensions_for_provider,
+    )
+    .await;
 
     if let Some(recipe) = session_config.recipe.clone() {
         if let Err(e) = session_manager
@@ -685,18 +891,25 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
     let edit_mode = config
         .get_param::<String>("EDIT_MODE")
         .ok()
-        .and_then(|edit_mode| match edit_mode.to_lowercase().as_str() {
-            "emacs" => Some(EditMode::Emacs),
-            "vi" => Some(EditMode::Vi),
-            _ => {
+        .and_then(|edit_mode| {
+            let parsed = vi::parse_edit_mode(&edit_mode);
+            if parsed.is_none() {
                 eprintln!("Invalid EDIT_MODE specified, defaulting to Emacs");
-                None
             }
+            parsed
         });
 
-    let debug_mode = session_config.debug || config.get_param("GOOSE_DEBUG").unwrap_or(false);
+    let verbosity = Verbosity::resolve(
+        session_config.verbosity,
+        session_config.debug || config.get_param("GOOSE_DEBUG").unwrap_or(false),
+    );
+    output::set_verbosity(verbosity);
+    output::set_show_tool_ids(session_config.show_tool_ids);
+    output::set_stream_raw(session_config.stream_raw);
+    output::set_redact(!session_config.no_redact);
+    let debug_mode = verbosity == Verbosity::Debug;
 
-    let session = CliSession::new(
+    let mut session = CliSession::new(
         Arc::try_unwrap(agent_ptr).unwrap_or_else(|_| panic!("There should be no more references")),
         session_id.clone(),
         debug_mode,
@@ -707,21 +920,130 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         session_config.output_format.clone(),
     )
     .await;
+    if session_config.non_interactive {
+        session.set_non_interactive(ApprovalPolicy::from_config());
+    }
+    if let Some(sandbox) = sandbox {
+        session.set_sandbox(sandbox);
+    }
+    if session_config.tui || config.get_param("GOOSE_CLI_TUI").unwrap_or(false) {
+        session.set_tui(true);
+    }
+    if let Some(path) = &session_config.record {
+        if let Err(e) = session.record_cast(path) {
+            output::render_error(&format!(
+                "Failed to start recording to {}: {}",
+                path.display(),
+                e
+            ));
+            session.discard_sandbox();
+            process::exit(1);
+        }
+    }
 
     configure_session_prompts(&session, config, &session_config, &session_id).await;
+    if let Some(memory) = &project_memory {
+        session
+            .agent
+            .extend_system_prompt(
+                project_memory::SYSTEM_PROMPT_KEY.to_string(),
+                memory.system_prompt(),
+            )
+            .await;
+    }
 
-    if !session_config.quiet {
+    if verbosity.shows_session_info() {
+        let title = if session_config.resume {
+            session_manager
+                .get_session(&session_id, false)
+                .await
+                .ok()
+                .map(|session| session.name)
+                .filter(|name| !name.is_empty())
+        } else {
+            None
+        };
         output::display_session_info(
             session_config.resume,
             &resolved.provider_name,
             &resolved.model_name,
             &Some(session_id),
             Some(&provider_for_display),
+            project_profile
+                .as_ref()
+                .map(|(path, profile)| (path.as_path(), profile.summary())),
+            project_memory
+                .as_ref()
+                .map_or(0, |memory| memory.memories.len()),
+            title.as_deref(),
         );
     }
+    if session_config.resume {
+        restore_context_from(&session).await;
+    }
     session
 }
 
+/// Summarizes the earlier session for `--context-from` and gives the summary to the agent, with
+/// the thinking indicator up while the model writes it. The summary is kept with the session, so
+/// resuming it gives the agent the same context.
+pub async fn add_context_from(session: &CliSession, source_id: &str) -> anyhow::Result<()> {
+    let session_manager = &session.agent.config.session_manager;
+    let source = session_manager
+        .get_session(source_id, true)
+        .await
+        .map_err(|e| anyhow::anyhow!("no session {}: {}", source_id, e))?;
+    let provider = session.agent.provider().await?;
+
+    output::show_thinking();
+    output::set_thinking_message(&format!("Summarizing session {}", source.name));
+    let summary = context_from::summarize(provider.as_ref(), &session.session_id, &source).await;
+    output::hide_thinking();
+    let (summary, usage) = summary?;
+
+    let state = ContextFromState {
+        system_prompt: context_from::system_prompt(&source, &summary),
+    };
+    session_manager
+        .update_extension_data(&session.session_id, |extension_data| {
+            state.to_extension_data(extension_data)
+        })
+        .await?;
+    session
+        .agent
+        .extend_system_prompt(
+            context_from::SYSTEM_PROMPT_KEY.to_string(),
+            state.system_prompt,
+        )
+        .await;
+    if output::get_verbosity().shows_session_info() {
+        output::render_context_from(&source, provider.get_name(), &usage);
+    }
+    Ok(())
+}
+
+/// Gives a resumed session the summary it was started with `--context-from`, if it was
+async fn restore_context_from(session: &CliSession) {
+    let Ok(stored) = session
+        .agent
+        .config
+        .session_manager
+        .get_session(&session.session_id, false)
+        .await
+    else {
+        return;
+    };
+    if let Some(state) = ContextFromState::from_extension_data(&stored.extension_data) {
+        session
+            .agent
+            .extend_system_prompt(
+                context_from::SYSTEM_PROMPT_KEY.to_string(),
+                state.system_prompt,
+            )
+            .await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -749,9 +1071,16 @@ mod tests {
             max_turns: None,
             scheduled_job_id: None,
             interactive: true,
-            quiet: false,
+            non_interactive: false,
+            verbosity: Some(Verbosity::Verbose),
+            show_tool_ids: false,
+            no_redact: false,
             output_format: "text".to_string(),
+            stream_raw: false,
             container: None,
+            record: None,
+            sandbox: false,
+            tui: false,
         };
 
         assert_eq!(config.extensions.len(), 1);
@@ -762,7 +1091,7 @@ mod tests {
         assert!(config.max_turns.is_none());
         assert!(config.scheduled_job_id.is_none());
         assert!(config.interactive);
-        assert!(!config.quiet);
+        assert_eq!(config.verbosity, Some(Verbosity::Verbose));
     }
 
     #[test]
@@ -783,7 +1112,8 @@ mod tests {
         assert!(config.max_turns.is_none());
         assert!(config.scheduled_job_id.is_none());
         assert!(!config.interactive);
-        assert!(!config.quiet);
+        assert!(!config.non_interactive);
+        assert!(config.verbosity.is_none());
         assert!(!config.fork);
     }
 
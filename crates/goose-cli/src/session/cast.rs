@@ -0,0 +1,127 @@
+use console::style;
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use super::term_writer::TermWriter;
+
+/// Records a session as an asciinema v2 cast (`--record`) by copying everything written to the
+/// terminal into it as it is written, so replaying it shows what was on screen with the pacing
+/// of the streamed response.
+pub struct CastWriter<W: Write> {
+    inner: Box<dyn TermWriter>,
+    writer: W,
+    started: Instant,
+}
+
+/// Creates a cast at `path` for a terminal the size of this one and writes its header.
+pub fn start(path: &Path, title: &str) -> std::io::Result<BufWriter<File>> {
+    let (height, width) = console::Term::stdout().size();
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_header(&mut writer, title, (width, height))?;
+    Ok(writer)
+}
+
+fn write_header(
+    writer: &mut impl Write,
+    title: &str,
+    (width, height): (u16, u16),
+) -> std::io::Result<()> {
+    let header = json!({
+        "version": 2,
+        "width": width,
+        "height": height,
+        "timestamp": chrono::Utc::now().timestamp(),
+        "title": title,
+        "env": { "TERM": std::env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string()) },
+    });
+    writeln!(writer, "{}", header)?;
+    writer.flush()
+}
+
+impl<W: Write> CastWriter<W> {
+    /// Records into a cast begun with [`start`], passing output on to `inner`.
+    pub fn new(writer: W, inner: Box<dyn TermWriter>) -> Self {
+        Self {
+            inner,
+            writer,
+            started: Instant::now(),
+        }
+    }
+
+    #[cfg(test)]
+    fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes an output event; terminals expect CRLF line endings in a cast.
+    fn output(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let data = text.replace("\r\n", "\n").replace('\n', "\r\n");
+        let event = json!([self.started.elapsed().as_secs_f64(), "o", data]);
+        let _ = writeln!(self.writer, "{}", event);
+        let _ = self.writer.flush();
+    }
+}
+
+impl<W: Write> TermWriter for CastWriter<W> {
+    fn write_str(&mut self, text: &str) {
+        self.output(text);
+        self.inner.write_str(text);
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.inner.is_terminal()
+    }
+
+    fn on_terminal(&self) -> bool {
+        self.inner.on_terminal()
+    }
+
+    /// The terminal echoed the input at the prompt, so only the cast needs it.
+    fn echo_input(&mut self, text: &str) {
+        self.output(&format!(
+            "\n{} {}\n",
+            style("( O)>").cyan().bold().force_styling(true),
+            text
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::term_writer::StringWriter;
+    use serde_json::Value;
+
+    #[test]
+    fn test_cast_has_header_and_timed_output_events() {
+        let screen = StringWriter::new();
+        let mut cast = Vec::new();
+        write_header(&mut cast, "goose", (100, 30)).unwrap();
+        let mut writer = CastWriter::new(cast, Box::new(screen.clone()));
+        writer.echo_input("hi");
+        writer.write_str("Hello\nthere");
+        writer.write_str("");
+
+        assert_eq!(screen.contents(), "Hello\nthere");
+        let cast = String::from_utf8(writer.into_inner()).unwrap();
+        let lines: Vec<Value> = cast
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 100);
+        assert_eq!(lines[0]["height"], 30);
+
+        let events = &lines[1..];
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e[1] == "o" && e[0].is_f64()));
+        assert!(console::strip_ansi_codes(events[0][2].as_str().unwrap()).contains("( O)> hi"));
+        assert_eq!(events[1][2], "Hello\r\nthere");
+    }
+}
@@ -0,0 +1,119 @@
+use base64::Engine;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::streaming_buffer::{split_code_blocks, MarkdownSegment};
+
+/// How text ended up on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipboardMethod {
+    /// A platform tool like `pbcopy` or `wl-copy`
+    Command(&'static str),
+    /// An OSC 52 escape sequence, which the terminal forwards to the clipboard of the machine it
+    /// runs on. Works over SSH, but some terminals ignore it or need it enabled.
+    Osc52,
+}
+
+/// The `n`th fenced code block (1-based) in `markdown`, or the last one when `n` is `None`.
+pub fn code_block(markdown: &str, n: Option<usize>) -> Option<&str> {
+    let blocks: Vec<&str> = split_code_blocks(markdown)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            MarkdownSegment::CodeBlock { code, .. } => Some(code),
+            MarkdownSegment::Text(_) => None,
+        })
+        .collect();
+    match n {
+        Some(n) => blocks.get(n.checked_sub(1)?).copied(),
+        None => blocks.last().copied(),
+    }
+}
+
+/// Puts `text` on the clipboard with the platform's tool, or with OSC 52 over SSH and when no
+/// tool is available.
+pub fn copy(text: &str) -> std::io::Result<ClipboardMethod> {
+    let remote =
+        std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some();
+    if !remote {
+        for (program, args) in clipboard_commands() {
+            if pipe_to(program, args, text).is_ok() {
+                return Ok(ClipboardMethod::Command(program));
+            }
+        }
+    }
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text, std::env::var_os("TMUX").is_some()).as_bytes())?;
+    stdout.flush()?;
+    Ok(ClipboardMethod::Osc52)
+}
+
+fn clipboard_commands() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else if cfg!(windows) {
+        vec![("clip", &[])]
+    } else {
+        let mut commands: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", &[]));
+        }
+        commands.push(("xclip", &["-selection", "clipboard"]));
+        commands.push(("xsel", &["--clipboard", "--input"]));
+        commands
+    }
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> std::io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    if child.wait()?.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("{} failed", program)))
+    }
+}
+
+/// tmux only passes the sequence on to the outer terminal when wrapped in its passthrough
+/// escape, with the inner ESC doubled.
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    if tmux {
+        format!("\x1bPtmux;\x1b\x1b]52;c;{}\x07\x1b\\", encoded)
+    } else {
+        format!("\x1b]52;c;{}\x07", encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const MARKDOWN: &str =
+        "Run this:\n```sh\ncargo build\n```\nthen:\n```rust\nfn main() {}\n```\n";
+
+    #[test_case(Some(1), Some("cargo build\n") ; "first block")]
+    #[test_case(Some(2), Some("fn main() {}\n") ; "second block")]
+    #[test_case(None, Some("fn main() {}\n") ; "last block by default")]
+    #[test_case(Some(3), None ; "out of range")]
+    #[test_case(Some(0), None ; "zero")]
+    fn test_code_block(n: Option<usize>, expected: Option<&str>) {
+        assert_eq!(code_block(MARKDOWN, n), expected);
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(
+            osc52_sequence("hi", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// `src/main.rs:40`, `src/main.rs:40-52`, `src/main.rs#L40` or `src/main.rs#L40-L52`
+static LINE_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?)(?::|#L)(\d+)(?:-L?\d+)?$").unwrap());
+
+/// What the info string of a fenced code block says about the code in it
+#[derive(Debug, Default, PartialEq)]
+pub struct CodeInfo {
+    /// Lowercase language, from the info string or else the path's extension
+    pub language: String,
+    /// The file the code is from
+    pub path: Option<String>,
+    /// The file's line number of the block's first line
+    pub first_line: usize,
+}
+
+impl CodeInfo {
+    /// Reads the opening fence line of a code block. Besides the language this understands a
+    /// path after it (`rust src/main.rs`), joined to it (`rust:src/main.rs`), on its own
+    /// (`src/main.rs`) or as an attribute (`title="src/main.rs"`), each optionally with the line
+    /// the block starts at (`src/main.rs:40` or `src/main.rs#L40-L52`). `is_language` tells
+    /// languages apart from paths.
+    pub fn parse(opening: &str, is_language: impl Fn(&str) -> bool) -> Self {
+        let info = opening.trim().trim_start_matches(['`', '~']).trim();
+        let mut words = info.split_whitespace();
+        let mut language = String::new();
+        let mut path_spec = None;
+
+        if let Some(first) = words.next() {
+            let first = first.trim_start_matches('{').trim_end_matches('}');
+            let first = first.strip_prefix('.').unwrap_or(first);
+            match first.split_once(':') {
+                Some((lang, spec)) if is_language(&lang.to_lowercase()) && !spec.is_empty() => {
+                    language = lang.to_lowercase();
+                    path_spec = Some(spec);
+                }
+                _ if is_language(&first.to_lowercase()) => language = first.to_lowercase(),
+                _ if looks_like_path(first) => path_spec = Some(first),
+                _ => language = first.to_lowercase(),
+            }
+        }
+
+        for word in words {
+            let word = word.trim_end_matches('}');
+            match word.split_once('=') {
+                Some((key, value)) if ["title", "file", "filename", "path"].contains(&key) => {
+                    path_spec = Some(value.trim_matches(['"', '\'']));
+                }
+                None if path_spec.is_none() && looks_like_path(word) => path_spec = Some(word),
+                _ => {}
+            }
+        }
+
+        let (path, first_line) = match path_spec.filter(|spec| !spec.is_empty()) {
+            Some(spec) => match LINE_SUFFIX.captures(spec) {
+                Some(caps) => (
+                    Some(caps[1].to_string()),
+                    caps[2].parse().unwrap_or(1).max(1),
+                ),
+                None => (Some(spec.to_string()), 1),
+            },
+            None => (None, 1),
+        };
+
+        if language.is_empty() {
+            if let Some(extension) = path
+                .as_deref()
+                .and_then(|path| Path::new(path).extension())
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .filter(|extension| is_language(extension))
+            {
+                language = extension;
+            }
+        }
+
+        Self {
+            language,
+            path,
+            first_line,
+        }
+    }
+
+    /// The header above the block: the path and the language, whichever are known
+    pub fn title(&self) -> Option<String> {
+        match (&self.path, self.language.is_empty()) {
+            (Some(path), false) => Some(format!("{} · {}", path, self.language)),
+            (Some(path), true) => Some(path.clone()),
+            (None, false) => Some(self.language.clone()),
+            (None, true) => None,
+        }
+    }
+}
+
+fn looks_like_path(word: &str) -> bool {
+    !word.contains('=')
+        && (word.contains('/') || word.contains('\\') || Path::new(word).extension().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn is_language(word: &str) -> bool {
+        ["rust", "rs", "python", "py", "toml", "diff"].contains(&word)
+    }
+
+    #[test_case("```rust", "rust", None, 1 ; "language only")]
+    #[test_case("```rust src/main.rs", "rust", Some("src/main.rs"), 1 ; "language and path")]
+    #[test_case("```rust:src/main.rs:40", "rust", Some("src/main.rs"), 40 ; "joined with a line")]
+    #[test_case("```src/lib.rs#L12-L30", "rs", Some("src/lib.rs"), 12 ; "path only with a range")]
+    #[test_case("~~~python title=\"app/main.py\"", "python", Some("app/main.py"), 1 ; "title attribute")]
+    #[test_case("```{.toml file=Cargo.toml}", "toml", Some("Cargo.toml"), 1 ; "braced attributes")]
+    #[test_case("```text", "text", None, 1 ; "unknown language")]
+    #[test_case("```", "", None, 1 ; "no info")]
+    fn test_parse(opening: &str, language: &str, path: Option<&str>, first_line: usize) {
+        assert_eq!(
+            CodeInfo::parse(opening, is_language),
+            CodeInfo {
+                language: language.to_string(),
+                path: path.map(str::to_string),
+                first_line,
+            }
+        );
+    }
+
+    #[test]
+    fn test_title() {
+        assert_eq!(
+            CodeInfo::parse("```rust src/main.rs:3", is_language).title(),
+            Some("src/main.rs · rust".to_string())
+        );
+        assert_eq!(CodeInfo::parse("```", is_language).title(), None);
+    }
+}
@@ -0,0 +1,96 @@
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use goose::token_counter::create_token_counter;
+use rmcp::model::Role;
+
+/// What a compaction pass did to the conversation, for showing it to the user.
+#[derive(Debug, PartialEq)]
+pub struct CompactionSummary {
+    /// Messages the model no longer sees
+    pub compacted_messages: usize,
+    /// Token estimate of what the model sees, before and after
+    pub tokens: Option<(usize, usize)>,
+    /// The summary the model now sees in place of the compacted messages
+    pub summary: Option<String>,
+}
+
+impl CompactionSummary {
+    /// Compares the conversation before and after the agent replaced its history. Returns `None`
+    /// when nothing was compacted.
+    pub async fn between(before: &Conversation, after: &Conversation) -> Option<Self> {
+        let compacted_messages = compacted_message_count(before.messages(), after.messages());
+        if compacted_messages == 0 {
+            return None;
+        }
+
+        let tokens = create_token_counter().await.ok().map(|counter| {
+            (
+                counter.count_chat_tokens("", before.messages(), &[]),
+                counter.count_chat_tokens("", after.messages(), &[]),
+            )
+        });
+
+        Some(Self {
+            compacted_messages,
+            tokens,
+            summary: summary_text(after.messages()),
+        })
+    }
+}
+
+/// Messages that were visible to the model before and are hidden from it afterwards. Compaction
+/// keeps the original messages in place and only changes their visibility.
+fn compacted_message_count(before: &[Message], after: &[Message]) -> usize {
+    before
+        .iter()
+        .zip(after)
+        .filter(|(old, new)| old.is_agent_visible() && !new.is_agent_visible())
+        .count()
+}
+
+/// The summary the agent inserted: the first assistant message only the model can see.
+fn summary_text(after: &[Message]) -> Option<String> {
+    after
+        .iter()
+        .find(|m| m.role == Role::Assistant && m.is_agent_visible() && !m.metadata.user_visible)
+        .map(|m| m.as_concat_text())
+        .filter(|text| !text.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::conversation::message::MessageMetadata;
+
+    #[test]
+    fn test_compacted_messages_and_summary() {
+        let before = vec![
+            Message::user().with_text("fix the build"),
+            Message::assistant().with_text("done"),
+            Message::user().with_text("now the tests"),
+        ];
+        let mut after: Vec<Message> = before
+            .iter()
+            .map(|m| m.clone().with_metadata(m.metadata.with_agent_invisible()))
+            .collect();
+        after.push(
+            Message::assistant()
+                .with_text("The user fixed the build and asked about tests.")
+                .with_metadata(MessageMetadata::agent_only()),
+        );
+        after.push(Message::user().with_text("now the tests"));
+
+        assert_eq!(compacted_message_count(&before, &after), 3);
+        assert_eq!(
+            summary_text(&after).as_deref(),
+            Some("The user fixed the build and asked about tests.")
+        );
+    }
+
+    #[test]
+    fn test_unchanged_history_is_not_a_compaction() {
+        let before = vec![Message::user().with_text("hello")];
+        assert_eq!(compacted_message_count(&before, &before), 0);
+        assert_eq!(summary_text(&before), None);
+    }
+}
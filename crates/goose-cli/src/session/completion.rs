@@ -6,8 +6,12 @@ use rustyline::{Context, Helper, Result};
 use std::borrow::Cow;
 use std::sync::Arc;
 
+use super::mentions;
 use super::{CompletionCache, HintStatus};
 
+/// Files offered for an `@` mention, best matches first
+const MAX_MENTION_CANDIDATES: usize = 20;
+
 /// Completer for goose CLI commands
 pub struct GooseCompleter {
     pub completion_cache: Arc<std::sync::RwLock<CompletionCache>>,
@@ -134,6 +138,35 @@ impl GooseCompleter {
             "/prompt",
             "/mode",
             "/recipe",
+            "/cost",
+            "/context",
+            "/theme",
+            "/tool",
+            "/plan",
+            "/endplan",
+            "/clear",
+            "/compact",
+            "/expand",
+            "/tools",
+            "/logs",
+            "/subagent",
+            "/copy",
+            "/fork",
+            "/rewind",
+            "/model",
+            "/retry",
+            "/params",
+            "/attach",
+            "/undo",
+            "/diff",
+            "/todo",
+            "/pin",
+            "/unpin",
+            "/memory",
+            "/forget",
+            "/jobs",
+            "/kill",
+            "/save",
         ];
 
         // Find commands that match the prefix
@@ -154,6 +187,52 @@ impl GooseCompleter {
         Ok((line.len(), vec![]))
     }
 
+    /// Complete the word being typed from a list of names. `separator` lets a word hold several
+    /// names, as in `/builtin developer,memory`, and only the last one is completed.
+    fn complete_names(line: &str, names: &[String], separator: Option<char>) -> (usize, Vec<Pair>) {
+        let word_start = line.rfind(' ').map_or(0, |i| i + 1);
+        let word = line.get(word_start..).unwrap_or("");
+        let start = match separator.and_then(|sep| word.rfind(sep)) {
+            Some(i) => word_start + i + 1,
+            None => word_start,
+        };
+        let partial = line.get(start..).unwrap_or("");
+        let already: Vec<&str> = match separator {
+            Some(sep) => word.split(sep).collect(),
+            None => Vec::new(),
+        };
+
+        let candidates = names
+            .iter()
+            .filter(|name| name.starts_with(partial) && !already.contains(&name.as_str()))
+            .map(|name| Pair {
+                display: name.clone(),
+                replacement: name.clone(),
+            })
+            .collect();
+        (start, candidates)
+    }
+
+    /// Complete builtin extension names for the /builtin command
+    fn complete_builtin_names(&self, line: &str) -> Result<(usize, Vec<Pair>)> {
+        let cache = self.completion_cache.read().unwrap();
+        Ok(Self::complete_names(line, &cache.builtins, Some(',')))
+    }
+
+    /// Complete extension names after `/prompts --extension`
+    fn complete_extension_names(&self, line: &str) -> Result<(usize, Vec<Pair>)> {
+        let cache = self.completion_cache.read().unwrap();
+        let mut names: Vec<String> = cache
+            .extensions
+            .iter()
+            .chain(cache.prompts.keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(Self::complete_names(line, &names, None))
+    }
+
     /// Complete argument keys for a specific prompt
     fn complete_argument_keys(&self, line: &str) -> Result<(usize, Vec<Pair>)> {
         let parts: Vec<&str> = line.get(8..).unwrap_or("").split_whitespace().collect();
@@ -259,6 +338,26 @@ impl GooseCompleter {
         Ok((line.len(), vec![]))
     }
 
+    /// Complete an `@path` mention being typed, fuzzily matching the workspace's files
+    fn complete_mention(&self, line: &str) -> Option<(usize, Vec<Pair>)> {
+        let word = line.rsplit(char::is_whitespace).next()?;
+        let query = word.strip_prefix('@')?;
+        let dir = std::env::current_dir().ok()?;
+        let mut cache = self.completion_cache.write().unwrap();
+        let candidates = mentions::rank(
+            cache.workspace_files.get(&dir),
+            query,
+            MAX_MENTION_CANDIDATES,
+        )
+        .into_iter()
+        .map(|path| Pair {
+            display: path.to_string(),
+            replacement: format!("@{} ", path),
+        })
+        .collect();
+        Some((line.len() - word.len(), candidates))
+    }
+
     /// Complete file paths
     fn complete_file_path(&self, line: &str, ctx: &Context) -> Result<(usize, Vec<Pair>)> {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -311,7 +410,7 @@ impl Completer for GooseCompleter {
             }
 
             // Handle /prompt command
-            if line.starts_with("/prompt") {
+            if line.starts_with("/prompt ") {
                 // If we're just after "/prompt" with or without a space
                 if line == "/prompt" || line == "/prompt " {
                     return self.complete_prompt_names(line);
@@ -352,8 +451,18 @@ impl Completer for GooseCompleter {
                     ));
                 }
 
-                // Check if we might be typing the --extension flag
+                // Complete the extension name after the flag
                 let parts: Vec<&str> = line.split_whitespace().collect();
+                let typing_name = if line.ends_with(' ') {
+                    parts.last() == Some(&"--extension")
+                } else {
+                    parts.len() == 3 && parts[1] == "--extension"
+                };
+                if typing_name {
+                    return self.complete_extension_names(line);
+                }
+
+                // Check if we might be typing the --extension flag
                 if parts.len() == 2
                     && parts[1].starts_with('-')
                     && "--extension".starts_with(parts[1])
@@ -372,9 +481,17 @@ impl Completer for GooseCompleter {
                 return self.complete_mode_flags(line);
             }
 
+            if line.starts_with("/builtin ") && line.split_whitespace().count() <= 2 {
+                return self.complete_builtin_names(line);
+            }
+
             return Ok((pos, vec![]));
         }
 
+        if let Some(completion) = self.complete_mention(line) {
+            return Ok(completion);
+        }
+
         // For normal text (not slash commands), try file path completion
         self.complete_file_path(line, ctx)
     }
@@ -517,6 +634,13 @@ mod tests {
             .prompt_info
             .insert("other_prompt".to_string(), other_prompt_info);
 
+        cache.extensions = vec!["developer".to_string(), "extension1".to_string()];
+        cache.builtins = vec![
+            "computercontroller".to_string(),
+            "developer".to_string(),
+            "memory".to_string(),
+        ];
+
         Arc::new(RwLock::new(cache))
     }
 
@@ -656,4 +780,76 @@ mod tests {
             .unwrap();
         assert_eq!(candidates.len(), 0);
     }
+
+    fn displays(candidates: &[Pair]) -> Vec<&str> {
+        candidates.iter().map(|c| c.display.as_str()).collect()
+    }
+
+    #[test]
+    fn test_complete_builtin_names() {
+        let completer = GooseCompleter::new(create_test_cache());
+
+        let (pos, candidates) = completer.complete_builtin_names("/builtin ").unwrap();
+        assert_eq!(pos, 9);
+        assert_eq!(candidates.len(), 3);
+
+        let (pos, candidates) = completer.complete_builtin_names("/builtin me").unwrap();
+        assert_eq!(pos, 9);
+        assert_eq!(displays(&candidates), vec!["memory"]);
+
+        // after a comma only the last name is completed, skipping ones already listed
+        let (pos, candidates) = completer
+            .complete_builtin_names("/builtin developer,")
+            .unwrap();
+        assert_eq!(pos, 19);
+        assert_eq!(displays(&candidates), vec!["computercontroller", "memory"]);
+    }
+
+    #[test]
+    fn test_complete_extension_names_for_prompts() {
+        let completer = GooseCompleter::new(create_test_cache());
+        let line = "/prompts --extension ";
+        let ctx_history = rustyline::history::DefaultHistory::new();
+        let ctx = Context::new(&ctx_history);
+
+        let (pos, candidates) = completer.complete(line, line.len(), &ctx).unwrap();
+        assert_eq!(pos, line.len());
+        assert_eq!(
+            displays(&candidates),
+            vec!["developer", "extension1", "extension2"]
+        );
+
+        let line = "/prompts --extension ext";
+        let (pos, candidates) = completer.complete(line, line.len(), &ctx).unwrap();
+        assert_eq!(pos, 21);
+        assert_eq!(displays(&candidates), vec!["extension1", "extension2"]);
+    }
+
+    #[test]
+    fn test_complete_slash_commands_includes_plan_commands() {
+        let completer = GooseCompleter::new(create_test_cache());
+        let (_pos, candidates) = completer.complete_slash_commands("/endp").unwrap();
+        assert_eq!(displays(&candidates), vec!["/endplan"]);
+    }
+
+    #[test]
+    fn test_complete_mention() {
+        let cache = create_test_cache();
+        cache.write().unwrap().workspace_files = mentions::WorkspaceFiles::with_files(vec![
+            "crates/goose-cli/src/main.rs".to_string(),
+            "src/main.rs".to_string(),
+            "README.md".to_string(),
+        ]);
+        let completer = GooseCompleter::new(cache);
+
+        let (pos, candidates) = completer.complete_mention("explain @smain").unwrap();
+        assert_eq!(pos, 8);
+        assert_eq!(
+            displays(&candidates),
+            vec!["src/main.rs", "crates/goose-cli/src/main.rs"]
+        );
+        assert_eq!(candidates[0].replacement, "@src/main.rs ");
+
+        assert!(completer.complete_mention("explain main").is_none());
+    }
 }
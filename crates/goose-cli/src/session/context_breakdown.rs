@@ -0,0 +1,184 @@
+use goose::context_mgmt::PINNED_CONTEXT_TEXT;
+use goose::conversation::message::{Message, MessageContent};
+use goose::session::{Pin, PinnedState};
+
+/// What each message costs beyond its content, as the token counter reckons it
+const TOKENS_PER_MESSAGE: usize = 4;
+
+/// Estimated tokens of what goes to the model each turn, by what it is, for `/context`
+#[derive(Debug, Default, PartialEq)]
+pub struct ContextBreakdown {
+    pub system_prompt: usize,
+    pub tool_schemas: usize,
+    pub history: usize,
+    /// Pinned messages, and what compaction carried over for the pins
+    pub pinned: usize,
+    pub tool_outputs: usize,
+}
+
+impl ContextBreakdown {
+    /// Sorts the agent-visible messages into history, pins and tool output and counts each
+    /// with `count`. Tool schemas are counted by the caller, as they aren't plain text.
+    pub fn measure(
+        count: impl Fn(&str) -> usize,
+        system_prompt: &str,
+        tool_schemas: usize,
+        messages: &[Message],
+        pinned: &PinnedState,
+    ) -> Self {
+        let mut breakdown = Self {
+            system_prompt: if system_prompt.is_empty() {
+                0
+            } else {
+                count(system_prompt) + TOKENS_PER_MESSAGE
+            },
+            tool_schemas,
+            ..Default::default()
+        };
+
+        for message in messages.iter().filter(|m| m.is_agent_visible()) {
+            let is_pinned_message = |pin: &Pin| match pin {
+                Pin::Message { id: Some(id), .. } => message.id.as_ref() == Some(id),
+                _ => false,
+            };
+            let is_pinned = pinned.pins.iter().any(is_pinned_message);
+            if is_pinned {
+                breakdown.pinned += TOKENS_PER_MESSAGE;
+            } else {
+                breakdown.history += TOKENS_PER_MESSAGE;
+            }
+
+            for content in &message.content {
+                match content {
+                    MessageContent::ToolResponse(_) => {
+                        breakdown.tool_outputs +=
+                            count(&content.as_tool_response_text().unwrap_or_default());
+                    }
+                    MessageContent::ToolRequest(request) => {
+                        if let Ok(call) = &request.tool_call {
+                            breakdown.history += count(&format!(
+                                "{}:{}:{:?}",
+                                request.id, call.name, call.arguments
+                            ));
+                        }
+                    }
+                    _ => {
+                        // compaction carries the pins over at the start of the summary
+                        if let Some(text) = content.as_text() {
+                            if is_pinned || text.starts_with(PINNED_CONTEXT_TEXT) {
+                                breakdown.pinned += count(text);
+                            } else {
+                                breakdown.history += count(text);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        breakdown
+    }
+
+    /// Each category with its label, in the order they are sent
+    pub fn categories(&self) -> [(&'static str, usize); 5] {
+        [
+            ("System prompt", self.system_prompt),
+            ("Tool schemas", self.tool_schemas),
+            ("Conversation history", self.history),
+            ("Pinned content", self.pinned),
+            ("Tool outputs", self.tool_outputs),
+        ]
+    }
+
+    pub fn total(&self) -> usize {
+        self.categories().iter().map(|(_, tokens)| tokens).sum()
+    }
+}
+
+/// How many of a bar's `width` cells each value fills out of `limit`. Cells are rounded on the
+/// running total, so together they fill as much of the bar as the values do.
+pub fn stacked_cells(values: &[usize], limit: usize, width: usize) -> Vec<usize> {
+    if limit == 0 {
+        return vec![0; values.len()];
+    }
+    let mut running = 0;
+    let mut drawn = 0;
+    values
+        .iter()
+        .map(|value| {
+            running += value;
+            let end = ((running as f64 / limit as f64) * width as f64).round() as usize;
+            let cells = end.min(width).saturating_sub(drawn);
+            drawn += cells;
+            cells
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{CallToolRequestParams, CallToolResult, Content};
+    use test_case::test_case;
+
+    fn words(text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+
+    #[test]
+    fn test_measure() {
+        let call = CallToolRequestParams {
+            meta: None,
+            task: None,
+            name: "developer__shell".into(),
+            arguments: None,
+        };
+        let messages = vec![
+            Message::user()
+                .with_text(format!("{} keep this", PINNED_CONTEXT_TEXT))
+                .with_text("summary of earlier work")
+                .with_id("summary"),
+            Message::user().with_text("run the tests").with_id("asked"),
+            Message::assistant().with_tool_request("1", Ok(call)),
+            Message::user().with_tool_response(
+                "1",
+                Ok(CallToolResult {
+                    content: vec![Content::text("test result: ok. 12 passed")],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            ),
+            Message::assistant().with_text("All tests pass"),
+        ];
+        let pinned = PinnedState {
+            pins: vec![Pin::Message {
+                id: Some("asked".to_string()),
+                text: "run the tests".to_string(),
+            }],
+        };
+
+        let breakdown = ContextBreakdown::measure(words, "be helpful", 30, &messages, &pinned);
+        assert_eq!(
+            breakdown,
+            ContextBreakdown {
+                system_prompt: 2 + TOKENS_PER_MESSAGE,
+                tool_schemas: 30,
+                history: 4 + 1 + 3 + 4 * TOKENS_PER_MESSAGE,
+                pinned: words(PINNED_CONTEXT_TEXT) + 2 + 3 + TOKENS_PER_MESSAGE,
+                tool_outputs: 5,
+            }
+        );
+        assert_eq!(
+            breakdown.total(),
+            breakdown.categories().iter().map(|(_, t)| t).sum::<usize>()
+        );
+    }
+
+    #[test_case(&[10, 10, 10], 100, 10, &[1, 1, 1] ; "exact")]
+    #[test_case(&[14, 14, 14], 100, 10, &[1, 2, 1] ; "rounded on the running total")]
+    #[test_case(&[80, 80], 100, 10, &[8, 2] ; "over the limit")]
+    #[test_case(&[5], 0, 10, &[0] ; "no limit")]
+    fn test_stacked_cells(values: &[usize], limit: usize, width: usize, expected: &[usize]) {
+        assert_eq!(stacked_cells(values, limit, width), expected);
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use goose::conversation::message::{Message, MessageContent};
+use goose::providers::base::{Provider, ProviderUsage};
+use goose::session::{ExtensionState, Session};
+use goose::utils::safe_truncate;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+
+use super::mentions::typed_text;
+
+/// Key of the system prompt extension that carries the earlier session
+pub const SYSTEM_PROMPT_KEY: &str = "context_from";
+
+/// How much of each message goes into the transcript
+const MAX_MESSAGE_CHARS: usize = 2000;
+/// How much transcript is summarized, keeping the end of the session when it is longer
+const MAX_TRANSCRIPT_CHARS: usize = 60_000;
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You summarize a finished session between a user and an AI \
+agent so another session can pick up from it. Cover what the user wanted, what was done and \
+decided, the files, commands and facts that matter, and what was left open. Reply with only the \
+summary, as a short markdown list.";
+
+/// The system prompt a session was started with from `--context-from`, kept so resuming the
+/// session gives the agent the earlier session again
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextFromState {
+    pub system_prompt: String,
+}
+
+impl ExtensionState for ContextFromState {
+    const EXTENSION_NAME: &'static str = "cli_context_from";
+    const VERSION: &'static str = "v0";
+}
+
+/// The user's and the agent's text of a session, with tool calls by name only
+fn transcript(messages: &[Message]) -> String {
+    let mut turns: Vec<String> = messages
+        .iter()
+        .filter(|message| message.is_agent_visible())
+        .filter_map(|message| {
+            let text = match message.role {
+                Role::User => typed_text(message),
+                Role::Assistant => message
+                    .content
+                    .iter()
+                    .filter_map(|content| match content {
+                        MessageContent::Text(text) => Some(text.text.clone()),
+                        MessageContent::ToolRequest(request) => request
+                            .tool_call
+                            .as_ref()
+                            .ok()
+                            .map(|call| format!("[called {}]", call.name)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            };
+            let text = text.trim();
+            if text.is_empty() {
+                return None;
+            }
+            let speaker = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+            };
+            Some(format!(
+                "{}:\n{}",
+                speaker,
+                safe_truncate(text, MAX_MESSAGE_CHARS)
+            ))
+        })
+        .collect();
+
+    let mut length = 0;
+    let keep = turns
+        .iter()
+        .rev()
+        .take_while(|turn| {
+            length += turn.len() + 2;
+            length <= MAX_TRANSCRIPT_CHARS
+        })
+        .count();
+    turns.drain(..turns.len() - keep);
+    turns.join("\n\n")
+}
+
+/// Summarizes an earlier session with the provider's fast model
+pub async fn summarize(
+    provider: &dyn Provider,
+    session_id: &str,
+    source: &Session,
+) -> Result<(String, ProviderUsage)> {
+    let messages = source
+        .conversation
+        .as_ref()
+        .map(|conversation| conversation.messages().as_slice())
+        .unwrap_or_default();
+    let transcript = transcript(messages);
+    if transcript.is_empty() {
+        anyhow::bail!("session {} has no messages to take context from", source.id);
+    }
+    let request = [Message::user().with_text(format!("Summarize this session.\n\n{}", transcript))];
+    let (reply, mut usage) = provider
+        .complete_fast(session_id, SUMMARY_SYSTEM_PROMPT, &request, &[])
+        .await
+        .with_context(|| format!("failed to summarize session {}", source.id))?;
+    usage
+        .ensure_tokens(SUMMARY_SYSTEM_PROMPT, &request, &reply, &[])
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to count the summary's tokens: {}", e))?;
+    let summary = reply.as_concat_text().trim().to_string();
+    if summary.is_empty() {
+        anyhow::bail!(
+            "the model returned an empty summary of session {}",
+            source.id
+        );
+    }
+    Ok((summary, usage))
+}
+
+/// The system prompt that gives the agent the earlier session
+pub fn system_prompt(source: &Session, summary: &str) -> String {
+    format!(
+        "This run continues from an earlier session, \"{}\" ({}, {}). Use this summary of it as \
+         background; the user's new instructions take precedence.\n\n{}",
+        source.name,
+        source.id,
+        source.updated_at.format("%Y-%m-%d"),
+        summary
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_keeps_text_and_tool_names() {
+        let messages = [
+            Message::user().with_text("Fix the login test"),
+            Message::assistant()
+                .with_text("Looking at it.")
+                .with_tool_request(
+                    "1",
+                    Ok(rmcp::model::CallToolRequestParams {
+                        meta: None,
+                        task: None,
+                        name: "developer__shell".into(),
+                        arguments: None,
+                    }),
+                ),
+            Message::user()
+                .with_tool_response("1", Ok(rmcp::model::CallToolResult::success(vec![]))),
+            Message::assistant().with_text("Fixed."),
+        ];
+        assert_eq!(
+            transcript(&messages),
+            "User:\nFix the login test\n\n\
+             Assistant:\nLooking at it.\n[called developer__shell]\n\n\
+             Assistant:\nFixed."
+        );
+    }
+
+    #[test]
+    fn test_state_round_trips_through_extension_data() {
+        let mut data = goose::session::ExtensionData::new();
+        let state = ContextFromState {
+            system_prompt: "This run continues from an earlier session".to_string(),
+        };
+        state.to_extension_data(&mut data).unwrap();
+        assert_eq!(ContextFromState::from_extension_data(&data), Some(state));
+    }
+
+    #[test]
+    fn test_transcript_keeps_the_end_of_long_sessions() {
+        let long = "x".repeat(MAX_MESSAGE_CHARS);
+        let mut messages: Vec<Message> = (0..MAX_TRANSCRIPT_CHARS / MAX_MESSAGE_CHARS + 5)
+            .map(|_| Message::user().with_text(&long))
+            .collect();
+        messages.push(Message::assistant().with_text("the last word"));
+        let transcript = transcript(&messages);
+        assert!(transcript.len() <= MAX_TRANSCRIPT_CHARS);
+        assert!(transcript.ends_with("the last word"));
+    }
+}
@@ -0,0 +1,220 @@
+use goose::session::ExtensionState;
+use serde::{Deserialize, Serialize};
+
+/// Token usage attributed to one provider/model pair.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub provider: String,
+    pub model: String,
+    pub turns: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Running token totals for a CLI session, persisted in the session's extension data so the
+/// numbers survive `--resume`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostTracker {
+    pub models: Vec<ModelUsage>,
+    /// Session-level accumulated counters seen at the end of the previous turn.
+    #[serde(default)]
+    last_input_tokens: i32,
+    #[serde(default)]
+    last_output_tokens: i32,
+}
+
+impl ExtensionState for CostTracker {
+    const EXTENSION_NAME: &'static str = "cli_cost";
+    const VERSION: &'static str = "v0";
+}
+
+impl CostTracker {
+    /// Record a finished turn given the session's accumulated token counters. The difference to
+    /// the previous turn is attributed to `model`. Counters that went backwards (e.g. after
+    /// `/clear`) are treated as a fresh start.
+    pub fn record_turn(
+        &mut self,
+        provider: &str,
+        model: &str,
+        accumulated_input: i32,
+        accumulated_output: i32,
+    ) {
+        let delta = |now: i32, last: i32| -> u64 {
+            if now < last {
+                now.max(0) as u64
+            } else {
+                (now - last) as u64
+            }
+        };
+        let input = delta(accumulated_input, self.last_input_tokens);
+        let output = delta(accumulated_output, self.last_output_tokens);
+        self.last_input_tokens = accumulated_input;
+        self.last_output_tokens = accumulated_output;
+
+        if input == 0 && output == 0 {
+            return;
+        }
+
+        let usage = match self
+            .models
+            .iter_mut()
+            .position(|u| u.provider == provider && u.model == model)
+        {
+            Some(idx) => &mut self.models[idx],
+            None => {
+                self.models.push(ModelUsage {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                    ..Default::default()
+                });
+                self.models.last_mut().unwrap()
+            }
+        };
+        usage.turns += 1;
+        usage.input_tokens += input;
+        usage.output_tokens += output;
+    }
+
+    /// Start counting from the session's current counters, e.g. for sessions created before
+    /// cost tracking existed.
+    pub fn set_baseline(&mut self, accumulated_input: i32, accumulated_output: i32) {
+        self.last_input_tokens = accumulated_input;
+        self.last_output_tokens = accumulated_output;
+    }
+
+    pub fn total_tokens(&self) -> (u64, u64) {
+        self.models.iter().fold((0, 0), |(input, output), u| {
+            (input + u.input_tokens, output + u.output_tokens)
+        })
+    }
+
+    /// Output tokens per turn so far, to guess what the next turn will produce.
+    pub fn average_output_tokens(&self) -> u64 {
+        let turns: u64 = self.models.iter().map(|u| u.turns as u64).sum();
+        match turns {
+            0 => DEFAULT_OUTPUT_TOKENS,
+            turns => self.total_tokens().1 / turns,
+        }
+    }
+}
+
+/// Output tokens assumed for the next turn before any turn has been recorded
+const DEFAULT_OUTPUT_TOKENS: u64 = 1_000;
+
+/// Most models listed when comparing what the next turn would cost
+pub const MAX_COMPARED_MODELS: usize = 10;
+
+/// What the next turn would cost on one model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnCost {
+    pub provider: String,
+    pub model: String,
+    pub cost: f64,
+    pub current: bool,
+}
+
+/// Prices the next turn on each candidate model with `price`, cheapest first. Models without
+/// pricing are left out, and so are the most expensive beyond `limit`, except for the current
+/// model, which is always listed when it has a price so the others can be compared to it.
+pub fn compare_turn_costs(
+    candidates: &[(String, String)],
+    current: (&str, &str),
+    price: impl Fn(&str, &str) -> Option<f64>,
+    limit: usize,
+) -> Vec<TurnCost> {
+    let mut costs: Vec<TurnCost> = Vec::new();
+    for (provider, model) in candidates {
+        if costs
+            .iter()
+            .any(|c| &c.provider == provider && &c.model == model)
+        {
+            continue;
+        }
+        if let Some(cost) = price(provider, model) {
+            costs.push(TurnCost {
+                provider: provider.clone(),
+                model: model.clone(),
+                cost,
+                current: (provider.as_str(), model.as_str()) == current,
+            });
+        }
+    }
+    costs.sort_by(|a, b| a.cost.total_cmp(&b.cost));
+
+    let mut kept = 0;
+    costs.retain(|c| {
+        kept += 1;
+        kept <= limit || c.current
+    });
+    costs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_turn_accumulates_per_model() {
+        let mut tracker = CostTracker::default();
+        tracker.record_turn("openai", "gpt-4o", 100, 20);
+        tracker.record_turn("openai", "gpt-4o-mini", 250, 50);
+        tracker.record_turn("openai", "gpt-4o", 300, 60);
+
+        assert_eq!(tracker.models.len(), 2);
+        assert_eq!(tracker.models[0].turns, 2);
+        assert_eq!(tracker.models[0].input_tokens, 150);
+        assert_eq!(tracker.models[0].output_tokens, 30);
+        assert_eq!(tracker.models[1].input_tokens, 150);
+        assert_eq!(tracker.total_tokens(), (300, 60));
+    }
+
+    #[test]
+    fn test_record_turn_handles_reset_counters() {
+        let mut tracker = CostTracker::default();
+        tracker.set_baseline(1000, 200);
+        tracker.record_turn("anthropic", "claude", 1100, 250);
+        tracker.record_turn("anthropic", "claude", 40, 10);
+        assert_eq!(tracker.total_tokens(), (140, 60));
+    }
+
+    #[test]
+    fn test_compare_turn_costs() {
+        let candidates: Vec<(String, String)> = [
+            ("openai", "gpt-4o"),
+            ("openai", "gpt-4o-mini"),
+            ("openai", "unpriced"),
+            ("anthropic", "claude-opus"),
+            ("openai", "gpt-4o-mini"),
+        ]
+        .iter()
+        .map(|(p, m)| (p.to_string(), m.to_string()))
+        .collect();
+        let price = |_: &str, model: &str| match model {
+            "gpt-4o" => Some(0.05),
+            "gpt-4o-mini" => Some(0.003),
+            "claude-opus" => Some(0.3),
+            _ => None,
+        };
+
+        let costs = compare_turn_costs(&candidates, ("anthropic", "claude-opus"), price, 1);
+        let models: Vec<&str> = costs.iter().map(|c| c.model.as_str()).collect();
+        assert_eq!(models, vec!["gpt-4o-mini", "claude-opus"]);
+        assert!(costs[1].current);
+    }
+
+    #[test]
+    fn test_average_output_tokens() {
+        let mut tracker = CostTracker::default();
+        assert_eq!(tracker.average_output_tokens(), DEFAULT_OUTPUT_TOKENS);
+        tracker.record_turn("openai", "gpt-4o", 100, 200);
+        tracker.record_turn("openai", "gpt-4o", 200, 600);
+        assert_eq!(tracker.average_output_tokens(), 300);
+    }
+
+    #[test]
+    fn test_turn_without_tokens_is_not_counted() {
+        let mut tracker = CostTracker::default();
+        tracker.record_turn("openai", "gpt-4o", 0, 0);
+        assert!(tracker.models.is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use anstream::println;
+use console::style;
+use goose::config::Config;
+use goose::utils::safe_truncate;
+use similar::{ChangeTag, DiffTag, TextDiff};
+
+const CONTEXT_RADIUS: usize = 3;
+const TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffStyle {
+    Unified,
+    SideBySide,
+}
+
+impl DiffStyle {
+    pub fn from_config() -> Self {
+        Config::global()
+            .get_param::<String>("GOOSE_CLI_DIFF_STYLE")
+            .ok()
+            .and_then(|s| Self::from_config_str(&s))
+            .unwrap_or(DiffStyle::Unified)
+    }
+
+    fn from_config_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "unified" => Some(DiffStyle::Unified),
+            "sidebyside" | "split" => Some(DiffStyle::SideBySide),
+            _ => None,
+        }
+    }
+}
+
+/// Builds a unified diff with `a/` and `b/` headers, or `None` when nothing changed.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let diff = TextDiff::from_lines(old, new);
+    let text = diff
+        .unified_diff()
+        .context_radius(CONTEXT_RADIUS)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string();
+    Some(text)
+}
+
+/// One row of a side-by-side view: the old line on the left, the new line on the right,
+/// each with its 1-based line number.
+#[derive(Debug, PartialEq)]
+pub struct SideBySideRow<'a> {
+    pub tag: DiffTag,
+    pub left: Option<(usize, &'a str)>,
+    pub right: Option<(usize, &'a str)>,
+}
+
+/// Pairs old and new lines into rows, grouped into hunks with a few lines of context.
+pub fn side_by_side_rows<'a>(old: &'a str, new: &'a str) -> Vec<Vec<SideBySideRow<'a>>> {
+    let diff = TextDiff::from_lines(old, new);
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    diff.grouped_ops(CONTEXT_RADIUS)
+        .iter()
+        .map(|group| {
+            let mut rows = Vec::new();
+            for op in group {
+                let (tag, old_range, new_range) = op.as_tag_tuple();
+                let len = old_range.len().max(new_range.len());
+                for i in 0..len {
+                    let left = (tag != DiffTag::Insert && i < old_range.len())
+                        .then_some(old_range.start + i)
+                        .and_then(|idx| old_lines.get(idx).map(|l| (idx + 1, *l)));
+                    let right = (tag != DiffTag::Delete && i < new_range.len())
+                        .then_some(new_range.start + i)
+                        .and_then(|idx| new_lines.get(idx).map(|l| (idx + 1, *l)));
+                    if left.is_some() || right.is_some() {
+                        rows.push(SideBySideRow { tag, left, right });
+                    }
+                }
+            }
+            rows
+        })
+        .collect()
+}
+
+/// Counts added and removed lines.
+pub fn diff_stats(old: &str, new: &str) -> (usize, usize) {
+    let diff = TextDiff::from_lines(old, new);
+    diff.iter_all_changes()
+        .fold((0, 0), |(added, removed), change| match change.tag() {
+            ChangeTag::Insert => (added + 1, removed),
+            ChangeTag::Delete => (added, removed + 1),
+            ChangeTag::Equal => (added, removed),
+        })
+}
+
+/// Changed files with their added and removed lines, as in `3 files changed, +42 -7`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DiffStat {
+    pub files: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl DiffStat {
+    /// Totals over `(old, new)` pairs of file contents.
+    pub fn of<S: AsRef<str>>(files: impl IntoIterator<Item = (S, S)>) -> Self {
+        files
+            .into_iter()
+            .fold(DiffStat::default(), |stat, (old, new)| {
+                let (added, removed) = diff_stats(old.as_ref(), new.as_ref());
+                DiffStat {
+                    files: stat.files + 1,
+                    added: stat.added + added,
+                    removed: stat.removed + removed,
+                }
+            })
+    }
+
+    pub fn files_changed(&self) -> String {
+        format!(
+            "{} file{} changed",
+            self.files,
+            if self.files == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Prints the change in two columns. Returns false, having printed nothing, when `width` is too
+/// narrow for them.
+pub fn render_side_by_side(old: &str, new: &str, width: usize, max_rows: Option<usize>) -> bool {
+    let hunks = side_by_side_rows(old, new);
+    let number_width = old
+        .lines()
+        .count()
+        .max(new.lines().count())
+        .to_string()
+        .len();
+    // "    " indent, two line-number gutters, and the " │ " separator
+    let column_width = width.saturating_sub(4 + 2 * (number_width + 1) + 3) / 2;
+    if column_width < 10 {
+        return false;
+    }
+
+    let mut printed = 0;
+    let total: usize = hunks.iter().map(Vec::len).sum();
+    for (i, hunk) in hunks.iter().enumerate() {
+        if i > 0 {
+            println!("    {}", style("┈".repeat(width.saturating_sub(4))).dim());
+        }
+        for row in hunk {
+            if max_rows.is_some_and(|max| printed >= max) {
+                println!(
+                    "    {}",
+                    style(format!("... {} more lines", total - printed)).dim()
+                );
+                return true;
+            }
+            let left = format_cell(row.left, number_width, column_width);
+            let right = format_cell(row.right, number_width, column_width);
+            let (left, right) = match row.tag {
+                DiffTag::Equal => (style(left).dim(), style(right).dim()),
+                DiffTag::Delete => (style(left).red(), style(right).dim()),
+                DiffTag::Insert => (style(left).dim(), style(right).green()),
+                DiffTag::Replace => (style(left).red(), style(right).green()),
+            };
+            println!("    {}{}{}", left, style(" │ ").dim(), right);
+            printed += 1;
+        }
+    }
+    true
+}
+
+fn format_cell(line: Option<(usize, &str)>, number_width: usize, column_width: usize) -> String {
+    match line {
+        Some((number, text)) => {
+            let text = text.replace('\t', &" ".repeat(TAB_WIDTH));
+            let text = safe_truncate(text.trim_end(), column_width);
+            format!(
+                "{:>nw$} {:<cw$}",
+                number,
+                text,
+                nw = number_width,
+                cw = column_width
+            )
+        }
+        None => " ".repeat(number_width + 1 + column_width),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_unified_diff_marks_changed_lines() {
+        let diff = unified_diff("src/lib.rs", "a\nb\nc\n", "a\nB\nc\n").unwrap();
+        assert!(diff.starts_with("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+        assert!(diff.contains("-b\n"));
+        assert!(diff.contains("+B\n"));
+        assert!(diff.contains(" a\n"));
+    }
+
+    #[test]
+    fn test_unified_diff_unchanged() {
+        assert_eq!(unified_diff("a.txt", "same\n", "same\n"), None);
+    }
+
+    #[test]
+    fn test_diff_stat_totals_files() {
+        let stat = DiffStat::of([("a\nb\n", "a\nB\nc\n"), ("", "new\n")]);
+        assert_eq!(
+            stat,
+            DiffStat {
+                files: 2,
+                added: 3,
+                removed: 1
+            }
+        );
+        assert_eq!(stat.files_changed(), "2 files changed");
+        assert_eq!(
+            DiffStat::of([("x\n", "")]).files_changed(),
+            "1 file changed"
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_pairs_replacements() {
+        let hunks = side_by_side_rows("one\ntwo\nthree\n", "one\n2\nthree\nfour\n");
+        assert_eq!(hunks.len(), 1);
+        let rows = &hunks[0];
+        assert_eq!(
+            rows[0],
+            SideBySideRow {
+                tag: DiffTag::Equal,
+                left: Some((1, "one")),
+                right: Some((1, "one")),
+            }
+        );
+        assert_eq!(
+            rows[1],
+            SideBySideRow {
+                tag: DiffTag::Replace,
+                left: Some((2, "two")),
+                right: Some((2, "2")),
+            }
+        );
+        assert_eq!(
+            rows.last().unwrap(),
+            &SideBySideRow {
+                tag: DiffTag::Insert,
+                left: None,
+                right: Some((4, "four")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_declines_narrow_width() {
+        assert!(!render_side_by_side("one\n", "two\n", 30, None));
+    }
+
+    #[test]
+    fn test_diff_stats() {
+        assert_eq!(diff_stats("a\nb\n", "a\nc\nd\n"), (2, 1));
+        assert_eq!(diff_stats("", "x\ny\n"), (2, 0));
+    }
+
+    #[test_case("unified", Some(DiffStyle::Unified))]
+    #[test_case("side-by-side", Some(DiffStyle::SideBySide))]
+    #[test_case("SIDE_BY_SIDE", Some(DiffStyle::SideBySide))]
+    #[test_case("split", Some(DiffStyle::SideBySide))]
+    #[test_case("fancy", None)]
+    fn test_diff_style_from_config_str(input: &str, expected: Option<DiffStyle>) {
+        assert_eq!(DiffStyle::from_config_str(input), expected);
+    }
+}
@@ -3,6 +3,44 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::io::{self, BufRead, IsTerminal, Write};
 
+/// A field of an elicitation request, read from its JSON schema.
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    name: String,
+    label: String,
+    required: bool,
+    default: Option<Value>,
+    kind: FieldKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldKind {
+    Boolean,
+    Choice(Vec<Choice>),
+    MultiChoice(Vec<Choice>),
+    /// An array without options to pick from, typed as comma-separated items of the given type
+    List(String),
+    Integer {
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+    },
+    Number {
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+    },
+    Text {
+        min_length: Option<usize>,
+        max_length: Option<usize>,
+        format: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Choice {
+    value: Value,
+    label: String,
+}
+
 pub fn collect_elicitation_input(
     message: &str,
     schema: &Value,
@@ -11,6 +49,12 @@ pub fn collect_elicitation_input(
         println!("\n{}", style(message).cyan());
     }
 
+    if std::io::stdin().is_terminal() {
+        let data = collect_form_input(&parse_fields(schema))?;
+        println!();
+        return Ok(data);
+    }
+
     let properties = match schema.get("properties").and_then(|p| p.as_object()) {
         Some(props) => props,
         None => return Ok(Some(HashMap::new())),
@@ -106,6 +150,292 @@ pub fn collect_elicitation_input(
     Ok(Some(data))
 }
 
+/// Shows one typed widget per field. Returns `None` if the user cancels.
+fn collect_form_input(fields: &[Field]) -> io::Result<Option<HashMap<String, Value>>> {
+    let mut data: HashMap<String, Value> = HashMap::new();
+
+    for field in fields {
+        let result = match &field.kind {
+            FieldKind::Boolean => cliclack::confirm(&field.label)
+                .initial_value(
+                    field
+                        .default
+                        .as_ref()
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                )
+                .interact()
+                .map(|v| Some(Value::Bool(v))),
+            FieldKind::Choice(choices) => {
+                let mut select = cliclack::select(&field.label);
+                for (idx, choice) in choices.iter().enumerate() {
+                    select = select.item(idx, &choice.label, "");
+                }
+                if let Some(idx) = field
+                    .default
+                    .as_ref()
+                    .and_then(|d| choices.iter().position(|c| &c.value == d))
+                {
+                    select = select.initial_value(idx);
+                }
+                select
+                    .interact()
+                    .map(|idx| Some(choices[idx].value.clone()))
+            }
+            FieldKind::MultiChoice(choices) => {
+                let mut multiselect = cliclack::multiselect(&field.label).required(field.required);
+                for (idx, choice) in choices.iter().enumerate() {
+                    multiselect = multiselect.item(idx, &choice.label, "");
+                }
+                let defaults: Vec<usize> = field
+                    .default
+                    .as_ref()
+                    .and_then(Value::as_array)
+                    .map(|values| {
+                        choices
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, c)| values.contains(&c.value))
+                            .map(|(idx, _)| idx)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                multiselect
+                    .initial_values(defaults)
+                    .interact()
+                    .map(|picked| {
+                        Some(Value::Array(
+                            picked
+                                .iter()
+                                .map(|idx| choices[*idx].value.clone())
+                                .collect(),
+                        ))
+                    })
+            }
+            kind => {
+                let mut input = cliclack::input(&field.label).required(field.required);
+                if let Some(default) = &field.default {
+                    input = input.default_input(&format_default(default));
+                }
+                let validator_kind = kind.clone();
+                let required = field.required;
+                input
+                    .validate(move |text: &String| validate_text(&validator_kind, required, text))
+                    .interact::<String>()
+                    .map(|text| text_to_value(kind, &text))
+            }
+        };
+
+        match result {
+            Ok(Some(value)) => {
+                data.insert(field.name.clone(), value);
+            }
+            Ok(None) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(Some(data))
+}
+
+fn parse_fields(schema: &Value) -> Vec<Field> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Vec::new();
+    };
+
+    properties
+        .iter()
+        .map(|(name, field_schema)| {
+            let title = field_schema
+                .get("title")
+                .and_then(|t| t.as_str())
+                .unwrap_or(name);
+            let label = match field_schema.get("description").and_then(|d| d.as_str()) {
+                Some(desc) => format!("{} ({})", title, desc),
+                None => title.to_string(),
+            };
+            Field {
+                name: name.clone(),
+                label,
+                required: required.contains(&name.as_str()),
+                default: field_schema.get("default").cloned(),
+                kind: field_kind(field_schema),
+            }
+        })
+        .collect()
+}
+
+fn field_kind(field_schema: &Value) -> FieldKind {
+    let bound = |key: &str| field_schema.get(key).and_then(Value::as_f64);
+    let length = |key: &str| {
+        field_schema
+            .get(key)
+            .and_then(Value::as_u64)
+            .map(|n| n as usize)
+    };
+
+    match field_schema.get("type").and_then(|t| t.as_str()) {
+        Some("boolean") => FieldKind::Boolean,
+        Some("array") => {
+            let items = field_schema.get("items");
+            let choices = items.map(choices).unwrap_or_default();
+            if choices.is_empty() {
+                let item_type = items
+                    .and_then(|i| i.get("type"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("string");
+                FieldKind::List(item_type.to_string())
+            } else {
+                FieldKind::MultiChoice(choices)
+            }
+        }
+        _ if !choices(field_schema).is_empty() => FieldKind::Choice(choices(field_schema)),
+        Some("integer") => FieldKind::Integer {
+            minimum: bound("minimum"),
+            maximum: bound("maximum"),
+        },
+        Some("number") => FieldKind::Number {
+            minimum: bound("minimum"),
+            maximum: bound("maximum"),
+        },
+        _ => FieldKind::Text {
+            min_length: length("minLength"),
+            max_length: length("maxLength"),
+            format: field_schema
+                .get("format")
+                .and_then(|f| f.as_str())
+                .map(str::to_string),
+        },
+    }
+}
+
+/// Options from `enum` (labelled by `enumNames` if present) or from `oneOf`/`anyOf` entries with
+/// a `const` and optional `title`.
+fn choices(schema: &Value) -> Vec<Choice> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let names = schema.get("enumNames").and_then(Value::as_array);
+        return values
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| Choice {
+                value: value.clone(),
+                label: names
+                    .and_then(|n| n.get(idx))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format_default(value)),
+            })
+            .collect();
+    }
+
+    ["oneOf", "anyOf"]
+        .iter()
+        .filter_map(|key| schema.get(*key).and_then(Value::as_array))
+        .flatten()
+        .filter_map(|option| {
+            let value = option.get("const")?.clone();
+            let label = option
+                .get("title")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| format_default(&value));
+            Some(Choice { value, label })
+        })
+        .collect()
+}
+
+fn validate_text(kind: &FieldKind, required: bool, text: &str) -> Result<(), String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return if required {
+            Err("This field is required".to_string())
+        } else {
+            Ok(())
+        };
+    }
+
+    let check_bounds = |n: f64, minimum: &Option<f64>, maximum: &Option<f64>| {
+        if let Some(min) = minimum.filter(|min| n < *min) {
+            return Err(format!("Must be at least {}", min));
+        }
+        if let Some(max) = maximum.filter(|max| n > *max) {
+            return Err(format!("Must be at most {}", max));
+        }
+        Ok(())
+    };
+
+    match kind {
+        FieldKind::Integer { minimum, maximum } => match text.parse::<i64>() {
+            Ok(n) => check_bounds(n as f64, minimum, maximum),
+            Err(_) => Err("Enter a whole number".to_string()),
+        },
+        FieldKind::Number { minimum, maximum } => match text.parse::<f64>() {
+            Ok(n) => check_bounds(n, minimum, maximum),
+            Err(_) => Err("Enter a number".to_string()),
+        },
+        FieldKind::List(item_type) => match list_items(item_type, text) {
+            Some(_) => Ok(()),
+            None => Err(format!("Enter {} values separated by commas", item_type)),
+        },
+        FieldKind::Text {
+            min_length,
+            max_length,
+            format,
+        } => {
+            let len = text.chars().count();
+            if let Some(min) = min_length.filter(|min| len < *min) {
+                return Err(format!("Must be at least {} characters", min));
+            }
+            if let Some(max) = max_length.filter(|max| len > *max) {
+                return Err(format!("Must be at most {} characters", max));
+            }
+            match format.as_deref() {
+                Some("email") if !text.contains('@') => {
+                    Err("Enter a valid email address".to_string())
+                }
+                Some("uri") if url::Url::parse(text).is_err() => {
+                    Err("Enter a valid URL".to_string())
+                }
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+fn text_to_value(kind: &FieldKind, text: &str) -> Option<Value> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let value = match kind {
+        FieldKind::Integer { .. } => parse_value(text, "integer", None),
+        FieldKind::Number { .. } => parse_value(text, "number", None),
+        FieldKind::List(item_type) => Value::Array(list_items(item_type, text)?),
+        _ => parse_value(text, "string", None),
+    };
+    (!value.is_null()).then_some(value)
+}
+
+/// Splits comma-separated input into items of `item_type`, or `None` if one doesn't parse
+fn list_items(item_type: &str, text: &str) -> Option<Vec<Value>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let value = parse_value(item, item_type, None);
+            (!value.is_null()).then_some(value)
+        })
+        .collect()
+}
+
 fn read_line() -> io::Result<Option<String>> {
     if !std::io::stdin().is_terminal() {
         let mut line = String::new();
@@ -127,6 +457,11 @@ fn format_default(value: &Value) -> String {
         Value::String(s) => s.clone(),
         Value::Bool(b) => b.to_string(),
         Value::Number(n) => n.to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(format_default)
+            .collect::<Vec<_>>()
+            .join(", "),
         _ => value.to_string(),
     }
 }
@@ -162,3 +497,78 @@ fn parse_value(input: &str, field_type: &str, enum_values: Option<&Vec<Value>>)
         _ => Value::String(input.to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[test]
+    fn test_parse_fields_picks_widgets() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "confirm": {"type": "boolean", "default": true},
+                "color": {"type": "string", "enum": ["r", "g"], "enumNames": ["Red", "Green"]},
+                "size": {"type": "string", "oneOf": [{"const": "s", "title": "Small"}]},
+                "tags": {"type": "array", "items": {"enum": ["a", "b"]}},
+                "labels": {"type": "array", "items": {"type": "string"}},
+                "count": {"type": "integer", "minimum": 1, "title": "Count"},
+                "email": {"type": "string", "format": "email", "description": "Contact"}
+            },
+            "required": ["count"]
+        });
+        let fields = parse_fields(&schema);
+        let field = |name: &str| fields.iter().find(|f| f.name == name).unwrap();
+
+        assert_eq!(field("confirm").kind, FieldKind::Boolean);
+        assert_eq!(field("confirm").default, Some(json!(true)));
+        match &field("color").kind {
+            FieldKind::Choice(choices) => {
+                assert_eq!(choices[1].label, "Green");
+                assert_eq!(choices[1].value, json!("g"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(&field("size").kind, FieldKind::Choice(c) if c[0].label == "Small"));
+        assert!(matches!(&field("tags").kind, FieldKind::MultiChoice(c) if c.len() == 2));
+        assert_eq!(field("labels").kind, FieldKind::List("string".to_string()));
+        assert!(field("count").required);
+        assert!(!field("email").required);
+        assert_eq!(field("email").label, "email (Contact)");
+    }
+
+    #[test_case(FieldKind::Integer { minimum: Some(1.0), maximum: None }, "0", false ; "integer below minimum")]
+    #[test_case(FieldKind::Integer { minimum: None, maximum: None }, "1.5", false ; "integer rejects fraction")]
+    #[test_case(FieldKind::Number { minimum: None, maximum: Some(1.0) }, "0.5", true ; "number in range")]
+    #[test_case(FieldKind::Text { min_length: Some(3), max_length: None, format: None }, "ab", false ; "text too short")]
+    #[test_case(FieldKind::Text { min_length: None, max_length: None, format: Some("email".into()) }, "a@b.c", true ; "valid email")]
+    #[test_case(FieldKind::Text { min_length: None, max_length: None, format: Some("uri".into()) }, "not a url", false ; "invalid uri")]
+    fn test_validate_text(kind: FieldKind, text: &str, valid: bool) {
+        assert_eq!(validate_text(&kind, false, text).is_ok(), valid);
+    }
+
+    #[test]
+    fn test_required_text_must_not_be_empty() {
+        let kind = FieldKind::Text {
+            min_length: None,
+            max_length: None,
+            format: None,
+        };
+        assert!(validate_text(&kind, true, "  ").is_err());
+        assert!(validate_text(&kind, false, "").is_ok());
+        assert_eq!(text_to_value(&kind, ""), None);
+    }
+
+    #[test]
+    fn test_list_is_typed_as_comma_separated_text() {
+        let strings = FieldKind::List("string".to_string());
+        assert_eq!(text_to_value(&strings, "a, b,,"), Some(json!(["a", "b"])));
+        assert_eq!(format_default(&json!(["a", "b"])), "a, b");
+
+        let integers = FieldKind::List("integer".to_string());
+        assert_eq!(text_to_value(&integers, "1,2"), Some(json!([1, 2])));
+        assert!(validate_text(&integers, false, "1, x").is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use goose::agents::ExtensionError;
+use goose::providers::errors::ProviderError;
+use thiserror::Error;
+
+/// Errors that end a turn, grouped by what the user can do about them.
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("Authentication with the provider failed: {0}")]
+    ProviderAuth(String),
+
+    #[error("Rate limited by the provider: {0}")]
+    RateLimit(String),
+
+    #[error("The conversation no longer fits in the model's context: {0}")]
+    ContextOverflow(String),
+
+    #[error("Extension failed to start: {0}")]
+    ExtensionStartup(String),
+
+    #[error("Tool call failed: {0}")]
+    ToolFailure(String),
+
+    #[error("Not allowed without a prompt: {0}")]
+    PermissionDenied(String),
+
+    #[error("Ran out of turns: {0}")]
+    BudgetExceeded(String),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl SessionError {
+    /// Works out the class of an error coming back from the agent.
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if let Some(provider_error) = error.downcast_ref::<ProviderError>() {
+            return match provider_error {
+                ProviderError::Authentication(msg) => Self::ProviderAuth(msg.clone()),
+                ProviderError::RateLimitExceeded { details, .. } => {
+                    Self::RateLimit(details.clone())
+                }
+                ProviderError::ContextLengthExceeded(msg) => Self::ContextOverflow(msg.clone()),
+                other => Self::Other(other.to_string()),
+            };
+        }
+        if let Some(extension_error) = error.downcast_ref::<ExtensionError>() {
+            return match extension_error {
+                ExtensionError::Client(e) => Self::ToolFailure(e.to_string()),
+                other => Self::ExtensionStartup(other.to_string()),
+            };
+        }
+        Self::Other(error.to_string())
+    }
+
+    /// A suggestion for getting past the error, shown under the message.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::ProviderAuth(_) => Some("Run `goose configure` to set your API key"),
+            Self::RateLimit(_) => Some("Wait a moment and send your message again"),
+            Self::ContextOverflow(_) => {
+                Some("Try /compact to summarize the conversation, or /clear to start over")
+            }
+            Self::ExtensionStartup(_) => {
+                Some("Check the extension's command and environment with `goose configure`")
+            }
+            Self::ToolFailure(_) => {
+                Some("The extension may have crashed; restart the session to reconnect it")
+            }
+            Self::PermissionDenied(_) => Some(
+                "Set GOOSE_CLI_NON_INTERACTIVE_APPROVAL=approve, or allow the tool with `goose configure`",
+            ),
+            Self::BudgetExceeded(_) => Some("Raise --max-turns or split the task into smaller runs"),
+            Self::Other(_) => None,
+        }
+    }
+
+    /// Process exit code for non-interactive runs, so scripts can tell failures apart.
+    /// 2 is left to clap for usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Other(_) => 1,
+            Self::ProviderAuth(_) => 3,
+            Self::RateLimit(_) => 4,
+            Self::ContextOverflow(_) => 5,
+            Self::ExtensionStartup(_) => 6,
+            Self::ToolFailure(_) => 7,
+            Self::PermissionDenied(_) => 8,
+            Self::BudgetExceeded(_) => 9,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(ProviderError::Authentication("bad key".into()), 3 ; "auth")]
+    #[test_case(ProviderError::RateLimitExceeded { details: "slow down".into(), retry_delay: None }, 4 ; "rate limit")]
+    #[test_case(ProviderError::ContextLengthExceeded("too long".into()), 5 ; "context")]
+    #[test_case(ProviderError::ServerError("500".into()), 1 ; "other provider error")]
+    fn test_classify_provider_errors(error: ProviderError, exit_code: i32) {
+        let classified = SessionError::classify(&anyhow::Error::new(error));
+        assert_eq!(classified.exit_code(), exit_code);
+    }
+
+    #[test]
+    fn test_classify_extension_and_unknown_errors() {
+        let setup = anyhow::Error::new(ExtensionError::SetupError("no such command".into()));
+        assert!(matches!(
+            SessionError::classify(&setup),
+            SessionError::ExtensionStartup(_)
+        ));
+
+        let other = SessionError::classify(&anyhow::anyhow!("something else"));
+        assert_eq!(other.to_string(), "something else");
+        assert_eq!(other.hint(), None);
+    }
+
+    #[test]
+    fn test_non_interactive_exit_codes() {
+        let denied = SessionError::PermissionDenied("developer__shell needs approval".into());
+        let budget = SessionError::BudgetExceeded("stopped after 5 turns".into());
+        assert_eq!(denied.exit_code(), 8);
+        assert_eq!(budget.exit_code(), 9);
+        assert!(denied.hint().is_some());
+        assert!(budget.hint().is_some());
+    }
+}
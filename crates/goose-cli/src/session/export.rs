@@ -4,6 +4,11 @@ use goose::conversation::message::{
 use goose::utils::safe_truncate;
 use rmcp::model::{RawContent, ResourceContents, Role};
 use serde_json::Value;
+use std::collections::HashMap;
+
+use super::diff::unified_diff;
+use super::files_touched::{files_touched, succeeded_calls};
+use super::output::shorten_path;
 
 const MAX_STRING_LENGTH_MD_EXPORT: usize = 4096; // Generous limit for export
 const REDACTED_PREFIX_LENGTH: usize = 100; // Show first 100 chars before trimming
@@ -338,6 +343,68 @@ pub fn tool_response_to_markdown(resp: &ToolResponse, export_all_content: bool)
     md
 }
 
+/// The files the session's tools read, wrote or deleted as a section closing an exported
+/// transcript, or nothing when no tool touched a file
+pub fn files_touched_markdown(messages: &[Message]) -> String {
+    let files = files_touched(messages);
+    if files.is_empty() {
+        return String::new();
+    }
+    let mut md = String::from("## Files touched\n\n| File | Read | Written | Deleted |\n");
+    md.push_str("| --- | ---: | ---: | ---: |\n");
+    for file in files {
+        md.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            shorten_path(&file.path, false).replace('|', "\\|"),
+            file.reads,
+            file.writes,
+            file.deletes
+        ));
+    }
+    md.push('\n');
+    md
+}
+
+/// The changes the session's text editor calls made, as one patch. Replacements are diffed on
+/// their own, so their hunks count lines from the replaced text rather than the file, and a
+/// write is diffed against what the session last wrote there, or an empty file.
+pub fn changes_patch(messages: &[Message]) -> String {
+    let mut written: HashMap<&str, &str> = HashMap::new();
+    let mut patch = String::new();
+    for call in succeeded_calls(messages) {
+        if call.name != "developer__text_editor" {
+            continue;
+        }
+        let Some(args) = &call.arguments else {
+            continue;
+        };
+        let str_arg = move |key: &str| args.get(key).and_then(Value::as_str);
+        let Some(path) = str_arg("path") else {
+            continue;
+        };
+        let change = match str_arg("command") {
+            Some("str_replace") => match str_arg("diff") {
+                Some(diff) => Some(diff.to_string()),
+                None => str_arg("old_str")
+                    .zip(str_arg("new_str"))
+                    .and_then(|(old, new)| unified_diff(path, old, new)),
+            },
+            Some("write") => str_arg("file_text").and_then(|new| {
+                let old = written.insert(path, new).unwrap_or_default();
+                unified_diff(path, old, new)
+            }),
+            _ => None,
+        };
+        if let Some(change) = change {
+            patch.push_str(&change);
+            if !patch.ends_with('\n') {
+                patch.push('\n');
+            }
+        }
+    }
+    patch
+}
+
 pub fn message_to_markdown(message: &Message, export_all_content: bool) -> String {
     let mut md = String::new();
     for content in &message.content {
@@ -396,6 +463,20 @@ pub fn message_to_markdown(message: &Message, export_all_content: bool) -> Strin
             MessageContent::SystemNotification(notification) => {
                 md.push_str(&format!("*{}*\n\n", notification.msg));
             }
+            MessageContent::Citations(citations) => {
+                md.push_str("**Sources:**\n");
+                for (i, citation) in citations.citations.iter().enumerate() {
+                    let title = citation.title.as_deref().or(citation.url.as_deref());
+                    match (title, &citation.url) {
+                        (Some(title), Some(url)) => {
+                            md.push_str(&format!("{}. [{}]({})\n", i + 1, title, url))
+                        }
+                        (Some(title), None) => md.push_str(&format!("{}. {}\n", i + 1, title)),
+                        (None, _) => {}
+                    }
+                }
+                md.push('\n');
+            }
             _ => {
                 md.push_str(
                     "`WARNING: Message content type could not be rendered to Markdown`\n\n",
@@ -410,7 +491,7 @@ pub fn message_to_markdown(message: &Message, export_all_content: bool) -> Strin
 mod tests {
     use super::*;
     use goose::conversation::message::{Message, ToolRequest, ToolResponse};
-    use rmcp::model::{CallToolRequestParams, Content, RawTextContent, TextContent};
+    use rmcp::model::{CallToolRequestParams, Content, JsonObject, RawTextContent, TextContent};
     use rmcp::object;
     use serde_json::json;
 
@@ -1327,4 +1408,74 @@ found 0 vulnerabilities"#;
         assert!(response_result.contains("added 57 packages"));
         assert!(response_result.contains("found 0 vulnerabilities"));
     }
+
+    #[test]
+    fn test_files_touched_markdown() {
+        let call = CallToolRequestParams {
+            meta: None,
+            task: None,
+            name: "developer__text_editor".into(),
+            arguments: Some(object!({"command": "write", "path": "/tmp/notes.md"})),
+        };
+        let messages = vec![
+            Message::assistant().with_tool_request("1", Ok(call)),
+            Message::user().with_tool_response(
+                "1",
+                Ok(rmcp::model::CallToolResult {
+                    content: vec![],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            ),
+        ];
+        assert_eq!(
+            files_touched_markdown(&messages),
+            "## Files touched\n\n| File | Read | Written | Deleted |\n\
+             | --- | ---: | ---: | ---: |\n| `/tmp/notes.md` | 0 | 1 | 0 |\n\n"
+        );
+        assert_eq!(files_touched_markdown(&[]), "");
+    }
+
+    #[test]
+    fn test_changes_patch() {
+        let call = |id: &str, args: JsonObject| {
+            let call = CallToolRequestParams {
+                meta: None,
+                task: None,
+                name: "developer__text_editor".into(),
+                arguments: Some(args),
+            };
+            vec![
+                Message::assistant().with_tool_request(id, Ok(call)),
+                Message::user().with_tool_response(
+                    id,
+                    Ok(rmcp::model::CallToolResult {
+                        content: vec![],
+                        structured_content: None,
+                        is_error: Some(false),
+                        meta: None,
+                    }),
+                ),
+            ]
+        };
+        let messages = [
+            call(
+                "1",
+                object!({"command": "write", "path": "notes.md", "file_text": "a\n"}),
+            ),
+            call(
+                "2",
+                object!({"command": "write", "path": "notes.md", "file_text": "a\nb\n"}),
+            ),
+            call("3", object!({"command": "view", "path": "notes.md"})),
+        ]
+        .concat();
+        assert_eq!(
+            changes_patch(&messages),
+            "--- a/notes.md\n+++ b/notes.md\n@@ -0,0 +1 @@\n+a\n\
+             --- a/notes.md\n+++ b/notes.md\n@@ -1 +1,2 @@\n a\n+b\n"
+        );
+        assert_eq!(changes_patch(&[]), "");
+    }
 }
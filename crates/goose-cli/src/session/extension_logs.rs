@@ -0,0 +1,168 @@
+use chrono::{DateTime, Local};
+use goose::config::paths::Paths;
+use goose::config::Config;
+use goose::session::session_manager::SESSIONS_FOLDER;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Log lines kept per extension for `/logs`.
+const MAX_LINES_PER_EXTENSION: usize = 500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub time: DateTime<Local>,
+    pub message: String,
+}
+
+/// Recent log messages from each extension, most of which are otherwise only flashed in a
+/// spinner. Optionally also appended to `<sessions dir>/logs/<session id>/<extension>.log`.
+#[derive(Default)]
+pub struct ExtensionLogs {
+    lines: HashMap<String, VecDeque<LogLine>>,
+    tee_dir: Option<PathBuf>,
+}
+
+impl ExtensionLogs {
+    pub fn record(&mut self, extension: &str, message: &str, time: DateTime<Local>) {
+        let line = LogLine {
+            time,
+            message: message.trim_end().to_string(),
+        };
+        if let Some(dir) = &self.tee_dir {
+            if let Err(e) = append_to_file(dir, extension, &line) {
+                tracing::debug!("Failed to write extension log for {}: {}", extension, e);
+            }
+        }
+
+        let lines = self.lines.entry(extension.to_string()).or_default();
+        if lines.len() == MAX_LINES_PER_EXTENSION {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// The last `count` lines of one extension, or of all of them interleaved by time.
+    pub fn tail(&self, extension: Option<&str>, count: usize) -> Vec<(String, LogLine)> {
+        let mut lines: Vec<(String, LogLine)> = self
+            .lines
+            .iter()
+            .filter(|(name, _)| extension.is_none_or(|wanted| *name == wanted))
+            .flat_map(|(name, lines)| lines.iter().map(move |l| (name.clone(), l.clone())))
+            .collect();
+        lines.sort_by_key(|(_, line)| line.time);
+        let skip = lines.len().saturating_sub(count);
+        lines.split_off(skip)
+    }
+
+    pub fn tee_dir(&self) -> Option<&Path> {
+        self.tee_dir.as_deref()
+    }
+
+    pub fn extensions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.lines.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+fn append_to_file(dir: &Path, extension: &str, line: &LogLine) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let file_name = format!("{}.log", extension.replace(['/', '\\'], "_"));
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(file_name))?;
+    writeln!(file, "{} {}", line.time.to_rfc3339(), line.message)
+}
+
+static EXTENSION_LOGS: LazyLock<Mutex<ExtensionLogs>> =
+    LazyLock::new(|| Mutex::new(ExtensionLogs::default()));
+
+/// Tee logs into files for this session when `GOOSE_CLI_TEE_EXTENSION_LOGS` is set.
+pub fn init_for_session(session_id: &str) {
+    let tee = Config::global()
+        .get_param::<bool>("GOOSE_CLI_TEE_EXTENSION_LOGS")
+        .unwrap_or(false);
+    if let Ok(mut logs) = EXTENSION_LOGS.lock() {
+        logs.tee_dir = tee.then(|| {
+            Paths::in_data_dir(SESSIONS_FOLDER)
+                .join("logs")
+                .join(session_id)
+        });
+    }
+}
+
+pub fn record(extension: &str, message: &str) {
+    if let Ok(mut logs) = EXTENSION_LOGS.lock() {
+        logs.record(extension, message, Local::now());
+    }
+}
+
+pub fn with_logs<T>(f: impl FnOnce(&ExtensionLogs) -> T) -> T {
+    let logs = EXTENSION_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    f(&logs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(secs: i64) -> DateTime<Local> {
+        Local.timestamp_opt(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_tail_interleaves_and_filters() {
+        let mut logs = ExtensionLogs::default();
+        logs.record("developer", "starting", at(1));
+        logs.record("github", "authenticated\n", at(2));
+        logs.record("developer", "ready", at(3));
+
+        let all: Vec<(String, String)> = logs
+            .tail(None, 10)
+            .into_iter()
+            .map(|(ext, line)| (ext, line.message))
+            .collect();
+        assert_eq!(
+            all,
+            vec![
+                ("developer".to_string(), "starting".to_string()),
+                ("github".to_string(), "authenticated".to_string()),
+                ("developer".to_string(), "ready".to_string()),
+            ]
+        );
+
+        let developer = logs.tail(Some("developer"), 1);
+        assert_eq!(developer.len(), 1);
+        assert_eq!(developer[0].1.message, "ready");
+        assert_eq!(logs.extensions(), vec!["developer", "github"]);
+    }
+
+    #[test]
+    fn test_keeps_only_recent_lines() {
+        let mut logs = ExtensionLogs::default();
+        for i in 0..MAX_LINES_PER_EXTENSION + 5 {
+            logs.record("developer", &i.to_string(), at(i as i64));
+        }
+        let lines = logs.tail(Some("developer"), usize::MAX);
+        assert_eq!(lines.len(), MAX_LINES_PER_EXTENSION);
+        assert_eq!(lines[0].1.message, "5");
+    }
+
+    #[test]
+    fn test_tee_appends_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut logs = ExtensionLogs {
+            tee_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        logs.record("developer", "one", at(1));
+        logs.record("developer", "two", at(2));
+        let written = std::fs::read_to_string(dir.path().join("developer.log")).unwrap();
+        assert_eq!(written.lines().count(), 2);
+        assert!(written.ends_with("two\n"));
+    }
+}
@@ -0,0 +1,490 @@
+use goose::conversation::message::ToolRequest;
+use goose::subprocess::SubprocessExt;
+use regex::Regex;
+use rmcp::model::CallToolRequestParams;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use super::diff::DiffStat;
+
+/// Files larger than this are not snapshotted, so they can't be restored with `/undo`.
+const MAX_SNAPSHOT_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Text editor commands that change the file
+pub const EDITING_COMMANDS: [&str; 4] = ["write", "str_replace", "insert", "undo_edit"];
+
+/// Output redirections (`> file`, `>> file`, `2> file`) and `tee` targets in a shell command,
+/// which covers heredocs like `cat > file <<EOF`.
+static SHELL_WRITE_TARGETS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:\d?>>?|\btee\s+(?:-a\s+)?)\s*(?:"([^"]+)"|'([^']+)'|([^\s;&|<>()'"]+))"#)
+        .unwrap()
+});
+
+/// A file as it was before a tool call changed it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSnapshot {
+    pub path: PathBuf,
+    /// `None` when the file did not exist yet
+    pub before: Option<Vec<u8>>,
+}
+
+/// The files one tool call changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub files: Vec<FileSnapshot>,
+}
+
+/// A file put back by `/undo`, with the content it had just before
+pub struct RestoredFile {
+    pub path: PathBuf,
+    pub discarded: Option<Vec<u8>>,
+    pub restored: Option<Vec<u8>>,
+}
+
+/// A file as it was before the first tool call in a range changed it, and as it is now
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub before: Option<Vec<u8>>,
+    pub after: Option<Vec<u8>>,
+}
+
+impl FileDiff {
+    pub fn before_text(&self) -> String {
+        lossy_text(&self.before)
+    }
+
+    pub fn after_text(&self) -> String {
+        lossy_text(&self.after)
+    }
+
+    pub fn stat(diffs: &[FileDiff]) -> DiffStat {
+        DiffStat::of(
+            diffs
+                .iter()
+                .map(|file| (file.before_text(), file.after_text())),
+        )
+    }
+}
+
+/// Where a turn started, for `/rewind`
+struct Checkpoint {
+    /// The turn's number, counting from 1
+    turn: usize,
+    /// How many changes were made before the turn
+    changes: usize,
+    /// A commit with the git working tree as it was, with `GOOSE_CLI_CHECKPOINT_GIT` on
+    tree: Option<String>,
+}
+
+/// Pre-images of files that tool calls are about to change, so the changes can be undone.
+/// Snapshots are taken when a tool request arrives, before the agent runs the tool, and kept
+/// once the response shows the file really changed.
+#[derive(Default)]
+pub struct FileHistory {
+    pending: Vec<FileChange>,
+    changes: Vec<FileChange>,
+    /// Index into `changes` of the first change made during the current turn
+    turn_start: usize,
+    checkpoints: Vec<Checkpoint>,
+    git_checkpoints: bool,
+}
+
+impl FileHistory {
+    /// Also checkpoints the git working tree at each turn, which catches the changes of
+    /// commands whose writes can't be told from their arguments
+    pub fn with_git_checkpoints(git_checkpoints: bool) -> Self {
+        Self {
+            git_checkpoints,
+            ..Self::default()
+        }
+    }
+
+    /// Marks the start of agent turn `turn`, for [`FileHistory::turn_diffs`] and
+    /// [`FileHistory::rewind`]. A turn answered in several goes keeps its first checkpoint.
+    pub fn start_turn(&mut self, turn: usize) {
+        self.turn_start = self.changes.len();
+        if self.checkpoints.last().is_some_and(|c| c.turn >= turn) {
+            return;
+        }
+        self.checkpoints.push(Checkpoint {
+            turn,
+            changes: self.changes.len(),
+            tree: self.git_checkpoints.then(git_checkpoint).flatten(),
+        });
+    }
+
+    /// Puts files back as they were before turn `turn + 1`, undoing the changes of every later
+    /// turn, newest first. Returns one entry per file.
+    pub fn rewind(&mut self, turn: usize) -> std::io::Result<Vec<RestoredFile>> {
+        let Some(idx) = self.checkpoints.iter().position(|c| c.turn > turn) else {
+            return Ok(Vec::new());
+        };
+        let checkpoint = self.checkpoints.split_off(idx).swap_remove(0);
+        let changes = self
+            .changes
+            .split_off(checkpoint.changes.min(self.changes.len()));
+        self.pending.clear();
+        self.turn_start = self.turn_start.min(self.changes.len());
+
+        let mut restored: Vec<RestoredFile> = Vec::new();
+        for file in changes.iter().rev().flat_map(|change| change.files.iter()) {
+            let file = restore(file)?;
+            match restored.iter_mut().find(|r| r.path == file.path) {
+                Some(earlier) => earlier.restored = file.restored,
+                None => restored.push(file),
+            }
+        }
+        if let Some(tree) = &checkpoint.tree {
+            restore_git_checkpoint(tree)?;
+        }
+        restored.retain(|file| file.discarded != file.restored);
+        Ok(restored)
+    }
+
+    /// Whether there is anything to put back for turns after `turn`
+    pub fn can_rewind(&self, turn: usize) -> bool {
+        self.checkpoints.iter().any(|c| c.turn > turn)
+    }
+
+    pub fn record_request(&mut self, request: &ToolRequest) {
+        let Ok(call) = &request.tool_call else {
+            return;
+        };
+        let files: Vec<FileSnapshot> = paths_written_by(call)
+            .into_iter()
+            .filter_map(|path| snapshot(&path))
+            .collect();
+        if !files.is_empty() {
+            self.pending.push(FileChange {
+                tool_call_id: request.id.clone(),
+                tool_name: call.name.to_string(),
+                files,
+            });
+        }
+    }
+
+    pub fn record_response(&mut self, tool_call_id: &str) {
+        let Some(idx) = self
+            .pending
+            .iter()
+            .position(|c| c.tool_call_id == tool_call_id)
+        else {
+            return;
+        };
+        let mut change = self.pending.remove(idx);
+        change
+            .files
+            .retain(|file| read_file(&file.path) != file.before);
+        if !change.files.is_empty() {
+            self.changes.push(change);
+        }
+    }
+
+    /// Reverts the most recent tool call that changed files.
+    pub fn undo(&mut self) -> Option<(FileChange, std::io::Result<Vec<RestoredFile>>)> {
+        let change = self.changes.pop()?;
+        self.turn_start = self.turn_start.min(self.changes.len());
+        let result = change.files.iter().map(restore).collect();
+        Some((change, result))
+    }
+
+    /// Files changed by tool calls during the current turn.
+    pub fn turn_diffs(&self) -> Vec<FileDiff> {
+        self.diffs_since(self.turn_start)
+    }
+
+    /// Files changed by tool calls since the session started.
+    pub fn session_diffs(&self) -> Vec<FileDiff> {
+        self.diffs_since(0)
+    }
+
+    /// One entry per file, in the order files were first changed, leaving out files that are
+    /// back to what they were.
+    fn diffs_since(&self, start: usize) -> Vec<FileDiff> {
+        let mut diffs: Vec<FileDiff> = Vec::new();
+        for file in self
+            .changes
+            .iter()
+            .skip(start)
+            .flat_map(|change| &change.files)
+        {
+            if diffs.iter().all(|diff| diff.path != file.path) {
+                diffs.push(FileDiff {
+                    path: file.path.clone(),
+                    before: file.before.clone(),
+                    after: read_file(&file.path),
+                });
+            }
+        }
+        diffs.retain(|diff| diff.before != diff.after);
+        diffs
+    }
+}
+
+/// Files a tool call is going to write, for the tools known to change files.
+fn paths_written_by(call: &CallToolRequestParams) -> Vec<PathBuf> {
+    let Some(args) = &call.arguments else {
+        return Vec::new();
+    };
+    let str_arg = |key: &str| args.get(key).and_then(Value::as_str);
+    let cwd = args
+        .get("working_dir")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+
+    match call.name.as_ref() {
+        "developer__text_editor" => match (str_arg("command"), str_arg("path")) {
+            (Some(command), Some(path)) if EDITING_COMMANDS.contains(&command) => {
+                vec![cwd.join(path)]
+            }
+            _ => Vec::new(),
+        },
+        "developer__shell" => str_arg("command")
+            .map(|command| {
+                shell_write_targets(command)
+                    .into_iter()
+                    .map(|target| cwd.join(target))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn shell_write_targets(command: &str) -> Vec<String> {
+    let mut targets: Vec<String> = SHELL_WRITE_TARGETS
+        .captures_iter(command)
+        .filter_map(|caps| caps.get(1).or(caps.get(2)).or(caps.get(3)))
+        .map(|m| m.as_str().to_string())
+        .filter(|target| !target.starts_with('&') && target != "/dev/null")
+        .collect();
+    targets.dedup();
+    targets
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .set_no_window()
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A commit of the working tree's tracked files, without touching the tree, the index or the
+/// stash list: the stash `git stash create` makes, or `HEAD` when nothing changed
+fn git_checkpoint() -> Option<String> {
+    git(&["stash", "create"])
+        .filter(|commit| !commit.is_empty())
+        .or_else(|| git(&["rev-parse", "HEAD"]))
+}
+
+/// Puts the tracked files of the whole working tree back as they were at `commit`. Files
+/// created since are left alone.
+fn restore_git_checkpoint(commit: &str) -> std::io::Result<()> {
+    let source = format!("--source={}", commit);
+    git(&["restore", &source, "--worktree", "--", ":/"])
+        .map(|_| ())
+        .ok_or_else(|| std::io::Error::other("git restore failed"))
+}
+
+fn lossy_text(bytes: &Option<Vec<u8>>) -> String {
+    bytes
+        .as_deref()
+        .map(|b| String::from_utf8_lossy(b).into_owned())
+        .unwrap_or_default()
+}
+
+fn read_file(path: &Path) -> Option<Vec<u8>> {
+    std::fs::read(path).ok()
+}
+
+fn snapshot(path: &Path) -> Option<FileSnapshot> {
+    match std::fs::metadata(path) {
+        Ok(meta) if meta.is_file() && meta.len() <= MAX_SNAPSHOT_BYTES => Some(FileSnapshot {
+            path: path.to_path_buf(),
+            before: Some(read_file(path)?),
+        }),
+        Ok(_) => None,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Some(FileSnapshot {
+            path: path.to_path_buf(),
+            before: None,
+        }),
+        Err(_) => None,
+    }
+}
+
+fn restore(file: &FileSnapshot) -> std::io::Result<RestoredFile> {
+    let discarded = read_file(&file.path);
+    match &file.before {
+        Some(content) => std::fs::write(&file.path, content)?,
+        None if discarded.is_some() => std::fs::remove_file(&file.path)?,
+        None => {}
+    }
+    Ok(RestoredFile {
+        path: file.path.clone(),
+        discarded,
+        restored: file.before.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn request(id: &str, name: &str, args: Value) -> ToolRequest {
+        ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(CallToolRequestParams {
+                meta: None,
+                task: None,
+                name: name.to_string().into(),
+                arguments: args.as_object().cloned(),
+            }),
+            metadata: None,
+            tool_meta: None,
+        }
+    }
+
+    #[test_case("cat > notes.md <<EOF\nhi\nEOF", &["notes.md"] ; "heredoc")]
+    #[test_case("echo hi >> log.txt 2>&1", &["log.txt"] ; "append and fd redirect")]
+    #[test_case("make 2> errors.txt > /dev/null", &["errors.txt"] ; "stderr and dev null")]
+    #[test_case("echo x | tee -a 'out file.txt'", &["out file.txt"] ; "tee with quoted path")]
+    #[test_case("ls -la", &[] ; "no writes")]
+    fn test_shell_write_targets(command: &str, expected: &[&str]) {
+        assert_eq!(shell_write_targets(command), expected);
+    }
+
+    #[test]
+    fn test_undo_restores_edited_and_removes_created_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let edited = dir.path().join("edited.txt");
+        let created = dir.path().join("created.txt");
+        std::fs::write(&edited, "before\n").unwrap();
+        let mut history = FileHistory::default();
+
+        history.record_request(&request(
+            "1",
+            "developer__text_editor",
+            serde_json::json!({"command": "write", "path": edited, "file_text": "after\n"}),
+        ));
+        std::fs::write(&edited, "after\n").unwrap();
+        history.record_response("1");
+
+        history.record_request(&request(
+            "2",
+            "developer__shell",
+            serde_json::json!({"command": format!("echo new > {}", created.display())}),
+        ));
+        std::fs::write(&created, "new\n").unwrap();
+        history.record_response("2");
+
+        history.record_request(&request(
+            "3",
+            "developer__text_editor",
+            serde_json::json!({"command": "write", "path": edited, "file_text": "after\n"}),
+        ));
+        history.record_response("3");
+
+        let (change, restored) = history.undo().unwrap();
+        assert_eq!(change.tool_call_id, "2");
+        assert_eq!(
+            restored.unwrap()[0].discarded.as_deref(),
+            Some(&b"new\n"[..])
+        );
+        assert!(!created.exists());
+
+        let (change, _) = history.undo().unwrap();
+        assert_eq!(change.tool_call_id, "1");
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "before\n");
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn test_turn_diffs_cover_only_the_current_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        std::fs::write(&first, "one\n").unwrap();
+        let mut history = FileHistory::default();
+        let write = |history: &mut FileHistory, id: &str, path: &Path, text: &str| {
+            history.record_request(&request(
+                id,
+                "developer__text_editor",
+                serde_json::json!({"command": "write", "path": path, "file_text": text}),
+            ));
+            std::fs::write(path, text).unwrap();
+            history.record_response(id);
+        };
+
+        history.start_turn(1);
+        write(&mut history, "1", &first, "two\n");
+        history.start_turn(2);
+        write(&mut history, "2", &second, "new\n");
+        write(&mut history, "3", &first, "three\n");
+
+        let turn = history.turn_diffs();
+        assert_eq!(turn.len(), 2);
+        assert_eq!(turn[0].path, second);
+        assert_eq!(turn[0].before, None);
+        assert_eq!(turn[1].before_text(), "two\n");
+        assert_eq!(turn[1].after_text(), "three\n");
+
+        let session = history.session_diffs();
+        assert_eq!(session.len(), 2);
+        assert_eq!(session[0].path, first);
+        assert_eq!(session[0].before_text(), "one\n");
+
+        write(&mut history, "4", &second, "");
+        std::fs::remove_file(&second).unwrap();
+        assert_eq!(history.session_diffs().len(), 1);
+    }
+
+    #[test]
+    fn test_rewind_restores_files_of_later_turns() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.txt");
+        let created = dir.path().join("created.txt");
+        std::fs::write(&notes, "one\n").unwrap();
+        let mut history = FileHistory::default();
+        let write = |history: &mut FileHistory, id: &str, path: &Path, text: &str| {
+            history.record_request(&request(
+                id,
+                "developer__text_editor",
+                serde_json::json!({"command": "write", "path": path, "file_text": text}),
+            ));
+            std::fs::write(path, text).unwrap();
+            history.record_response(id);
+        };
+
+        history.start_turn(1);
+        write(&mut history, "1", &notes, "two\n");
+        history.start_turn(2);
+        write(&mut history, "2", &notes, "three\n");
+        history.start_turn(2);
+        write(&mut history, "3", &created, "new\n");
+        history.start_turn(3);
+        write(&mut history, "4", &notes, "four\n");
+
+        assert!(history.can_rewind(1));
+        let restored = history.rewind(1).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].path, notes);
+        assert_eq!(restored[0].discarded.as_deref(), Some(&b"four\n"[..]));
+        assert_eq!(restored[0].restored.as_deref(), Some(&b"two\n"[..]));
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "two\n");
+        assert!(!created.exists());
+
+        assert!(!history.can_rewind(1));
+        assert!(history.rewind(1).unwrap().is_empty());
+        assert_eq!(history.undo().unwrap().0.tool_call_id, "1");
+    }
+}
@@ -0,0 +1,212 @@
+use goose::conversation::message::{Message, MessageContent};
+use rmcp::model::CallToolRequestParams;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::file_history::{shell_write_targets, EDITING_COMMANDS};
+
+/// A file the session's tools read, wrote or deleted, with how many times each
+#[derive(Debug, Default, PartialEq)]
+pub struct TouchedFile {
+    pub path: String,
+    pub reads: usize,
+    pub writes: usize,
+    pub deletes: usize,
+}
+
+enum Access {
+    Read,
+    Write,
+    Delete,
+}
+
+/// Files removed by `rm` in a shell command, e.g. both in `rm -f a.txt b.txt && make`
+fn shell_delete_targets(command: &str) -> Vec<String> {
+    command
+        .split(['\n', ';', '|', '&'])
+        .filter_map(|segment| {
+            let mut words = segment.split_whitespace();
+            (words.next()? == "rm").then_some(words)
+        })
+        .flatten()
+        .filter(|word| !word.starts_with('-'))
+        .map(|word| word.trim_matches(['"', '\'']).to_string())
+        .collect()
+}
+
+/// The files a tool call reads, writes or deletes, for the tools known to touch files
+fn accesses(call: &CallToolRequestParams) -> Vec<(String, Access)> {
+    let Some(args) = &call.arguments else {
+        return Vec::new();
+    };
+    let str_arg = |key: &str| args.get(key).and_then(Value::as_str);
+    let resolve = |path: String| match str_arg("working_dir") {
+        Some(dir) => Path::new(dir).join(path).display().to_string(),
+        None => path,
+    };
+
+    match call.name.as_ref() {
+        "developer__text_editor" => match (str_arg("command"), str_arg("path")) {
+            (Some("view"), Some(path)) => vec![(path.to_string(), Access::Read)],
+            (Some(command), Some(path)) if EDITING_COMMANDS.contains(&command) => {
+                vec![(path.to_string(), Access::Write)]
+            }
+            _ => Vec::new(),
+        },
+        "developer__shell" => {
+            let Some(command) = str_arg("command") else {
+                return Vec::new();
+            };
+            let writes = shell_write_targets(command)
+                .into_iter()
+                .map(|path| (resolve(path), Access::Write));
+            let deletes = shell_delete_targets(command)
+                .into_iter()
+                .map(|path| (resolve(path), Access::Delete));
+            writes.chain(deletes).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The tool calls whose response wasn't an error, in the order they were made
+pub fn succeeded_calls(messages: &[Message]) -> Vec<&CallToolRequestParams> {
+    let succeeded: HashSet<&str> = messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|content| match content {
+            MessageContent::ToolResponse(response) => match &response.tool_result {
+                Ok(result) if result.is_error != Some(true) => Some(response.id.as_str()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|content| match content {
+            MessageContent::ToolRequest(request) if succeeded.contains(request.id.as_str()) => {
+                request.tool_call.as_ref().ok()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every file read, written or deleted by a tool call that succeeded, in the order the files
+/// were first touched
+pub fn files_touched(messages: &[Message]) -> Vec<TouchedFile> {
+    let mut files: Vec<TouchedFile> = Vec::new();
+    for (path, access) in succeeded_calls(messages).into_iter().flat_map(accesses) {
+        let index = match files.iter().position(|file| file.path == path) {
+            Some(index) => index,
+            None => {
+                files.push(TouchedFile {
+                    path,
+                    ..Default::default()
+                });
+                files.len() - 1
+            }
+        };
+        let file = &mut files[index];
+        match access {
+            Access::Read => file.reads += 1,
+            Access::Write => file.writes += 1,
+            Access::Delete => file.deletes += 1,
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::CallToolResult;
+    use test_case::test_case;
+
+    fn call(name: &str, args: Value) -> CallToolRequestParams {
+        CallToolRequestParams {
+            meta: None,
+            task: None,
+            name: name.to_string().into(),
+            arguments: args.as_object().cloned(),
+        }
+    }
+
+    fn tool_turn(id: &str, call: CallToolRequestParams, is_error: bool) -> [Message; 2] {
+        [
+            Message::assistant().with_tool_request(id, Ok(call)),
+            Message::user().with_tool_response(
+                id,
+                Ok(CallToolResult {
+                    content: vec![],
+                    structured_content: None,
+                    is_error: Some(is_error),
+                    meta: None,
+                }),
+            ),
+        ]
+    }
+
+    #[test_case("rm -f a.txt b.txt && make", &["a.txt", "b.txt"] ; "flags and several files")]
+    #[test_case("cargo build; rm \"out.bin\"", &["out.bin"] ; "after another command")]
+    #[test_case("echo rm a.txt", &[] ; "not the command")]
+    fn test_shell_delete_targets(command: &str, expected: &[&str]) {
+        assert_eq!(shell_delete_targets(command), expected);
+    }
+
+    #[test]
+    fn test_files_touched_counts_successful_calls() {
+        let view = |path: &str| {
+            call(
+                "developer__text_editor",
+                serde_json::json!({"command": "view", "path": path}),
+            )
+        };
+        let messages: Vec<Message> = [
+            tool_turn("1", view("/src/main.rs"), false),
+            tool_turn(
+                "2",
+                call(
+                    "developer__text_editor",
+                    serde_json::json!({"command": "str_replace", "path": "/src/main.rs"}),
+                ),
+                false,
+            ),
+            tool_turn("3", view("/src/missing.rs"), true),
+            tool_turn(
+                "4",
+                call(
+                    "developer__shell",
+                    serde_json::json!({"command": "rm old.log", "working_dir": "/src"}),
+                ),
+                false,
+            ),
+            tool_turn("5", view("/src/main.rs"), false),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        assert_eq!(
+            files_touched(&messages),
+            vec![
+                TouchedFile {
+                    path: "/src/main.rs".to_string(),
+                    reads: 2,
+                    writes: 1,
+                    deletes: 0,
+                },
+                TouchedFile {
+                    path: "/src/old.log".to_string(),
+                    reads: 0,
+                    writes: 0,
+                    deletes: 1,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use goose::conversation::message::{Message, MessageContent};
+use rmcp::model::Role;
+
+/// Whether the message is something the user typed, as opposed to tool results and other
+/// messages the agent sends on the user's behalf. Each of these starts a turn.
+pub fn is_prompt(message: &Message) -> bool {
+    message.role == Role::User
+        && message.metadata.user_visible
+        && message
+            .content
+            .iter()
+            .any(|c| matches!(c, MessageContent::Text(_)))
+        && !message
+            .content
+            .iter()
+            .any(|c| matches!(c, MessageContent::ToolResponse(_)))
+}
+
+pub fn turn_count(messages: &[Message]) -> usize {
+    messages.iter().filter(|m| is_prompt(m)).count()
+}
+
+/// How many messages to keep to branch off after `turn` (1-based), including the agent's whole
+/// response to it. `None` keeps everything. Returns `None` when there is no such turn.
+pub fn fork_point(messages: &[Message], turn: Option<usize>) -> Option<usize> {
+    let Some(turn) = turn else {
+        return Some(messages.len());
+    };
+    if turn == 0 || turn > turn_count(messages) {
+        return None;
+    }
+    Some(
+        messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| is_prompt(m))
+            .nth(turn)
+            .map(|(idx, _)| idx)
+            .unwrap_or(messages.len()),
+    )
+}
+
+/// Where the `turns` latest turns start, the index of the first one's prompt, to roll the
+/// session back to before them. Returns `None` when there aren't that many turns.
+pub fn rewind_point(messages: &[Message], turns: usize) -> Option<usize> {
+    let count = turn_count(messages);
+    if turns == 0 || turns > count {
+        return None;
+    }
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| is_prompt(m))
+        .nth(count - turns)
+        .map(|(idx, _)| idx)
+}
+
+/// Where the latest turn the model still sees starts, the index of its prompt, once the agent
+/// has answered it. Turns replaced by `/retry` are kept for the user only and skipped.
+pub fn last_answered_turn(messages: &[Message]) -> Option<usize> {
+    let start = messages
+        .iter()
+        .rposition(|m| is_prompt(m) && m.metadata.agent_visible)?;
+    messages
+        .iter()
+        .skip(start + 1)
+        .any(|m| m.role == Role::Assistant && m.metadata.agent_visible)
+        .then_some(start)
+}
+
+/// Marks the fork in a transcript. Only the user sees it; the model's context is unchanged.
+pub fn banner(text: String) -> Message {
+    Message::assistant().with_text(text).user_only()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::CallToolResult;
+    use test_case::test_case;
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message::user().with_text("list the files"),
+            Message::assistant().with_text("running ls"),
+            Message::user().with_tool_response("1", Ok(CallToolResult::success(vec![]))),
+            Message::assistant().with_text("here they are"),
+            Message::user().with_text("now delete them"),
+            Message::assistant().with_text("done"),
+        ]
+    }
+
+    #[test_case(None, Some(6) ; "everything")]
+    #[test_case(Some(1), Some(4) ; "after the first turn")]
+    #[test_case(Some(2), Some(6) ; "after the last turn")]
+    #[test_case(Some(0), None ; "turn zero")]
+    #[test_case(Some(3), None ; "past the end")]
+    fn test_fork_point(turn: Option<usize>, expected: Option<usize>) {
+        assert_eq!(fork_point(&conversation(), turn), expected);
+    }
+
+    #[test_case(1, Some(4) ; "last turn")]
+    #[test_case(2, Some(0) ; "every turn")]
+    #[test_case(0, None ; "no turns")]
+    #[test_case(3, None ; "more than there are")]
+    fn test_rewind_point(turns: usize, expected: Option<usize>) {
+        assert_eq!(rewind_point(&conversation(), turns), expected);
+    }
+
+    #[test]
+    fn test_last_answered_turn() {
+        let mut messages = conversation();
+        assert_eq!(last_answered_turn(&messages), Some(4));
+
+        let replaced: Vec<Message> = messages.drain(4..).map(Message::user_only).collect();
+        messages.extend(replaced);
+        messages.push(Message::user().with_text("now delete them"));
+        assert_eq!(last_answered_turn(&messages), None);
+
+        messages.push(Message::assistant().with_text("deleted"));
+        assert_eq!(last_answered_turn(&messages), Some(6));
+    }
+
+    #[test]
+    fn test_turn_count_skips_tool_responses_and_banners() {
+        let mut messages = conversation();
+        messages.push(banner("forked".to_string()));
+        assert_eq!(turn_count(&messages), 2);
+    }
+}
@@ -0,0 +1,93 @@
+use goose::config::paths::Paths;
+use rustyline::history::DefaultHistory;
+use rustyline::Editor;
+use std::path::{Path, PathBuf};
+
+use super::completion::GooseCompleter;
+use crate::project_profile::{project_key, project_root};
+
+/// Inputs kept per project; the oldest are dropped beyond this
+pub const MAX_HISTORY: usize = 1000;
+
+/// The history file's name for a project: its root path with everything but letters and
+/// digits replaced, e.g. `-home-me-src-goose.txt` for `/home/me/src/goose`
+fn file_name(root: &Path) -> String {
+    format!("{}.txt", project_key(root))
+}
+
+/// Where the inputs typed in sessions of the project containing `dir` are kept
+pub fn history_path(dir: &Path) -> PathBuf {
+    Paths::data_dir()
+        .join("history")
+        .join(file_name(&project_root(dir)))
+}
+
+/// The input history of the current project, recalled with Up and searched with Ctrl-R in
+/// every session started there. A project without history of its own starts from the history
+/// goose kept for all projects before it was kept per project.
+pub struct HistoryManager {
+    history_file: PathBuf,
+    legacy_history_files: [PathBuf; 2],
+}
+
+impl HistoryManager {
+    pub fn new() -> Self {
+        let dir = std::env::current_dir().unwrap_or_default();
+        Self {
+            history_file: history_path(&dir),
+            legacy_history_files: [
+                Paths::state_dir().join("history.txt"),
+                Paths::config_dir().join("history.txt"),
+            ],
+        }
+    }
+
+    pub fn load(&self, editor: &mut Editor<GooseCompleter, DefaultHistory>) {
+        if let Some(parent) = self.history_file.parent() {
+            if !parent.exists() {
+                if let Err(e) = std::fs::create_dir_all(parent) {
+                    eprintln!("Warning: Failed to create history directory: {}", e);
+                }
+            }
+        }
+
+        let mut history_files =
+            std::iter::once(&self.history_file).chain(&self.legacy_history_files);
+        if let Some(file) = history_files.find(|f| f.exists()) {
+            if let Err(err) = editor.load_history(file) {
+                eprintln!("Warning: Failed to load command history: {}", err);
+            }
+        }
+    }
+
+    /// Adds what was typed since the last save to the file, so sessions running side by side
+    /// in the same project don't overwrite each other's inputs
+    pub fn save(&self, editor: &mut Editor<GooseCompleter, DefaultHistory>) {
+        if let Err(err) = editor.append_history(&self.history_file) {
+            eprintln!("Warning: Failed to save command history: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("/home/me/src/goose", "-home-me-src-goose.txt" ; "unix")]
+    #[test_case("C:\\Users\\me\\my app", "C--Users-me-my-app.txt" ; "windows")]
+    fn test_file_name(root: &str, expected: &str) {
+        assert_eq!(file_name(Path::new(root)), expected);
+    }
+
+    #[test]
+    fn test_history_is_shared_across_a_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::create_dir_all(dir.path().join("crates/cli")).unwrap();
+        assert_eq!(
+            history_path(&dir.path().join("crates/cli")),
+            history_path(dir.path())
+        );
+    }
+}
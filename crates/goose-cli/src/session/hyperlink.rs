@@ -0,0 +1,129 @@
+use goose::config::Config;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"https?://[^\s\x1b<>"'`]+"#).unwrap());
+
+/// Whether the terminal seems to understand OSC 8 links, going by what it exports.
+static TERMINAL_SUPPORTS_LINKS: LazyLock<bool> = LazyLock::new(|| {
+    let var = |key: &str| std::env::var(key).unwrap_or_default();
+    supports_links(
+        &var("TERM"),
+        &var("TERM_PROGRAM"),
+        &var("VTE_VERSION"),
+        std::env::var_os("WT_SESSION").is_some() || std::env::var_os("KONSOLE_VERSION").is_some(),
+        std::env::var_os("TMUX").is_some(),
+    )
+});
+
+fn supports_links(
+    term: &str,
+    term_program: &str,
+    vte_version: &str,
+    known_terminal: bool,
+    in_tmux: bool,
+) -> bool {
+    // tmux drops the links unless it has been set up to pass them on
+    if in_tmux || term.starts_with("screen") || term == "dumb" {
+        return false;
+    }
+    known_terminal
+        || vte_version.parse::<u32>().is_ok_and(|v| v >= 5000)
+        || ["iTerm.app", "WezTerm", "vscode", "ghostty", "Hyper"].contains(&term_program)
+        || ["kitty", "alacritty", "foot", "wezterm"]
+            .iter()
+            .any(|name| term.contains(name))
+}
+
+/// File paths and URLs are printed as clickable links when the output is a terminal that
+/// supports them. `GOOSE_CLI_HYPERLINKS` set to true or false overrides the detection.
+pub fn enabled(is_terminal: bool) -> bool {
+    is_terminal
+        && Config::global()
+            .get_param::<bool>("GOOSE_CLI_HYPERLINKS")
+            .unwrap_or(*TERMINAL_SUPPORTS_LINKS)
+}
+
+/// `text` that opens `url` when clicked
+pub fn link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// A `file://` URL for a path, resolved against the current directory when relative.
+pub fn file_url(path: &Path) -> Option<String> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    url::Url::from_file_path(absolute).ok().map(String::from)
+}
+
+pub fn has_url(text: &str) -> bool {
+    URL.is_match(text)
+}
+
+/// Turns the URLs in already rendered text into links, leaving out trailing punctuation that
+/// more likely ends the sentence than the URL.
+pub fn linkify_urls(text: &str) -> String {
+    URL.replace_all(text, |captures: &regex::Captures| {
+        let found = &captures[0];
+        let url = found.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+        format!(
+            "{}{}",
+            link(url, url),
+            found.strip_prefix(url).unwrap_or("")
+        )
+    })
+    .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("xterm-kitty", "", "", false, false, true ; "kitty")]
+    #[test_case("xterm-256color", "iTerm.app", "", false, false, true ; "iterm")]
+    #[test_case("xterm-256color", "", "6003", false, false, true ; "recent vte")]
+    #[test_case("xterm-256color", "", "4202", false, false, false ; "old vte")]
+    #[test_case("xterm-256color", "", "", true, false, true ; "windows terminal")]
+    #[test_case("xterm-kitty", "", "", false, true, false ; "inside tmux")]
+    #[test_case("xterm-256color", "Apple_Terminal", "", false, false, false ; "unknown terminal")]
+    fn test_supports_links(
+        term: &str,
+        term_program: &str,
+        vte_version: &str,
+        known_terminal: bool,
+        in_tmux: bool,
+        expected: bool,
+    ) {
+        assert_eq!(
+            supports_links(term, term_program, vte_version, known_terminal, in_tmux),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_linkify_urls() {
+        assert_eq!(
+            linkify_urls("see https://example.com/a?b=1. or \x1b[4mhttp://x.io\x1b[0m"),
+            format!(
+                "see {}. or \x1b[4m{}\x1b[0m",
+                link("https://example.com/a?b=1", "https://example.com/a?b=1"),
+                link("http://x.io", "http://x.io")
+            )
+        );
+        assert_eq!(linkify_urls("no links here"), "no links here");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_url_escapes_path() {
+        assert_eq!(
+            file_url(Path::new("/tmp/my notes.md")).as_deref(),
+            Some("file:///tmp/my%20notes.md")
+        );
+    }
+}
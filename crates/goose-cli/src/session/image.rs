@@ -0,0 +1,346 @@
+use anstream::println;
+use base64::Engine;
+use console::style;
+use goose::config::Config;
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat};
+use std::io::{self, Cursor, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tempfile::TempPath;
+
+const KITTY_CHUNK_SIZE: usize = 4096;
+const SIXEL_MAX_WIDTH: u32 = 800;
+const SIXEL_MAX_HEIGHT: u32 = 600;
+
+/// Images saved for terminals that can't draw them, deleted by [`remove_saved_images`]
+static SAVED_IMAGES: Mutex<Vec<TempPath>> = Mutex::new(Vec::new());
+
+/// How an image is shown in the terminal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    /// No inline support: the image is written to a temp file and its path printed.
+    File,
+}
+
+impl ImageProtocol {
+    /// Honours `GOOSE_CLI_IMAGE_PROTOCOL` (kitty, iterm, sixel, file) and otherwise guesses from
+    /// the environment the terminal exports.
+    pub fn detect() -> Self {
+        if let Ok(value) = Config::global().get_param::<String>("GOOSE_CLI_IMAGE_PROTOCOL") {
+            if let Some(protocol) = Self::from_config_str(&value) {
+                return protocol;
+            }
+        }
+        if !io::stdout().is_terminal() {
+            return ImageProtocol::File;
+        }
+        let var = |key: &str| std::env::var(key).unwrap_or_default();
+        Self::from_env(
+            &var("TERM"),
+            &var("TERM_PROGRAM"),
+            &var("LC_TERMINAL"),
+            std::env::var_os("KITTY_WINDOW_ID").is_some(),
+            std::env::var_os("TMUX").is_some(),
+        )
+    }
+
+    fn from_config_str(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "kitty" => Some(ImageProtocol::Kitty),
+            "iterm" | "iterm2" => Some(ImageProtocol::Iterm2),
+            "sixel" => Some(ImageProtocol::Sixel),
+            "file" | "none" | "off" => Some(ImageProtocol::File),
+            _ => None,
+        }
+    }
+
+    fn from_env(
+        term: &str,
+        term_program: &str,
+        lc_terminal: &str,
+        kitty_window: bool,
+        in_tmux: bool,
+    ) -> Self {
+        // tmux swallows graphics escapes unless passthrough is configured
+        if in_tmux {
+            return ImageProtocol::File;
+        }
+        if kitty_window || term.contains("kitty") || term_program == "ghostty" {
+            return ImageProtocol::Kitty;
+        }
+        if term_program == "iTerm.app" || term_program == "WezTerm" || lc_terminal == "iTerm2" {
+            return ImageProtocol::Iterm2;
+        }
+        if term.contains("sixel") || term == "foot" || term_program == "mlterm" {
+            return ImageProtocol::Sixel;
+        }
+        ImageProtocol::File
+    }
+}
+
+/// Show base64-encoded image content from a message.
+pub fn render_image(data: &str, mime_type: &str) {
+    let bytes = match base64::engine::general_purpose::STANDARD.decode(data.trim()) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            println!("Image: [invalid data, type: {}]", mime_type);
+            return;
+        }
+    };
+
+    if show_inline(&bytes, mime_type) {
+        println!();
+        return;
+    }
+
+    match save_to_temp_file(&bytes, mime_type) {
+        Ok(path) => println!(
+            "{} {}",
+            style("Image saved to").dim(),
+            style(path.display()).cyan()
+        ),
+        Err(e) => println!("Image: [type: {}, could not be saved: {}]", mime_type, e),
+    }
+}
+
+/// Show an image the user attached, when the terminal can draw it. Its chip already says
+/// which file it is, so there's nothing to save otherwise.
+pub fn render_attached_image(data: &str, mime_type: &str) {
+    let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data.trim()) else {
+        return;
+    };
+    if show_inline(&bytes, mime_type) {
+        println!();
+    }
+}
+
+/// Draws the image with the terminal's graphics protocol, if it has one
+fn show_inline(bytes: &[u8], mime_type: &str) -> bool {
+    match ImageProtocol::detect() {
+        ImageProtocol::Kitty => png_bytes(bytes, mime_type)
+            .map(|png| write_escape(&kitty_escape(&png)))
+            .is_some_and(|r| r.is_ok()),
+        ImageProtocol::Iterm2 => write_escape(&iterm2_escape(bytes)).is_ok(),
+        ImageProtocol::Sixel => image::load_from_memory(bytes)
+            .map(|img| write_escape(&sixel_escape(&img)))
+            .is_ok_and(|r| r.is_ok()),
+        ImageProtocol::File => false,
+    }
+}
+
+fn write_escape(sequence: &str) -> io::Result<()> {
+    // Written straight to stdout: anstream would strip the graphics escapes
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+fn png_bytes(bytes: &[u8], mime_type: &str) -> Option<Vec<u8>> {
+    if mime_type == "image/png" {
+        return Some(bytes.to_vec());
+    }
+    let img = image::load_from_memory(bytes).ok()?;
+    let mut png = Vec::new();
+    img.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+fn kitty_escape(png: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        let control = if i == 0 {
+            format!("a=T,f=100,m={}", more)
+        } else {
+            format!("m={}", more)
+        };
+        out.push_str(&format!(
+            "\x1b_G{};{}\x1b\\",
+            control,
+            String::from_utf8_lossy(chunk)
+        ));
+    }
+    out
+}
+
+fn iterm2_escape(bytes: &[u8]) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};preserveAspectRatio=1:{}\x07",
+        bytes.len(),
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+/// Encodes an image as sixels using a fixed 6x6x6 colour cube, which keeps the encoder simple
+/// and is close enough for previews.
+fn sixel_escape(img: &DynamicImage) -> String {
+    let img = if img.width() > SIXEL_MAX_WIDTH || img.height() > SIXEL_MAX_HEIGHT {
+        img.resize(SIXEL_MAX_WIDTH, SIXEL_MAX_HEIGHT, FilterType::Triangle)
+    } else {
+        img.clone()
+    };
+    let (width, height) = img.dimensions();
+    let rgba = img.to_rgba8();
+
+    let palette_index = |x: u32, y: u32| -> Option<usize> {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        if a < 128 {
+            return None;
+        }
+        let level = |c: u8| (c as usize * 5 + 127) / 255;
+        Some(level(r) * 36 + level(g) * 6 + level(b))
+    };
+
+    let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+    for i in 0..216 {
+        let percent = |level: usize| level * 100 / 5;
+        out.push_str(&format!(
+            "#{};2;{};{};{}",
+            i,
+            percent(i / 36),
+            percent((i / 6) % 6),
+            percent(i % 6)
+        ));
+    }
+
+    for band in (0..height).step_by(6) {
+        let mut colors: Vec<usize> = (band..(band + 6).min(height))
+            .flat_map(|y| (0..width).filter_map(move |x| palette_index(x, y)))
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for (n, color) in colors.iter().enumerate() {
+            if n > 0 {
+                out.push('$');
+            }
+            out.push_str(&format!("#{}", color));
+            let row: Vec<u8> = (0..width)
+                .map(|x| {
+                    (0..6)
+                        .filter(|dy| band + dy < height)
+                        .filter(|dy| palette_index(x, band + dy) == Some(*color))
+                        .fold(0u8, |bits, dy| bits | (1 << dy))
+                })
+                .collect();
+            push_run_length(&mut out, &row);
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_run_length(out: &mut String, row: &[u8]) {
+    let mut i = 0;
+    while i < row.len() {
+        let bits = row[i];
+        let run = row[i..].iter().take_while(|b| **b == bits).count();
+        let ch = (b'?' + bits) as char;
+        if run > 3 {
+            out.push_str(&format!("!{}{}", run, ch));
+        } else {
+            for _ in 0..run {
+                out.push(ch);
+            }
+        }
+        i += run;
+    }
+}
+
+fn save_to_temp_file(bytes: &[u8], mime_type: &str) -> io::Result<PathBuf> {
+    let extension = match mime_type {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "png",
+    };
+    let mut file = tempfile::Builder::new()
+        .prefix("goose-image-")
+        .suffix(&format!(".{}", extension))
+        .tempfile()?;
+    file.write_all(bytes)?;
+    let path = file.into_temp_path();
+    let saved = path.to_path_buf();
+    SAVED_IMAGES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(path);
+    Ok(saved)
+}
+
+/// Deletes the images saved during the session, once the session is over.
+pub fn remove_saved_images() {
+    SAVED_IMAGES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+    use test_case::test_case;
+
+    #[test_case("xterm-kitty", "", "", false, false, ImageProtocol::Kitty ; "kitty term")]
+    #[test_case("xterm-256color", "", "", true, false, ImageProtocol::Kitty ; "kitty window id")]
+    #[test_case("xterm-256color", "iTerm.app", "", false, false, ImageProtocol::Iterm2 ; "iterm")]
+    #[test_case("xterm-256color", "", "iTerm2", false, false, ImageProtocol::Iterm2 ; "iterm over ssh")]
+    #[test_case("foot", "", "", false, false, ImageProtocol::Sixel ; "foot")]
+    #[test_case("xterm-kitty", "", "", false, true, ImageProtocol::File ; "inside tmux")]
+    #[test_case("xterm-256color", "Apple_Terminal", "", false, false, ImageProtocol::File ; "unsupported")]
+    fn test_protocol_from_env(
+        term: &str,
+        term_program: &str,
+        lc_terminal: &str,
+        kitty_window: bool,
+        in_tmux: bool,
+        expected: ImageProtocol,
+    ) {
+        assert_eq!(
+            ImageProtocol::from_env(term, term_program, lc_terminal, kitty_window, in_tmux),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_kitty_escape_chunks_payload() {
+        let escape = kitty_escape(&vec![0u8; KITTY_CHUNK_SIZE]);
+        let parts: Vec<&str> = escape.split("\x1b\\").filter(|p| !p.is_empty()).collect();
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].starts_with("\x1b_Ga=T,f=100,m=1;"));
+        assert!(parts[1].starts_with("\x1b_Gm=0;"));
+    }
+
+    #[test]
+    fn test_sixel_escape_single_red_pixel() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 255])));
+        let escape = sixel_escape(&img);
+        assert!(escape.starts_with("\x1bPq\"1;1;1;1"));
+        // pure red is cube index 5 * 36 = 180, only the top bit of the band is set
+        assert!(escape.ends_with("#180@-\x1b\\"));
+    }
+
+    #[test]
+    fn test_run_length() {
+        let mut out = String::new();
+        push_run_length(&mut out, &[1, 1, 1, 1, 1, 0, 63]);
+        assert_eq!(out, "!5@?~");
+    }
+
+    #[test]
+    fn test_saved_images_removed() {
+        let path = save_to_temp_file(b"not really a png", "image/png").unwrap();
+        assert!(path.exists());
+        remove_saved_images();
+        assert!(!path.exists());
+    }
+}
@@ -1,4 +1,7 @@
 use super::completion::GooseCompleter;
+use super::keymap::{self, KeyAction};
+use super::params::{self, ParamChange};
+use super::vi;
 use super::{CompletionCache, HintStatus};
 use anyhow::Result;
 use goose::config::Config;
@@ -15,6 +18,27 @@ pub enum InputResult {
     AddBuiltin(String),
     ToggleTheme,
     SelectTheme(String),
+    PickTheme,
+    ShowToolCall(usize),
+    Tools(ToolsQuery),
+    ExpandTruncated,
+    ShowLogs(Option<String>),
+    Subagent(Option<String>),
+    CopyCodeBlock(Option<usize>),
+    Fork(Option<usize>),
+    Rewind(usize),
+    Undo,
+    Diff,
+    Model(Option<String>),
+    Regenerate(RegenerateOptions),
+    Params(Option<ParamChange>),
+    Attach(Vec<String>),
+    Todo,
+    Pin(Option<String>),
+    Unpin(usize),
+    Memory(Option<String>),
+    Forget(usize),
+    SaveResource(Option<usize>, Option<String>),
     Retry,
     ListPrompts(Option<String>),
     PromptCommand(PromptCommandOptions),
@@ -25,6 +49,10 @@ pub enum InputResult {
     Recipe(Option<String>),
     Compact,
     ToggleFullToolOutput,
+    Cost,
+    Context,
+    Jobs,
+    KillJob(u32),
 }
 
 #[derive(Debug)]
@@ -34,6 +62,35 @@ pub struct PromptCommandOptions {
     pub arguments: HashMap<String, String>,
 }
 
+/// What `/tools` shows: every tool, the tools of an extension or one tool, with `schema` the
+/// tool's input schema in full
+#[derive(Debug, Default, PartialEq)]
+pub struct ToolsQuery {
+    pub filter: Option<String>,
+    pub schema: bool,
+}
+
+/// Generation settings for a regenerated answer, applied to that answer only
+#[derive(Debug, Default, PartialEq)]
+pub struct RegenerateOptions {
+    pub temperature: Option<f32>,
+    pub model: Option<String>,
+}
+
+impl RegenerateOptions {
+    /// e.g. `openai/gpt-4o, temperature 0.2`, or `None` to keep the session's settings
+    pub fn describe(&self) -> Option<String> {
+        let settings: Vec<String> = [
+            self.model.clone(),
+            self.temperature.map(|t| format!("temperature {}", t)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!settings.is_empty()).then(|| settings.join(", "))
+    }
+}
+
 #[derive(Debug)]
 pub struct PlanCommandOptions {
     pub message_text: String,
@@ -78,6 +135,123 @@ impl rustyline::ConditionalEventHandler for CtrlCHandler {
     }
 }
 
+/// Runs a keymap action from the prompt. The line is handed back as an interrupt, with the
+/// action and whatever had been typed left in the cache so the next prompt can restore it.
+struct KeyActionHandler {
+    action: KeyAction,
+    completion_cache: Arc<std::sync::RwLock<CompletionCache>>,
+}
+
+impl rustyline::ConditionalEventHandler for KeyActionHandler {
+    fn handle(
+        &self,
+        _event: &rustyline::Event,
+        _n: usize,
+        _positive: bool,
+        ctx: &rustyline::EventContext,
+    ) -> Option<rustyline::Cmd> {
+        let mut cache = self.completion_cache.write().unwrap();
+        cache.hint_status = HintStatus::Default;
+        cache.key_action = Some(self.action);
+        cache.draft = ctx.line().to_string();
+        Some(rustyline::Cmd::Interrupt)
+    }
+}
+
+/// Passes a key to the vi registers, which claim it when it is part of a `"` command
+struct ViRegisterHandler {
+    key: char,
+}
+
+impl rustyline::ConditionalEventHandler for ViRegisterHandler {
+    fn handle(
+        &self,
+        _event: &rustyline::Event,
+        _n: usize,
+        _positive: bool,
+        ctx: &rustyline::EventContext,
+    ) -> Option<rustyline::Cmd> {
+        vi::handle_key(self.key, ctx)
+    }
+}
+
+/// Lets the vi registers see the keys that aren't bound to them, which end a `"` command
+struct ViOtherKeyHandler;
+
+impl rustyline::ConditionalEventHandler for ViOtherKeyHandler {
+    fn handle(
+        &self,
+        _event: &rustyline::Event,
+        _n: usize,
+        _positive: bool,
+        _ctx: &rustyline::EventContext,
+    ) -> Option<rustyline::Cmd> {
+        vi::handle_other_key()
+    }
+}
+
+fn bind_keys(
+    editor: &mut Editor<GooseCompleter, rustyline::history::DefaultHistory>,
+    completion_cache: &Arc<std::sync::RwLock<CompletionCache>>,
+) {
+    let newline_key = get_newline_key();
+    editor.bind_sequence(
+        rustyline::KeyEvent(
+            rustyline::KeyCode::Char(newline_key),
+            rustyline::Modifiers::CTRL,
+        ),
+        rustyline::EventHandler::Simple(rustyline::Cmd::Newline),
+    );
+
+    editor.bind_sequence(
+        rustyline::KeyEvent(rustyline::KeyCode::Char('c'), rustyline::Modifiers::CTRL),
+        rustyline::EventHandler::Conditional(Box::new(CtrlCHandler::new(completion_cache.clone()))),
+    );
+
+    for key in vi::register_keys() {
+        editor.bind_sequence(
+            rustyline::KeyEvent::new(key, rustyline::Modifiers::NONE),
+            rustyline::EventHandler::Conditional(Box::new(ViRegisterHandler { key })),
+        );
+    }
+    editor.bind_sequence(
+        rustyline::Event::Any,
+        rustyline::EventHandler::Conditional(Box::new(ViOtherKeyHandler)),
+    );
+
+    for (key, action) in keymap::keymap().prompt_bindings() {
+        editor.bind_sequence(
+            key,
+            rustyline::EventHandler::Conditional(Box::new(KeyActionHandler {
+                action,
+                completion_cache: completion_cache.clone(),
+            })),
+        );
+    }
+}
+
+/// Reads a line, starting from the text left behind by a keymap action. `Err` carries the
+/// result when the prompt was left without a line.
+fn read_line(
+    editor: &mut Editor<GooseCompleter, rustyline::history::DefaultHistory>,
+    completion_cache: &Arc<std::sync::RwLock<CompletionCache>>,
+) -> Result<std::result::Result<String, InputResult>> {
+    let prompt = get_input_prompt_string();
+    let draft = std::mem::take(&mut completion_cache.write().unwrap().draft);
+
+    match editor.readline_with_initial(&prompt, (&draft, "")) {
+        Ok(text) => Ok(Ok(text)),
+        Err(rustyline::error::ReadlineError::Interrupted) => {
+            let action = completion_cache.write().unwrap().key_action.take();
+            Ok(Err(action
+                .and_then(KeyAction::input_result)
+                .unwrap_or(InputResult::Exit)))
+        }
+        Err(rustyline::error::ReadlineError::Eof) => Ok(Err(InputResult::Exit)),
+        Err(e) => Err(e.into()),
+    }
+}
+
 pub fn get_newline_key() -> char {
     Config::global()
         .get_param::<String>("GOOSE_CLI_NEWLINE_KEY")
@@ -110,29 +284,11 @@ pub fn get_input(
         .map(|h| h.completion_cache.clone())
         .ok_or_else(|| anyhow::anyhow!("Editor helper not set"))?;
 
-    let newline_key = get_newline_key();
-    editor.bind_sequence(
-        rustyline::KeyEvent(
-            rustyline::KeyCode::Char(newline_key),
-            rustyline::Modifiers::CTRL,
-        ),
-        rustyline::EventHandler::Simple(rustyline::Cmd::Newline),
-    );
-
-    editor.bind_sequence(
-        rustyline::KeyEvent(rustyline::KeyCode::Char('c'), rustyline::Modifiers::CTRL),
-        rustyline::EventHandler::Conditional(Box::new(CtrlCHandler::new(completion_cache))),
-    );
-
-    let prompt = get_input_prompt_string();
+    bind_keys(editor, &completion_cache);
 
-    let input = match editor.readline(&prompt) {
+    let input = match read_line(editor, &completion_cache)? {
         Ok(text) => text,
-        Err(e) => match e {
-            rustyline::error::ReadlineError::Interrupted => return Ok(InputResult::Exit),
-            rustyline::error::ReadlineError::Eof => return Ok(InputResult::Exit),
-            _ => return Err(e.into()),
-        },
+        Err(result) => return Ok(result),
     };
 
     // Add valid input to history (history saving to file is handled in the Session::interactive method)
@@ -140,27 +296,7 @@ pub fn get_input(
         editor.add_history_entry(input.as_str())?;
     }
 
-    // Handle non-slash commands first
-    if !input.starts_with('/') {
-        let trimmed = input.trim();
-        if trimmed.is_empty()
-            || trimmed.eq_ignore_ascii_case("exit")
-            || trimmed.eq_ignore_ascii_case("quit")
-        {
-            return Ok(if trimmed.is_empty() {
-                InputResult::Retry
-            } else {
-                InputResult::Exit
-            });
-        }
-        return Ok(InputResult::Message(trimmed.to_string()));
-    }
-
-    // Handle slash commands
-    match handle_slash_command(&input) {
-        Some(result) => Ok(result),
-        None => Ok(InputResult::Message(input.trim().to_string())),
-    }
+    Ok(parse_line(&input))
 }
 
 fn get_regular_input(
@@ -171,29 +307,11 @@ fn get_regular_input(
         .map(|h| h.completion_cache.clone())
         .ok_or_else(|| anyhow::anyhow!("Editor helper not set"))?;
 
-    let newline_key = get_newline_key();
-    editor.bind_sequence(
-        rustyline::KeyEvent(
-            rustyline::KeyCode::Char(newline_key),
-            rustyline::Modifiers::CTRL,
-        ),
-        rustyline::EventHandler::Simple(rustyline::Cmd::Newline),
-    );
+    bind_keys(editor, &completion_cache);
 
-    editor.bind_sequence(
-        rustyline::KeyEvent(rustyline::KeyCode::Char('c'), rustyline::Modifiers::CTRL),
-        rustyline::EventHandler::Conditional(Box::new(CtrlCHandler::new(completion_cache))),
-    );
-
-    let prompt = get_input_prompt_string();
-
-    let input = match editor.readline(&prompt) {
+    let input = match read_line(editor, &completion_cache)? {
         Ok(text) => text,
-        Err(e) => match e {
-            rustyline::error::ReadlineError::Interrupted => return Ok(InputResult::Exit),
-            rustyline::error::ReadlineError::Eof => return Ok(InputResult::Exit),
-            _ => return Err(e.into()),
-        },
+        Err(result) => return Ok(result),
     };
 
     // Add valid input to history (history saving to file is handled in the Session::interactive method)
@@ -201,6 +319,11 @@ fn get_regular_input(
         editor.add_history_entry(input.as_str())?;
     }
 
+    Ok(parse_line(&input))
+}
+
+/// What a line typed at the prompt asks for: a message, or a slash command.
+pub fn parse_line(input: &str) -> InputResult {
     // Handle non-slash commands first
     if !input.starts_with('/') {
         let trimmed = input.trim();
@@ -208,22 +331,38 @@ fn get_regular_input(
             || trimmed.eq_ignore_ascii_case("exit")
             || trimmed.eq_ignore_ascii_case("quit")
         {
-            return Ok(if trimmed.is_empty() {
+            return if trimmed.is_empty() {
                 InputResult::Retry
             } else {
                 InputResult::Exit
-            });
+            };
         }
-        return Ok(InputResult::Message(trimmed.to_string()));
+        return InputResult::Message(trimmed.to_string());
     }
 
     // Handle slash commands
-    match handle_slash_command(&input) {
-        Some(result) => Ok(result),
-        None => Ok(InputResult::Message(input.trim().to_string())),
+    match handle_slash_command(input) {
+        Some(result) => result,
+        None => InputResult::Message(input.trim().to_string()),
     }
 }
 
+/// Whether handling the input may ask something with a prompt of its own, which needs the
+/// terminal to itself.
+pub fn may_prompt(input: &InputResult) -> bool {
+    matches!(
+        input,
+        InputResult::Model(None)
+            | InputResult::PickTheme
+            | InputResult::SaveResource(_, None)
+            | InputResult::ExpandTruncated
+            | InputResult::Plan(_)
+            | InputResult::Recipe(_)
+            | InputResult::Compact
+            | InputResult::PromptCommand(_)
+    )
+}
+
 fn handle_slash_command(input: &str) -> Option<InputResult> {
     let input = input.trim();
 
@@ -240,6 +379,31 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_RECIPE: &str = "/recipe";
     const CMD_COMPACT: &str = "/compact";
     const CMD_SUMMARIZE_DEPRECATED: &str = "/summarize";
+    const CMD_COST: &str = "/cost";
+    const CMD_CONTEXT: &str = "/context";
+    const CMD_THEME: &str = "/theme";
+    const CMD_TOOL: &str = "/tool";
+    const CMD_TOOLS: &str = "/tools";
+    const CMD_EXPAND: &str = "/expand";
+    const CMD_LOGS: &str = "/logs";
+    const CMD_SUBAGENT: &str = "/subagent";
+    const CMD_COPY: &str = "/copy";
+    const CMD_FORK: &str = "/fork";
+    const CMD_REWIND: &str = "/rewind";
+    const CMD_UNDO: &str = "/undo";
+    const CMD_DIFF: &str = "/diff";
+    const CMD_MODEL: &str = "/model";
+    const CMD_RETRY: &str = "/retry";
+    const CMD_PARAMS: &str = "/params";
+    const CMD_ATTACH: &str = "/attach";
+    const CMD_TODO: &str = "/todo";
+    const CMD_PIN: &str = "/pin";
+    const CMD_UNPIN: &str = "/unpin";
+    const CMD_SAVE: &str = "/save";
+    const CMD_MEMORY: &str = "/memory";
+    const CMD_FORGET: &str = "/forget";
+    const CMD_JOBS: &str = "/jobs";
+    const CMD_KILL: &str = "/kill";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -255,11 +419,11 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
                 .unwrap_or_default()
                 .trim()
                 .to_lowercase();
-            if ["light", "dark", "ansi"].contains(&t.as_str()) {
+            if ["light", "dark", "ansi", "auto"].contains(&t.as_str()) {
                 Some(InputResult::SelectTheme(t))
             } else {
                 println!(
-                    "Theme Unavailable: {} Available themes are: light, dark, ansi",
+                    "Theme Unavailable: {} Available themes are: light, dark, ansi, auto",
                     t
                 );
                 Some(InputResult::Retry)
@@ -303,6 +467,188 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_COMPACT => Some(InputResult::Compact),
+        s if s == CMD_COST => Some(InputResult::Cost),
+        s if s == CMD_CONTEXT => Some(InputResult::Context),
+        s if s == CMD_THEME => Some(InputResult::PickTheme),
+        s if s == CMD_TOOL || s.starts_with("/tool ") => {
+            let arg = s.get(CMD_TOOL.len()..).unwrap_or("").trim();
+            match arg.trim_start_matches('#').parse::<usize>() {
+                Ok(number) => Some(InputResult::ShowToolCall(number)),
+                Err(_) => {
+                    println!("Usage: /tool <number>, e.g. /tool 3 to show tool call #3 in full");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_TOOLS || s.starts_with("/tools ") => {
+            match parse_tools_query(s.get(CMD_TOOLS.len()..).unwrap_or("")) {
+                Some(query) => Some(InputResult::Tools(query)),
+                None => {
+                    println!("Usage: /tools [extension|tool] [--schema], e.g. /tools developer or /tools shell --schema");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_EXPAND => Some(InputResult::ExpandTruncated),
+        s if s == CMD_LOGS || s.starts_with("/logs ") => {
+            let extension = s.get(CMD_LOGS.len()..).unwrap_or("").trim();
+            Some(InputResult::ShowLogs(
+                (!extension.is_empty()).then(|| extension.to_string()),
+            ))
+        }
+        s if s == CMD_SUBAGENT || s.starts_with("/subagent ") => {
+            let id = s.get(CMD_SUBAGENT.len()..).unwrap_or("").trim();
+            Some(InputResult::Subagent(
+                (!id.is_empty()).then(|| id.to_string()),
+            ))
+        }
+        s if s == CMD_COPY || s.starts_with("/copy ") => {
+            let arg = s.get(CMD_COPY.len()..).unwrap_or("").trim();
+            if arg.is_empty() {
+                return Some(InputResult::CopyCodeBlock(None));
+            }
+            match arg.parse::<usize>() {
+                Ok(n) => Some(InputResult::CopyCodeBlock(Some(n))),
+                Err(_) => {
+                    println!("Usage: /copy [n], e.g. /copy 2 to copy the second code block of the last response");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_FORK || s.starts_with("/fork ") => {
+            let arg = s.get(CMD_FORK.len()..).unwrap_or("").trim();
+            if arg.is_empty() {
+                return Some(InputResult::Fork(None));
+            }
+            match arg.parse::<usize>() {
+                Ok(turn) => Some(InputResult::Fork(Some(turn))),
+                Err(_) => {
+                    println!(
+                        "Usage: /fork [turn], e.g. /fork 2 to branch off after your second message"
+                    );
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_REWIND || s.starts_with("/rewind ") => {
+            let arg = s.get(CMD_REWIND.len()..).unwrap_or("").trim();
+            if arg.is_empty() {
+                return Some(InputResult::Rewind(1));
+            }
+            match arg.parse::<usize>() {
+                Ok(turns) if turns > 0 => Some(InputResult::Rewind(turns)),
+                _ => {
+                    println!("Usage: /rewind [n], e.g. /rewind 2 to drop your last two messages and what the agent did for them");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_UNDO => Some(InputResult::Undo),
+        s if s == CMD_DIFF => Some(InputResult::Diff),
+        s if s == CMD_TODO => Some(InputResult::Todo),
+        s if s == CMD_PIN || s.starts_with("/pin ") => {
+            let target = s.get(CMD_PIN.len()..).unwrap_or("").trim();
+            Some(InputResult::Pin(
+                (!target.is_empty()).then(|| target.to_string()),
+            ))
+        }
+        s if s == CMD_UNPIN || s.starts_with("/unpin ") => {
+            let arg = s.get(CMD_UNPIN.len()..).unwrap_or("").trim();
+            match arg.trim_start_matches('#').parse::<usize>() {
+                Ok(number) => Some(InputResult::Unpin(number)),
+                Err(_) => {
+                    println!(
+                        "Usage: /unpin <n>, e.g. /unpin 2 to remove the second pin listed by /pin"
+                    );
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_MEMORY || s.starts_with("/memory ") => {
+            let note = s.get(CMD_MEMORY.len()..).unwrap_or("").trim();
+            Some(InputResult::Memory(
+                (!note.is_empty()).then(|| note.to_string()),
+            ))
+        }
+        s if s == CMD_FORGET || s.starts_with("/forget ") => {
+            let arg = s.get(CMD_FORGET.len()..).unwrap_or("").trim();
+            match arg.trim_start_matches('#').parse::<usize>() {
+                Ok(number) => Some(InputResult::Forget(number)),
+                Err(_) => {
+                    println!(
+                        "Usage: /forget <n>, e.g. /forget 2 to remove the second memory listed by /memory"
+                    );
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_JOBS => Some(InputResult::Jobs),
+        s if s == CMD_KILL || s.starts_with("/kill ") => {
+            let arg = s.get(CMD_KILL.len()..).unwrap_or("").trim();
+            match arg.trim_start_matches('%').parse::<u32>() {
+                Ok(id) => Some(InputResult::KillJob(id)),
+                Err(_) => {
+                    println!(
+                        "Usage: /kill <id>, e.g. /kill 2 to stop background job 2 listed by /jobs"
+                    );
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_SAVE || s.starts_with("/save ") => {
+            let args = s.get(CMD_SAVE.len()..).unwrap_or("").trim();
+            let (number, path) = match args.split_once(char::is_whitespace) {
+                Some((first, rest)) => (first, rest.trim()),
+                None => (args, ""),
+            };
+            match number.trim_start_matches('#').parse::<usize>() {
+                Ok(number) => Some(InputResult::SaveResource(
+                    Some(number),
+                    (!path.is_empty()).then(|| path.to_string()),
+                )),
+                Err(_) => Some(InputResult::SaveResource(
+                    None,
+                    (!args.is_empty()).then(|| args.to_string()),
+                )),
+            }
+        }
+        s if s == CMD_MODEL || s.starts_with("/model ") => {
+            let target = s.get(CMD_MODEL.len()..).unwrap_or("").trim();
+            Some(InputResult::Model(
+                (!target.is_empty()).then(|| target.to_string()),
+            ))
+        }
+        s if s == CMD_RETRY || s.starts_with("/retry ") => {
+            match parse_retry_command(s.get(CMD_RETRY.len()..).unwrap_or("")) {
+                Some(options) => Some(InputResult::Regenerate(options)),
+                None => {
+                    println!("Usage: /retry [--temperature <x>] [--model [provider/]model], e.g. /retry --temperature 0.2");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_PARAMS || s.starts_with("/params ") => {
+            let args = s.get(CMD_PARAMS.len()..).unwrap_or("").trim();
+            if args.is_empty() {
+                return Some(InputResult::Params(None));
+            }
+            match params::parse(args) {
+                Some(change) => Some(InputResult::Params(Some(change))),
+                None => {
+                    println!("Usage: /params [temperature|max_tokens|effort <value>|default], e.g. /params effort high");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
+        s if s == CMD_ATTACH || s.starts_with("/attach ") => {
+            match shlex::split(s.get(CMD_ATTACH.len()..).unwrap_or("")) {
+                Some(paths) => Some(InputResult::Attach(paths)),
+                None => {
+                    println!("Usage: /attach [path...], e.g. /attach \"screen shot.png\" notes.md");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
         s if s == CMD_SUMMARIZE_DEPRECATED => {
             println!("{}", console::style("⚠️  Note: /summarize has been renamed to /compact and will be removed in a future release.").yellow());
             Some(InputResult::Compact)
@@ -312,6 +658,23 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     }
 }
 
+fn parse_retry_command(args: &str) -> Option<RegenerateOptions> {
+    let mut options = RegenerateOptions::default();
+    let mut parts = shlex::split(args)?.into_iter();
+    while let Some(part) = parts.next() {
+        let (flag, value) = match part.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), value.to_string()),
+            None => (part, parts.next()?),
+        };
+        match flag.as_str() {
+            "--temperature" => options.temperature = Some(value.parse().ok()?),
+            "--model" => options.model = Some(value),
+            _ => return None,
+        }
+    }
+    Some(options)
+}
+
 fn parse_recipe_command(s: &str) -> Option<InputResult> {
     const CMD_RECIPE: &str = "/recipe";
 
@@ -337,6 +700,22 @@ fn parse_recipe_command(s: &str) -> Option<InputResult> {
     Some(InputResult::Recipe(Some(filepath.to_string())))
 }
 
+fn parse_tools_query(args: &str) -> Option<ToolsQuery> {
+    let mut query = ToolsQuery::default();
+    for arg in shlex::split(args)? {
+        match arg.as_str() {
+            "--schema" => query.schema = true,
+            _ if arg.starts_with('-') || query.filter.is_some() => return None,
+            _ => query.filter = Some(arg),
+        }
+    }
+    // a schema is shown for one tool at a time
+    if query.schema && query.filter.is_none() {
+        return None;
+    }
+    Some(query)
+}
+
 fn parse_prompts_command(args: &str) -> Option<InputResult> {
     let parts: Vec<String> = shlex::split(args).unwrap_or_default();
 
@@ -413,11 +792,12 @@ fn print_help() {
         "Available commands:
 /exit or /quit - Exit the session
 /t - Toggle Light/Dark/Ansi theme
-/t <name> - Set theme directly (light, dark, ansi)
+/t <name> - Set theme directly (light, dark, ansi, or auto to follow the terminal background)
+/theme - Browse syntax highlighting themes with a live preview
 /r - Toggle full tool output display (show complete tool parameters without truncation)
 /extension <command> - Add a stdio extension (format: ENV1=val1 command args...)
 /builtin <names> - Add builtin extensions by name (comma-separated)
-/prompts [--extension <name>] - List all available prompts, optionally filtered by extension
+/prompts [--extension <name>] - Search the extensions' prompts, fill in the arguments of one and run it
 /prompt <n> [--info] [key=value...] - Get prompt info or execute a prompt
 /mode <name> - Set the goose mode to use ('auto', 'approve', 'chat', 'smart_approve')
 /plan <message_text> -  Enters 'plan' mode with optional message. Create a plan based on the current messages and asks user if they want to act on it.
@@ -429,13 +809,46 @@ fn print_help() {
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
 /compact - Compact the current conversation to reduce context length while preserving key information.
+/cost - Show token usage and estimated cost for this session, per model
+/context - Break down what fills the context: system prompt, tool schemas, history, pins and tool output
+/tool <number> - Show a tool call and its response in full, by the number shown with --show-tool-ids
+/tools [extension|tool] [--schema] - List the available tools by extension, or show one tool, with --schema its input schema in full
+/expand - Open the last tool call or table that was shown truncated in the pager, in full
+/logs [extension] - Show recent log messages from all extensions, or from one extension
+/subagent [id] - Show the full transcript of a subagent, or list the session's subagents
+/copy [n] - Copy the nth code block of the last response to the clipboard (default: the last block)
+/undo - Revert the files changed by the most recent tool call that edited files
+/diff - Show what tools changed in files during this session, as a diff
+/todo - Show the agent's todo list and how much of it is done
+/pin [n|file] - Keep the nth latest message (1 = the last) or a file in full when the conversation is compacted; lists the pins without an argument
+/unpin <n> - Remove a pin by its number in the /pin list
+/memory [decision:|convention:|gotcha:] <note> - Remember a note about this project in every session here; lists the memories without a note
+/forget <n> - Remove a memory by its number in the /memory list
+/jobs - List the background jobs started by the shell tool, such as dev servers, with the end of their output
+/kill <id> - Stop a background job and the processes it started
+/save [n] [path] - Write the nth binary resource from a tool result (default: the latest) to a file
+/rewind [n] - Roll the session back n turns (default: 1), dropping them from the conversation and restoring the files their tool calls changed
+/fork [turn] - Continue in a new session branched off after the given turn (default: the latest), keeping this one as it is
+/model [[provider/]model] - Switch the provider or model for the rest of the session, picking from a list with projected costs if none is given
+/retry [--temperature <x>] [--model [provider/]model] - Replace the last answer with a new one, optionally generated with another temperature or model
+/params [temperature|max_tokens|effort <value>|default] - Show the generation parameters, or change one for the rest of the session
+/attach [path...] - Send files or images with your next message; lists the attached files without a path
 /? or /help - Display this help message
 /clear - Clears the current chat history
 
 Navigation:
 Ctrl+C - Clear current line if text is entered, otherwise exit the session
 Ctrl+{newline_key} - Add a newline (configurable via GOOSE_CLI_NEWLINE_KEY)
-Up/Down arrows - Navigate through command history"
+Up/Down arrows - Navigate through command history, kept per project across sessions
+Ctrl+R - Search the command history; Ctrl+R again for older matches
+@path + Tab - Mention a file, picked fuzzily from the files git tracks, to attach it to the message"
+    );
+    for (key, action) in keymap::keymap().describe() {
+        println!("{} - {}", key, action.replace('_', " "));
+    }
+    println!(
+        "Bind keys to toggle_full_tool_output, toggle_theme, expand_output, show_cost, show_todo \
+         or interrupt in the GOOSE_CLI_KEYMAP config section, e.g. toggle_full_tool_output: ctrl-o"
     );
 }
 
@@ -464,6 +877,18 @@ Configure with: goose configure set goose_prompt_editor \"vim\""
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_line() {
+        assert!(matches!(parse_line("  "), InputResult::Retry));
+        assert!(matches!(parse_line("Quit"), InputResult::Exit));
+        assert!(matches!(parse_line(" hello \n"), InputResult::Message(ref m) if m == "hello"));
+        assert!(matches!(parse_line("/todo"), InputResult::Todo));
+        assert!(matches!(
+            parse_line("/usr/bin is empty"),
+            InputResult::Message(_)
+        ));
+    }
+
     #[test]
     fn test_handle_slash_command() {
         // Test exit commands
@@ -498,6 +923,189 @@ mod tests {
             Some(InputResult::ToggleFullToolOutput)
         ));
 
+        // Test cost command
+        assert!(matches!(
+            handle_slash_command("/cost"),
+            Some(InputResult::Cost)
+        ));
+        assert!(matches!(
+            handle_slash_command("/context"),
+            Some(InputResult::Context)
+        ));
+        assert!(matches!(
+            handle_slash_command("/theme"),
+            Some(InputResult::PickTheme)
+        ));
+        assert!(matches!(
+            handle_slash_command("/tool #3"),
+            Some(InputResult::ShowToolCall(3))
+        ));
+        assert!(matches!(
+            handle_slash_command("/tool last"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/logs"),
+            Some(InputResult::ShowLogs(None))
+        ));
+        if let Some(InputResult::ShowLogs(Some(extension))) = handle_slash_command("/logs github") {
+            assert_eq!(extension, "github");
+        } else {
+            panic!("Expected ShowLogs with an extension");
+        }
+        assert!(matches!(
+            handle_slash_command("/subagent"),
+            Some(InputResult::Subagent(None))
+        ));
+        if let Some(InputResult::Subagent(Some(id))) = handle_slash_command("/subagent 3") {
+            assert_eq!(id, "3");
+        } else {
+            panic!("Expected Subagent with an id");
+        }
+        assert!(matches!(
+            handle_slash_command("/copy"),
+            Some(InputResult::CopyCodeBlock(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/copy 2"),
+            Some(InputResult::CopyCodeBlock(Some(2)))
+        ));
+        assert!(matches!(
+            handle_slash_command("/copy last"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/undo"),
+            Some(InputResult::Undo)
+        ));
+        assert!(matches!(
+            handle_slash_command("/diff"),
+            Some(InputResult::Diff)
+        ));
+        assert!(matches!(
+            handle_slash_command("/expand"),
+            Some(InputResult::ExpandTruncated)
+        ));
+        assert!(matches!(
+            handle_slash_command("/fork"),
+            Some(InputResult::Fork(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/fork 2"),
+            Some(InputResult::Fork(Some(2)))
+        ));
+        assert!(matches!(
+            handle_slash_command("/rewind"),
+            Some(InputResult::Rewind(1))
+        ));
+        assert!(matches!(
+            handle_slash_command("/rewind 3"),
+            Some(InputResult::Rewind(3))
+        ));
+        assert!(matches!(
+            handle_slash_command("/rewind 0"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/model"),
+            Some(InputResult::Model(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/todo"),
+            Some(InputResult::Todo)
+        ));
+        assert!(matches!(
+            handle_slash_command("/pin"),
+            Some(InputResult::Pin(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/pin @NOTES.md"),
+            Some(InputResult::Pin(Some(ref target))) if target == "@NOTES.md"
+        ));
+        assert!(matches!(
+            handle_slash_command("/unpin 2"),
+            Some(InputResult::Unpin(2))
+        ));
+        assert!(matches!(
+            handle_slash_command("/memory"),
+            Some(InputResult::Memory(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/memory gotcha: tests need docker"),
+            Some(InputResult::Memory(Some(ref note))) if note == "gotcha: tests need docker"
+        ));
+        assert!(matches!(
+            handle_slash_command("/forget 1"),
+            Some(InputResult::Forget(1))
+        ));
+        assert!(matches!(
+            handle_slash_command("/forget all"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/jobs"),
+            Some(InputResult::Jobs)
+        ));
+        assert!(matches!(
+            handle_slash_command("/kill %3"),
+            Some(InputResult::KillJob(3))
+        ));
+        assert!(matches!(
+            handle_slash_command("/kill"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/save"),
+            Some(InputResult::SaveResource(None, None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/save 2 out/chart.png"),
+            Some(InputResult::SaveResource(Some(2), Some(ref path))) if path == "out/chart.png"
+        ));
+        assert!(matches!(
+            handle_slash_command("/save chart.png"),
+            Some(InputResult::SaveResource(None, Some(ref path))) if path == "chart.png"
+        ));
+        assert!(matches!(
+            handle_slash_command("/retry"),
+            Some(InputResult::Regenerate(ref options)) if *options == RegenerateOptions::default()
+        ));
+        assert!(matches!(
+            handle_slash_command("/retry --temperature 0.2 --model=openai/gpt-4o"),
+            Some(InputResult::Regenerate(RegenerateOptions { temperature: Some(t), model: Some(ref model) }))
+                if t == 0.2 && model == "openai/gpt-4o"
+        ));
+        assert!(matches!(
+            handle_slash_command("/retry --temperature warm"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/params"),
+            Some(InputResult::Params(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/params temperature 0.5"),
+            Some(InputResult::Params(Some(ParamChange::Temperature(Some(t))))) if t == 0.5
+        ));
+        assert!(matches!(
+            handle_slash_command("/params effort extreme"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/attach"),
+            Some(InputResult::Attach(ref paths)) if paths.is_empty()
+        ));
+        assert!(matches!(
+            handle_slash_command("/attach \"screen shot.png\" notes.md"),
+            Some(InputResult::Attach(ref paths)) if paths == &["screen shot.png", "notes.md"]
+        ));
+        if let Some(InputResult::Model(Some(target))) = handle_slash_command("/model openai/gpt-4o")
+        {
+            assert_eq!(target, "openai/gpt-4o");
+        } else {
+            panic!("Expected Model");
+        }
+
         // Test extension command
         if let Some(InputResult::AddExtension(cmd)) = handle_slash_command("/extension foo bar") {
             assert_eq!(cmd, "foo bar");
@@ -535,6 +1143,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tools_command() {
+        assert!(matches!(
+            handle_slash_command("/tools"),
+            Some(InputResult::Tools(ToolsQuery {
+                filter: None,
+                schema: false
+            }))
+        ));
+        if let Some(InputResult::Tools(query)) = handle_slash_command("/tools shell --schema") {
+            assert_eq!(query.filter.as_deref(), Some("shell"));
+            assert!(query.schema);
+        } else {
+            panic!("Expected Tools");
+        }
+        assert!(matches!(
+            handle_slash_command("/tools --schema"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/tool 3"),
+            Some(InputResult::ShowToolCall(3))
+        ));
+    }
+
     #[test]
     fn test_prompt_command() {
         // Test basic prompt info command
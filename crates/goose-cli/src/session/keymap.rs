@@ -0,0 +1,280 @@
+use goose::config::Config;
+use rustyline::{KeyCode, KeyEvent, Modifiers};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tracing::warn;
+
+use super::input::InputResult;
+
+/// In-session actions that can be bound to a key with the `GOOSE_CLI_KEYMAP` config section:
+///
+/// ```yaml
+/// GOOSE_CLI_KEYMAP:
+///   toggle_full_tool_output: ctrl-o
+///   show_cost: alt-c
+///   interrupt: ctrl-g
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    ToggleFullToolOutput,
+    ToggleTheme,
+    ExpandOutput,
+    ShowCost,
+    ShowTodo,
+    /// Stops the agent's response, like Ctrl-C, while it is streaming
+    Interrupt,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 6] = [
+        KeyAction::ToggleFullToolOutput,
+        KeyAction::ToggleTheme,
+        KeyAction::ExpandOutput,
+        KeyAction::ShowCost,
+        KeyAction::ShowTodo,
+        KeyAction::Interrupt,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyAction::ToggleFullToolOutput => "toggle_full_tool_output",
+            KeyAction::ToggleTheme => "toggle_theme",
+            KeyAction::ExpandOutput => "expand_output",
+            KeyAction::ShowCost => "show_cost",
+            KeyAction::ShowTodo => "show_todo",
+            KeyAction::Interrupt => "interrupt",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// What the action does at the prompt, the same as its slash command. `None` for actions
+    /// that only apply while the agent is responding.
+    pub fn input_result(self) -> Option<InputResult> {
+        match self {
+            KeyAction::ToggleFullToolOutput => Some(InputResult::ToggleFullToolOutput),
+            KeyAction::ToggleTheme => Some(InputResult::ToggleTheme),
+            KeyAction::ExpandOutput => Some(InputResult::ExpandTruncated),
+            KeyAction::ShowCost => Some(InputResult::Cost),
+            KeyAction::ShowTodo => Some(InputResult::Todo),
+            KeyAction::Interrupt => None,
+        }
+    }
+}
+
+/// A key as written in the config, e.g. `ctrl-o`, `alt-t`, `ctrl-alt-x` or `f5`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeySpec {
+    code: KeyCode,
+    modifiers: Modifiers,
+}
+
+impl KeySpec {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim().to_lowercase();
+        let mut parts: Vec<&str> = spec.split(['-', '+']).collect();
+        let key = parts.pop().unwrap_or_default();
+        let mut modifiers = Modifiers::NONE;
+        for part in parts {
+            modifiers |= match part {
+                "ctrl" => Modifiers::CTRL,
+                "alt" | "meta" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", part, spec)),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key '{}' in '{}'", key, spec)),
+            },
+        };
+        if modifiers == Modifiers::NONE && matches!(code, KeyCode::Char(_)) {
+            return Err(format!(
+                "'{}' needs a modifier, or it couldn't be typed",
+                spec
+            ));
+        }
+        Ok(Self { code, modifiers })
+    }
+
+    pub fn key_event(self) -> KeyEvent {
+        KeyEvent(self.code, self.modifiers)
+    }
+
+    /// The byte the terminal sends for a plain Ctrl-letter key, which is all the listener used
+    /// while streaming can tell apart.
+    pub fn control_byte(self) -> Option<u8> {
+        match (self.code, self.modifiers) {
+            (KeyCode::Char(c), Modifiers::CTRL) if c.is_ascii_lowercase() => Some(c as u8 & 0x1f),
+            _ => None,
+        }
+    }
+
+    fn is_ctrl(self, letter: char) -> bool {
+        self.code == KeyCode::Char(letter) && self.modifiers == Modifiers::CTRL
+    }
+}
+
+static KEYMAP: LazyLock<Keymap> = LazyLock::new(Keymap::from_config);
+
+/// The keymap from the config, read once per process
+pub fn keymap() -> &'static Keymap {
+    &KEYMAP
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<(KeySpec, KeyAction)>,
+}
+
+impl Keymap {
+    fn from_config() -> Self {
+        let entries = Config::global()
+            .get_param::<HashMap<String, String>>("GOOSE_CLI_KEYMAP")
+            .unwrap_or_default();
+        let newline_key = super::input::get_newline_key();
+        let (keymap, errors) = Self::parse(&entries, newline_key);
+        for error in errors {
+            warn!("Ignoring GOOSE_CLI_KEYMAP entry: {}", error);
+        }
+        keymap
+    }
+
+    /// Builds the keymap from `action: key` entries, leaving out the ones that don't parse or
+    /// would take over a key the prompt already uses.
+    pub fn parse(entries: &HashMap<String, String>, newline_key: char) -> (Self, Vec<String>) {
+        let mut bindings = Vec::new();
+        let mut errors = Vec::new();
+        let mut names: Vec<&String> = entries.keys().collect();
+        names.sort();
+        for name in names {
+            let spec = &entries[name];
+            let Some(action) = KeyAction::from_name(name) else {
+                errors.push(format!("unknown action '{}'", name));
+                continue;
+            };
+            let key = match KeySpec::parse(spec) {
+                Ok(key) => key,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            // Ctrl-S and Ctrl-Q pause and resume the output while streaming
+            let mut reserved = vec!['c', 'd', newline_key];
+            if action == KeyAction::Interrupt {
+                reserved.extend(['s', 'q']);
+            }
+            if reserved.into_iter().any(|letter| key.is_ctrl(letter)) {
+                errors.push(format!("'{}' is already used by the prompt", spec));
+                continue;
+            }
+            if action == KeyAction::Interrupt && key.control_byte().is_none() {
+                errors.push(format!("'{}' must be a ctrl-letter key to interrupt", spec));
+                continue;
+            }
+            bindings.push((key, action));
+        }
+        (Self { bindings }, errors)
+    }
+
+    /// Bindings used at the prompt
+    pub fn prompt_bindings(&self) -> impl Iterator<Item = (KeyEvent, KeyAction)> + '_ {
+        self.bindings
+            .iter()
+            .filter(|(_, action)| action.input_result().is_some())
+            .map(|(key, action)| (key.key_event(), *action))
+    }
+
+    /// The byte that interrupts the agent while it is streaming, if one is bound
+    pub fn interrupt_byte(&self) -> Option<u8> {
+        self.bindings
+            .iter()
+            .find(|(_, action)| *action == KeyAction::Interrupt)
+            .and_then(|(key, _)| key.control_byte())
+    }
+
+    /// `(key, action)` pairs as configured, for the help text
+    pub fn describe(&self) -> Vec<(String, &'static str)> {
+        self.bindings
+            .iter()
+            .map(|(key, action)| (describe_key(*key), action.name()))
+            .collect()
+    }
+}
+
+fn describe_key(key: KeySpec) -> String {
+    let mut parts = Vec::new();
+    if key.modifiers.contains(Modifiers::CTRL) {
+        parts.push("Ctrl".to_string());
+    }
+    if key.modifiers.contains(Modifiers::ALT) {
+        parts.push("Alt".to_string());
+    }
+    if key.modifiers.contains(Modifiers::SHIFT) {
+        parts.push("Shift".to_string());
+    }
+    parts.push(match key.code {
+        KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        _ => "?".to_string(),
+    });
+    parts.join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("ctrl-o", KeyCode::Char('o'), Modifiers::CTRL ; "ctrl letter")]
+    #[test_case("Alt+T", KeyCode::Char('t'), Modifiers::ALT ; "plus and case")]
+    #[test_case("ctrl-alt-x", KeyCode::Char('x'), Modifiers::CTRL_ALT ; "two modifiers")]
+    #[test_case("f5", KeyCode::F(5), Modifiers::NONE ; "function key")]
+    fn test_parse_key(spec: &str, code: KeyCode, modifiers: Modifiers) {
+        assert_eq!(KeySpec::parse(spec), Ok(KeySpec { code, modifiers }));
+    }
+
+    #[test_case("o" ; "no modifier")]
+    #[test_case("hyper-o" ; "unknown modifier")]
+    #[test_case("ctrl-enter" ; "unknown key")]
+    #[test_case("f13" ; "function key out of range")]
+    fn test_parse_key_rejects(spec: &str) {
+        assert!(KeySpec::parse(spec).is_err());
+    }
+
+    #[test]
+    fn test_keymap_skips_bad_and_reserved_entries() {
+        let entries: HashMap<String, String> = [
+            ("toggle_full_tool_output", "ctrl-o"),
+            ("show_cost", "ctrl-c"),
+            ("toggle_theme", "ctrl-j"),
+            ("interrupt", "alt-g"),
+            ("explode", "ctrl-x"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let (keymap, errors) = Keymap::parse(&entries, 'j');
+        assert_eq!(
+            keymap.describe(),
+            vec![("Ctrl+O".to_string(), "toggle_full_tool_output")]
+        );
+        assert_eq!(errors.len(), 4);
+        assert_eq!(keymap.interrupt_byte(), None);
+    }
+
+    #[test]
+    fn test_interrupt_binds_only_while_streaming() {
+        let entries = HashMap::from([("interrupt".to_string(), "ctrl-g".to_string())]);
+        let (keymap, errors) = Keymap::parse(&entries, 'j');
+        assert!(errors.is_empty());
+        assert_eq!(keymap.interrupt_byte(), Some(0x07));
+        assert_eq!(keymap.prompt_bindings().count(), 0);
+    }
+}
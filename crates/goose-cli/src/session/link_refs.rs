@@ -0,0 +1,253 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Marks where dimmed text starts and ends in markdown on its way through bat, which passes
+/// private use characters through untouched; [`apply_marks`] turns them into escapes afterwards
+const DIM_START: char = '\u{E000}';
+const DIM_END: char = '\u{E001}';
+
+/// `[id]: https://example.com "Title"`
+static DEFINITION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^ {0,3}\[([^\]\^][^\]]*)\]:\s*<?([^\s<>]+)>?(?:\s+("[^"]*"|'[^']*'|\([^)]*\)))?\s*$"#,
+    )
+    .unwrap()
+});
+
+/// `[^note]: The text of the note`
+static FOOTNOTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ {0,3}\[\^([^\]\s]+)\]:\s*(.*)$").unwrap());
+
+/// The start of a line that may still become a definition once the rest of it streams in
+static DEFINITION_START: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ {0,3}\[[^\]]*(\]:?)?$|^ {0,3}\[[^\]]+\]:").unwrap());
+
+/// Footnote markers, images, and inline, full, collapsed and shortcut links
+static LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\[\^([^\]\s]+)\]|(!?)\[([^\[\]]+)\](?:\(([^()\s]+)(?:\s+"[^"]*")?\)|\[([^\[\]]*)\])?"#,
+    )
+    .unwrap()
+});
+
+/// Resolves the links of one assistant message as it streams in. Link targets are inlined
+/// after their text where they are known, and reference definitions and footnotes are taken
+/// out of the text and kept for the "Links" section shown after the message, since bat would
+/// print them verbatim wherever the model put them.
+#[derive(Debug, Default)]
+pub struct LinkRefs {
+    /// Reference definitions seen so far, by lowercase label
+    definitions: HashMap<String, String>,
+    /// Definitions and footnotes in the order they came, as label and target
+    entries: Vec<(String, String)>,
+    in_code_block: bool,
+    /// The footnote that indented lines still continue
+    open_footnote: Option<usize>,
+    /// An unfinished last line held back in case it is a definition
+    partial: String,
+}
+
+impl LinkRefs {
+    /// The next piece of the message, with definitions taken out and known targets inlined.
+    /// Code blocks and inline code are left alone.
+    pub fn rewrite(&mut self, markdown: &str) -> String {
+        let markdown = std::mem::take(&mut self.partial) + markdown;
+        let mut out = String::with_capacity(markdown.len());
+        for line in markdown.split_inclusive('\n') {
+            if !line.ends_with('\n') && !self.in_code_block && DEFINITION_START.is_match(line) {
+                self.partial = line.to_string();
+                break;
+            }
+            let text = line.trim_end_matches(['\n', '\r']);
+            let trimmed = text.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                self.in_code_block = !self.in_code_block;
+                self.open_footnote = None;
+                out.push_str(line);
+                continue;
+            }
+            if self.in_code_block {
+                out.push_str(line);
+                continue;
+            }
+            if self.take_definition(text) {
+                continue;
+            }
+            out.push_str(&self.inline_line(text));
+            out.push_str(line.strip_prefix(text).unwrap_or_default());
+        }
+        out
+    }
+
+    /// Keeps a definition line, or a continuation of the footnote before it, for the section
+    fn take_definition(&mut self, line: &str) -> bool {
+        if let Some(caps) = FOOTNOTE.captures(line) {
+            self.entries
+                .push((format!("[^{}]", &caps[1]), caps[2].trim().to_string()));
+            self.open_footnote = Some(self.entries.len() - 1);
+            return true;
+        }
+        if let Some(caps) = DEFINITION.captures(line) {
+            self.definitions
+                .insert(caps[1].to_lowercase(), caps[2].to_string());
+            self.entries
+                .push((format!("[{}]", &caps[1]), caps[2].to_string()));
+            self.open_footnote = None;
+            return true;
+        }
+        match self.open_footnote {
+            Some(index) if line.starts_with("    ") || line.starts_with('\t') => {
+                let note = &mut self.entries[index].1;
+                note.push(' ');
+                note.push_str(line.trim());
+                true
+            }
+            _ => {
+                if !line.trim().is_empty() {
+                    self.open_footnote = None;
+                }
+                false
+            }
+        }
+    }
+
+    fn inline_line(&self, line: &str) -> String {
+        // a table cell is drawn by the table renderer rather than bat, so it goes unmarked
+        let marked = !line.trim_start().starts_with('|');
+        line.split('`')
+            .enumerate()
+            .map(|(i, part)| {
+                if i % 2 == 1 {
+                    return part.to_string();
+                }
+                LINK.replace_all(part, |caps: &Captures| self.inline_link(caps, marked))
+                    .into_owned()
+            })
+            .collect::<Vec<_>>()
+            .join("`")
+    }
+
+    fn inline_link(&self, caps: &Captures, marked: bool) -> String {
+        let dim = |text: String| {
+            if marked {
+                format!("{}{}{}", DIM_START, text, DIM_END)
+            } else {
+                text
+            }
+        };
+        if let Some(note) = caps.get(1) {
+            return dim(format!("[^{}]", note.as_str()));
+        }
+        let text = &caps[3];
+        let target = if !caps[2].is_empty() {
+            None
+        } else if let Some(url) = caps.get(4) {
+            Some(url.as_str())
+        } else {
+            let label = match caps.get(5) {
+                Some(label) if !label.as_str().is_empty() => label.as_str(),
+                _ => text,
+            };
+            self.definitions
+                .get(&label.to_lowercase())
+                .map(String::as_str)
+        };
+        match target {
+            Some(url) => format!("{} {}", text, dim(format!("({})", url))),
+            // defined further down, so it is listed in the section instead
+            None => caps[0].to_string(),
+        }
+    }
+
+    /// The end of the message: what was held back of it, and its definitions and footnotes.
+    /// Starts over for the next message.
+    pub fn finish(&mut self) -> (String, Vec<(String, String)>) {
+        let partial = std::mem::take(&mut self.partial);
+        let mut rest = String::new();
+        if !self.take_definition(&partial) {
+            rest = self.inline_line(&partial);
+        }
+        (rest, std::mem::take(self).entries)
+    }
+}
+
+/// Turns the marks of rendered text into dim escapes, or drops them without colors
+pub fn apply_marks(rendered: &str, colored: bool) -> String {
+    let (start, end) = if colored {
+        ("\x1b[2m", "\x1b[22m")
+    } else {
+        ("", "")
+    };
+    rendered.replace(DIM_START, start).replace(DIM_END, end)
+}
+
+pub fn has_marks(text: &str) -> bool {
+    text.contains([DIM_START, DIM_END])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn plain(refs: &mut LinkRefs, markdown: &str) -> String {
+        apply_marks(&refs.rewrite(markdown), false)
+    }
+
+    #[test_case("See [the docs](https://docs.rs).", "See the docs (https://docs.rs)." ; "inline")]
+    #[test_case("See [the docs][d].\n\n[d]: https://docs.rs\n", "See [the docs][d].\n\n" ; "defined later")]
+    #[test_case("[d]: <https://docs.rs> \"Docs\"\nSee [the docs][D] and [d][].\n", "See the docs (https://docs.rs) and d (https://docs.rs).\n" ; "defined earlier")]
+    #[test_case("![logo](logo.png) and - [x] done", "![logo](logo.png) and - [x] done" ; "images and task boxes")]
+    #[test_case("Use `[a](b)` here", "Use `[a](b)` here" ; "inline code")]
+    #[test_case("```md\n[a](b)\n[d]: https://x.dev\n```\n", "```md\n[a](b)\n[d]: https://x.dev\n```\n" ; "code block")]
+    fn test_rewrite(markdown: &str, expected: &str) {
+        assert_eq!(plain(&mut LinkRefs::default(), markdown), expected);
+    }
+
+    #[test]
+    fn test_entries_collect_definitions_and_footnotes_across_chunks() {
+        let mut refs = LinkRefs::default();
+        assert_eq!(
+            plain(&mut refs, "Fast[^1], see [bench][b].\n\n"),
+            "Fast[^1], see [bench][b].\n\n"
+        );
+        assert_eq!(
+            plain(
+                &mut refs,
+                "[^1]: Measured on\n    a laptop.\n[b]: https://ben"
+            ),
+            ""
+        );
+        assert_eq!(
+            plain(&mut refs, "ch.dev\nDone.\n[c]: https://c.dev"),
+            "Done.\n"
+        );
+        assert_eq!(
+            refs.finish(),
+            (
+                String::new(),
+                vec![
+                    ("[^1]".to_string(), "Measured on a laptop.".to_string()),
+                    ("[b]".to_string(), "https://bench.dev".to_string()),
+                    ("[c]".to_string(), "https://c.dev".to_string()),
+                ]
+            )
+        );
+        assert_eq!(refs.finish(), (String::new(), vec![]));
+
+        assert_eq!(plain(&mut refs, "Done.\n[draft"), "Done.\n");
+        assert_eq!(refs.finish().0, "[draft");
+    }
+
+    #[test]
+    fn test_marks_dim_targets_outside_tables() {
+        let mut refs = LinkRefs::default();
+        let rewritten = refs.rewrite("[a](https://a.dev)\n| [b](https://b.dev) |\n");
+        assert!(has_marks(&rewritten));
+        assert_eq!(
+            apply_marks(&rewritten, true),
+            "a \x1b[2m(https://a.dev)\x1b[22m\n| b (https://b.dev) |\n"
+        );
+    }
+}
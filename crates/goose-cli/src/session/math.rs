@@ -0,0 +1,513 @@
+use std::borrow::Cow;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// TeX commands that stand for a single symbol
+const SYMBOLS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ε"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("vartheta", "ϑ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "φ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Upsilon", "Υ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("cdot", "·"),
+    ("times", "×"),
+    ("div", "÷"),
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("le", "≤"),
+    ("leq", "≤"),
+    ("ge", "≥"),
+    ("geq", "≥"),
+    ("ne", "≠"),
+    ("neq", "≠"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("propto", "∝"),
+    ("ll", "≪"),
+    ("gg", "≫"),
+    ("infty", "∞"),
+    ("partial", "∂"),
+    ("nabla", "∇"),
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("int", "∫"),
+    ("oint", "∮"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("gets", "←"),
+    ("leftrightarrow", "↔"),
+    ("Rightarrow", "⇒"),
+    ("implies", "⇒"),
+    ("Leftarrow", "⇐"),
+    ("iff", "⇔"),
+    ("Leftrightarrow", "⇔"),
+    ("mapsto", "↦"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("ni", "∋"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("supset", "⊃"),
+    ("supseteq", "⊇"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("setminus", "∖"),
+    ("emptyset", "∅"),
+    ("varnothing", "∅"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("neg", "¬"),
+    ("lnot", "¬"),
+    ("land", "∧"),
+    ("wedge", "∧"),
+    ("lor", "∨"),
+    ("vee", "∨"),
+    ("oplus", "⊕"),
+    ("otimes", "⊗"),
+    ("circ", "∘"),
+    ("degree", "°"),
+    ("prime", "′"),
+    ("ldots", "…"),
+    ("dots", "…"),
+    ("cdots", "⋯"),
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("mid", "|"),
+    ("quad", "  "),
+    ("qquad", "    "),
+];
+
+/// Commands that are set upright in TeX and read fine as their name
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "lg", "exp", "lim", "sup", "inf", "max", "min", "arg", "det", "dim", "gcd", "deg",
+    "ker", "Pr",
+];
+
+/// Commands whose argument is printed as is
+const TEXT_COMMANDS: &[&str] = &[
+    "text",
+    "textrm",
+    "textbf",
+    "textit",
+    "mathrm",
+    "mathbf",
+    "mathit",
+    "mathsf",
+    "mathtt",
+    "mathcal",
+    "boldsymbol",
+    "operatorname",
+    "mbox",
+];
+
+/// Sizing commands that only change how the following delimiter is drawn
+const SIZING_COMMANDS: &[&str] = &[
+    "left",
+    "right",
+    "big",
+    "Big",
+    "bigg",
+    "Bigg",
+    "bigl",
+    "bigr",
+    "Bigl",
+    "Bigr",
+    "displaystyle",
+];
+
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('a', 'ᵃ'),
+    ('b', 'ᵇ'),
+    ('c', 'ᶜ'),
+    ('d', 'ᵈ'),
+    ('e', 'ᵉ'),
+    ('f', 'ᶠ'),
+    ('g', 'ᵍ'),
+    ('h', 'ʰ'),
+    ('i', 'ⁱ'),
+    ('j', 'ʲ'),
+    ('k', 'ᵏ'),
+    ('l', 'ˡ'),
+    ('m', 'ᵐ'),
+    ('n', 'ⁿ'),
+    ('o', 'ᵒ'),
+    ('p', 'ᵖ'),
+    ('r', 'ʳ'),
+    ('s', 'ˢ'),
+    ('t', 'ᵗ'),
+    ('u', 'ᵘ'),
+    ('v', 'ᵛ'),
+    ('w', 'ʷ'),
+    ('x', 'ˣ'),
+    ('y', 'ʸ'),
+    ('z', 'ᶻ'),
+    ('T', 'ᵀ'),
+];
+
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('j', 'ⱼ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('r', 'ᵣ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('u', 'ᵤ'),
+    ('v', 'ᵥ'),
+    ('x', 'ₓ'),
+];
+
+const VULGAR_FRACTIONS: &[(&str, &str, &str)] = &[
+    ("1", "2", "½"),
+    ("1", "3", "⅓"),
+    ("2", "3", "⅔"),
+    ("1", "4", "¼"),
+    ("3", "4", "¾"),
+    ("1", "5", "⅕"),
+    ("2", "5", "⅖"),
+    ("3", "5", "⅗"),
+    ("4", "5", "⅘"),
+    ("1", "6", "⅙"),
+    ("5", "6", "⅚"),
+    ("1", "8", "⅛"),
+    ("3", "8", "⅜"),
+    ("5", "8", "⅝"),
+    ("7", "8", "⅞"),
+];
+
+/// Replaces `$...$` and `$$...$$` math in markdown with a unicode approximation, so
+/// `$\frac{1}{2} \alpha^2$` reads as `½ α²`. Inline code is left alone, and a `$` only opens
+/// inline math when followed by a non-space and closes it when preceded by one and not followed
+/// by a letter or digit, so prices and shell variables stay as they are.
+pub fn render_math(markdown: &str) -> Cow<'_, str> {
+    if !markdown.contains('$') {
+        return Cow::Borrowed(markdown);
+    }
+
+    let mut out = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+    while let Some(idx) = rest.find(['$', '`', '\\']) {
+        out.push_str(rest.get(..idx).unwrap_or(""));
+        let tail = rest.get(idx..).unwrap_or("");
+
+        if tail.starts_with('\\') {
+            // keep escapes like \$ as they are
+            let len = tail.chars().take(2).map(char::len_utf8).sum();
+            out.push_str(tail.get(..len).unwrap_or(tail));
+            rest = tail.get(len..).unwrap_or("");
+        } else if tail.starts_with('`') {
+            let len = code_span_len(tail);
+            out.push_str(tail.get(..len).unwrap_or(tail));
+            rest = tail.get(len..).unwrap_or("");
+        } else if let Some((tex, len)) = display_math(tail).or_else(|| inline_math(tail)) {
+            out.push_str(&to_unicode(tex.trim()));
+            rest = tail.get(len..).unwrap_or("");
+        } else {
+            out.push('$');
+            rest = tail.get(1..).unwrap_or("");
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Length of the code span at the start of `text`, or of the opening backticks when it isn't
+/// closed.
+fn code_span_len(text: &str) -> usize {
+    let ticks = text.len() - text.trim_start_matches('`').len();
+    let fence = text.get(..ticks).unwrap_or("");
+    text.get(ticks..)
+        .and_then(|body| body.find(fence))
+        .map(|end| ticks + end + ticks)
+        .unwrap_or(ticks)
+}
+
+fn display_math(text: &str) -> Option<(&str, usize)> {
+    let body = text.strip_prefix("$$")?;
+    let end = body.find("$$")?;
+    let tex = body.get(..end)?;
+    (!tex.trim().is_empty()).then_some((tex, end + 4))
+}
+
+fn inline_math(text: &str) -> Option<(&str, usize)> {
+    let body = text.strip_prefix('$')?;
+    if body.starts_with(char::is_whitespace) || body.starts_with('$') {
+        return None;
+    }
+    let line = body.split('\n').next().unwrap_or("");
+    let mut search_from = 0;
+    while let Some(offset) = line.get(search_from..)?.find('$') {
+        let end = search_from + offset;
+        let tex = line.get(..end)?;
+        let after = line.get(end + 1..).unwrap_or("");
+        let closes = !tex.ends_with(char::is_whitespace)
+            && !tex.ends_with('\\')
+            && !after.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        if !tex.is_empty() && closes {
+            return Some((tex, end + 2));
+        }
+        search_from = end + 1;
+    }
+    None
+}
+
+/// Unicode rendering of a TeX math expression. Anything it doesn't know is kept as written.
+pub fn to_unicode(tex: &str) -> String {
+    let mut parser = Parser {
+        chars: tex.chars().peekable(),
+    };
+    parser.group(false)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// Converts up to the closing brace of the current group, or to the end.
+    fn group(&mut self, braced: bool) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '}' if braced => break,
+                '{' => out.push_str(&self.group(true)),
+                '\\' => out.push_str(&self.command()),
+                '^' => {
+                    let arg = self.argument();
+                    out.push_str(&script(&arg, SUPERSCRIPTS, '^'));
+                }
+                '_' => {
+                    let arg = self.argument();
+                    out.push_str(&script(&arg, SUBSCRIPTS, '_'));
+                }
+                '~' => out.push(' '),
+                '&' => {}
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// The next argument: a braced group, a command or a single character.
+    fn argument(&mut self) -> String {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match self.chars.next() {
+            Some('{') => self.group(true),
+            Some('\\') => self.command(),
+            Some(c) => c.to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn command(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            name.push(c);
+        }
+        if name.is_empty() {
+            return match self.chars.next() {
+                Some(',' | ';' | ':' | ' ') => " ".to_string(),
+                Some('!') => String::new(),
+                Some('\\') => "\n".to_string(),
+                Some(c) => c.to_string(),
+                None => String::new(),
+            };
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument();
+                let denominator = self.argument();
+                fraction(&numerator, &denominator)
+            }
+            "sqrt" => {
+                if self.chars.next_if_eq(&'[').is_some() {
+                    while self.chars.next().is_some_and(|c| c != ']') {}
+                }
+                let arg = self.argument();
+                if arg.chars().count() > 1 {
+                    format!("√({})", arg)
+                } else {
+                    format!("√{}", arg)
+                }
+            }
+            "mathbb" => self.argument().chars().map(double_struck).collect(),
+            name if TEXT_COMMANDS.contains(&name) => self.argument(),
+            name if SIZING_COMMANDS.contains(&name) => {
+                // `\left.` is an invisible delimiter
+                self.chars.next_if_eq(&'.');
+                String::new()
+            }
+            name if FUNCTIONS.contains(&name) => name.to_string(),
+            name => SYMBOLS
+                .iter()
+                .find(|(command, _)| *command == name)
+                .map(|(_, symbol)| symbol.to_string())
+                .unwrap_or_else(|| format!("\\{}", name)),
+        }
+    }
+}
+
+/// A super- or subscript in unicode when every character has a form for it, otherwise the
+/// marker followed by the script, parenthesized when longer than one character.
+fn script(arg: &str, table: &[(char, char)], marker: char) -> String {
+    if marker == '^' && !arg.is_empty() && arg.chars().all(|c| c == '′') {
+        return arg.to_string();
+    }
+    let converted: Option<String> = arg
+        .chars()
+        .map(|c| {
+            table
+                .iter()
+                .find(|(plain, _)| *plain == c)
+                .map(|(_, script)| *script)
+        })
+        .collect();
+    match converted {
+        Some(converted) if !converted.is_empty() => converted,
+        _ if arg.chars().count() > 1 => format!("{}({})", marker, arg),
+        _ => format!("{}{}", marker, arg),
+    }
+}
+
+fn fraction(numerator: &str, denominator: &str) -> String {
+    if let Some((_, _, glyph)) = VULGAR_FRACTIONS
+        .iter()
+        .find(|(n, d, _)| *n == numerator && *d == denominator)
+    {
+        return glyph.to_string();
+    }
+    format!(
+        "{}/{}",
+        parenthesized(numerator),
+        parenthesized(denominator)
+    )
+}
+
+/// Wraps compound expressions in parentheses so they keep together when written inline.
+fn parenthesized(expr: &str) -> String {
+    if expr.chars().all(|c| c.is_alphanumeric() || c == '.') {
+        expr.to_string()
+    } else {
+        format!("({})", expr)
+    }
+}
+
+fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(r"x^2 + y^2 = z^2", "x² + y² = z²" ; "superscripts")]
+    #[test_case(r"a_{n+1} = a_n + a_{n-1}", "aₙ₊₁ = aₙ + aₙ₋₁" ; "subscripts")]
+    #[test_case(r"\frac{1}{2} \pi r^2", "½ π r²" ; "vulgar fraction")]
+    #[test_case(r"\frac{a+b}{c}", "(a+b)/c" ; "compound fraction")]
+    #[test_case(r"\sqrt{x^2 + 1}", "√(x² + 1)" ; "square root")]
+    #[test_case(r"\alpha \leq \beta \cdot \Gamma", "α ≤ β · Γ" ; "greek and operators")]
+    #[test_case(r"\forall x \in \mathbb{R}", "∀ x ∈ ℝ" ; "blackboard bold")]
+    #[test_case(r"\sum_{i=1}^{n} i", "∑ᵢ₌₁ⁿ i" ; "sum limits")]
+    #[test_case(r"e^{i\pi}", "e^(iπ)" ; "unconvertible superscript")]
+    #[test_case(r"\left( \frac{x}{y} \right)", "( x/y )" ; "sizing commands")]
+    #[test_case(r"\text{cost} = \unknown", "cost = \\unknown" ; "text and unknown commands")]
+    fn test_to_unicode(tex: &str, expected: &str) {
+        assert_eq!(to_unicode(tex), expected);
+    }
+
+    #[test_case("The area is $\\pi r^2$.", "The area is π r²." ; "inline")]
+    #[test_case("$$\n\\frac{3}{4}\n$$", "¾" ; "display")]
+    #[test_case("It costs $5 and $10 now", "It costs $5 and $10 now" ; "prices")]
+    #[test_case("Use $HOME/$USER here", "Use $HOME/$USER here" ; "shell variables")]
+    #[test_case("Run `echo $x$` then $x^2$", "Run `echo $x$` then x²" ; "inline code untouched")]
+    #[test_case("A literal \\$x$ stays", "A literal \\$x$ stays" ; "escaped dollar")]
+    fn test_render_math(markdown: &str, expected: &str) {
+        assert_eq!(render_math(markdown), expected);
+    }
+}
@@ -0,0 +1,418 @@
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use goose::conversation::message::{Message, MessageContent};
+use goose::subprocess::SubprocessExt;
+use ignore::WalkBuilder;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use super::resources::format_size;
+
+/// Files larger than this are attached by reference; the model can still read them with its tools
+const MAX_INLINE_BYTES: u64 = 64 * 1024;
+/// Images larger than this aren't attached, as providers reject them
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+/// Files listed for completion, so a huge checkout doesn't stall the prompt
+const MAX_LISTED_FILES: usize = 50_000;
+/// How long the file list is reused for completion before the workspace is listed again
+const FILE_LIST_TTL: Duration = Duration::from_secs(10);
+
+/// Wraps each attached file in the message, and marks the text as an attachment when it is
+/// shown again
+const ATTACHMENT_OPEN: &str = "<attached-file path=\"";
+const ATTACHMENT_CLOSE: &str = "</attached-file>";
+
+/// The files `@` completes to: the ones git tracks, or everything not ignored outside a repository
+pub fn list_files(dir: &Path) -> Vec<String> {
+    let output = Command::new("git")
+        .args(["ls-files", "-z"])
+        .current_dir(dir)
+        .set_no_window()
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+    if let Some(output) = output {
+        return String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .take(MAX_LISTED_FILES)
+            .map(str::to_string)
+            .collect();
+    }
+    WalkBuilder::new(dir)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let path = entry.path().strip_prefix(dir).ok()?;
+            Some(path.to_string_lossy().replace('\\', "/"))
+        })
+        .take(MAX_LISTED_FILES)
+        .collect()
+}
+
+/// The workspace's files, listed again once the list is a few seconds old so files the agent
+/// creates show up
+#[derive(Default)]
+pub struct WorkspaceFiles {
+    files: Vec<String>,
+    listed: Option<Instant>,
+}
+
+impl WorkspaceFiles {
+    #[cfg(test)]
+    pub fn with_files(files: Vec<String>) -> Self {
+        Self {
+            files,
+            listed: Some(Instant::now()),
+        }
+    }
+
+    pub fn get(&mut self, dir: &Path) -> &[String] {
+        if self
+            .listed
+            .is_none_or(|listed| listed.elapsed() > FILE_LIST_TTL)
+        {
+            self.files = list_files(dir);
+            self.listed = Some(Instant::now());
+        }
+        &self.files
+    }
+}
+
+fn is_boundary(c: char) -> bool {
+    matches!(c, '/' | '_' | '-' | '.' | ' ')
+}
+
+/// How well `path` matches `query` typed after `@`, or `None` if the query's characters don't
+/// all appear in it in order. Runs of characters, matches at the start of a word and matches in
+/// the file name rather than its directories score higher.
+pub fn fuzzy_score(path: &str, query: &str) -> Option<i64> {
+    let chars: Vec<char> = path.chars().collect();
+    let name_start = chars.iter().rposition(|&c| c == '/').map_or(0, |i| i + 1);
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.chars().map(|c| c.to_ascii_lowercase()) {
+        let found = (next..chars.len()).find(|&i| chars[i].to_ascii_lowercase() == q)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || is_boundary(chars[found - 1]) {
+            score += 3;
+        }
+        if found >= name_start {
+            score += 2;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// The best `limit` matches for `query`, shorter paths first among equal scores
+pub fn rank<'a>(files: &'a [String], query: &str, limit: usize) -> Vec<&'a str> {
+    let mut scored: Vec<(i64, &str)> = files
+        .iter()
+        .filter_map(|file| Some((fuzzy_score(file, query)?, file.as_str())))
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then(a.1.len().cmp(&b.1.len()))
+            .then(a.1.cmp(b.1))
+    });
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, file)| file)
+        .collect()
+}
+
+/// The paths mentioned as `@path` in a message, each once. A mention starts a word, and
+/// punctuation after it, like the comma in `look at @src/main.rs, then…`, isn't part of it.
+pub fn find_mentions(text: &str) -> Vec<&str> {
+    let mut mentions = Vec::new();
+    for word in text.split_whitespace() {
+        let word = word.trim_start_matches(['(', '`', '"', '\'']);
+        let Some(path) = word.strip_prefix('@') else {
+            continue;
+        };
+        let path = path.trim_end_matches([',', '.', ';', ':', '!', '?', ')', '`', '"', '\'']);
+        if !path.is_empty() && !mentions.contains(&path) {
+            mentions.push(path);
+        }
+    }
+    mentions
+}
+
+/// A mentioned file, as it is added to the message
+#[derive(Debug, PartialEq)]
+pub enum Attachment {
+    /// The file's text, so the model doesn't need a tool call to read it
+    Content { path: String, text: String },
+    /// Only the path, for files too large to inline or that aren't text
+    Reference { path: String, size: u64 },
+    /// An image attached with `--image`, `--file` or `/attach`, sent to the model base64
+    /// encoded after its path
+    Image {
+        path: String,
+        data: String,
+        mime_type: String,
+        size: u64,
+    },
+}
+
+impl Attachment {
+    /// The attachment for a path mentioned in a message, or `None` when it isn't a file, as
+    /// in `@someone`
+    pub fn load(dir: &Path, path: &str) -> Option<Self> {
+        if path.contains(['"', '<', '>']) {
+            return None;
+        }
+        let full = dir.join(path);
+        let metadata = std::fs::metadata(&full).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        let reference = Attachment::Reference {
+            path: path.to_string(),
+            size: metadata.len(),
+        };
+        if metadata.len() > MAX_INLINE_BYTES {
+            return Some(reference);
+        }
+        match String::from_utf8(std::fs::read(&full).ok()?) {
+            Ok(text) if !text.contains('\0') => Some(Attachment::Content {
+                path: path.to_string(),
+                text,
+            }),
+            _ => Some(reference),
+        }
+    }
+
+    /// A file given with `--file`, `--image` or `/attach`: an image if its content is one,
+    /// otherwise attached like a mentioned file. With `image` anything else is an error.
+    pub fn from_path(path: &str, image: bool) -> Result<Self> {
+        let metadata = std::fs::metadata(path).with_context(|| format!("can't attach {}", path))?;
+        if !metadata.is_file() {
+            bail!("can't attach {}: not a file", path);
+        }
+        let size = metadata.len();
+        // larger files are only referenced, so there's no need to read them
+        let bytes = if size <= MAX_IMAGE_BYTES {
+            std::fs::read(path).with_context(|| format!("can't attach {}", path))?
+        } else {
+            Vec::new()
+        };
+        match ::image::guess_format(&bytes) {
+            Ok(format) => Ok(Attachment::Image {
+                path: path.to_string(),
+                data: base64::engine::general_purpose::STANDARD.encode(&bytes),
+                mime_type: format.to_mime_type().to_string(),
+                size,
+            }),
+            Err(_) if image && size > MAX_IMAGE_BYTES => bail!(
+                "can't attach {}: images can be at most {}",
+                path,
+                format_size(MAX_IMAGE_BYTES as usize)
+            ),
+            Err(_) if image => bail!("can't attach {}: not a supported image", path),
+            Err(_) => match String::from_utf8(bytes) {
+                Ok(text) if size <= MAX_INLINE_BYTES && !text.contains('\0') => {
+                    Ok(Attachment::Content {
+                        path: path.to_string(),
+                        text,
+                    })
+                }
+                _ => Ok(Attachment::Reference {
+                    path: path.to_string(),
+                    size,
+                }),
+            },
+        }
+    }
+
+    /// The attachment added to `message`: its text, followed by the image itself for images
+    pub fn add_to(&self, message: Message) -> Message {
+        let message = message.with_text(self.to_text());
+        match self {
+            Attachment::Image {
+                data, mime_type, ..
+            } => message.with_image(data, mime_type),
+            _ => message,
+        }
+    }
+
+    /// The text added to the message for the model
+    pub fn to_text(&self) -> String {
+        match self {
+            Attachment::Content { path, text } => format!(
+                "{}{}\">\n{}\n{}",
+                ATTACHMENT_OPEN,
+                path,
+                text.trim_end_matches('\n'),
+                ATTACHMENT_CLOSE
+            ),
+            Attachment::Reference { path, size } => format!(
+                "{}{}\" size=\"{}\">\nNot included because it is large or not text; read it \
+                 with your tools if you need it.\n{}",
+                ATTACHMENT_OPEN,
+                path,
+                format_size(*size as usize),
+                ATTACHMENT_CLOSE
+            ),
+            Attachment::Image { path, size, .. } => format!(
+                "{}{}\" size=\"{}\">\nAttached as the image that follows.\n{}",
+                ATTACHMENT_OPEN,
+                path,
+                format_size(*size as usize),
+                ATTACHMENT_CLOSE
+            ),
+        }
+    }
+}
+
+/// The files mentioned in `text` that exist under `dir`
+pub fn attachments(dir: &Path, text: &str) -> Vec<Attachment> {
+    find_mentions(text)
+        .into_iter()
+        .filter_map(|path| Attachment::load(dir, path))
+        .collect()
+}
+
+/// What the chip for an attachment in a message says, e.g. `src/main.rs` or
+/// `logo.png · 12.0 KB`, or `None` if the text isn't an attachment
+pub fn chip(text: &str) -> Option<String> {
+    let rest = text.strip_prefix(ATTACHMENT_OPEN)?;
+    let (path, rest) = rest.split_once('"')?;
+    let size = rest
+        .strip_prefix(" size=\"")
+        .and_then(|rest| rest.split_once('"'))
+        .map(|(size, _)| size);
+    Some(match size {
+        Some(size) => format!("{} · {}", path, size),
+        None => path.to_string(),
+    })
+}
+
+/// What the user typed in a message, without the files attached to it
+pub fn typed_text(message: &Message) -> String {
+    message
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            MessageContent::Text(text) if chip(&text.text).is_none() => Some(text.text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("see @src/main.rs, then @README.md.", &["src/main.rs", "README.md"] ; "punctuation")]
+    #[test_case("(@a.rs) and @a.rs again", &["a.rs"] ; "once each")]
+    #[test_case("mail me@example.com or @ alone", &[] ; "not a mention")]
+    fn test_find_mentions(text: &str, expected: &[&str]) {
+        assert_eq!(find_mentions(text), expected);
+    }
+
+    #[test]
+    fn test_rank_prefers_file_names_and_runs() {
+        let files: Vec<String> = [
+            "crates/goose-cli/src/main.rs",
+            "docs/domain.md",
+            "src/main.rs",
+            "Cargo.toml",
+        ]
+        .iter()
+        .map(|file| file.to_string())
+        .collect();
+        assert_eq!(
+            rank(&files, "main", 3),
+            vec![
+                "src/main.rs",
+                "crates/goose-cli/src/main.rs",
+                "docs/domain.md"
+            ]
+        );
+        assert_eq!(rank(&files, "cgt", 5), vec!["Cargo.toml"]);
+        assert!(rank(&files, "xyz", 5).is_empty());
+    }
+
+    #[test]
+    fn test_attachments_inline_text_and_reference_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "use tabs\n").unwrap();
+        std::fs::write(dir.path().join("logo.png"), [0x89, b'P', 0, 0]).unwrap();
+
+        let attached = attachments(dir.path(), "see @notes.md and @logo.png, ask @someone");
+        assert_eq!(
+            attached,
+            vec![
+                Attachment::Content {
+                    path: "notes.md".to_string(),
+                    text: "use tabs\n".to_string()
+                },
+                Attachment::Reference {
+                    path: "logo.png".to_string(),
+                    size: 4
+                },
+            ]
+        );
+        assert_eq!(
+            attached[0].to_text(),
+            "<attached-file path=\"notes.md\">\nuse tabs\n</attached-file>"
+        );
+        assert_eq!(chip(&attached[0].to_text()), Some("notes.md".to_string()));
+        assert_eq!(
+            chip(&attached[1].to_text()),
+            Some("logo.png · 4 B".to_string())
+        );
+    }
+
+    #[test]
+    fn test_attach_files_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("shot.png");
+        ::image::RgbImage::new(2, 2).save(&png).unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "use tabs\n").unwrap();
+        let png = png.to_str().unwrap();
+        let notes = notes.to_str().unwrap();
+
+        let attachment = Attachment::from_path(png, false).unwrap();
+        let Attachment::Image { mime_type, .. } = &attachment else {
+            panic!("expected an image, got {:?}", attachment);
+        };
+        assert_eq!(mime_type, "image/png");
+        let message = attachment.add_to(Message::user().with_text("what's wrong here?"));
+        assert!(matches!(message.content[2], MessageContent::Image(_)));
+        assert_eq!(typed_text(&message), "what's wrong here?");
+
+        assert!(matches!(
+            Attachment::from_path(notes, false),
+            Ok(Attachment::Content { .. })
+        ));
+        assert!(Attachment::from_path(notes, true).is_err());
+        assert!(Attachment::from_path(dir.path().to_str().unwrap(), false).is_err());
+    }
+
+    #[test]
+    fn test_typed_text_leaves_out_attachments() {
+        let attachment = Attachment::Content {
+            path: "a.rs".to_string(),
+            text: "fn a() {}".to_string(),
+        };
+        let message = Message::user()
+            .with_text("explain @a.rs")
+            .with_text(attachment.to_text());
+        assert_eq!(typed_text(&message), "explain @a.rs");
+        assert_eq!(chip("explain @a.rs"), None);
+    }
+}
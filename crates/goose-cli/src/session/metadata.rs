@@ -0,0 +1,91 @@
+use goose::session::ExtensionState;
+use serde::{Deserialize, Serialize};
+
+/// Tags added with `goose session tag`, persisted in the session's extension data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionTags {
+    pub tags: Vec<String>,
+}
+
+impl ExtensionState for SessionTags {
+    const EXTENSION_NAME: &'static str = "cli_tags";
+    const VERSION: &'static str = "v0";
+}
+
+impl SessionTags {
+    /// Adds a tag unless it is already there, ignoring case. Returns whether it was added.
+    pub fn add(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() || self.contains(tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        true
+    }
+
+    /// Removes a tag, ignoring case. Returns whether it was there.
+    pub fn remove(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|t| !t.eq_ignore_ascii_case(tag.trim()));
+        self.tags.len() != before
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+}
+
+/// What `goose session list` shows beyond the session store's own columns, written at the end
+/// of every turn: the model that answered last and the estimated cost so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionMetadata {
+    pub model: Option<String>,
+    /// Estimated USD, when price data is known for every model the session used
+    pub cost: Option<f64>,
+}
+
+impl ExtensionState for SessionMetadata {
+    const EXTENSION_NAME: &'static str = "cli_metadata";
+    const VERSION: &'static str = "v0";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::session::ExtensionData;
+
+    #[test]
+    fn test_tags_ignore_case_and_duplicates() {
+        let mut tags = SessionTags::default();
+        assert!(tags.add("bugfix"));
+        assert!(!tags.add(" BugFix "));
+        assert!(!tags.add(""));
+        assert!(tags.add("release"));
+        assert_eq!(tags.tags, vec!["bugfix", "release"]);
+
+        assert!(tags.contains("RELEASE"));
+        assert!(tags.remove("Bugfix"));
+        assert!(!tags.remove("bugfix"));
+        assert_eq!(tags.tags, vec!["release"]);
+    }
+
+    #[test]
+    fn test_round_trip_through_extension_data() {
+        let mut data = ExtensionData::default();
+        let metadata = SessionMetadata {
+            model: Some("gpt-4o".to_string()),
+            cost: Some(0.42),
+        };
+        metadata.to_extension_data(&mut data).unwrap();
+        SessionTags {
+            tags: vec!["infra".to_string()],
+        }
+        .to_extension_data(&mut data)
+        .unwrap();
+
+        assert_eq!(SessionMetadata::from_extension_data(&data), Some(metadata));
+        assert!(SessionTags::from_extension_data(&data)
+            .unwrap()
+            .contains("infra"));
+    }
+}
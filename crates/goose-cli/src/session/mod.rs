@@ -1,57 +1,160 @@
+mod accessible;
+mod ansi_output;
+mod attach;
 mod builder;
+mod cast;
+mod clipboard;
+mod code_block;
+mod compaction;
 mod completion;
+mod context_breakdown;
+mod context_from;
+mod cost;
+mod diff;
 mod editor;
 mod elicitation;
+mod error;
 mod export;
+mod extension_logs;
+mod file_history;
+mod files_touched;
+mod fork;
+mod history;
+mod hyperlink;
+mod image;
 mod input;
+mod keymap;
+mod link_refs;
+mod math;
+mod mentions;
+mod metadata;
+mod model_picker;
+mod non_interactive;
+mod notify;
 mod output;
+mod palette;
+mod params;
+mod pins;
+mod plan_progress;
+mod prompt_args;
+mod prompt_picker;
+mod provider_retry;
+mod redaction;
+mod render_hints;
+pub mod renderer;
+mod repeated_output;
+mod replay;
+mod resources;
+mod sandbox;
+mod shell_preview;
+mod status_line;
+mod stream_keys;
 pub mod streaming_buffer;
+mod table;
 mod task_execution_display;
+mod term_background;
+pub mod term_writer;
+mod terminal;
+mod theme_picker;
 mod thinking;
-
+mod thought_stream;
+mod todo;
+mod tool_frames;
+mod tool_log;
+mod tool_pane;
+mod tool_timeouts;
+mod truncation;
+mod tui;
+mod turn_stats;
+mod vi;
+
+use crate::i18n::{tr, tr_with};
+use crate::project_memory::{self, Memory, ProjectMemory};
 use crate::session::task_execution_display::{
     format_task_execution_notification, TASK_EXECUTION_NOTIFICATION_TYPE,
 };
 use goose::conversation::Conversation;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::str::FromStr;
 use tokio::signal::ctrl_c;
 use tokio_util::task::AbortOnDropHandle;
 
-pub use self::export::message_to_markdown;
-pub use builder::{build_session, SessionBuilderConfig};
+pub use self::attach::attach_to_session;
+pub use self::error::SessionError;
+pub use self::export::{changes_patch, files_touched_markdown, message_to_markdown};
+pub use self::metadata::{SessionMetadata, SessionTags};
+pub use self::redaction::Redactor;
+pub use self::replay::replay_conversation;
+pub use builder::{add_context_from, build_session, dry_run, SessionBuilderConfig};
 use console::Color;
+use goose::agents::sampling;
 use goose::agents::AgentEvent;
-use goose::agents::SUBAGENT_TOOL_REQUEST_TYPE;
+use goose::agents::{
+    SamplingPolicy, SamplingPreview, SAMPLING_RESULT_TYPE, SUBAGENT_TOOL_REQUEST_TYPE,
+};
+use goose::network_inspector::{allow_hosts, is_network_alert, network_alert_hosts};
+use goose::otel::spans;
 use goose::permission::permission_confirmation::PrincipalType;
 use goose::permission::Permission;
 use goose::permission::PermissionConfirmation;
 use goose::providers::base::Provider;
+use goose::session::{
+    ExtensionState, PinnedState, Session, SubagentsState, TodoState, MAX_PINNED_FILE_BYTES,
+};
+use goose::stale_file_inspector::is_stale_file_alert;
+use goose::token_counter::create_token_counter;
 use goose::utils::safe_truncate;
+use goose_mcp::developer::jobs;
+pub use output::{run_status_hook, set_term_writer, Verbosity};
 
 use anyhow::{Context, Result};
+use compaction::CompactionSummary;
 use completion::GooseCompleter;
+use context_breakdown::ContextBreakdown;
+use cost::{CostTracker, ModelUsage, TurnCost};
+use file_history::FileHistory;
+use files_touched::files_touched;
 use goose::agents::extension::{Envs, ExtensionConfig, PLATFORM_EXTENSIONS};
 use goose::agents::types::RetryConfig;
 use goose::agents::{Agent, SessionConfig, COMPACT_TRIGGERS};
+use goose::config::paths::Paths;
 use goose::config::{Config, GooseMode};
-use input::InputResult;
+use history::HistoryManager;
+use input::{InputResult, RegenerateOptions};
+use non_interactive::ApprovalPolicy;
+use plan_progress::PlanChecklist;
+use provider_retry::ProviderRetry;
+use renderer::{Renderer, TurnUsage};
 use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
-use rmcp::model::{ErrorCode, ErrorData};
+use rmcp::model::{Content, ErrorCode, ErrorData, JsonObject, Tool};
+use shell_preview::AffectedPath;
+use status_line::StatusLine;
+use stream_keys::{StreamKey, StreamKeyListener};
+use turn_stats::TurnTimer;
 
-use goose::config::paths::Paths;
 use goose::conversation::message::{ActionRequiredData, Message, MessageContent};
 use rustyline::EditMode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio;
 use tokio_util::sync::CancellationToken;
-use tracing::warn;
+use tracing::{warn, Instrument};
+
+/// What to do after the agent's stream ended in an error.
+enum RetryOutcome {
+    /// Send this message again to continue the turn
+    Resend(Message),
+    /// Ctrl-C was pressed while waiting to retry
+    Cancelled,
+    GiveUp,
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 struct JsonOutput {
@@ -71,6 +174,25 @@ enum StreamEvent {
     Message {
         message: Message,
     },
+    Text {
+        text: String,
+    },
+    Thinking {
+        thinking: String,
+    },
+    ToolRequest {
+        id: String,
+        name: String,
+        arguments: Option<JsonObject>,
+    },
+    ToolResponse {
+        id: String,
+        is_error: bool,
+        content: Vec<Content>,
+    },
+    SystemNotification {
+        message: String,
+    },
     Notification {
         extension_id: String,
         #[serde(flatten)]
@@ -83,6 +205,15 @@ enum StreamEvent {
     Error {
         error: String,
     },
+    Cost {
+        input_tokens: u64,
+        output_tokens: u64,
+        models: Vec<ModelUsage>,
+    },
+    ContextUsage {
+        total_tokens: i32,
+        context_limit: usize,
+    },
     Complete {
         total_tokens: Option<i32>,
     },
@@ -106,53 +237,6 @@ pub enum RunMode {
     Plan,
 }
 
-struct HistoryManager {
-    history_file: PathBuf,
-    old_history_file: PathBuf,
-}
-
-impl HistoryManager {
-    fn new() -> Self {
-        Self {
-            history_file: Paths::state_dir().join("history.txt"),
-            old_history_file: Paths::config_dir().join("history.txt"),
-        }
-    }
-
-    fn load(
-        &self,
-        editor: &mut rustyline::Editor<GooseCompleter, rustyline::history::DefaultHistory>,
-    ) {
-        if let Some(parent) = self.history_file.parent() {
-            if !parent.exists() {
-                if let Err(e) = std::fs::create_dir_all(parent) {
-                    eprintln!("Warning: Failed to create history directory: {}", e);
-                }
-            }
-        }
-
-        let history_files = [&self.history_file, &self.old_history_file];
-        if let Some(file) = history_files.iter().find(|f| f.exists()) {
-            if let Err(err) = editor.load_history(file) {
-                eprintln!("Warning: Failed to load command history: {}", err);
-            }
-        }
-    }
-
-    fn save(
-        &self,
-        editor: &mut rustyline::Editor<GooseCompleter, rustyline::history::DefaultHistory>,
-    ) {
-        if let Err(err) = editor.save_history(&self.history_file) {
-            eprintln!("Warning: Failed to save command history: {}", err);
-        } else if self.old_history_file.exists() {
-            if let Err(err) = std::fs::remove_file(&self.old_history_file) {
-                eprintln!("Warning: Failed to remove old history file: {}", err);
-            }
-        }
-    }
-}
-
 pub struct CliSession {
     agent: Agent,
     messages: Conversation,
@@ -165,6 +249,29 @@ pub struct CliSession {
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
     output_format: String,
+    renderer: Box<dyn Renderer>,
+    cost_tracker: CostTracker,
+    /// Model reported by the most recent ModelChange event (lead/worker switching)
+    active_model: Option<String>,
+    /// Error that ended the last turn, reported as the exit status of headless runs
+    last_error: Option<SessionError>,
+    /// Bottom bar configured with GOOSE_CLI_STATUS_LINE, shown in interactive sessions
+    status_line: Option<StatusLine>,
+    /// Pre-images of files changed by tools, for /undo
+    file_history: FileHistory,
+    /// Set for `--non-interactive` runs, which answer tool approvals with it instead of
+    /// prompting and fail on anything else that would need the user
+    approval_policy: Option<ApprovalPolicy>,
+    /// Steps of the plan being acted on, updated from the progress the agent reports
+    plan_progress: Option<PlanChecklist>,
+    /// Where a `--sandbox` session works until its changes are applied
+    sandbox: Option<sandbox::Sandbox>,
+    /// Run interactive sessions in the full screen interface
+    tui: bool,
+    /// Root of the session's trace, with a child span for every turn
+    trace: tracing::Span,
+    /// Files added with `--file`, `--image` or `/attach`, sent with the next message
+    pending_attachments: Vec<mentions::Attachment>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -178,8 +285,18 @@ pub enum HintStatus {
 pub struct CompletionCache {
     pub prompts: HashMap<String, Vec<String>>,
     pub prompt_info: HashMap<String, output::PromptInfo>,
+    /// Names of the extensions enabled in the session
+    pub extensions: Vec<String>,
+    /// Names accepted by /builtin
+    pub builtins: Vec<String>,
     pub last_updated: Instant,
     pub hint_status: HintStatus,
+    /// Keymap action pressed at the prompt, waiting to be run
+    pub key_action: Option<keymap::KeyAction>,
+    /// What had been typed when the action was pressed, restored at the next prompt
+    pub draft: String,
+    /// Files `@` mentions complete to
+    pub workspace_files: mentions::WorkspaceFiles,
 }
 
 impl CompletionCache {
@@ -187,8 +304,13 @@ impl CompletionCache {
         Self {
             prompts: HashMap::new(),
             prompt_info: HashMap::new(),
+            extensions: Vec::new(),
+            builtins: Vec::new(),
             last_updated: Instant::now(),
             hint_status: HintStatus::Default,
+            key_action: None,
+            draft: String::new(),
+            workspace_files: mentions::WorkspaceFiles::default(),
         }
     }
 }
@@ -240,13 +362,36 @@ impl CliSession {
         retry_config: Option<RetryConfig>,
         output_format: String,
     ) -> Self {
-        let messages = agent
+        let session = agent
             .config
             .session_manager
             .get_session(&session_id, true)
             .await
-            .map(|session| session.conversation.unwrap_or_default())
             .unwrap();
+        output::set_redaction_dir(&session.working_dir);
+        let cost_tracker = CostTracker::from_extension_data(&session.extension_data)
+            .unwrap_or_else(|| {
+                let mut tracker = CostTracker::default();
+                tracker.set_baseline(
+                    session.accumulated_input_tokens.unwrap_or(0),
+                    session.accumulated_output_tokens.unwrap_or(0),
+                );
+                tracker
+            });
+        output::set_pinned(
+            &PinnedState::from_extension_data(&session.extension_data).unwrap_or_default(),
+        );
+        let messages = session.conversation.unwrap_or_default();
+        let renderer = renderer::renderer_for_format(&output_format, debug);
+        extension_logs::init_for_session(&session_id);
+        let trace = spans::session(
+            &session_id,
+            session.provider_name.as_deref().unwrap_or_default(),
+            session
+                .model_config
+                .as_ref()
+                .map_or("", |config| config.model_name.as_str()),
+        );
 
         CliSession {
             agent,
@@ -260,6 +405,22 @@ impl CliSession {
             edit_mode,
             retry_config,
             output_format,
+            renderer,
+            cost_tracker,
+            active_model: None,
+            last_error: None,
+            status_line: None,
+            file_history: FileHistory::with_git_checkpoints(
+                Config::global()
+                    .get_param::<bool>("GOOSE_CLI_CHECKPOINT_GIT")
+                    .unwrap_or(false),
+            ),
+            approval_policy: None,
+            plan_progress: None,
+            sandbox: None,
+            tui: false,
+            trace,
+            pending_attachments: Vec::new(),
         }
     }
 
@@ -267,6 +428,117 @@ impl CliSession {
         &self.session_id
     }
 
+    /// Show interactive sessions in the full screen interface instead of the scrollback.
+    pub fn set_tui(&mut self, tui: bool) {
+        self.tui = tui;
+    }
+
+    /// Replace the output backend, e.g. to capture structured output instead of printing.
+    pub fn set_renderer(&mut self, renderer: Box<dyn Renderer>) {
+        self.renderer = renderer;
+    }
+
+    /// Also write everything shown in the terminal from now on to an asciinema cast at `path`.
+    pub fn record_cast(&mut self, path: &Path) -> std::io::Result<()> {
+        let cast = cast::start(path, &format!("goose session {}", self.session_id))?;
+        output::wrap_term_writer(|inner| Box::new(cast::CastWriter::new(cast, inner)));
+        Ok(())
+    }
+
+    /// Never prompt: tool approvals and extensions' requests to use the model are answered by
+    /// `policy`, and elicitations and running out of turns end the run with an error.
+    pub fn set_non_interactive(&mut self, policy: ApprovalPolicy) {
+        self.approval_policy = Some(policy);
+        sampling::set_policy(match policy {
+            ApprovalPolicy::Approve => SamplingPolicy::Allow,
+            ApprovalPolicy::Deny => SamplingPolicy::Deny,
+        });
+    }
+
+    pub fn set_sandbox(&mut self, sandbox: sandbox::Sandbox) {
+        self.sandbox = Some(sandbox);
+    }
+
+    /// Removes the sandbox of a session that ends before it starts, see [`sandbox::Sandbox::discard`].
+    pub fn discard_sandbox(&mut self) {
+        if let Some(sandbox) = self.sandbox.take() {
+            sandbox.discard();
+        }
+    }
+
+    /// Ends a `--sandbox` session: shows what changed and applies it to the checkout once
+    /// confirmed. Runs that can't ask, or where the patch no longer applies, keep it in a file.
+    pub async fn close_sandbox(&mut self) -> Result<()> {
+        let Some(sandbox) = self.sandbox.take() else {
+            return Ok(());
+        };
+        std::env::set_current_dir(sandbox.origin())?;
+        self.agent
+            .config
+            .session_manager
+            .update(&self.session_id)
+            .working_dir(sandbox.origin().to_path_buf())
+            .apply()
+            .await?;
+
+        let patch = sandbox.diff()?;
+        let text_output = self.output_format == "text";
+        if patch.is_empty() {
+            if text_output {
+                output::render_text("No changes were made in the sandbox", None, true);
+            }
+            return sandbox.remove();
+        }
+
+        let files = sandbox::changed_files(&patch);
+        let can_ask =
+            text_output && self.approval_policy.is_none() && std::io::stdin().is_terminal();
+        if text_output {
+            output::render_sandbox_changes(&patch, &files);
+        }
+        let apply = can_ask
+            && cliclack::confirm(format!(
+                "Apply these changes to {}?",
+                sandbox.origin().display()
+            ))
+            .initial_value(false)
+            .interact()?;
+
+        let applied = if apply {
+            match sandbox.apply(&patch) {
+                Ok(()) => true,
+                Err(e) => {
+                    output::render_error(&format!("Failed to apply the changes: {}", e));
+                    false
+                }
+            }
+        } else {
+            false
+        };
+        if applied {
+            output::render_text(
+                &format!("Applied changes to {} files", files.len()),
+                Some(Color::Green),
+                false,
+            );
+        } else {
+            let path = Paths::in_state_dir("sandbox").join(format!("{}.patch", self.session_id));
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, &patch)?;
+            eprintln!(
+                "{}",
+                console::style(format!(
+                    "Sandbox changes were not applied; the patch is in {} (apply it with `git apply`)",
+                    path.display()
+                ))
+                .yellow()
+            );
+        }
+        sandbox.remove()
+    }
+
     /// Parse a stdio extension command string into an ExtensionConfig
     /// Format: "ENV1=val1 ENV2=val2 command args..."
     pub fn parse_stdio_extension(extension_command: &str) -> Result<ExtensionConfig> {
@@ -374,7 +646,11 @@ impl CliSession {
             self.agent
                 .add_extension(config, &self.session_id)
                 .await
-                .map_err(|e| anyhow::anyhow!("Failed to start extension: {}", e))?;
+                .map_err(|e| {
+                    // kept under the session error so the extension's stderr can be shown
+                    let message = e.to_string();
+                    anyhow::Error::new(e).context(SessionError::ExtensionStartup(message))
+                })?;
         }
 
         self.invalidate_completion_cache().await;
@@ -464,8 +740,13 @@ impl CliSession {
 
     /// Start an interactive session, optionally with an initial message
     pub async fn interactive(&mut self, prompt: Option<String>) -> Result<()> {
+        // extensions ask before using the model only where someone is there to answer
+        sampling::set_policy(SamplingPolicy::Ask);
+        if self.tui && std::io::stdout().is_terminal() && std::io::stdin().is_terminal() {
+            return self.interactive_tui(prompt).await;
+        }
         if let Some(prompt) = prompt {
-            let msg = Message::user().with_text(&prompt);
+            let msg = self.with_attachments(Message::user().with_text(&prompt));
             self.process_message(msg, CancellationToken::default())
                 .await?;
         }
@@ -477,8 +758,18 @@ impl CliSession {
         history_manager.load(&mut editor);
 
         output::display_greeting();
+        if !self.messages.is_empty() && output::get_verbosity().shows_session_info() {
+            let providers = model_picker::configured_providers().await;
+            if let Some((costs, _, _)) = self.turn_costs(&providers).await {
+                output::render_turn_cost_hint(&costs);
+            }
+        }
+        self.status_line = StatusLine::from_config();
         loop {
-            self.display_context_usage().await?;
+            if output::get_verbosity().shows_session_info() {
+                self.display_context_usage().await?;
+            }
+            self.refresh_status_line().await;
 
             let conversation_strings: Vec<String> = self
                 .messages
@@ -501,20 +792,135 @@ impl CliSession {
                 .await?;
         }
 
+        self.status_line = None;
+        self.wind_down().await;
+        output::render_files_touched(&files_touched(self.messages.messages()), self.debug);
+        println!(
+            "\n  {} {}",
+            console::style("●").red(),
+            console::style(tr_with("session-closed", &[("session", &self.session_id)])).dim()
+        );
+
+        Ok(())
+    }
+
+    /// Cleans up what the session set up around the conversation when it ends: background
+    /// jobs, images saved for viewing and the tool pane.
+    async fn wind_down(&self) {
+        jobs::stop_all().await;
+        image::remove_saved_images();
+        output::close_tool_pane();
+    }
+
+    /// The interactive loop in the full screen interface. The conversation, everything printed
+    /// and the agent's responses go to the message pane, and the input box takes the place of
+    /// the prompt. Prompts of their own, such as tool approvals, get the normal screen back
+    /// while they are answered.
+    async fn interactive_tui(&mut self, prompt: Option<String>) -> Result<()> {
+        self.update_completion_cache().await?;
+        let mut editor = self.create_editor()?;
+        let history_manager = HistoryManager::new();
+        history_manager.load(&mut editor);
+        let history = editor.history().iter().cloned().collect();
+
+        let tui = tui::Tui::enter(history)?;
+        let previous_writer = output::set_term_writer(Box::new(tui.writer()));
+        let previous_renderer = std::mem::replace(
+            &mut self.renderer,
+            Box::new(tui::TuiRenderer::new(tui.clone(), self.debug)),
+        );
+        let result = self
+            .run_tui(&tui, prompt, &history_manager, &mut editor)
+            .await;
+        self.renderer = previous_renderer;
+        output::set_term_writer(previous_writer);
+        tui.leave();
+        self.wind_down().await;
+
+        output::render_files_touched(&files_touched(self.messages.messages()), self.debug);
         println!(
             "\n  {} {}",
             console::style("●").red(),
-            console::style(format!("session closed · {}", &self.session_id)).dim()
+            console::style(tr_with("session-closed", &[("session", &self.session_id)])).dim()
         );
+        result
+    }
+
+    async fn run_tui(
+        &mut self,
+        tui: &tui::Tui,
+        prompt: Option<String>,
+        history: &HistoryManager,
+        editor: &mut rustyline::Editor<GooseCompleter, rustyline::history::DefaultHistory>,
+    ) -> Result<()> {
+        for message in &self.messages {
+            output::render_message(message, self.debug);
+        }
+        output::display_greeting();
+        if let Some(prompt) = prompt {
+            tui.set_busy(true);
+            let msg = self.with_attachments(Message::user().with_text(&prompt));
+            self.process_message(msg, CancellationToken::default())
+                .await?;
+        }
 
+        loop {
+            tui.set_status(self.tui_status().await);
+            output::run_status_hook("waiting");
+            let Some(line) = tui.read_input()? else {
+                break;
+            };
+            if !line.trim().is_empty() {
+                editor.add_history_entry(line.as_str())?;
+            }
+            let input = input::parse_line(&line);
+            if matches!(input, InputResult::Exit) {
+                break;
+            }
+            let prompts = input::may_prompt(&input);
+            if prompts {
+                tui.release();
+            }
+            let result = self.handle_input(input, history, editor).await;
+            if prompts {
+                tui.reclaim();
+            }
+            tui.set_busy(false);
+            result?;
+        }
         Ok(())
     }
 
+    /// The bottom line of the full screen interface
+    async fn tui_status(&self) -> String {
+        let (provider, model) = self.current_model().await;
+        let context_limit = match self.agent.provider().await {
+            Ok(provider) => provider.get_model_config().context_limit(),
+            Err(_) => 0,
+        };
+        let context_tokens = self
+            .get_total_token_usage()
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(0)
+            .max(0) as usize;
+        tui::status_line(
+            &provider,
+            &model,
+            context_tokens,
+            context_limit,
+            output::estimate_tracked_cost(&self.cost_tracker),
+        )
+    }
+
     fn create_editor(
         &self,
     ) -> Result<rustyline::Editor<GooseCompleter, rustyline::history::DefaultHistory>> {
-        let builder =
-            rustyline::Config::builder().completion_type(rustyline::CompletionType::Circular);
+        let builder = rustyline::Config::builder()
+            .completion_type(rustyline::CompletionType::Circular)
+            .max_history_size(history::MAX_HISTORY)?
+            .history_ignore_dups(true)?;
         let builder = match self.edit_mode {
             Some(mode) => builder.edit_mode(mode),
             None => builder.edit_mode(EditMode::Emacs),
@@ -544,7 +950,7 @@ impl CliSession {
                 history.save(editor);
                 match self.add_extension(cmd.clone()).await {
                     Ok(_) => output::render_extension_success(&cmd),
-                    Err(e) => output::render_extension_error(&cmd, &e.to_string()),
+                    Err(e) => output::render_extension_error(&cmd, &e, self.debug),
                 }
             }
             InputResult::AddBuiltin(names) => {
@@ -567,11 +973,21 @@ impl CliSession {
                 self.handle_select_theme(&theme_name);
             }
             InputResult::Retry => {}
+            InputResult::Tools(query) => {
+                history.save(editor);
+                self.handle_tools(query).await;
+            }
             InputResult::ListPrompts(extension) => {
                 history.save(editor);
-                match self.list_prompts(extension).await {
-                    Ok(prompts) => output::render_prompts(&prompts),
-                    Err(e) => output::render_error(&e.to_string()),
+                if std::io::stdout().is_terminal() {
+                    if let Err(e) = self.handle_prompt_picker(extension).await {
+                        output::render_error(&e.to_string());
+                    }
+                } else {
+                    match self.list_prompts(extension).await {
+                        Ok(prompts) => output::render_prompts(&prompts),
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
                 }
             }
             InputResult::GooseMode(mode) => {
@@ -601,10 +1017,180 @@ impl CliSession {
                 history.save(editor);
                 self.handle_compact().await?;
             }
+            InputResult::Cost => {
+                history.save(editor);
+                output::render_cost_breakdown(&self.cost_tracker);
+            }
+            InputResult::Context => {
+                history.save(editor);
+                if let Err(e) = self.display_context_breakdown().await {
+                    output::render_error(&e.to_string());
+                }
+            }
+            InputResult::Jobs => {
+                history.save(editor);
+                output::render_jobs(&jobs::list());
+            }
+            InputResult::KillJob(id) => {
+                history.save(editor);
+                output::render_job_stopped(id, jobs::stop(id).await.as_ref());
+            }
+            InputResult::PickTheme => {
+                history.save(editor);
+                if let Err(e) = theme_picker::pick_theme() {
+                    output::render_error(&format!("Theme picker failed: {}", e));
+                }
+            }
+            InputResult::ShowToolCall(number) => {
+                history.save(editor);
+                if !output::render_tool_call(number) {
+                    output::render_error(&format!("No tool call #{} in this session", number));
+                }
+            }
+            InputResult::ExpandTruncated => {
+                history.save(editor);
+                match output::last_truncated_output() {
+                    Some(text) => {
+                        if let Err(e) = stream_keys::page(&text) {
+                            output::render_error(&format!("Failed to open pager: {}", e));
+                        }
+                    }
+                    None => output::render_error("Nothing has been truncated yet"),
+                }
+            }
+            InputResult::ShowLogs(extension) => {
+                history.save(editor);
+                output::render_extension_logs(extension.as_deref());
+            }
+            InputResult::Subagent(id) => {
+                history.save(editor);
+                if let Err(e) = self.handle_subagent(id.as_deref()).await {
+                    output::render_error(&format!("Failed to show the subagent: {}", e));
+                }
+            }
+            InputResult::CopyCodeBlock(n) => {
+                history.save(editor);
+                self.copy_code_block(n);
+            }
+            InputResult::Rewind(turns) => {
+                history.save(editor);
+                if let Err(e) = self.handle_rewind(turns).await {
+                    output::render_error(&format!("Failed to rewind the session: {}", e));
+                }
+            }
+            InputResult::Undo => {
+                history.save(editor);
+                match self.file_history.undo() {
+                    Some((change, Ok(files))) => output::render_undo(&change.tool_name, &files),
+                    Some((change, Err(e))) => output::render_error(&format!(
+                        "Failed to undo the changes of {}: {}",
+                        change.tool_name, e
+                    )),
+                    None => output::render_error("No file changes by tools to undo"),
+                }
+            }
+            InputResult::Diff => {
+                history.save(editor);
+                output::render_file_diffs(&self.file_history.session_diffs());
+            }
+            InputResult::Fork(turn) => {
+                history.save(editor);
+                if let Err(e) = self.handle_fork(turn).await {
+                    output::render_error(&format!("Failed to fork the session: {}", e));
+                }
+            }
+            InputResult::Todo => {
+                history.save(editor);
+                match self.get_session().await {
+                    Ok(session) => output::render_todo_list(
+                        &TodoState::from_extension_data(&session.extension_data)
+                            .map(|state| todo::TodoList::parse(&state.content))
+                            .unwrap_or_default(),
+                    ),
+                    Err(e) => output::render_error(&format!("Failed to read the todo list: {}", e)),
+                }
+            }
+            InputResult::Pin(target) => {
+                history.save(editor);
+                if let Err(e) = self.handle_pin(target).await {
+                    output::render_error(&format!("Failed to pin: {}", e));
+                }
+            }
+            InputResult::Unpin(number) => {
+                history.save(editor);
+                if let Err(e) = self.handle_unpin(number).await {
+                    output::render_error(&format!("Failed to unpin: {}", e));
+                }
+            }
+            InputResult::Memory(note) => {
+                history.save(editor);
+                if let Err(e) = self.handle_memory(note).await {
+                    output::render_error(&format!("Failed to remember: {}", e));
+                }
+            }
+            InputResult::Forget(number) => {
+                history.save(editor);
+                if let Err(e) = self.handle_forget(number).await {
+                    output::render_error(&format!("Failed to forget: {}", e));
+                }
+            }
+            InputResult::SaveResource(number, path) => {
+                history.save(editor);
+                if let Err(e) = self.handle_save_resource(number, path) {
+                    output::render_error(&format!("Failed to save the resource: {}", e));
+                }
+            }
+            InputResult::Model(target) => {
+                history.save(editor);
+                if let Err(e) = self.handle_model(target).await {
+                    output::render_error(&format!("Failed to switch model: {}", e));
+                }
+            }
+            InputResult::Regenerate(options) => {
+                history.save(editor);
+                if let Err(e) = self.handle_regenerate(options).await {
+                    output::render_error(&format!("Failed to retry: {}", e));
+                }
+            }
+            InputResult::Params(change) => {
+                history.save(editor);
+                if let Err(e) = self.handle_params(change).await {
+                    output::render_error(&format!("Failed to change the parameters: {}", e));
+                }
+            }
+            InputResult::Attach(paths) => {
+                history.save(editor);
+                self.handle_attach(paths);
+            }
         }
         Ok(())
     }
 
+    /// Adds a file to the next message, as an image if it is one. With `image` the file has
+    /// to be an image.
+    pub fn attach(&mut self, path: &str, image: bool) -> Result<()> {
+        let attachment = mentions::Attachment::from_path(path, image)?;
+        self.pending_attachments.push(attachment);
+        Ok(())
+    }
+
+    /// `message` with the files attached since the last message
+    fn with_attachments(&mut self, message: Message) -> Message {
+        self.pending_attachments
+            .drain(..)
+            .fold(message, |message, attachment| attachment.add_to(message))
+    }
+
+    /// `/attach`: adds files to the next message, or lists the ones waiting for it
+    fn handle_attach(&mut self, paths: Vec<String>) {
+        for path in &paths {
+            if let Err(e) = self.attach(path, false) {
+                output::render_error(&format!("{:#}", e));
+            }
+        }
+        output::render_pending_attachments(&self.pending_attachments);
+    }
+
     async fn handle_message_input(
         &mut self,
         content: &str,
@@ -614,7 +1200,8 @@ impl CliSession {
         match self.run_mode {
             RunMode::Normal => {
                 history.save(editor);
-                self.push_message(Message::user().with_text(content));
+                let message = self.with_attachments(user_message(content));
+                self.push_message(message);
 
                 if let Err(e) = crate::project_tracker::update_project_tracker(
                     Some(content),
@@ -641,7 +1228,8 @@ impl CliSession {
             }
             RunMode::Plan => {
                 let mut plan_messages = self.messages.clone();
-                plan_messages.push(Message::user().with_text(content));
+                let message = self.with_attachments(user_message(content));
+                plan_messages.push(message);
                 let reasoner = get_reasoner().await?;
                 self.plan_with_reasoner_model(plan_messages, reasoner)
                     .await?;
@@ -683,6 +1271,18 @@ impl CliSession {
                 println!("Switching to Ansi theme");
                 output::Theme::Ansi
             }
+            "auto" => {
+                let detected = match output::set_auto_theme() {
+                    output::Theme::Light => "Light",
+                    output::Theme::Dark => "Dark",
+                    output::Theme::Ansi => "Ansi (background could not be detected)",
+                };
+                println!(
+                    "Following the terminal background, using {} theme",
+                    detected
+                );
+                return;
+            }
             _ => output::Theme::Dark,
         };
         output::set_theme(new_theme);
@@ -777,29 +1377,293 @@ impl CliSession {
         Ok(())
     }
 
-    async fn handle_recipe(&mut self, filepath_opt: Option<String>) {
-        println!("{}", console::style("Generating Recipe").green());
+    /// Copies the conversation up to `turn` into a new session and continues there. Both
+    /// transcripts get a note pointing at the other one.
+    async fn handle_fork(&mut self, turn: Option<usize>) -> Result<()> {
+        let Some(end) = fork::fork_point(self.messages.messages(), turn) else {
+            output::render_error(&format!(
+                "There is no turn {} to fork from; this session has {} turns",
+                turn.unwrap_or_default(),
+                fork::turn_count(self.messages.messages())
+            ));
+            return Ok(());
+        };
+        let kept = self.messages.messages().get(..end).unwrap_or_default();
+        let turn = fork::turn_count(kept);
 
-        output::show_thinking();
-        let recipe = self
-            .agent
-            .create_recipe(&self.session_id, self.messages.clone())
-            .await;
-        output::hide_thinking();
+        let session_manager = &self.agent.config.session_manager;
+        let original = self.get_session().await?;
+        let forked = session_manager
+            .copy_session(&self.session_id, format!("{} (fork)", original.name))
+            .await?;
 
-        match recipe {
-            Ok(recipe) => {
-                let filepath_str = filepath_opt.as_deref().unwrap_or("recipe.yaml");
-                match self.save_recipe(&recipe, filepath_str) {
-                    Ok(path) => println!(
-                        "{}",
-                        console::style(format!("Saved recipe to {}", path.display())).green()
-                    ),
-                    Err(e) => println!("{}", console::style(e).red()),
+        let mut conversation = Conversation::new_unvalidated(kept.iter().cloned());
+        conversation.push(fork::banner(format!(
+            "⑂ Forked from session {} after turn {}",
+            self.session_id, turn
+        )));
+        session_manager
+            .replace_conversation(&forked.id, &conversation)
+            .await?;
+        session_manager
+            .add_message(
+                &self.session_id,
+                &fork::banner(format!(
+                    "⑂ Forked into session {} after turn {}",
+                    forked.id, turn
+                )),
+            )
+            .await?;
+
+        output::render_fork(&self.session_id, &forked.id, turn);
+        self.session_id = forked.id;
+        self.messages = conversation;
+        extension_logs::init_for_session(&self.session_id);
+        Ok(())
+    }
+
+    /// `/tools`: lists the tools the agent has, or shows those of an extension or one tool
+    async fn handle_tools(&self, query: input::ToolsQuery) {
+        let tools = self.agent.list_tools(&self.session_id, None).await;
+        let Some(filter) = query.filter else {
+            let all: Vec<&Tool> = tools.iter().collect();
+            output::render_tools(&all);
+            return;
+        };
+        match output::select_tools(&tools, &filter) {
+            output::ToolSelection::Extension(selected) if !query.schema => {
+                output::render_tools(&selected)
+            }
+            output::ToolSelection::Extension(_) => output::render_error(&format!(
+                "{} is an extension; pass one of its tools for --schema",
+                filter
+            )),
+            output::ToolSelection::Tools(selected) if selected.is_empty() => output::render_error(
+                &format!("No extension or tool named {}; /tools lists them", filter),
+            ),
+            output::ToolSelection::Tools(selected) => {
+                for tool in selected {
+                    output::render_tool(tool, query.schema);
                 }
             }
-            Err(e) => {
-                println!(
+        }
+    }
+
+    /// `/rewind`: drops the latest turns from the conversation, for the model and the
+    /// transcript alike, and puts back the files their tool calls changed.
+    async fn handle_rewind(&mut self, turns: usize) -> Result<()> {
+        let messages = self.messages.messages();
+        let Some(start) = fork::rewind_point(messages, turns) else {
+            output::render_error(&format!(
+                "There is nothing to rewind {} turns to; this session has {} turns",
+                turns,
+                fork::turn_count(messages)
+            ));
+            return Ok(());
+        };
+        let kept = fork::turn_count(&messages[..start]);
+        let dropped: Vec<String> = messages[start..]
+            .iter()
+            .filter(|message| fork::is_prompt(message))
+            .map(mentions::typed_text)
+            .collect();
+
+        let conversation = Conversation::new_unvalidated(messages[..start].iter().cloned());
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &conversation)
+            .await?;
+        self.messages = conversation;
+
+        let files = if self.file_history.can_rewind(kept) {
+            Some(self.file_history.rewind(kept)?)
+        } else {
+            None
+        };
+        output::render_rewind(&dropped, files.as_deref());
+        Ok(())
+    }
+
+    /// Lists the pins, or pins a message or file so compaction keeps it in full. Pins live in
+    /// the session's extension data, where the agent reads them when it compacts.
+    async fn handle_pin(&self, target: Option<String>) -> Result<()> {
+        let session = self.get_session().await?;
+        let mut pinned =
+            PinnedState::from_extension_data(&session.extension_data).unwrap_or_default();
+        let Some(target) = target else {
+            output::render_pins(&pinned);
+            return Ok(());
+        };
+
+        let pin = match pins::parse_target(&target) {
+            pins::PinTarget::Message(n) => {
+                let messages = session.conversation.unwrap_or_default();
+                let Some(message) = pins::recent_message(messages.messages(), n) else {
+                    output::render_error(&format!("There is no message {} to pin", n));
+                    return Ok(());
+                };
+                output::render_message(message, self.debug);
+                pins::message_pin(message)
+            }
+            pins::PinTarget::File(path) => {
+                let path = std::fs::canonicalize(path)
+                    .with_context(|| format!("{} is not a message number or a file", target))?;
+                if !path.is_file() {
+                    anyhow::bail!("{} is not a file", path.display());
+                }
+                if std::fs::metadata(&path)?.len() > MAX_PINNED_FILE_BYTES {
+                    output::render_error(&format!(
+                        "{} is too large to pin (over {} KiB)",
+                        path.display(),
+                        MAX_PINNED_FILE_BYTES / 1024
+                    ));
+                    return Ok(());
+                }
+                goose::session::Pin::File { path }
+            }
+        };
+        if pinned.add(pin) {
+            self.save_pins(&pinned).await?;
+        }
+        output::render_pins(&pinned);
+        Ok(())
+    }
+
+    async fn handle_memory(&self, note: Option<String>) -> Result<()> {
+        let path = project_memory::memory_path(&std::env::current_dir()?);
+        let mut memory = ProjectMemory::load(&path)?;
+        if let Some(note) = note {
+            let Some(note) = Memory::parse(&note) else {
+                output::render_error("Nothing to remember");
+                return Ok(());
+            };
+            if memory.add(note) {
+                memory.save(&path)?;
+                self.update_memory_prompt(&memory).await;
+            }
+        }
+        output::render_memories(&memory, &path);
+        Ok(())
+    }
+
+    async fn handle_forget(&self, number: usize) -> Result<()> {
+        let path = project_memory::memory_path(&std::env::current_dir()?);
+        let mut memory = ProjectMemory::load(&path)?;
+        if memory.remove(number).is_none() {
+            output::render_error(&format!("There is no memory {}", number));
+            return Ok(());
+        }
+        memory.save(&path)?;
+        self.update_memory_prompt(&memory).await;
+        output::render_memories(&memory, &path);
+        Ok(())
+    }
+
+    /// Gives the agent the memories as they are now, from the next message on
+    async fn update_memory_prompt(&self, memory: &ProjectMemory) {
+        self.agent
+            .extend_system_prompt(
+                project_memory::SYSTEM_PROMPT_KEY.to_string(),
+                memory.system_prompt(),
+            )
+            .await;
+    }
+
+    async fn handle_unpin(&self, number: usize) -> Result<()> {
+        let session = self.get_session().await?;
+        let mut pinned =
+            PinnedState::from_extension_data(&session.extension_data).unwrap_or_default();
+        if pinned.remove(number).is_none() {
+            output::render_error(&format!("There is no pin {}", number));
+            return Ok(());
+        }
+        self.save_pins(&pinned).await?;
+        output::render_pins(&pinned);
+        Ok(())
+    }
+
+    fn handle_save_resource(&self, number: Option<usize>, path: Option<String>) -> Result<()> {
+        let Some(resource) = output::binary_resource(number) else {
+            output::render_error(&match number {
+                Some(number) => format!("There is no binary resource {}", number),
+                None => "No tool has returned a binary resource yet".to_string(),
+            });
+            return Ok(());
+        };
+        let path = match path {
+            Some(path) => path,
+            None => cliclack::input("Save to:")
+                .default_input(&resource.file_name())
+                .interact()?,
+        };
+        let written = resource.save(Path::new(&path))?;
+        output::render_text(
+            &format!("Saved {} to {}", resources::format_size(written), path),
+            None,
+            true,
+        );
+        Ok(())
+    }
+
+    async fn handle_subagent(&self, id: Option<&str>) -> Result<()> {
+        let session = self.get_session().await?;
+        let state =
+            SubagentsState::from_extension_data(&session.extension_data).unwrap_or_default();
+        let Some(id) = id else {
+            output::render_subagents(&state);
+            return Ok(());
+        };
+        let subagent = state
+            .find(id)
+            .ok_or_else(|| anyhow::anyhow!("no subagent {} in this session", id))?;
+        let transcript = self
+            .agent
+            .config
+            .session_manager
+            .get_session(&subagent.session_id, true)
+            .await?
+            .conversation
+            .unwrap_or_default();
+        output::render_subagent_transcript(subagent, transcript.messages(), self.debug);
+        Ok(())
+    }
+
+    async fn save_pins(&self, pinned: &PinnedState) -> Result<()> {
+        self.agent
+            .config
+            .session_manager
+            .update_extension_data(&self.session_id, |extension_data| {
+                pinned.to_extension_data(extension_data)
+            })
+            .await?;
+        output::set_pinned(pinned);
+        Ok(())
+    }
+
+    async fn handle_recipe(&mut self, filepath_opt: Option<String>) {
+        println!("{}", console::style("Generating Recipe").green());
+
+        output::show_thinking();
+        let recipe = self
+            .agent
+            .create_recipe(&self.session_id, self.messages.clone())
+            .await;
+        output::hide_thinking();
+
+        match recipe {
+            Ok(recipe) => {
+                let filepath_str = filepath_opt.as_deref().unwrap_or("recipe.yaml");
+                match self.save_recipe(&recipe, filepath_str) {
+                    Ok(path) => println!(
+                        "{}",
+                        console::style(format!("Saved recipe to {}", path.display())).green()
+                    ),
+                    Err(e) => println!("{}", console::style(e).red()),
+                }
+            }
+            Err(e) => {
+                println!(
                     "{}: {:?}",
                     console::style("Failed to generate recipe").red(),
                     e
@@ -876,7 +1740,9 @@ impl CliSession {
                     }
                 };
                 if should_act {
-                    output::render_act_on_plan();
+                    let plan_text = plan_response.as_concat_text();
+                    let checklist = PlanChecklist::parse(&plan_text);
+                    output::render_act_on_plan(checklist.as_ref());
                     self.run_mode = RunMode::Normal;
                     // set goose mode: auto if that isn't already the case
                     let config = Config::global();
@@ -887,14 +1753,28 @@ impl CliSession {
 
                     // clear the messages before acting on the plan
                     self.messages.clear();
-                    // add the plan response as a user message
-                    let plan_message = Message::user().with_text(plan_response.as_concat_text());
+                    // add the plan response as a user message, asking for progress on its steps
+                    let plan_message = match &checklist {
+                        Some(checklist) => Message::user().with_text(format!(
+                            "{}\n\n{}",
+                            plan_text,
+                            checklist.instructions()
+                        )),
+                        None => Message::user().with_text(plan_text),
+                    };
                     self.push_message(plan_message);
+                    self.plan_progress = checklist;
                     // act on the plan
                     output::show_thinking();
-                    self.process_agent_response(true, CancellationToken::default())
-                        .await?;
+                    let result = self
+                        .process_agent_response(true, CancellationToken::default())
+                        .await;
                     output::hide_thinking();
+                    if let Some(mut plan) = self.plan_progress.take() {
+                        plan.finish();
+                        output::render_plan_checklist(&plan);
+                    }
+                    result?;
 
                     // Reset run & goose mode
                     if curr_goose_mode != GooseMode::Auto {
@@ -918,19 +1798,93 @@ impl CliSession {
 
     /// Process a single message and exit
     pub async fn headless(&mut self, prompt: String) -> Result<()> {
-        let message = Message::user().with_text(&prompt);
-        self.process_message(message, CancellationToken::default())
-            .await?;
-        Ok(())
+        let message = self.with_attachments(Message::user().with_text(&prompt));
+        let result = self
+            .process_message(message, CancellationToken::default())
+            .await;
+        self.wind_down().await;
+        result?;
+        match self.last_error.take() {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Run without a terminal, answering the messages of clients attached with
+    /// `goose session attach` one at a time until the process is stopped
+    pub async fn serve(&mut self) -> Result<()> {
+        let mut server = attach::Server::bind(&self.session_id)?;
+        server.add_history(self.messages.messages());
+        self.set_renderer(Box::new(server.renderer()));
+        eprintln!(
+            "Serving session {} at {}; attach with `goose session attach {}`",
+            self.session_id,
+            server.path().display(),
+            self.session_id
+        );
+
+        let mut shutdown = crate::signal::shutdown_signal();
+        loop {
+            let text = tokio::select! {
+                text = server.next_message() => text,
+                _ = &mut shutdown => None,
+            };
+            let Some(text) = text else {
+                self.wind_down().await;
+                return Ok(());
+            };
+            let cancel_token = server.start_turn();
+            if let Err(e) = self
+                .process_message(user_message(&text), cancel_token)
+                .await
+            {
+                self.renderer.render_error(&e.to_string());
+            } else if let Some(e) = self.last_error.take() {
+                self.renderer.render_error(&e.to_string());
+            }
+            server.end_turn();
+        }
     }
 
+    /// Streams the agent's response to the latest message as one turn of the session's trace,
+    /// with the tokens it used and whether it failed.
     async fn process_agent_response(
         &mut self,
         interactive: bool,
         cancel_token: CancellationToken,
     ) -> Result<()> {
+        let span = spans::turn(&self.trace, fork::turn_count(self.messages.messages()));
+        let (input_before, output_before) = self.cost_tracker.total_tokens();
+        let result = self
+            .stream_agent_response(interactive, cancel_token)
+            .instrument(span.clone())
+            .await;
+        let (input_tokens, output_tokens) = self.cost_tracker.total_tokens();
+        spans::record_tokens(
+            &span,
+            Some(input_tokens.saturating_sub(input_before)),
+            Some(output_tokens.saturating_sub(output_before)),
+        );
+        if let Err(e) = &result {
+            spans::record_error(&span, e);
+        } else if let Some(e) = &self.last_error {
+            spans::record_error(&span, e);
+        }
+        result
+    }
+
+    async fn stream_agent_response(
+        &mut self,
+        interactive: bool,
+        cancel_token: CancellationToken,
+    ) -> Result<()> {
+        self.last_error = None;
+        self.file_history
+            .start_turn(fork::turn_count(self.messages.messages()));
+        let mut turn_timer = TurnTimer::start(Instant::now());
         let is_json_mode = self.output_format == "json";
-        let is_stream_json_mode = self.output_format == "stream-json";
+        // Both streaming formats report errors and extension notifications the same way
+        let is_stream_json_mode = matches!(self.output_format.as_str(), "stream-json" | "jsonl");
 
         let session_config = SessionConfig {
             id: self.session_id.clone(),
@@ -942,6 +1896,7 @@ impl CliSession {
             .messages
             .last()
             .ok_or_else(|| anyhow::anyhow!("No user message"))?;
+        self.renderer.render_user_message(user_message);
 
         let cancel_token_interrupt = cancel_token.clone();
         let handle = tokio::spawn(async move {
@@ -958,46 +1913,210 @@ impl CliSession {
                 session_config.clone(),
                 Some(cancel_token.clone()),
             )
-            .await?;
+            .await?
+            .peekable();
 
         let mut progress_bars = output::McpSpinners::new();
         let cancel_token_clone = cancel_token.clone();
-        let mut markdown_buffer = streaming_buffer::MarkdownBuffer::new();
+        let mut retries = ProviderRetry::from_config();
+
+        // Ctrl-S / Ctrl-Q pause and resume the rendered output while the agent keeps working.
+        // Listening consumes type-ahead, so it is only on with GOOSE_CLI_PAUSE_KEYS=true.
+        let (key_sender, mut key_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let paused = Arc::new(AtomicBool::new(false));
+        let listen_for_keys = interactive
+            && self.output_format == "text"
+            && Config::global()
+                .get_param::<bool>("GOOSE_CLI_PAUSE_KEYS")
+                .unwrap_or(false);
+        let start_key_listener = || {
+            listen_for_keys
+                .then(|| StreamKeyListener::start(key_sender.clone(), paused.clone()))
+                .flatten()
+        };
+        let mut key_listener = start_key_listener();
+        let mut countdown_tick = tokio::time::interval(Duration::from_secs(1));
+        let mut rate_limit_waits = goose::providers::rate_budget::subscribe();
+        let show_rate_limit_waits = interactive
+            && self.output_format == "text"
+            && output::McpSpinners::supports_subagent_panel();
 
         use futures::StreamExt;
         loop {
             tokio::select! {
+                Ok(()) = rate_limit_waits.changed(), if show_rate_limit_waits => {
+                    let waiting = rate_limit_waits.borrow_and_update().clone();
+                    progress_bars.subagent_rate_limit_waits(&waiting, &self.session_id);
+                }
+                _ = countdown_tick.tick(), if interactive && output::tool_timeouts_running() => {
+                    if !paused.load(Ordering::Relaxed) {
+                        output::render_tool_timeout_countdown();
+                    }
+                }
+                Some(key) = key_receiver.recv() => {
+                    match key {
+                        StreamKey::Pause => {
+                            paused.store(true, Ordering::Relaxed);
+                            output::hide_thinking();
+                            self.renderer.pause();
+                        }
+                        StreamKey::Resume => {
+                            paused.store(false, Ordering::Relaxed);
+                            self.renderer.resume();
+                        }
+                        StreamKey::Scrollback => {
+                            // the pager needs the terminal to itself
+                            drop(key_listener.take());
+                            if let Err(e) = stream_keys::page(&self.scrollback_markdown()) {
+                                warn!("Failed to open pager: {}", e);
+                            }
+                            key_listener = start_key_listener();
+                        }
+                        StreamKey::Interrupt => {
+                            if paused.swap(false, Ordering::Relaxed) {
+                                self.renderer.resume();
+                            }
+                            cancel_token_clone.cancel();
+                        }
+                    }
+                }
                 result = stream.next() => {
                     match result {
-                        Some(Ok(AgentEvent::Message(message))) => {
-                            if let Some((id, security_prompt)) = find_tool_confirmation(&message) {
-                                let permission = prompt_tool_confirmation(&security_prompt)?;
+                        Some(Ok(AgentEvent::Message(mut message))) => {
+                            if let Some(first) = find_tool_confirmation(&message) {
+                                // parallel tool calls are asked about back to back, so collect
+                                // them to be approved together
+                                let mut batch = vec![first];
+                                loop {
+                                    let next_is_confirmation = matches!(
+                                        tokio::time::timeout(CONFIRMATION_BATCH_WINDOW, Pin::new(&mut stream).peek()).await,
+                                        Ok(Some(Ok(AgentEvent::Message(next)))) if find_tool_confirmation(next).is_some()
+                                    );
+                                    if !next_is_confirmation {
+                                        break;
+                                    }
+                                    if let Some(Ok(AgentEvent::Message(next))) = stream.next().await {
+                                        batch.extend(find_tool_confirmation(&next));
+                                    }
+                                }
+
+                                let confirmations = match self.approval_policy {
+                                    Some(policy) => {
+                                        let approved = policy == ApprovalPolicy::Approve;
+                                        if !approved {
+                                            let tools: Vec<&str> = batch.iter().map(|p| p.tool_name.as_str()).collect();
+                                            self.fail_non_interactive(
+                                                SessionError::PermissionDenied(format!("{} needs approval", tools.join(", "))),
+                                                is_stream_json_mode,
+                                            );
+                                        }
+                                        let permission = if approved { Permission::AllowOnce } else { Permission::Cancel };
+                                        Ok(batch.iter().map(|_| PermissionConfirmation {
+                                            principal_type: PrincipalType::Tool,
+                                            permission: permission.clone(),
+                                        }).collect())
+                                    }
+                                    None => {
+                                        // "always allow" decisions are kept for the directory
+                                        // the inspectors look them up by
+                                        let working_dir = match self.get_session().await {
+                                            Ok(session) => session.working_dir,
+                                            Err(_) => std::env::current_dir()?,
+                                        };
+                                        paused.store(false, Ordering::Relaxed);
+                                        self.renderer.resume();
+                                        self.renderer.release_terminal();
+                                        drop(key_listener.take());
+                                        notify::notify_user(&match batch.as_slice() {
+                                            [single] => format!("{} needs your approval", single.tool_name),
+                                            calls => format!("{} tool calls need your approval", calls.len()),
+                                        });
+                                        let confirmations = match batch.as_slice() {
+                                            [single] => {
+                                                preview_shell_call(single);
+                                                prompt_tool_confirmation(&single.tool_name, &single.security_prompt, &working_dir)
+                                                    .map(|confirmation| vec![confirmation])
+                                            }
+                                            calls => prompt_batch_confirmation(calls, &working_dir),
+                                        };
+                                        self.renderer.reclaim_terminal();
+                                        key_listener = start_key_listener();
+                                        confirmations
+                                    }
+                                };
+                                let confirmations = confirmations?;
 
-                                if permission == Permission::Cancel {
-                                    output::render_text("Tool call cancelled. Returning to chat...", Some(Color::Yellow), true);
+                                if confirmations.iter().any(|c| c.permission == Permission::Cancel) {
+                                    if self.last_error.is_none() {
+                                        self.renderer.render_text("Tool call cancelled. Returning to chat...", Some(Color::Yellow));
+                                    }
                                     let mut response_message = Message::user();
-                                    response_message.content.push(MessageContent::tool_response(
-                                        id,
-                                        Err(ErrorData {
-                                            code: ErrorCode::INVALID_REQUEST,
-                                            message: std::borrow::Cow::from("Tool call cancelled by user"),
-                                            data: None,
-                                        }),
-                                    ));
+                                    for pending in batch {
+                                        response_message.content.push(MessageContent::tool_response(
+                                            pending.id,
+                                            Err(ErrorData {
+                                                code: ErrorCode::INVALID_REQUEST,
+                                                message: std::borrow::Cow::from("Tool call cancelled by user"),
+                                                data: None,
+                                            }),
+                                        ));
+                                    }
                                     self.messages.push(response_message);
                                     cancel_token_clone.cancel();
                                     drop(stream);
                                     break;
                                 }
-                                self.agent.handle_confirmation(id, PermissionConfirmation {
-                                    principal_type: PrincipalType::Tool,
-                                    permission,
-                                }).await;
+                                for (pending, confirmation) in batch.into_iter().zip(confirmations) {
+                                    output::restart_tool_clock(&pending.id);
+                                    self.agent.handle_confirmation(pending.id, confirmation).await;
+                                }
+                            } else if let Some((sampling_id, preview)) = find_sampling_request(&message) {
+                                // only interactive sessions are asked; a declined request only
+                                // fails the extension's call, not the turn
+                                output::hide_thinking();
+                                let _ = progress_bars.hide();
+                                paused.store(false, Ordering::Relaxed);
+                                self.renderer.resume();
+                                self.renderer.release_terminal();
+                                drop(key_listener.take());
+                                notify::notify_user(&tr("sampling-notification"));
+                                output::render_sampling_request(&preview);
+                                let approved = prompt_sampling_approval();
+                                self.renderer.reclaim_terminal();
+                                key_listener = start_key_listener();
+                                let approved = approved?;
+                                if !approved {
+                                    self.renderer.render_text(&tr("sampling-declined"), Some(Color::Yellow));
+                                }
+                                let response_message = Message::user()
+                                    .with_content(MessageContent::action_required_elicitation_response(
+                                        sampling_id,
+                                        sampling::approval_response(approved),
+                                    ))
+                                    .with_visibility(false, true);
+                                self.messages.push(response_message.clone());
+                                let _ = self.agent.reply(response_message, session_config.clone(), Some(cancel_token.clone())).await?;
+                            } else if let Some((_, elicitation_message, _)) = find_elicitation_request(&message).filter(|_| self.approval_policy.is_some()) {
+                                self.fail_non_interactive(
+                                    SessionError::PermissionDenied(format!("an extension asked for input: {}", elicitation_message)),
+                                    is_stream_json_mode,
+                                );
+                                cancel_token_clone.cancel();
+                                drop(stream);
+                                break;
                             } else if let Some((elicitation_id, elicitation_message, schema)) = find_elicitation_request(&message) {
                                 output::hide_thinking();
                                 let _ = progress_bars.hide();
-
-                                match elicitation::collect_elicitation_input(&elicitation_message, &schema) {
+                                paused.store(false, Ordering::Relaxed);
+                                self.renderer.resume();
+                                self.renderer.release_terminal();
+                                drop(key_listener.take());
+                                notify::notify_user(&tr("elicitation-notification"));
+                                let input = elicitation::collect_elicitation_input(&elicitation_message, &schema);
+                                self.renderer.reclaim_terminal();
+                                key_listener = start_key_listener();
+
+                                match input {
                                     Ok(Some(user_data)) => {
                                         let user_data_value = serde_json::to_value(user_data)
                                             .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
@@ -1013,31 +2132,58 @@ impl CliSession {
                                         let _ = self.agent.reply(response_message, session_config.clone(), Some(cancel_token.clone())).await?;
                                     }
                                     Ok(None) => {
-                                        output::render_text("Information request cancelled.", Some(Color::Yellow), true);
+                                        self.renderer.render_text(&tr("elicitation-cancelled"), Some(Color::Yellow));
                                         cancel_token_clone.cancel();
                                         drop(stream);
                                         break;
                                     }
                                     Err(e) => {
-                                        output::render_error(&format!("Failed to collect input: {}", e));
+                                        self.renderer.render_error(&format!("Failed to collect input: {}", e));
                                         cancel_token_clone.cancel();
                                         drop(stream);
                                         break;
                                     }
                                 }
                             } else {
+                                if self.approval_policy.is_some() && non_interactive::ran_out_of_turns(&message) {
+                                    self.fail_non_interactive(
+                                        SessionError::BudgetExceeded(format!(
+                                            "stopped after {} turns",
+                                            self.max_turns.map_or("the maximum number of".to_string(), |t| t.to_string())
+                                        )),
+                                        is_stream_json_mode,
+                                    );
+                                }
+                                let plan_changed = self
+                                    .plan_progress
+                                    .as_mut()
+                                    .is_some_and(|plan| plan.record(&mut message));
                                 log_tool_metrics(&message, &self.messages);
+                                turn_timer.record(&message, Instant::now());
                                 self.messages.push(message.clone());
+                                for content in &message.content {
+                                    match content {
+                                        MessageContent::ToolRequest(request) => self.file_history.record_request(request),
+                                        MessageContent::ToolResponse(response) => self.file_history.record_response(&response.id),
+                                        _ => {}
+                                    }
+                                }
 
                                 if interactive { output::hide_thinking() };
+                                // subagents report back through their delegating tool's response
+                                if message.content.iter().any(|c| matches!(c, MessageContent::ToolResponse(_))) {
+                                    progress_bars.finish_subagents();
+                                }
                                 let _ = progress_bars.hide();
 
-                                if is_stream_json_mode {
-                                    emit_stream_event(&StreamEvent::Message { message: message.clone() });
-                                } else if !is_json_mode {
-                                    output::render_message_streaming(&message, &mut markdown_buffer, self.debug);
+                                self.renderer.render_message(&message);
+                                if let Some(plan) = self.plan_progress.as_ref().filter(|_| plan_changed) {
+                                    if !is_json_mode && !is_stream_json_mode {
+                                        output::render_plan_checklist(plan);
+                                    }
                                 }
                             }
+                            self.draw_status_line(&progress_bars);
                         }
                         Some(Ok(AgentEvent::McpNotification((extension_id, notification)))) => {
                             handle_mcp_notification(
@@ -1049,25 +2195,46 @@ impl CliSession {
                                 is_json_mode,
                                 self.debug,
                             );
+                            self.draw_status_line(&progress_bars);
                         }
                         Some(Ok(AgentEvent::HistoryReplaced(updated_conversation))) => {
+                            if !is_json_mode && !is_stream_json_mode && output::get_verbosity().shows_notifications() {
+                                if let Some(summary) = CompactionSummary::between(&self.messages, &updated_conversation).await {
+                                    output::render_compaction_summary(&summary);
+                                }
+                            }
                             self.messages = updated_conversation;
                         }
                         Some(Ok(AgentEvent::ModelChange { model, mode })) => {
-                            if is_stream_json_mode {
-                                emit_stream_event(&StreamEvent::ModelChange { model: model.clone(), mode: mode.clone() });
-                            } else if self.debug {
-                                eprintln!("Model changed to {} in {} mode", model, mode);
-                            }
+                            self.renderer.render_model_change(&model, &mode);
+                            self.active_model = Some(model);
+                            self.draw_status_line(&progress_bars);
                         }
                         Some(Err(e)) => {
-                            handle_agent_error(&e, is_stream_json_mode);
-                            cancel_token_clone.cancel();
                             drop(stream);
+                            match self.retry_after_error(&e, &mut retries, interactive, &cancel_token_clone).await? {
+                                RetryOutcome::Resend(message) => {
+                                    stream = self
+                                        .agent
+                                        .reply(message, session_config.clone(), Some(cancel_token.clone()))
+                                        .await?
+                                        .peekable();
+                                    continue;
+                                }
+                                RetryOutcome::Cancelled => {
+                                    if let Err(e) = self.handle_interrupted_messages(true).await {
+                                        eprintln!("Error handling interruption: {}", e);
+                                    }
+                                    break;
+                                }
+                                RetryOutcome::GiveUp => {}
+                            }
+                            self.last_error = Some(handle_agent_error(&e, is_stream_json_mode));
+                            cancel_token_clone.cancel();
                             if let Err(e) = self.handle_interrupted_messages(false).await {
                                 eprintln!("Error handling interruption: {}", e);
                             } else if !is_stream_json_mode {
-                                output::render_error(
+                                self.renderer.render_error(
                                     "The error above was an exception we were not able to handle.\n\
                                     These errors are often related to connection or authentication\n\
                                     We've removed the conversation up to the most recent user message\n\
@@ -1081,6 +2248,9 @@ impl CliSession {
                 }
                 _ = cancel_token_clone.cancelled() => {
                     drop(stream);
+                    if let Err(e) = self.keep_partial_response().await {
+                        eprintln!("Error saving the interrupted response: {}", e);
+                    }
                     if let Err(e) = self.handle_interrupted_messages(true).await {
                         eprintln!("Error handling interruption: {}", e);
                     }
@@ -1089,46 +2259,504 @@ impl CliSession {
             }
         }
 
-        if !is_json_mode && !is_stream_json_mode {
-            output::flush_markdown_buffer_current_theme(&mut markdown_buffer);
-        }
+        drop(key_listener);
+        progress_bars.finish_subagents();
+        let turn_diffs = self.file_history.turn_diffs();
+        if !turn_diffs.is_empty() {
+            self.renderer
+                .render_diffstat(&file_history::FileDiff::stat(&turn_diffs));
+        }
+        if interactive {
+            notify::notify_user("goose is waiting for your input");
+        }
+
+        let session = self
+            .agent
+            .config
+            .session_manager
+            .get_session(&self.session_id, false)
+            .await
+            .ok();
+        if let Some(session) = &session {
+            let (_, output_tokens_before) = self.cost_tracker.total_tokens();
+            if let Err(e) = self.record_turn_cost(session).await {
+                warn!("Failed to record token usage: {}", e);
+            }
+            if output::turn_stats_enabled() {
+                let (_, output_tokens) = self.cost_tracker.total_tokens();
+                self.renderer.render_turn_stats(&turn_timer.finish(
+                    Instant::now(),
+                    output_tokens.saturating_sub(output_tokens_before),
+                ));
+            }
+            if let Ok(provider) = self.agent.provider().await {
+                self.renderer.render_usage(&TurnUsage {
+                    cost: &self.cost_tracker,
+                    context_tokens: session.total_tokens.unwrap_or(0),
+                    context_limit: provider.get_model_config().context_limit(),
+                });
+            }
+        }
+        let total_tokens = session.and_then(|s| s.total_tokens);
+
+        if is_json_mode {
+            let json_output = JsonOutput {
+                messages: self.messages.messages().to_vec(),
+                metadata: JsonMetadata {
+                    total_tokens,
+                    status: "completed".to_string(),
+                },
+            };
+            println!("{}", serde_json::to_string_pretty(&json_output)?);
+        }
+        self.renderer.finish(total_tokens);
+
+        Ok(())
+    }
+
+    /// Records why a non-interactive run can't go on; it becomes the exit status.
+    fn fail_non_interactive(&mut self, error: SessionError, is_stream_json_mode: bool) {
+        if is_stream_json_mode {
+            emit_stream_event(&StreamEvent::Error {
+                error: error.to_string(),
+            });
+        } else {
+            output::render_session_error(&error);
+        }
+        self.last_error = Some(error);
+    }
+
+    /// Copies a code block of the last assistant response to the clipboard.
+    fn copy_code_block(&self, n: Option<usize>) {
+        let Some(text) = self
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == rmcp::model::Role::Assistant && m.metadata.user_visible)
+            .map(|m| m.as_concat_text())
+        else {
+            output::render_error("There is no response to copy from yet");
+            return;
+        };
+        let Some(code) = clipboard::code_block(&text, n) else {
+            output::render_error(&match n {
+                Some(n) => format!("The last response has no code block #{}", n),
+                None => "The last response has no code blocks".to_string(),
+            });
+            return;
+        };
+        match clipboard::copy(code) {
+            Ok(method) => output::render_copied(code.lines().count(), method),
+            Err(e) => output::render_error(&format!("Failed to copy to the clipboard: {}", e)),
+        }
+    }
+
+    /// Redraws the status line with what changes while the agent is responding.
+    fn draw_status_line(&mut self, progress_bars: &output::McpSpinners) {
+        let Some(status_line) = &mut self.status_line else {
+            return;
+        };
+        let info = status_line.info_mut();
+        info.subagents = progress_bars.active_subagents();
+        if let Some(model) = &self.active_model {
+            info.model = model.clone();
+        }
+        status_line.draw();
+    }
+
+    /// Updates the status line with the session's totals, e.g. after a turn finished.
+    async fn refresh_status_line(&mut self) {
+        if self.status_line.is_none() {
+            return;
+        }
+        let session = self.get_session().await.ok();
+        let model_config = self
+            .agent
+            .provider()
+            .await
+            .ok()
+            .map(|p| p.get_model_config());
+        let Some(status_line) = &mut self.status_line else {
+            return;
+        };
+
+        let info = status_line.info_mut();
+        info.provider = Config::global()
+            .get_goose_provider()
+            .unwrap_or_else(|_| "unknown".to_string());
+        if let Some(model_config) = &model_config {
+            info.model = self
+                .active_model
+                .clone()
+                .unwrap_or_else(|| model_config.model_name.clone());
+            info.context_limit = model_config.context_limit();
+        }
+        info.context_tokens = session.and_then(|s| s.total_tokens).unwrap_or(0).max(0) as usize;
+        info.cost = output::estimate_tracked_cost(&self.cost_tracker);
+        info.subagents = 0;
+        status_line.draw();
+    }
+
+    /// The conversation so far as markdown, for paging back while output is paused.
+    fn scrollback_markdown(&self) -> String {
+        self.messages
+            .iter()
+            .map(|message| {
+                let role = match message.role {
+                    rmcp::model::Role::User => "### User:",
+                    rmcp::model::Role::Assistant => "### Assistant:",
+                };
+                format!("{}\n{}", role, message_to_markdown(message, false))
+            })
+            .collect::<Vec<_>>()
+            .join("\n---\n\n")
+    }
+
+    /// Attribute the tokens used since the previous turn to the model that answered and store
+    /// the running totals in the session.
+    async fn record_turn_cost(&mut self, session: &Session) -> Result<()> {
+        let provider_name = Config::global()
+            .get_goose_provider()
+            .unwrap_or_else(|_| "unknown".to_string());
+        let model = match &self.active_model {
+            Some(model) => model.clone(),
+            None => self.agent.provider().await?.get_model_config().model_name,
+        };
+        self.cost_tracker.record_turn(
+            &provider_name,
+            &model,
+            session.accumulated_input_tokens.unwrap_or(0),
+            session.accumulated_output_tokens.unwrap_or(0),
+        );
+
+        let metadata = SessionMetadata {
+            model: Some(model),
+            cost: output::estimate_tracked_cost(&self.cost_tracker),
+        };
+        let cost_tracker = &self.cost_tracker;
+        self.agent
+            .config
+            .session_manager
+            .update_extension_data(&self.session_id, |extension_data| {
+                cost_tracker.to_extension_data(extension_data)?;
+                metadata.to_extension_data(extension_data)
+            })
+            .await
+    }
+
+    /// When Ctrl-C stops the model mid-response, shows what was already streamed and keeps it
+    /// in the conversation, marked as interrupted, so the next turn can pick up from there. The
+    /// agent only stores a response once it is complete, so it is saved here unless the agent
+    /// got to it first.
+    async fn keep_partial_response(&mut self) -> Result<()> {
+        self.renderer
+            .render_text(INTERRUPTED_MARKER, Some(Color::Yellow));
+
+        let Some(partial) = self.messages.last().and_then(mark_interrupted) else {
+            return Ok(());
+        };
+        self.messages.pop();
+        self.push_message(partial.clone());
+
+        let session_manager = &self.agent.config.session_manager;
+        let stored = session_manager.get_session(&self.session_id, true).await?;
+        let already_stored = partial.id.is_some()
+            && stored
+                .conversation
+                .and_then(|c| c.messages().last().map(|m| m.id.clone()))
+                .is_some_and(|id| id == partial.id);
+        if already_stored {
+            return Ok(());
+        }
+        session_manager
+            .add_message(&self.session_id, &partial)
+            .await
+    }
+
+    /// Decides what to do about an error that ended the stream. Rate limits and transient
+    /// server errors are retried after a countdown by sending the last stored user message
+    /// again; once the retries are used up an interactive session may switch provider and
+    /// carry on.
+    async fn retry_after_error(
+        &mut self,
+        error: &anyhow::Error,
+        retries: &mut ProviderRetry,
+        interactive: bool,
+        cancel_token: &CancellationToken,
+    ) -> Result<RetryOutcome> {
+        let session_manager = self.agent.config.session_manager.clone();
+        let mut stored = session_manager
+            .get_session(&self.session_id, true)
+            .await?
+            .conversation
+            .unwrap_or_default()
+            .messages()
+            .clone();
+        // only a turn that stopped waiting on the model can be sent again as it was
+        if !stored
+            .last()
+            .is_some_and(|m| m.role == rmcp::model::Role::User)
+        {
+            return Ok(RetryOutcome::GiveUp);
+        }
+
+        let show_countdown = self.output_format == "text";
+        match retries.next_delay(error) {
+            Some(delay) => {
+                warn!("Retrying after provider error: {}", error);
+                if !count_down(error, delay, cancel_token, show_countdown).await {
+                    return Ok(RetryOutcome::Cancelled);
+                }
+            }
+            None if retries.gave_up_on(error)
+                && interactive
+                && show_countdown
+                && std::io::stdin().is_terminal() =>
+            {
+                output::hide_thinking();
+                output::render_error(&error.to_string());
+                if !self.offer_provider_switch().await? {
+                    return Ok(RetryOutcome::GiveUp);
+                }
+                retries.reset();
+            }
+            None => return Ok(RetryOutcome::GiveUp),
+        }
+
+        let Some(message) = stored.pop() else {
+            return Ok(RetryOutcome::GiveUp);
+        };
+        // the agent stores the message again when it is resent
+        session_manager
+            .replace_conversation(&self.session_id, &Conversation::new_unvalidated(stored))
+            .await?;
+        Ok(RetryOutcome::Resend(message))
+    }
+
+    /// Asks whether to continue with another provider or model after retries ran out.
+    async fn offer_provider_switch(&mut self) -> Result<bool> {
+        let switch = cliclack::confirm("The provider keeps failing. Switch provider or model?")
+            .initial_value(false)
+            .interact()?;
+        if !switch {
+            return Ok(false);
+        }
+        let (current_provider, current_model) = self.current_model().await;
+        let providers = model_picker::configured_providers().await;
+        let Some((provider, model)) =
+            model_picker::pick(&providers, &current_provider, &current_model)?
+        else {
+            return Ok(false);
+        };
+        self.switch_model(&provider, &model).await?;
+        Ok(true)
+    }
+
+    /// Provider and model answering the next turn.
+    async fn current_model(&self) -> (String, String) {
+        match self.agent.provider().await {
+            Ok(provider) => (
+                provider.get_name().to_string(),
+                provider.get_model_config().model_name,
+            ),
+            Err(_) => Default::default(),
+        }
+    }
+
+    /// `/model`: switches to the given model, or one picked from the configured providers.
+    async fn handle_model(&mut self, target: Option<String>) -> Result<()> {
+        let (current_provider, current_model) = self.current_model().await;
+        let providers = model_picker::configured_providers().await;
+        let choice = match target {
+            Some(target) => {
+                let names: Vec<&str> = providers.iter().map(|p| p.name.as_str()).collect();
+                Some(model_picker::parse_target(
+                    &target,
+                    &names,
+                    &current_provider,
+                ))
+            }
+            None => {
+                if let Some((costs, input_tokens, output_tokens)) =
+                    self.turn_costs(&providers).await
+                {
+                    output::render_turn_cost_comparison(&costs, input_tokens, output_tokens);
+                }
+                model_picker::pick(&providers, &current_provider, &current_model)?
+            }
+        };
+        match choice {
+            Some((provider, model)) if provider == current_provider && model == current_model => {
+                output::render_text(
+                    &format!("Already using {} on {}", model, provider),
+                    None,
+                    true,
+                );
+            }
+            Some((provider, model)) => self.switch_model(&provider, &model).await?,
+            None => output::render_error("No configured providers; run `goose configure` first"),
+        }
+        Ok(())
+    }
+
+    /// `/retry`: hides the last answer from the model, keeping it in the transcript, and asks
+    /// for a new one. Other settings given apply to the new answer only.
+    async fn handle_regenerate(&mut self, options: RegenerateOptions) -> Result<()> {
+        let Some(start) = fork::last_answered_turn(self.messages.messages()) else {
+            output::render_error("There is no answer to retry yet");
+            return Ok(());
+        };
+        let original_provider = self.agent.provider().await?;
+        let provider = self.regenerate_provider(&options).await?;
+
+        let messages = self.messages.messages();
+        let mut prompt = Message::user();
+        prompt.content = messages[start].content.clone();
+        let mut conversation = Conversation::new_unvalidated(messages.iter().take(start).cloned());
+        conversation.extend(messages.iter().skip(start).cloned().map(|message| {
+            let user_visible = message.metadata.user_visible;
+            message.with_visibility(user_visible, false)
+        }));
+        let settings = options.describe();
+        conversation.push(fork::banner(match &settings {
+            Some(settings) => format!("↻ Answer replaced, regenerated with {}", settings),
+            None => "↻ Answer replaced".to_string(),
+        }));
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &conversation)
+            .await?;
+        self.messages = conversation;
+        output::render_answer_replaced(settings.as_deref());
+
+        if let Some(provider) = &provider {
+            self.agent
+                .update_provider(provider.clone(), &self.session_id)
+                .await?;
+        }
+        self.push_message(prompt);
+        output::show_thinking();
+        let result = self
+            .process_agent_response(true, CancellationToken::default())
+            .await;
+        output::hide_thinking();
+        if provider.is_some() {
+            self.agent
+                .update_provider(original_provider, &self.session_id)
+                .await?;
+        }
+        result
+    }
+
+    /// The provider to regenerate an answer with, when `/retry` asks for another model or
+    /// temperature than the session's.
+    async fn regenerate_provider(
+        &self,
+        options: &RegenerateOptions,
+    ) -> Result<Option<Arc<dyn Provider>>> {
+        if options.model.is_none() && options.temperature.is_none() {
+            return Ok(None);
+        }
+        let current = self.agent.provider().await?;
+        let mut provider_name = current.get_name().to_string();
+        let mut model_config = current.get_model_config();
+        if let Some(target) = &options.model {
+            let providers = model_picker::configured_providers().await;
+            let names: Vec<&str> = providers.iter().map(|p| p.name.as_str()).collect();
+            let (provider, model) = model_picker::parse_target(target, &names, &provider_name);
+            model_config = goose::model::ModelConfig::new(&model)?.with_canonical_limits(&provider);
+            provider_name = provider;
+        }
+        if let Some(temperature) = options.temperature {
+            model_config = model_config.with_temperature(Some(temperature));
+        }
+        let extensions = self.agent.get_extension_configs().await;
+        Ok(Some(
+            goose::providers::create(&provider_name, model_config, extensions).await?,
+        ))
+    }
+
+    /// What the next turn would cost on the current model and on the models of the configured
+    /// providers, with the context and output sizes that assumes. `None` before anything has
+    /// been sent.
+    async fn turn_costs(
+        &self,
+        providers: &[goose::providers::base::ProviderMetadata],
+    ) -> Option<(Vec<TurnCost>, usize, u64)> {
+        let context_tokens = self.get_session().await.ok()?.total_tokens?.max(0) as usize;
+        if context_tokens == 0 {
+            return None;
+        }
+        let output_tokens = self.cost_tracker.average_output_tokens();
+        let (current_provider, current_model) = self.current_model().await;
+        let mut candidates = vec![(current_provider.clone(), current_model.clone())];
+        for metadata in providers {
+            candidates.extend(
+                metadata
+                    .known_models
+                    .iter()
+                    .map(|info| (metadata.name.clone(), info.name.clone())),
+            );
+        }
+        let costs = cost::compare_turn_costs(
+            &candidates,
+            (&current_provider, &current_model),
+            |provider, model| {
+                output::estimate_cost_usd(provider, model, context_tokens, output_tokens as usize)
+            },
+            cost::MAX_COMPARED_MODELS,
+        );
+        Some((costs, context_tokens, output_tokens))
+    }
+
+    /// Shows the generation parameters, or changes one for the following turns. The change is
+    /// kept with the session's model config, so resuming the session keeps it too.
+    async fn handle_params(&mut self, change: Option<params::ParamChange>) -> Result<()> {
+        let current = self.agent.provider().await?;
+        let Some(change) = change else {
+            output::render_params(&current.get_model_config(), false);
+            return Ok(());
+        };
+        let model_config = params::apply(current.get_model_config(), change)?;
+        let extensions = self.agent.get_extension_configs().await;
+        let provider =
+            goose::providers::create(current.get_name(), model_config.clone(), extensions).await?;
+        self.agent
+            .update_provider(provider, &self.session_id)
+            .await?;
+        self.active_model = Some(model_config.model_name.clone());
+        output::render_params(&model_config, true);
+        Ok(())
+    }
 
-        if is_json_mode {
-            let metadata = match self
-                .agent
-                .config
-                .session_manager
-                .get_session(&self.session_id, false)
-                .await
-            {
-                Ok(session) => JsonMetadata {
-                    total_tokens: session.total_tokens,
-                    status: "completed".to_string(),
-                },
-                Err(_) => JsonMetadata {
-                    total_tokens: None,
-                    status: "completed".to_string(),
-                },
-            };
-            let json_output = JsonOutput {
-                messages: self.messages.messages().to_vec(),
-                metadata,
-            };
-            println!("{}", serde_json::to_string_pretty(&json_output)?);
-        } else if is_stream_json_mode {
-            let total_tokens = self
-                .agent
-                .config
-                .session_manager
-                .get_session(&self.session_id, false)
-                .await
-                .ok()
-                .and_then(|s| s.total_tokens);
-            emit_stream_event(&StreamEvent::Complete { total_tokens });
-        } else {
-            println!();
-        }
+    /// Swaps the provider for the following turns and notes the switch in the transcript.
+    async fn switch_model(&mut self, provider_name: &str, model: &str) -> Result<()> {
+        let extensions = self.agent.get_extension_configs().await;
+        let provider =
+            goose::providers::create_with_named_model(provider_name, model, extensions).await?;
+        self.agent
+            .update_provider(provider.clone(), &self.session_id)
+            .await?;
+        self.active_model = Some(model.to_string());
+
+        // kept in the transcript but never sent to the model
+        let note = Message::assistant()
+            .with_text(format!("Switched to {} on {}", model, provider_name))
+            .user_only();
+        self.agent
+            .config
+            .session_manager
+            .add_message(&self.session_id, &note)
+            .await?;
+        self.push_message(note);
 
+        output::display_model_switch(
+            provider_name,
+            model,
+            &Some(self.session_id.clone()),
+            Some(&provider),
+        );
         Ok(())
     }
 
@@ -1211,11 +2839,22 @@ impl CliSession {
     pub async fn update_completion_cache(&mut self) -> Result<()> {
         // Get fresh data
         let prompts = self.agent.list_extension_prompts(&self.session_id).await;
+        let mut extensions = self.agent.list_extensions().await;
+        extensions.sort();
+        let mut builtins: Vec<String> = goose_mcp::BUILTIN_EXTENSIONS
+            .keys()
+            .chain(PLATFORM_EXTENSIONS.keys())
+            .map(|name| name.to_string())
+            .collect();
+        builtins.sort();
+        builtins.dedup();
 
         // Update the cache with write lock
         let mut cache = self.completion_cache.write().unwrap();
         cache.prompts.clear();
         cache.prompt_info.clear();
+        cache.extensions = extensions;
+        cache.builtins = builtins;
 
         for (extension, prompt_list) in prompts {
             let names: Vec<String> = prompt_list.iter().map(|p| p.name.clone()).collect();
@@ -1325,6 +2964,35 @@ impl CliSession {
         Ok(())
     }
 
+    /// Estimates what the next turn sends to the model, by category, for `/context`
+    async fn display_context_breakdown(&self) -> Result<()> {
+        let provider = self.agent.provider().await?;
+        let context_limit = provider.get_model_config().context_limit();
+        let session = self.get_session().await?;
+        let (tools, _, system_prompt) = self
+            .agent
+            .prepare_tools_and_prompt(&self.session_id, &session.working_dir)
+            .await?;
+        let counter = create_token_counter()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to count tokens: {}", e))?;
+        let pinned = PinnedState::from_extension_data(&session.extension_data).unwrap_or_default();
+
+        let breakdown = ContextBreakdown::measure(
+            |text| counter.count_tokens(text),
+            &system_prompt,
+            counter.count_tokens_for_tools(&tools),
+            self.messages.messages(),
+            &pinned,
+        );
+        output::render_context_breakdown(
+            &breakdown,
+            context_limit,
+            session.total_tokens.map(|tokens| tokens as usize),
+        );
+        Ok(())
+    }
+
     /// Handle prompt command execution
     async fn handle_prompt_command(&mut self, opts: input::PromptCommandOptions) -> Result<()> {
         // name is required
@@ -1339,63 +3007,150 @@ impl CliSession {
                 None => output::render_error(&format!("Prompt '{}' not found", opts.name)),
             }
         } else {
-            // Convert the arguments HashMap to a Value
-            let arguments = serde_json::to_value(opts.arguments)
-                .map_err(|e| anyhow::anyhow!("Failed to serialize arguments: {}", e))?;
-
-            match self.get_prompt(&opts.name, arguments).await {
-                Ok(messages) => {
-                    let start_len = self.messages.len();
-                    let mut valid = true;
-                    let num_messages = messages.len();
-                    for (i, prompt_message) in messages.into_iter().enumerate() {
-                        let msg = Message::from(prompt_message);
-                        // ensure we get a User - Assistant - User type pattern
-                        let expected_role = if i % 2 == 0 {
-                            rmcp::model::Role::User
-                        } else {
-                            rmcp::model::Role::Assistant
-                        };
-
-                        if msg.role != expected_role {
-                            output::render_error(&format!(
-                                "Expected {:?} message at position {}, but found {:?}",
-                                expected_role, i, msg.role
-                            ));
-                            valid = false;
-                            // get rid of everything we added to messages
-                            self.messages.truncate(start_len);
-                            break;
-                        }
+            match self.prompt_messages(&opts.name, opts.arguments).await {
+                Ok(messages) => self.start_prompt(messages, true).await?,
+                Err(e) => output::render_error(&e.to_string()),
+            }
+        }
 
-                        if msg.role == rmcp::model::Role::User {
-                            output::render_message(&msg, self.debug);
-                        }
-                        self.push_message(msg);
-                    }
+        Ok(())
+    }
 
-                    if valid {
-                        if num_messages > 1 {
-                            for i in 0..(num_messages - 1) {
-                                let msg = &self.messages.messages()[start_len + i];
-                                self.agent
-                                    .config
-                                    .session_manager
-                                    .add_message(&self.session_id, msg)
-                                    .await?;
-                            }
-                        }
+    /// `/prompts` in a terminal: picks a prompt from the extensions' prompts, asks for its
+    /// arguments and runs it.
+    async fn handle_prompt_picker(&mut self, extension: Option<String>) -> Result<()> {
+        let prompts = self.agent.list_extension_prompts(&self.session_id).await;
+        if let Some(filter) = &extension {
+            if !prompts.contains_key(filter) {
+                return Err(anyhow::anyhow!("Extension '{}' not found", filter));
+            }
+        }
+        let Some((extension, prompt)) = prompt_picker::pick(prompts, extension.as_deref())? else {
+            output::render_text("No extension offers prompts", None, true);
+            return Ok(());
+        };
+        output::render_prompt_info(&output::PromptInfo {
+            name: prompt.name.clone(),
+            description: prompt.description.clone(),
+            arguments: prompt.arguments.clone(),
+            extension: Some(extension),
+        });
+        let arguments = prompt_args::collect(
+            prompt.arguments.as_deref().unwrap_or_default(),
+            HashMap::new(),
+        )?;
+        let messages = self.prompt_messages(&prompt.name, arguments).await?;
+        self.start_prompt(messages, true).await
+    }
 
-                        output::show_thinking();
-                        self.process_agent_response(true, CancellationToken::default())
-                            .await?;
-                        output::hide_thinking();
-                    }
-                }
-                Err(e) => output::render_error(&e.to_string()),
+    /// Run an extension prompt and exit, for `goose run --prompt`
+    pub async fn headless_prompt(
+        &mut self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<()> {
+        let messages = self.prompt_messages(name, arguments).await?;
+        self.start_prompt(messages, false).await?;
+        match self.last_error.take() {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Fill in the arguments of a prompt. When required ones are missing they are asked for
+    /// if there is someone to ask, otherwise this fails naming them.
+    async fn resolve_prompt_arguments(
+        &mut self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<HashMap<String, String>> {
+        let Some(declared) = self
+            .get_prompt_info(name)
+            .await?
+            .and_then(|info| info.arguments)
+        else {
+            return Ok(arguments);
+        };
+
+        let missing = prompt_args::missing_required(&declared, &arguments);
+        if missing.is_empty() {
+            return Ok(arguments);
+        }
+        if self.approval_policy.is_some() || !std::io::stdin().is_terminal() {
+            return Err(anyhow::anyhow!(
+                "Prompt '{}' is missing required arguments: {}",
+                name,
+                missing.join(", ")
+            ));
+        }
+        prompt_args::collect(&declared, arguments)
+    }
+
+    /// Fetch a prompt's messages, checking they alternate User - Assistant - User
+    async fn prompt_messages(
+        &mut self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<Vec<Message>> {
+        let arguments = self.resolve_prompt_arguments(name, arguments).await?;
+        // Convert the arguments HashMap to a Value
+        let arguments = serde_json::to_value(arguments)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize arguments: {}", e))?;
+
+        let messages: Vec<Message> = self
+            .get_prompt(name, arguments)
+            .await?
+            .into_iter()
+            .map(Message::from)
+            .collect();
+        for (i, msg) in messages.iter().enumerate() {
+            let expected_role = if i % 2 == 0 {
+                rmcp::model::Role::User
+            } else {
+                rmcp::model::Role::Assistant
+            };
+            if msg.role != expected_role {
+                return Err(anyhow::anyhow!(
+                    "Expected {:?} message at position {}, but found {:?}",
+                    expected_role,
+                    i,
+                    msg.role
+                ));
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Add a prompt's messages to the conversation and get the response to them
+    async fn start_prompt(&mut self, messages: Vec<Message>, interactive: bool) -> Result<()> {
+        let start_len = self.messages.len();
+        let num_messages = messages.len();
+        for msg in messages {
+            if interactive && msg.role == rmcp::model::Role::User {
+                output::render_message(&msg, self.debug);
+            }
+            self.push_message(msg);
+        }
+
+        if num_messages > 1 {
+            for i in 0..(num_messages - 1) {
+                let msg = &self.messages.messages()[start_len + i];
+                self.agent
+                    .config
+                    .session_manager
+                    .add_message(&self.session_id, msg)
+                    .await?;
             }
         }
 
+        if interactive {
+            output::show_thinking();
+        }
+        self.process_agent_response(interactive, CancellationToken::default())
+            .await?;
+        if interactive {
+            output::hide_thinking();
+        }
         Ok(())
     }
 
@@ -1453,49 +3208,85 @@ fn emit_stream_event(event: &StreamEvent) {
     }
 }
 
-/// Prompt user for tool call confirmation, returns the Permission selected
-fn prompt_tool_confirmation(security_prompt: &Option<String>) -> Result<Permission> {
+/// Prompt user for tool call confirmation, returns the permission selected and whether an
+/// "always" decision applies to the tool or to its whole extension
+fn prompt_tool_confirmation(
+    tool_name: &str,
+    security_prompt: &Option<String>,
+    working_dir: &Path,
+) -> Result<PermissionConfirmation> {
     output::hide_thinking();
 
+    let choice = |permission, principal_type| PermissionConfirmation {
+        principal_type,
+        permission,
+    };
+
+    if let Some(alert) = security_prompt
+        .as_deref()
+        .filter(|prompt| is_stale_file_alert(prompt))
+    {
+        return prompt_stale_file_confirmation(alert);
+    }
+    if let Some(alert) = security_prompt
+        .as_deref()
+        .filter(|prompt| is_network_alert(prompt))
+    {
+        return prompt_network_confirmation(alert, working_dir);
+    }
+
     let prompt = if let Some(security_message) = security_prompt {
         println!("\n{}", security_message);
-        "Do you allow this tool call?".to_string()
+        tr("tool-confirm-security-prompt")
     } else {
-        "Goose would like to call the above tool, do you allow?".to_string()
+        tr("tool-confirm-prompt")
     };
 
-    let permission_result = if security_prompt.is_none() {
-        cliclack::select(prompt)
-            .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
-            .item(
-                Permission::AlwaysAllow,
-                "Always Allow",
-                "Always allow the tool call",
-            )
-            .item(Permission::DenyOnce, "Deny", "Deny the tool call")
-            .item(
-                Permission::Cancel,
-                "Cancel",
-                "Cancel the AI response and tool call",
-            )
-            .interact()
-    } else {
-        cliclack::select(prompt)
-            .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
-            .item(Permission::DenyOnce, "Deny", "Deny the tool call")
-            .item(
-                Permission::Cancel,
-                "Cancel",
-                "Cancel the AI response and tool call",
-            )
-            .interact()
-    };
+    let mut select = cliclack::select(prompt).item(
+        choice(Permission::AllowOnce, PrincipalType::Tool),
+        tr("tool-allow"),
+        tr("tool-allow-hint"),
+    );
+    if security_prompt.is_none() {
+        select = select.item(
+            choice(Permission::AlwaysAllow, PrincipalType::Tool),
+            tr("tool-always-allow"),
+            tr("tool-always-allow-hint"),
+        );
+        if let Some((extension_name, _)) = tool_name.split_once("__") {
+            let args = [("extension", extension_name)];
+            select = select.item(
+                choice(Permission::AlwaysAllow, PrincipalType::Extension),
+                tr_with("extension-always-allow", &args),
+                tr_with("extension-always-allow-hint", &args),
+            );
+        }
+    }
+    select = select.item(
+        choice(Permission::DenyOnce, PrincipalType::Tool),
+        tr("tool-deny"),
+        tr("tool-deny-hint"),
+    );
+    if security_prompt.is_none() {
+        select = select.item(
+            choice(Permission::AlwaysDeny, PrincipalType::Tool),
+            tr("tool-always-deny"),
+            tr("tool-always-deny-hint"),
+        );
+    }
+    let permission_result = select
+        .item(
+            choice(Permission::Cancel, PrincipalType::Tool),
+            tr("tool-cancel"),
+            tr("tool-cancel-hint"),
+        )
+        .interact();
 
     match permission_result {
         Ok(p) => Ok(p),
         Err(e) => {
             if e.kind() == std::io::ErrorKind::Interrupted {
-                Ok(Permission::Cancel)
+                Ok(choice(Permission::Cancel, PrincipalType::Tool))
             } else {
                 Err(e.into())
             }
@@ -1503,18 +3294,249 @@ fn prompt_tool_confirmation(security_prompt: &Option<String>) -> Result<Permissi
     }
 }
 
+/// Asks about an edit to a file that changed on disk since the agent read it: apply it anyway,
+/// have the agent read the file again and redo it, or stop the response
+fn prompt_stale_file_confirmation(alert: &str) -> Result<PermissionConfirmation> {
+    output::render_stale_file_alert(alert);
+    let choice = |permission| PermissionConfirmation {
+        principal_type: PrincipalType::Tool,
+        permission,
+    };
+    let permission_result = cliclack::select(tr("tool-stale-file-prompt"))
+        .item(
+            choice(Permission::AllowOnce),
+            tr("tool-stale-file-apply"),
+            tr("tool-stale-file-apply-hint"),
+        )
+        .item(
+            choice(Permission::DenyOnce),
+            tr("tool-stale-file-reread"),
+            tr("tool-stale-file-reread-hint"),
+        )
+        .item(
+            choice(Permission::Cancel),
+            tr("tool-stale-file-abort"),
+            tr("tool-stale-file-abort-hint"),
+        )
+        .interact();
+    match permission_result {
+        Ok(p) => Ok(p),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(choice(Permission::Cancel)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Asks about a tool call that connects to hosts not allowed for the project: allow it once,
+/// allow its hosts in this project from now on, deny it, or stop the response
+fn prompt_network_confirmation(alert: &str, working_dir: &Path) -> Result<PermissionConfirmation> {
+    println!("\n{}", console::style(alert).yellow());
+    let choice = |permission| PermissionConfirmation {
+        principal_type: PrincipalType::Tool,
+        permission,
+    };
+    let permission_result = cliclack::select(tr("tool-network-prompt"))
+        .item(
+            choice(Permission::AllowOnce),
+            tr("tool-allow"),
+            tr("tool-network-allow-hint"),
+        )
+        .item(
+            choice(Permission::AlwaysAllow),
+            tr("tool-network-always-allow"),
+            tr("tool-network-always-allow-hint"),
+        )
+        .item(
+            choice(Permission::DenyOnce),
+            tr("tool-deny"),
+            tr("tool-deny-hint"),
+        )
+        .item(
+            choice(Permission::Cancel),
+            tr("tool-cancel"),
+            tr("tool-cancel-hint"),
+        )
+        .interact();
+    match permission_result {
+        // the hosts are allowed rather than the tool, which still asks for other hosts
+        Ok(p) if p.permission == Permission::AlwaysAllow => {
+            if let Err(e) = allow_hosts(working_dir, &network_alert_hosts(alert)) {
+                output::render_error(&tr_with(
+                    "tool-network-allow-failed",
+                    &[("error", &e.to_string())],
+                ));
+            }
+            Ok(choice(Permission::AllowOnce))
+        }
+        Ok(p) => Ok(p),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(choice(Permission::Cancel)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// How long to wait for further confirmation requests after the first one. The agent asks about
+/// all parallel tool calls of a turn at once, so the rest follow almost immediately.
+const CONFIRMATION_BATCH_WINDOW: Duration = Duration::from_millis(50);
+
+/// A tool call waiting for the user's approval
+struct PendingConfirmation {
+    id: String,
+    tool_name: String,
+    arguments: JsonObject,
+    security_prompt: Option<String>,
+}
+
+/// Ask about several tool calls in one go: every call is listed and the user picks the ones to
+/// allow, the rest are denied. Calls flagged by a security check are asked about one by one
+/// instead, so the finding is shown with each of them.
+fn prompt_batch_confirmation(
+    batch: &[PendingConfirmation],
+    working_dir: &Path,
+) -> Result<Vec<PermissionConfirmation>> {
+    output::hide_thinking();
+
+    if batch
+        .iter()
+        .any(|pending| pending.security_prompt.is_some())
+    {
+        let mut confirmations = Vec::with_capacity(batch.len());
+        for (idx, pending) in batch.iter().enumerate() {
+            output::render_pending_tool_call(idx + 1, batch.len(), &pending.tool_name);
+            preview_shell_call(pending);
+            let confirmation = prompt_tool_confirmation(
+                &pending.tool_name,
+                &pending.security_prompt,
+                working_dir,
+            )?;
+            let cancelled = confirmation.permission == Permission::Cancel;
+            confirmations.push(confirmation);
+            if cancelled {
+                break;
+            }
+        }
+        return Ok(confirmations);
+    }
+
+    for (idx, pending) in batch.iter().enumerate() {
+        if shell_call_preview(pending).is_some_and(|(affected, _)| !affected.is_empty()) {
+            output::render_pending_tool_call(idx + 1, batch.len(), &pending.tool_name);
+            preview_shell_call(pending);
+        }
+    }
+
+    let items: Vec<(usize, String, String)> = batch
+        .iter()
+        .enumerate()
+        .map(|(idx, pending)| {
+            (
+                idx,
+                pending.tool_name.clone(),
+                output::format_tool_args_summary(&pending.arguments),
+            )
+        })
+        .collect();
+    let selected = cliclack::multiselect(tr_with(
+        "tool-confirm-batch-prompt",
+        &[("count", &batch.len().to_string())],
+    ))
+    .items(&items)
+    .initial_values((0..batch.len()).collect())
+    .required(false)
+    .interact();
+
+    let selected = match selected {
+        Ok(selected) => selected,
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+            return Ok(vec![PermissionConfirmation {
+                principal_type: PrincipalType::Tool,
+                permission: Permission::Cancel,
+            }]);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let confirmations: Vec<PermissionConfirmation> = (0..batch.len())
+        .map(|idx| PermissionConfirmation {
+            principal_type: PrincipalType::Tool,
+            permission: if selected.contains(&idx) {
+                Permission::AllowOnce
+            } else {
+                Permission::DenyOnce
+            },
+        })
+        .collect();
+    for (pending, confirmation) in batch.iter().zip(&confirmations) {
+        output::render_tool_decision(
+            &pending.tool_name,
+            confirmation.permission == Permission::AllowOnce,
+        );
+    }
+    Ok(confirmations)
+}
+
+/// The files a shell call waiting for approval would change, and the directory it runs in.
+fn shell_call_preview(pending: &PendingConfirmation) -> Option<(Vec<AffectedPath>, PathBuf)> {
+    if pending.tool_name != "developer__shell" || !shell_preview::enabled() {
+        return None;
+    }
+    let command = pending.arguments.get("command").and_then(Value::as_str)?;
+    let cwd = pending
+        .arguments
+        .get("working_dir")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    Some((shell_preview::affected_paths(command), cwd))
+}
+
+/// Shows what a shell call would change before asking about it, so the decision isn't based on
+/// the command string alone.
+fn preview_shell_call(pending: &PendingConfirmation) {
+    if let Some((affected, cwd)) = shell_call_preview(pending) {
+        output::render_shell_preview(&affected, &cwd);
+    }
+}
+
 /// Extract tool confirmation request from a message
-fn find_tool_confirmation(message: &Message) -> Option<(String, Option<String>)> {
+fn find_tool_confirmation(message: &Message) -> Option<PendingConfirmation> {
     message.content.iter().find_map(|content| {
         if let MessageContent::ActionRequired(action) = content {
-            if let ActionRequiredData::ToolConfirmation { id, prompt, .. } = &action.data {
-                return Some((id.clone(), prompt.clone()));
+            if let ActionRequiredData::ToolConfirmation {
+                id,
+                tool_name,
+                arguments,
+                prompt,
+            } = &action.data
+            {
+                return Some(PendingConfirmation {
+                    id: id.clone(),
+                    tool_name: tool_name.clone(),
+                    arguments: arguments.clone(),
+                    security_prompt: prompt.clone(),
+                });
             }
         }
         None
     })
 }
 
+/// An extension's request to use the model, which comes as an elicitation asking to approve it
+fn find_sampling_request(message: &Message) -> Option<(String, SamplingPreview)> {
+    let (id, _, schema) = find_elicitation_request(message)?;
+    Some((id, SamplingPreview::from_schema(&schema)?))
+}
+
+fn prompt_sampling_approval() -> Result<bool> {
+    match cliclack::confirm(tr("sampling-prompt"))
+        .initial_value(false)
+        .interact()
+    {
+        Ok(approved) => Ok(approved),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Extract elicitation request from a message
 fn find_elicitation_request(message: &Message) -> Option<(String, String, Value)> {
     message.content.iter().find_map(|content| {
@@ -1542,6 +3564,34 @@ fn handle_mcp_notification(
     is_json_mode: bool,
     debug: bool,
 ) {
+    if let ServerNotification::LoggingMessageNotification(log_notif) = notification {
+        let data = &log_notif.params.data;
+        if data.get("type").and_then(Value::as_str) == Some(SAMPLING_RESULT_TYPE) {
+            // shown whatever the verbosity, as the user approved the request
+            if !is_stream_json_mode && !is_json_mode {
+                if interactive {
+                    let _ = progress_bars.hide();
+                }
+                output::render_sampling_result(
+                    data.get("provider")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown"),
+                    data.get("model")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown"),
+                    data.get("output_tokens").and_then(Value::as_i64),
+                );
+            }
+            return;
+        }
+        let (formatted, _, _) = format_logging_notification(&log_notif.params.data, debug);
+        let formatted = output::redact(&formatted);
+        extension_logs::record(extension_id, &formatted);
+        output::record_tool_output(extension_id, &formatted);
+    }
+    if !is_stream_json_mode && !output::get_verbosity().shows_notifications() {
+        return;
+    }
     match notification {
         ServerNotification::LoggingMessageNotification(log_notif) => {
             if let Some(obj) = log_notif.params.data.as_object() {
@@ -1559,6 +3609,14 @@ fn handle_mcp_notification(
                             .and_then(|v| v.as_object())
                             .cloned();
 
+                        if interactive
+                            && !is_stream_json_mode
+                            && !is_json_mode
+                            && output::McpSpinners::supports_subagent_panel()
+                        {
+                            progress_bars.subagent_tool_call(subagent_id, tool_name);
+                            return;
+                        }
                         if interactive {
                             let _ = progress_bars.hide();
                         }
@@ -1589,6 +3647,7 @@ fn handle_mcp_notification(
 
             let (formatted, subagent_id, notif_type) =
                 format_logging_notification(&log_notif.params.data, debug);
+            let formatted = output::redact(&formatted).into_owned();
 
             if is_stream_json_mode {
                 emit_stream_event(&StreamEvent::Notification {
@@ -1599,6 +3658,7 @@ fn handle_mcp_notification(
                 });
             } else {
                 display_log_notification(
+                    extension_id,
                     &formatted,
                     subagent_id.as_deref(),
                     notif_type.as_deref(),
@@ -1692,6 +3752,7 @@ fn format_logging_notification(
 
 /// Display a logging notification based on its type and context
 fn display_log_notification(
+    request_id: &str,
     formatted_message: &str,
     subagent_id: Option<&str>,
     notification_type: Option<&str>,
@@ -1724,7 +3785,7 @@ fn display_log_notification(
                 .ok()
                 .unwrap_or(output::DEFAULT_MIN_PRIORITY);
 
-            if min_priority < 0.1 {
+            if min_priority < 0.1 && !output::hold_tool_log(request_id, formatted_message) {
                 if interactive {
                     let _ = progress_bars.hide();
                 }
@@ -1786,38 +3847,35 @@ fn log_tool_metrics(message: &Message, messages: &Conversation) {
     }
 }
 
-/// Handle and display an agent error
-fn handle_agent_error(e: &anyhow::Error, is_stream_json_mode: bool) {
-    let error_msg = e.to_string();
+/// Handle and display an agent error, returning its class
+fn handle_agent_error(e: &anyhow::Error, is_stream_json_mode: bool) -> SessionError {
+    let error = SessionError::classify(e);
 
     if is_stream_json_mode {
         emit_stream_event(&StreamEvent::Error {
-            error: error_msg.clone(),
+            error: e.to_string(),
         });
     }
 
-    if e.downcast_ref::<goose::providers::errors::ProviderError>()
-        .map(|provider_error| {
-            matches!(
-                provider_error,
-                goose::providers::errors::ProviderError::ContextLengthExceeded(_)
-            )
-        })
-        .unwrap_or(false)
-    {
-        if !is_stream_json_mode {
-            output::render_text(
-                "Compaction requested. Should have happened in the agent!",
-                Some(Color::Yellow),
-                true,
-            );
-        }
+    if matches!(error, SessionError::ContextOverflow(_)) {
         warn!("Compaction requested. Should have happened in the agent!");
     }
 
     if !is_stream_json_mode {
-        eprintln!("Error: {}", error_msg);
+        output::render_session_error(&error);
+    }
+    error
+}
+
+/// The message for what the user typed, with the files it mentions as `@path` attached
+fn user_message(content: &str) -> Message {
+    let mut message = Message::user().with_text(content);
+    if let Ok(dir) = std::env::current_dir() {
+        for attachment in mentions::attachments(&dir, content) {
+            message = message.with_text(attachment.to_text());
+        }
     }
+    message
 }
 
 async fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
@@ -1854,6 +3912,38 @@ async fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
     Ok(reasoner)
 }
 
+/// Shows the time left before retrying in the thinking indicator. Returns false if cancelled
+/// while waiting.
+async fn count_down(
+    error: &anyhow::Error,
+    delay: Duration,
+    cancel_token: &CancellationToken,
+    show: bool,
+) -> bool {
+    let deadline = Instant::now() + delay;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        if show {
+            output::render_retry_countdown(&provider_retry::countdown_message(error, remaining));
+        }
+        // wake on the second boundary so the countdown ticks in whole seconds
+        let tick = match remaining.subsec_millis() {
+            0 => Duration::from_secs(1),
+            millis => Duration::from_millis(u64::from(millis)),
+        };
+        tokio::select! {
+            _ = cancel_token.cancelled() => {
+                output::hide_thinking();
+                return false;
+            }
+            _ = tokio::time::sleep(tick) => {}
+        }
+    }
+}
+
 /// Format elapsed time duration
 /// Shows seconds if less than 60, otherwise shows minutes:seconds
 fn format_elapsed_time(duration: std::time::Duration) -> String {
@@ -1867,11 +3957,36 @@ fn format_elapsed_time(duration: std::time::Duration) -> String {
     }
 }
 
+const INTERRUPTED_MARKER: &str = "[response interrupted by the user]";
+
+/// The partial assistant response with the interrupted marker added, if the message is one:
+/// streamed text without tool calls, which are answered separately.
+fn mark_interrupted(message: &Message) -> Option<Message> {
+    let is_partial_text = message.role == rmcp::model::Role::Assistant
+        && message
+            .content
+            .iter()
+            .any(|c| matches!(c, MessageContent::Text(t) if !t.text.trim().is_empty()))
+        && !message
+            .content
+            .iter()
+            .any(|c| matches!(c, MessageContent::ToolRequest(_)));
+    if !is_partial_text {
+        return None;
+    }
+    let mut marked = message.clone();
+    marked
+        .content
+        .push(MessageContent::text(format!("\n\n{}", INTERRUPTED_MARKER)));
+    Some(marked)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use goose::agents::extension::Envs;
     use goose::config::ExtensionConfig;
+    use goose::session::{SessionManager, SessionType};
     use std::time::Duration;
     use test_case::test_case;
 
@@ -2031,4 +4146,135 @@ mod tests {
             expected
         );
     }
+
+    #[test]
+    fn test_mark_interrupted_keeps_partial_text() {
+        let partial = Message::assistant().with_text("Here is the first half");
+        let marked = mark_interrupted(&partial).unwrap();
+        assert_eq!(
+            marked.as_concat_text(),
+            format!("Here is the first half\n\n\n{}", INTERRUPTED_MARKER)
+        );
+        assert_eq!(marked.id, partial.id);
+
+        assert!(mark_interrupted(&Message::user().with_text("question")).is_none());
+        assert!(mark_interrupted(&Message::assistant().with_text("  ")).is_none());
+        let tool_call = Message::assistant()
+            .with_text("Let me look")
+            .with_tool_request(
+                "1",
+                Ok(rmcp::model::CallToolRequestParams {
+                    meta: None,
+                    task: None,
+                    name: "developer__shell".into(),
+                    arguments: None,
+                }),
+            );
+        assert!(mark_interrupted(&tool_call).is_none());
+    }
+
+    /// Rate limits the first turn it is asked for, then answers.
+    struct RateLimitedOnceProvider {
+        calls: std::sync::atomic::AtomicUsize,
+        model_config: goose::model::ModelConfig,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for RateLimitedOnceProvider {
+        fn get_name(&self) -> &str {
+            "rate-limited-once"
+        }
+
+        fn get_model_config(&self) -> goose::model::ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete_with_model(
+            &self,
+            _session_id: Option<&str>,
+            _model_config: &goose::model::ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<
+            (Message, goose::providers::base::ProviderUsage),
+            goose::providers::errors::ProviderError,
+        > {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(goose::providers::errors::ProviderError::RateLimitExceeded {
+                    details: "slow down".to_string(),
+                    retry_delay: Some(Duration::from_millis(10)),
+                });
+            }
+            Ok((
+                Message::assistant().with_text("recovered"),
+                goose::providers::base::ProviderUsage::new(
+                    "mock".to_string(),
+                    goose::providers::base::Usage::default(),
+                ),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_turn_is_retried() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let agent = Agent::with_config(goose::agents::AgentConfig::new(
+            Arc::new(SessionManager::new(temp_dir.path().to_path_buf())),
+            Arc::new(goose::config::permission::PermissionManager::new(
+                temp_dir.path().to_path_buf(),
+            )),
+            None,
+            goose::config::GooseMode::Auto,
+            true,
+            goose::agents::GoosePlatform::GooseCli,
+        ));
+        let session = agent
+            .config
+            .session_manager
+            .create_session(
+                temp_dir.path().to_path_buf(),
+                "retry".to_string(),
+                SessionType::Hidden,
+            )
+            .await
+            .unwrap();
+        let provider = Arc::new(RateLimitedOnceProvider {
+            calls: Default::default(),
+            model_config: goose::model::ModelConfig::new("mock").unwrap(),
+        });
+        agent
+            .update_provider(provider.clone(), &session.id)
+            .await
+            .unwrap();
+
+        let mut cli_session = CliSession::new(
+            agent,
+            session.id,
+            false,
+            None,
+            None,
+            None,
+            None,
+            "text".into(),
+        )
+        .await;
+        cli_session
+            .process_message(
+                Message::user().with_text("hello"),
+                CancellationToken::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+        let messages = cli_session.message_history();
+        assert_eq!(
+            messages.messages().last().unwrap().as_concat_text(),
+            "recovered"
+        );
+        assert!(!messages
+            .iter()
+            .any(|m| m.as_concat_text().contains("Ran into this error")));
+    }
 }
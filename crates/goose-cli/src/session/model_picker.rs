@@ -0,0 +1,191 @@
+use anyhow::Result;
+use goose::config::Config;
+use goose::providers::base::{ConfigKey, ModelInfo, ProviderMetadata};
+use goose::providers::canonical::maybe_get_canonical_model;
+
+use super::output;
+
+/// Value of the entry that lets a model name be typed in
+const OTHER_MODEL: &str = "";
+
+/// Whether every key the provider needs has a value, from the environment, the config file or
+/// the keyring, or a default.
+fn is_configured(metadata: &ProviderMetadata, has_value: impl Fn(&ConfigKey) -> bool) -> bool {
+    metadata
+        .config_keys
+        .iter()
+        .filter(|key| key.required && key.default.is_none())
+        .all(has_value)
+}
+
+fn has_config_value(key: &ConfigKey) -> bool {
+    let config = Config::global();
+    std::env::var_os(&key.name).is_some()
+        || if key.secret {
+            config.get_secret::<String>(&key.name).is_ok()
+        } else {
+            config.get_param::<String>(&key.name).is_ok()
+        }
+}
+
+/// Providers that are ready to use, by display name.
+pub async fn configured_providers() -> Vec<ProviderMetadata> {
+    let mut providers: Vec<ProviderMetadata> = goose::providers::providers()
+        .await
+        .into_iter()
+        .map(|(metadata, _)| metadata)
+        .filter(|metadata| is_configured(metadata, has_config_value))
+        .collect();
+    providers.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    providers
+}
+
+/// Context size and pricing shown next to a model, from the model registry when it knows the
+/// model and otherwise from what the provider reports.
+fn model_hint(provider: &str, model: &ModelInfo) -> String {
+    if let Some(canonical) = maybe_get_canonical_model(provider, &model.name) {
+        return output::format_model_metadata(&canonical);
+    }
+    let mut parts = Vec::new();
+    if model.context_limit > 0 {
+        parts.push(format!(
+            "{} context",
+            output::format_tokens(model.context_limit)
+        ));
+    }
+    if let (Some(input), Some(output)) = (model.input_token_cost, model.output_token_cost) {
+        // providers report cost per token
+        parts.push(format!(
+            "${:.2}/${:.2} per 1M tokens",
+            input * 1_000_000.0,
+            output * 1_000_000.0
+        ));
+    }
+    parts.join(" · ")
+}
+
+/// Splits a `/model` argument into provider and model. `openai/gpt-4o` names the provider
+/// when `openai` is one; anything else is a model of the current provider, which keeps model
+/// names like `anthropic/claude-sonnet-4` on openrouter intact.
+pub fn parse_target(
+    arg: &str,
+    provider_names: &[&str],
+    current_provider: &str,
+) -> (String, String) {
+    match arg.split_once('/') {
+        Some((provider, model)) if !model.is_empty() && provider_names.contains(&provider) => {
+            (provider.to_string(), model.to_string())
+        }
+        _ => (current_provider.to_string(), arg.to_string()),
+    }
+}
+
+/// Asks for a provider and one of its models, starting from the current ones. Returns `None`
+/// if nothing is configured.
+pub fn pick(
+    providers: &[ProviderMetadata],
+    current_provider: &str,
+    current_model: &str,
+) -> Result<Option<(String, String)>> {
+    if providers.is_empty() {
+        return Ok(None);
+    }
+    let mut select = cliclack::select("Provider");
+    for metadata in providers {
+        select = select.item(
+            metadata.name.clone(),
+            &metadata.display_name,
+            &metadata.name,
+        );
+    }
+    if providers.iter().any(|p| p.name == current_provider) {
+        select = select.initial_value(current_provider.to_string());
+    }
+    let provider_name: String = select.interact()?;
+    let Some(metadata) = providers.iter().find(|p| p.name == provider_name) else {
+        return Ok(None);
+    };
+
+    let mut model = OTHER_MODEL.to_string();
+    if !metadata.known_models.is_empty() {
+        let mut select = cliclack::select("Model");
+        for info in &metadata.known_models {
+            select = select.item(
+                info.name.clone(),
+                &info.name,
+                model_hint(&provider_name, info),
+            );
+        }
+        select = select.item(OTHER_MODEL.to_string(), "Other...", "type a model name");
+        let initial = if provider_name == current_provider {
+            current_model
+        } else {
+            metadata.default_model.as_str()
+        };
+        if metadata.known_models.iter().any(|m| m.name == initial) {
+            select = select.initial_value(initial.to_string());
+        }
+        model = select.interact()?;
+    }
+    if model == OTHER_MODEL {
+        let default_model = if provider_name == current_provider {
+            current_model
+        } else {
+            metadata.default_model.as_str()
+        };
+        model = cliclack::input("Model")
+            .default_input(default_model)
+            .interact()?;
+    }
+    Ok(Some((provider_name, model)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("gpt-4o", ("openrouter", "gpt-4o") ; "model only")]
+    #[test_case("openai/gpt-4o", ("openai", "gpt-4o") ; "provider and model")]
+    #[test_case("anthropic/claude-sonnet-4", ("openrouter", "anthropic/claude-sonnet-4") ; "slash in model name")]
+    #[test_case("openai/", ("openrouter", "openai/") ; "missing model")]
+    fn test_parse_target(arg: &str, expected: (&str, &str)) {
+        let (provider, model) = parse_target(arg, &["openai", "openrouter"], "openrouter");
+        assert_eq!((provider.as_str(), model.as_str()), expected);
+    }
+
+    #[test]
+    fn test_is_configured_needs_required_keys_without_defaults() {
+        let metadata = ProviderMetadata::new(
+            "local",
+            "Local",
+            "",
+            "small",
+            vec!["small"],
+            "",
+            vec![
+                ConfigKey::new("LOCAL_API_KEY", true, true, None),
+                ConfigKey::new("LOCAL_HOST", true, false, Some("localhost")),
+                ConfigKey::new("LOCAL_ORG", false, false, None),
+            ],
+        );
+        assert!(is_configured(&metadata, |key| key.name == "LOCAL_API_KEY"));
+        assert!(!is_configured(&metadata, |key| key.name == "LOCAL_HOST"));
+    }
+
+    #[test]
+    fn test_model_hint_falls_back_to_provider_info() {
+        let info = ModelInfo {
+            name: "in-house-model".to_string(),
+            context_limit: 32_000,
+            input_token_cost: Some(0.000_001),
+            output_token_cost: Some(0.000_002),
+            currency: None,
+            supports_cache_control: None,
+        };
+        assert_eq!(
+            model_hint("in-house", &info),
+            "32k context · $1.00/$2.00 per 1M tokens"
+        );
+    }
+}
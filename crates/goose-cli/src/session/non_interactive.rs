@@ -0,0 +1,68 @@
+use goose::agents::MAX_TURNS_REACHED_MESSAGE;
+use goose::config::Config;
+use goose::conversation::message::Message;
+use rmcp::model::Role;
+use tracing::warn;
+
+/// How tool calls that need approval, and extensions' requests to use the model, are answered
+/// in `--non-interactive` runs, where there is nobody to ask. Set with
+/// GOOSE_CLI_NON_INTERACTIVE_APPROVAL; denying is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalPolicy {
+    Approve,
+    Deny,
+}
+
+impl ApprovalPolicy {
+    pub fn from_config() -> Self {
+        let Ok(value) = Config::global().get_param::<String>("GOOSE_CLI_NON_INTERACTIVE_APPROVAL")
+        else {
+            return Self::Deny;
+        };
+        Self::from_config_str(&value).unwrap_or_else(|| {
+            warn!(
+                "Unknown GOOSE_CLI_NON_INTERACTIVE_APPROVAL '{}', denying tool calls",
+                value
+            );
+            Self::Deny
+        })
+    }
+
+    fn from_config_str(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "approve" | "allow" => Some(Self::Approve),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the agent stopped because it used up its turns (`--max-turns`).
+pub fn ran_out_of_turns(message: &Message) -> bool {
+    message.role == Role::Assistant && message.as_concat_text() == MAX_TURNS_REACHED_MESSAGE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("approve", Some(ApprovalPolicy::Approve) ; "approve")]
+    #[test_case(" Allow ", Some(ApprovalPolicy::Approve) ; "allow alias")]
+    #[test_case("deny", Some(ApprovalPolicy::Deny) ; "deny")]
+    #[test_case("ask", None ; "unknown")]
+    fn test_from_config_str(value: &str, expected: Option<ApprovalPolicy>) {
+        assert_eq!(ApprovalPolicy::from_config_str(value), expected);
+    }
+
+    #[test]
+    fn test_ran_out_of_turns() {
+        assert!(ran_out_of_turns(
+            &Message::assistant().with_text(MAX_TURNS_REACHED_MESSAGE)
+        ));
+        assert!(!ran_out_of_turns(&Message::assistant().with_text("Done")));
+        assert!(!ran_out_of_turns(
+            &Message::user().with_text(MAX_TURNS_REACHED_MESSAGE)
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+use goose::config::Config;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const FOCUS_UNKNOWN: u8 = 0;
+const FOCUS_IN: u8 = 1;
+const FOCUS_OUT: u8 = 2;
+
+/// Last focus report from the terminal. Reports only arrive while the stream key listener is
+/// running, which enables focus reporting for as long as it reads input.
+static FOCUS: AtomicU8 = AtomicU8::new(FOCUS_UNKNOWN);
+
+pub fn set_focused(focused: bool) {
+    FOCUS.store(
+        if focused { FOCUS_IN } else { FOCUS_OUT },
+        Ordering::Relaxed,
+    );
+}
+
+/// Terminals that never report focus are treated as unfocused, so notifications still work
+/// there, just without the focus check.
+fn terminal_focused() -> bool {
+    FOCUS.load(Ordering::Relaxed) == FOCUS_IN
+}
+
+/// How to get the user's attention, from `GOOSE_CLI_NOTIFY`: `bell`, `desktop`, `both` (or
+/// `true`), and `off` (the default).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NotifyMode {
+    pub bell: bool,
+    pub desktop: bool,
+}
+
+impl NotifyMode {
+    pub fn from_config() -> Self {
+        Config::global()
+            .get_param::<String>("GOOSE_CLI_NOTIFY")
+            .map(|s| Self::from_config_str(&s))
+            .unwrap_or_default()
+    }
+
+    fn from_config_str(val: &str) -> Self {
+        match val.trim().to_lowercase().as_str() {
+            "bell" => Self {
+                bell: true,
+                desktop: false,
+            },
+            "desktop" => Self {
+                bell: false,
+                desktop: true,
+            },
+            "both" | "true" | "on" => Self {
+                bell: true,
+                desktop: true,
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Rings the bell and/or shows a desktop notification, if configured and the terminal is not
+/// focused.
+pub fn notify_user(message: &str) {
+    let mode = NotifyMode::from_config();
+    if mode == NotifyMode::default() || terminal_focused() || !std::io::stdout().is_terminal() {
+        return;
+    }
+    if mode.bell {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+    }
+    if mode.desktop {
+        if let Err(e) = desktop_notification(message) {
+            tracing::debug!("Failed to send desktop notification: {}", e);
+        }
+    }
+}
+
+fn desktop_notification(message: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title \"goose\"",
+            applescript_string(message)
+        ));
+        command
+    } else if cfg!(windows) {
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command", &windows_toast_script(message)]);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=goose", "goose", message]);
+        command
+    };
+    // don't wait for the notifier; it only matters that it was started
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn windows_toast_script(message: &str) -> String {
+    let escaped = message
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "''");
+    format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode('goose')) > $null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode('{}')) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('goose').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        escaped
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("bell", true, false)]
+    #[test_case("Desktop", false, true)]
+    #[test_case("both", true, true)]
+    #[test_case("true", true, true)]
+    #[test_case("off", false, false)]
+    #[test_case("nonsense", false, false)]
+    fn test_notify_mode_from_config_str(val: &str, bell: bool, desktop: bool) {
+        assert_eq!(
+            NotifyMode::from_config_str(val),
+            NotifyMode { bell, desktop }
+        );
+    }
+
+    #[test]
+    fn test_applescript_string_escapes_quotes() {
+        assert_eq!(
+            applescript_string(r#"run "ls" \ now"#),
+            r#""run \"ls\" \\ now""#
+        );
+    }
+}
@@ -1,25 +1,92 @@
-use anstream::println;
+use bat::line_range::{LineRange, LineRanges};
 use bat::WrappingMode;
-use console::{measure_text_width, style, Color, Term};
+use console::{measure_text_width, style, Color, Style, Term};
+use goose::agents::{tool_elapsed, tool_timed_out, ProcessExit, SamplingPreview};
 use goose::config::Config;
+use goose::context_mgmt::ToolOutputCompression;
 use goose::conversation::message::{
-    ActionRequiredData, Message, MessageContent, ToolRequest, ToolResponse,
+    ActionRequiredData, Citation, Message, MessageContent, SystemNotificationContent, ToolRequest,
+    ToolResponse,
 };
-use goose::providers::canonical::maybe_get_canonical_model;
+use goose::providers::base::ProviderUsage;
+use goose::providers::canonical::{maybe_get_canonical_model, CanonicalModel, Modality};
+use goose::session::{PinnedState, Session, SubagentRecord, SubagentsState};
+use goose::stale_file_inspector::{STALE_FILE_DISK_HEADING, STALE_FILE_EDIT_HEADING};
 #[cfg(target_os = "windows")]
 use goose::subprocess::SubprocessExt;
 use goose::utils::safe_truncate;
+use goose_mcp::developer::jobs::{self, JobInfo, JobStatus};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use rmcp::model::{CallToolRequestParams, JsonObject, PromptArgument};
+use rmcp::model::{
+    CallToolRequestParams, JsonObject, PromptArgument, RawResource, ResourceContents, Tool,
+};
 use serde_json::Value;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::{Error, IsTerminal, Write};
 use std::path::Path;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, LazyLock, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::accessible;
+use super::ansi_output;
+use super::clipboard::ClipboardMethod;
+use super::code_block::CodeInfo;
+use super::compaction::CompactionSummary;
+use super::context_breakdown::{stacked_cells, ContextBreakdown};
+use super::cost::{CostTracker, TurnCost};
+use super::diff;
+use super::error::SessionError;
+use super::extension_logs;
+use super::file_history::{FileDiff, RestoredFile};
+use super::files_touched::TouchedFile;
+use super::hyperlink;
+use super::image::{render_attached_image, render_image};
+use super::link_refs::{self, LinkRefs};
+use super::math;
+use super::mentions;
+use super::palette::palette;
+use super::params;
+use super::pins;
+use super::plan_progress::{PlanChecklist, StepStatus};
+use super::redaction::Redactor;
+use super::render_hints;
+use super::repeated_output::{OutputChange, RepeatedOutput};
+use super::resources::{self, BinaryResource};
+use super::shell_preview::{AffectedPath, Effect};
+use super::streaming_buffer::{split_code_blocks, MarkdownBuffer, MarkdownSegment};
+use super::table::MarkdownTable;
+use super::term_background;
+use super::term_writer::{StdoutWriter, TermWriter};
+use super::terminal;
+use super::thought_stream::{self, ThoughtStream};
+use super::todo::{TodoItem, TodoList, TodoStatus};
+use super::tool_frames::{Frame, ToolFrames};
+use super::tool_log::ToolCallLog;
+use super::tool_pane::ToolPane;
+use super::tool_timeouts::{FinishedCall, ToolTimeouts};
+use super::truncation::{Truncated, TruncationConfig};
+use super::turn_stats::TurnStats;
+use crate::i18n::{tr, tr_with};
+use crate::project_memory::ProjectMemory;
+
+/// `print!` to the current [`TermWriter`]
+macro_rules! term_print {
+    ($($arg:tt)*) => {
+        write_term(&format!($($arg)*))
+    };
+}
 
-use super::streaming_buffer::MarkdownBuffer;
+/// `println!` to the current [`TermWriter`]
+macro_rules! term_println {
+    () => {
+        write_term("\n")
+    };
+    ($($arg:tt)*) => {
+        write_term(&format!("{}\n", format_args!($($arg)*)))
+    };
+}
 
 pub const DEFAULT_MIN_PRIORITY: f32 = 0.0;
 pub const DEFAULT_CLI_LIGHT_THEME: &str = "GitHub";
@@ -34,7 +101,8 @@ pub enum Theme {
 }
 
 impl Theme {
-    fn as_str(&self) -> String {
+    /// The bat theme used for this mode.
+    pub fn as_str(&self) -> String {
         match self {
             Theme::Light => Config::global()
                 .get_param::<String>("GOOSE_CLI_LIGHT_THEME")
@@ -49,6 +117,8 @@ impl Theme {
     fn from_config_str(val: &str) -> Self {
         if val.eq_ignore_ascii_case("light") {
             Theme::Light
+        } else if val.eq_ignore_ascii_case("auto") {
+            Self::detect()
         } else if val.eq_ignore_ascii_case("ansi") {
             Theme::Ansi
         } else {
@@ -56,6 +126,15 @@ impl Theme {
         }
     }
 
+    /// Light or Dark to match the terminal background, or Ansi if the terminal does not say.
+    pub fn detect() -> Self {
+        match term_background::is_light_background() {
+            Some(true) => Theme::Light,
+            Some(false) => Theme::Dark,
+            None => Theme::Ansi,
+        }
+    }
+
     fn as_config_string(&self) -> String {
         match self {
             Theme::Light => "light".to_string(),
@@ -76,6 +155,64 @@ thread_local! {
             )
     );
     static SHOW_FULL_TOOL_OUTPUT: RefCell<bool> = const { RefCell::new(false) };
+    static CURRENT_VERBOSITY: RefCell<Verbosity> = const { RefCell::new(Verbosity::Normal) };
+    static REPEATED_OUTPUT: RefCell<RepeatedOutput> = RefCell::new(RepeatedOutput::default());
+    static TOOL_CALLS: RefCell<ToolCallLog> = RefCell::new(ToolCallLog::default());
+    static TOOL_PANE: RefCell<Option<ToolPane>> = RefCell::new(ToolPane::from_config());
+    static SHOW_TOOL_IDS: Cell<bool> = const { Cell::new(false) };
+    static STREAM_RAW: Cell<bool> = const { Cell::new(false) };
+    /// Mask secrets in tool calls and their output, off with `--no-redact`
+    static REDACT: Cell<bool> = const { Cell::new(true) };
+    /// Number to append to the next tool header, set while rendering a request
+    static HEADER_TOOL_NUMBER: Cell<Option<usize>> = const { Cell::new(None) };
+    static TRUNCATION: TruncationConfig = TruncationConfig::from_config();
+    /// Set when something was cut short while rendering the current tool request or response
+    static TRUNCATED: Cell<bool> = const { Cell::new(false) };
+    /// The most recent output that was shown truncated, for /expand
+    static LAST_TRUNCATED: RefCell<Option<TruncatedOutput>> = const { RefCell::new(None) };
+    static TERM_WRITER: RefCell<Box<dyn TermWriter>> = RefCell::new(Box::new(StdoutWriter));
+    /// The todo list as of the agent's last `todo__write`, to show only what changed
+    static TODO_LIST: RefCell<TodoList> = RefCell::new(TodoList::default());
+    static TOOL_FRAMES: RefCell<ToolFrames> = RefCell::new(ToolFrames::default());
+    /// Running tool calls that have a timeout, for the countdown and their partial output
+    static TOOL_TIMEOUTS: RefCell<ToolTimeouts> = RefCell::new(ToolTimeouts::default());
+    /// Binary resources from tool results, numbered from 1 for /save
+    static BINARY_RESOURCES: RefCell<Vec<BinaryResource>> = const { RefCell::new(Vec::new()) };
+    /// The session's pins, to mark pinned messages when they are shown
+    static PINNED: RefCell<PinnedState> = RefCell::new(PinnedState::default());
+    /// Thinking streamed so far in the current response, until the answer starts
+    static THOUGHTS: RefCell<Option<ThoughtStream>> = const { RefCell::new(None) };
+}
+
+/// Sends output to `writer` instead, returning the writer used until now.
+pub fn set_term_writer(writer: Box<dyn TermWriter>) -> Box<dyn TermWriter> {
+    TERM_WRITER.with(|w| std::mem::replace(&mut *w.borrow_mut(), writer))
+}
+
+/// Replaces the writer with one built around it, such as a recording that passes output on.
+pub fn wrap_term_writer(wrap: impl FnOnce(Box<dyn TermWriter>) -> Box<dyn TermWriter>) {
+    TERM_WRITER.with(|w| {
+        let mut w = w.borrow_mut();
+        let inner = std::mem::replace(&mut *w, Box::new(StdoutWriter));
+        *w = wrap(inner);
+    });
+}
+
+/// Passes what the user typed to the writer, see [`TermWriter::echo_input`].
+pub fn echo_input(text: &str) {
+    TERM_WRITER.with(|w| w.borrow_mut().echo_input(text));
+}
+
+fn write_term(text: &str) {
+    TERM_WRITER.with(|w| w.borrow_mut().write_str(text));
+}
+
+fn term_is_terminal() -> bool {
+    TERM_WRITER.with(|w| w.borrow().is_terminal())
+}
+
+fn term_on_terminal() -> bool {
+    TERM_WRITER.with(|w| w.borrow().on_terminal())
 }
 
 pub fn set_theme(theme: Theme) {
@@ -97,6 +234,28 @@ pub fn set_theme(theme: Theme) {
     }
 }
 
+/// Persist the bat theme used in light or dark mode. The ansi mode always uses base16.
+pub fn set_bat_theme(theme: Theme, name: &str) {
+    let key = match theme {
+        Theme::Light => "GOOSE_CLI_LIGHT_THEME",
+        Theme::Dark => "GOOSE_CLI_DARK_THEME",
+        Theme::Ansi => return,
+    };
+    if let Err(e) = Config::global().set_param(key, name) {
+        eprintln!("Failed to save theme setting to config: {}", e);
+    }
+}
+
+/// Follow the terminal background from now on, including in later sessions.
+pub fn set_auto_theme() -> Theme {
+    let theme = Theme::detect();
+    CURRENT_THEME.with(|t| *t.borrow_mut() = theme);
+    if let Err(e) = Config::global().set_param("GOOSE_CLI_THEME", "auto") {
+        eprintln!("Failed to save theme setting to config: {}", e);
+    }
+    theme
+}
+
 pub fn get_theme() -> Theme {
     CURRENT_THEME.with(|t| *t.borrow())
 }
@@ -113,16 +272,566 @@ pub fn get_show_full_tool_output() -> bool {
     SHOW_FULL_TOOL_OUTPUT.with(|s| *s.borrow())
 }
 
+/// Number tool calls on screen so each response can be matched to its request.
+pub fn set_show_tool_ids(show: bool) {
+    SHOW_TOOL_IDS.with(|s| s.set(show));
+}
+
+/// Write response text as it streams in, without waiting for markdown to be complete or
+/// rendering it. This is always the case when the output is not a terminal.
+pub fn set_stream_raw(raw: bool) {
+    STREAM_RAW.with(|s| s.set(raw));
+}
+
+fn stream_raw() -> bool {
+    STREAM_RAW.with(|s| s.get()) || !term_on_terminal()
+}
+
+static REDACTOR: OnceLock<Redactor> = OnceLock::new();
+
+/// Reads the `.env` whose secrets are masked from `dir`, the session's working directory.
+/// Only the first call counts; without one the current directory is used.
+pub fn set_redaction_dir(dir: &Path) {
+    let _ = REDACTOR.set(Redactor::from_config(dir));
+}
+
+fn redactor() -> &'static Redactor {
+    REDACTOR.get_or_init(|| Redactor::from_config(&std::env::current_dir().unwrap_or_default()))
+}
+
+/// Show secrets in tool calls and their output as they are, for debugging.
+pub fn set_redact(redact: bool) {
+    REDACT.with(|r| r.set(redact));
+}
+
+/// `text` with secrets masked, unless `--no-redact` was given
+pub fn redact(text: &str) -> Cow<'_, str> {
+    if REDACT.with(Cell::get) {
+        redactor().redact(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+fn redacted_request(req: &ToolRequest) -> Option<ToolRequest> {
+    REDACT.with(Cell::get).then(|| {
+        let mut req = req.clone();
+        redactor().redact_tool_request(&mut req);
+        req
+    })
+}
+
+fn redacted_response(resp: &ToolResponse) -> Option<ToolResponse> {
+    REDACT.with(Cell::get).then(|| {
+        let mut resp = resp.clone();
+        redactor().redact_tool_response(&mut resp);
+        resp
+    })
+}
+
+/// Re-prints tool call `number` and its response without truncation. Returns false if there is
+/// no such call.
+pub fn render_tool_call(number: usize) -> bool {
+    let Some((request, response)) = TOOL_CALLS.with(|log| {
+        log.borrow()
+            .get(number)
+            .map(|entry| (entry.request.clone(), entry.response.clone()))
+    }) else {
+        return false;
+    };
+
+    let full_output = get_show_full_tool_output();
+    let verbosity = get_verbosity();
+    let show_ids = SHOW_TOOL_IDS.with(|s| s.replace(true));
+    SHOW_FULL_TOOL_OUTPUT.with(|s| *s.borrow_mut() = true);
+    set_verbosity(verbosity.max(Verbosity::Normal));
+
+    let theme = get_theme();
+    render_tool_request(&request, theme, false);
+    match &response {
+        Some(response) => render_tool_response(response, theme, false),
+        None => term_println!("    {}", style("no response yet").dim()),
+    }
+    term_println!();
+
+    set_verbosity(verbosity);
+    SHOW_FULL_TOOL_OUTPUT.with(|s| *s.borrow_mut() = full_output);
+    SHOW_TOOL_IDS.with(|s| s.set(show_ids));
+    true
+}
+
+/// Reports what `/undo` put back, with a diff from the discarded to the restored content.
+pub fn render_undo(tool_name: &str, files: &[RestoredFile]) {
+    term_println!(
+        "\n  {} {}",
+        style("↶").cyan().bold(),
+        style(format!("undid the changes of {}", tool_name)).bold()
+    );
+    print_restored_files(files);
+    term_println!();
+}
+
+/// What `/rewind` dropped: the prompts of the turns, and the files put back as they were
+/// before them. `files` is `None` when no snapshots were taken for those turns, as in a
+/// resumed session.
+pub fn render_rewind(prompts: &[String], files: Option<&[RestoredFile]>) {
+    let turns = if prompts.len() == 1 { "turn" } else { "turns" };
+    term_println!(
+        "\n  {} {}",
+        style("⟲").cyan().bold(),
+        style(format!("rewound {} {}", prompts.len(), turns)).bold()
+    );
+    for prompt in prompts {
+        let first_line = prompt.trim().lines().next().unwrap_or_default();
+        term_println!(
+            "    {} {}",
+            style("dropped").red(),
+            style(safe_truncate(first_line, 80)).dim()
+        );
+    }
+    match files {
+        Some([]) => term_println!("    {}", style("no files to restore").dim()),
+        Some(files) => print_restored_files(files),
+        None => term_println!(
+            "    {}",
+            style("files were not snapshotted for these turns and are left as they are").dim()
+        ),
+    }
+    term_println!();
+}
+
+fn print_restored_files(files: &[RestoredFile]) {
+    for file in files {
+        let path = shorten_path(&file.path.to_string_lossy(), false);
+        match (&file.discarded, &file.restored) {
+            (Some(_), None) => term_println!("    {} {}", style("removed").red(), path),
+            (None, Some(_)) => term_println!("    {} {}", style("recreated").green(), path),
+            _ => term_println!("    {} {}", style("restored").green(), path),
+        }
+        let text = |bytes: &Option<Vec<u8>>| {
+            bytes
+                .as_deref()
+                .map(|b| String::from_utf8_lossy(b).into_owned())
+                .unwrap_or_default()
+        };
+        if let Some(patch) =
+            diff::unified_diff(&path, &text(&file.discarded), &text(&file.restored))
+        {
+            print_diff(&patch, false);
+        }
+    }
+}
+
+/// The lines changed by the turn's tool calls, with a pointer to `/diff`.
+pub fn render_diffstat(stat: &diff::DiffStat) {
+    if !get_verbosity().shows_notifications() {
+        return;
+    }
+    hide_thinking();
+    term_println!(
+        "\n  {} {}, {} {}  {}",
+        style("±").cyan(),
+        style(stat.files_changed()).bold(),
+        style(format!("+{}", stat.added)).green(),
+        style(format!("-{}", stat.removed)).red(),
+        style("(/diff to see the changes)").dim()
+    );
+}
+
+/// The prompt for an edit to a file that changed on disk since the agent read it, with the
+/// changes on disk and the agent's edit highlighted as diffs
+pub fn render_stale_file_alert(alert: &str) {
+    hide_thinking();
+    let sections = alert
+        .split_once(&format!("\n{}\n", STALE_FILE_DISK_HEADING))
+        .and_then(|(intro, rest)| {
+            let (disk, edit) = rest.split_once(&format!("\n{}\n", STALE_FILE_EDIT_HEADING))?;
+            Some((intro, disk, edit))
+        });
+    let Some((intro, disk, edit)) = sections else {
+        term_println!("\n{}", alert);
+        return;
+    };
+    term_println!("\n{}", style(intro.trim_end()).yellow());
+    term_println!("\n  {}", style(STALE_FILE_DISK_HEADING).bold());
+    print_diff(disk, true);
+    term_println!("\n  {}", style(STALE_FILE_EDIT_HEADING).bold());
+    print_diff(edit, true);
+}
+
+/// Full diffs of the files tool calls changed during the session, for `/diff`.
+pub fn render_file_diffs(diffs: &[FileDiff]) {
+    if diffs.is_empty() {
+        term_println!(
+            "{}",
+            style("No files changed by tools in this session").dim()
+        );
+        return;
+    }
+    for file in diffs {
+        let path = shorten_path(&file.path.to_string_lossy(), false);
+        if let Some(patch) = diff::unified_diff(&path, &file.before_text(), &file.after_text()) {
+            // shown in full, unlike the diffs in tool calls
+            print_diff(&patch, true);
+        }
+    }
+    let stat = FileDiff::stat(diffs);
+    term_println!(
+        "\n  {}, {} {}\n",
+        stat.files_changed(),
+        style(format!("+{}", stat.added)).green(),
+        style(format!("-{}", stat.removed)).red()
+    );
+}
+
+pub fn render_fork(original: &str, forked: &str, turn: usize) {
+    term_println!(
+        "\n  {} {}",
+        style("⑂").cyan().bold(),
+        style(format!("forked after turn {}", turn)).bold()
+    );
+    term_println!(
+        "    {}",
+        style(format!(
+            "now in session {} · the original stays in {}",
+            forked, original
+        ))
+        .dim()
+    );
+}
+
+/// Marks an answer replaced by `/retry`, with the settings the new one is generated with.
+pub fn render_answer_replaced(settings: Option<&str>) {
+    term_println!(
+        "\n  {} {}",
+        style("↻").cyan().bold(),
+        style("previous answer replaced").bold()
+    );
+    let note = match settings {
+        Some(settings) => format!(
+            "regenerating with {} · the old answer stays in the export",
+            settings
+        ),
+        None => "regenerating · the old answer stays in the export".to_string(),
+    };
+    term_println!("    {}", style(note).dim());
+}
+
+pub fn render_pins(pinned: &PinnedState) {
+    if pinned.is_empty() {
+        term_println!(
+            "\n  {}\n",
+            style("Nothing pinned yet. /pin <n> pins the nth latest message, /pin <file> a file")
+                .dim()
+        );
+        return;
+    }
+    term_println!();
+    for (number, pin) in pinned.pins.iter().enumerate() {
+        term_println!(
+            "  {} {} {}",
+            style("📌").yellow(),
+            style(format!("{}.", number + 1)).dim(),
+            pins::describe(pin)
+        );
+    }
+    term_println!(
+        "    {}\n",
+        style("kept in full when the conversation is compacted · /unpin <n> to remove").dim()
+    );
+}
+
+/// The project's memories, for `/memory` without a note.
+pub fn render_memories(memory: &ProjectMemory, path: &Path) {
+    if memory.memories.is_empty() {
+        term_println!(
+            "\n  {}\n",
+            style(
+                "No project memories yet. /memory [decision:|convention:|gotcha:] <note> \
+                 remembers one for every session in this project"
+            )
+            .dim()
+        );
+        return;
+    }
+    term_println!();
+    for (number, memory) in memory.memories.iter().enumerate() {
+        term_println!(
+            "  {} {} {} {}",
+            style("🧠").dim(),
+            style(format!("{}.", number + 1)).dim(),
+            style(memory.kind.label()).cyan(),
+            memory.text
+        );
+    }
+    term_println!(
+        "    {}\n",
+        style(format!(
+            "kept in {} · /forget <n> to remove",
+            path.display()
+        ))
+        .dim()
+    );
+}
+
+/// The subagents the session delegated to, for `/subagent` without an id.
+pub fn render_subagents(state: &SubagentsState) {
+    if state.subagents.is_empty() {
+        term_println!("\n  {}\n", style("No subagents in this session yet").dim());
+        return;
+    }
+    term_println!();
+    for subagent in &state.subagents {
+        term_println!(
+            "  {} {} {}",
+            style("🤖").dim(),
+            style(format!(
+                "subagent:{}",
+                short_subagent_id(&subagent.session_id)
+            ))
+            .cyan(),
+            subagent.description
+        );
+    }
+    term_println!(
+        "    {}\n",
+        style("/subagent <id> shows a subagent's transcript").dim()
+    );
+}
+
+/// A subagent's whole conversation: the task it was given, its tool calls and their results,
+/// and its answer.
+pub fn render_subagent_transcript(subagent: &SubagentRecord, messages: &[Message], debug: bool) {
+    term_println!(
+        "\n  {} {} {}",
+        style("🤖").dim(),
+        style(format!(
+            "subagent:{}",
+            short_subagent_id(&subagent.session_id)
+        ))
+        .cyan()
+        .bold(),
+        style(&subagent.description).dim()
+    );
+    for message in messages.iter().filter(|m| m.is_user_visible()) {
+        if message.role == rmcp::model::Role::User
+            && message
+                .content
+                .iter()
+                .any(|c| matches!(c, MessageContent::Text(_)))
+        {
+            term_println!("\n{}", style("task:").cyan().bold());
+        }
+        render_message(message, debug);
+    }
+    term_println!(
+        "\n  {}\n",
+        style(format!(
+            "end of subagent:{}",
+            short_subagent_id(&subagent.session_id)
+        ))
+        .dim()
+    );
+}
+
+/// The part of a subagent's session id shown in the live panel and tool call lines
+fn short_subagent_id(subagent_id: &str) -> &str {
+    subagent_id.rsplit('_').next().unwrap_or(subagent_id)
+}
+
+pub fn render_sandbox_entered(origin: &Path, sandbox: &Path) {
+    term_println!(
+        "\n  {} {}",
+        style("⛶").cyan().bold(),
+        style("sandboxed").bold()
+    );
+    term_println!(
+        "    {}",
+        style(format!(
+            "working in {} · {} is left alone until you apply the changes",
+            sandbox.display(),
+            origin.display()
+        ))
+        .dim()
+    );
+}
+
+/// The combined diff of a sandboxed session, in full, before asking to apply it.
+pub fn render_sandbox_changes(patch: &str, files: &[&str]) {
+    let plural = if files.len() == 1 { "" } else { "s" };
+    term_println!(
+        "\n  {} {}",
+        style("⛶").cyan().bold(),
+        style(format!(
+            "{} file{} changed in the sandbox",
+            files.len(),
+            plural
+        ))
+        .bold()
+    );
+    for file in files {
+        term_println!("    {}", style(file).dim());
+    }
+    term_println!();
+    print_diff(patch, true);
+}
+
+pub fn render_copied(lines: usize, method: ClipboardMethod) {
+    let plural = if lines == 1 { "" } else { "s" };
+    let via = match method {
+        ClipboardMethod::Command(program) => program.to_string(),
+        ClipboardMethod::Osc52 => "the terminal (OSC 52)".to_string(),
+    };
+    term_println!(
+        "  {} {}",
+        style("⧉").cyan(),
+        style(format!("copied {} line{} via {}", lines, plural, via)).dim()
+    );
+}
+
+const EXTENSION_LOG_TAIL: usize = 50;
+
+/// Prints the most recent log messages from one extension, or from all of them.
+pub fn render_extension_logs(extension: Option<&str>) {
+    extension_logs::with_logs(|logs| {
+        let lines = logs.tail(extension, EXTENSION_LOG_TAIL);
+        if lines.is_empty() {
+            let known = logs.extensions();
+            match extension {
+                Some(name) if !known.is_empty() => term_println!(
+                    "  {}",
+                    style(format!(
+                        "no logs from {}; extensions with logs: {}",
+                        name,
+                        known.join(", ")
+                    ))
+                    .dim()
+                ),
+                _ => term_println!("  {}", style("no extension logs yet").dim()),
+            }
+            return;
+        }
+
+        for (name, line) in &lines {
+            term_println!(
+                "  {} {} {}",
+                style(line.time.format("%H:%M:%S")).dim(),
+                style(format!("[{}]", name)).cyan(),
+                line.message
+            );
+        }
+        if let Some(dir) = logs.tee_dir() {
+            term_println!(
+                "  {}",
+                style(format!("full logs in {}", dir.display())).dim()
+            );
+        }
+    });
+}
+
+/// How much besides the model's response a session prints. Levels are ordered, so each one
+/// shows everything the previous one does.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only the model's response
+    Silent,
+    /// Tool calls are listed by name, without parameters or output
+    Minimal,
+    #[default]
+    Normal,
+    /// Also shows the model's thinking
+    Verbose,
+    /// Full tool output and paths, nothing truncated
+    Debug,
+}
+
+impl Verbosity {
+    /// Reads `GOOSE_CLI_VERBOSITY`, if set to a known level.
+    pub fn from_config() -> Option<Self> {
+        Config::global()
+            .get_param::<String>("GOOSE_CLI_VERBOSITY")
+            .ok()
+            .and_then(|val| Self::from_config_str(&val))
+    }
+
+    fn from_config_str(val: &str) -> Option<Self> {
+        match val.trim().to_lowercase().as_str() {
+            "silent" | "quiet" => Some(Verbosity::Silent),
+            "minimal" => Some(Verbosity::Minimal),
+            "normal" => Some(Verbosity::Normal),
+            "verbose" => Some(Verbosity::Verbose),
+            "debug" => Some(Verbosity::Debug),
+            _ => None,
+        }
+    }
+
+    /// Picks the level for a session: an explicit level wins, then `--debug`, then the
+    /// configured default.
+    pub fn resolve(explicit: Option<Verbosity>, debug: bool) -> Self {
+        explicit
+            .or(debug.then_some(Verbosity::Debug))
+            .or_else(Self::from_config)
+            .unwrap_or_default()
+    }
+
+    pub fn shows_session_info(&self) -> bool {
+        *self >= Verbosity::Minimal
+    }
+
+    pub fn shows_tool_calls(&self) -> bool {
+        *self >= Verbosity::Minimal
+    }
+
+    pub fn shows_tool_details(&self) -> bool {
+        *self >= Verbosity::Normal
+    }
+
+    pub fn shows_notifications(&self) -> bool {
+        *self >= Verbosity::Normal
+    }
+
+    pub fn shows_thinking(&self) -> bool {
+        *self >= Verbosity::Verbose || std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok()
+    }
+}
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    CURRENT_VERBOSITY.with(|v| *v.borrow_mut() = verbosity);
+}
+
+pub fn get_verbosity() -> Verbosity {
+    CURRENT_VERBOSITY.with(|v| *v.borrow())
+}
+
 // Simple wrapper around spinner to manage its state
 #[derive(Default)]
 pub struct ThinkingIndicator {
     spinner: Option<cliclack::ProgressBar>,
+    /// Consoles without escape sequence support get a plain line instead of an animated spinner
+    plain: bool,
 }
 
 impl ThinkingIndicator {
     pub fn show(&mut self) {
+        if accessible::enabled() {
+            if !self.plain {
+                term_println!("{}", accessible::THINKING);
+                self.plain = true;
+            }
+            return;
+        }
+        if !terminal::supports_ansi() {
+            if !self.plain {
+                term_println!(
+                    "{}",
+                    style(format!("{} {}", tr("thinking"), tr("interrupt-hint"))).dim()
+                );
+                self.plain = true;
+            }
+            return;
+        }
         let spinner = cliclack::spinner();
-        let hint = style("(Ctrl+C to interrupt)").dim();
+        let hint = style(tr("interrupt-hint")).dim();
         if Config::global()
             .get_param("RANDOM_THINKING_MESSAGES")
             .unwrap_or(true)
@@ -133,19 +842,20 @@ impl ThinkingIndicator {
                 hint,
             ));
         } else {
-            spinner.start(format!("Thinking...  {}", hint));
+            spinner.start(format!("{}  {}", tr("thinking"), hint));
         }
         self.spinner = Some(spinner);
     }
 
     pub fn hide(&mut self) {
+        self.plain = false;
         if let Some(spinner) = self.spinner.take() {
             spinner.stop("");
         }
     }
 
     pub fn is_shown(&self) -> bool {
-        self.spinner.is_some()
+        self.spinner.is_some() || self.plain
     }
 }
 
@@ -163,13 +873,14 @@ thread_local! {
 }
 
 pub fn show_thinking() {
-    if std::io::stdout().is_terminal() {
+    // the full screen interface says so in its input box instead
+    if std::io::stdout().is_terminal() && term_is_terminal() {
         THINKING.with(|t| t.borrow_mut().show());
     }
 }
 
 pub fn hide_thinking() {
-    if std::io::stdout().is_terminal() {
+    if std::io::stdout().is_terminal() && term_is_terminal() {
         THINKING.with(|t| t.borrow_mut().hide());
     }
 }
@@ -216,41 +927,74 @@ pub fn set_thinking_message(s: &String) {
     }
 }
 
+/// Dim countdown in the thinking indicator while waiting to retry a failed request.
+pub fn render_retry_countdown(message: &str) {
+    show_thinking();
+    set_thinking_message(&style(message).dim().to_string());
+}
+
+pub fn set_pinned(pinned: &PinnedState) {
+    PINNED.with(|p| *p.borrow_mut() = pinned.clone());
+}
+
+fn is_pinned(message: &Message) -> bool {
+    message
+        .id
+        .as_deref()
+        .is_some_and(|id| PINNED.with(|p| p.borrow().is_message_pinned(id)))
+}
+
 pub fn render_message(message: &Message, debug: bool) {
     let theme = get_theme();
 
+    if is_pinned(message) {
+        term_println!("\n  {}", style("📌 pinned").yellow().dim());
+    }
+
     for content in &message.content {
         match content {
             MessageContent::ActionRequired(action) => match &action.data {
                 ActionRequiredData::ToolConfirmation { tool_name, .. } => {
-                    println!("action_required(tool_confirmation): {}", tool_name)
+                    term_println!("action_required(tool_confirmation): {}", tool_name)
                 }
                 ActionRequiredData::Elicitation { message, .. } => {
-                    println!("action_required(elicitation): {}", message)
+                    term_println!("action_required(elicitation): {}", message)
                 }
                 ActionRequiredData::ElicitationResponse { id, .. } => {
-                    println!("action_required(elicitation_response): {}", id)
+                    term_println!("action_required(elicitation_response): {}", id)
+                }
+            },
+            MessageContent::Text(text) => match mentions::chip(&text.text) {
+                Some(chip) if message.role == rmcp::model::Role::User => {
+                    render_attachment_chip(&chip)
                 }
+                _ if message.role == rmcp::model::Role::Assistant => {
+                    print_assistant_markdown(&text.text, theme)
+                }
+                _ => print_markdown(&text.text, theme),
             },
-            MessageContent::Text(text) => print_markdown(&text.text, theme),
             MessageContent::ToolRequest(req) => render_tool_request(req, theme, debug),
             MessageContent::ToolResponse(resp) => render_tool_response(resp, theme, debug),
+            MessageContent::Image(image) if message.role == rmcp::model::Role::User => {
+                render_attached_image(&image.data, &image.mime_type);
+            }
             MessageContent::Image(image) => {
-                println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
+                render_image(&image.data, &image.mime_type);
             }
             MessageContent::Thinking(thinking) => {
-                if std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok()
-                    && std::io::stdout().is_terminal()
-                {
-                    println!("\n{}", style("Thinking:").dim().italic());
+                if get_verbosity().shows_thinking() && std::io::stdout().is_terminal() {
+                    term_println!("\n{}", style("Thinking:").dim().italic());
                     print_markdown(&thinking.thinking, theme);
                 }
             }
             MessageContent::RedactedThinking(_) => {
                 // For redacted thinking, print thinking was redacted
-                println!("\n{}", style("Thinking:").dim().italic());
-                print_markdown("Thinking was redacted", theme);
+                if get_verbosity().shows_thinking() {
+                    term_println!("\n{}", style("Thinking:").dim().italic());
+                    print_markdown("Thinking was redacted", theme);
+                }
             }
+            MessageContent::Citations(citations) => render_citations(&citations.citations),
             MessageContent::SystemNotification(notification) => {
                 use goose::conversation::message::SystemNotificationType;
 
@@ -259,31 +1003,95 @@ pub fn render_message(message: &Message, debug: bool) {
                         show_thinking();
                         set_thinking_message(&notification.msg);
                     }
-                    SystemNotificationType::InlineMessage => {
-                        hide_thinking();
-                        println!("\n{}", style(&notification.msg).yellow());
+                    SystemNotificationType::InlineMessage
+                        if get_verbosity().shows_notifications() =>
+                    {
+                        render_inline_notification(notification);
                     }
+                    SystemNotificationType::InlineMessage => {}
                 }
             }
             _ => {
-                println!("WARNING: Message content type could not be rendered");
+                term_println!("WARNING: Message content type could not be rendered");
             }
         }
     }
+    render_link_section();
 
     let _ = std::io::stdout().flush();
 }
 
+/// A file attached to a user message with an `@` mention, shown by name instead of its content
+pub fn render_attachment_chip(chip: &str) {
+    term_println!("  {} {}", style("📎").dim(), style(chip).dim());
+}
+
+/// The chips for the files attached to a user message, with attached images drawn when the
+/// terminal can
+pub fn render_attachment_chips(message: &Message) {
+    for content in &message.content {
+        match content {
+            MessageContent::Image(image) => render_attached_image(&image.data, &image.mime_type),
+            _ => {
+                if let Some(chip) = content.as_text().and_then(mentions::chip) {
+                    render_attachment_chip(&chip);
+                }
+            }
+        }
+    }
+}
+
+/// The files `/attach` added that go with the next message
+pub fn render_pending_attachments(attachments: &[mentions::Attachment]) {
+    if attachments.is_empty() {
+        term_println!(
+            "{}",
+            style("No files attached. /attach <path> adds one to your next message").dim()
+        );
+        return;
+    }
+    term_println!("{}", style("Attached to your next message:").dim());
+    for attachment in attachments {
+        if let Some(chip) = mentions::chip(&attachment.to_text()) {
+            render_attachment_chip(&chip);
+        }
+    }
+}
+
+/// Notices from the agent stand out, except that a tool output was compressed for the model,
+/// which is reported dimmed like the other bookkeeping lines.
+fn render_inline_notification(notification: &SystemNotificationContent) {
+    hide_thinking();
+    match ToolOutputCompression::from_notification(notification) {
+        Some(compression) => term_println!(
+            "  {} {}",
+            style("⇣").cyan(),
+            style(compression.describe()).dim()
+        ),
+        None => term_println!("\n{}", style(&notification.msg).yellow()),
+    }
+}
+
 /// Render a streaming message, using a buffer to accumulate text content
 /// and only render when markdown constructs are complete.
 pub fn render_message_streaming(message: &Message, buffer: &mut MarkdownBuffer, debug: bool) {
     let theme = get_theme();
 
     for content in &message.content {
+        if !matches!(
+            content,
+            MessageContent::Thinking(_) | MessageContent::SystemNotification(_)
+        ) {
+            collapse_thoughts(theme);
+        }
         match content {
+            MessageContent::Text(text) if stream_raw() => {
+                let held = buffer.flush();
+                term_print!("{}{}", held, text.text);
+            }
             MessageContent::Text(text) => {
                 if let Some(safe_content) = buffer.push(&text.text) {
-                    print_markdown(&safe_content, theme);
+                    print_assistant_markdown(&safe_content, theme);
                 }
             }
             MessageContent::ToolRequest(req) => {
@@ -298,33 +1106,36 @@ pub fn render_message_streaming(message: &Message, buffer: &mut MarkdownBuffer,
                 flush_markdown_buffer(buffer, theme);
                 match &action.data {
                     ActionRequiredData::ToolConfirmation { tool_name, .. } => {
-                        println!("action_required(tool_confirmation): {}", tool_name)
+                        term_println!("action_required(tool_confirmation): {}", tool_name)
                     }
                     ActionRequiredData::Elicitation { message, .. } => {
-                        println!("action_required(elicitation): {}", message)
+                        term_println!("action_required(elicitation): {}", message)
                     }
                     ActionRequiredData::ElicitationResponse { id, .. } => {
-                        println!("action_required(elicitation_response): {}", id)
+                        term_println!("action_required(elicitation_response): {}", id)
                     }
                 }
             }
             MessageContent::Image(image) => {
                 flush_markdown_buffer(buffer, theme);
-                println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
+                render_image(&image.data, &image.mime_type);
             }
             MessageContent::Thinking(thinking) => {
-                if std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok()
-                    && std::io::stdout().is_terminal()
-                {
+                if std::io::stdout().is_terminal() {
                     flush_markdown_buffer(buffer, theme);
-                    println!("\n{}", style("Thinking:").dim().italic());
-                    print_markdown(&thinking.thinking, theme);
+                    stream_thought(&thinking.thinking);
                 }
             }
             MessageContent::RedactedThinking(_) => {
+                if get_verbosity().shows_thinking() {
+                    flush_markdown_buffer(buffer, theme);
+                    term_println!("\n{}", style("Thinking:").dim().italic());
+                    print_markdown("Thinking was redacted", theme);
+                }
+            }
+            MessageContent::Citations(citations) => {
                 flush_markdown_buffer(buffer, theme);
-                println!("\n{}", style("Thinking:").dim().italic());
-                print_markdown("Thinking was redacted", theme);
+                render_citations(&citations.citations);
             }
             MessageContent::SystemNotification(notification) => {
                 use goose::conversation::message::SystemNotificationType;
@@ -334,16 +1145,18 @@ pub fn render_message_streaming(message: &Message, buffer: &mut MarkdownBuffer,
                         show_thinking();
                         set_thinking_message(&notification.msg);
                     }
-                    SystemNotificationType::InlineMessage => {
+                    SystemNotificationType::InlineMessage
+                        if get_verbosity().shows_notifications() =>
+                    {
                         flush_markdown_buffer(buffer, theme);
-                        hide_thinking();
-                        println!("\n{}", style(&notification.msg).yellow());
+                        render_inline_notification(notification);
                     }
+                    SystemNotificationType::InlineMessage => {}
                 }
             }
             _ => {
                 flush_markdown_buffer(buffer, theme);
-                println!("WARNING: Message content type could not be rendered");
+                term_println!("WARNING: Message content type could not be rendered");
             }
         }
     }
@@ -351,14 +1164,65 @@ pub fn render_message_streaming(message: &Message, buffer: &mut MarkdownBuffer,
     let _ = std::io::stdout().flush();
 }
 
-pub fn flush_markdown_buffer(buffer: &mut MarkdownBuffer, theme: Theme) {
-    let remaining = buffer.flush();
-    if !remaining.is_empty() {
-        print_markdown(&remaining, theme);
-    }
-}
-
-pub fn flush_markdown_buffer_current_theme(buffer: &mut MarkdownBuffer) {
+/// Adds streamed thinking to a live region showing its last few lines, dim and italic, which
+/// [`collapse_thoughts`] replaces once the answer starts.
+fn stream_thought(chunk: &str) {
+    hide_thinking();
+    let live = term_is_terminal() && terminal::supports_ansi() && !accessible::enabled();
+    let width = Term::stdout()
+        .size_checked()
+        .map(|(_h, w)| (w as usize).saturating_sub(INDENT.len()))
+        .unwrap_or(80);
+    THOUGHTS.with(|thoughts| {
+        let mut thoughts = thoughts.borrow_mut();
+        let thoughts = thoughts.get_or_insert_with(|| {
+            ThoughtStream::new(Instant::now(), thought_stream::visible_lines())
+        });
+        thoughts.push(chunk);
+        if live {
+            let lines = thoughts.tail(width);
+            clear_last_lines(thoughts.drawn);
+            for line in &lines {
+                term_println!("{}{}", INDENT, style(line).dim().italic());
+            }
+            thoughts.drawn = lines.len();
+        }
+    });
+}
+
+fn clear_last_lines(count: usize) {
+    if count > 0 {
+        write_term(&format!("\x1b[{}A\r\x1b[J", count));
+    }
+}
+
+/// Replaces the thinking region with how long the model thought, followed by the whole of
+/// its thinking when `GOOSE_CLI_SHOW_THINKING` or verbose output asks for it.
+fn collapse_thoughts(theme: Theme) {
+    let Some(thoughts) = THOUGHTS.with(|t| t.borrow_mut().take()) else {
+        return;
+    };
+    clear_last_lines(thoughts.drawn);
+    term_println!(
+        "  {} {}",
+        style("✻").dim(),
+        style(thoughts.summary(Instant::now())).dim().italic()
+    );
+    if get_verbosity().shows_thinking() {
+        print_markdown(thoughts.text(), theme);
+    }
+}
+
+pub fn flush_markdown_buffer(buffer: &mut MarkdownBuffer, theme: Theme) {
+    let remaining = buffer.flush();
+    if !remaining.is_empty() {
+        print_assistant_markdown(&remaining, theme);
+    }
+    render_link_section();
+}
+
+pub fn flush_markdown_buffer_current_theme(buffer: &mut MarkdownBuffer) {
+    collapse_thoughts(get_theme());
     flush_markdown_buffer(buffer, get_theme());
 }
 
@@ -367,8 +1231,8 @@ pub fn render_text(text: &str, color: Option<Color>, dim: bool) {
 }
 
 pub fn render_text_no_newlines(text: &str, color: Option<Color>, dim: bool) {
-    if !std::io::stdout().is_terminal() {
-        println!("{}", text);
+    if !term_is_terminal() {
+        term_println!("{}", text);
         return;
     }
     let mut styled_text = style(text);
@@ -380,11 +1244,11 @@ pub fn render_text_no_newlines(text: &str, color: Option<Color>, dim: bool) {
     } else {
         styled_text = styled_text.green();
     }
-    print!("{}", styled_text);
+    term_print!("{}", styled_text);
 }
 
 pub fn render_enter_plan_mode() {
-    println!(
+    term_println!(
         "\n{} {}\n",
         style("Entering plan mode.").green().bold(),
         style("You can provide instructions to create a plan and then act on it. To exit early, type /endplan")
@@ -393,24 +1257,79 @@ pub fn render_enter_plan_mode() {
     );
 }
 
-pub fn render_act_on_plan() {
-    println!(
+pub fn render_act_on_plan(plan: Option<&PlanChecklist>) {
+    term_println!(
         "\n{}\n",
         style("Exiting plan mode and acting on the above plan")
             .green()
             .bold(),
     );
+    if let Some(plan) = plan {
+        render_plan_checklist(plan);
+    }
+}
+
+/// The plan's steps with the progress the agent has reported, reprinted as it changes.
+pub fn render_plan_checklist(plan: &PlanChecklist) {
+    let done = plan
+        .steps()
+        .iter()
+        .filter(|step| step.status == StepStatus::Done)
+        .count();
+    term_println!(
+        "  {}",
+        style(format!("plan · {}/{} done", done, plan.steps().len())).dim()
+    );
+    for (i, step) in plan.steps().iter().enumerate() {
+        let (mark, text) = match step.status {
+            StepStatus::Pending => (style("☐").dim(), style(&step.text).dim()),
+            StepStatus::InProgress => (style("▶").cyan(), style(&step.text).cyan().bold()),
+            StepStatus::Done => (style("☑").green(), style(&step.text)),
+            StepStatus::Failed => (style("☒").red(), style(&step.text).red()),
+        };
+        term_println!("  {} {}. {}", mark, i + 1, text);
+    }
+    term_println!();
 }
 
 pub fn render_exit_plan_mode() {
-    println!("\n{}\n", style("Exiting plan mode.").green().bold());
+    term_println!("\n{}\n", style("Exiting plan mode.").green().bold());
 }
 
 pub fn goose_mode_message(text: &str) {
-    println!("\n{}", style(text).yellow(),);
+    term_println!("\n{}", style(text).yellow(),);
 }
 
 fn render_tool_request(req: &ToolRequest, theme: Theme, debug: bool) {
+    let redacted = redacted_request(req);
+    let req = redacted.as_ref().unwrap_or(req);
+    let number = TOOL_CALLS.with(|log| log.borrow_mut().record_request(req));
+    if let Ok(call) = &req.tool_call {
+        TOOL_FRAMES.with(|f| f.borrow_mut().add_request(&req.id, &call.name));
+        TOOL_TIMEOUTS.with(|t| t.borrow_mut().start(&req.id, &call.name, Instant::now()));
+    }
+    let verbosity = get_verbosity();
+    if !verbosity.shows_tool_calls() {
+        return;
+    }
+    if SHOW_TOOL_IDS.with(Cell::get) {
+        HEADER_TOOL_NUMBER.with(|n| n.set(Some(number)));
+    }
+    if !verbosity.shows_tool_details() {
+        if let Some(call) = req.tool_call.as_ref().ok().filter(|c| c.name != "load") {
+            print_tool_header(call);
+            term_println!();
+        }
+        HEADER_TOOL_NUMBER.with(Cell::take);
+        return;
+    }
+
+    if let Ok(call) = &req.tool_call {
+        if tool_output_diff_enabled() {
+            REPEATED_OUTPUT.with(|r| r.borrow_mut().record_request(&req.id, call));
+        }
+    }
+
     match &req.tool_call {
         Ok(call) => match call.name.to_string().as_str() {
             "developer__text_editor" => render_text_editor_request(call, debug),
@@ -420,17 +1339,99 @@ fn render_tool_request(req: &ToolRequest, theme: Theme, debug: bool) {
             "subagent" => render_delegate_request(call, debug),
             "todo__write" => render_todo_request(call, debug),
             "load" => {}
-            _ => render_default_request(call, debug),
+            _ => render_default_request(call, req.tool_meta.as_ref(), debug),
         },
         Err(e) => print_markdown(&e.to_string(), theme),
     }
+    // not every renderer prints a header (e.g. `load`)
+    HEADER_TOOL_NUMBER.with(Cell::take);
+    note_truncation(Some(number));
+}
+
+fn note_truncation(number: Option<usize>) {
+    if TRUNCATED.with(Cell::take) {
+        if let Some(number) = number {
+            LAST_TRUNCATED
+                .with(|last| *last.borrow_mut() = Some(TruncatedOutput::ToolCall(number)));
+        }
+    }
+}
+
+fn print_truncation_marker(truncated: &Truncated) {
+    TRUNCATED.with(|t| t.set(true));
+    term_println!("{}{}", INDENT, style(truncated.marker()).dim());
+}
+
+/// Output that was cut short to fit, which /expand shows in full
+enum TruncatedOutput {
+    /// A tool call, by its number in the tool call log
+    ToolCall(usize),
+    /// A markdown table from a response, as the model wrote it
+    Table(String),
+}
+
+/// The last output that was shown truncated, in full as plain text for the pager.
+pub fn last_truncated_output() -> Option<String> {
+    LAST_TRUNCATED.with(|last| match last.borrow().as_ref()? {
+        TruncatedOutput::ToolCall(number) => tool_call_text(*number),
+        TruncatedOutput::Table(table) => Some(table.clone()),
+    })
+}
+
+/// A tool call and its response in full as plain text
+fn tool_call_text(number: usize) -> Option<String> {
+    TOOL_CALLS.with(|log| {
+        let log = log.borrow();
+        let entry = log.get(number)?;
+        let call = entry.request.tool_call.as_ref().ok()?;
+        let mut text = format!("#{} {}\n", number, call.name);
+        for (key, value) in call.arguments.iter().flatten() {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => serde_json::to_string_pretty(other).unwrap_or_default(),
+            };
+            text.push_str(&format!("\n{}:\n{}\n", key, value.trim_end()));
+        }
+        match entry.response.as_ref().map(|r| &r.tool_result) {
+            Some(Ok(result)) => {
+                text.push_str("\n── output ──\n");
+                for content in &result.content {
+                    if let Some(t) = content.as_text() {
+                        text.push_str(&format!("{}\n", t.text.trim_end()));
+                    }
+                }
+            }
+            Some(Err(e)) => text.push_str(&format!("\n── error ──\n{}\n", e.message)),
+            None => text.push_str("\n(no response yet)\n"),
+        }
+        Some(text)
+    })
 }
 
 fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
+    let redacted = redacted_response(resp);
+    let resp = redacted.as_ref().unwrap_or(resp);
+    let number = TOOL_CALLS.with(|log| log.borrow_mut().record_response(resp));
+    let frame = TOOL_FRAMES.with(|f| f.borrow_mut().finish(&resp.id));
+    let call = TOOL_TIMEOUTS.with(|t| t.borrow_mut().finish(&resp.id));
+    if !get_verbosity().shows_tool_details() {
+        return;
+    }
+    if let Some(frame) = &frame {
+        let elapsed = resp.tool_result.as_ref().ok().and_then(tool_elapsed);
+        print_frame_header(frame, elapsed);
+    }
+    let mut output_lines = None;
+    if let Some(number) = number.filter(|_| SHOW_TOOL_IDS.with(Cell::get)) {
+        term_println!("  {}", style(format!("↳ #{}", number)).dim());
+    }
     let config = Config::global();
 
     match &resp.tool_result {
         Ok(result) => {
+            let mut texts = Vec::new();
+            let mut resources = Vec::new();
+            let mut links = Vec::new();
             for content in &result.content {
                 if let Some(audience) = content.audience() {
                     if !audience.contains(&rmcp::model::Role::User) {
@@ -443,56 +1444,515 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
                     .ok()
                     .unwrap_or(DEFAULT_MIN_PRIORITY);
 
+                // resources are rarely annotated, but are shown unless marked as unimportant
+                let is_resource =
+                    content.as_resource().is_some() || content.as_resource_link().is_some();
                 if content
                     .priority()
                     .is_some_and(|priority| priority < min_priority)
-                    || (content.priority().is_none() && !debug)
+                    || (content.priority().is_none() && !debug && !is_resource)
                 {
                     continue;
                 }
 
                 if debug {
-                    println!("{:#?}", content);
+                    term_println!("{:#?}", content);
                 } else if let Some(text) = content.as_text() {
-                    print_markdown(&text.text, theme);
+                    texts.push(text.text.as_str());
+                } else if let Some(resource) = content.as_resource() {
+                    resources.push(&resource.resource);
+                } else if let Some(link) = content.as_resource_link() {
+                    links.push(link);
                 }
             }
+            output_lines = Some(texts.iter().map(|text| text.lines().count()).sum());
+            if !texts.is_empty() && !send_to_tool_pane(number, &resp.id, &texts) {
+                render_tool_output(&resp.id, &texts, theme);
+            }
+            resources
+                .into_iter()
+                .for_each(|resource| render_resource(resource, theme));
+            links.into_iter().for_each(render_resource_link);
+        }
+        Err(e) => match tool_timed_out(e) {
+            Some(limit) => render_tool_timeout(call.as_ref(), limit),
+            None => print_markdown(&e.to_string(), theme),
+        },
+    }
+    note_truncation(number);
+    if accessible::enabled() {
+        term_println!("{}", accessible::tool_finished(output_lines));
+    } else if frame.is_some() {
+        term_println!("  {}", style("└─").dim());
+    }
+}
+
+/// An embedded resource from a tool result: where it is from, then a highlighted preview for
+/// text, or for binary content its size and how to save it.
+fn render_resource(resource: &ResourceContents, theme: Theme) {
+    match resource {
+        ResourceContents::TextResourceContents {
+            uri,
+            mime_type,
+            text,
+            ..
+        } => {
+            print_resource_header(uri, mime_type.as_deref(), None);
+            let max_lines = if get_show_full_tool_output() {
+                usize::MAX
+            } else {
+                resources::PREVIEW_LINES
+            };
+            let (shown, hidden) = resources::preview(text, max_lines);
+            let language = resources::language_for(uri, mime_type.as_deref());
+            print_markdown(&format!("```{}\n{}\n```", language, shown), theme);
+            if hidden > 0 {
+                term_println!(
+                    "    {}",
+                    style(format!("… {} more lines, /r shows them all", hidden)).dim()
+                );
+            }
+        }
+        ResourceContents::BlobResourceContents {
+            uri,
+            mime_type,
+            blob,
+            ..
+        } => {
+            let binary = BinaryResource {
+                uri: uri.clone(),
+                mime_type: mime_type.clone(),
+                blob: blob.clone(),
+            };
+            print_resource_header(uri, mime_type.as_deref(), Some(binary.size()));
+            if mime_type
+                .as_deref()
+                .is_some_and(|m| m.starts_with("image/"))
+            {
+                render_image(blob, mime_type.as_deref().unwrap_or_default());
+            }
+            let number = BINARY_RESOURCES.with(|r| {
+                let mut r = r.borrow_mut();
+                r.push(binary);
+                r.len()
+            });
+            term_println!(
+                "    {}",
+                style(format!("binary · /save {} to write it to a file", number)).dim()
+            );
+        }
+    }
+}
+
+fn render_resource_link(link: &RawResource) {
+    let name = link.title.as_deref().unwrap_or(&link.name);
+    let mut details = vec![link.uri.clone()];
+    details.extend(link.mime_type.clone());
+    details.extend(link.size.map(|size| resources::format_size(size as usize)));
+    term_println!(
+        "  {} {} {}",
+        style("↗").cyan(),
+        name,
+        style(format!("· {}", details.join(" · "))).dim()
+    );
+    if let Some(description) = &link.description {
+        term_println!("    {}", style(description).dim());
+    }
+}
+
+fn print_resource_header(uri: &str, mime_type: Option<&str>, size: Option<usize>) {
+    let shown_uri = if hyperlink::enabled(term_is_terminal()) {
+        hyperlink::link(uri, uri)
+    } else {
+        uri.to_string()
+    };
+    let mut details: Vec<String> = mime_type.map(str::to_string).into_iter().collect();
+    details.extend(size.map(resources::format_size));
+    term_println!(
+        "  {} {}{}",
+        style("▤").cyan(),
+        shown_uri,
+        if details.is_empty() {
+            String::new()
+        } else {
+            style(format!(" · {}", details.join(" · ")))
+                .dim()
+                .to_string()
+        }
+    );
+}
+
+/// A binary resource shown earlier, by the number `/save` was given, or the latest one
+pub fn binary_resource(number: Option<usize>) -> Option<BinaryResource> {
+    BINARY_RESOURCES.with(|r| {
+        let r = r.borrow();
+        match number {
+            Some(number) => r.get(number.checked_sub(1)?).cloned(),
+            None => r.last().cloned(),
+        }
+    })
+}
+
+/// Opens the block for one of several tool calls that ran at the same time, with what it
+/// printed while running.
+fn print_frame_header(frame: &Frame, elapsed: Option<Duration>) {
+    let (tool, extension) = split_tool_name(&frame.name);
+    if accessible::enabled() {
+        term_println!();
+        term_println!(
+            "{}",
+            accessible::tool_batch_started(frame.index, frame.total, &tool, elapsed)
+        );
+        frame.logs.iter().for_each(|line| term_println!("{}", line));
+        return;
+    }
+    let mut label = format!(
+        "  {} {} {}",
+        style("┌─").dim(),
+        style(format!("{}/{}", frame.index, frame.total)).dim(),
+        style(tool).cyan()
+    );
+    if !extension.is_empty() {
+        label.push_str(&format!(" {}", style(extension).magenta().dim()));
+    }
+    if let Some(elapsed) = elapsed {
+        label.push_str(&format!(
+            " {}",
+            style(format!("· {:.1}s", elapsed.as_secs_f64())).dim()
+        ));
+    }
+    term_println!();
+    term_println!("{}", label);
+    for line in &frame.logs {
+        term_println!("  {} {}", style("│").dim(), line);
+    }
+}
+
+/// Keeps a line a tool printed, to show what it got done if it times out.
+pub fn record_tool_output(request_id: &str, line: &str) {
+    TOOL_TIMEOUTS.with(|t| t.borrow_mut().record_output(request_id, line));
+}
+
+/// Doesn't count the time a call waited for approval against its timeout.
+pub fn restart_tool_clock(request_id: &str) {
+    TOOL_TIMEOUTS.with(|t| t.borrow_mut().restart(request_id, Instant::now()));
+}
+
+pub fn tool_timeouts_running() -> bool {
+    TOOL_TIMEOUTS.with(|t| !t.borrow().is_empty())
+}
+
+/// Counts down in the thinking indicator once a tool call gets close to its timeout.
+pub fn render_tool_timeout_countdown() {
+    if let Some(countdown) = TOOL_TIMEOUTS.with(|t| t.borrow().countdown(Instant::now())) {
+        if !is_showing_thinking() {
+            show_thinking();
+        }
+        set_thinking_message(&style(countdown).yellow().to_string());
+    }
+}
+
+/// A call stopped by its timeout, with the last lines it printed before.
+fn render_tool_timeout(call: Option<&FinishedCall>, limit: Duration) {
+    let name = call.map_or("the tool", |call| call.name.as_str());
+    term_println!(
+        "  {} {}",
+        style("⏱").red(),
+        style(format!(
+            "{} timed out after {}s and was stopped",
+            name,
+            limit.as_secs()
+        ))
+        .red()
+        .bold()
+    );
+    let Some(call) = call.filter(|call| !call.output.is_empty()) else {
+        term_println!("    {}", style("no output before it was stopped").dim());
+        return;
+    };
+    term_println!("    {}", style("last output:").dim());
+    for line in &call.output {
+        term_println!("    {} {}", style("│").dim(), line);
+    }
+}
+
+/// Holds back a line a tool printed while it runs alongside others, to be shown with its
+/// result. Returns false when the line should be printed now.
+pub fn hold_tool_log(request_id: &str, line: &str) -> bool {
+    TOOL_FRAMES.with(|f| f.borrow_mut().hold_log(request_id, line))
+}
+
+/// Closes the tool pane and removes its log, which would otherwise be left to the thread's
+/// destructors and skipped when the process exits.
+pub fn close_tool_pane() {
+    TOOL_PANE.with(|pane| pane.borrow_mut().take());
+}
+
+/// Sends long output to the tool pane when `GOOSE_CLI_TOOL_PANE` is on, leaving a note in the
+/// chat. Returns false when the output should be printed here instead.
+fn send_to_tool_pane(number: Option<usize>, request_id: &str, texts: &[&str]) -> bool {
+    let text = texts.join("\n");
+    TOOL_PANE.with(|pane| {
+        let mut pane = pane.borrow_mut();
+        let Some(tool_pane) = pane.as_mut().filter(|p| p.wants(&text)) else {
+            return false;
+        };
+        let label = number
+            .and_then(|n| {
+                TOOL_CALLS.with(|log| {
+                    let log = log.borrow();
+                    let call = log.get(n)?.request.tool_call.as_ref().ok()?;
+                    Some(format!("#{} {}", n, call.name))
+                })
+            })
+            .unwrap_or_else(|| request_id.to_string());
+        match tool_pane.send(&label, &text) {
+            Ok(()) => {
+                term_println!(
+                    "    {}",
+                    style(format!(
+                        "{} lines of output in the {} pane · {}",
+                        text.lines().count(),
+                        tool_pane.multiplexer().name(),
+                        tool_pane.log_path().display()
+                    ))
+                    .dim()
+                );
+                true
+            }
+            Err(e) => {
+                term_println!(
+                    "    {}",
+                    style(format!(
+                        "tool pane unavailable ({}), showing output here",
+                        e
+                    ))
+                    .dim()
+                );
+                *pane = None;
+                false
+            }
+        }
+    })
+}
+
+/// Prints tool output, or with `GOOSE_CLI_TOOL_OUTPUT_DIFF` only what changed since the last
+/// identical call.
+fn render_tool_output(request_id: &str, texts: &[&str], theme: Theme) {
+    let change = if tool_output_diff_enabled() {
+        REPEATED_OUTPUT.with(|r| r.borrow_mut().compare(request_id, &texts.join("\n")))
+    } else {
+        OutputChange::New
+    };
+    match change {
+        OutputChange::New => texts
+            .iter()
+            .for_each(|text| print_tool_output_text(text, theme)),
+        OutputChange::Unchanged => {
+            term_println!("    {}", style("output unchanged since the last run").dim());
+        }
+        OutputChange::Changed(diff) => {
+            term_println!("    {}", style("changes since the last run:").dim());
+            print_diff(&diff, false);
+        }
+    }
+}
+
+/// Tool output is shown in full unless `GOOSE_CLI_TRUNCATION` sets an output limit.
+fn print_tool_output_text(text: &str, theme: Theme) {
+    let limit = TRUNCATION
+        .with(|t| t.output)
+        .filter(|_| !get_show_full_tool_output());
+    match limit.and_then(|limit| limit.apply(text)) {
+        Some(truncated) => {
+            print_output_text(truncated.shown, theme);
+            print_truncation_marker(&truncated);
+        }
+        None => print_output_text(text, theme),
+    }
+}
+
+/// Output styled with escape sequences, like colored `cargo` or `pytest` output, is passed
+/// through as it is rather than rendered as markdown, which would mangle it. The codes are
+/// stripped where they can't be shown. `GOOSE_CLI_WRAP_TOOL_OUTPUT` breaks its long lines at
+/// the terminal width.
+fn print_output_text(text: &str, theme: Theme) {
+    if !ansi_output::has_ansi(text) {
+        print_markdown(text, theme);
+        return;
+    }
+    if !(term_is_terminal() && terminal::supports_ansi() && env_no_color()) {
+        print_markdown(&console::strip_ansi_codes(text), theme);
+        return;
+    }
+
+    let wrap_width = Config::global()
+        .get_param::<bool>("GOOSE_CLI_WRAP_TOOL_OUTPUT")
+        .unwrap_or(false)
+        .then(|| Term::stdout().size_checked())
+        .flatten()
+        .map(|(_h, w)| w as usize);
+    let sanitized = ansi_output::sanitize(text);
+    for line in sanitized.lines() {
+        match wrap_width {
+            Some(width) => ansi_output::wrap(line, width)
+                .iter()
+                .for_each(|piece| term_println!("{}", piece)),
+            None => term_println!("{}", line),
         }
-        Err(e) => print_markdown(&e.to_string(), theme),
     }
 }
 
+fn tool_output_diff_enabled() -> bool {
+    !get_show_full_tool_output()
+        && Config::global()
+            .get_param::<bool>("GOOSE_CLI_TOOL_OUTPUT_DIFF")
+            .unwrap_or(false)
+}
+
 pub fn render_error(message: &str) {
-    println!("\n  {} {}\n", style("error:").red().bold(), message);
+    term_println!("\n  {} {}\n", palette().error_prefix(), message);
+}
+
+/// Errors that end a turn go to stderr, so they stay out of output that is piped or captured.
+pub fn render_session_error(error: &SessionError) {
+    eprintln!("\n  {} {}", palette().error_prefix(), error);
+    if let Some(hint) = error.hint() {
+        eprintln!("  {} {}", style("hint:").cyan().bold(), style(hint).dim());
+    }
+    eprintln!();
 }
 
 pub fn render_prompts(prompts: &HashMap<String, Vec<String>>) {
-    println!();
+    term_println!();
     for (extension, prompts) in prompts {
-        println!(" {}", style(extension).green());
+        term_println!(" {}", style(extension).green());
         for prompt in prompts {
-            println!("  - {}", style(prompt).cyan());
+            term_println!("  - {}", style(prompt).cyan());
+        }
+    }
+    term_println!();
+}
+
+/// The tools `/tools <filter>` is about: those of the extension it names, or the tools it
+/// names, by their full name or without the extension prefix
+pub enum ToolSelection<'a> {
+    Extension(Vec<&'a Tool>),
+    Tools(Vec<&'a Tool>),
+}
+
+pub fn select_tools<'a>(tools: &'a [Tool], filter: &str) -> ToolSelection<'a> {
+    let of_extension: Vec<&Tool> = tools
+        .iter()
+        .filter(|tool| split_tool_name(&tool.name).1 == filter)
+        .collect();
+    if !of_extension.is_empty() {
+        return ToolSelection::Extension(of_extension);
+    }
+    ToolSelection::Tools(
+        tools
+            .iter()
+            .filter(|tool| tool.name == filter || split_tool_name(&tool.name).0 == filter)
+            .collect(),
+    )
+}
+
+/// Tools listed by extension, with the first line of their description
+pub fn render_tools(tools: &[&Tool]) {
+    let mut by_extension: BTreeMap<String, Vec<(String, &Tool)>> = BTreeMap::new();
+    for tool in tools {
+        let (name, extension) = split_tool_name(&tool.name);
+        by_extension
+            .entry(extension)
+            .or_default()
+            .push((name, tool));
+    }
+    term_println!();
+    for (extension, mut tools) in by_extension {
+        tools.sort_by(|a, b| a.0.cmp(&b.0));
+        term_println!(" {}", style(&extension).green());
+        let width = tools.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, tool) in tools {
+            let description = tool
+                .description
+                .as_deref()
+                .and_then(|d| d.trim().lines().next())
+                .unwrap_or_default();
+            term_println!(
+                "  {:<width$}  {}",
+                style(&name).cyan(),
+                style(safe_truncate(description, 80)).dim(),
+                width = width
+            );
+        }
+    }
+    term_println!();
+}
+
+/// One tool's description, and its parameters or with `schema` its input schema in full
+pub fn render_tool(tool: &Tool, schema: bool) {
+    let (name, extension) = split_tool_name(&tool.name);
+    term_println!();
+    term_println!(
+        "  {} {} {}",
+        palette().tool_bullet(),
+        style(&name).bold(),
+        style(&extension).magenta().dim()
+    );
+    if let Some(description) = &tool.description {
+        for line in description.trim().lines() {
+            term_println!("    {}", line);
+        }
+    }
+    let input_schema = tool.input_schema.as_ref();
+    if schema {
+        term_println!("\n    {}", style("input schema:").dim());
+        print_params(&Some(input_schema.clone()), 2, true);
+    } else if let Some(properties) = input_schema.get("properties").and_then(Value::as_object) {
+        let required: Vec<&str> = input_schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        term_println!();
+        for (param, spec) in properties {
+            let kind = spec.get("type").and_then(Value::as_str).unwrap_or("any");
+            let marker = if required.contains(&param.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            term_println!(
+                "    {}{} {}",
+                style(param).dim(),
+                style(marker).dim(),
+                style(kind).cyan()
+            );
         }
+        term_println!(
+            "\n    {}",
+            style(format!("/tools {} --schema for the input schema", name)).dim()
+        );
     }
-    println!();
+    term_println!();
 }
 
 pub fn render_prompt_info(info: &PromptInfo) {
-    println!();
+    term_println!();
     if let Some(ext) = &info.extension {
-        println!(" {}: {}", style("Extension").green(), ext);
+        term_println!(" {}: {}", style("Extension").green(), ext);
     }
-    println!(" Prompt: {}", style(&info.name).cyan().bold());
+    term_println!(" Prompt: {}", style(&info.name).cyan().bold());
     if let Some(desc) = &info.description {
-        println!("\n {}", desc);
+        term_println!("\n {}", desc);
     }
     render_arguments(info);
-    println!();
+    term_println!();
 }
 
 fn render_arguments(info: &PromptInfo) {
     if let Some(args) = &info.arguments {
-        println!("\n Arguments:");
+        term_println!("\n Arguments:");
         for arg in args {
             let required = arg.required.unwrap_or(false);
             let req_str = if required {
@@ -501,7 +1961,7 @@ fn render_arguments(info: &PromptInfo) {
                 style("(optional)").dim()
             };
 
-            println!(
+            term_println!(
                 "  {} {} {}",
                 style(&arg.name).yellow(),
                 req_str,
@@ -512,49 +1972,128 @@ fn render_arguments(info: &PromptInfo) {
 }
 
 pub fn render_extension_success(name: &str) {
-    println!();
-    println!(
-        "  {} extension `{}`",
-        style("added").green(),
-        style(name).cyan(),
+    term_println!();
+    term_println!(
+        "  {}",
+        tr_with(
+            "extension-added",
+            &[
+                ("status", &style(tr("status-added")).green().to_string()),
+                ("name", &style(name).cyan().to_string()),
+            ]
+        )
     );
-    println!();
+    term_println!();
+}
+
+/// Lines of an extension's stderr shown under its error outside debug mode
+const COLLAPSED_STDERR_LINES: usize = 5;
+
+/// The stderr lines to show, and how many earlier ones are left out
+fn stderr_lines(stderr: &str, debug: bool) -> (Vec<&str>, usize) {
+    let lines: Vec<&str> = stderr.lines().collect();
+    let hidden = if debug {
+        0
+    } else {
+        lines.len().saturating_sub(COLLAPSED_STDERR_LINES)
+    };
+    (lines[hidden..].to_vec(), hidden)
 }
 
-pub fn render_extension_error(name: &str, error: &str) {
-    println!();
-    println!(
-        "  {} to add extension {}",
-        style("failed").red(),
-        style(name).red()
+/// Shows why an extension failed to be added. When its process quit during startup, the
+/// exit code and the end of its stderr go under the error, all of the kept stderr in debug
+/// mode and its last few lines otherwise.
+pub fn render_extension_error(name: &str, error: &anyhow::Error, debug: bool) {
+    term_println!();
+    term_println!(
+        "  {}",
+        tr_with(
+            "extension-failed",
+            &[
+                ("status", &style(tr("status-failed")).red().to_string()),
+                ("name", &style(name).red().to_string()),
+            ]
+        )
     );
-    println!();
-    println!("{}", style(error).dim());
-    println!();
+    term_println!();
+    let Some(exit) = error.chain().find_map(|e| e.downcast_ref::<ProcessExit>()) else {
+        term_println!("{}", style(error).dim());
+        term_println!();
+        return;
+    };
+
+    match std::error::Error::source(exit) {
+        Some(source) => term_println!("{}", style(source).dim()),
+        None => term_println!("{}", style(error).dim()),
+    }
+    let exit_code = match exit.exit_code() {
+        Some(code) => tr_with("extension-exit-code", &[("code", &code.to_string())]),
+        None => tr("extension-exit-unknown"),
+    };
+    term_println!("  {}", style(exit_code).yellow());
+    if exit.stderr_tail().is_empty() {
+        term_println!("  {}", style(tr("extension-no-stderr")).dim());
+        term_println!();
+        return;
+    }
+    term_println!("  {}", style(tr("extension-stderr")).dim());
+    let (lines, hidden) = stderr_lines(exit.stderr_tail(), debug);
+    if hidden > 0 {
+        term_println!(
+            "  {} {}",
+            style("│").dim(),
+            style(tr_with(
+                "extension-stderr-hidden",
+                &[("count", &hidden.to_string())]
+            ))
+            .dim()
+            .italic()
+        );
+    }
+    for line in lines {
+        term_println!("  {} {}", style("│").dim(), line);
+    }
+    term_println!();
 }
 
 pub fn render_builtin_success(names: &str) {
-    println!();
-    println!(
-        "  {} builtin{}: {}",
-        style("added").green(),
-        if names.contains(',') { "s" } else { "" },
-        style(names).cyan()
+    term_println!();
+    term_println!(
+        "  {}",
+        tr_with(
+            if names.contains(',') {
+                "builtins-added"
+            } else {
+                "builtin-added"
+            },
+            &[
+                ("status", &style(tr("status-added")).green().to_string()),
+                ("names", &style(names).cyan().to_string()),
+            ]
+        )
     );
-    println!();
+    term_println!();
 }
 
 pub fn render_builtin_error(names: &str, error: &str) {
-    println!();
-    println!(
-        "  {} to add builtin{}: {}",
-        style("failed").red(),
-        if names.contains(',') { "s" } else { "" },
-        style(names).red()
+    term_println!();
+    term_println!(
+        "  {}",
+        tr_with(
+            if names.contains(',') {
+                "builtins-failed"
+            } else {
+                "builtin-failed"
+            },
+            &[
+                ("status", &style(tr("status-failed")).red().to_string()),
+                ("names", &style(names).red().to_string()),
+            ]
+        )
     );
-    println!();
-    println!("{}", style(error).dim());
-    println!();
+    term_println!();
+    term_println!("{}", style(error).dim());
+    term_println!();
 }
 
 fn render_text_editor_request(call: &CallToolRequestParams, debug: bool) {
@@ -562,32 +2101,135 @@ fn render_text_editor_request(call: &CallToolRequestParams, debug: bool) {
 
     if let Some(args) = &call.arguments {
         if let Some(Value::String(path)) = args.get("path") {
-            println!(
+            term_println!(
                 "    {} {}",
                 style("path").dim(),
-                style(shorten_path(path, debug)).dim()
+                style(link_path(path, &shorten_path(path, debug))).dim()
             );
         }
 
-        if let Some(args) = &call.arguments {
-            let mut other_args = serde_json::Map::new();
-            for (k, v) in args {
-                if k != "path" {
-                    other_args.insert(k.clone(), v.clone());
-                }
+        let change = text_editor_change(args);
+
+        let mut other_args = serde_json::Map::new();
+        for (k, v) in args {
+            let consumed = change.is_some() && DIFF_ARGS.contains(&k.as_str());
+            if k != "path" && !consumed {
+                other_args.insert(k.clone(), v.clone());
             }
-            if !other_args.is_empty() {
-                print_params(&Some(other_args), 1, debug);
+        }
+        if !other_args.is_empty() {
+            print_params(&Some(other_args), 1, debug);
+        }
+
+        if let Some(change) = change {
+            let path = args.get("path").and_then(Value::as_str).unwrap_or("");
+            render_text_editor_change(path, change, debug);
+        }
+    }
+    term_println!();
+}
+
+const DIFF_ARGS: [&str; 4] = ["old_str", "new_str", "file_text", "diff"];
+const MAX_DIFF_LINES: usize = 200;
+
+enum TextEditorChange {
+    Patch(String),
+    Replace { old: String, new: String },
+}
+
+/// Works out what a str_replace or write call is about to change, if it can be shown as a diff.
+fn text_editor_change(args: &JsonObject) -> Option<TextEditorChange> {
+    let str_arg = |key: &str| args.get(key).and_then(Value::as_str);
+    match str_arg("command")? {
+        "str_replace" => {
+            if let Some(patch) = str_arg("diff") {
+                return Some(TextEditorChange::Patch(patch.to_string()));
             }
+            Some(TextEditorChange::Replace {
+                old: str_arg("old_str")?.to_string(),
+                new: str_arg("new_str")?.to_string(),
+            })
+        }
+        "write" => Some(TextEditorChange::Replace {
+            old: str_arg("path")
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .unwrap_or_default(),
+            new: str_arg("file_text")?.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+fn render_text_editor_change(path: &str, change: TextEditorChange, debug: bool) {
+    let (old, new) = match change {
+        TextEditorChange::Patch(patch) => {
+            print_diff(&patch, debug);
+            return;
         }
+        TextEditorChange::Replace { old, new } => (old, new),
+    };
+
+    let (added, removed) = diff::diff_stats(&old, &new);
+    term_println!(
+        "    {} {} {}",
+        style("diff").dim(),
+        style(format!("+{}", added)).green(),
+        style(format!("-{}", removed)).red()
+    );
+
+    let max_lines = (!debug && !get_show_full_tool_output()).then_some(MAX_DIFF_LINES);
+    let width = Term::stdout()
+        .size_checked()
+        .map(|(_h, w)| w as usize)
+        .unwrap_or(80);
+
+    // too narrow a terminal for two columns gets the unified diff
+    let side_by_side = diff::DiffStyle::from_config() == diff::DiffStyle::SideBySide
+        && term_is_terminal()
+        && diff::render_side_by_side(&old, &new, width, max_lines);
+    if !side_by_side {
+        if let Some(patch) = diff::unified_diff(&shorten_path(path, debug), &old, &new) {
+            print_diff(&patch, debug);
+        }
+    }
+}
+
+fn print_diff(patch: &str, debug: bool) {
+    let line_count = patch.lines().count();
+    let truncated = !debug && !get_show_full_tool_output() && line_count > MAX_DIFF_LINES;
+    let content = if truncated {
+        let mut head = patch
+            .lines()
+            .take(MAX_DIFF_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        head.push('\n');
+        head
+    } else {
+        patch.to_string()
+    };
+
+    print_highlighted(
+        bat::PrettyPrinter::new()
+            .input(bat::Input::from_bytes(content.as_bytes()))
+            .theme(get_theme().as_str())
+            .colored_output(env_no_color())
+            .language("Diff")
+            .wrapping_mode(WrappingMode::NoWrapping(true)),
+    );
+
+    if truncated {
+        term_println!(
+            "    {}",
+            style(format!("... {} more lines", line_count - MAX_DIFF_LINES)).dim()
+        );
     }
-    println!();
 }
 
 fn render_shell_request(call: &CallToolRequestParams, debug: bool) {
     print_tool_header(call);
     print_params(&call.arguments, 1, debug);
-    println!();
+    term_println!();
 }
 
 fn render_execute_code_request(call: &CallToolRequestParams, debug: bool) {
@@ -599,15 +2241,15 @@ fn render_execute_code_request(call: &CallToolRequestParams, debug: bool) {
         .filter(|arr| !arr.is_empty());
 
     let Some(tool_graph) = tool_graph else {
-        return render_default_request(call, debug);
+        return render_default_request(call, None, debug);
     };
 
     let count = tool_graph.len();
     let plural = if count == 1 { "" } else { "s" };
-    println!();
-    println!(
+    term_println!();
+    term_println!(
         "  {} {} {} tool call{}",
-        style("▸").dim(),
+        palette().tool_bullet(),
         style("execute").dim(),
         style(count).dim(),
         plural,
@@ -635,7 +2277,7 @@ fn render_execute_code_request(call: &CallToolRequestParams, debug: bool) {
         } else {
             format!(" (uses {})", deps.join(", "))
         };
-        println!(
+        term_println!(
             "    {}. {} {}{}",
             style(i + 1).dim(),
             style(tool).dim(),
@@ -651,10 +2293,10 @@ fn render_execute_code_request(call: &CallToolRequestParams, debug: bool) {
         .and_then(Value::as_str)
         .filter(|c| !c.is_empty());
     if code.is_some_and(|_| debug) {
-        println!("{}", style(code.unwrap_or_default()).green());
+        term_println!("{}", style(code.unwrap_or_default()).green());
     }
 
-    println!();
+    term_println!();
 }
 
 fn render_delegate_request(call: &CallToolRequestParams, debug: bool) {
@@ -662,7 +2304,7 @@ fn render_delegate_request(call: &CallToolRequestParams, debug: bool) {
 
     if let Some(args) = &call.arguments {
         if let Some(Value::String(source)) = args.get("source") {
-            println!("    {} {}", style("source").dim(), style(source).dim());
+            term_println!("    {} {}", style("source").dim(), style(source).dim());
         }
 
         if let Some(Value::String(instructions)) = args.get("instructions") {
@@ -671,7 +2313,7 @@ fn render_delegate_request(call: &CallToolRequestParams, debug: bool) {
             } else {
                 instructions.clone()
             };
-            println!(
+            term_println!(
                 "    {} {}",
                 style("instructions").dim(),
                 style(display).dim()
@@ -679,7 +2321,7 @@ fn render_delegate_request(call: &CallToolRequestParams, debug: bool) {
         }
 
         if let Some(Value::Object(params)) = args.get("parameters") {
-            println!("    {}:", style("parameters").dim());
+            term_println!("    {}:", style("parameters").dim());
             print_params(&Some(params.clone()), 2, debug);
         }
 
@@ -695,24 +2337,152 @@ fn render_delegate_request(call: &CallToolRequestParams, debug: bool) {
         }
     }
 
-    println!();
+    term_println!();
 }
 
+/// Shows the agent's todo list as a checklist, or just the items it checked off when that is
+/// all that changed. Content without checkbox items is shown as it is.
 fn render_todo_request(call: &CallToolRequestParams, _debug: bool) {
     print_tool_header(call);
 
-    if let Some(args) = &call.arguments {
-        if let Some(Value::String(content)) = args.get("content") {
-            println!("    {} {}", style("content").dim(), style(content).dim());
+    let content = call
+        .arguments
+        .as_ref()
+        .and_then(|args| args.get("content"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let list = TodoList::parse(content);
+    if list.is_empty() {
+        term_println!("    {} {}", style("content").dim(), style(content).dim());
+        term_println!();
+        return;
+    }
+
+    let previous = TODO_LIST.with(|t| t.replace(list.clone()));
+    match list.changes_since(&previous) {
+        Some(changed) => changed.into_iter().for_each(print_todo_item),
+        None => list.items().iter().for_each(print_todo_item),
+    }
+    print_todo_progress(&list);
+    term_println!();
+}
+
+/// The full todo list, for `/todo`.
+pub fn render_todo_list(list: &TodoList) {
+    if list.is_empty() {
+        term_println!("\n  {}\n", style("No todo list yet").dim());
+        return;
+    }
+    term_println!();
+    list.items().iter().for_each(print_todo_item);
+    print_todo_progress(list);
+    term_println!();
+}
+
+/// How many lines of each background job's output `/jobs` shows
+const JOB_TAIL_LINES: usize = 3;
+
+/// The shell tool's background jobs with the end of their output, for `/jobs`.
+pub fn render_jobs(jobs: &[JobInfo]) {
+    if jobs.is_empty() {
+        term_println!("\n  {}\n", style("No background jobs").dim());
+        return;
+    }
+    term_println!();
+    for job in jobs {
+        let status = format_job_status(job.status, job.pid, job.started.elapsed());
+        let status = match job.status {
+            JobStatus::Running => style(status).green(),
+            JobStatus::Exited(Some(0)) | JobStatus::Stopped => style(status).dim(),
+            JobStatus::Exited(_) => style(status).red(),
+        };
+        term_println!(
+            "  {} {} {}",
+            style(format!("[{}]", job.id)).bold(),
+            job.command,
+            status
+        );
+        for line in jobs::tail(job, JOB_TAIL_LINES).lines() {
+            term_println!("      {}", style(safe_truncate(line, 200)).dim());
         }
     }
-    println!();
+    term_println!("\n  {}\n", style("/kill <id> stops a job").dim());
 }
 
-fn render_default_request(call: &CallToolRequestParams, debug: bool) {
-    print_tool_header(call);
-    print_params(&call.arguments, 1, debug);
-    println!();
+/// What `/kill` did, given the job as it is now
+pub fn render_job_stopped(id: u32, job: Option<&JobInfo>) {
+    match job {
+        Some(job) if job.status == JobStatus::Stopped => term_println!(
+            "\n  {} {}\n",
+            style(format!("Stopped job {}", id)).green(),
+            style(&job.command).dim()
+        ),
+        Some(job) => render_error(&format!(
+            "Job {} had already {}",
+            id,
+            format_job_status(job.status, job.pid, job.started.elapsed())
+        )),
+        None => render_error(&format!("No background job {}; /jobs lists them", id)),
+    }
+}
+
+fn format_job_status(status: JobStatus, pid: Option<u32>, elapsed: Duration) -> String {
+    match status {
+        JobStatus::Running => match pid {
+            Some(pid) => format!(
+                "running for {} · pid {}",
+                indicatif::HumanDuration(elapsed),
+                pid
+            ),
+            None => format!("running for {}", indicatif::HumanDuration(elapsed)),
+        },
+        JobStatus::Exited(Some(code)) => format!("exited with code {}", code),
+        JobStatus::Exited(None) => "exited on a signal".to_string(),
+        JobStatus::Stopped => "stopped".to_string(),
+    }
+}
+
+fn print_todo_item(item: &TodoItem) {
+    let indent = "  ".repeat(item.depth);
+    let (mark, text) = match item.status {
+        TodoStatus::Pending => (style("☐").dim(), style(&item.text)),
+        TodoStatus::InProgress => (style("▶").cyan(), style(&item.text).cyan().bold()),
+        TodoStatus::Done => (style("☑").green(), style(&item.text).dim()),
+    };
+    term_println!("    {}{} {}", indent, mark, text);
+}
+
+fn print_todo_progress(list: &TodoList) {
+    let (done, total) = list.progress();
+    term_println!(
+        "    {}",
+        style(format!(
+            "todo · {}/{} done · {}%",
+            done,
+            total,
+            list.percent()
+        ))
+        .dim()
+    );
+}
+
+fn render_default_request(call: &CallToolRequestParams, tool_meta: Option<&Value>, debug: bool) {
+    match render_hints::hints_for(&call.name, tool_meta) {
+        // debug output always shows the call as it was made
+        Some(hints) if !debug => {
+            match &hints.label {
+                Some(label) => print_labeled_tool_header(label),
+                None => print_tool_header(call),
+            }
+            let arguments = call.arguments.as_ref().map(|args| hints.apply(args));
+            print_params(&arguments, 1, debug);
+        }
+        _ => {
+            print_tool_header(call);
+            print_params(&call.arguments, 1, debug);
+        }
+    }
+    term_println!();
 }
 
 fn split_tool_name(tool_name: &str) -> (String, String) {
@@ -736,6 +2506,85 @@ pub fn format_subagent_tool_call_message(subagent_id: &str, tool_name: &str) ->
     }
 }
 
+/// How much of each message of a sampling request is shown before it is approved
+const SAMPLING_PREVIEW_CHARS: usize = 500;
+
+/// The models an extension prefers for a sampling request and what it cares most about,
+/// e.g. `claude, gpt · cost 0.8 · speed 0.2`
+fn format_model_preferences(preview: &SamplingPreview) -> Option<String> {
+    let mut parts = Vec::new();
+    if !preview.model_hints.is_empty() {
+        parts.push(preview.model_hints.join(", "));
+    }
+    for (label, priority) in [
+        ("cost", preview.cost_priority),
+        ("speed", preview.speed_priority),
+        ("intelligence", preview.intelligence_priority),
+    ] {
+        if let Some(priority) = priority {
+            parts.push(format!("{} {}", label, priority));
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+fn print_sampling_text(label: &str, text: &str) {
+    let text = safe_truncate(text.trim(), SAMPLING_PREVIEW_CHARS);
+    let mut lines = text.lines();
+    term_println!(
+        "    {} {}",
+        style(format!("{}:", label)).dim(),
+        lines.next().unwrap_or_default()
+    );
+    for line in lines {
+        term_println!("      {}", line);
+    }
+}
+
+/// Shows what an extension wants the model for, and which model would answer, before the
+/// user approves the sampling request
+pub fn render_sampling_request(preview: &SamplingPreview) {
+    hide_thinking();
+    term_println!();
+    term_println!(
+        "  {} {}",
+        palette().tool_bullet(),
+        style("sampling request").dim()
+    );
+    term_println!(
+        "    {} {} {}",
+        style("model:").dim(),
+        style(&preview.provider).dim(),
+        style(&preview.model).cyan()
+    );
+    if let Some(preferences) = format_model_preferences(preview) {
+        term_println!("    {} {}", style("prefers:").dim(), preferences);
+    }
+    term_println!("    {} {}", style("max tokens:").dim(), preview.max_tokens);
+    if let Some(system_prompt) = &preview.system_prompt {
+        print_sampling_text("system", system_prompt);
+    }
+    for message in &preview.messages {
+        print_sampling_text(&message.role, &message.text);
+    }
+}
+
+/// Shows which provider and model answered an extension's sampling request
+pub fn render_sampling_result(provider: &str, model: &str, output_tokens: Option<i64>) {
+    let tokens = output_tokens
+        .map(|tokens| format!(" · {} tokens", format_tokens(tokens.max(0) as usize)))
+        .unwrap_or_default();
+    term_println!(
+        "  {} {}",
+        palette().tool_bullet(),
+        style(format!(
+            "sampling answered by {} · {}{}",
+            provider, model, tokens
+        ))
+        .dim()
+    );
+}
+
 pub fn render_subagent_tool_call(
     subagent_id: &str,
     tool_name: &str,
@@ -753,23 +2602,97 @@ pub fn render_subagent_tool_call(
     }
     let tool_header = format!(
         "  {} {}",
-        style("▸").dim(),
+        palette().tool_bullet(),
         style(format_subagent_tool_call_message(subagent_id, tool_name)).dim(),
     );
-    println!();
-    println!("{}", tool_header);
-    print_params(&arguments.cloned(), 1, debug);
-    println!();
+    term_println!();
+    term_println!("{}", tool_header);
+    let mut arguments = arguments.cloned();
+    if REDACT.with(Cell::get) {
+        arguments
+            .iter_mut()
+            .flat_map(|arguments| arguments.values_mut())
+            .for_each(|value| redactor().redact_value(value));
+    }
+    print_params(&arguments, 1, debug);
+    term_println!();
+}
+
+const MAX_TOOL_ARGS_SUMMARY_CHARS: usize = 80;
+
+/// Tool arguments on one line, e.g. `command: ls -la, timeout: 30`, for listing several calls.
+pub fn format_tool_args_summary(arguments: &JsonObject) -> String {
+    let summary = arguments
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => s.lines().next().unwrap_or("").to_string(),
+                other => other.to_string(),
+            };
+            format!("{}: {}", key, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    safe_truncate(&summary, MAX_TOOL_ARGS_SUMMARY_CHARS)
+}
+
+/// Header for one call of a batch that is being approved call by call.
+pub fn render_pending_tool_call(number: usize, total: usize, tool_name: &str) {
+    term_println!(
+        "\n  {} {}",
+        style(format!("[{}/{}]", number, total)).dim(),
+        style(tool_name).cyan()
+    );
+}
+
+/// The files a shell command waiting for approval would change, and what is there now.
+pub fn render_shell_preview(affected: &[AffectedPath], cwd: &Path) {
+    if affected.is_empty() {
+        return;
+    }
+    term_println!("  {}", style("would change:").dim());
+    for path in affected {
+        let (verb, color) = match &path.effect {
+            Effect::Delete => ("delete", Color::Red),
+            Effect::Move { .. } => ("move", Color::Yellow),
+            Effect::Write => ("write", Color::Yellow),
+            Effect::Edit => ("edit", Color::Yellow),
+        };
+        let target = match &path.effect {
+            Effect::Move { to } => format!("{} → {}", path.path, to),
+            _ => path.path.clone(),
+        };
+        term_println!(
+            "    {} {} {}",
+            style(format!("{:<6}", verb)).fg(color),
+            target,
+            style(format!("({})", path.detail(cwd))).dim()
+        );
+    }
+}
+
+/// The decision made for a call approved as part of a batch.
+pub fn render_tool_decision(tool_name: &str, allowed: bool) {
+    if allowed {
+        term_println!("  {} {}", style("✓").green(), style(tool_name).dim());
+    } else {
+        term_println!(
+            "  {} {} {}",
+            style("✗").red(),
+            style(tool_name).dim(),
+            style("denied").red().dim()
+        );
+    }
 }
 
 fn render_subagent_tool_graph(subagent_id: &str, tool_graph: &[Value]) {
     let short_id = subagent_id.rsplit('_').next().unwrap_or(subagent_id);
     let count = tool_graph.len();
     let plural = if count == 1 { "" } else { "s" };
-    println!();
-    println!(
+    term_println!();
+    term_println!(
         "  {} {} {} {} tool call{}",
-        style("▸").dim(),
+        palette().tool_bullet(),
         style(format!("[subagent:{}]", short_id)).dim(),
         style("execute_code").dim(),
         style(count).dim(),
@@ -798,7 +2721,7 @@ fn render_subagent_tool_graph(subagent_id: &str, tool_graph: &[Value]) {
         } else {
             format!(" (uses {})", deps.join(", "))
         };
-        println!(
+        term_println!(
             "    {}. {} {}{}",
             style(i + 1).dim(),
             style(tool).dim(),
@@ -806,25 +2729,60 @@ fn render_subagent_tool_graph(subagent_id: &str, tool_graph: &[Value]) {
             style(deps_str).dim()
         );
     }
-    println!();
+    term_println!();
 }
 
 // Helper functions
 
+fn print_labeled_tool_header(label: &str) {
+    term_println!();
+    if accessible::enabled() {
+        term_println!(
+            "{}{}",
+            accessible::tool_started(label),
+            tool_number_suffix()
+        );
+        return;
+    }
+    term_println!(
+        "  {} {}{}",
+        palette().tool_bullet(),
+        style(label).dim(),
+        tool_number_suffix()
+    );
+}
+
 fn print_tool_header(call: &CallToolRequestParams) {
     let (tool, extension) = split_tool_name(&call.name);
+    if accessible::enabled() {
+        term_println!();
+        term_println!(
+            "{}{}",
+            accessible::tool_started(&tool),
+            tool_number_suffix()
+        );
+        return;
+    }
     let tool_header = if extension.is_empty() {
-        format!("  {} {}", style("▸").dim(), style(&tool).dim())
+        format!("  {} {}", palette().tool_bullet(), style(&tool).dim())
     } else {
         format!(
             "  {} {} {}",
-            style("▸").dim(),
+            palette().tool_bullet(),
             style(&tool).dim(),
             style(extension).magenta().dim(),
         )
     };
-    println!();
-    println!("{}", tool_header);
+    term_println!();
+    term_println!("{}{}", tool_header, tool_number_suffix());
+}
+
+/// ` #n` for the request being rendered when tool ids are shown, otherwise empty.
+fn tool_number_suffix() -> String {
+    HEADER_TOOL_NUMBER
+        .with(Cell::take)
+        .map(|number| format!(" {}", style(format!("#{}", number)).dim()))
+        .unwrap_or_default()
 }
 
 // Respect NO_COLOR, as https://crates.io/crates/console already does
@@ -833,34 +2791,291 @@ pub fn env_no_color() -> bool {
     std::env::var_os("NO_COLOR").is_none()
 }
 
+thread_local! {
+    static LINK_REFS: RefCell<LinkRefs> = RefCell::new(LinkRefs::default());
+}
+
+/// Link references and footnotes are resolved unless `GOOSE_CLI_RESOLVE_LINKS` is false.
+fn link_resolution_enabled() -> bool {
+    Config::global()
+        .get_param::<bool>("GOOSE_CLI_RESOLVE_LINKS")
+        .unwrap_or(true)
+}
+
+/// Prints a piece of an assistant message, with link targets inlined and the definitions
+/// kept for [`render_link_section`].
+fn print_assistant_markdown(content: &str, theme: Theme) {
+    if !term_is_terminal() || !link_resolution_enabled() {
+        print_markdown(content, theme);
+        return;
+    }
+    let content = LINK_REFS.with(|refs| refs.borrow_mut().rewrite(content));
+    print_markdown(&content, theme);
+}
+
+/// How much of the quoted passage of a citation without a URL is shown
+const MAX_CITED_TEXT_CHARS: usize = 80;
+
+/// One numbered line per source the provider cited, counting a source quoted several times
+/// once
+fn format_citations(citations: &[Citation]) -> Vec<String> {
+    let mut sources: Vec<&Citation> = Vec::new();
+    for citation in citations {
+        let seen = sources
+            .iter()
+            .any(|source| match (&source.url, &citation.url) {
+                (Some(a), Some(b)) => a == b,
+                (None, None) => source.title == citation.title,
+                _ => false,
+            });
+        if !seen {
+            sources.push(citation);
+        }
+    }
+    sources
+        .iter()
+        .enumerate()
+        .map(|(i, source)| {
+            let described = match (&source.title, &source.url) {
+                (Some(title), Some(url)) => format!("{} · {}", title, url),
+                (None, Some(url)) => url.clone(),
+                (Some(title), None) => match &source.cited_text {
+                    Some(text) => format!(
+                        "{} · \"{}\"",
+                        title,
+                        safe_truncate(text.trim(), MAX_CITED_TEXT_CHARS)
+                    ),
+                    None => title.clone(),
+                },
+                (None, None) => String::new(),
+            };
+            format!("[{}] {}", i + 1, described)
+        })
+        .collect()
+}
+
+/// The sources the provider says the response draws on, after its text
+fn render_citations(citations: &[Citation]) {
+    let lines = format_citations(citations);
+    if lines.is_empty() {
+        return;
+    }
+    let links = hyperlink::enabled(term_is_terminal());
+    term_println!();
+    for line in lines {
+        let line = style(line).dim().to_string();
+        if links {
+            term_println!("  {}", hyperlink::linkify_urls(&line));
+        } else {
+            term_println!("  {}", line);
+        }
+    }
+}
+
+/// The reference definitions and footnotes of the message just printed, as a "Links" section
+fn render_link_section() {
+    let (rest, entries) = LINK_REFS.with(|refs| refs.borrow_mut().finish());
+    if !rest.is_empty() {
+        print_markdown(&rest, get_theme());
+    }
+    if entries.is_empty() {
+        return;
+    }
+    let links = hyperlink::enabled(term_is_terminal());
+    term_println!("\n  {}", style("Links").bold().dim());
+    for (label, target) in entries {
+        let target = if links {
+            hyperlink::linkify_urls(&target)
+        } else {
+            target
+        };
+        term_println!("  {} {}", style(label).dim(), target);
+    }
+}
+
 fn print_markdown(content: &str, theme: Theme) {
-    if std::io::stdout().is_terminal() {
-        if let Some((before, table, after)) = extract_markdown_table(content) {
-            if !before.is_empty() {
-                print_markdown_raw(&before, theme);
-            }
-            print_table(&table, theme);
-            if !after.is_empty() {
-                print_markdown(after, theme);
+    if !term_is_terminal() {
+        term_print!("{}", content);
+        return;
+    }
+
+    for segment in split_code_blocks(content) {
+        match segment {
+            MarkdownSegment::Text(text) if math_rendering_enabled() => {
+                print_markdown_text(&math::render_math(text), theme)
             }
-        } else {
-            print_markdown_raw(content, theme);
+            MarkdownSegment::Text(text) => print_markdown_text(text, theme),
+            MarkdownSegment::CodeBlock {
+                language,
+                code,
+                block,
+            } => print_code_block(language, code, block, theme),
+        }
+    }
+}
+
+/// `$...$` math is shown with unicode symbols unless `GOOSE_CLI_RENDER_MATH` is false.
+fn math_rendering_enabled() -> bool {
+    Config::global()
+        .get_param::<bool>("GOOSE_CLI_RENDER_MATH")
+        .unwrap_or(true)
+}
+
+fn print_markdown_text(content: &str, theme: Theme) {
+    if let Some((before, table, after)) = extract_markdown_table(content) {
+        if !before.is_empty() {
+            print_markdown_raw(&before, theme);
         }
+        print_table(&table, theme);
+        if !after.is_empty() {
+            print_markdown_text(after, theme);
+        }
+    } else {
+        print_markdown_raw(content, theme);
+    }
+}
+
+/// Languages bat can highlight, by lowercase syntax name and file extension.
+static KNOWN_LANGUAGES: LazyLock<HashSet<String>> = LazyLock::new(|| {
+    bat::PrettyPrinter::new()
+        .syntaxes()
+        .flat_map(|syntax| {
+            std::iter::once(syntax.name.to_lowercase()).chain(
+                syntax
+                    .file_extensions
+                    .into_iter()
+                    .map(|ext| ext.to_lowercase()),
+            )
+        })
+        .collect()
+});
+
+/// Highlights a complete fenced code block with its own language, keeping the fences dim.
+/// Blocks without a language bat knows go through the markdown renderer as before.
+fn print_code_block(language: &str, code: &str, block: &str, theme: Theme) {
+    let mut lines = block.lines();
+    let opening = lines.next().unwrap_or_default();
+    let info = CodeInfo::parse(opening, |word| KNOWN_LANGUAGES.contains(word));
+    if code_numbers_enabled() && (KNOWN_LANGUAGES.contains(&info.language) || info.path.is_some()) {
+        print_numbered_code_block(&info, code, theme);
+        return;
+    }
+
+    let language = language.to_lowercase();
+    if language.is_empty() || !KNOWN_LANGUAGES.contains(&language) {
+        print_markdown_raw(block, theme);
+        return;
+    }
+
+    term_println!("{}", style(opening).dim());
+    print_highlighted(
+        bat::PrettyPrinter::new()
+            .input(bat::Input::from_bytes(code.as_bytes()))
+            .theme(theme.as_str())
+            .colored_output(env_no_color())
+            .language(&language)
+            .wrapping_mode(WrappingMode::NoWrapping(true)),
+    );
+    if let Some(closing) = lines.last() {
+        term_println!("{}", style(closing).dim());
+    }
+}
+
+/// With `GOOSE_CLI_CODE_NUMBERS` on, code blocks are shown in a grid with a path and language
+/// header and line numbers, which count from the line the block starts at in its file.
+fn code_numbers_enabled() -> bool {
+    Config::global()
+        .get_param::<bool>("GOOSE_CLI_CODE_NUMBERS")
+        .unwrap_or(false)
+}
+
+/// The furthest into a file a block's line numbers may start. The lines before it are padded
+/// in, so a block claiming to start further in is numbered from 1 rather than padded.
+const MAX_FIRST_LINE: usize = 100_000;
+
+/// The line a block's numbers start at
+fn numbering_start(first_line: usize) -> usize {
+    if first_line <= MAX_FIRST_LINE {
+        first_line.max(1)
     } else {
-        print!("{}", content);
+        1
+    }
+}
+
+fn print_numbered_code_block(info: &CodeInfo, code: &str, theme: Theme) {
+    // bat numbers from the first line of its input, so the lines before the block are padded
+    // in and left out of the range it prints
+    let first_line = numbering_start(info.first_line);
+    let padding = "\n".repeat(first_line - 1);
+    let padded = format!("{}{}", padding, code);
+    let mut input = bat::Input::from_bytes(padded.as_bytes());
+    if let Some(path) = &info.path {
+        input = input.name(path);
+    }
+    if let Some(title) = info.title() {
+        input = input.title(title);
+    }
+
+    let mut printer = bat::PrettyPrinter::new();
+    printer
+        .input(input)
+        .theme(theme.as_str())
+        .colored_output(env_no_color())
+        .header(true)
+        .grid(!accessible::enabled())
+        // a patch's own lines are not the file's, its hunk headers say where they are
+        .line_numbers(!matches!(info.language.as_str(), "diff" | "patch"))
+        .snip(false)
+        .line_ranges(LineRanges::from(vec![LineRange::new(
+            first_line,
+            usize::MAX,
+        )]))
+        .wrapping_mode(WrappingMode::NoWrapping(true));
+    if KNOWN_LANGUAGES.contains(&info.language) {
+        printer.language(&info.language);
     }
+    print_highlighted(&mut printer);
 }
 
 /// Renders markdown content using bat (no table processing)
 fn print_markdown_raw(content: &str, theme: Theme) {
-    bat::PrettyPrinter::new()
+    let mut printer = bat::PrettyPrinter::new();
+    printer
         .input(bat::Input::from_bytes(content.as_bytes()))
         .theme(theme.as_str())
         .colored_output(env_no_color())
         .language("Markdown")
-        .wrapping_mode(WrappingMode::NoWrapping(true))
-        .print()
-        .unwrap();
+        .wrapping_mode(WrappingMode::NoWrapping(true));
+    let links = hyperlink::has_url(content) && hyperlink::enabled(term_is_terminal());
+    if links || link_refs::has_marks(content) {
+        // the links and dimmed targets go in after highlighting, which would otherwise split
+        // them up
+        let mut rendered = String::new();
+        printer.print_with_writer(Some(&mut rendered)).unwrap();
+        let rendered = link_refs::apply_marks(&rendered, env_no_color());
+        if links {
+            write_term(&hyperlink::linkify_urls(&rendered));
+        } else {
+            write_term(&rendered);
+        }
+    } else {
+        print_highlighted(&mut printer);
+    }
+}
+
+/// Prints what bat highlighted through the terminal writer, like the rest of the output.
+fn print_highlighted(printer: &mut bat::PrettyPrinter) {
+    let mut rendered = String::new();
+    printer.print_with_writer(Some(&mut rendered)).unwrap();
+    write_term(&rendered);
+}
+
+/// `text` as a link to the file at `path`, when links are enabled.
+fn link_path(path: &str, text: &str) -> String {
+    match hyperlink::file_url(Path::new(path)).filter(|_| hyperlink::enabled(term_is_terminal())) {
+        Some(url) => hyperlink::link(&url, text),
+        None => text.to_string(),
+    }
 }
 
 fn extract_markdown_table(content: &str) -> Option<(String, Vec<&str>, &str)> {
@@ -944,106 +3159,61 @@ fn extract_markdown_table(content: &str) -> Option<(String, Vec<&str>, &str)> {
     Some((before, table, after))
 }
 
+/// Lays the table out again to fit the terminal, cutting wide cells short rather than letting
+/// the terminal wrap its rows. The full table is kept for /expand.
 fn print_table(table_lines: &[&str], theme: Theme) {
-    use comfy_table::{presets, Cell, CellAlignment, ContentArrangement, Table};
-
-    let mut table = Table::new();
-    table.set_content_arrangement(ContentArrangement::Dynamic);
-
-    table.load_preset(presets::ASCII_MARKDOWN);
-
-    let mut rows: Vec<Vec<String>> = Vec::new();
-    let mut alignments: Vec<CellAlignment> = Vec::new();
-    let mut separator_idx = None;
-
-    for (i, line) in table_lines.iter().enumerate() {
-        let cells: Vec<String> = line
-            .trim()
-            .trim_matches('|')
-            .split('|')
-            .map(|s| s.trim().to_string())
-            .collect();
-
-        let is_separator = cells.iter().all(|c| {
-            let t = c.trim();
-            t.chars().all(|ch| ch == '-' || ch == ':') && t.contains('-')
-        });
-        if is_separator {
-            separator_idx = Some(i);
-            alignments = cells
-                .iter()
-                .map(|c| {
-                    let t = c.trim();
-                    if t.starts_with(':') && t.ends_with(':') {
-                        CellAlignment::Center
-                    } else if t.ends_with(':') {
-                        CellAlignment::Right
-                    } else {
-                        CellAlignment::Left
-                    }
-                })
-                .collect();
-        } else {
-            rows.push(cells);
-        }
-    }
-
-    if separator_idx.is_none() && !rows.is_empty() {
-        alignments = vec![CellAlignment::Left; rows[0].len()];
-    }
-
-    if let Some(header) = rows.first() {
-        let header_cells: Vec<Cell> = header
-            .iter()
-            .enumerate()
-            .map(|(i, text)| {
-                let cell = Cell::new(text);
-                if let Some(align) = alignments.get(i) {
-                    cell.set_alignment(*align)
-                } else {
-                    cell
-                }
-            })
-            .collect();
-        table.set_header(header_cells);
-    }
-
-    for row in rows.iter().skip(1) {
-        let cells: Vec<Cell> = row
-            .iter()
-            .enumerate()
-            .map(|(i, text)| {
-                let cell = Cell::new(text);
-                if let Some(align) = alignments.get(i) {
-                    cell.set_alignment(*align)
-                } else {
-                    cell
-                }
-            })
-            .collect();
-        table.add_row(cells);
+    let Some(table) = MarkdownTable::parse(table_lines) else {
+        print_markdown_raw(&(table_lines.join("\n") + "\n"), theme);
+        return;
+    };
+    let width = Term::stdout()
+        .size_checked()
+        .map(|(_h, w)| w as usize)
+        .unwrap_or(80);
+    let layout = table.layout(width);
+    print_markdown_raw(&layout.text, theme);
+    if layout.truncated {
+        LAST_TRUNCATED
+            .with(|last| *last.borrow_mut() = Some(TruncatedOutput::Table(table_lines.join("\n"))));
+        term_println!(
+            "{}",
+            style("… table cut to fit the terminal (/expand to see all)").dim()
+        );
     }
-
-    let table_str = table.to_string();
-    print_markdown_raw(&table_str, theme);
 }
 
 const INDENT: &str = "    ";
 
-fn print_value_with_prefix(prefix: &String, value: &Value, debug: bool) {
+fn print_value_with_prefix(prefix: &String, key: &str, value: &Value, debug: bool) {
     let prefix_width = measure_text_width(prefix.as_str());
-    print!("{}", prefix);
-    print_value(value, debug, prefix_width)
+    term_print!("{}", prefix);
+    print_value(key, value, debug, prefix_width)
 }
 
-fn print_value(value: &Value, debug: bool, reserve_width: usize) {
+/// Prints a parameter value, cut to the limit configured for `key` in `GOOSE_CLI_TRUNCATION`
+/// or else to the terminal width.
+fn print_value(key: &str, value: &Value, debug: bool, reserve_width: usize) {
     let max_width = Term::stdout()
         .size_checked()
         .map(|(_h, w)| (w as usize).saturating_sub(reserve_width));
     let show_full = get_show_full_tool_output();
+    let limit = TRUNCATION.with(|t| t.for_param(key));
     let formatted = match value {
+        Value::String(s) if !(debug || show_full) && limit.is_some() => {
+            match limit.and_then(|limit| limit.apply(s)) {
+                Some(truncated) => {
+                    term_println!("{}", style(truncated.shown).green());
+                    print_truncation_marker(&truncated);
+                    return;
+                }
+                None => style(s.to_string()).green(),
+            }
+        }
         Value::String(s) => match (max_width, debug || show_full) {
-            (Some(w), false) if s.len() > w => style(safe_truncate(s, w)),
+            (Some(w), false) if s.len() > w => {
+                TRUNCATED.with(|t| t.set(true));
+                style(safe_truncate(s, w))
+            }
             _ => style(s.to_string()),
         }
         .green(),
@@ -1052,7 +3222,7 @@ fn print_value(value: &Value, debug: bool, reserve_width: usize) {
         Value::Null => style("null".to_string()).dim(),
         _ => unreachable!(),
     };
-    println!("{}", formatted);
+    term_println!("{}", formatted);
 }
 
 fn print_params(value: &Option<JsonObject>, depth: usize, debug: bool) {
@@ -1062,7 +3232,7 @@ fn print_params(value: &Option<JsonObject>, depth: usize, debug: bool) {
         for (key, val) in json_object.iter() {
             match val {
                 Value::Object(obj) => {
-                    println!("{}{}:", indent, style(key).dim());
+                    term_println!("{}{}:", indent, style(key).dim());
                     print_params(&Some(obj.clone()), depth + 1, debug);
                 }
                 Value::Array(arr) => {
@@ -1089,18 +3259,19 @@ fn print_params(value: &Option<JsonObject>, depth: usize, debug: bool) {
                         let joined_values = values.join(", ");
                         print_value_with_prefix(
                             &format!("{}{}: ", indent, style(key).dim()),
+                            key,
                             &Value::String(joined_values),
                             debug,
                         );
                     } else {
                         // Use the original multi-line format for complex arrays
-                        println!("{}{}:", indent, style(key).dim());
+                        term_println!("{}{}:", indent, style(key).dim());
                         for item in arr.iter() {
                             if let Value::Object(obj) = item {
-                                println!("{}{}- ", indent, INDENT);
+                                term_println!("{}{}- ", indent, INDENT);
                                 print_params(&Some(obj.clone()), depth + 2, debug);
                             } else {
-                                println!("{}{}- {}", indent, INDENT, item);
+                                term_println!("{}{}- {}", indent, INDENT, item);
                             }
                         }
                     }
@@ -1108,6 +3279,7 @@ fn print_params(value: &Option<JsonObject>, depth: usize, debug: bool) {
                 _ => {
                     print_value_with_prefix(
                         &format!("{}{}: ", indent, style(key).dim()),
+                        key,
                         val,
                         debug,
                     );
@@ -1117,7 +3289,45 @@ fn print_params(value: &Option<JsonObject>, depth: usize, debug: bool) {
     }
 }
 
-fn shorten_path(path: &str, debug: bool) -> String {
+/// The files the session's tools read, wrote or deleted, shown when the session ends
+pub fn render_files_touched(files: &[TouchedFile], debug: bool) {
+    if files.is_empty() {
+        return;
+    }
+    term_println!(
+        "\n  {}",
+        style(tr_with(
+            "files-touched",
+            &[("count", &files.len().to_string())]
+        ))
+        .bold()
+    );
+    let paths: Vec<String> = files
+        .iter()
+        .map(|file| shorten_path(&file.path, debug))
+        .collect();
+    let width = paths.iter().map(|path| measure_text_width(path)).max();
+    for (file, path) in files.iter().zip(&paths) {
+        let counts: Vec<String> = [
+            ("files-touched-read", file.reads),
+            ("files-touched-written", file.writes),
+            ("files-touched-deleted", file.deletes),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(key, count)| tr_with(key, &[("count", &count.to_string())]))
+        .collect();
+        let padding = width.unwrap_or_default() - measure_text_width(path);
+        term_println!(
+            "    {}{}  {}",
+            path,
+            " ".repeat(padding),
+            style(counts.join(" · ")).dim()
+        );
+    }
+}
+
+pub fn shorten_path(path: &str, debug: bool) -> String {
     // In debug mode, return the full path
     if debug {
         return path.to_string();
@@ -1172,6 +3382,9 @@ pub fn display_session_info(
     model: &str,
     session_id: &Option<String>,
     provider_instance: Option<&Arc<dyn goose::providers::base::Provider>>,
+    project_profile: Option<(&Path, String)>,
+    project_memories: usize,
+    title: Option<&str>,
 ) {
     let status = if resume {
         "resuming"
@@ -1180,41 +3393,134 @@ pub fn display_session_info(
     } else {
         "new session"
     };
+    print_session_header(status, provider, model, session_id, provider_instance);
+    if let Some(title) = title {
+        term_println!("  {} {}", style(" ").dim(), style(title).bold());
+    }
+    if let Some((path, summary)) = project_profile {
+        term_println!(
+            "  {} {}",
+            style(" ").dim(),
+            style(format!("profile {} · {}", path.display(), summary)).dim()
+        );
+    }
+    if project_memories > 0 {
+        term_println!(
+            "  {} {}",
+            style(" ").dim(),
+            style(format!(
+                "Loaded {} project {} (view with /memory)",
+                project_memories,
+                if project_memories == 1 {
+                    "memory"
+                } else {
+                    "memories"
+                }
+            ))
+            .dim()
+        );
+    }
+}
 
-    let model_display = if let Some(provider_inst) = provider_instance {
-        if let Some(lead_worker) = provider_inst.as_lead_worker() {
-            let (lead_model, worker_model) = lead_worker.get_model_info();
-            format!("{} → {}", lead_model, worker_model)
-        } else {
-            model.to_string()
+/// The earlier session a run was given with `--context-from`, and what summarizing it cost
+pub fn render_context_from(source: &Session, provider: &str, usage: &ProviderUsage) {
+    let input = usage.usage.input_tokens.unwrap_or(0).max(0) as usize;
+    let output = usage.usage.output_tokens.unwrap_or(0).max(0) as usize;
+    let cost = estimate_cost_usd(provider, &usage.model, input, output)
+        .map(|cost| format!(" · ${:.4}", cost))
+        .unwrap_or_default();
+    term_println!(
+        "  {} {}",
+        style("↳").dim(),
+        style(format!(
+            "context from {} ({}) · summary took {} in, {} out{}",
+            source.name,
+            source.id,
+            format_tokens(input),
+            format_tokens(output),
+            cost
+        ))
+        .dim()
+    );
+}
+
+/// The session header again after `/model` swapped the provider.
+pub fn display_model_switch(
+    provider: &str,
+    model: &str,
+    session_id: &Option<String>,
+    provider_instance: Option<&Arc<dyn goose::providers::base::Provider>>,
+) {
+    print_session_header("switched", provider, model, session_id, provider_instance);
+}
+
+fn print_session_header(
+    status: &str,
+    provider: &str,
+    model: &str,
+    session_id: &Option<String>,
+    provider_instance: Option<&Arc<dyn goose::providers::base::Provider>>,
+) {
+    let model_display = if let Some(provider_inst) = provider_instance {
+        if let Some(lead_worker) = provider_inst.as_lead_worker() {
+            let (lead_model, worker_model) = lead_worker.get_model_info();
+            format!("{} → {}", lead_model, worker_model)
+        } else {
+            model.to_string()
         }
     } else {
         model.to_string()
     };
 
-    println!(
+    term_println!(
         "\n  {} {} {} {} {}",
-        style("●").green(),
+        palette().header_symbol(),
         style(status).dim(),
         style("·").dim(),
         style(provider).dim(),
         style(&model_display).cyan(),
     );
 
+    match maybe_get_canonical_model(provider, model) {
+        Some(canonical) => term_println!(
+            "  {} {}",
+            style(" ").dim(),
+            style(format_model_metadata(&canonical)).dim()
+        ),
+        None => term_println!(
+            "  {} {}",
+            style("⚠").yellow(),
+            style(format!(
+                "{} is not in the model registry; context size and pricing are unknown",
+                model
+            ))
+            .yellow()
+            .dim()
+        ),
+    }
+
+    if let Some(provider_inst) = provider_instance {
+        term_println!(
+            "  {} {}",
+            style(" ").dim(),
+            style(params::describe(&provider_inst.get_model_config())).dim()
+        );
+    }
+
     let cwd_display = std::env::current_dir()
         .ok()
         .map(|p| p.display().to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
     if let Some(id) = session_id {
-        println!(
+        term_println!(
             "  {} {} {}",
             style(" ").dim(),
             style(id).dim(),
             style(format!("· {}", cwd_display)).dim(),
         );
     } else {
-        println!(
+        term_println!(
             "  {} {}",
             style(" ").dim(),
             style(format!("  {}", cwd_display)).dim(),
@@ -1222,35 +3528,250 @@ pub fn display_session_info(
     }
 }
 
-pub fn set_terminal_title() {
-    if !std::io::stdout().is_terminal() {
-        return;
+/// What a run would use, for `goose run --dry-run`: the session header, what the most
+/// expensive run could cost, the extensions it would load and the recipe's prompts
+pub fn display_dry_run(
+    provider: &str,
+    model_config: &goose::model::ModelConfig,
+    extensions: &[goose::config::ExtensionConfig],
+    recipe: Option<&goose::recipe::Recipe>,
+    max_turns: u32,
+) {
+    print_session_header("dry run", provider, &model_config.model_name, &None, None);
+    term_println!(
+        "  {} {}",
+        style(" ").dim(),
+        style(params::describe(model_config)).dim()
+    );
+
+    let input_tokens = model_config.context_limit();
+    let output_tokens = model_config.max_output_tokens().max(0) as usize;
+    let per_turn = estimate_cost_usd(
+        provider,
+        &model_config.model_name,
+        input_tokens,
+        output_tokens,
+    );
+    term_println!("\n  {} {}", style("$").cyan(), style("cost ceiling").bold());
+    term_println!(
+        "    {}",
+        style(format_cost_ceiling(per_turn, max_turns)).dim()
+    );
+    term_println!(
+        "    {}",
+        style(format!(
+            "a turn sends at most {} tokens and gets at most {} back",
+            format_tokens(input_tokens),
+            format_tokens(output_tokens)
+        ))
+        .dim()
+    );
+
+    term_println!(
+        "\n  {} {}",
+        style("⚙").cyan(),
+        style(format!("extensions ({})", extensions.len())).bold()
+    );
+    if extensions.is_empty() {
+        term_println!("    {}", style("none").dim());
+    }
+    for extension in extensions {
+        term_println!(
+            "    {} {}",
+            style(extension.name()).cyan(),
+            style(format!("· {}", extension)).dim()
+        );
+    }
+
+    if let Some(recipe) = recipe {
+        let sections = [
+            ("instructions", recipe.instructions.as_deref()),
+            ("prompt", recipe.prompt.as_deref()),
+        ];
+        for (label, text) in sections {
+            let Some(text) = text.filter(|text| !text.trim().is_empty()) else {
+                continue;
+            };
+            term_println!("\n  {} {}", style("▸").cyan(), style(label).bold());
+            for line in text.trim_end().lines() {
+                term_println!("    {}", style(line).dim());
+            }
+        }
+        if let Some(sub_recipes) = recipe.sub_recipes.as_ref().filter(|s| !s.is_empty()) {
+            term_println!("\n  {} {}", style("▸").cyan(), style("sub-recipes").bold());
+            for sub_recipe in sub_recipes {
+                term_println!(
+                    "    {} {}",
+                    style(&sub_recipe.name).cyan(),
+                    style(format!("· {}", sub_recipe.path)).dim()
+                );
+            }
+        }
+        if recipe
+            .response
+            .as_ref()
+            .is_some_and(|r| r.json_schema.is_some())
+        {
+            term_println!(
+                "\n  {} {}",
+                style("▸").cyan(),
+                style("answers with JSON matching the recipe's response schema").bold()
+            );
+        }
+    }
+
+    term_println!(
+        "\n{}",
+        style("Nothing was run. Leave out --dry-run to start it.").dim()
+    );
+}
+
+/// e.g. `up to $0.72 a turn, $720.00 over 1000 turns`, the most a run could cost if every
+/// turn filled the context and the output limit
+fn format_cost_ceiling(per_turn: Option<f64>, max_turns: u32) -> String {
+    match per_turn {
+        Some(cost) => format!(
+            "up to ${:.2} a turn, ${:.2} over {} turns (--max-turns)",
+            cost,
+            cost * max_turns as f64,
+            max_turns
+        ),
+        None => format!(
+            "unknown, the model has no pricing in the registry; at most {} turns (--max-turns)",
+            max_turns
+        ),
     }
+}
+
+/// The generation parameters for `/params`, after one was `changed` or when asked for
+pub fn render_params(config: &goose::model::ModelConfig, changed: bool) {
+    let label = if changed {
+        "parameters changed"
+    } else {
+        "parameters"
+    };
+    term_println!("\n  {} {}", style("⚙").cyan(), style(label).bold());
+    term_println!(
+        "    {} {}",
+        style(&config.model_name).cyan(),
+        style(format!("· {}", params::describe(config))).dim()
+    );
+    if !changed {
+        term_println!(
+            "    {}",
+            style("change one with /params temperature|max_tokens|effort <value>|default").dim()
+        );
+    }
+}
+
+/// Context window, price and capabilities of a model, e.g.
+/// `200k context · $3.00/$15.00 per 1M tokens · vision · tools`.
+pub fn format_model_metadata(model: &CanonicalModel) -> String {
+    let mut parts = Vec::new();
+    if model.limit.context > 0 {
+        parts.push(format!("{} context", format_tokens(model.limit.context)));
+    }
+    match (model.cost.input, model.cost.output) {
+        (Some(input), Some(output)) => {
+            parts.push(format!("${:.2}/${:.2} per 1M tokens", input, output))
+        }
+        (Some(input), None) => parts.push(format!("${:.2} per 1M input tokens", input)),
+        _ => {}
+    }
+    if model.modalities.input.contains(&Modality::Image) {
+        parts.push("vision".to_string());
+    }
+    if model.tool_call {
+        parts.push("tools".to_string());
+    }
+    if model.reasoning == Some(true) {
+        parts.push("reasoning".to_string());
+    }
+    parts.join(" · ")
+}
+
+pub fn set_terminal_title() {
     let dir_name = std::env::current_dir()
         .ok()
         .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
         .unwrap_or_default();
-    // Sanitize: strip control characters (ESC, BEL, etc.) to prevent terminal escape injection
-    let sanitized: String = dir_name.chars().filter(|c| !c.is_control()).collect();
-    // OSC 0 sets the terminal window/tab title
-    print!("\x1b]0;🪿 {}\x07", sanitized);
-    let _ = std::io::stdout().flush();
+    // legacy consoles can't draw the emoji in their title bar
+    let prefix = if terminal::supports_ansi() {
+        "🪿"
+    } else {
+        "goose"
+    };
+    terminal::set_title(&format!("{} {}", prefix, dir_name));
 }
 
 pub fn display_greeting() {
     set_terminal_title();
-    println!(
+    term_println!(
         "\n{} {}\n",
         style("🪿 goose").bold(),
-        style("ready — type a message to get started").dim()
+        style(tr("greeting")).dim()
     );
 }
 
+pub fn format_tokens(n: usize) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.0}k", n as f64 / 1_000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+const MAX_COMPACTION_SUMMARY_CHARS: usize = 100;
+
+/// Reports what an automatic or manual compaction reclaimed and the summary that replaced the
+/// compacted messages.
+pub fn render_compaction_summary(summary: &CompactionSummary) {
+    hide_thinking();
+    let mut line = format!("compacted {} messages", summary.compacted_messages);
+    if let Some((before, after)) = summary.tokens {
+        line.push_str(&format!(
+            " · {} → {} tokens ({} reclaimed)",
+            format_tokens(before),
+            format_tokens(after),
+            format_tokens(before.saturating_sub(after))
+        ));
+    }
+    term_println!("\n  {} {}", style("⇣").cyan(), style(line).dim());
+
+    if let Some(text) = &summary.summary {
+        let first_line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        term_println!(
+            "    {} {}",
+            style("summary:").dim(),
+            style(safe_truncate(
+                first_line.trim(),
+                MAX_COMPACTION_SUMMARY_CHARS
+            ))
+            .dim()
+        );
+    }
+}
+
+/// Timing stats after each response, turned on with `GOOSE_CLI_TURN_STATS`.
+pub fn turn_stats_enabled() -> bool {
+    get_verbosity().shows_notifications()
+        && Config::global()
+            .get_param::<bool>("GOOSE_CLI_TURN_STATS")
+            .unwrap_or(false)
+}
+
+pub fn render_turn_stats(stats: &TurnStats) {
+    hide_thinking();
+    term_println!("\n  {}", style(stats.summary()).dim());
+}
+
 pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     use console::style;
 
     if context_limit == 0 {
-        println!(
+        term_println!(
             "  {}",
             style("context usage unavailable (context limit is 0)").dim()
         );
@@ -1260,30 +3781,24 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     let percentage =
         (((total_tokens as f64 / context_limit as f64) * 100.0).round() as usize).min(100);
 
+    if accessible::enabled() {
+        term_println!(
+            "{}",
+            accessible::context_usage(
+                percentage,
+                &format_tokens(total_tokens),
+                &format_tokens(context_limit)
+            )
+        );
+        return;
+    }
+
     let bar_width = 20;
     let filled = ((percentage as f64 / 100.0) * bar_width as f64).round() as usize;
-    let empty = bar_width - filled.min(bar_width);
 
-    let bar = format!("{}{}", "━".repeat(filled), "╌".repeat(empty));
-    let colored_bar = if percentage < 50 {
-        style(bar).green().dim()
-    } else if percentage < 85 {
-        style(bar).yellow()
-    } else {
-        style(bar).red()
-    };
-
-    fn format_tokens(n: usize) -> String {
-        if n >= 1_000_000 {
-            format!("{:.1}M", n as f64 / 1_000_000.0)
-        } else if n >= 1_000 {
-            format!("{:.0}k", n as f64 / 1_000.0)
-        } else {
-            n.to_string()
-        }
-    }
+    let colored_bar = palette().context_bar(filled, bar_width, percentage);
 
-    println!(
+    term_println!(
         "  {} {} {}",
         colored_bar,
         style(format!("{}%", percentage)).dim(),
@@ -1296,7 +3811,109 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     );
 }
 
-fn estimate_cost_usd(
+/// Colors of the parts of the `/context` bar, in the order of [`ContextBreakdown::categories`]
+const CONTEXT_CATEGORY_COLORS: [Color; 5] = [
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Green,
+];
+const CONTEXT_BREAKDOWN_BAR_WIDTH: usize = 40;
+
+/// What fills the context, as a bar stacked from each category and a table of their tokens.
+/// `reported` is the provider's count from the last turn, to set the estimate against.
+pub fn render_context_breakdown(
+    breakdown: &ContextBreakdown,
+    context_limit: usize,
+    reported: Option<usize>,
+) {
+    use comfy_table::{presets, Cell, CellAlignment, Table};
+
+    let categories = breakdown.categories();
+    let share = |tokens: usize| {
+        if context_limit == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.1}%", tokens as f64 / context_limit as f64 * 100.0)
+        }
+    };
+
+    term_println!();
+    if !accessible::enabled() && context_limit > 0 {
+        let values: Vec<usize> = categories.iter().map(|(_, tokens)| *tokens).collect();
+        let cells = stacked_cells(&values, context_limit, CONTEXT_BREAKDOWN_BAR_WIDTH);
+        let bar: String = cells
+            .iter()
+            .zip(CONTEXT_CATEGORY_COLORS)
+            .map(|(cells, color)| {
+                palette()
+                    .context_segment(*cells, &Style::new().fg(color))
+                    .to_string()
+            })
+            .collect();
+        let free = CONTEXT_BREAKDOWN_BAR_WIDTH.saturating_sub(cells.iter().sum());
+        term_println!(
+            "  {}{} {}",
+            bar,
+            palette().context_free(free),
+            style(format!(
+                "{}/{}",
+                format_tokens(breakdown.total()),
+                format_tokens(context_limit)
+            ))
+            .dim()
+        );
+    }
+
+    let mut table = Table::new();
+    table.load_preset(presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec!["Category", "Tokens", "Of context"]);
+    for ((label, tokens), color) in categories.iter().zip(CONTEXT_CATEGORY_COLORS) {
+        let label = if accessible::enabled() {
+            label.to_string()
+        } else {
+            format!(
+                "{} {}",
+                palette().context_segment(1, &Style::new().fg(color)),
+                label
+            )
+        };
+        table.add_row(vec![
+            Cell::new(label),
+            Cell::new(tokens).set_alignment(CellAlignment::Right),
+            Cell::new(share(*tokens)).set_alignment(CellAlignment::Right),
+        ]);
+    }
+    table.add_row(vec![
+        Cell::new("Total (estimated)"),
+        Cell::new(breakdown.total()).set_alignment(CellAlignment::Right),
+        Cell::new(share(breakdown.total())).set_alignment(CellAlignment::Right),
+    ]);
+    if context_limit > 0 {
+        let free = context_limit.saturating_sub(breakdown.total());
+        table.add_row(vec![
+            Cell::new("Free"),
+            Cell::new(free).set_alignment(CellAlignment::Right),
+            Cell::new(share(free)).set_alignment(CellAlignment::Right),
+        ]);
+    }
+    term_println!("{}", table);
+
+    if let Some(reported) = reported.filter(|reported| *reported > 0) {
+        term_println!(
+            "  {}",
+            style(format!(
+                "The provider counted {} tokens on the last turn",
+                format_tokens(reported)
+            ))
+            .dim()
+        );
+    }
+    term_println!();
+}
+
+pub fn estimate_cost_usd(
     provider: &str,
     model: &str,
     input_tokens: usize,
@@ -1326,23 +3943,291 @@ pub fn display_cost_usage(provider: &str, model: &str, input_tokens: usize, outp
     }
 }
 
+/// Print the per-model token usage collected by `/cost`, with estimated prices where known.
+pub fn render_cost_breakdown(tracker: &CostTracker) {
+    use comfy_table::{presets, Cell, CellAlignment, Table};
+
+    if tracker.models.is_empty() {
+        term_println!("\n  {}\n", style("No token usage recorded yet").dim());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec!["Model", "Turns", "Input", "Output", "Cost (USD)"]);
+
+    let mut total_cost = Some(0.0);
+    for usage in &tracker.models {
+        let cost = estimate_cost_usd(
+            &usage.provider,
+            &usage.model,
+            usage.input_tokens as usize,
+            usage.output_tokens as usize,
+        );
+        total_cost = total_cost.zip(cost).map(|(total, cost)| total + cost);
+        table.add_row(vec![
+            Cell::new(format!("{}/{}", usage.provider, usage.model)),
+            Cell::new(usage.turns).set_alignment(CellAlignment::Right),
+            Cell::new(usage.input_tokens).set_alignment(CellAlignment::Right),
+            Cell::new(usage.output_tokens).set_alignment(CellAlignment::Right),
+            Cell::new(format_cost(cost)).set_alignment(CellAlignment::Right),
+        ]);
+    }
+
+    let (input, output) = tracker.total_tokens();
+    let turns: u32 = tracker.models.iter().map(|u| u.turns).sum();
+    table.add_row(vec![
+        Cell::new("Total"),
+        Cell::new(turns).set_alignment(CellAlignment::Right),
+        Cell::new(input).set_alignment(CellAlignment::Right),
+        Cell::new(output).set_alignment(CellAlignment::Right),
+        Cell::new(format_cost(total_cost)).set_alignment(CellAlignment::Right),
+    ]);
+
+    term_println!("\n{}\n", table);
+}
+
+/// Estimated price of everything the tracker recorded, if all its models have price data.
+pub fn estimate_tracked_cost(tracker: &CostTracker) -> Option<f64> {
+    tracker.models.iter().try_fold(0.0, |total, usage| {
+        estimate_cost_usd(
+            &usage.provider,
+            &usage.model,
+            usage.input_tokens as usize,
+            usage.output_tokens as usize,
+        )
+        .map(|cost| total + cost)
+    })
+}
+
+/// What the next turn would cost on each model, cheapest first, as the picker for `/model`
+/// is about to be shown.
+pub fn render_turn_cost_comparison(costs: &[TurnCost], input_tokens: usize, output_tokens: u64) {
+    use comfy_table::{presets, Cell, CellAlignment, Table};
+
+    if costs.is_empty() {
+        return;
+    }
+    let current = costs.iter().find(|c| c.current).map(|c| c.cost);
+
+    let mut table = Table::new();
+    table.load_preset(presets::UTF8_BORDERS_ONLY);
+    table.set_header(vec!["Model", "Next turn (USD)", "vs current"]);
+    for cost in costs {
+        let name = format!("{}/{}", cost.provider, cost.model);
+        table.add_row(vec![
+            Cell::new(if cost.current {
+                format!("{} (current)", name)
+            } else {
+                name
+            }),
+            Cell::new(format_cost(Some(cost.cost))).set_alignment(CellAlignment::Right),
+            Cell::new(format_cost_change(cost.cost, current)).set_alignment(CellAlignment::Right),
+        ]);
+    }
+
+    term_println!(
+        "\n  {}",
+        style(format!(
+            "Projected cost of the next turn, sending {} tokens of context and getting about {} back",
+            format_tokens(input_tokens),
+            format_tokens(output_tokens as usize)
+        ))
+        .dim()
+    );
+    term_println!("{}\n", table);
+}
+
+/// One line pointing out the cheapest model when it is cheaper than the current one, e.g. when
+/// resuming a long session.
+pub fn render_turn_cost_hint(costs: &[TurnCost]) {
+    let (Some(current), Some(cheapest)) = (costs.iter().find(|c| c.current), costs.first()) else {
+        return;
+    };
+    if cheapest.current {
+        return;
+    }
+    term_println!(
+        "  {}",
+        style(format!(
+            "The next turn costs about {} on {}; {} on {}/{} ({}). /model to switch",
+            format_cost(Some(current.cost)),
+            current.model,
+            format_cost(Some(cheapest.cost)),
+            cheapest.provider,
+            cheapest.model,
+            format_cost_change(cheapest.cost, Some(current.cost))
+        ))
+        .dim()
+    );
+}
+
+/// How `cost` compares to the current model's, e.g. `-80%`
+fn format_cost_change(cost: f64, current: Option<f64>) -> String {
+    match current {
+        Some(current) if current > 0.0 => format!("{:+.0}%", (cost / current - 1.0) * 100.0),
+        _ => String::new(),
+    }
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map(|c| format!("${:.4}", c))
+        .unwrap_or_else(|| "n/a".to_string())
+}
+
 pub struct McpSpinners {
     bars: HashMap<String, ProgressBar>,
     log_spinner: Option<ProgressBar>,
+    subagents: Vec<SubagentStatus>,
+    /// How many subagents are held back by the provider's rate limits, under the panel
+    rate_limit_line: Option<ProgressBar>,
+    /// The last progress line printed per token in accessible mode
+    reported: HashMap<String, String>,
 
     multi_bar: MultiProgress,
 }
 
+/// One line of the live subagent panel.
+struct SubagentStatus {
+    id: String,
+    bar: ProgressBar,
+    started: Instant,
+    tool_calls: usize,
+    /// The latest tool call, shown again once the subagent no longer waits for a rate limit
+    activity: String,
+}
+
 impl McpSpinners {
     pub fn new() -> Self {
         McpSpinners {
             bars: HashMap::new(),
             log_spinner: None,
+            subagents: Vec::new(),
+            rate_limit_line: None,
+            reported: HashMap::new(),
             multi_bar: MultiProgress::new(),
         }
     }
 
+    pub fn active_subagents(&self) -> usize {
+        self.subagents.len()
+    }
+
+    /// Whether subagent activity can be shown as a live panel rather than one line per call.
+    pub fn supports_subagent_panel() -> bool {
+        std::io::stdout().is_terminal() && terminal::supports_ansi() && !accessible::enabled()
+    }
+
+    /// Show a subagent's latest tool call on its line of the panel.
+    pub fn subagent_tool_call(&mut self, subagent_id: &str, tool_name: &str) {
+        let idx = match self.subagents.iter().position(|s| s.id == subagent_id) {
+            Some(idx) => idx,
+            None => {
+                let short_id = subagent_id.rsplit('_').next().unwrap_or(subagent_id);
+                let bar = self.multi_bar.add(
+                    ProgressBar::new_spinner()
+                        .with_style(
+                            ProgressStyle::with_template(
+                                "  {spinner:.cyan} {prefix:.dim} {msg} {elapsed:.dim}",
+                            )
+                            .unwrap()
+                            .tick_chars("⠋⠙⠚⠛⠓⠒⠊⠉ "),
+                        )
+                        .with_prefix(format!("subagent:{}", short_id)),
+                );
+                self.subagents.push(SubagentStatus {
+                    id: subagent_id.to_string(),
+                    bar,
+                    started: Instant::now(),
+                    tool_calls: 0,
+                    activity: String::new(),
+                });
+                self.subagents.len() - 1
+            }
+        };
+
+        let (tool, extension) = split_tool_name(tool_name);
+        let status = &mut self.subagents[idx];
+        status.tool_calls += 1;
+        status.activity = if extension.is_empty() {
+            tool
+        } else {
+            format!("{} {}", tool, style(extension).magenta().dim())
+        };
+        status.bar.set_message(status.activity.clone());
+        // the panel is frozen while other output is printed, wake every line back up
+        for status in &self.subagents {
+            status.bar.enable_steady_tick(Duration::from_millis(100));
+        }
+    }
+
+    /// Mark the subagents waiting for room in the provider's rate limit window, with a line
+    /// under the panel counting them. `waiting` lists sessions, of which `own_session` is the
+    /// parent's rather than a subagent's.
+    pub fn subagent_rate_limit_waits(&mut self, waiting: &BTreeSet<String>, own_session: &str) {
+        for status in &self.subagents {
+            status.bar.set_message(if waiting.contains(&status.id) {
+                style("waiting for rate limit window")
+                    .yellow()
+                    .dim()
+                    .to_string()
+            } else {
+                status.activity.clone()
+            });
+        }
+
+        let count = waiting.iter().filter(|id| *id != own_session).count();
+        if count == 0 {
+            if let Some(line) = self.rate_limit_line.take() {
+                line.finish_and_clear();
+                self.multi_bar.remove(&line);
+            }
+            return;
+        }
+        let line = self.rate_limit_line.get_or_insert_with(|| {
+            self.multi_bar.add(
+                ProgressBar::new_spinner()
+                    .with_style(ProgressStyle::with_template("  {prefix} {msg:.yellow}").unwrap())
+                    .with_prefix("⏳"),
+            )
+        });
+        line.set_message(format_rate_limit_waits(count));
+    }
+
+    /// Collapse the panel into a single summary line once the subagents have reported back.
+    pub fn finish_subagents(&mut self) {
+        if let Some(line) = self.rate_limit_line.take() {
+            line.finish_and_clear();
+            self.multi_bar.remove(&line);
+        }
+        if self.subagents.is_empty() {
+            return;
+        }
+        let tool_calls: usize = self.subagents.iter().map(|s| s.tool_calls).sum();
+        let longest = self
+            .subagents
+            .iter()
+            .map(|s| s.started.elapsed())
+            .max()
+            .unwrap_or_default();
+        let count = self.subagents.len();
+        for status in self.subagents.drain(..) {
+            status.bar.finish_and_clear();
+            self.multi_bar.remove(&status.bar);
+        }
+        let _ = self.multi_bar.println(format!(
+            "  {} {} {}",
+            style("✓").green(),
+            style(format_subagent_summary(count, tool_calls, longest)).dim(),
+            style("· /subagent for transcripts").dim()
+        ));
+    }
+
     pub fn log(&mut self, message: &str) {
+        // the latest log line is only a passing status; /logs has them all
+        if accessible::enabled() {
+            return;
+        }
         let spinner = self.log_spinner.get_or_insert_with(|| {
             let bar = self.multi_bar.add(
                 ProgressBar::new_spinner()
@@ -1361,6 +4246,14 @@ impl McpSpinners {
     }
 
     pub fn update(&mut self, token: &str, value: f64, total: Option<f64>, message: Option<&str>) {
+        if accessible::enabled() {
+            let line = accessible::progress(total.map(|total| value / total * 100.0), message);
+            if self.reported.get(token) != Some(&line) {
+                term_println!("{}", line);
+                self.reported.insert(token.to_string(), line);
+            }
+            return;
+        }
         let bar = self.bars.entry(token.to_string()).or_insert_with(|| {
             if let Some(total) = total {
                 self.multi_bar.add(
@@ -1386,15 +4279,397 @@ impl McpSpinners {
         if let Some(spinner) = self.log_spinner.as_mut() {
             spinner.disable_steady_tick();
         }
+        self.subagents.iter().for_each(|status| {
+            status.bar.disable_steady_tick();
+        });
         self.multi_bar.clear()
     }
 }
 
+fn format_rate_limit_waits(count: usize) -> String {
+    format!(
+        "{} subagent{} waiting for rate limit window",
+        count,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+fn format_subagent_summary(count: usize, tool_calls: usize, elapsed: Duration) -> String {
+    format!(
+        "{} subagent{} finished · {} tool call{} · {}",
+        count,
+        if count == 1 { "" } else { "s" },
+        tool_calls,
+        if tool_calls == 1 { "" } else { "s" },
+        indicatif::HumanDuration(elapsed)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::session::term_writer::StringWriter;
+    use rmcp::model::Content;
     use std::env;
 
+    /// Renders a message into a [`StringWriter`] and returns what was shown, without styling.
+    fn rendered(message: &Message) -> String {
+        let writer = StringWriter::new();
+        let previous = set_term_writer(Box::new(writer.clone()));
+        render_message(message, false);
+        set_term_writer(previous);
+        writer.contents()
+    }
+
+    #[test]
+    fn test_shell_tool_request_snapshot() {
+        let mut arguments = rmcp::model::JsonObject::new();
+        arguments.insert("command".to_string(), Value::from("ls -la"));
+        let message = Message::assistant().with_tool_request(
+            "call_1",
+            Ok(CallToolRequestParams {
+                meta: None,
+                task: None,
+                name: "developer__shell".into(),
+                arguments: Some(arguments),
+            }),
+        );
+        assert_eq!(
+            rendered(&message),
+            "\n  ▸ shell developer\n    command: ls -la\n\n"
+        );
+    }
+
+    #[test]
+    fn test_pinned_message_is_marked() {
+        set_pinned(&PinnedState {
+            pins: vec![goose::session::Pin::Message {
+                id: Some("msg_1".to_string()),
+                text: "keep this".to_string(),
+            }],
+        });
+        assert_eq!(
+            rendered(&Message::user().with_id("msg_1")),
+            "\n  📌 pinned\n"
+        );
+        assert_eq!(rendered(&Message::user().with_id("msg_2")), "");
+        set_pinned(&PinnedState::default());
+    }
+
+    #[test]
+    fn test_piped_text_is_written_as_it_streams() {
+        let writer = StringWriter::new();
+        let previous = set_term_writer(Box::new(writer.clone()));
+        let mut buffer = MarkdownBuffer::new();
+        render_message_streaming(
+            &Message::assistant().with_text("Some **bo"),
+            &mut buffer,
+            false,
+        );
+        assert_eq!(writer.contents(), "Some **bo");
+        render_message_streaming(
+            &Message::assistant().with_text("ld** text"),
+            &mut buffer,
+            false,
+        );
+        set_term_writer(previous);
+        assert_eq!(writer.contents(), "Some **bold** text");
+    }
+
+    #[test]
+    fn test_highlighted_diff_goes_through_the_writer() {
+        let writer = StringWriter::new();
+        let previous = set_term_writer(Box::new(writer.clone()));
+        print_diff("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-old\n+new\n", true);
+        set_term_writer(previous);
+        assert!(writer.contents().contains("-old\n+new"));
+    }
+
+    #[test]
+    fn test_binary_resource_is_kept_for_save() {
+        let writer = StringWriter::new();
+        let previous = set_term_writer(Box::new(writer.clone()));
+        render_resource(
+            &ResourceContents::BlobResourceContents {
+                uri: "file:///tmp/report.pdf".to_string(),
+                mime_type: Some("application/pdf".to_string()),
+                blob: "aGVsbG8=".to_string(),
+                meta: None,
+            },
+            Theme::Dark,
+        );
+        set_term_writer(previous);
+
+        let output = writer.contents();
+        assert!(output.contains("file:///tmp/report.pdf · application/pdf · 5 B"));
+        let number = BINARY_RESOURCES.with(|r| r.borrow().len());
+        assert!(output.contains(&format!("/save {} to write it to a file", number)));
+        let resource = binary_resource(Some(number)).unwrap();
+        assert_eq!(resource.file_name(), "report.pdf");
+        assert_eq!(binary_resource(None), Some(resource));
+    }
+
+    #[test]
+    fn test_todo_write_shows_only_checked_off_items_after_first() {
+        let todo_write = |content: &str| {
+            let mut arguments = rmcp::model::JsonObject::new();
+            arguments.insert("content".to_string(), Value::from(content));
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(CallToolRequestParams {
+                    meta: None,
+                    task: None,
+                    name: "todo__write".into(),
+                    arguments: Some(arguments),
+                }),
+            )
+        };
+        assert_eq!(
+            rendered(&todo_write("- [ ] a\n  - [ ] b")),
+            "\n  ▸ write todo\n    ☐ a\n      ☐ b\n    todo · 0/2 done · 0%\n\n"
+        );
+        assert_eq!(
+            rendered(&todo_write("- [ ] a\n  - [x] b")),
+            "\n  ▸ write todo\n      ☑ b\n    todo · 1/2 done · 50%\n\n"
+        );
+    }
+
+    #[test]
+    fn test_tool_response_snapshot_skips_unprioritized_content() {
+        let message = Message::user().with_tool_response(
+            "call_1",
+            Ok(rmcp::model::CallToolResult::success(vec![
+                Content::text("Cargo.toml\nsrc\n").with_priority(1.0),
+                Content::text("only for the model"),
+            ])),
+        );
+        assert_eq!(rendered(&message), "Cargo.toml\nsrc\n");
+    }
+
+    #[test]
+    fn test_concurrent_tool_responses_are_framed() {
+        let shell = |command: &str| {
+            let mut arguments = rmcp::model::JsonObject::new();
+            arguments.insert("command".to_string(), Value::from(command));
+            Ok(CallToolRequestParams {
+                meta: None,
+                task: None,
+                name: "developer__shell".into(),
+                arguments: Some(arguments),
+            })
+        };
+        rendered(
+            &Message::assistant()
+                .with_tool_request("call_1", shell("make"))
+                .with_tool_request("call_2", shell("ls")),
+        );
+        assert!(hold_tool_log("call_2", "building..."));
+
+        let mut result =
+            rmcp::model::CallToolResult::success(vec![Content::text("done\n").with_priority(1.0)]);
+        let mut meta = rmcp::model::Meta::new();
+        meta.0.insert(
+            goose::agents::TOOL_ELAPSED_MS_META_KEY.to_string(),
+            Value::from(2300),
+        );
+        result.meta = Some(meta);
+        assert_eq!(
+            rendered(&Message::user().with_tool_response("call_2", Ok(result))),
+            "\n  ┌─ 2/2 shell developer · 2.3s\n  │ building...\ndone\n  └─\n"
+        );
+    }
+
+    #[test]
+    fn test_format_subagent_summary() {
+        assert_eq!(
+            format_subagent_summary(1, 1, Duration::from_secs(3)),
+            "1 subagent finished · 1 tool call · 3 seconds"
+        );
+        assert_eq!(
+            format_subagent_summary(3, 14, Duration::from_secs(90)),
+            "3 subagents finished · 14 tool calls · 2 minutes"
+        );
+    }
+
+    #[test]
+    fn test_format_job_status() {
+        assert_eq!(
+            format_job_status(JobStatus::Running, Some(4242), Duration::from_secs(90)),
+            "running for 2 minutes · pid 4242"
+        );
+        assert_eq!(
+            format_job_status(JobStatus::Exited(Some(1)), None, Duration::ZERO),
+            "exited with code 1"
+        );
+        assert_eq!(
+            format_job_status(JobStatus::Stopped, Some(4242), Duration::ZERO),
+            "stopped"
+        );
+    }
+
+    #[test]
+    fn test_format_rate_limit_waits() {
+        assert_eq!(
+            format_rate_limit_waits(1),
+            "1 subagent waiting for rate limit window"
+        );
+        assert_eq!(
+            format_rate_limit_waits(2),
+            "2 subagents waiting for rate limit window"
+        );
+    }
+
+    #[test]
+    fn test_format_cost_ceiling() {
+        assert_eq!(
+            format_cost_ceiling(Some(0.72), 1000),
+            "up to $0.72 a turn, $720.00 over 1000 turns (--max-turns)"
+        );
+        assert!(format_cost_ceiling(None, 5).starts_with("unknown"));
+    }
+
+    fn tool(name: &str) -> Tool {
+        Tool::new(
+            name.to_string(),
+            "does things",
+            Arc::new(serde_json::Map::new()),
+        )
+    }
+
+    #[test]
+    fn test_select_tools() {
+        let tools = [
+            tool("developer__shell"),
+            tool("developer__text_editor"),
+            tool("memory__remember"),
+        ];
+        let names = |selected: &[&Tool]| -> Vec<String> {
+            selected.iter().map(|t| t.name.to_string()).collect()
+        };
+        match select_tools(&tools, "developer") {
+            ToolSelection::Extension(selected) => assert_eq!(selected.len(), 2),
+            ToolSelection::Tools(_) => panic!("expected the developer extension"),
+        }
+        match select_tools(&tools, "remember") {
+            ToolSelection::Tools(selected) => {
+                assert_eq!(names(&selected), vec!["memory__remember"])
+            }
+            ToolSelection::Extension(_) => panic!("expected a tool"),
+        }
+        match select_tools(&tools, "developer__shell") {
+            ToolSelection::Tools(selected) => assert_eq!(selected.len(), 1),
+            ToolSelection::Extension(_) => panic!("expected a tool"),
+        }
+        assert!(matches!(
+            select_tools(&tools, "missing"),
+            ToolSelection::Tools(selected) if selected.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_format_model_preferences() {
+        let mut preview = SamplingPreview {
+            system_prompt: None,
+            messages: Vec::new(),
+            model_hints: vec!["claude".to_string(), "gpt".to_string()],
+            cost_priority: Some(0.8),
+            speed_priority: None,
+            intelligence_priority: Some(0.5),
+            max_tokens: 100,
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+        };
+        assert_eq!(
+            format_model_preferences(&preview).as_deref(),
+            Some("claude, gpt · cost 0.8 · intelligence 0.5")
+        );
+        preview.model_hints.clear();
+        preview.cost_priority = None;
+        preview.intelligence_priority = None;
+        assert_eq!(format_model_preferences(&preview), None);
+    }
+
+    #[test]
+    fn test_format_citations_numbers_each_source_once() {
+        let citation = |title: Option<&str>, url: Option<&str>, text: Option<&str>| Citation {
+            title: title.map(str::to_string),
+            url: url.map(str::to_string),
+            cited_text: text.map(str::to_string),
+        };
+        let citations = [
+            citation(
+                Some("Rust 1.0"),
+                Some("https://blog.rust-lang.org"),
+                Some("first"),
+            ),
+            citation(None, Some("https://docs.rs"), None),
+            citation(
+                Some("Rust 1.0"),
+                Some("https://blog.rust-lang.org"),
+                Some("second"),
+            ),
+            citation(Some("Release notes"), None, Some("Version 1.0.0\n")),
+        ];
+        assert_eq!(
+            format_citations(&citations),
+            vec![
+                "[1] Rust 1.0 · https://blog.rust-lang.org",
+                "[2] https://docs.rs",
+                "[3] Release notes · \"Version 1.0.0\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_tool_args_summary() {
+        let args = serde_json::json!({"command": "ls -la\necho done", "timeout": 30});
+        assert_eq!(
+            format_tool_args_summary(args.as_object().unwrap()),
+            "command: ls -la, timeout: 30"
+        );
+    }
+
+    #[test]
+    fn test_format_model_metadata() {
+        let model: CanonicalModel = serde_json::from_value(serde_json::json!({
+            "id": "anthropic/claude-sonnet-4",
+            "name": "Claude Sonnet 4",
+            "tool_call": true,
+            "reasoning": true,
+            "modalities": {"input": ["text", "image"], "output": ["text"]},
+            "cost": {"input": 3.0, "output": 15.0},
+            "limit": {"context": 200000}
+        }))
+        .unwrap();
+        assert_eq!(
+            format_model_metadata(&model),
+            "200k context · $3.00/$15.00 per 1M tokens · vision · tools · reasoning"
+        );
+    }
+
+    #[test]
+    fn test_verbosity_from_config_str() {
+        assert_eq!(Verbosity::from_config_str("Quiet"), Some(Verbosity::Silent));
+        assert_eq!(
+            Verbosity::from_config_str(" verbose "),
+            Some(Verbosity::Verbose)
+        );
+        assert_eq!(Verbosity::from_config_str("loud"), None);
+    }
+
+    #[test]
+    fn test_verbosity_levels_are_cumulative() {
+        assert!(!Verbosity::Silent.shows_tool_calls());
+        assert!(Verbosity::Minimal.shows_tool_calls());
+        assert!(!Verbosity::Minimal.shows_tool_details());
+        assert!(Verbosity::Debug.shows_tool_details());
+        assert_eq!(
+            Verbosity::resolve(Some(Verbosity::Minimal), true),
+            Verbosity::Minimal
+        );
+        assert_eq!(Verbosity::resolve(None, true), Verbosity::Debug);
+    }
+
     #[test]
     fn test_short_paths_unchanged() {
         assert_eq!(shorten_path("/usr/bin", false), "/usr/bin");
@@ -1460,4 +4735,23 @@ mod tests {
             "/v/l/p/w/m/components/file.txt"
         );
     }
+
+    #[test]
+    fn test_stderr_lines_collapse_outside_debug() {
+        let stderr = "1\n2\n3\n4\n5\n6\n7";
+        assert_eq!(
+            stderr_lines(stderr, false),
+            (vec!["3", "4", "5", "6", "7"], 2)
+        );
+        assert_eq!(stderr_lines(stderr, true).1, 0);
+        assert_eq!(stderr_lines("boom", false), (vec!["boom"], 0));
+    }
+
+    #[test]
+    fn test_numbering_start_is_capped() {
+        assert_eq!(numbering_start(42), 42);
+        assert_eq!(numbering_start(0), 1);
+        assert_eq!(numbering_start(MAX_FIRST_LINE), MAX_FIRST_LINE);
+        assert_eq!(numbering_start(usize::MAX), 1);
+    }
 }
@@ -0,0 +1,240 @@
+use console::{Style, StyledObject};
+use goose::config::Config;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// Colors and symbols of the CLI's own output, outside of the bat-highlighted markdown, set
+/// with the `GOOSE_CLI_PALETTE` config section so they can match the terminal's colors:
+///
+/// ```yaml
+/// GOOSE_CLI_PALETTE:
+///   header: blue.bold
+///   tool_bullet: ">"
+///   tool: white
+///   error_prefix: "✗"
+///   context_filled: "#"
+///   context_empty: "."
+/// ```
+///
+/// Styles are dotted names as console reads them, e.g. `red.bold`, `on_black.white` or
+/// `#ff8800`. Entries left out keep their default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    header: Style,
+    header_symbol: String,
+    tool: Style,
+    tool_bullet: String,
+    error: Style,
+    error_prefix: String,
+    context_low: Style,
+    context_mid: Style,
+    context_high: Style,
+    context_filled: char,
+    context_empty: char,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            header: Style::new().green(),
+            header_symbol: "●".to_string(),
+            tool: Style::new().dim(),
+            tool_bullet: "▸".to_string(),
+            error: Style::new().red().bold(),
+            error_prefix: "error:".to_string(),
+            context_low: Style::new().green().dim(),
+            context_mid: Style::new().yellow(),
+            context_high: Style::new().red(),
+            context_filled: '━',
+            context_empty: '╌',
+        }
+    }
+}
+
+/// A style such as `red.bold`, refusing the parts console would silently ignore
+fn parse_style(spec: &str) -> Result<Style, String> {
+    let spec = spec.trim();
+    let unknown: Vec<&str> = spec
+        .split('.')
+        .filter(|part| Style::from_dotted_str(part) == Style::new())
+        .collect();
+    if spec.is_empty() || !unknown.is_empty() {
+        return Err(format!("'{}' is not a style", spec));
+    }
+    Ok(Style::from_dotted_str(spec))
+}
+
+fn parse_symbol(spec: &str) -> Result<String, String> {
+    if spec.trim().is_empty() {
+        return Err("symbols can't be blank".to_string());
+    }
+    Ok(spec.to_string())
+}
+
+/// The context bar is a fixed number of cells, so its glyphs are one character each
+fn parse_glyph(spec: &str) -> Result<char, String> {
+    let mut chars = spec.chars();
+    match (chars.next(), chars.next()) {
+        (Some(glyph), None) if !glyph.is_whitespace() => Ok(glyph),
+        _ => Err(format!("'{}' is not a single character", spec)),
+    }
+}
+
+static PALETTE: LazyLock<Palette> = LazyLock::new(Palette::from_config);
+
+/// The palette from the config, read once per process
+pub fn palette() -> &'static Palette {
+    &PALETTE
+}
+
+impl Palette {
+    fn from_config() -> Self {
+        let entries = Config::global()
+            .get_param::<HashMap<String, String>>("GOOSE_CLI_PALETTE")
+            .unwrap_or_default();
+        let (palette, errors) = Self::parse(&entries);
+        for error in errors {
+            warn!("Ignoring GOOSE_CLI_PALETTE entry: {}", error);
+        }
+        palette
+    }
+
+    /// Builds the palette from `name: value` entries over the defaults, leaving out the ones
+    /// that don't parse.
+    pub fn parse(entries: &HashMap<String, String>) -> (Self, Vec<String>) {
+        let mut palette = Self::default();
+        let mut errors = Vec::new();
+        let mut names: Vec<&String> = entries.keys().collect();
+        names.sort();
+        for name in names {
+            let spec = &entries[name];
+            let result = match name.as_str() {
+                "header" => parse_style(spec).map(|style| palette.header = style),
+                "header_symbol" => parse_symbol(spec).map(|symbol| palette.header_symbol = symbol),
+                "tool" => parse_style(spec).map(|style| palette.tool = style),
+                "tool_bullet" => parse_symbol(spec).map(|symbol| palette.tool_bullet = symbol),
+                "error" => parse_style(spec).map(|style| palette.error = style),
+                "error_prefix" => parse_symbol(spec).map(|symbol| palette.error_prefix = symbol),
+                "context_low" => parse_style(spec).map(|style| palette.context_low = style),
+                "context_mid" => parse_style(spec).map(|style| palette.context_mid = style),
+                "context_high" => parse_style(spec).map(|style| palette.context_high = style),
+                "context_filled" => parse_glyph(spec).map(|glyph| palette.context_filled = glyph),
+                "context_empty" => parse_glyph(spec).map(|glyph| palette.context_empty = glyph),
+                _ => Err(format!("unknown entry '{}'", name)),
+            };
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", name, e));
+            }
+        }
+        (palette, errors)
+    }
+
+    /// The dot that starts the session header
+    pub fn header_symbol(&self) -> StyledObject<&str> {
+        self.header.apply_to(self.header_symbol.as_str())
+    }
+
+    /// The bullet in front of each tool call
+    pub fn tool_bullet(&self) -> StyledObject<&str> {
+        self.tool.apply_to(self.tool_bullet.as_str())
+    }
+
+    /// The prefix of error messages
+    pub fn error_prefix(&self) -> StyledObject<&str> {
+        self.error.apply_to(self.error_prefix.as_str())
+    }
+
+    /// A bar of `width` cells with `filled` of them filled, colored by how full the context is
+    pub fn context_bar(
+        &self,
+        filled: usize,
+        width: usize,
+        percentage: usize,
+    ) -> StyledObject<String> {
+        let bar = format!(
+            "{}{}",
+            self.context_filled.to_string().repeat(filled.min(width)),
+            self.context_empty
+                .to_string()
+                .repeat(width - filled.min(width))
+        );
+        let style = if percentage < 50 {
+            &self.context_low
+        } else if percentage < 85 {
+            &self.context_mid
+        } else {
+            &self.context_high
+        };
+        style.apply_to(bar)
+    }
+
+    /// `cells` of the context bar's filled glyph in `style`, for a bar stacked from several parts
+    pub fn context_segment(&self, cells: usize, style: &Style) -> StyledObject<String> {
+        style.apply_to(self.context_filled.to_string().repeat(cells))
+    }
+
+    /// `cells` of the context bar's empty glyph, for the free end of a stacked bar
+    pub fn context_free(&self, cells: usize) -> StyledObject<String> {
+        Style::new()
+            .dim()
+            .apply_to(self.context_empty.to_string().repeat(cells))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn entries(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_overrides_defaults() {
+        let (palette, errors) = Palette::parse(&entries(&[
+            ("header", "blue.bold"),
+            ("tool_bullet", ">"),
+            ("context_filled", "#"),
+        ]));
+        assert!(errors.is_empty());
+        assert_eq!(palette.header, Style::new().blue().bold());
+        assert_eq!(palette.tool_bullet, ">");
+        assert_eq!(palette.context_filled, '#');
+        assert_eq!(palette.error, Palette::default().error);
+    }
+
+    #[test_case("header", "blue.shiny" ; "unknown style part")]
+    #[test_case("tool_bullet", "  " ; "blank symbol")]
+    #[test_case("context_empty", "--" ; "glyph too long")]
+    #[test_case("bullet", ">" ; "unknown entry")]
+    fn test_parse_rejects(name: &str, value: &str) {
+        let (palette, errors) = Palette::parse(&entries(&[(name, value)]));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(palette, Palette::default());
+    }
+
+    #[test]
+    fn test_context_bar_cells() {
+        let (palette, _) =
+            Palette::parse(&entries(&[("context_filled", "#"), ("context_empty", ".")]));
+        assert_eq!(
+            palette
+                .context_bar(3, 5, 60)
+                .force_styling(false)
+                .to_string(),
+            "###.."
+        );
+        assert_eq!(
+            palette
+                .context_bar(9, 5, 100)
+                .force_styling(false)
+                .to_string(),
+            "#####"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::{bail, Result};
+use goose::model::ModelConfig;
+
+/// Reasoning efforts the OpenAI format reads from a `-low`, `-medium` or `-high` suffix on the
+/// model name, for the models that take one
+const REASONING_EFFORTS: [&str; 3] = ["low", "medium", "high"];
+/// What those models use when the name has no suffix
+const DEFAULT_REASONING_EFFORT: &str = "medium";
+/// Model families that take a reasoning effort, as recognized by the OpenAI format
+const REASONING_MODEL_PREFIXES: [&str; 4] = ["o1", "o3", "o4", "gpt-5"];
+
+/// A generation parameter set with `/params`, `None` going back to the default
+#[derive(Debug, PartialEq)]
+pub enum ParamChange {
+    Temperature(Option<f32>),
+    MaxTokens(Option<i32>),
+    ReasoningEffort(Option<String>),
+}
+
+/// `temperature 0.2`, `max_tokens 4096`, `effort high`, or any of them with `default`, or
+/// `None` when the arguments don't name a parameter and a valid value for it
+pub fn parse(args: &str) -> Option<ParamChange> {
+    let mut parts = args.split_whitespace();
+    let (name, value) = (parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let value = (value != "default").then_some(value);
+    match name {
+        "temperature" | "temp" => Some(ParamChange::Temperature(match value {
+            Some(value) => Some(value.parse().ok().filter(|t: &f32| *t >= 0.0)?),
+            None => None,
+        })),
+        "max_tokens" | "max-tokens" => Some(ParamChange::MaxTokens(match value {
+            Some(value) => Some(value.parse().ok().filter(|n: &i32| *n > 0)?),
+            None => None,
+        })),
+        "effort" | "reasoning_effort" | "reasoning-effort" => {
+            Some(ParamChange::ReasoningEffort(match value {
+                Some(value) if REASONING_EFFORTS.contains(&value) => Some(value.to_string()),
+                Some(_) => return None,
+                None => None,
+            }))
+        }
+        _ => None,
+    }
+}
+
+fn takes_reasoning_effort(model_name: &str) -> bool {
+    REASONING_MODEL_PREFIXES
+        .iter()
+        .any(|prefix| model_name.starts_with(prefix))
+}
+
+/// The model's name without a reasoning effort suffix, and the effort it asks for
+fn split_reasoning_effort(model_name: &str) -> (&str, Option<&str>) {
+    match model_name.rsplit_once('-') {
+        Some((base, effort)) if REASONING_EFFORTS.contains(&effort) => (base, Some(effort)),
+        _ => (model_name, None),
+    }
+}
+
+/// The reasoning effort the model runs with, for models that take one
+pub fn reasoning_effort(model_name: &str) -> Option<&str> {
+    if !takes_reasoning_effort(model_name) {
+        return None;
+    }
+    Some(
+        split_reasoning_effort(model_name)
+            .1
+            .unwrap_or(DEFAULT_REASONING_EFFORT),
+    )
+}
+
+/// The model config with the change made, to create the session's provider again with
+pub fn apply(config: ModelConfig, change: ParamChange) -> Result<ModelConfig> {
+    Ok(match change {
+        ParamChange::Temperature(temperature) => config.with_temperature(temperature),
+        ParamChange::MaxTokens(max_tokens) => config.with_max_tokens(max_tokens),
+        ParamChange::ReasoningEffort(effort) => {
+            if !takes_reasoning_effort(&config.model_name) {
+                bail!("{} doesn't take a reasoning effort", config.model_name);
+            }
+            let base = split_reasoning_effort(&config.model_name).0;
+            let model_name = match effort {
+                Some(effort) => format!("{}-{}", base, effort),
+                None => base.to_string(),
+            };
+            ModelConfig {
+                model_name,
+                ..config
+            }
+        }
+    })
+}
+
+/// e.g. `temperature 0.2 · max tokens 4096 · reasoning effort high`
+pub fn describe(config: &ModelConfig) -> String {
+    let mut parts = vec![
+        match config.temperature {
+            Some(temperature) => format!("temperature {}", temperature),
+            None => "temperature default".to_string(),
+        },
+        format!("max tokens {}", config.max_output_tokens()),
+    ];
+    if let Some(effort) = reasoning_effort(&config.model_name) {
+        parts.push(format!("reasoning effort {}", effort));
+    }
+    parts.join(" · ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("temperature 0.2", Some(ParamChange::Temperature(Some(0.2))) ; "temperature")]
+    #[test_case("max_tokens default", Some(ParamChange::MaxTokens(None)) ; "reset")]
+    #[test_case("effort high", Some(ParamChange::ReasoningEffort(Some("high".to_string()))) ; "effort")]
+    #[test_case("effort extreme", None ; "unknown effort")]
+    #[test_case("max_tokens -1", None ; "negative")]
+    #[test_case("temperature", None ; "no value")]
+    #[test_case("top_p 0.9", None ; "unknown parameter")]
+    fn test_parse(args: &str, expected: Option<ParamChange>) {
+        assert_eq!(parse(args), expected);
+    }
+
+    #[test_case("o3-mini-high", Some("high") ; "suffix")]
+    #[test_case("gpt-5", Some("medium") ; "default")]
+    #[test_case("gpt-4o", None ; "no effort")]
+    fn test_reasoning_effort(model_name: &str, expected: Option<&str>) {
+        assert_eq!(reasoning_effort(model_name), expected);
+    }
+
+    #[test]
+    fn test_apply_reasoning_effort_renames_the_model() {
+        let config = ModelConfig::new("o3-mini-low").unwrap();
+        let config = apply(
+            config,
+            ParamChange::ReasoningEffort(Some("high".to_string())),
+        )
+        .unwrap();
+        assert_eq!(config.model_name, "o3-mini-high");
+        let config = apply(config, ParamChange::ReasoningEffort(None)).unwrap();
+        assert_eq!(config.model_name, "o3-mini");
+
+        let config = ModelConfig::new("gpt-4o").unwrap();
+        assert!(apply(
+            config,
+            ParamChange::ReasoningEffort(Some("low".to_string()))
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use goose::conversation::message::{Message, MessageContent};
+use goose::session::Pin;
+use goose::utils::safe_truncate;
+use std::path::Path;
+
+/// What `/pin <target>` refers to: a message by how far back it is, or a file.
+#[derive(Debug, PartialEq)]
+pub enum PinTarget<'a> {
+    /// 1 is the most recent message with text
+    Message(usize),
+    File(&'a Path),
+}
+
+pub fn parse_target(arg: &str) -> PinTarget<'_> {
+    match arg.trim_start_matches('#').parse::<usize>() {
+        Ok(n) => PinTarget::Message(n),
+        Err(_) => PinTarget::File(Path::new(arg.trim_start_matches('@'))),
+    }
+}
+
+/// The `n`th most recent message shown in the conversation that has text, 1 being the last.
+/// Tool results are skipped; they are better kept by pinning the file they came from.
+pub fn recent_message(messages: &[Message], n: usize) -> Option<&Message> {
+    messages
+        .iter()
+        .rev()
+        .filter(|m| {
+            m.metadata.user_visible
+                && !m.content.iter().any(|c| {
+                    matches!(
+                        c,
+                        MessageContent::ToolRequest(_) | MessageContent::ToolResponse(_)
+                    )
+                })
+                && !m.as_concat_text().trim().is_empty()
+        })
+        .nth(n.checked_sub(1)?)
+}
+
+pub fn message_pin(message: &Message) -> Pin {
+    Pin::Message {
+        id: message.id.clone(),
+        text: message.as_concat_text(),
+    }
+}
+
+/// A one-line preview of a pin for the `/pin` listing
+pub fn describe(pin: &Pin) -> String {
+    match pin {
+        Pin::Message { text, .. } => {
+            let line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            format!("message: {}", safe_truncate(line.trim(), 60))
+        }
+        Pin::File { path } => format!("file: {}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::CallToolResult;
+    use test_case::test_case;
+
+    #[test_case("2", PinTarget::Message(2) ; "number")]
+    #[test_case("#1", PinTarget::Message(1) ; "hash number")]
+    #[test_case("@src/main.rs", PinTarget::File(Path::new("src/main.rs")) ; "mention")]
+    #[test_case("NOTES.md", PinTarget::File(Path::new("NOTES.md")) ; "path")]
+    fn test_parse_target(arg: &str, expected: PinTarget) {
+        assert_eq!(parse_target(arg), expected);
+    }
+
+    #[test]
+    fn test_recent_message_skips_tool_traffic() {
+        let messages = vec![
+            Message::user().with_text("use tabs, not spaces"),
+            Message::assistant().with_text("noted"),
+            Message::user().with_tool_response("1", Ok(CallToolResult::success(vec![]))),
+            Message::assistant().with_text("hidden").agent_only(),
+        ];
+        let text = |n| recent_message(&messages, n).map(|m| m.as_concat_text());
+        assert_eq!(text(1).as_deref(), Some("noted"));
+        assert_eq!(text(2).as_deref(), Some("use tabs, not spaces"));
+        assert_eq!(text(3), None);
+        assert_eq!(text(0), None);
+    }
+}
@@ -0,0 +1,270 @@
+use goose::conversation::message::{Message, MessageContent};
+use regex::Regex;
+use rmcp::model::Role;
+use std::sync::LazyLock;
+
+static NUMBERED_STEP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:#{1,6}\s+)?(?:[Ss]tep\s+)?(\d+)[.):]\s+(.+)$").unwrap());
+
+static PROGRESS_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\[step\s+(\d+):\s*(started|in progress|done|failed)\]").unwrap()
+});
+
+/// What could still turn into a progress marker once more text streams in
+static MARKER_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\[(?:s(?:t(?:e(?:p(?:\s+(?:\d+(?::\s*[a-z ]*)?)?)?)?)?)?)?$").unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepStatus {
+    Pending,
+    InProgress,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    pub text: String,
+    pub status: StepStatus,
+}
+
+/// The steps of a plan the agent is acting on, with the progress it has reported. The agent is
+/// asked to announce each step with a marker like `[step 2: done]`, which is picked out of its
+/// streamed text.
+#[derive(Debug, Clone)]
+pub struct PlanChecklist {
+    steps: Vec<PlanStep>,
+    /// Text after the last newline, which may hold half of a marker
+    partial_line: String,
+    /// How much of `partial_line` has been let through to be shown
+    shown: usize,
+}
+
+impl PlanChecklist {
+    /// Reads the top-level numbered steps of a plan, either a list (`1. ...`) or headings
+    /// (`## Step 1: ...`). Returns `None` if there are fewer than two.
+    pub fn parse(plan: &str) -> Option<Self> {
+        let mut steps = Vec::new();
+        let mut in_code_block = false;
+        for line in plan.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+            let Some(captures) = NUMBERED_STEP.captures(line) else {
+                continue;
+            };
+            // only count steps numbered in sequence, so nested lists further down don't
+            // restart the plan
+            if captures[1].parse::<usize>().ok() != Some(steps.len() + 1) {
+                continue;
+            }
+            steps.push(PlanStep {
+                text: captures[2].trim().trim_matches('*').trim().to_string(),
+                status: StepStatus::Pending,
+            });
+        }
+        (steps.len() >= 2).then_some(Self {
+            steps,
+            partial_line: String::new(),
+            shown: 0,
+        })
+    }
+
+    pub fn steps(&self) -> &[PlanStep] {
+        &self.steps
+    }
+
+    /// Added to the plan when handing it to the agent
+    pub fn instructions(&self) -> String {
+        format!(
+            "While working through the {} steps above, report progress on a line of its own \
+             as `[step N: started]` when you begin step N, then `[step N: done]` or \
+             `[step N: failed]` when it is finished.",
+            self.steps.len()
+        )
+    }
+
+    /// Picks progress markers out of a streamed assistant message and takes them out of its
+    /// text, so they are neither shown nor kept with the conversation. Text that may be the
+    /// start of a marker is held back until the rest of it arrives. Returns whether any step
+    /// changed.
+    pub fn record(&mut self, message: &mut Message) -> bool {
+        if message.role != Role::Assistant {
+            return false;
+        }
+        let mut changed = false;
+        for content in &mut message.content {
+            if let MessageContent::Text(text) = content {
+                self.partial_line.push_str(&text.text);
+                let mut shown = String::new();
+                if let Some(end) = self.partial_line.rfind('\n') {
+                    let complete: String = self.partial_line.drain(..=end).collect();
+                    changed |= self.apply_markers(&complete);
+                    let unshown = complete.get(self.shown..).unwrap_or_default();
+                    shown.push_str(&strip_marker_lines(unshown, self.shown == 0));
+                    self.shown = 0;
+                }
+
+                let pending = self.partial_line.get(self.shown..).unwrap_or_default();
+                let whole_line_may_be_marker =
+                    self.shown == 0 && PROGRESS_MARKER.replace_all(pending, "").trim().is_empty();
+                let held_from = if whole_line_may_be_marker {
+                    0
+                } else {
+                    pending
+                        .rfind('[')
+                        .filter(|&i| MARKER_PREFIX.is_match(pending.get(i..).unwrap_or_default()))
+                        .unwrap_or(pending.len())
+                };
+                let released = pending.get(..held_from).unwrap_or_default();
+                shown.push_str(&PROGRESS_MARKER.replace_all(released, ""));
+                self.shown += held_from;
+                text.text = shown;
+            }
+        }
+        changed
+    }
+
+    /// Applies whatever is left over once the agent has finished responding.
+    pub fn finish(&mut self) -> bool {
+        let rest = std::mem::take(&mut self.partial_line);
+        self.shown = 0;
+        self.apply_markers(&rest)
+    }
+
+    fn apply_markers(&mut self, text: &str) -> bool {
+        let mut changed = false;
+        for captures in PROGRESS_MARKER.captures_iter(text) {
+            let Some(index) = captures[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .filter(|i| *i < self.steps.len())
+            else {
+                continue;
+            };
+            let status = match captures[2].to_lowercase().as_str() {
+                "done" => StepStatus::Done,
+                "failed" => StepStatus::Failed,
+                _ => StepStatus::InProgress,
+            };
+            if status == StepStatus::InProgress {
+                // a step still going when a later one starts was finished without saying so
+                for step in &mut self.steps[..index] {
+                    if step.status == StepStatus::InProgress {
+                        step.status = StepStatus::Done;
+                        changed = true;
+                    }
+                }
+            }
+            if self.steps[index].status != status {
+                self.steps[index].status = status;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Takes the markers out of complete lines, dropping the lines that held nothing else. The
+/// first line is kept when the start of it was already shown, to end it.
+fn strip_marker_lines(text: &str, at_line_start: bool) -> String {
+    text.split_inclusive('\n')
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let stripped = PROGRESS_MARKER.replace_all(line, "");
+            let only_markers = stripped.len() != line.len() && stripped.trim().is_empty();
+            (!only_markers || (i == 0 && !at_line_start)).then(|| stripped.into_owned())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const PLAN: &str = "Here is the plan:\n\n\
+        1. **Read the config loader**\n   1. check defaults\n\
+        2. Add the new option\n\
+        ```\n3. not a step\n```\n\
+        3. Write tests\n";
+
+    fn statuses(plan: &PlanChecklist) -> Vec<StepStatus> {
+        plan.steps().iter().map(|s| s.status).collect()
+    }
+
+    #[test]
+    fn test_parse_numbered_list() {
+        let plan = PlanChecklist::parse(PLAN).unwrap();
+        let texts: Vec<_> = plan.steps().iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Read the config loader",
+                "Add the new option",
+                "Write tests"
+            ]
+        );
+    }
+
+    #[test_case("## Step 1: Explore\n## Step 2: Change\n", Some(2) ; "step headings")]
+    #[test_case("1. Only one thing\n", None ; "single step")]
+    #[test_case("No plan here", None ; "no steps")]
+    fn test_parse_step_count(plan: &str, expected: Option<usize>) {
+        assert_eq!(
+            PlanChecklist::parse(plan).map(|p| p.steps().len()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_record_markers_split_across_chunks() {
+        use StepStatus::*;
+        let mut plan = PlanChecklist::parse(PLAN).unwrap();
+        assert!(!plan.record(&mut Message::assistant().with_text("[step 1: sta")));
+        assert!(plan.record(&mut Message::assistant().with_text("rted]\nLooking...\n")));
+        assert_eq!(statuses(&plan), vec![InProgress, Pending, Pending]);
+
+        assert!(plan.record(&mut Message::assistant().with_text("[Step 2: started]\n")));
+        assert_eq!(statuses(&plan), vec![Done, InProgress, Pending]);
+
+        assert!(!plan.record(&mut Message::user().with_text("[step 3: done]\n")));
+        assert!(!plan.record(&mut Message::assistant().with_text("[step 9: done]\n")));
+
+        assert!(!plan.record(&mut Message::assistant().with_text("[step 2: failed]")));
+        assert!(plan.finish());
+        assert_eq!(statuses(&plan), vec![Done, Failed, Pending]);
+    }
+
+    #[test]
+    fn test_record_takes_markers_out_of_the_text() {
+        let mut plan = PlanChecklist::parse(PLAN).unwrap();
+        let shown: String = [
+            "[step 1: sta",
+            "rted]\nReading ",
+            "the loader [st",
+            "ep 1: done] now\n",
+            "[link](url) and [s",
+            "ome] text\n",
+            "[step 2: started]",
+            "\n",
+        ]
+        .into_iter()
+        .map(|chunk| {
+            let mut message = Message::assistant().with_text(chunk);
+            plan.record(&mut message);
+            message.as_concat_text()
+        })
+        .collect();
+        assert_eq!(
+            shown,
+            "Reading the loader  now\n[link](url) and [some] text\n"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use rmcp::model::PromptArgument;
+use std::collections::HashMap;
+
+fn is_required(argument: &PromptArgument) -> bool {
+    argument.required.unwrap_or(false)
+}
+
+/// Required arguments of a prompt that weren't given, or were given empty, in the order the
+/// prompt declares them.
+pub fn missing_required<'a>(
+    arguments: &'a [PromptArgument],
+    provided: &HashMap<String, String>,
+) -> Vec<&'a str> {
+    arguments
+        .iter()
+        .filter(|arg| is_required(arg))
+        .filter(|arg| {
+            provided
+                .get(&arg.name)
+                .is_none_or(|value| value.trim().is_empty())
+        })
+        .map(|arg| arg.name.as_str())
+        .collect()
+}
+
+fn label(argument: &PromptArgument) -> String {
+    let name = argument.title.as_deref().unwrap_or(&argument.name);
+    match &argument.description {
+        Some(description) => format!("{} ({})", name, description),
+        None => name.to_string(),
+    }
+}
+
+/// Asks for each of the prompt's arguments, pre-filled with what was already given. Required
+/// ones can't be left empty; optional ones left empty are not passed on. Values given for
+/// arguments the prompt doesn't declare are kept as they are.
+pub fn collect(
+    arguments: &[PromptArgument],
+    mut provided: HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    for argument in arguments {
+        let required = is_required(argument);
+        let mut input = cliclack::input(label(argument)).required(required);
+        if let Some(value) = provided.get(&argument.name) {
+            input = input.default_input(value);
+        }
+        let value: String = input
+            .validate(move |text: &String| {
+                if required && text.trim().is_empty() {
+                    Err("This argument is required")
+                } else {
+                    Ok(())
+                }
+            })
+            .interact()?;
+
+        if value.trim().is_empty() {
+            provided.remove(&argument.name);
+        } else {
+            provided.insert(argument.name.clone(), value);
+        }
+    }
+    Ok(provided)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argument(name: &str, required: Option<bool>) -> PromptArgument {
+        PromptArgument {
+            name: name.to_string(),
+            title: None,
+            description: None,
+            required,
+        }
+    }
+
+    #[test]
+    fn test_missing_required() {
+        let arguments = vec![
+            argument("repo", Some(true)),
+            argument("branch", None),
+            argument("title", Some(true)),
+            argument("body", Some(true)),
+        ];
+        let provided = HashMap::from([
+            ("repo".to_string(), "goose".to_string()),
+            ("title".to_string(), "  ".to_string()),
+            ("extra".to_string(), "kept".to_string()),
+        ]);
+        assert_eq!(
+            missing_required(&arguments, &provided),
+            vec!["title", "body"]
+        );
+    }
+
+    #[test]
+    fn test_label_prefers_title_and_shows_description() {
+        let mut arg = argument("repo", Some(true));
+        assert_eq!(label(&arg), "repo");
+        arg.title = Some("Repository".to_string());
+        arg.description = Some("owner/name".to_string());
+        assert_eq!(label(&arg), "Repository (owner/name)");
+    }
+}
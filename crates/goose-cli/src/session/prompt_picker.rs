@@ -0,0 +1,186 @@
+use anyhow::Result;
+use goose::utils::safe_truncate;
+use rmcp::model::Prompt;
+use std::collections::HashMap;
+
+/// Value of the entry that lists the prompts of every extension
+const ALL_EXTENSIONS: &str = "";
+/// Longest description shown next to a prompt in the list
+const MAX_HINT_CHARS: usize = 80;
+
+/// Extensions that have prompts, by name, with their prompts by name
+fn sorted(prompts: HashMap<String, Vec<Prompt>>) -> Vec<(String, Vec<Prompt>)> {
+    let mut extensions: Vec<(String, Vec<Prompt>)> = prompts
+        .into_iter()
+        .filter(|(_, prompts)| !prompts.is_empty())
+        .map(|(extension, mut prompts)| {
+            prompts.sort_by(|a, b| a.name.cmp(&b.name));
+            (extension, prompts)
+        })
+        .collect();
+    extensions.sort_by(|a, b| a.0.cmp(&b.0));
+    extensions
+}
+
+/// The prompt's name, after its extension's when prompts of several extensions are listed
+/// together, so typing either narrows the list down.
+fn prompt_label(extension: &str, prompt: &Prompt, qualified: bool) -> String {
+    let name = prompt.title.as_deref().unwrap_or(&prompt.name);
+    if qualified {
+        format!("{}/{}", extension, name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// The first line of the description and how many arguments the prompt takes
+fn prompt_hint(prompt: &Prompt) -> String {
+    let mut parts = Vec::new();
+    if let Some(line) = prompt
+        .description
+        .as_deref()
+        .and_then(|description| description.lines().next())
+        .filter(|line| !line.trim().is_empty())
+    {
+        parts.push(safe_truncate(line.trim(), MAX_HINT_CHARS));
+    }
+    let arguments = prompt.arguments.as_deref().unwrap_or_default();
+    if !arguments.is_empty() {
+        let required = arguments
+            .iter()
+            .filter(|arg| arg.required.unwrap_or(false))
+            .count();
+        let count = plural(arguments.len(), "arg");
+        parts.push(match required {
+            0 => format!("{} optional", count),
+            n if n == arguments.len() => count,
+            n => format!("{}, {} required", count, n),
+        });
+    }
+    parts.join(" · ")
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
+
+/// Picks a prompt from the extensions' prompts, or from `extension`'s: first the extension,
+/// when more than one has prompts, then the prompt. Both lists are searched by typing. `None`
+/// when there are no prompts to pick from.
+pub fn pick(
+    prompts: HashMap<String, Vec<Prompt>>,
+    extension: Option<&str>,
+) -> Result<Option<(String, Prompt)>> {
+    let mut extensions = sorted(prompts);
+    extensions.retain(|(name, _)| extension.is_none_or(|e| e == name));
+    if extensions.is_empty() {
+        return Ok(None);
+    }
+
+    if extensions.len() > 1 {
+        let total = extensions.iter().map(|(_, prompts)| prompts.len()).sum();
+        let mut select = cliclack::select("Extension").filter_mode().item(
+            ALL_EXTENSIONS.to_string(),
+            "All extensions",
+            plural(total, "prompt"),
+        );
+        for (name, prompts) in &extensions {
+            select = select.item(name.clone(), name, plural(prompts.len(), "prompt"));
+        }
+        let chosen: String = select.interact()?;
+        if chosen != ALL_EXTENSIONS {
+            extensions.retain(|(name, _)| *name == chosen);
+        }
+    }
+
+    let qualified = extensions.len() > 1;
+    let candidates: Vec<(String, Prompt)> = extensions
+        .into_iter()
+        .flat_map(|(extension, prompts)| {
+            prompts
+                .into_iter()
+                .map(move |prompt| (extension.clone(), prompt))
+        })
+        .collect();
+    let mut select = cliclack::select("Prompt").filter_mode();
+    for (idx, (extension, prompt)) in candidates.iter().enumerate() {
+        select = select.item(
+            idx,
+            prompt_label(extension, prompt, qualified),
+            prompt_hint(prompt),
+        );
+    }
+    let chosen: usize = select.interact()?;
+    Ok(candidates.into_iter().nth(chosen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::PromptArgument;
+    use test_case::test_case;
+
+    fn argument(name: &str, required: bool) -> PromptArgument {
+        PromptArgument {
+            name: name.to_string(),
+            title: None,
+            description: None,
+            required: Some(required),
+        }
+    }
+
+    #[test]
+    fn test_sorted_drops_extensions_without_prompts() {
+        let prompts = HashMap::from([
+            (
+                "github".to_string(),
+                vec![
+                    Prompt::new("review", None::<String>, None),
+                    Prompt::new("issue", None::<String>, None),
+                ],
+            ),
+            ("empty".to_string(), vec![]),
+            (
+                "developer".to_string(),
+                vec![Prompt::new("plan", None::<String>, None)],
+            ),
+        ]);
+        let names: Vec<(String, Vec<String>)> = sorted(prompts)
+            .into_iter()
+            .map(|(extension, prompts)| (extension, prompts.into_iter().map(|p| p.name).collect()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("developer".to_string(), vec!["plan".to_string()]),
+                (
+                    "github".to_string(),
+                    vec!["issue".to_string(), "review".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test_case(None, None, "" ; "nothing to say")]
+    #[test_case(Some("Review a PR\nLonger text"), None, "Review a PR" ; "first line of the description")]
+    #[test_case(None, Some(vec![argument("pr", true), argument("focus", false)]), "2 args, 1 required" ; "some required")]
+    #[test_case(Some("Plan"), Some(vec![argument("goal", true)]), "Plan · 1 arg" ; "all required")]
+    #[test_case(None, Some(vec![argument("focus", false)]), "1 arg optional" ; "none required")]
+    fn test_prompt_hint(
+        description: Option<&str>,
+        arguments: Option<Vec<PromptArgument>>,
+        expected: &str,
+    ) {
+        assert_eq!(
+            prompt_hint(&Prompt::new("p", description, arguments)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_prompt_label() {
+        let prompt = Prompt::new("review", None::<String>, None);
+        assert_eq!(prompt_label("github", &prompt, true), "github/review");
+        assert_eq!(prompt_label("github", &prompt, false), "review");
+    }
+}
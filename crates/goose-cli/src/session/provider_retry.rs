@@ -0,0 +1,142 @@
+use goose::config::Config;
+use goose::providers::errors::ProviderError;
+use std::time::Duration;
+
+const DEFAULT_MAX_RETRIES: usize = 3;
+const INITIAL_DELAY: Duration = Duration::from_secs(2);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Sends the turn again when the provider rate limits it or has a transient server error,
+/// waiting twice as long each time. Providers already retry a request a few times on their
+/// own; this covers failures that outlast those, up to `GOOSE_CLI_MAX_RETRIES` per turn.
+#[derive(Debug, Clone)]
+pub struct ProviderRetry {
+    max_retries: usize,
+    attempts: usize,
+}
+
+impl ProviderRetry {
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            attempts: 0,
+        }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(
+            Config::global()
+                .get_param::<usize>("GOOSE_CLI_MAX_RETRIES")
+                .unwrap_or(DEFAULT_MAX_RETRIES),
+        )
+    }
+
+    /// How long to wait before trying again, or `None` if the error isn't worth retrying or
+    /// the retries are used up. Counts the attempt.
+    pub fn next_delay(&mut self, error: &anyhow::Error) -> Option<Duration> {
+        let provider_error = retryable(error)?;
+        if self.attempts >= self.max_retries {
+            return None;
+        }
+        self.attempts += 1;
+        Some(match provider_error {
+            ProviderError::RateLimitExceeded {
+                retry_delay: Some(delay),
+                ..
+            } => *delay,
+            _ => INITIAL_DELAY
+                .saturating_mul(1 << (self.attempts - 1).min(16))
+                .min(MAX_DELAY),
+        })
+    }
+
+    /// Whether the error would have been retried if there were retries left.
+    pub fn gave_up_on(&self, error: &anyhow::Error) -> bool {
+        self.max_retries > 0 && self.attempts >= self.max_retries && retryable(error).is_some()
+    }
+
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+    }
+}
+
+fn retryable(error: &anyhow::Error) -> Option<&ProviderError> {
+    error
+        .downcast_ref::<ProviderError>()
+        .filter(|e| e.is_retryable())
+}
+
+/// Shown in the thinking indicator while waiting, e.g. `Rate limited, retrying in 12s...`
+pub fn countdown_message(error: &anyhow::Error, remaining: Duration) -> String {
+    let reason = match retryable(error) {
+        Some(ProviderError::RateLimitExceeded { .. }) => "Rate limited",
+        _ => "Provider error",
+    };
+    // round up so the countdown never shows 0s while still waiting
+    let seconds = remaining.as_millis().div_ceil(1000);
+    format!("{}, retrying in {}s...", reason, seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn rate_limited(retry_delay: Option<Duration>) -> anyhow::Error {
+        ProviderError::RateLimitExceeded {
+            details: "slow down".to_string(),
+            retry_delay,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_backoff_doubles_until_retries_run_out() {
+        let error: anyhow::Error = ProviderError::ServerError("502".to_string()).into();
+        let mut retry = ProviderRetry::new(3);
+        let delays: Vec<_> = std::iter::from_fn(|| retry.next_delay(&error)).collect();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_secs(2),
+                Duration::from_secs(4),
+                Duration::from_secs(8)
+            ]
+        );
+        assert!(retry.gave_up_on(&error));
+
+        retry.reset();
+        assert_eq!(retry.next_delay(&error), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_uses_delay_from_provider() {
+        let mut retry = ProviderRetry::new(3);
+        assert_eq!(
+            retry.next_delay(&rate_limited(Some(Duration::from_secs(30)))),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test_case(ProviderError::Authentication("bad key".to_string()).into() ; "auth")]
+    #[test_case(ProviderError::ContextLengthExceeded("too long".to_string()).into() ; "context")]
+    #[test_case(anyhow::anyhow!("something else") ; "not a provider error")]
+    fn test_not_retried(error: anyhow::Error) {
+        let mut retry = ProviderRetry::new(3);
+        assert_eq!(retry.next_delay(&error), None);
+        assert!(!retry.gave_up_on(&error));
+    }
+
+    #[test]
+    fn test_countdown_message() {
+        assert_eq!(
+            countdown_message(&rate_limited(None), Duration::from_millis(11_200)),
+            "Rate limited, retrying in 12s..."
+        );
+        let error: anyhow::Error = ProviderError::ServerError("503".to_string()).into();
+        assert_eq!(
+            countdown_message(&error, Duration::from_secs(4)),
+            "Provider error, retrying in 4s..."
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use goose::config::Config;
+use goose::utils::safe_truncate;
+use rmcp::model::JsonObject;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Key under which an MCP server can advertise hints in a tool's `_meta`. Only `truncate` is
+/// taken from there.
+pub const RENDER_HINTS_META_KEY: &str = "goose/render";
+
+/// How the CLI should present calls to a tool that has no built-in renderer.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ToolRenderHints {
+    /// Shown instead of the tool name in the call header
+    pub label: Option<String>,
+    /// Arguments to show. Empty shows all of them.
+    #[serde(default)]
+    pub show: Vec<String>,
+    #[serde(default)]
+    pub hide: Vec<String>,
+    /// Maximum characters to show for string arguments
+    #[serde(default)]
+    pub truncate: HashMap<String, usize>,
+}
+
+impl ToolRenderHints {
+    /// The arguments to display, filtered and truncated per the hints.
+    pub fn apply(&self, arguments: &JsonObject) -> JsonObject {
+        let keys: Vec<&String> = if self.show.is_empty() {
+            arguments.keys().collect()
+        } else {
+            self.show
+                .iter()
+                .filter(|key| arguments.contains_key(*key))
+                .collect()
+        };
+
+        keys.into_iter()
+            .filter(|key| !self.hide.contains(*key))
+            .map(|key| {
+                let value = match (&arguments[key], self.truncate.get(key)) {
+                    (Value::String(s), Some(max)) => Value::String(safe_truncate(s, *max)),
+                    (value, _) => value.clone(),
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+}
+
+/// User-configured hints from `GOOSE_CLI_TOOL_RENDER_HINTS`, keyed by full tool name
+/// (`extension__tool`) or by `extension__*` to cover every tool of an extension:
+///
+/// ```yaml
+/// GOOSE_CLI_TOOL_RENDER_HINTS:
+///   github__create_issue:
+///     label: New issue
+///     show: [repo, title]
+///   jira__*:
+///     truncate: { description: 80 }
+/// ```
+static CONFIGURED_HINTS: LazyLock<HashMap<String, ToolRenderHints>> = LazyLock::new(|| {
+    Config::global()
+        .get_param("GOOSE_CLI_TOOL_RENDER_HINTS")
+        .unwrap_or_default()
+});
+
+/// Hints for a tool call. Configured hints take precedence over ones the server advertises in
+/// the tool's metadata, so users can override a server's choices.
+///
+/// Calls are rendered before it is known whether they need approval, and the rendered call is
+/// what the user approves, so a server only gets to truncate long arguments. Letting it relabel
+/// the call or leave arguments out would let it disguise what it is asking to run.
+pub fn hints_for(tool_name: &str, tool_meta: Option<&Value>) -> Option<ToolRenderHints> {
+    lookup(&CONFIGURED_HINTS, tool_name).or_else(|| from_meta(tool_meta?))
+}
+
+fn lookup(hints: &HashMap<String, ToolRenderHints>, tool_name: &str) -> Option<ToolRenderHints> {
+    if let Some(found) = hints.get(tool_name) {
+        return Some(found.clone());
+    }
+    let (extension, _) = tool_name.rsplit_once("__")?;
+    hints.get(&format!("{}__*", extension)).cloned()
+}
+
+fn from_meta(tool_meta: &Value) -> Option<ToolRenderHints> {
+    let hints: ToolRenderHints =
+        serde_json::from_value(tool_meta.get(RENDER_HINTS_META_KEY)?.clone()).ok()?;
+    Some(ToolRenderHints {
+        truncate: hints.truncate,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn arguments() -> JsonObject {
+        json!({"repo": "block/goose", "title": "Crash on start", "body": "It crashes every time"})
+            .as_object()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn test_apply_filters_and_truncates() {
+        let hints = ToolRenderHints {
+            show: vec!["title".into(), "body".into(), "missing".into()],
+            hide: vec!["repo".into()],
+            truncate: HashMap::from([("body".to_string(), 10)]),
+            ..Default::default()
+        };
+        let shown = hints.apply(&arguments());
+        assert_eq!(shown.len(), 2);
+        assert_eq!(shown["title"], "Crash on start");
+        assert_eq!(shown["body"], "It cras...");
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_extension_wildcard() {
+        let hints = HashMap::from([
+            (
+                "github__*".to_string(),
+                ToolRenderHints {
+                    label: Some("GitHub".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "github__create_issue".to_string(),
+                ToolRenderHints {
+                    label: Some("New issue".into()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let label = |name: &str| lookup(&hints, name).and_then(|h| h.label);
+        assert_eq!(label("github__create_issue").as_deref(), Some("New issue"));
+        assert_eq!(label("github__list_prs").as_deref(), Some("GitHub"));
+        assert_eq!(label("jira__search"), None);
+    }
+
+    #[test]
+    fn test_hints_from_tool_meta() {
+        let meta = json!({ RENDER_HINTS_META_KEY: { "truncate": { "body": 10 } } });
+        let hints = from_meta(&meta).unwrap();
+        assert_eq!(hints.truncate, HashMap::from([("body".to_string(), 10)]));
+        assert!(from_meta(&json!({"other": 1})).is_none());
+    }
+
+    #[test]
+    fn test_tool_meta_cannot_hide_or_relabel_arguments() {
+        let meta = json!({ RENDER_HINTS_META_KEY: {
+            "label": "Read file",
+            "show": ["title"],
+            "hide": ["body"],
+            "truncate": { "body": 10 }
+        } });
+        let hints = hints_for("github__create_issue", Some(&meta)).unwrap();
+        assert_eq!(hints.label, None);
+        let shown = hints.apply(&arguments());
+        assert_eq!(shown.len(), 3);
+        assert_eq!(shown["repo"], "block/goose");
+        assert_eq!(shown["body"], "It cras...");
+    }
+}
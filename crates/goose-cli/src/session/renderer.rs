@@ -0,0 +1,657 @@
+use console::Color;
+use goose::config::Config;
+use goose::conversation::message::{Message, MessageContent, SystemNotificationType};
+use rmcp::model::Content;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::cost::CostTracker;
+use super::diff::DiffStat;
+use super::mentions;
+use super::output;
+use super::streaming_buffer::MarkdownBuffer;
+use super::turn_stats::TurnStats;
+use super::StreamEvent;
+
+const DEFAULT_STREAM_FLUSH_MS: u64 = 400;
+
+/// Output backend for a session. The agent loop reports what happened and the renderer decides
+/// how it is presented, so embedders and tests can swap the terminal output for something else.
+pub trait Renderer: Send {
+    /// Called with the user's message before the agent responds to it. The terminal already
+    /// shows what was typed.
+    fn render_user_message(&mut self, _message: &Message) {}
+
+    /// Render a message from the agent as it is streamed in.
+    fn render_message(&mut self, message: &Message);
+
+    /// Render an informational line such as a cancellation notice.
+    fn render_text(&mut self, text: &str, color: Option<Color>);
+
+    fn render_error(&mut self, error: &str);
+
+    fn render_model_change(&mut self, model: &str, mode: &str);
+
+    /// Report token usage after a turn. The terminal shows this before the next prompt instead.
+    fn render_usage(&mut self, _usage: &TurnUsage) {}
+
+    /// Report how long the turn took and how fast the response streamed.
+    fn render_turn_stats(&mut self, _stats: &TurnStats) {}
+
+    /// Summarize the files the turn's tool calls changed.
+    fn render_diffstat(&mut self, _stat: &DiffStat) {}
+
+    /// Hold messages instead of printing them until [`Renderer::resume`] is called.
+    fn pause(&mut self) {}
+
+    /// Print anything held while paused and continue rendering as messages arrive.
+    fn resume(&mut self) {}
+
+    /// Hand the terminal over to a prompt, for renderers that draw the whole screen.
+    fn release_terminal(&mut self) {}
+
+    /// Take the terminal back once the prompt is answered.
+    fn reclaim_terminal(&mut self) {}
+
+    /// Called once the agent has finished responding.
+    fn finish(&mut self, total_tokens: Option<i32>);
+}
+
+/// Token accounting for the session at the end of a turn.
+pub struct TurnUsage<'a> {
+    pub cost: &'a CostTracker,
+    pub context_tokens: i32,
+    pub context_limit: usize,
+}
+
+/// Creates the renderer for an `--output-format` value.
+pub fn renderer_for_format(output_format: &str, debug: bool) -> Box<dyn Renderer> {
+    match output_format {
+        "stream-json" => Box::new(JsonLinesRenderer::new(std::io::stdout())),
+        "jsonl" => Box::new(EventStreamRenderer::new(std::io::stdout())),
+        // json mode prints a single document once the run is complete
+        "json" => Box::new(NullRenderer),
+        _ => Box::new(AnsiRenderer::new(debug)),
+    }
+}
+
+/// The interactive terminal backend: markdown through bat, styled tool calls, and so on.
+pub struct AnsiRenderer {
+    debug: bool,
+    markdown_buffer: MarkdownBuffer,
+    /// Messages received while paused, rendered in order on resume
+    held: Option<Vec<Message>>,
+}
+
+impl AnsiRenderer {
+    pub fn new(debug: bool) -> Self {
+        Self {
+            debug,
+            markdown_buffer: stream_flush_delay()
+                .map(MarkdownBuffer::with_flush_after)
+                .unwrap_or_default(),
+            held: None,
+        }
+    }
+}
+
+/// How long prose may be held back waiting for markdown to close before it is shown anyway,
+/// from `GOOSE_CLI_STREAM_FLUSH_MS` (0 turns this off).
+fn stream_flush_delay() -> Option<Duration> {
+    let ms = Config::global()
+        .get_param::<u64>("GOOSE_CLI_STREAM_FLUSH_MS")
+        .unwrap_or(DEFAULT_STREAM_FLUSH_MS);
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+impl Renderer for AnsiRenderer {
+    fn render_user_message(&mut self, message: &Message) {
+        output::echo_input(&mentions::typed_text(message));
+        output::render_attachment_chips(message);
+    }
+
+    fn render_message(&mut self, message: &Message) {
+        if let Some(held) = &mut self.held {
+            held.push(message.clone());
+            return;
+        }
+        output::render_message_streaming(message, &mut self.markdown_buffer, self.debug);
+    }
+
+    fn pause(&mut self) {
+        if self.held.is_none() {
+            output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+            output::render_text_no_newlines(
+                "\n⏸ paused — Ctrl-Q or Enter to resume, b to scroll back\n",
+                Some(Color::Yellow),
+                true,
+            );
+            self.held = Some(Vec::new());
+        }
+    }
+
+    fn resume(&mut self) {
+        if let Some(held) = self.held.take() {
+            for message in &held {
+                output::render_message_streaming(message, &mut self.markdown_buffer, self.debug);
+            }
+        }
+    }
+
+    fn render_text(&mut self, text: &str, color: Option<Color>) {
+        self.resume();
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_text(text, color, true);
+    }
+
+    fn render_error(&mut self, error: &str) {
+        self.resume();
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_error(error);
+    }
+
+    fn render_model_change(&mut self, model: &str, mode: &str) {
+        if self.debug {
+            eprintln!("Model changed to {} in {} mode", model, mode);
+        }
+    }
+
+    fn render_turn_stats(&mut self, stats: &TurnStats) {
+        self.resume();
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_turn_stats(stats);
+    }
+
+    fn render_diffstat(&mut self, stat: &DiffStat) {
+        self.resume();
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_diffstat(stat);
+    }
+
+    fn finish(&mut self, _total_tokens: Option<i32>) {
+        self.resume();
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        println!();
+    }
+}
+
+/// Writes one JSON event per line, the format behind `--output-format stream-json`.
+pub struct JsonLinesRenderer<W: Write + Send> {
+    writer: W,
+}
+
+impl<W: Write + Send> JsonLinesRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn emit(&mut self, event: &StreamEvent) {
+        if let Ok(json) = serde_json::to_string(event) {
+            let _ = writeln!(self.writer, "{}", json);
+            let _ = self.writer.flush();
+        }
+    }
+}
+
+impl<W: Write + Send> Renderer for JsonLinesRenderer<W> {
+    fn render_message(&mut self, message: &Message) {
+        self.emit(&StreamEvent::Message {
+            message: message.clone(),
+        });
+    }
+
+    // Plain notices have no event type; consumers see the effect in the following events
+    fn render_text(&mut self, _text: &str, _color: Option<Color>) {}
+
+    fn render_error(&mut self, error: &str) {
+        self.emit(&StreamEvent::Error {
+            error: error.to_string(),
+        });
+    }
+
+    fn render_model_change(&mut self, model: &str, mode: &str) {
+        self.emit(&StreamEvent::ModelChange {
+            model: model.to_string(),
+            mode: mode.to_string(),
+        });
+    }
+
+    fn finish(&mut self, total_tokens: Option<i32>) {
+        self.emit(&StreamEvent::Complete { total_tokens });
+    }
+}
+
+/// Writes one JSON event per rendered item (`--output-format jsonl`). Unlike
+/// [`JsonLinesRenderer`] messages are split into their text chunks, tool requests and tool
+/// responses, and token usage is reported after every turn.
+pub struct EventStreamRenderer<W: Write + Send> {
+    inner: JsonLinesRenderer<W>,
+}
+
+impl<W: Write + Send> EventStreamRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: JsonLinesRenderer::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner.into_inner()
+    }
+}
+
+impl<W: Write + Send> Renderer for EventStreamRenderer<W> {
+    fn render_message(&mut self, message: &Message) {
+        for content in &message.content {
+            let event = match content {
+                MessageContent::Text(text) => StreamEvent::Text {
+                    text: text.text.clone(),
+                },
+                MessageContent::Thinking(thinking) => StreamEvent::Thinking {
+                    thinking: thinking.thinking.clone(),
+                },
+                MessageContent::ToolRequest(req) => match &req.tool_call {
+                    Ok(call) => StreamEvent::ToolRequest {
+                        id: req.id.clone(),
+                        name: call.name.to_string(),
+                        arguments: call.arguments.clone(),
+                    },
+                    Err(e) => StreamEvent::Error {
+                        error: format!("Invalid tool request {}: {}", req.id, e.message),
+                    },
+                },
+                MessageContent::ToolResponse(resp) => match &resp.tool_result {
+                    Ok(result) => StreamEvent::ToolResponse {
+                        id: resp.id.clone(),
+                        is_error: result.is_error.unwrap_or(false),
+                        content: result.content.clone(),
+                    },
+                    Err(e) => StreamEvent::ToolResponse {
+                        id: resp.id.clone(),
+                        is_error: true,
+                        content: vec![Content::text(e.message.to_string())],
+                    },
+                },
+                MessageContent::SystemNotification(notification)
+                    if notification.notification_type == SystemNotificationType::InlineMessage =>
+                {
+                    StreamEvent::SystemNotification {
+                        message: notification.msg.clone(),
+                    }
+                }
+                _ => continue,
+            };
+            self.inner.emit(&event);
+        }
+    }
+
+    fn render_text(&mut self, _text: &str, _color: Option<Color>) {}
+
+    fn render_error(&mut self, error: &str) {
+        self.inner.render_error(error);
+    }
+
+    fn render_model_change(&mut self, model: &str, mode: &str) {
+        self.inner.render_model_change(model, mode);
+    }
+
+    fn render_usage(&mut self, usage: &TurnUsage) {
+        let (input_tokens, output_tokens) = usage.cost.total_tokens();
+        self.inner.emit(&StreamEvent::Cost {
+            input_tokens,
+            output_tokens,
+            models: usage.cost.models.clone(),
+        });
+        self.inner.emit(&StreamEvent::ContextUsage {
+            total_tokens: usage.context_tokens,
+            context_limit: usage.context_limit,
+        });
+    }
+
+    fn finish(&mut self, total_tokens: Option<i32>) {
+        self.inner.finish(total_tokens);
+    }
+}
+
+/// Prints nothing but the agent's final answer once the run is done, for `goose ask`. Text
+/// from earlier steps, before a tool call, isn't part of the answer.
+pub struct AnswerRenderer<W: Write + Send> {
+    writer: W,
+    json: bool,
+    answer: String,
+    /// The message the answer so far came from; a new message starts a new answer
+    answer_id: Option<String>,
+    error: Option<String>,
+    tokens: (u64, u64),
+}
+
+#[derive(serde::Serialize)]
+struct Answer<'a> {
+    answer: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+    total_tokens: Option<i32>,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+impl<W: Write + Send> AnswerRenderer<W> {
+    pub fn new(writer: W, json: bool) -> Self {
+        Self {
+            writer,
+            json,
+            answer: String::new(),
+            answer_id: None,
+            error: None,
+            tokens: (0, 0),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + Send> Renderer for AnswerRenderer<W> {
+    fn render_message(&mut self, message: &Message) {
+        if message.role != rmcp::model::Role::Assistant {
+            return;
+        }
+        if message.id != self.answer_id {
+            self.answer.clear();
+            self.answer_id = message.id.clone();
+        }
+        for content in &message.content {
+            match content {
+                MessageContent::Text(text) => self.answer.push_str(&text.text),
+                // the agent is still working, so what it said so far was not the answer
+                MessageContent::ToolRequest(_) => self.answer.clear(),
+                _ => {}
+            }
+        }
+    }
+
+    fn render_text(&mut self, _text: &str, _color: Option<Color>) {}
+
+    fn render_error(&mut self, error: &str) {
+        self.error = Some(error.to_string());
+    }
+
+    fn render_model_change(&mut self, _model: &str, _mode: &str) {}
+
+    fn render_usage(&mut self, usage: &TurnUsage) {
+        self.tokens = usage.cost.total_tokens();
+    }
+
+    fn finish(&mut self, total_tokens: Option<i32>) {
+        let answer = self.answer.trim();
+        if self.json {
+            let (input_tokens, output_tokens) = self.tokens;
+            let document = Answer {
+                answer,
+                error: self.error.as_deref(),
+                total_tokens,
+                input_tokens,
+                output_tokens,
+            };
+            if let Ok(json) = serde_json::to_string(&document) {
+                let _ = writeln!(self.writer, "{}", json);
+            }
+        } else {
+            if let Some(error) = &self.error {
+                eprintln!("{}", error);
+            }
+            if !answer.is_empty() {
+                let _ = writeln!(self.writer, "{}", answer);
+            }
+        }
+        let _ = self.writer.flush();
+    }
+}
+
+/// Discards everything, for callers that only want the conversation itself.
+pub struct NullRenderer;
+
+impl Renderer for NullRenderer {
+    fn render_message(&mut self, _message: &Message) {}
+    fn render_text(&mut self, _text: &str, _color: Option<Color>) {}
+    fn render_error(&mut self, _error: &str) {}
+    fn render_model_change(&mut self, _model: &str, _mode: &str) {}
+    fn finish(&mut self, _total_tokens: Option<i32>) {}
+}
+
+/// What the session loop asked a renderer to show, in order. Lets tests check what a turn
+/// produced without formatting it for a terminal, and attached clients show a served session.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum RenderEvent {
+    UserMessage(Message),
+    Message(Message),
+    Text(String),
+    Error(String),
+    ModelChange { model: String, mode: String },
+    TurnStats(TurnStats),
+    Finish(Option<i32>),
+}
+
+/// Records every event instead of showing it. Clones share the recording.
+#[derive(Clone, Default)]
+pub struct RecordingRenderer {
+    events: Arc<Mutex<Vec<RenderEvent>>>,
+}
+
+impl RecordingRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> Vec<RenderEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn record(&self, event: RenderEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn render_user_message(&mut self, message: &Message) {
+        self.record(RenderEvent::UserMessage(message.clone()));
+    }
+
+    fn render_message(&mut self, message: &Message) {
+        self.record(RenderEvent::Message(message.clone()));
+    }
+
+    fn render_text(&mut self, text: &str, _color: Option<Color>) {
+        self.record(RenderEvent::Text(text.to_string()));
+    }
+
+    fn render_error(&mut self, error: &str) {
+        self.record(RenderEvent::Error(error.to_string()));
+    }
+
+    fn render_model_change(&mut self, model: &str, mode: &str) {
+        self.record(RenderEvent::ModelChange {
+            model: model.to_string(),
+            mode: mode.to_string(),
+        });
+    }
+
+    fn render_turn_stats(&mut self, stats: &TurnStats) {
+        self.record(RenderEvent::TurnStats(stats.clone()));
+    }
+
+    fn finish(&mut self, total_tokens: Option<i32>) {
+        self.record(RenderEvent::Finish(total_tokens));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn events(output: Vec<u8>) -> Vec<Value> {
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_answer_renderer_prints_only_the_final_answer() {
+        let mut renderer = AnswerRenderer::new(Vec::new(), false);
+        renderer.render_message(
+            &Message::assistant()
+                .with_id("m1")
+                .with_text("Let me check.")
+                .with_tool_request(
+                    "call_1",
+                    Ok(rmcp::model::CallToolRequestParams {
+                        meta: None,
+                        task: None,
+                        name: "developer__shell".into(),
+                        arguments: None,
+                    }),
+                ),
+        );
+        renderer.render_message(&Message::user().with_text("tool output"));
+        renderer.render_message(&Message::assistant().with_id("m2").with_text("It is "));
+        renderer.render_message(&Message::assistant().with_id("m2").with_text("42.\n"));
+        renderer.finish(Some(10));
+        assert_eq!(
+            String::from_utf8(renderer.into_inner()).unwrap(),
+            "It is 42.\n"
+        );
+    }
+
+    #[test]
+    fn test_answer_renderer_json() {
+        let mut renderer = AnswerRenderer::new(Vec::new(), true);
+        renderer.render_message(&Message::assistant().with_text("yes"));
+        let mut cost = CostTracker::default();
+        cost.record_turn("openai", "gpt-4o", 120, 30);
+        renderer.render_usage(&TurnUsage {
+            cost: &cost,
+            context_tokens: 150,
+            context_limit: 128_000,
+        });
+        renderer.finish(Some(150));
+
+        let events = events(renderer.into_inner());
+        assert_eq!(
+            events,
+            vec![serde_json::json!({
+                "answer": "yes",
+                "total_tokens": 150,
+                "input_tokens": 120,
+                "output_tokens": 30,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_json_lines_renderer_emits_one_event_per_line() {
+        let mut renderer = JsonLinesRenderer::new(Vec::new());
+        renderer.render_message(&Message::assistant().with_text("hello"));
+        renderer.render_text("ignored", None);
+        renderer.render_model_change("gpt-4o", "auto");
+        renderer.render_error("boom");
+        renderer.finish(Some(42));
+
+        let events = events(renderer.into_inner());
+        let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(types, vec!["message", "model_change", "error", "complete"]);
+        assert_eq!(events[0]["message"]["content"][0]["text"], "hello");
+        assert_eq!(events[1]["model"], "gpt-4o");
+        assert_eq!(events[2]["error"], "boom");
+        assert_eq!(events[3]["total_tokens"], 42);
+    }
+
+    #[test]
+    fn test_event_stream_renderer_splits_messages() {
+        let mut renderer = EventStreamRenderer::new(Vec::new());
+        let mut arguments = rmcp::model::JsonObject::new();
+        arguments.insert("command".to_string(), Value::from("ls"));
+        renderer.render_message(
+            &Message::assistant()
+                .with_text("listing files")
+                .with_tool_request(
+                    "call_1",
+                    Ok(rmcp::model::CallToolRequestParams {
+                        meta: None,
+                        task: None,
+                        name: "developer__shell".into(),
+                        arguments: Some(arguments),
+                    }),
+                ),
+        );
+        renderer.render_message(&Message::user().with_tool_response(
+            "call_1",
+            Ok(rmcp::model::CallToolResult::success(vec![Content::text(
+                "Cargo.toml",
+            )])),
+        ));
+        let mut cost = CostTracker::default();
+        cost.record_turn("openai", "gpt-4o", 120, 30);
+        renderer.render_usage(&TurnUsage {
+            cost: &cost,
+            context_tokens: 150,
+            context_limit: 128_000,
+        });
+        renderer.finish(Some(150));
+
+        let events = events(renderer.into_inner());
+        let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "text",
+                "tool_request",
+                "tool_response",
+                "cost",
+                "context_usage",
+                "complete"
+            ]
+        );
+        assert_eq!(events[1]["name"], "developer__shell");
+        assert_eq!(events[1]["arguments"]["command"], "ls");
+        assert_eq!(events[2]["content"][0]["text"], "Cargo.toml");
+        assert_eq!(events[3]["models"][0]["model"], "gpt-4o");
+        assert_eq!(events[4]["context_limit"], 128_000);
+    }
+
+    #[test]
+    fn test_recording_renderer_keeps_events_in_order() {
+        let recording = RecordingRenderer::new();
+        let mut renderer: Box<dyn Renderer> = Box::new(recording.clone());
+        let reply = Message::assistant().with_text("hello");
+        renderer.render_user_message(&Message::user().with_text("hi"));
+        renderer.render_message(&reply);
+        renderer.render_model_change("gpt-4o", "auto");
+        renderer.render_error("boom");
+        renderer.finish(Some(7));
+
+        let events = recording.events();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[1], RenderEvent::Message(reply));
+        assert_eq!(
+            events[2..],
+            [
+                RenderEvent::ModelChange {
+                    model: "gpt-4o".to_string(),
+                    mode: "auto".to_string()
+                },
+                RenderEvent::Error("boom".to_string()),
+                RenderEvent::Finish(Some(7)),
+            ]
+        );
+    }
+}
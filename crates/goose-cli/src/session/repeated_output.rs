@@ -0,0 +1,99 @@
+use rmcp::model::CallToolRequestParams;
+use similar::TextDiff;
+use std::collections::HashMap;
+
+/// How a tool's output compares with the last run of the same call.
+#[derive(Debug, PartialEq)]
+pub enum OutputChange {
+    /// First run of this call, or a response we could not match to a request
+    New,
+    Unchanged,
+    /// Only the hunks that differ, as a unified diff without file headers
+    Changed(String),
+}
+
+/// Remembers the output of each distinct tool call (name plus arguments) so that running the
+/// same call again, such as `cargo test` after a fix, can show just what changed.
+#[derive(Default)]
+pub struct RepeatedOutput {
+    pending: HashMap<String, String>,
+    last: HashMap<String, String>,
+}
+
+impl RepeatedOutput {
+    pub fn record_request(&mut self, request_id: &str, call: &CallToolRequestParams) {
+        // without preserve_order the arguments serialise with sorted keys, so equal calls match
+        let arguments = serde_json::to_string(&call.arguments).unwrap_or_default();
+        self.pending.insert(
+            request_id.to_string(),
+            format!("{}:{}", call.name, arguments),
+        );
+    }
+
+    pub fn compare(&mut self, request_id: &str, output: &str) -> OutputChange {
+        let Some(key) = self.pending.remove(request_id) else {
+            return OutputChange::New;
+        };
+        let change = match self.last.get(&key) {
+            None => OutputChange::New,
+            Some(previous) if previous == output => OutputChange::Unchanged,
+            Some(previous) => OutputChange::Changed(
+                TextDiff::from_lines(previous.as_str(), output)
+                    .unified_diff()
+                    .context_radius(1)
+                    .to_string(),
+            ),
+        };
+        self.last.insert(key, output.to_string());
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(command: &str) -> CallToolRequestParams {
+        CallToolRequestParams {
+            meta: None,
+            task: None,
+            name: "developer__shell".into(),
+            arguments: json!({ "command": command }).as_object().cloned(),
+        }
+    }
+
+    #[test]
+    fn test_repeated_call_shows_only_changes() {
+        let mut history = RepeatedOutput::default();
+        history.record_request("1", &call("cargo test"));
+        assert_eq!(
+            history.compare("1", "test a ... ok\ntest b ... FAILED\n"),
+            OutputChange::New
+        );
+
+        history.record_request("2", &call("cargo test"));
+        let OutputChange::Changed(diff) = history.compare("2", "test a ... ok\ntest b ... ok\n")
+        else {
+            panic!("expected a diff");
+        };
+        assert!(diff.contains("-test b ... FAILED\n"));
+        assert!(diff.contains("+test b ... ok\n"));
+
+        history.record_request("3", &call("cargo test"));
+        assert_eq!(
+            history.compare("3", "test a ... ok\ntest b ... ok\n"),
+            OutputChange::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_different_arguments_are_separate() {
+        let mut history = RepeatedOutput::default();
+        history.record_request("1", &call("ls"));
+        history.compare("1", "a\n");
+        history.record_request("2", &call("ls -a"));
+        assert_eq!(history.compare("2", "a\n"), OutputChange::New);
+        assert_eq!(history.compare("unknown", "a\n"), OutputChange::New);
+    }
+}
@@ -0,0 +1,60 @@
+use console::style;
+use goose::conversation::message::Message;
+use rmcp::model::Role;
+use std::time::Duration;
+
+use super::mentions;
+use super::output;
+
+/// Re-renders a stored conversation with the original gaps between messages, divided by
+/// `speed`. Gaps longer than `max_pause` seconds (e.g. the user stepping away) are shortened
+/// to it before scaling.
+pub async fn replay_conversation(messages: &[Message], speed: f64, max_pause: f64) {
+    let mut previous: Option<i64> = None;
+    for message in messages.iter().filter(|m| m.metadata.user_visible) {
+        if let Some(previous) = previous {
+            tokio::time::sleep(replay_delay(previous, message.created, speed, max_pause)).await;
+        }
+        previous = Some(message.created);
+
+        if message.role == Role::User && message.has_only_text_content() {
+            println!(
+                "\n{} {}",
+                style("( O)>").cyan().bold(),
+                mentions::typed_text(message)
+            );
+            output::render_attachment_chips(message);
+        } else {
+            output::render_message(message, false);
+        }
+    }
+    println!();
+}
+
+/// The pause before a message created at `created`, given the previous one at `previous`
+/// (both unix seconds).
+fn replay_delay(previous: i64, created: i64, speed: f64, max_pause: f64) -> Duration {
+    if speed <= 0.0 {
+        return Duration::ZERO;
+    }
+    let gap = (created - previous).max(0) as f64;
+    Duration::from_secs_f64(gap.min(max_pause.max(0.0)) / speed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(100, 104, 1.0, 10.0, 4.0 ; "original timing")]
+    #[test_case(100, 104, 2.0, 10.0, 2.0 ; "twice as fast")]
+    #[test_case(100, 400, 1.0, 10.0, 10.0 ; "long gap capped")]
+    #[test_case(100, 90, 1.0, 10.0, 0.0 ; "out of order")]
+    #[test_case(100, 104, 0.0, 10.0, 0.0 ; "zero speed skips waiting")]
+    fn test_replay_delay(previous: i64, created: i64, speed: f64, max_pause: f64, secs: f64) {
+        assert_eq!(
+            replay_delay(previous, created, speed, max_pause),
+            Duration::from_secs_f64(secs)
+        );
+    }
+}
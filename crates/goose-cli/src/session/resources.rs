@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use std::path::Path;
+
+/// Lines of a text resource shown inline; the rest is summarized
+pub const PREVIEW_LINES: usize = 20;
+
+/// A binary resource from a tool result, kept so `/save` can write it out later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryResource {
+    pub uri: String,
+    pub mime_type: Option<String>,
+    /// Base64, as it came from the extension
+    pub blob: String,
+}
+
+impl BinaryResource {
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD
+            .decode(self.blob.trim())
+            .context("The resource is not valid base64")
+    }
+
+    /// Size of the decoded content, without decoding it
+    pub fn size(&self) -> usize {
+        let blob = self.blob.trim();
+        let padding = blob.chars().rev().take_while(|c| *c == '=').count();
+        (blob.len() / 4 * 3).saturating_sub(padding)
+    }
+
+    /// A file name to suggest when saving, from the last segment of the URI
+    pub fn file_name(&self) -> String {
+        let path = self.uri.split(['?', '#']).next().unwrap_or_default();
+        path.rsplit('/')
+            .find(|segment| !segment.is_empty() && !segment.contains(':'))
+            .unwrap_or("resource")
+            .to_string()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<usize> {
+        let bytes = self.bytes()?;
+        std::fs::write(path, &bytes)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(bytes.len())
+    }
+}
+
+/// The language to highlight a text resource with: the URI's file extension, or else the
+/// subtype of its MIME type, e.g. `json` for `application/json` or `rust` for `text/x-rust`.
+pub fn language_for(uri: &str, mime_type: Option<&str>) -> String {
+    let path = uri.split(['?', '#']).next().unwrap_or_default();
+    let file_name = path.rsplit('/').next().unwrap_or_default();
+    if let Some((_, extension)) = file_name.rsplit_once('.') {
+        if !extension.is_empty() {
+            return extension.to_lowercase();
+        }
+    }
+    mime_type
+        .and_then(|mime| mime.split(';').next())
+        .and_then(|mime| mime.split_once('/'))
+        .map(|(_, subtype)| subtype.trim_start_matches("x-").to_lowercase())
+        .filter(|subtype| subtype != "plain")
+        .unwrap_or_default()
+}
+
+/// The first `max_lines` lines of `text` and how many lines were left out
+pub fn preview(text: &str, max_lines: usize) -> (String, usize) {
+    let total = text.lines().count();
+    let shown: Vec<&str> = text.lines().take(max_lines).collect();
+    (shown.join("\n"), total.saturating_sub(max_lines))
+}
+
+pub fn format_size(bytes: usize) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("file:///src/main.rs", None, "rs" ; "extension")]
+    #[test_case("mem://notes", Some("application/json"), "json" ; "mime subtype")]
+    #[test_case("db://rows/1", Some("text/x-python; charset=utf-8"), "python" ; "x prefix and parameters")]
+    #[test_case("mem://log", Some("text/plain"), "" ; "plain text")]
+    #[test_case("https://example.com/a.b/page?q=1.2", None, "" ; "dots outside the file name")]
+    fn test_language_for(uri: &str, mime_type: Option<&str>, expected: &str) {
+        assert_eq!(language_for(uri, mime_type), expected);
+    }
+
+    #[test]
+    fn test_preview() {
+        let text = "1\n2\n3\n4";
+        assert_eq!(preview(text, 2), ("1\n2".to_string(), 2));
+        assert_eq!(preview(text, 10), (text.to_string(), 0));
+    }
+
+    #[test]
+    fn test_binary_resource() {
+        let resource = BinaryResource {
+            uri: "file:///tmp/out/chart.png?v=2".to_string(),
+            mime_type: Some("image/png".to_string()),
+            blob: base64::engine::general_purpose::STANDARD.encode(b"hello"),
+        };
+        assert_eq!(resource.size(), 5);
+        assert_eq!(resource.bytes().unwrap(), b"hello");
+        assert_eq!(resource.file_name(), "chart.png");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.bin");
+        assert_eq!(resource.save(&path).unwrap(), 5);
+        assert_eq!(std::fs::read(path).unwrap(), b"hello");
+
+        let opaque = BinaryResource {
+            uri: "blob:".to_string(),
+            ..resource
+        };
+        assert_eq!(opaque.file_name(), "resource");
+    }
+}
@@ -0,0 +1,324 @@
+use anyhow::{bail, Context, Result};
+use goose::subprocess::SubprocessExt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Where `--sandbox` sessions do their work: a detached git worktree of the repository, or a
+/// copy of the directory when it isn't in one. Either way the sandbox gets a baseline commit
+/// of what was there at the start, so the agent's changes come out as one patch to review and
+/// apply to the real checkout.
+pub struct Sandbox {
+    /// Directory the session was started in
+    origin: PathBuf,
+    /// Where patches apply: the repository root, or the origin for a copy
+    target: PathBuf,
+    /// Root of the worktree or copy
+    root: PathBuf,
+    /// The session's directory inside the sandbox
+    working_dir: PathBuf,
+    worktree: bool,
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .set_no_window()
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn git_with_input(dir: &Path, args: &[&str], input: &str) -> Result<()> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .set_no_window()
+        .spawn()
+        .context("Failed to run git")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Commits everything in the sandbox as the state its diff is taken against.
+fn commit_baseline(root: &Path) -> Result<()> {
+    git(root, &["add", "-A"])?;
+    git(
+        root,
+        &[
+            "-c",
+            "user.name=goose",
+            "-c",
+            "user.email=goose@localhost",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "--no-verify",
+            "-m",
+            "sandbox baseline",
+        ],
+    )?;
+    Ok(())
+}
+
+/// Copies a directory, leaving out what its ignore files exclude, such as build output, other
+/// repositories' `.git` directories and symlinks, which could point back out of the copy.
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    let walker = ignore::WalkBuilder::new(from)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+    for entry in walker {
+        let entry = entry?;
+        let Ok(relative) = entry.path().strip_prefix(from) else {
+            continue;
+        };
+        let dest = to.join(relative);
+        match entry.file_type() {
+            Some(file_type) if file_type.is_dir() => fs::create_dir_all(&dest)?,
+            Some(file_type) if file_type.is_file() => {
+                fs::copy(entry.path(), &dest)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+impl Sandbox {
+    /// Sets up a sandbox for `origin`, named after the session so leftovers can be traced.
+    /// The directory is created fresh with a random suffix, so nothing else can have put it
+    /// there first.
+    pub fn create(origin: &Path, name: &str) -> Result<Self> {
+        let root = tempfile::Builder::new()
+            .prefix(&format!("goose-sandbox-{}-", name))
+            .tempdir()
+            .context("Failed to create the sandbox directory")?
+            .keep();
+        let sandbox = match git(origin, &["rev-parse", "--show-toplevel"]) {
+            Ok(toplevel) => {
+                Self::create_worktree(origin, PathBuf::from(toplevel.trim()), root.clone())
+            }
+            Err(_) => Self::create_copy(origin, root.clone()),
+        };
+        if sandbox.is_err() {
+            let _ = fs::remove_dir_all(&root);
+        }
+        sandbox
+    }
+
+    fn create_worktree(origin: &Path, repo: PathBuf, root: PathBuf) -> Result<Self> {
+        let root_arg = root.to_string_lossy();
+        git(
+            &repo,
+            &["worktree", "add", "--detach", "-q", &root_arg, "HEAD"],
+        )?;
+        // git reports the resolved path of the repository
+        let relative = origin
+            .canonicalize()
+            .ok()
+            .and_then(|o| o.strip_prefix(&repo).ok().map(Path::to_path_buf))
+            .unwrap_or_default();
+        let sandbox = Self {
+            origin: origin.to_path_buf(),
+            working_dir: root.join(relative),
+            target: repo,
+            root,
+            worktree: true,
+        };
+
+        // start from the checkout as it is, not as last committed
+        let uncommitted = git(&sandbox.target, &["diff", "HEAD", "--binary"])?;
+        if !uncommitted.is_empty() {
+            git_with_input(&sandbox.root, &["apply", "--binary", "-"], &uncommitted)?;
+        }
+        let untracked = git(
+            &sandbox.target,
+            &["ls-files", "--others", "--exclude-standard", "-z"],
+        )?;
+        for path in untracked.split('\0').filter(|p| !p.is_empty()) {
+            let dest = sandbox.root.join(path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(sandbox.target.join(path), dest)?;
+        }
+        commit_baseline(&sandbox.root)?;
+        Ok(sandbox)
+    }
+
+    fn create_copy(origin: &Path, root: PathBuf) -> Result<Self> {
+        copy_dir(origin, &root)?;
+        git(&root, &["init", "-q"])?;
+        commit_baseline(&root)?;
+        Ok(Self {
+            origin: origin.to_path_buf(),
+            target: origin.to_path_buf(),
+            working_dir: root.clone(),
+            root,
+            worktree: false,
+        })
+    }
+
+    pub fn origin(&self) -> &Path {
+        &self.origin
+    }
+
+    pub fn working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+
+    /// Everything changed in the sandbox since it was created, as a patch relative to the
+    /// repository root (or the copied directory).
+    pub fn diff(&self) -> Result<String> {
+        git(&self.root, &["add", "-A"])?;
+        git(&self.root, &["diff", "--cached", "--binary", "HEAD"])
+    }
+
+    /// Applies a patch from [`Sandbox::diff`] to the real checkout. Nothing is changed if any
+    /// part of it doesn't apply.
+    pub fn apply(&self, patch: &str) -> Result<()> {
+        git_with_input(&self.target, &["apply", "--binary", "-"], patch)
+    }
+
+    /// Deletes the sandbox without looking at what changed, for runs that end before the agent
+    /// got to work. Leaves the sandbox before deleting it.
+    pub fn discard(self) {
+        let _ = std::env::set_current_dir(&self.origin);
+        let root = self.root.clone();
+        if let Err(e) = self.remove() {
+            tracing::warn!("Failed to remove the sandbox at {}: {}", root.display(), e);
+        }
+    }
+
+    /// Deletes the worktree or copy.
+    pub fn remove(self) -> Result<()> {
+        if self.worktree {
+            let root_arg = self.root.to_string_lossy();
+            git(&self.target, &["worktree", "remove", "--force", &root_arg])?;
+        } else {
+            fs::remove_dir_all(&self.root)?;
+        }
+        Ok(())
+    }
+}
+
+/// Files a patch touches, from its `diff --git a/... b/...` headers.
+pub fn changed_files(patch: &str) -> Vec<&str> {
+    patch
+        .lines()
+        .filter_map(|line| line.strip_prefix("diff --git a/"))
+        .filter_map(|paths| paths.split_once(" b/").map(|(_, b)| b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let mut full = vec!["-c", "user.name=test", "-c", "user.email=test@localhost"];
+        full.extend_from_slice(args);
+        git(dir, &full).unwrap();
+    }
+
+    #[test]
+    fn test_copy_sandbox_round_trip() {
+        let origin = tempfile::tempdir().unwrap();
+        fs::write(origin.path().join("notes.txt"), "one\n").unwrap();
+
+        let sandbox =
+            Sandbox::create(origin.path(), &format!("test-copy-{}", std::process::id())).unwrap();
+        fs::write(sandbox.working_dir().join("notes.txt"), "one\ntwo\n").unwrap();
+        fs::write(sandbox.working_dir().join("new.txt"), "new\n").unwrap();
+
+        let patch = sandbox.diff().unwrap();
+        assert_eq!(changed_files(&patch), vec!["new.txt", "notes.txt"]);
+        // the checkout is untouched until the patch is applied
+        assert_eq!(
+            fs::read_to_string(origin.path().join("notes.txt")).unwrap(),
+            "one\n"
+        );
+
+        sandbox.apply(&patch).unwrap();
+        sandbox.remove().unwrap();
+        assert_eq!(
+            fs::read_to_string(origin.path().join("notes.txt")).unwrap(),
+            "one\ntwo\n"
+        );
+        assert!(origin.path().join("new.txt").exists());
+    }
+
+    #[test]
+    fn test_copy_dir_leaves_out_ignored_files() {
+        let from = tempfile::tempdir().unwrap();
+        fs::write(from.path().join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir_all(from.path().join("target")).unwrap();
+        fs::write(from.path().join("target/build.log"), "built\n").unwrap();
+        fs::create_dir_all(from.path().join("src")).unwrap();
+        fs::write(from.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let to = tempfile::tempdir().unwrap();
+        copy_dir(from.path(), to.path()).unwrap();
+        assert!(to.path().join("src/main.rs").exists());
+        assert!(to.path().join(".gitignore").exists());
+        assert!(!to.path().join("target").exists());
+    }
+
+    #[test]
+    fn test_worktree_sandbox_starts_from_uncommitted_state() {
+        let repo = tempfile::tempdir().unwrap();
+        run_git(repo.path(), &["init", "-q"]);
+        fs::create_dir(repo.path().join("src")).unwrap();
+        fs::write(repo.path().join("src/lib.rs"), "fn a() {}\n").unwrap();
+        run_git(repo.path(), &["add", "-A"]);
+        run_git(repo.path(), &["commit", "-q", "-m", "init"]);
+        fs::write(repo.path().join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        fs::write(repo.path().join("src/draft.rs"), "draft\n").unwrap();
+
+        let origin = repo.path().join("src");
+        let sandbox =
+            Sandbox::create(&origin, &format!("test-worktree-{}", std::process::id())).unwrap();
+        assert!(sandbox.working_dir().ends_with("src"));
+        assert_eq!(
+            fs::read_to_string(sandbox.working_dir().join("lib.rs")).unwrap(),
+            "fn a() {}\nfn b() {}\n"
+        );
+        assert!(sandbox.working_dir().join("draft.rs").exists());
+        assert_eq!(sandbox.diff().unwrap(), "");
+
+        fs::write(sandbox.working_dir().join("draft.rs"), "done\n").unwrap();
+        let patch = sandbox.diff().unwrap();
+        assert_eq!(changed_files(&patch), vec!["src/draft.rs"]);
+        sandbox.apply(&patch).unwrap();
+        sandbox.remove().unwrap();
+        assert_eq!(
+            fs::read_to_string(origin.join("draft.rs")).unwrap(),
+            "done\n"
+        );
+    }
+}
@@ -0,0 +1,434 @@
+use goose::config::Config;
+use ignore::WalkBuilder;
+use regex::Regex;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use super::file_history::shell_write_targets;
+
+/// Words that run the command after them, like `sudo rm …`
+const WRAPPERS: [&str; 5] = ["sudo", "env", "nohup", "time", "command"];
+/// Directories about to be deleted are counted up to this many files
+const MAX_COUNTED_FILES: usize = 10_000;
+/// Files about to be changed are read to count their lines up to this size
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+/// `<<EOF`, `<<-EOF`, `<< 'EOF'`, though not the `<<<` of a here-string or a shift inside
+/// `((...))`, which [`heredoc_delimiter`] leaves out
+static HEREDOC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<<-?\s*['"]?([A-Za-z_][A-Za-z0-9_]*)['"]?"#).unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Effect {
+    Delete,
+    Move { to: String },
+    Write,
+    Edit,
+}
+
+/// A path a shell command would change, as written in the command
+#[derive(Debug, Clone, PartialEq)]
+pub struct AffectedPath {
+    pub path: String,
+    pub effect: Effect,
+}
+
+impl AffectedPath {
+    fn new(path: impl Into<String>, effect: Effect) -> Self {
+        Self {
+            path: path.into(),
+            effect,
+        }
+    }
+
+    /// What is at the path now, e.g. `new file`, `42 lines` or `directory, 3 files`.
+    pub fn detail(&self, cwd: &Path) -> String {
+        if self.path.contains(['*', '?', '[', '$', '`', '~']) {
+            return "expanded when run".to_string();
+        }
+        let path = cwd.join(&self.path);
+        let Ok(meta) = std::fs::metadata(&path) else {
+            return match self.effect {
+                Effect::Write => "new file".to_string(),
+                _ => "not found".to_string(),
+            };
+        };
+        if meta.is_dir() {
+            let files = WalkBuilder::new(&path)
+                .standard_filters(false)
+                .build()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+                .take(MAX_COUNTED_FILES)
+                .count();
+            let more = if files == MAX_COUNTED_FILES { "+" } else { "" };
+            return format!(
+                "directory, {}{} file{}",
+                files,
+                more,
+                if files == 1 { "" } else { "s" }
+            );
+        }
+        if meta.len() > MAX_READ_BYTES {
+            return format!("{} bytes", meta.len());
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                let lines = text.lines().count();
+                format!("{} line{}", lines, if lines == 1 { "" } else { "s" })
+            }
+            Err(_) => format!("{} bytes", meta.len()),
+        }
+    }
+}
+
+/// Whether shell commands waiting for approval show the files they would change, on unless
+/// `GOOSE_CLI_SHELL_PREVIEW` is false.
+pub fn enabled() -> bool {
+    Config::global()
+        .get_param::<bool>("GOOSE_CLI_SHELL_PREVIEW")
+        .unwrap_or(true)
+}
+
+/// The files a shell command would delete, move, write or edit in place, found by reading it:
+/// `rm`, `mv`, `cp`, `sed -i` and output redirections. Nothing is run, so paths only known at
+/// run time, from variables, globs or command substitution, are kept as written.
+pub fn affected_paths(command: &str) -> Vec<AffectedPath> {
+    let mut affected: Vec<AffectedPath> = Vec::new();
+    for line in script_lines(command) {
+        for simple_command in split_commands(&line) {
+            let Some(words) = shlex::split(&simple_command) else {
+                continue;
+            };
+            affected.extend(command_effects(strip_redirections(words)));
+        }
+    }
+    affected.extend(
+        shell_write_targets(command)
+            .into_iter()
+            .map(|path| AffectedPath::new(path, Effect::Write)),
+    );
+    let mut unique = Vec::with_capacity(affected.len());
+    for path in affected {
+        if !unique.contains(&path) {
+            unique.push(path);
+        }
+    }
+    unique
+}
+
+/// The command's lines with continuations joined and heredoc bodies left out, as those are
+/// input rather than commands.
+fn script_lines(command: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut heredoc_end: Option<String> = None;
+    for line in command.lines() {
+        if let Some(end) = &heredoc_end {
+            if line.trim() == end {
+                heredoc_end = None;
+            }
+            continue;
+        }
+        if let Some(continued) = line.strip_suffix('\\') {
+            current.push_str(continued);
+            continue;
+        }
+        current.push_str(line);
+        heredoc_end = heredoc_delimiter(&current);
+        lines.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// The word that ends a heredoc started on `line`
+fn heredoc_delimiter(line: &str) -> Option<String> {
+    let arithmetic = arithmetic_spans(line);
+    HEREDOC
+        .captures_iter(line)
+        .filter_map(|caps| Some((caps.get(0)?.start(), caps.get(1)?)))
+        .find(|(start, _)| {
+            let here_string = line
+                .get(..*start)
+                .is_some_and(|before| before.ends_with('<'));
+            !here_string && !arithmetic.iter().any(|span| span.contains(start))
+        })
+        .map(|(_, name)| name.as_str().to_string())
+}
+
+/// Where `((...))` and `$((...))` arithmetic is on a line, up to the end of the line when it
+/// isn't closed
+fn arithmetic_spans(line: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut rest = line;
+    let mut offset = 0;
+    while let Some(start) = rest.find("((") {
+        let mut depth = 0;
+        let mut end = rest.len();
+        for (i, c) in rest.char_indices().skip_while(|(i, _)| *i < start) {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        spans.push(offset + start..offset + end);
+        offset += end;
+        rest = rest.get(end..).unwrap_or_default();
+    }
+    spans
+}
+
+/// Splits a line at `;`, `|`, `&&`, `||` and `&` outside of quotes. The `&` of `2>&1` and
+/// `&>` is kept.
+fn split_commands(line: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut previous: Option<char> = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) => {
+                if c == q {
+                    quote = None;
+                }
+                current.push(c);
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, ';' | '|') => commands.push(std::mem::take(&mut current)),
+            (None, '&') if previous != Some('>') && chars.peek() != Some(&'>') => {
+                commands.push(std::mem::take(&mut current))
+            }
+            _ => current.push(c),
+        }
+        previous = Some(c);
+    }
+    commands.push(current);
+    commands.retain(|command| !command.trim().is_empty());
+    commands
+}
+
+/// Leaves out redirections and their targets, which [`shell_write_targets`] covers.
+fn strip_redirections(words: Vec<String>) -> Vec<String> {
+    let mut kept = Vec::with_capacity(words.len());
+    let mut words = words.into_iter();
+    while let Some(word) = words.next() {
+        let operator = word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '&');
+        if operator.starts_with(['>', '<']) {
+            // `> file` rather than `>file`
+            if operator.trim_start_matches(['>', '<', '&']).is_empty() {
+                words.next();
+            }
+            continue;
+        }
+        kept.push(word);
+    }
+    kept
+}
+
+fn command_effects(words: Vec<String>) -> Vec<AffectedPath> {
+    let mut words = words.as_slice();
+    while let Some((first, rest)) = words.split_first() {
+        if WRAPPERS.contains(&first.as_str()) || is_assignment(first) {
+            words = rest;
+        } else {
+            break;
+        }
+    }
+    let Some((program, args)) = words.split_first() else {
+        return Vec::new();
+    };
+    match program.rsplit('/').next().unwrap_or(program) {
+        "rm" | "rmdir" | "unlink" => operands(args)
+            .into_iter()
+            .map(|path| AffectedPath::new(path, Effect::Delete))
+            .collect(),
+        "mv" => {
+            let mut paths = operands(args);
+            match paths.pop() {
+                Some(to) => paths
+                    .into_iter()
+                    .map(|path| AffectedPath::new(path, Effect::Move { to: to.clone() }))
+                    .collect(),
+                None => Vec::new(),
+            }
+        }
+        "cp" => {
+            let mut paths = operands(args);
+            match paths.pop() {
+                Some(to) if !paths.is_empty() => vec![AffectedPath::new(to, Effect::Write)],
+                _ => Vec::new(),
+            }
+        }
+        "sed" => sed_in_place_files(args)
+            .into_iter()
+            .map(|path| AffectedPath::new(path, Effect::Edit))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `NAME=value` before a command
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Arguments that aren't options, and everything after `--`
+fn operands(args: &[String]) -> Vec<String> {
+    let mut operands = Vec::new();
+    let mut options_done = false;
+    for arg in args {
+        if !options_done && arg == "--" {
+            options_done = true;
+        } else if options_done || !arg.starts_with('-') || arg == "-" {
+            operands.push(arg.clone());
+        }
+    }
+    operands
+}
+
+/// The files of `sed -i`, `sed -i.bak` (GNU) or `sed -i ''` (BSD), after the script
+fn sed_in_place_files(args: &[String]) -> Vec<String> {
+    let mut in_place = false;
+    let mut script_given = false;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--expression" || arg == "--file" {
+            script_given = true;
+            args.next();
+        } else if arg.starts_with("--expression=") || arg.starts_with("--file=") {
+            script_given = true;
+        } else if arg.starts_with("--in-place") {
+            in_place = true;
+        } else if let Some(flags) = arg.strip_prefix('-').filter(|f| !f.starts_with('-')) {
+            for (idx, flag) in flags.char_indices() {
+                match flag {
+                    // the rest is the backup suffix
+                    'i' => {
+                        in_place = true;
+                        break;
+                    }
+                    // the script follows, attached or as the next argument
+                    'e' | 'f' => {
+                        script_given = true;
+                        if idx + 1 == flags.len() {
+                            args.next();
+                        }
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        } else if !arg.is_empty() {
+            files.push(arg.clone());
+        }
+    }
+    if !in_place {
+        return Vec::new();
+    }
+    if !script_given && !files.is_empty() {
+        files.remove(0);
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn delete(path: &str) -> AffectedPath {
+        AffectedPath::new(path, Effect::Delete)
+    }
+
+    fn write(path: &str) -> AffectedPath {
+        AffectedPath::new(path, Effect::Write)
+    }
+
+    fn edit(path: &str) -> AffectedPath {
+        AffectedPath::new(path, Effect::Edit)
+    }
+
+    #[test_case("ls -la && cargo test", &[] ; "read only")]
+    #[test_case("rm -rf target build/", &[delete("target"), delete("build/")] ; "rm")]
+    #[test_case("sudo rm -- -weird", &[delete("-weird")] ; "wrapper and double dash")]
+    #[test_case("cd src; rm old.rs && echo done", &[delete("old.rs")] ; "chained")]
+    #[test_case("cargo build 2>&1 | tee build.log", &[write("build.log")] ; "pipe to tee")]
+    #[test_case("cp a.txt b.txt > copy.log", &[write("b.txt"), write("copy.log")] ; "cp with redirect")]
+    #[test_case("sed -i 's/a/b/' x.py y.py", &[edit("x.py"), edit("y.py")] ; "sed in place")]
+    #[test_case("sed -i '' -e 's/a/b/' x.py", &[edit("x.py")] ; "bsd sed with expression")]
+    #[test_case("sed -i.bak s/a/b/ x.py", &[edit("x.py")] ; "sed backup suffix")]
+    #[test_case("sed 's/a/b/' x.py", &[] ; "sed to stdout")]
+    #[test_case("cat > notes.md <<'EOF'\nrm -rf /\nEOF\nrm notes.bak", &[delete("notes.bak"), write("notes.md")] ; "heredoc body is not run")]
+    #[test_case("rm \\\n  a.txt", &[delete("a.txt")] ; "line continuation")]
+    #[test_case("grep x <<< word\nrm a.txt", &[delete("a.txt")] ; "here-string")]
+    #[test_case("echo $((1 << n))\nrm a.txt", &[delete("a.txt")] ; "shift in arithmetic")]
+    fn test_affected_paths(command: &str, expected: &[AffectedPath]) {
+        assert_eq!(affected_paths(command), expected);
+    }
+
+    #[test]
+    fn test_mv() {
+        assert_eq!(
+            affected_paths("mv -f a.txt 'b c.txt' dest/"),
+            vec![
+                AffectedPath::new("a.txt", Effect::Move { to: "dest/".into() }),
+                AffectedPath::new("b c.txt", Effect::Move { to: "dest/".into() }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detail() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("two.txt"), "a\nb\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("build/sub")).unwrap();
+        std::fs::write(dir.path().join("build/x"), "").unwrap();
+        std::fs::write(dir.path().join("build/sub/y"), "").unwrap();
+
+        let detail = |path: &str, effect| AffectedPath::new(path, effect).detail(dir.path());
+        assert_eq!(detail("two.txt", Effect::Delete), "2 lines");
+        assert_eq!(detail("build", Effect::Delete), "directory, 2 files");
+        assert_eq!(detail("gone.txt", Effect::Delete), "not found");
+        assert_eq!(detail("new.txt", Effect::Write), "new file");
+        assert_eq!(detail("*.log", Effect::Delete), "expanded when run");
+    }
+
+    #[test]
+    fn test_detail_does_not_read_large_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = std::fs::File::create(dir.path().join("big.bin")).unwrap();
+        file.set_len(MAX_READ_BYTES + 1).unwrap();
+
+        assert_eq!(
+            AffectedPath::new("big.bin", Effect::Delete).detail(dir.path()),
+            format!("{} bytes", MAX_READ_BYTES + 1)
+        );
+    }
+
+    #[test_case("cat <<EOF", Some("EOF") ; "heredoc")]
+    #[test_case("cat <<-'END' > out.txt", Some("END") ; "quoted and indented")]
+    #[test_case("grep x <<<EOF", None ; "here-string")]
+    #[test_case("echo $(( (1 << n) + 1 ))", None ; "shift in arithmetic")]
+    #[test_case("(( x <<= 2 )); cat <<EOF", Some("EOF") ; "heredoc after arithmetic")]
+    fn test_heredoc_delimiter(line: &str, expected: Option<&str>) {
+        assert_eq!(heredoc_delimiter(line).as_deref(), expected);
+    }
+}
@@ -0,0 +1,185 @@
+use console::{style, Term};
+use goose::config::Config;
+use std::io::{IsTerminal, Write};
+
+use super::palette::palette;
+
+const DEFAULT_TEMPLATE: &str = "{model} · {context} {tokens} · {cost} · {subagents} subagents";
+const CONTEXT_BAR_WIDTH: usize = 10;
+
+/// What the status line can show. Filled in by the session as turns finish and events arrive.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusInfo {
+    pub provider: String,
+    pub model: String,
+    pub context_tokens: usize,
+    pub context_limit: usize,
+    pub cost: Option<f64>,
+    pub subagents: usize,
+}
+
+/// A line pinned to the bottom row of the terminal. The rows above it are turned into a scroll
+/// region, so output scrolls past without overwriting it. The terminal is restored on drop.
+pub struct StatusLine {
+    template: String,
+    info: StatusInfo,
+    height: u16,
+}
+
+impl StatusLine {
+    /// Reads the template from `GOOSE_CLI_STATUS_LINE`. `true` uses the default template, a
+    /// string containing placeholders like `{model}` is used as the template itself. Returns
+    /// `None` when unset, turned off, stdout is not a terminal, or in accessible mode.
+    pub fn from_config() -> Option<Self> {
+        let config = Config::global();
+        let template = match config.get_param::<String>("GOOSE_CLI_STATUS_LINE") {
+            Ok(value) => template_from_config_str(&value)?,
+            Err(_) => config
+                .get_param::<bool>("GOOSE_CLI_STATUS_LINE")
+                .unwrap_or(false)
+                .then(|| DEFAULT_TEMPLATE.to_string())?,
+        };
+        if !std::io::stdout().is_terminal() || super::accessible::enabled() {
+            return None;
+        }
+
+        Some(Self {
+            template,
+            info: StatusInfo::default(),
+            height: 0,
+        })
+    }
+
+    pub fn info_mut(&mut self) -> &mut StatusInfo {
+        &mut self.info
+    }
+
+    /// Redraws the line, reserving the bottom row first if the terminal is new or was resized.
+    pub fn draw(&mut self) {
+        let (rows, cols) = Term::stdout().size();
+        if rows < 3 {
+            return;
+        }
+        let mut out = std::io::stdout();
+        let mut seq = String::new();
+        if rows != self.height {
+            // make room so the cursor is not on the row being reserved, then set the scroll
+            // region, which also moves the cursor, so keep it where it was
+            seq.push_str(&format!("\n\x1b[1A\x1b7\x1b[1;{}r\x1b8", rows - 1));
+            self.height = rows;
+        }
+        let line = render_template(&self.template, &self.info);
+        seq.push_str(&format!(
+            "\x1b7\x1b[{};1H\x1b[2K{}\x1b8",
+            rows,
+            console::truncate_str(&line, cols as usize, "…")
+        ));
+        let _ = out.write_all(seq.as_bytes()).and_then(|_| out.flush());
+    }
+}
+
+impl Drop for StatusLine {
+    fn drop(&mut self) {
+        if self.height == 0 {
+            return;
+        }
+        let mut out = std::io::stdout();
+        let seq = format!("\x1b7\x1b[r\x1b[{};1H\x1b[2K\x1b8", self.height);
+        let _ = out.write_all(seq.as_bytes()).and_then(|_| out.flush());
+    }
+}
+
+fn template_from_config_str(value: &str) -> Option<String> {
+    match value.trim().to_lowercase().as_str() {
+        "" | "false" | "off" | "0" => None,
+        "true" | "on" | "1" | "default" => Some(DEFAULT_TEMPLATE.to_string()),
+        _ => Some(value.to_string()),
+    }
+}
+
+/// Fills in `{provider}`, `{model}`, `{context}` (a usage bar), `{context_pct}`, `{tokens}`,
+/// `{cost}` and `{subagents}`. Unknown placeholders are left as they are.
+fn render_template(template: &str, info: &StatusInfo) -> String {
+    let percentage = if info.context_limit == 0 {
+        0
+    } else {
+        ((info.context_tokens as f64 / info.context_limit as f64) * 100.0).round() as usize
+    }
+    .min(100);
+
+    template
+        .replace("{provider}", &info.provider)
+        .replace("{model}", &style(&info.model).cyan().to_string())
+        .replace("{context}", &context_bar(percentage))
+        .replace("{context_pct}", &format!("{}%", percentage))
+        .replace(
+            "{tokens}",
+            &format!(
+                "{}/{}",
+                super::output::format_tokens(info.context_tokens),
+                super::output::format_tokens(info.context_limit)
+            ),
+        )
+        .replace(
+            "{cost}",
+            &info
+                .cost
+                .map(|c| format!("${:.2}", c))
+                .unwrap_or_else(|| "$-".to_string()),
+        )
+        .replace("{subagents}", &info.subagents.to_string())
+}
+
+fn context_bar(percentage: usize) -> String {
+    let filled = (percentage * CONTEXT_BAR_WIDTH).div_ceil(100);
+    palette()
+        .context_bar(filled, CONTEXT_BAR_WIDTH, percentage)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("true", Some(DEFAULT_TEMPLATE))]
+    #[test_case("off", None)]
+    #[test_case("", None)]
+    #[test_case("{model} {cost}", Some("{model} {cost}"))]
+    fn test_template_from_config_str(value: &str, expected: Option<&str>) {
+        assert_eq!(template_from_config_str(value).as_deref(), expected);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let info = StatusInfo {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            context_tokens: 32_000,
+            context_limit: 128_000,
+            cost: Some(0.1234),
+            subagents: 2,
+        };
+        let line = render_template(
+            "{provider}/{model} {context_pct} {tokens} {cost} {subagents} {unknown}",
+            &info,
+        );
+        assert_eq!(
+            console::strip_ansi_codes(&line),
+            "openai/gpt-4o 25% 32k/128k $0.12 2 {unknown}"
+        );
+    }
+
+    #[test]
+    fn test_context_bar_width() {
+        for percentage in [0, 1, 50, 99, 100] {
+            let bar = context_bar(percentage);
+            assert_eq!(
+                console::measure_text_width(&bar),
+                CONTEXT_BAR_WIDTH,
+                "{}",
+                percentage
+            );
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{keymap, notify};
+
+const CTRL_Q: u8 = 0x11;
+const CTRL_S: u8 = 0x13;
+
+/// Keys understood while the agent's response is streaming.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamKey {
+    /// Ctrl-S: hold further output until resumed
+    Pause,
+    /// Ctrl-Q: print held output and keep streaming
+    Resume,
+    /// `b` while paused: page through the conversation so far
+    Scrollback,
+    /// The key bound to `interrupt` in the keymap: stop the response, like Ctrl-C
+    Interrupt,
+}
+
+/// Recognises the `ESC [ I` / `ESC [ O` focus reports terminals send once focus reporting is on.
+#[cfg_attr(not(unix), allow(dead_code))]
+#[derive(Default)]
+struct FocusParser {
+    matched: usize,
+}
+
+impl FocusParser {
+    /// Feeds one byte, returning the focus state once a full report has been read.
+    fn push(&mut self, byte: u8) -> Option<bool> {
+        match (self.matched, byte) {
+            (0, 0x1b) | (1, b'[') => {
+                self.matched += 1;
+                None
+            }
+            (2, b'I') | (2, b'O') => {
+                self.matched = 0;
+                Some(byte == b'I')
+            }
+            _ => {
+                self.matched = usize::from(byte == 0x1b);
+                None
+            }
+        }
+    }
+
+    fn in_sequence(&self) -> bool {
+        self.matched > 0
+    }
+}
+
+pub fn key_for_byte(byte: u8, paused: bool, interrupt: Option<u8>) -> Option<StreamKey> {
+    match byte {
+        _ if interrupt == Some(byte) => Some(StreamKey::Interrupt),
+        CTRL_S if paused => Some(StreamKey::Resume),
+        CTRL_S => Some(StreamKey::Pause),
+        CTRL_Q => Some(StreamKey::Resume),
+        b'b' | b'B' if paused => Some(StreamKey::Scrollback),
+        b'q' | b'\r' | b'\n' if paused => Some(StreamKey::Resume),
+        _ => None,
+    }
+}
+
+/// Reads keys from the terminal on a background thread for as long as it is alive. The terminal
+/// is switched out of line mode (and away from XON/XOFF, which would otherwise swallow Ctrl-S)
+/// and restored on drop. Drop it before anything else reads from stdin, such as a prompt.
+///
+/// While it runs, focus reporting is enabled so notifications can tell whether the user is
+/// looking at the terminal.
+#[cfg_attr(not(unix), allow(dead_code))]
+pub struct StreamKeyListener {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    #[cfg(unix)]
+    saved: libc::termios,
+}
+
+impl StreamKeyListener {
+    /// Returns `None` when stdin is not an interactive terminal.
+    #[cfg(unix)]
+    pub fn start(sender: UnboundedSender<StreamKey>, paused: Arc<AtomicBool>) -> Option<Self> {
+        use std::io::IsTerminal;
+        use std::os::fd::AsRawFd;
+
+        let stdin = std::io::stdin();
+        if !stdin.is_terminal() {
+            return None;
+        }
+        let fd = stdin.as_raw_fd();
+
+        // SAFETY: fd is the process's stdin and the termios structs are fully initialised by
+        // tcgetattr before use.
+        let saved = unsafe {
+            let mut saved: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(fd, &mut saved) != 0 {
+                return None;
+            }
+            let mut raw = saved;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+            raw.c_iflag &= !libc::IXON;
+            raw.c_cc[libc::VMIN] = 0;
+            raw.c_cc[libc::VTIME] = 0;
+            if libc::tcsetattr(fd, libc::TCSANOW, &raw) != 0 {
+                return None;
+            }
+            saved
+        };
+
+        // the user just submitted a message, so the terminal has focus
+        notify::set_focused(true);
+        set_focus_reporting(true);
+
+        let interrupt = keymap::keymap().interrupt_byte();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let mut focus = FocusParser::default();
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            while !thread_stop.load(Ordering::Relaxed) {
+                // SAFETY: pollfd points at a single valid pollfd for the duration of the call
+                let ready = unsafe { libc::poll(&mut pollfd, 1, 100) };
+                if ready <= 0 {
+                    continue;
+                }
+                let mut byte = 0u8;
+                // SAFETY: reads at most one byte into a local buffer
+                let read = unsafe { libc::read(fd, &mut byte as *mut u8 as *mut libc::c_void, 1) };
+                if read != 1 {
+                    continue;
+                }
+                if let Some(focused) = focus.push(byte) {
+                    notify::set_focused(focused);
+                    continue;
+                }
+                if focus.in_sequence() {
+                    continue;
+                }
+                if let Some(key) = key_for_byte(byte, paused.load(Ordering::Relaxed), interrupt) {
+                    if sender.send(key).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Some(Self {
+            stop,
+            handle: Some(handle),
+            saved,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_sender: UnboundedSender<StreamKey>, _paused: Arc<AtomicBool>) -> Option<Self> {
+        None
+    }
+}
+
+impl Drop for StreamKeyListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+            set_focus_reporting(false);
+            // SAFETY: restores the settings captured in start on the same descriptor
+            unsafe {
+                libc::tcsetattr(std::io::stdin().as_raw_fd(), libc::TCSANOW, &self.saved);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_focus_reporting(enabled: bool) {
+    use std::io::Write;
+    let mut stdout = std::io::stdout();
+    let sequence: &[u8] = if enabled {
+        b"\x1b[?1004h"
+    } else {
+        b"\x1b[?1004l"
+    };
+    let _ = stdout.write_all(sequence).and_then(|_| stdout.flush());
+}
+
+/// Show text in the user's pager (`$PAGER`, falling back to `less -R`).
+pub fn page(text: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -R".to_string());
+    let mut parts = shlex::split(&pager).unwrap_or_default().into_iter();
+    let program = parts.next().unwrap_or_else(|| "less".to_string());
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager may exit before reading everything
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(CTRL_S, false, Some(StreamKey::Pause) ; "ctrl-s pauses")]
+    #[test_case(CTRL_S, true, Some(StreamKey::Resume) ; "ctrl-s toggles back")]
+    #[test_case(CTRL_Q, true, Some(StreamKey::Resume) ; "ctrl-q resumes")]
+    #[test_case(b'b', true, Some(StreamKey::Scrollback) ; "scrollback while paused")]
+    #[test_case(b'b', false, None ; "typing while streaming is ignored")]
+    #[test_case(b'\r', true, Some(StreamKey::Resume) ; "enter resumes")]
+    fn test_key_for_byte(byte: u8, paused: bool, expected: Option<StreamKey>) {
+        assert_eq!(key_for_byte(byte, paused, None), expected);
+    }
+
+    #[test_case(0x07, false ; "while streaming")]
+    #[test_case(0x07, true ; "while paused")]
+    fn test_interrupt_key(byte: u8, paused: bool) {
+        assert_eq!(
+            key_for_byte(byte, paused, Some(0x07)),
+            Some(StreamKey::Interrupt)
+        );
+    }
+
+    #[test]
+    fn test_focus_parser() {
+        let mut parser = FocusParser::default();
+        let reports: Vec<Option<bool>> = b"\x1b[Ox\x1b\x1b[I"
+            .iter()
+            .map(|b| parser.push(*b))
+            .collect();
+        assert_eq!(
+            reports,
+            vec![None, None, Some(false), None, None, None, None, Some(true)]
+        );
+        assert!(!parser.in_sequence());
+    }
+}
@@ -22,6 +22,7 @@
 
 use regex::Regex;
 use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
 /// Regex that tokenizes markdown inline elements.
 /// Order matters: longer/more-specific patterns first.
@@ -53,9 +54,17 @@ static INLINE_TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| {
 ///
 /// Accumulates chunks and returns content that is safe to render,
 /// holding back any incomplete markdown constructs.
+///
+/// With [`MarkdownBuffer::with_flush_after`], prose that has been held back for longer than the
+/// given time (say, after a stray `_` in `snake_case`) is released up to the last word, so a
+/// one-paragraph answer still streams. Code blocks, tables, headings and list items are never
+/// split this way.
 #[derive(Default)]
 pub struct MarkdownBuffer {
     buffer: String,
+    flush_after: Option<Duration>,
+    /// When the content currently in the buffer started being held back
+    held_since: Option<Instant>,
 }
 
 /// Tracks the current parsing state for markdown constructs.
@@ -103,13 +112,41 @@ impl MarkdownBuffer {
         Self::default()
     }
 
+    /// A buffer that releases held-back prose at word boundaries once it has waited `delay`.
+    pub fn with_flush_after(delay: Duration) -> Self {
+        Self {
+            flush_after: Some(delay),
+            ..Self::default()
+        }
+    }
+
     /// Add a chunk of markdown text to the buffer.
     ///
     /// Returns any content that is safe to render, or None if the buffer
     /// contains only incomplete constructs.
     pub fn push(&mut self, chunk: &str) -> Option<String> {
+        self.push_at(chunk, Instant::now())
+    }
+
+    fn push_at(&mut self, chunk: &str, now: Instant) -> Option<String> {
         self.buffer.push_str(chunk);
-        let safe_end = self.find_safe_end();
+        let (mut safe_end, state) = self.find_safe_end();
+
+        let waited_too_long = match (self.flush_after, self.held_since) {
+            (Some(delay), Some(since)) => now.duration_since(since) >= delay,
+            _ => false,
+        };
+        if safe_end == 0 && waited_too_long {
+            safe_end = self.partial_flush_end(&state);
+        }
+
+        self.held_since = if safe_end >= self.buffer.len() {
+            None
+        } else if safe_end > 0 {
+            Some(now)
+        } else {
+            self.held_since.or(Some(now))
+        };
 
         if safe_end > 0 {
             // SAFETY: safe_end is always at a valid UTF-8 char boundary because:
@@ -129,11 +166,29 @@ impl MarkdownBuffer {
     /// Call this at the end of a stream to get any buffered content,
     /// even if markdown constructs are unclosed.
     pub fn flush(&mut self) -> String {
+        self.held_since = None;
         std::mem::take(&mut self.buffer)
     }
 
-    /// Find the last byte position where the parse state is "clean".
-    fn find_safe_end(&self) -> usize {
+    /// Where held-back prose can be cut when it has waited too long: after the last whitespace,
+    /// or at the start of the current line for list items. Returns 0 inside block constructs.
+    fn partial_flush_end(&self, state: &ParseState) -> usize {
+        if state.in_code_block || state.in_table || state.pending_heading {
+            return 0;
+        }
+        let line_start = self.buffer.rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.buffer[line_start..];
+        if is_list_item(line) {
+            return line_start;
+        }
+        line.rfind(char::is_whitespace)
+            .map(|i| line_start + i + 1)
+            .unwrap_or(line_start)
+    }
+
+    /// Find the last byte position where the parse state is "clean", along with the state at
+    /// the end of the buffer.
+    fn find_safe_end(&self) -> (usize, ParseState) {
         let mut state = ParseState::default();
         let mut last_safe: usize = 0;
         let bytes = self.buffer.as_bytes();
@@ -188,7 +243,7 @@ impl MarkdownBuffer {
             pos = line_end;
         }
 
-        last_safe
+        (last_safe, state)
     }
 
     /// Process block-level constructs at the start of a line.
@@ -365,6 +420,95 @@ impl MarkdownBuffer {
     }
 }
 
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if ["- ", "* ", "+ "]
+        .iter()
+        .any(|marker| trimmed.starts_with(marker))
+    {
+        return true;
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && trimmed.chars().skip(digits).take(2).collect::<String>() == ". "
+}
+
+/// A piece of markdown that is ready to render: either prose or a complete fenced code block.
+#[derive(Debug, PartialEq)]
+pub enum MarkdownSegment<'a> {
+    Text(&'a str),
+    CodeBlock {
+        /// First word of the info string, e.g. `rust` for a ```` ```rust ```` fence.
+        language: &'a str,
+        /// The lines between the fences.
+        code: &'a str,
+        /// The whole block, including both fence lines.
+        block: &'a str,
+    },
+}
+
+/// Split flushed markdown into prose and fenced code blocks so code can be highlighted with its
+/// own language. A fence that is still open at the end stays part of the trailing text.
+// SAFETY: slicing happens only at line boundaries (after '\n') and after ASCII fence characters.
+#[allow(clippy::string_slice)]
+pub fn split_code_blocks(content: &str) -> Vec<MarkdownSegment<'_>> {
+    struct OpenFence {
+        start: usize,
+        body_start: usize,
+        fence_char: char,
+        fence_len: usize,
+    }
+
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut open: Option<OpenFence> = None;
+    let mut line_start = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let trimmed = line.trim_start();
+        let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        let fence_len = fence_char
+            .map(|c| trimmed.chars().take_while(|&ch| ch == c).count())
+            .unwrap_or(0);
+
+        match (&open, fence_char) {
+            (None, Some(c)) if fence_len >= 3 => {
+                open = Some(OpenFence {
+                    start: line_start,
+                    body_start: line_end,
+                    fence_char: c,
+                    fence_len,
+                });
+            }
+            (Some(fence), Some(c))
+                if c == fence.fence_char
+                    && fence_len >= fence.fence_len
+                    && trimmed[fence_len..].trim().is_empty() =>
+            {
+                let opening = &content[fence.start..fence.body_start];
+                let info = opening.trim_start().trim_start_matches(fence.fence_char);
+                if text_start < fence.start {
+                    segments.push(MarkdownSegment::Text(&content[text_start..fence.start]));
+                }
+                segments.push(MarkdownSegment::CodeBlock {
+                    language: info.split_whitespace().next().unwrap_or(""),
+                    code: &content[fence.body_start..line_start],
+                    block: &content[fence.start..line_end],
+                });
+                text_start = line_end;
+                open = None;
+            }
+            _ => {}
+        }
+        line_start = line_end;
+    }
+
+    if text_start < content.len() {
+        segments.push(MarkdownSegment::Text(&content[text_start..]));
+    }
+    segments
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,6 +582,11 @@ mod tests {
         &["```\ncode"]
         ; "unclosed code block flushes at end"
     )]
+    #[test_case(
+        &["```rust\nfn a() {}\n", "\n", "\nfn b() {}\n", "```\n"],
+        &["```rust\nfn a() {}\n\n\nfn b() {}\n```\n"]
+        ; "blank lines inside code block do not split it"
+    )]
     fn test_code_blocks(chunks: &[&str], expected: &[&str]) {
         assert_eq!(stream(chunks), expected);
     }
@@ -593,4 +742,90 @@ mod tests {
     fn test_incomplete_constructs(chunks: &[&str], expected: &[&str]) {
         assert_eq!(stream(chunks), expected);
     }
+
+    /// Like `stream`, but with each chunk arriving `gap_ms` after the previous one.
+    fn stream_timed(chunks: &[&str], gap_ms: u64, flush_after_ms: u64) -> Vec<String> {
+        let mut buf = MarkdownBuffer::with_flush_after(Duration::from_millis(flush_after_ms));
+        let start = Instant::now();
+        let mut results: Vec<String> = chunks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, chunk)| {
+                buf.push_at(chunk, start + Duration::from_millis(gap_ms * i as u64))
+            })
+            .collect();
+        let remaining = buf.flush();
+        if !remaining.is_empty() {
+            results.push(remaining);
+        }
+        results
+    }
+
+    #[test_case(
+        &["Rename my_var", " to the new", " name and", " rerun."],
+        100,
+        &["Rename my", "_var to the ", "new name and", " rerun."]
+        ; "stray underscore releases words after the delay"
+    )]
+    #[test_case(
+        &["Rename my_var", " to the new", " name"],
+        10,
+        &["Rename my", "_var to the new name"]
+        ; "held until the delay passes"
+    )]
+    #[test_case(
+        &["```rust\nlet a", " = 1;\n", "let b = 2;\n", "```\n"],
+        100,
+        &["```rust\nlet a = 1;\nlet b = 2;\n```\n"]
+        ; "code blocks stay whole"
+    )]
+    #[test_case(
+        &["Steps:\n* use the", " new", " loop\n"],
+        100,
+        &["Steps:\n", "* use the new loop\n"]
+        ; "list items stay whole"
+    )]
+    fn test_time_based_flush(chunks: &[&str], gap_ms: u64, expected: &[&str]) {
+        assert_eq!(stream_timed(chunks, gap_ms, 50), expected);
+    }
+
+    #[test]
+    fn test_split_code_blocks() {
+        let content = "Intro:\n\n```rust\nfn main() {}\n```\nOutro\n";
+        assert_eq!(
+            split_code_blocks(content),
+            vec![
+                MarkdownSegment::Text("Intro:\n\n"),
+                MarkdownSegment::CodeBlock {
+                    language: "rust",
+                    code: "fn main() {}\n",
+                    block: "```rust\nfn main() {}\n```\n",
+                },
+                MarkdownSegment::Text("Outro\n"),
+            ]
+        );
+    }
+
+    #[test_case("````md\n```\ninner\n```\n````\n", "md", "```\ninner\n```\n" ; "longer outer fence")]
+    #[test_case("~~~ python title\nx = 1\n~~~", "python", "x = 1\n" ; "tilde fence with extra info and no newline")]
+    #[test_case("```\nplain\n```\n", "", "plain\n" ; "no language")]
+    fn test_split_code_blocks_fences(content: &str, language: &str, code: &str) {
+        assert_eq!(
+            split_code_blocks(content),
+            vec![MarkdownSegment::CodeBlock {
+                language,
+                code,
+                block: content,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_split_code_blocks_unclosed_fence_stays_text() {
+        let content = "text\n```rust\nfn main() {\n";
+        assert_eq!(
+            split_code_blocks(content),
+            vec![MarkdownSegment::Text(content)]
+        );
+    }
 }
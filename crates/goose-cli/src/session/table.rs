@@ -0,0 +1,208 @@
+use console::{measure_text_width, pad_str, Alignment};
+
+/// Columns are never cut narrower than this, even when the table then overflows the terminal
+const MIN_COLUMN_WIDTH: usize = 5;
+
+/// A markdown table, laid out again to fit the terminal instead of being wrapped by character
+#[derive(Debug, PartialEq)]
+pub struct MarkdownTable {
+    header: Vec<String>,
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+}
+
+/// A table as it is printed, and whether any cell had to be cut short to fit
+#[derive(Debug, PartialEq)]
+pub struct Layout {
+    pub text: String,
+    pub truncated: bool,
+}
+
+fn cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    line.split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+fn alignment(separator: &str) -> Option<Alignment> {
+    if !separator.contains('-') || !separator.chars().all(|c| c == '-' || c == ':') {
+        return None;
+    }
+    Some(
+        match (separator.starts_with(':'), separator.ends_with(':')) {
+            (true, true) => Alignment::Center,
+            (false, true) => Alignment::Right,
+            _ => Alignment::Left,
+        },
+    )
+}
+
+impl MarkdownTable {
+    /// The table in `lines`: a header, a `|---|:---:|` separator and any number of rows.
+    pub fn parse(lines: &[&str]) -> Option<Self> {
+        let (header, rest) = lines.split_first()?;
+        let (separator, rows) = rest.split_first()?;
+        let header = cells(header);
+        let alignments = cells(separator)
+            .iter()
+            .map(|cell| alignment(cell))
+            .collect::<Option<Vec<_>>>()?;
+        let rows = rows.iter().map(|line| cells(line)).collect();
+        Some(Self {
+            header,
+            alignments,
+            rows,
+        })
+    }
+
+    fn column_count(&self) -> usize {
+        self.rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.header.len()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn natural_widths(&self) -> Vec<usize> {
+        let mut widths = vec![0; self.column_count()];
+        for row in std::iter::once(&self.header).chain(&self.rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(measure_text_width(cell));
+            }
+        }
+        widths
+    }
+
+    /// How wide each column can be for the table to fit in `max_width`. Narrow columns keep
+    /// their width and the wide ones share what is left equally.
+    fn column_widths(&self, max_width: usize) -> Vec<usize> {
+        let natural = self.natural_widths();
+        // `| ` and ` |` around the row and ` | ` between cells
+        let borders = 3 * natural.len() + 1;
+        let mut available = max_width.saturating_sub(borders);
+        if natural.iter().sum::<usize>() <= available {
+            return natural;
+        }
+
+        let mut widths = natural.clone();
+        let mut by_width: Vec<usize> = (0..natural.len()).collect();
+        by_width.sort_by_key(|&column| natural[column]);
+        for (placed, &column) in by_width.iter().enumerate() {
+            let share = available / (natural.len() - placed);
+            widths[column] = natural[column].min(share.max(MIN_COLUMN_WIDTH));
+            available = available.saturating_sub(widths[column]);
+        }
+        widths
+    }
+
+    /// The table with its columns aligned and cut with `…` where needed to fit `max_width`
+    pub fn layout(&self, max_width: usize) -> Layout {
+        let widths = self.column_widths(max_width);
+        let mut truncated = false;
+        let mut line = |row: &[String]| {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, &width)| {
+                    let cell = row.get(column).map(String::as_str).unwrap_or("");
+                    let align = self
+                        .alignments
+                        .get(column)
+                        .copied()
+                        .unwrap_or(Alignment::Left);
+                    truncated |= measure_text_width(cell) > width;
+                    pad_str(cell, width, align, Some("…")).into_owned()
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut lines = vec![line(&self.header)];
+        let separators: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, &width)| {
+                let dashes = "-".repeat(width);
+                match self.alignments.get(column) {
+                    Some(Alignment::Center) => format!(":{}:", dashes),
+                    Some(Alignment::Right) => format!("-{}:", dashes),
+                    _ => format!("-{}-", dashes),
+                }
+            })
+            .collect();
+        lines.push(format!("|{}|", separators.join("|")));
+        for row in &self.rows {
+            lines.push(line(row));
+        }
+
+        Layout {
+            text: lines.join("\n") + "\n",
+            truncated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn table(lines: &str) -> MarkdownTable {
+        MarkdownTable::parse(&lines.lines().collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_reads_alignments() {
+        let parsed = table("| a | b | c |\n|---|:-:|--:|\n| 1 | 2 | 3 |");
+        assert_eq!(
+            parsed.alignments,
+            vec![Alignment::Left, Alignment::Center, Alignment::Right]
+        );
+        assert_eq!(parsed.rows, vec![vec!["1", "2", "3"]]);
+    }
+
+    #[test_case(&["| a |"] ; "header only")]
+    #[test_case(&["| a |", "| b |"] ; "no separator")]
+    fn test_parse_needs_a_separator(lines: &[&str]) {
+        assert_eq!(MarkdownTable::parse(lines), None);
+    }
+
+    #[test]
+    fn test_layout_keeps_a_table_that_fits() {
+        let layout = table("| name | n |\n|---|--:|\n| goose | 12 |").layout(80);
+        assert_eq!(
+            layout.text,
+            "| name  |  n |\n|-------|---:|\n| goose | 12 |\n"
+        );
+        assert!(!layout.truncated);
+    }
+
+    #[test]
+    fn test_layout_cuts_wide_columns_to_fit() {
+        let layout = table(
+            "| id | description |\n|---|---|\n| 1 | a very long description that does not fit |",
+        )
+        .layout(30);
+        assert!(layout.truncated);
+        for line in layout.text.lines() {
+            assert_eq!(measure_text_width(line), 30, "{}", line);
+        }
+        assert!(layout.text.contains("| 1  | a very long descript… |"));
+    }
+
+    #[test]
+    fn test_short_rows_are_padded() {
+        let layout = table("| a | b |\n|---|---|\n| 1 |").layout(80);
+        assert_eq!(layout.text.lines().last(), Some("| 1 |   |"));
+    }
+
+    #[test]
+    fn test_columns_keep_a_minimum_width() {
+        let parsed = table("| aaaaaaaa | bbbbbbbb |\n|---|---|");
+        assert_eq!(parsed.column_widths(10), vec![5, 5]);
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::OnceLock;
+
+/// How long to wait for the terminal to answer. Terminals without OSC 11 support never reply.
+#[cfg(unix)]
+const QUERY_TIMEOUT_MS: i32 = 150;
+
+/// Whether the terminal background is light, asked once per process via OSC 11. `None` when
+/// there is no terminal or it did not answer.
+pub fn is_light_background() -> Option<bool> {
+    static DETECTED: OnceLock<Option<bool>> = OnceLock::new();
+    *DETECTED.get_or_init(|| {
+        let response = query_background()?;
+        let (r, g, b) = parse_osc11_response(&response)?;
+        Some(is_light(r, g, b))
+    })
+}
+
+#[cfg(unix)]
+fn query_background() -> Option<Vec<u8>> {
+    use std::io::{IsTerminal, Write};
+    use std::os::fd::AsRawFd;
+
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let fd = tty.as_raw_fd();
+
+    // SAFETY: fd belongs to the open tty and the termios structs are initialised by tcgetattr
+    let saved = unsafe {
+        let mut saved: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut saved) != 0 {
+            return None;
+        }
+        let mut raw = saved;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        if libc::tcsetattr(fd, libc::TCSANOW, &raw) != 0 {
+            return None;
+        }
+        saved
+    };
+
+    let mut response = Vec::new();
+    if tty
+        .write_all(b"\x1b]11;?\x07")
+        .and_then(|_| tty.flush())
+        .is_ok()
+    {
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let mut buf = [0u8; 64];
+        // SAFETY: pollfd and buf are valid for the duration of each call
+        while unsafe { libc::poll(&mut pollfd, 1, QUERY_TIMEOUT_MS) } > 0 {
+            let read = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+            if read <= 0 {
+                break;
+            }
+            response.extend_from_slice(&buf[..read as usize]);
+            if response.ends_with(b"\x07") || response.ends_with(b"\x1b\\") || response.len() > 256
+            {
+                break;
+            }
+        }
+    }
+
+    // SAFETY: restores the settings captured above on the same descriptor
+    unsafe {
+        libc::tcsetattr(fd, libc::TCSANOW, &saved);
+    }
+    (!response.is_empty()).then_some(response)
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<Vec<u8>> {
+    None
+}
+
+/// Parses `ESC ] 11 ; rgb:RRRR/GGGG/BBBB` (terminated by BEL or ST) into components scaled to
+/// 0.0..=1.0. Each component may have one to four hex digits.
+fn parse_osc11_response(response: &[u8]) -> Option<(f64, f64, f64)> {
+    let text = String::from_utf8_lossy(response);
+    let (_, rest) = text.split_once("rgb:")?;
+    let body: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_hexdigit() || *c == '/')
+        .collect();
+
+    let components: Vec<f64> = body
+        .split('/')
+        .map(|hex| {
+            if hex.is_empty() || hex.len() > 4 {
+                return None;
+            }
+            let value = u32::from_str_radix(hex, 16).ok()?;
+            let max = (1u32 << (4 * hex.len())) - 1;
+            Some(value as f64 / max as f64)
+        })
+        .collect::<Option<_>>()?;
+
+    match components.as_slice() {
+        [r, g, b] => Some((*r, *g, *b)),
+        _ => None,
+    }
+}
+
+/// Relative luminance (sRGB coefficients) above the midpoint counts as a light background.
+fn is_light(r: f64, g: f64, b: f64) -> bool {
+    0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(b"\x1b]11;rgb:ffff/ffff/ffff\x07", Some(true) ; "white with bel")]
+    #[test_case(b"\x1b]11;rgb:0000/0000/0000\x1b\\", Some(false) ; "black with st")]
+    #[test_case(b"\x1b]11;rgb:fd/f6/e3\x07", Some(true) ; "solarized light two digits")]
+    #[test_case(b"\x1b]11;rgb:2828/2c2c/3434\x07", Some(false) ; "one dark")]
+    #[test_case(b"\x1b]11;rgb:ffff/ffff\x07", None ; "missing component")]
+    #[test_case(b"garbage", None ; "no rgb")]
+    fn test_parse_background(response: &[u8], expected: Option<bool>) {
+        assert_eq!(
+            parse_osc11_response(response).map(|(r, g, b)| is_light(r, g, b)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+
+/// Where the terminal renderer's formatted output goes. Everything `output` prints goes through
+/// the current writer, so tests can swap stdout for a [`StringWriter`] and compare what a tool
+/// call renders to.
+pub trait TermWriter {
+    fn write_str(&mut self, text: &str);
+
+    /// Whether output is shown on a terminal, which decides between highlighted and plain text.
+    fn is_terminal(&self) -> bool;
+
+    /// Whether the output ends up on a terminal at all, which decides whether responses are
+    /// rendered as they complete or streamed raw. A writer can format plain text for a
+    /// terminal it draws into itself.
+    fn on_terminal(&self) -> bool {
+        self.is_terminal()
+    }
+
+    /// What the user typed at the prompt, which the terminal already shows. Only writers that
+    /// keep a copy of the screen need it.
+    fn echo_input(&mut self, _text: &str) {}
+}
+
+/// Writes to stdout, stripping styling when stdout is not a terminal.
+pub struct StdoutWriter;
+
+impl TermWriter for StdoutWriter {
+    fn write_str(&mut self, text: &str) {
+        let _ = anstream::stdout().write_all(text.as_bytes());
+    }
+
+    fn is_terminal(&self) -> bool {
+        std::io::stdout().is_terminal()
+    }
+}
+
+/// Writes to stderr, for commands that keep stdout for their result alone.
+pub struct StderrWriter;
+
+impl TermWriter for StderrWriter {
+    fn write_str(&mut self, text: &str) {
+        let _ = anstream::stderr().write_all(text.as_bytes());
+    }
+
+    fn is_terminal(&self) -> bool {
+        std::io::stderr().is_terminal()
+    }
+}
+
+/// Collects output in memory. Clones share the buffer, so one can be handed to the renderer
+/// while the other reads what was written.
+#[derive(Clone, Default)]
+pub struct StringWriter {
+    buffer: Arc<Mutex<String>>,
+}
+
+impl StringWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written so far, without styling.
+    pub fn contents(&self) -> String {
+        console::strip_ansi_codes(&self.buffer.lock().unwrap()).into_owned()
+    }
+
+    /// Like [`StringWriter::contents`], emptying the buffer.
+    pub fn take(&self) -> String {
+        let text = std::mem::take(&mut *self.buffer.lock().unwrap());
+        console::strip_ansi_codes(&text).into_owned()
+    }
+}
+
+impl TermWriter for StringWriter {
+    fn write_str(&mut self, text: &str) {
+        self.buffer.lock().unwrap().push_str(text);
+    }
+
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_writer_clones_share_and_strip_styling() {
+        let writer = StringWriter::new();
+        let mut handle: Box<dyn TermWriter> = Box::new(writer.clone());
+        handle.write_str("\x1b[1mbold\x1b[0m\n");
+        handle.write_str("plain");
+        assert_eq!(writer.contents(), "bold\nplain");
+        assert_eq!(writer.take(), "bold\nplain");
+        assert_eq!(writer.contents(), "");
+    }
+}
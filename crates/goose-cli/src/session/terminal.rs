@@ -0,0 +1,88 @@
+use std::io::{IsTerminal, Write};
+use std::sync::OnceLock;
+
+static ANSI_SUPPORT: OnceLock<bool> = OnceLock::new();
+
+/// Whether stdout understands ANSI escape sequences. On Windows this turns on virtual terminal
+/// processing the first time it is called; legacy conhost, where that fails, also gets colors
+/// turned off so styled text prints as plain text instead of escape codes.
+pub fn supports_ansi() -> bool {
+    *ANSI_SUPPORT.get_or_init(|| {
+        let supported = enable_virtual_terminal();
+        if !supported {
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+        }
+        supported
+    })
+}
+
+#[cfg(not(windows))]
+fn enable_virtual_terminal() -> bool {
+    true
+}
+
+#[cfg(windows)]
+fn enable_virtual_terminal() -> bool {
+    use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+    use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+    use winapi::um::processenv::GetStdHandle;
+    use winapi::um::winbase::STD_OUTPUT_HANDLE;
+    use winapi::um::wincon::ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+
+    // Windows Terminal and terminals outside the console host (mintty, ConPTY-based IDE
+    // terminals) handle escape sequences themselves
+    if std::env::var_os("WT_SESSION").is_some() || !std::io::stdout().is_terminal() {
+        return true;
+    }
+
+    // SAFETY: the handle comes from GetStdHandle and is only passed to console mode calls,
+    // which fail gracefully for handles that are not consoles
+    unsafe {
+        let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+        if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+            return false;
+        }
+        let mut mode = 0;
+        if GetConsoleMode(handle, &mut mode) == 0 {
+            return false;
+        }
+        mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+            || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+    }
+}
+
+/// Sets the window or tab title, with an escape sequence where supported and through the
+/// console API on legacy Windows consoles.
+pub fn set_title(title: &str) {
+    if !std::io::stdout().is_terminal() {
+        return;
+    }
+    // Sanitize: strip control characters (ESC, BEL, etc.) to prevent terminal escape injection
+    let sanitized: String = title.chars().filter(|c| !c.is_control()).collect();
+    if supports_ansi() {
+        // OSC 0 sets the terminal window/tab title
+        print!("\x1b]0;{}\x07", sanitized);
+        let _ = std::io::stdout().flush();
+    } else {
+        set_console_title(&sanitized);
+    }
+}
+
+#[cfg(not(windows))]
+fn set_console_title(_title: &str) {}
+
+#[cfg(windows)]
+fn set_console_title(title: &str) {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::wincon::SetConsoleTitleW;
+
+    let wide: Vec<u16> = std::ffi::OsStr::new(title)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    // SAFETY: `wide` is a NUL-terminated UTF-16 string that outlives the call
+    unsafe {
+        SetConsoleTitleW(wide.as_ptr());
+    }
+}
@@ -0,0 +1,144 @@
+use console::{style, Key, Term};
+use std::io;
+use std::ops::Range;
+
+use super::output::{self, Theme};
+
+const VISIBLE_THEMES: usize = 7;
+
+const PREVIEW: &str = "## Preview
+
+Some *emphasis*, **bold** and `inline code`.
+
+```rust
+fn greet(name: &str) -> String {
+    format!(\"Hello, {}!\", name) // comment
+}
+```
+
+- [a link](https://example.com)
+";
+
+/// Lets the user arrow through the bat themes with a live preview. The chosen theme is saved for
+/// the current light/dark mode; from the ansi mode it becomes the dark theme.
+pub fn pick_theme() -> io::Result<()> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        println!("The theme picker needs an interactive terminal");
+        return Ok(());
+    }
+
+    let mode = match output::get_theme() {
+        Theme::Light => Theme::Light,
+        Theme::Dark | Theme::Ansi => Theme::Dark,
+    };
+    let themes: Vec<String> = bat::PrettyPrinter::new()
+        .themes()
+        .map(str::to_string)
+        .collect();
+    if themes.is_empty() {
+        return Ok(());
+    }
+    let current = mode.as_str();
+    let mut selected = themes.iter().position(|t| *t == current).unwrap_or(0);
+
+    term.hide_cursor()?;
+    let mut drawn = 0;
+    let choice = loop {
+        term.clear_last_lines(drawn)?;
+        drawn = draw(&themes, selected)?;
+        match term.read_key() {
+            Ok(Key::ArrowUp | Key::Char('k')) => {
+                selected = selected.checked_sub(1).unwrap_or(themes.len() - 1);
+            }
+            Ok(Key::ArrowDown | Key::Char('j') | Key::Tab) => {
+                selected = (selected + 1) % themes.len();
+            }
+            Ok(Key::Enter) => break Some(themes[selected].clone()),
+            Ok(Key::Escape | Key::CtrlC | Key::Char('q')) => break None,
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => break None,
+            Err(e) => {
+                term.show_cursor()?;
+                return Err(e);
+            }
+        }
+    };
+    term.clear_last_lines(drawn)?;
+    term.show_cursor()?;
+
+    match choice {
+        Some(name) => {
+            output::set_bat_theme(mode, &name);
+            output::set_theme(mode);
+            println!(
+                "{} theme set to {}",
+                if matches!(mode, Theme::Light) {
+                    "Light"
+                } else {
+                    "Dark"
+                },
+                style(name).cyan()
+            );
+        }
+        None => println!("Theme unchanged"),
+    }
+    Ok(())
+}
+
+/// Prints the theme list around `selected` and the preview in that theme, returning the number
+/// of lines written so the next frame can replace them.
+fn draw(themes: &[String], selected: usize) -> io::Result<usize> {
+    println!(
+        "{}",
+        style("Select a theme (↑/↓ to browse, Enter to apply, Esc to cancel)").dim()
+    );
+    let range = visible_range(themes.len(), selected, VISIBLE_THEMES);
+    let listed = range.len();
+    for idx in range {
+        if idx == selected {
+            println!(
+                "{} {}",
+                style("›").cyan(),
+                style(&themes[idx]).cyan().bold()
+            );
+        } else {
+            println!("  {}", themes[idx]);
+        }
+    }
+    println!();
+
+    bat::PrettyPrinter::new()
+        .input(bat::Input::from_bytes(PREVIEW.as_bytes()))
+        .theme(&themes[selected])
+        .language("Markdown")
+        .colored_output(true)
+        .wrapping_mode(bat::WrappingMode::NoWrapping(true))
+        .print()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+    Ok(1 + listed + 1 + PREVIEW.lines().count())
+}
+
+/// The window of `height` entries to show so that `selected` stays visible, roughly centred.
+fn visible_range(len: usize, selected: usize, height: usize) -> Range<usize> {
+    if len <= height {
+        return 0..len;
+    }
+    let start = selected.saturating_sub(height / 2).min(len - height);
+    start..start + height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(3, 1, 7, 0..3 ; "fewer themes than rows")]
+    #[test_case(20, 0, 7, 0..7 ; "at the top")]
+    #[test_case(20, 10, 7, 7..14 ; "centred")]
+    #[test_case(20, 19, 7, 13..20 ; "at the bottom")]
+    fn test_visible_range(len: usize, selected: usize, height: usize, expected: Range<usize>) {
+        assert_eq!(visible_range(len, selected, height), expected);
+    }
+}
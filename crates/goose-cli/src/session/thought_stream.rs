@@ -0,0 +1,111 @@
+use console::truncate_str;
+use goose::config::Config;
+use std::time::Instant;
+
+use super::output::format_tokens;
+
+/// Lines of thinking kept on screen while the model thinks, unless `GOOSE_CLI_THINKING_LINES`
+/// says otherwise
+const DEFAULT_VISIBLE_LINES: usize = 4;
+
+/// Rough size of a token, to say how long the thinking was without running a tokenizer
+const CHARS_PER_TOKEN: usize = 4;
+
+/// The model's thinking as it streams in. Only the last few lines are shown, and the region
+/// collapses to one line once the answer starts.
+pub struct ThoughtStream {
+    text: String,
+    started: Instant,
+    visible_lines: usize,
+    /// How many lines of the region are on screen, to redraw it in place
+    pub drawn: usize,
+}
+
+pub fn visible_lines() -> usize {
+    Config::global()
+        .get_param("GOOSE_CLI_THINKING_LINES")
+        .unwrap_or(DEFAULT_VISIBLE_LINES)
+}
+
+impl ThoughtStream {
+    pub fn new(started: Instant, visible_lines: usize) -> Self {
+        Self {
+            text: String::new(),
+            started,
+            visible_lines,
+            drawn: 0,
+        }
+    }
+
+    pub fn push(&mut self, chunk: &str) {
+        self.text.push_str(chunk);
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The latest lines of thinking, each cut to `width` so the region keeps its height
+    pub fn tail(&self, width: usize) -> Vec<String> {
+        let lines: Vec<&str> = self
+            .text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(self.visible_lines))
+            .map(|line| truncate_str(line, width, "…").into_owned())
+            .collect()
+    }
+
+    fn estimated_tokens(&self) -> usize {
+        self.text.chars().count().div_ceil(CHARS_PER_TOKEN)
+    }
+
+    /// e.g. `thought for 42s (1k tokens)`, what the region collapses to
+    pub fn summary(&self, now: Instant) -> String {
+        let secs = now.saturating_duration_since(self.started).as_secs();
+        let elapsed = if secs < 60 {
+            format!("{}s", secs)
+        } else {
+            format!("{}m {:02}s", secs / 60, secs % 60)
+        };
+        format!(
+            "thought for {} ({} tokens)",
+            elapsed,
+            format_tokens(self.estimated_tokens())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use test_case::test_case;
+
+    #[test]
+    fn test_tail_keeps_the_last_lines_cut_to_width() {
+        let mut thoughts = ThoughtStream::new(Instant::now(), 2);
+        thoughts.push("first line\n\nsecond ");
+        thoughts.push("line\nthird line is rather long\n");
+        assert_eq!(
+            thoughts.tail(12),
+            vec!["second line".to_string(), "third line …".to_string()]
+        );
+    }
+
+    #[test_case(42, 4_001, "thought for 42s (1k tokens)" ; "seconds")]
+    #[test_case(75, 300, "thought for 1m 15s (75 tokens)" ; "minutes")]
+    fn test_summary(secs: u64, chars: usize, expected: &str) {
+        let started = Instant::now();
+        let mut thoughts = ThoughtStream::new(started, 4);
+        thoughts.push(&"a".repeat(chars));
+        assert_eq!(
+            thoughts.summary(started + Duration::from_secs(secs)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+static CHECKBOX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)[-*+]\s+\[([ xX~/-])\]\s+(.+)$").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TodoStatus {
+    Pending,
+    InProgress,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem {
+    pub text: String,
+    pub status: TodoStatus,
+    /// Nesting level, 0 for top-level items
+    pub depth: usize,
+}
+
+/// The checklist the agent keeps with `todo__write`. Its content is free-form markdown; the
+/// checkbox items (`- [ ]`, `- [x]`, and `- [~]` for in progress) are what gets tracked.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TodoList {
+    items: Vec<TodoItem>,
+}
+
+impl TodoList {
+    pub fn parse(content: &str) -> Self {
+        let mut indents: Vec<usize> = Vec::new();
+        let items = content
+            .lines()
+            .filter_map(|line| CHECKBOX.captures(line))
+            .map(|captures| {
+                let indent = captures[1].replace('\t', "    ").len();
+                // nesting follows the indents seen so far, however wide they are
+                while indents.last().is_some_and(|last| *last >= indent) {
+                    indents.pop();
+                }
+                let depth = indents.len();
+                indents.push(indent);
+                let status = match &captures[2] {
+                    "x" | "X" => TodoStatus::Done,
+                    " " => TodoStatus::Pending,
+                    _ => TodoStatus::InProgress,
+                };
+                TodoItem {
+                    text: captures[3].trim().to_string(),
+                    status,
+                    depth,
+                }
+            })
+            .collect();
+        Self { items }
+    }
+
+    pub fn items(&self) -> &[TodoItem] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Done items and the total
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self
+            .items
+            .iter()
+            .filter(|item| item.status == TodoStatus::Done)
+            .count();
+        (done, self.items.len())
+    }
+
+    pub fn percent(&self) -> usize {
+        match self.progress() {
+            (_, 0) => 0,
+            (done, total) => done * 100 / total,
+        }
+    }
+
+    /// Items whose status changed since `previous`, when both lists have the same items.
+    /// `None` means the list itself changed and should be shown in full.
+    pub fn changes_since<'a>(&'a self, previous: &TodoList) -> Option<Vec<&'a TodoItem>> {
+        let same_items = self.items.len() == previous.items.len()
+            && self
+                .items
+                .iter()
+                .zip(&previous.items)
+                .all(|(a, b)| a.text == b.text && a.depth == b.depth);
+        same_items.then(|| {
+            self.items
+                .iter()
+                .zip(&previous.items)
+                .filter(|(a, b)| a.status != b.status)
+                .map(|(a, _)| a)
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const TODO: &str = "## Release\n\
+        - [x] Bump version\n\
+        - [ ] Update changelog\n    \
+            - [~] Collect merged PRs\n    \
+            - [ ] Write highlights\n\
+        * [X] Tag release\n\
+        Notes that aren't items\n";
+
+    #[test]
+    fn test_parse_items_with_nesting() {
+        let list = TodoList::parse(TODO);
+        let summary: Vec<_> = list
+            .items()
+            .iter()
+            .map(|item| (item.text.as_str(), item.status, item.depth))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Bump version", TodoStatus::Done, 0),
+                ("Update changelog", TodoStatus::Pending, 0),
+                ("Collect merged PRs", TodoStatus::InProgress, 1),
+                ("Write highlights", TodoStatus::Pending, 1),
+                ("Tag release", TodoStatus::Done, 0),
+            ]
+        );
+        assert_eq!(list.progress(), (2, 5));
+        assert_eq!(list.percent(), 40);
+    }
+
+    #[test_case("", 0 ; "empty")]
+    #[test_case("- [x] one\n- [x] two", 100 ; "all done")]
+    #[test_case("- [ ] a\n- [x] b\n- [ ] c", 33 ; "rounds down")]
+    fn test_percent(content: &str, expected: usize) {
+        assert_eq!(TodoList::parse(content).percent(), expected);
+    }
+
+    #[test]
+    fn test_changes_since() {
+        let before = TodoList::parse("- [ ] a\n- [ ] b");
+        let after = TodoList::parse("- [x] a\n- [ ] b");
+        let changed: Vec<_> = after
+            .changes_since(&before)
+            .unwrap()
+            .iter()
+            .map(|item| item.text.as_str())
+            .collect();
+        assert_eq!(changed, vec!["a"]);
+
+        let reworded = TodoList::parse("- [x] a\n- [ ] c");
+        assert_eq!(reworded.changes_since(&after), None);
+    }
+}
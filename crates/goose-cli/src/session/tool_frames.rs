@@ -0,0 +1,117 @@
+/// Groups the output of tool calls the agent runs at the same time. Calls are collected as
+/// their requests are shown; once two or more of them are running, whatever they print is held
+/// back and shown with each call's result, in its own labeled block, instead of interleaved.
+#[derive(Debug, Default)]
+pub struct ToolFrames {
+    calls: Vec<FramedCall>,
+    /// Set once a result has come in, so the next request starts a new batch
+    running: bool,
+}
+
+#[derive(Debug)]
+struct FramedCall {
+    id: String,
+    name: String,
+    logs: Vec<String>,
+    done: bool,
+}
+
+/// A finished call's block: its place in the batch and the output held back while it ran.
+#[derive(Debug, PartialEq)]
+pub struct Frame {
+    pub index: usize,
+    pub total: usize,
+    pub name: String,
+    pub logs: Vec<String>,
+}
+
+impl ToolFrames {
+    pub fn add_request(&mut self, id: &str, name: &str) {
+        if self.running {
+            self.calls.clear();
+            self.running = false;
+        }
+        self.calls.push(FramedCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            logs: Vec::new(),
+            done: false,
+        });
+    }
+
+    fn framed(&mut self, id: &str) -> Option<&mut FramedCall> {
+        if self.calls.len() < 2 {
+            return None;
+        }
+        self.calls
+            .iter_mut()
+            .find(|call| call.id == id && !call.done)
+    }
+
+    /// Holds back a line printed by a running call. Returns false when the call isn't part of
+    /// a concurrent batch and the line should be printed as usual.
+    pub fn hold_log(&mut self, id: &str, line: &str) -> bool {
+        match self.framed(id) {
+            Some(call) => {
+                call.logs.push(line.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The block for a call whose result just came in, if it ran alongside others.
+    pub fn finish(&mut self, id: &str) -> Option<Frame> {
+        self.running = true;
+        let total = self.calls.len();
+        let index = self.calls.iter().position(|call| call.id == id)?;
+        let call = self.framed(id)?;
+        call.done = true;
+        Some(Frame {
+            index: index + 1,
+            total,
+            name: call.name.clone(),
+            logs: std::mem::take(&mut call.logs),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_call_is_not_framed() {
+        let mut frames = ToolFrames::default();
+        frames.add_request("a", "developer__shell");
+        assert!(!frames.hold_log("a", "output"));
+        assert_eq!(frames.finish("a"), None);
+    }
+
+    #[test]
+    fn test_concurrent_calls_hold_their_output() {
+        let mut frames = ToolFrames::default();
+        frames.add_request("a", "developer__shell");
+        frames.add_request("b", "fetch__get");
+        assert!(frames.hold_log("b", "b1"));
+        assert!(frames.hold_log("a", "a1"));
+        assert!(frames.hold_log("b", "b2"));
+        assert!(!frames.hold_log("other", "x"));
+
+        assert_eq!(
+            frames.finish("a"),
+            Some(Frame {
+                index: 1,
+                total: 2,
+                name: "developer__shell".to_string(),
+                logs: vec!["a1".to_string()],
+            })
+        );
+        assert_eq!(frames.finish("b").unwrap().logs, vec!["b1", "b2"]);
+        assert_eq!(frames.finish("b"), None);
+
+        // the next turn starts over
+        frames.add_request("c", "developer__shell");
+        assert_eq!(frames.finish("c"), None);
+    }
+}
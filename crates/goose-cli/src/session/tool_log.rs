@@ -0,0 +1,87 @@
+use goose::conversation::message::{ToolRequest, ToolResponse};
+use std::collections::HashMap;
+
+/// A tool call seen in this session and, once it arrives, its response.
+pub struct ToolCallEntry {
+    pub request: ToolRequest,
+    pub response: Option<ToolResponse>,
+}
+
+/// Numbers tool calls in the order they are rendered, starting at 1, so a response can be
+/// matched to its request on screen and a call can be looked up again with `/tool <n>`.
+#[derive(Default)]
+pub struct ToolCallLog {
+    entries: Vec<ToolCallEntry>,
+    by_id: HashMap<String, usize>,
+}
+
+impl ToolCallLog {
+    /// Returns the call's number. A request seen again (e.g. re-rendered history) keeps its number.
+    pub fn record_request(&mut self, request: &ToolRequest) -> usize {
+        if let Some(&index) = self.by_id.get(&request.id) {
+            return index + 1;
+        }
+        self.entries.push(ToolCallEntry {
+            request: request.clone(),
+            response: None,
+        });
+        self.by_id
+            .insert(request.id.clone(), self.entries.len() - 1);
+        self.entries.len()
+    }
+
+    /// Returns the number of the matching request, if it was seen.
+    pub fn record_response(&mut self, response: &ToolResponse) -> Option<usize> {
+        let index = *self.by_id.get(&response.id)?;
+        self.entries[index].response = Some(response.clone());
+        Some(index + 1)
+    }
+
+    pub fn get(&self, number: usize) -> Option<&ToolCallEntry> {
+        self.entries.get(number.checked_sub(1)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{CallToolRequestParams, CallToolResult, Content};
+
+    fn request(id: &str) -> ToolRequest {
+        ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(CallToolRequestParams {
+                meta: None,
+                task: None,
+                name: "developer__shell".into(),
+                arguments: None,
+            }),
+            metadata: None,
+            tool_meta: None,
+        }
+    }
+
+    fn response(id: &str) -> ToolResponse {
+        ToolResponse {
+            id: id.to_string(),
+            tool_result: Ok(CallToolResult::success(vec![Content::text("ok")])),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_numbers_calls_and_matches_responses() {
+        let mut log = ToolCallLog::default();
+        assert_eq!(log.record_request(&request("a")), 1);
+        assert_eq!(log.record_request(&request("b")), 2);
+        assert_eq!(log.record_request(&request("a")), 1);
+
+        assert_eq!(log.record_response(&response("b")), Some(2));
+        assert_eq!(log.record_response(&response("unknown")), None);
+
+        assert!(log.get(1).unwrap().response.is_none());
+        assert!(log.get(2).unwrap().response.is_some());
+        assert!(log.get(0).is_none());
+        assert!(log.get(3).is_none());
+    }
+}
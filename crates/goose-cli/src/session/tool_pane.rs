@@ -0,0 +1,195 @@
+use goose::config::Config;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tempfile::NamedTempFile;
+
+/// Tool output shorter than this stays in the chat.
+const DEFAULT_MIN_LINES: usize = 40;
+
+/// The terminal multiplexer goose is running inside
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Multiplexer {
+    Tmux,
+    Screen,
+}
+
+impl Multiplexer {
+    fn detect() -> Option<Self> {
+        if std::env::var_os("TMUX").is_some() {
+            Some(Self::Tmux)
+        } else if std::env::var_os("STY").is_some() {
+            Some(Self::Screen)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Tmux => "tmux",
+            Self::Screen => "screen",
+        }
+    }
+}
+
+/// Sends long tool output to a log file followed in a second tmux pane or screen region, so
+/// the conversation stays readable while the full logs are still there to scroll through.
+/// Turned on with `GOOSE_CLI_TOOL_PANE`; `GOOSE_CLI_TOOL_PANE_MIN_LINES` sets what counts as
+/// long.
+pub struct ToolPane {
+    multiplexer: Multiplexer,
+    /// Created fresh and readable only by the user, and removed when the pane is dropped
+    log: NamedTempFile,
+    min_lines: usize,
+    /// tmux pane showing the log, once opened
+    pane_id: Option<String>,
+    screen_region_open: bool,
+}
+
+impl ToolPane {
+    pub fn from_config() -> Option<Self> {
+        let config = Config::global();
+        if !config
+            .get_param::<bool>("GOOSE_CLI_TOOL_PANE")
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        Some(Self {
+            multiplexer: Multiplexer::detect()?,
+            log: tempfile::Builder::new()
+                .prefix("goose-tool-output-")
+                .suffix(".log")
+                .tempfile()
+                .ok()?,
+            min_lines: config
+                .get_param::<usize>("GOOSE_CLI_TOOL_PANE_MIN_LINES")
+                .unwrap_or(DEFAULT_MIN_LINES),
+            pane_id: None,
+            screen_region_open: false,
+        })
+    }
+
+    pub fn multiplexer(&self) -> Multiplexer {
+        self.multiplexer
+    }
+
+    pub fn log_path(&self) -> &Path {
+        self.log.path()
+    }
+
+    pub fn wants(&self, text: &str) -> bool {
+        text.lines().count() >= self.min_lines
+    }
+
+    /// Appends the output to the log under a header, opening the pane if it isn't showing.
+    pub fn send(&mut self, label: &str, text: &str) -> std::io::Result<()> {
+        let mut log = self.log.as_file();
+        writeln!(
+            log,
+            "\n\x1b[1m── {} · {} ──\x1b[0m",
+            label,
+            chrono::Local::now().format("%H:%M:%S")
+        )?;
+        writeln!(log, "{}", text.trim_end())?;
+        self.ensure_open()
+    }
+
+    fn ensure_open(&mut self) -> std::io::Result<()> {
+        let follow = format!("tail -n +1 -F {}", shell_quote(self.log.path()));
+        match self.multiplexer {
+            Multiplexer::Tmux => {
+                if self.pane_id.as_deref().is_some_and(tmux_pane_alive) {
+                    return Ok(());
+                }
+                let output = Command::new("tmux")
+                    .args(["split-window", "-h", "-d", "-l", "40%", "-P", "-F"])
+                    .arg("#{pane_id}")
+                    .arg(follow)
+                    .stderr(Stdio::null())
+                    .output()?;
+                if !output.status.success() {
+                    return Err(std::io::Error::other("tmux split-window failed"));
+                }
+                self.pane_id = Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+            }
+            Multiplexer::Screen => {
+                // screen can't tell whether the region was closed, so it is only opened once
+                if self.screen_region_open {
+                    return Ok(());
+                }
+                let status = Command::new("screen")
+                    .args(["-X", "eval", "split -v", "focus right"])
+                    .arg(format!("screen -t goose-tools {}", follow))
+                    .arg("focus left")
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()?;
+                if !status.success() {
+                    return Err(std::io::Error::other("screen split failed"));
+                }
+                self.screen_region_open = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ToolPane {
+    fn drop(&mut self) {
+        if let Some(pane_id) = self.pane_id.as_deref().filter(|id| tmux_pane_alive(id)) {
+            let _ = Command::new("tmux")
+                .args(["kill-pane", "-t", pane_id])
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+fn tmux_pane_alive(pane_id: &str) -> bool {
+    Command::new("tmux")
+        .args(["display-message", "-p", "-t", pane_id, "#{pane_id}"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(
+            shell_quote(Path::new("/tmp/it's here.log")),
+            r"'/tmp/it'\''s here.log'"
+        );
+    }
+
+    #[test]
+    fn test_send_appends_labelled_output() {
+        let mut pane = ToolPane {
+            multiplexer: Multiplexer::Screen,
+            log: NamedTempFile::new().unwrap(),
+            min_lines: 2,
+            pane_id: None,
+            // pretend the region is showing so no screen command runs
+            screen_region_open: true,
+        };
+        assert!(!pane.wants("one line"));
+        assert!(pane.wants("one\ntwo"));
+
+        pane.send("#1 developer__shell", "one\ntwo\n").unwrap();
+        pane.send("#2 developer__shell", "three").unwrap();
+        let log = std::fs::read_to_string(pane.log_path()).unwrap();
+        assert!(log.contains("── #1 developer__shell · "));
+        assert!(log.contains("one\ntwo\n"));
+        assert!(log.ends_with("three\n"));
+    }
+}
@@ -0,0 +1,155 @@
+use goose::agents::tool_timeout;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Lines of output kept per call, the latest ones, to show if it times out
+const MAX_PARTIAL_OUTPUT_LINES: usize = 40;
+/// The countdown shows for the last quarter of a timeout, but for at least this long
+const MIN_COUNTDOWN: Duration = Duration::from_secs(10);
+
+/// A call with a configured timeout that hasn't responded yet
+struct RunningCall {
+    id: String,
+    name: String,
+    started: Instant,
+    timeout: Duration,
+    output: VecDeque<String>,
+}
+
+/// A call that has responded, with the last lines it printed while it ran
+pub struct FinishedCall {
+    pub name: String,
+    pub output: Vec<String>,
+}
+
+/// Tool calls with a timeout set in `GOOSE_TOOL_TIMEOUTS`, tracked while they run to count
+/// down the time they have left and keep what they printed in case they are stopped.
+#[derive(Default)]
+pub struct ToolTimeouts {
+    running: Vec<RunningCall>,
+}
+
+impl ToolTimeouts {
+    pub fn start(&mut self, id: &str, tool_name: &str, now: Instant) {
+        if let Some(timeout) = tool_timeout(tool_name) {
+            self.start_with(id, tool_name, timeout, now);
+        }
+    }
+
+    fn start_with(&mut self, id: &str, tool_name: &str, timeout: Duration, now: Instant) {
+        self.running.retain(|call| call.id != id);
+        self.running.push(RunningCall {
+            id: id.to_string(),
+            name: tool_name.to_string(),
+            started: now,
+            timeout,
+            output: VecDeque::new(),
+        });
+    }
+
+    /// Starts the clock again for a call that waited for approval, which doesn't count.
+    pub fn restart(&mut self, id: &str, now: Instant) {
+        if let Some(call) = self.running.iter_mut().find(|call| call.id == id) {
+            call.started = now;
+        }
+    }
+
+    pub fn record_output(&mut self, id: &str, line: &str) {
+        if let Some(call) = self.running.iter_mut().find(|call| call.id == id) {
+            if call.output.len() == MAX_PARTIAL_OUTPUT_LINES {
+                call.output.pop_front();
+            }
+            call.output.push_back(line.to_string());
+        }
+    }
+
+    pub fn finish(&mut self, id: &str) -> Option<FinishedCall> {
+        let idx = self.running.iter().position(|call| call.id == id)?;
+        let call = self.running.remove(idx);
+        Some(FinishedCall {
+            name: call.name,
+            output: call.output.into(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// `shell times out in 8s` for the call closest to its timeout, once it is close.
+    pub fn countdown(&self, now: Instant) -> Option<String> {
+        let (call, remaining) = self
+            .running
+            .iter()
+            .map(|call| {
+                let elapsed = now.saturating_duration_since(call.started);
+                (call, call.timeout.saturating_sub(elapsed))
+            })
+            .min_by_key(|(_, remaining)| *remaining)?;
+        let countdown = (call.timeout / 4).max(MIN_COUNTDOWN);
+        if remaining > countdown {
+            return None;
+        }
+        let tool = call.name.rsplit("__").next().unwrap_or(&call.name);
+        Some(format!(
+            "{} times out in {}s",
+            tool,
+            remaining.as_secs_f64().ceil() as u64
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0, None ; "just started")]
+    #[test_case(85, None ; "before the last quarter")]
+    #[test_case(91, Some("shell times out in 29s") ; "last quarter")]
+    #[test_case(119, Some("shell times out in 1s") ; "almost out")]
+    #[test_case(130, Some("shell times out in 0s") ; "overdue")]
+    fn test_countdown(elapsed: u64, expected: Option<&str>) {
+        let start = Instant::now();
+        let mut timeouts = ToolTimeouts::default();
+        timeouts.start_with("1", "developer__shell", Duration::from_secs(120), start);
+        assert_eq!(
+            timeouts
+                .countdown(start + Duration::from_secs(elapsed))
+                .as_deref(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_short_timeouts_count_down_the_whole_minimum() {
+        let start = Instant::now();
+        let mut timeouts = ToolTimeouts::default();
+        timeouts.start_with("1", "developer__text_editor", Duration::from_secs(8), start);
+        assert_eq!(
+            timeouts.countdown(start).as_deref(),
+            Some("text_editor times out in 8s")
+        );
+    }
+
+    #[test]
+    fn test_restart_and_partial_output() {
+        let start = Instant::now();
+        let mut timeouts = ToolTimeouts::default();
+        timeouts.start_with("1", "developer__shell", Duration::from_secs(40), start);
+        timeouts.restart("1", start + Duration::from_secs(60));
+        assert_eq!(timeouts.countdown(start + Duration::from_secs(60)), None);
+
+        for n in 0..MAX_PARTIAL_OUTPUT_LINES + 2 {
+            timeouts.record_output("1", &format!("line {}", n));
+        }
+        timeouts.record_output("other", "ignored");
+
+        let finished = timeouts.finish("1").unwrap();
+        assert_eq!(finished.name, "developer__shell");
+        assert_eq!(finished.output.len(), MAX_PARTIAL_OUTPUT_LINES);
+        assert_eq!(finished.output[0], "line 2");
+        assert!(timeouts.is_empty());
+        assert!(timeouts.finish("1").is_none());
+    }
+}
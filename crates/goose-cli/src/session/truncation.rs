@@ -0,0 +1,132 @@
+use goose::config::Config;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// How much of a value to show before cutting it short. Unset fields don't limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct Limit {
+    pub lines: Option<usize>,
+    pub bytes: Option<usize>,
+}
+
+/// The shown part of a value that didn't fit its [`Limit`]
+#[derive(Debug, PartialEq)]
+pub struct Truncated<'a> {
+    pub shown: &'a str,
+    pub hidden_lines: usize,
+    pub hidden_bytes: usize,
+}
+
+impl Limit {
+    /// Cuts `text` down to the limit, or returns `None` when it fits.
+    pub fn apply<'a>(&self, text: &'a str) -> Option<Truncated<'a>> {
+        let mut end = text.len();
+        if let Some(lines) = self.lines {
+            if let Some((idx, _)) = text.match_indices('\n').nth(lines.saturating_sub(1)) {
+                end = idx;
+            }
+        }
+        if let Some(bytes) = self.bytes {
+            end = end.min(bytes);
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+        }
+
+        let hidden = text.get(end..).unwrap_or("");
+        if hidden.trim().is_empty() {
+            return None;
+        }
+        Some(Truncated {
+            shown: text.get(..end).unwrap_or(""),
+            hidden_lines: hidden.trim_start_matches('\n').lines().count(),
+            hidden_bytes: hidden.len(),
+        })
+    }
+}
+
+/// Truncation limits for tool calls, from `GOOSE_CLI_TRUNCATION`:
+///
+/// ```yaml
+/// GOOSE_CLI_TRUNCATION:
+///   default: { lines: 1 }          # any other string parameter
+///   params:
+///     command: { lines: 10 }
+///     file_text: { lines: 20, bytes: 4000 }
+///   output: { lines: 200 }         # tool responses
+/// ```
+///
+/// Parameters without a limit are cut at the terminal width, and tool responses are shown in
+/// full, as before.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TruncationConfig {
+    #[serde(default)]
+    pub default: Option<Limit>,
+    #[serde(default)]
+    pub params: HashMap<String, Limit>,
+    #[serde(default)]
+    pub output: Option<Limit>,
+}
+
+impl TruncationConfig {
+    pub fn from_config() -> Self {
+        Config::global()
+            .get_param("GOOSE_CLI_TRUNCATION")
+            .unwrap_or_default()
+    }
+
+    pub fn for_param(&self, key: &str) -> Option<Limit> {
+        self.params.get(key).copied().or(self.default)
+    }
+}
+
+impl Truncated<'_> {
+    /// e.g. "… 12 more lines (/expand to see all)"
+    pub fn marker(&self) -> String {
+        let hidden = if self.hidden_lines > 1 {
+            format!("{} more lines", self.hidden_lines)
+        } else {
+            format!("{} more bytes", self.hidden_bytes)
+        };
+        format!("… {} (/expand to see all)", hidden)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Some(2), None, "one\ntwo\nthree\nfour", Some(("one\ntwo", 2)) ; "lines")]
+    #[test_case(None, Some(5), "one\ntwo\nthree", Some(("one\nt", 2)) ; "bytes")]
+    #[test_case(Some(1), Some(2), "héllo", Some(("h", 1)) ; "bytes on a char boundary")]
+    #[test_case(Some(2), None, "one\ntwo\n", None ; "trailing newline fits")]
+    #[test_case(None, None, "anything", None ; "no limit")]
+    fn test_apply(
+        lines: Option<usize>,
+        bytes: Option<usize>,
+        text: &str,
+        expected: Option<(&str, usize)>,
+    ) {
+        let cut = Limit { lines, bytes }.apply(text);
+        assert_eq!(cut.as_ref().map(|c| (c.shown, c.hidden_lines)), expected);
+    }
+
+    #[test]
+    fn test_config_falls_back_to_default() {
+        let config: TruncationConfig = serde_json::from_value(serde_json::json!({
+            "default": {"lines": 1},
+            "params": {"command": {"lines": 10, "bytes": 2000}},
+        }))
+        .unwrap();
+        assert_eq!(
+            config.for_param("command"),
+            Some(Limit {
+                lines: Some(10),
+                bytes: Some(2000)
+            })
+        );
+        assert_eq!(config.for_param("path").and_then(|l| l.lines), Some(1));
+        assert_eq!(config.output, None);
+    }
+}
@@ -0,0 +1,775 @@
+use anyhow::Result;
+use console::Color;
+use goose::conversation::message::Message;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers,
+};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{Frame, Terminal};
+use std::io::{IsTerminal, Stdout};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::diff::DiffStat;
+use super::mentions;
+use super::output;
+use super::renderer::Renderer;
+use super::streaming_buffer::MarkdownBuffer;
+use super::term_writer::TermWriter;
+use super::turn_stats::TurnStats;
+
+/// Most rows the input box grows to before it scrolls
+const MAX_INPUT_ROWS: usize = 8;
+
+/// Everything printed in the session so far, as plain lines, and how far it is scrolled back.
+#[derive(Debug, Default)]
+pub struct Pane {
+    lines: Vec<String>,
+    /// Rows scrolled up from the bottom; 0 follows new output
+    offset: usize,
+}
+
+impl Pane {
+    /// Appends output as it was written, continuing the last line until a newline.
+    pub fn push_str(&mut self, text: &str) {
+        let text = console::strip_ansi_codes(text).replace('\r', "");
+        let mut parts = text.split('\n');
+        if let Some(first) = parts.next() {
+            match self.lines.last_mut() {
+                Some(last) => last.push_str(first),
+                None => self.lines.push(first.to_string()),
+            }
+        }
+        self.lines.extend(parts.map(str::to_string));
+    }
+
+    pub fn scroll_up(&mut self, rows: usize) {
+        self.offset = self.offset.saturating_add(rows);
+    }
+
+    pub fn scroll_down(&mut self, rows: usize) {
+        self.offset = self.offset.saturating_sub(rows);
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.offset = 0;
+    }
+
+    /// The rows that fit in `height`, with lines wrapped at `width`. Scrolling stops at the top.
+    pub fn visible(&mut self, width: usize, height: usize) -> Vec<String> {
+        let rows: Vec<String> = self
+            .lines
+            .iter()
+            .flat_map(|line| wrap(line, width.max(1)))
+            .collect();
+        self.offset = self.offset.min(rows.len().saturating_sub(height));
+        let end = rows.len() - self.offset;
+        rows[end.saturating_sub(height)..end].to_vec()
+    }
+}
+
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+/// The text being typed, which may span several lines, and the recall of earlier inputs.
+#[derive(Debug, Default)]
+pub struct InputBox {
+    text: String,
+    /// Byte offset of the cursor in `text`
+    cursor: usize,
+    history: Vec<String>,
+    /// Position in `history` while recalling, `history.len()` when not
+    recalled: usize,
+    /// Set while searching the history with Ctrl-R
+    search: Option<Search>,
+}
+
+/// A reverse search through the history: the text searched for, and what was typed before the
+/// search started, which Esc brings back
+#[derive(Debug, Default)]
+struct Search {
+    query: String,
+    typed: String,
+}
+
+impl InputBox {
+    pub fn with_history(history: Vec<String>) -> Self {
+        Self {
+            recalled: history.len(),
+            history,
+            ..Default::default()
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.text.get(..self.cursor).and_then(|s| s.chars().last()) {
+            self.cursor -= c.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn left(&mut self) {
+        if let Some(c) = self.text.get(..self.cursor).and_then(|s| s.chars().last()) {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn right(&mut self) {
+        if let Some(c) = self.text.get(self.cursor..).and_then(|s| s.chars().next()) {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Shows the previous (`back`) or next input from the history in place of the text.
+    pub fn recall(&mut self, back: bool) {
+        let target = if back {
+            self.recalled.checked_sub(1)
+        } else {
+            Some(self.recalled + 1).filter(|n| *n <= self.history.len())
+        };
+        let Some(target) = target else {
+            return;
+        };
+        self.recalled = target;
+        self.text = self.history.get(target).cloned().unwrap_or_default();
+        self.cursor = self.text.len();
+    }
+
+    /// What is being searched for, while searching the history
+    pub fn search_query(&self) -> Option<&str> {
+        self.search.as_ref().map(|search| search.query.as_str())
+    }
+
+    /// Starts searching the history, or with a search going, finds the next older match.
+    pub fn search_back(&mut self) {
+        match &self.search {
+            Some(search) => {
+                let query = search.query.clone();
+                self.find(&query, self.recalled);
+            }
+            None => {
+                self.search = Some(Search {
+                    query: String::new(),
+                    typed: self.text.clone(),
+                })
+            }
+        }
+    }
+
+    /// Adds to (`Some`) or takes the last character off (`None`) what is searched for, and
+    /// shows the latest input that matches it from where the search is.
+    pub fn edit_search(&mut self, c: Option<char>) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        let from = match c {
+            Some(c) => {
+                search.query.push(c);
+                (self.recalled + 1).min(self.history.len())
+            }
+            None => {
+                search.query.pop();
+                self.history.len()
+            }
+        };
+        let query = search.query.clone();
+        self.find(&query, from);
+    }
+
+    /// Stops searching, leaving the match in the box, or with `restore`, what was typed before
+    pub fn end_search(&mut self, restore: bool) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+        if restore {
+            self.text = search.typed;
+            self.cursor = self.text.len();
+            self.recalled = self.history.len();
+        }
+    }
+
+    /// Shows the latest input before `before` containing `query`, if there is one
+    fn find(&mut self, query: &str, before: usize) {
+        let found = self.history[..before.min(self.history.len())]
+            .iter()
+            .rposition(|input| input.contains(query));
+        if let Some(found) = found {
+            self.recalled = found;
+            self.text = self.history[found].clone();
+            self.cursor = self.text.len();
+        }
+    }
+
+    /// Empties the box, returning what was typed and adding it to the history.
+    pub fn take(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        self.cursor = 0;
+        if !text.trim().is_empty() {
+            self.history.push(text.clone());
+        }
+        self.recalled = self.history.len();
+        text
+    }
+
+    pub fn rows(&self) -> usize {
+        self.text.split('\n').count()
+    }
+
+    /// Row and column of the cursor within the box
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let before = self.text.get(..self.cursor).unwrap_or_default();
+        let row = before.matches('\n').count();
+        let column = before
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .count();
+        (row, column)
+    }
+}
+
+/// What a key press at the input box leads to.
+#[derive(Debug, PartialEq)]
+pub enum KeyOutcome {
+    Continue,
+    Submit(String),
+    Exit,
+}
+
+/// The status bar: model, how full the context is and the cost so far.
+pub fn status_line(
+    provider: &str,
+    model: &str,
+    context_tokens: usize,
+    context_limit: usize,
+    cost: Option<f64>,
+) -> String {
+    let mut parts = vec![format!(" {}/{}", provider, model)];
+    if context_limit > 0 {
+        parts.push(format!(
+            "context {}/{} ({}%)",
+            output::format_tokens(context_tokens),
+            output::format_tokens(context_limit),
+            context_tokens * 100 / context_limit
+        ));
+    }
+    if let Some(cost) = cost {
+        parts.push(format!("${:.4}", cost));
+    }
+    parts.push("/help for commands · Ctrl-D to exit".to_string());
+    parts.join(" · ")
+}
+
+/// The state drawn on screen: output, input and the status bar.
+#[derive(Debug, Default)]
+pub struct Screen {
+    pub pane: Pane,
+    pub input: InputBox,
+    pub status: String,
+    /// Set while the agent is responding
+    pub busy: bool,
+    /// Rows the pane had when last drawn, for paging
+    page: usize,
+}
+
+impl Screen {
+    pub fn handle_key(&mut self, key: KeyEvent) -> KeyOutcome {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let page = self.page.max(2) - 1;
+        if self.input.search_query().is_some() {
+            match key.code {
+                KeyCode::Char('r') if control => self.input.search_back(),
+                KeyCode::Char('g') if control => self.input.end_search(true),
+                KeyCode::Esc => self.input.end_search(true),
+                KeyCode::Char(c) if !control => self.input.edit_search(Some(c)),
+                KeyCode::Backspace => self.input.edit_search(None),
+                // anything else takes the match and goes on as usual, so Enter sends it
+                _ => {
+                    self.input.end_search(false);
+                    return self.handle_key(key);
+                }
+            }
+            return KeyOutcome::Continue;
+        }
+        match key.code {
+            KeyCode::Enter
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) =>
+            {
+                self.input.insert('\n')
+            }
+            KeyCode::Enter if self.input.text().trim().is_empty() => {}
+            KeyCode::Enter => {
+                self.pane.scroll_to_bottom();
+                return KeyOutcome::Submit(self.input.take());
+            }
+            KeyCode::Char('d') if control && self.input.text().is_empty() => {
+                return KeyOutcome::Exit
+            }
+            KeyCode::Char('c') | KeyCode::Char('u') if control => self.input.clear(),
+            KeyCode::Char('r') if control => self.input.search_back(),
+            KeyCode::Char('a') if control => self.input.home(),
+            KeyCode::Char('e') if control => self.input.end(),
+            KeyCode::Char(_) if control => {}
+            KeyCode::Char(c) => self.input.insert(c),
+            KeyCode::Backspace => self.input.backspace(),
+            KeyCode::Delete => self.input.delete(),
+            KeyCode::Left => self.input.left(),
+            KeyCode::Right => self.input.right(),
+            KeyCode::Home => self.input.home(),
+            KeyCode::End => self.input.end(),
+            KeyCode::Up if self.input.rows() == 1 => self.input.recall(true),
+            KeyCode::Down if self.input.rows() == 1 => self.input.recall(false),
+            KeyCode::PageUp => self.pane.scroll_up(page),
+            KeyCode::PageDown => self.pane.scroll_down(page),
+            KeyCode::Esc => self.pane.scroll_to_bottom(),
+            _ => {}
+        }
+        KeyOutcome::Continue
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let input_rows = self.input.rows().clamp(1, MAX_INPUT_ROWS) as u16;
+        let [pane, input, status] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(input_rows + 2),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.page = pane.height as usize;
+        let rows = self.pane.visible(pane.width as usize, pane.height as usize);
+        frame.render_widget(
+            Paragraph::new(rows.into_iter().map(Line::from).collect::<Vec<_>>()),
+            pane,
+        );
+
+        let title = if self.busy {
+            " working… Ctrl-C to interrupt ".to_string()
+        } else if let Some(query) = self.input.search_query() {
+            format!(
+                " reverse-i-search: {} · Ctrl-R for older · Esc to cancel ",
+                query
+            )
+        } else {
+            " Enter to send · Alt-Enter for a new line · Ctrl-R to search · PgUp/PgDn to scroll "
+                .to_string()
+        };
+        let (row, column) = self.input.cursor_position();
+        let scroll = (row + 1).saturating_sub(MAX_INPUT_ROWS) as u16;
+        frame.render_widget(
+            Paragraph::new(self.input.text())
+                .scroll((scroll, 0))
+                .block(Block::bordered().title(title).dim()),
+            input,
+        );
+        if !self.busy {
+            frame.set_cursor_position((
+                input.x + 1 + column as u16,
+                input.y + 1 + row as u16 - scroll,
+            ));
+        }
+
+        frame.render_widget(
+            Paragraph::new(self.status.as_str()).style(Style::new().reversed()),
+            status,
+        );
+    }
+}
+
+struct TuiState {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    screen: Screen,
+    /// Whether the alternate screen is showing; output is only collected while it is not
+    active: bool,
+}
+
+impl TuiState {
+    fn draw(&mut self) {
+        if self.active {
+            let screen = &mut self.screen;
+            let _ = self.terminal.draw(|frame| screen.render(frame));
+        }
+    }
+}
+
+/// The full screen interface for `--tui`: a scrollable pane with everything the session prints,
+/// an input box and a status bar, drawn in the alternate screen so the shell's scrollback is
+/// left as it was. Clones share the screen.
+#[derive(Clone)]
+pub struct Tui {
+    state: Arc<Mutex<TuiState>>,
+}
+
+impl Tui {
+    /// Switches to the alternate screen. `history` is recalled with Up and Down.
+    pub fn enter(history: Vec<String>) -> Result<Self> {
+        let terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+        let tui = Self {
+            state: Arc::new(Mutex::new(TuiState {
+                terminal,
+                screen: Screen {
+                    input: InputBox::with_history(history),
+                    ..Default::default()
+                },
+                active: false,
+            })),
+        };
+        tui.reclaim();
+        Ok(tui)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TuiState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Goes back to the normal screen, e.g. for a prompt that draws its own interface.
+    pub fn release(&self) {
+        let mut state = self.lock();
+        if state.active {
+            state.active = false;
+            let _ = disable_raw_mode();
+            let _ = execute!(
+                std::io::stdout(),
+                DisableBracketedPaste,
+                LeaveAlternateScreen
+            );
+            let _ = state.terminal.show_cursor();
+        }
+    }
+
+    /// Returns to the alternate screen and draws it from scratch.
+    pub fn reclaim(&self) {
+        let mut state = self.lock();
+        if !state.active {
+            let _ = execute!(
+                std::io::stdout(),
+                EnterAlternateScreen,
+                EnableBracketedPaste
+            );
+            let _ = state.terminal.clear();
+            state.active = true;
+            state.draw();
+        }
+    }
+
+    /// Redraws everything, covering up anything else that wrote to the terminal meanwhile.
+    pub fn refresh(&self) {
+        let mut state = self.lock();
+        let _ = state.terminal.clear();
+        state.draw();
+    }
+
+    pub fn set_status(&self, status: String) {
+        let mut state = self.lock();
+        state.screen.status = status;
+        state.draw();
+    }
+
+    pub fn set_busy(&self, busy: bool) {
+        let mut state = self.lock();
+        state.screen.busy = busy;
+        state.draw();
+    }
+
+    /// Waits for the next input, or `None` once the user asks to leave.
+    pub fn read_input(&self) -> Result<Option<String>> {
+        self.refresh();
+        enable_raw_mode()?;
+        let result = loop {
+            let event = match event::read() {
+                Ok(event) => event,
+                Err(e) => break Err(e.into()),
+            };
+            let mut state = self.lock();
+            let outcome = match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press => state.screen.handle_key(key),
+                Event::Paste(text) => {
+                    text.chars().for_each(|c| state.screen.input.insert(c));
+                    KeyOutcome::Continue
+                }
+                _ => KeyOutcome::Continue,
+            };
+            match outcome {
+                KeyOutcome::Continue => state.draw(),
+                KeyOutcome::Submit(text) => {
+                    state.screen.busy = true;
+                    state.draw();
+                    break Ok(Some(text));
+                }
+                KeyOutcome::Exit => break Ok(None),
+            }
+        };
+        // Ctrl-C interrupts a response through the signal, which raw mode would swallow
+        disable_raw_mode()?;
+        result
+    }
+
+    /// Where `output` prints while the interface is showing.
+    pub fn writer(&self) -> TuiWriter {
+        TuiWriter { tui: self.clone() }
+    }
+
+    /// Restores the terminal for good.
+    pub fn leave(&self) {
+        self.release();
+    }
+}
+
+/// Adds everything printed to the pane. Output is formatted as it would be for a pipe, since
+/// the pane shows plain text.
+pub struct TuiWriter {
+    tui: Tui,
+}
+
+impl TermWriter for TuiWriter {
+    fn write_str(&mut self, text: &str) {
+        let mut state = self.tui.lock();
+        state.screen.pane.push_str(text);
+        state.draw();
+    }
+
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    fn on_terminal(&self) -> bool {
+        std::io::stdout().is_terminal()
+    }
+}
+
+/// Renders the session into the [`Tui`] pane through the same path as the terminal renderer,
+/// and keeps the status bar up to date.
+pub struct TuiRenderer {
+    tui: Tui,
+    debug: bool,
+    markdown_buffer: MarkdownBuffer,
+}
+
+impl TuiRenderer {
+    pub fn new(tui: Tui, debug: bool) -> Self {
+        Self {
+            tui,
+            debug,
+            markdown_buffer: MarkdownBuffer::new(),
+        }
+    }
+}
+
+impl Renderer for TuiRenderer {
+    fn render_user_message(&mut self, message: &Message) {
+        // unlike at a prompt, the input box is emptied once sent
+        output::render_text_no_newlines(
+            &format!("\n> {}\n", mentions::typed_text(message)),
+            None,
+            false,
+        );
+        output::render_attachment_chips(message);
+    }
+
+    fn render_message(&mut self, message: &Message) {
+        output::render_message_streaming(message, &mut self.markdown_buffer, self.debug);
+    }
+
+    fn render_text(&mut self, text: &str, color: Option<Color>) {
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_text(text, color, true);
+    }
+
+    fn render_error(&mut self, error: &str) {
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_error(error);
+    }
+
+    fn render_model_change(&mut self, _model: &str, _mode: &str) {}
+
+    fn render_turn_stats(&mut self, stats: &TurnStats) {
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_turn_stats(stats);
+    }
+
+    fn render_diffstat(&mut self, stat: &DiffStat) {
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        output::render_diffstat(stat);
+    }
+
+    fn release_terminal(&mut self) {
+        self.tui.release();
+    }
+
+    fn reclaim_terminal(&mut self) {
+        self.tui.reclaim();
+    }
+
+    fn finish(&mut self, _total_tokens: Option<i32>) {
+        output::flush_markdown_buffer_current_theme(&mut self.markdown_buffer);
+        self.tui.set_busy(false);
+        // spinners from extensions may have drawn over the screen
+        self.tui.refresh();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_pane_continues_lines_and_scrolls() {
+        let mut pane = Pane::default();
+        pane.push_str("one\ntw");
+        pane.push_str("o\n\x1b[1mthree\x1b[0m\nfour");
+        assert_eq!(pane.visible(10, 2), vec!["three", "four"]);
+
+        pane.scroll_up(10);
+        assert_eq!(pane.visible(10, 2), vec!["one", "two"]);
+        pane.scroll_down(1);
+        assert_eq!(pane.visible(10, 2), vec!["two", "three"]);
+        pane.scroll_to_bottom();
+        assert_eq!(pane.visible(3, 3), vec!["thr", "ee", "four"]);
+    }
+
+    #[test]
+    fn test_input_box_editing_and_history() {
+        let mut input = InputBox::with_history(vec!["earlier".to_string()]);
+        "héllo".chars().for_each(|c| input.insert(c));
+        input.left();
+        input.left();
+        input.backspace();
+        assert_eq!(input.text(), "hélo");
+        assert_eq!(input.cursor_position(), (0, 2));
+        input.end();
+        input.insert('\n');
+        input.insert('x');
+        assert_eq!(input.cursor_position(), (1, 1));
+
+        assert_eq!(input.take(), "hélo\nx");
+        input.recall(true);
+        assert_eq!(input.text(), "hélo\nx");
+        input.recall(true);
+        assert_eq!(input.text(), "earlier");
+        input.recall(false);
+        input.recall(false);
+        assert_eq!(input.text(), "");
+    }
+
+    #[test]
+    fn test_reverse_search() {
+        let history = ["cargo test", "git status", "cargo build", "ls"];
+        let mut screen = Screen {
+            input: InputBox::with_history(history.iter().map(|h| h.to_string()).collect()),
+            ..Default::default()
+        };
+        "draft".chars().for_each(|c| screen.input.insert(c));
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+        screen.handle_key(ctrl_r);
+        "cargo".chars().for_each(|c| {
+            screen.handle_key(key(c));
+        });
+        assert_eq!(screen.input.search_query(), Some("cargo"));
+        assert_eq!(screen.input.text(), "cargo build");
+        screen.handle_key(ctrl_r);
+        assert_eq!(screen.input.text(), "cargo test");
+        screen.handle_key(ctrl_r);
+        assert_eq!(screen.input.text(), "cargo test");
+
+        screen.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(screen.input.search_query(), None);
+        assert_eq!(screen.input.text(), "draft");
+
+        screen.handle_key(ctrl_r);
+        screen.handle_key(key('s'));
+        assert_eq!(screen.input.text(), "ls");
+        assert_eq!(
+            screen.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+            KeyOutcome::Submit("ls".to_string())
+        );
+    }
+
+    #[test]
+    fn test_status_line() {
+        assert_eq!(
+            status_line("openai", "gpt-4o", 32_000, 128_000, Some(0.0123)),
+            " openai/gpt-4o · context 32k/128k (25%) · $0.0123 · /help for commands · Ctrl-D to exit"
+        );
+    }
+
+    #[test]
+    fn test_screen_keys() {
+        let mut screen = Screen::default();
+        assert_eq!(
+            screen.handle_key(key(KeyCode::Enter, KeyModifiers::NONE)),
+            KeyOutcome::Continue
+        );
+        screen.handle_key(key(KeyCode::Char('h'), KeyModifiers::NONE));
+        screen.handle_key(key(KeyCode::Enter, KeyModifiers::ALT));
+        screen.handle_key(key(KeyCode::Char('i'), KeyModifiers::NONE));
+        assert_eq!(
+            screen.handle_key(key(KeyCode::Enter, KeyModifiers::NONE)),
+            KeyOutcome::Submit("h\ni".to_string())
+        );
+
+        screen.handle_key(key(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert_eq!(
+            screen.handle_key(key(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            KeyOutcome::Continue
+        );
+        screen.handle_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert_eq!(screen.input.text(), "");
+        assert_eq!(
+            screen.handle_key(key(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            KeyOutcome::Exit
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use goose::conversation::message::{Message, MessageContent};
+use rmcp::model::Role;
+use std::time::{Duration, Instant};
+
+/// Timing of the assistant's output during one turn, from the arrival times of streamed
+/// messages. A turn can involve several model calls with tool runs in between, so generation
+/// time only counts the gaps between consecutive streamed chunks, not the waits before them.
+pub struct TurnTimer {
+    started: Instant,
+    first_token: Option<Duration>,
+    /// When the previous assistant chunk arrived, if nothing else came after it
+    last_chunk: Option<Instant>,
+    generating: Duration,
+}
+
+/// What the stats line after a turn shows
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TurnStats {
+    pub time_to_first_token: Option<Duration>,
+    pub total: Duration,
+    pub generating: Duration,
+    pub output_tokens: u64,
+}
+
+impl TurnTimer {
+    pub fn start(now: Instant) -> Self {
+        Self {
+            started: now,
+            first_token: None,
+            last_chunk: None,
+            generating: Duration::ZERO,
+        }
+    }
+
+    pub fn record(&mut self, message: &Message, now: Instant) {
+        let streamed = message.role == Role::Assistant
+            && message.content.iter().any(|c| {
+                matches!(
+                    c,
+                    MessageContent::Text(_)
+                        | MessageContent::Thinking(_)
+                        | MessageContent::ToolRequest(_)
+                )
+            });
+        if !streamed {
+            self.last_chunk = None;
+            return;
+        }
+        if self.first_token.is_none() {
+            self.first_token = Some(now.saturating_duration_since(self.started));
+        }
+        if let Some(last) = self.last_chunk {
+            self.generating += now.saturating_duration_since(last);
+        }
+        self.last_chunk = Some(now);
+    }
+
+    pub fn finish(&self, now: Instant, output_tokens: u64) -> TurnStats {
+        TurnStats {
+            time_to_first_token: self.first_token,
+            total: now.saturating_duration_since(self.started),
+            generating: self.generating,
+            output_tokens,
+        }
+    }
+}
+
+impl TurnStats {
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        let seconds = self.generating.as_secs_f64();
+        (seconds > 0.0 && self.output_tokens > 0).then(|| self.output_tokens as f64 / seconds)
+    }
+
+    /// e.g. "first token 0.8s · 12.4s total · 41 tok/s · 506 tokens"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ttft) = self.time_to_first_token {
+            parts.push(format!("first token {:.1}s", ttft.as_secs_f64()));
+        }
+        parts.push(format!("{:.1}s total", self.total.as_secs_f64()));
+        if let Some(rate) = self.tokens_per_second() {
+            parts.push(format!("{:.0} tok/s", rate));
+        }
+        parts.push(format!("{} tokens", self.output_tokens));
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::CallToolResult;
+
+    #[test]
+    fn test_generation_time_skips_tool_runs() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut timer = TurnTimer::start(start);
+
+        timer.record(&Message::assistant().with_text("Let me"), at(800));
+        timer.record(&Message::assistant().with_text(" check"), at(1000));
+        timer.record(
+            &Message::user().with_tool_response("1", Ok(CallToolResult::success(vec![]))),
+            at(3000),
+        );
+        timer.record(&Message::assistant().with_text("Done"), at(4000));
+        timer.record(&Message::assistant().with_text("."), at(4800));
+
+        let stats = timer.finish(at(5000), 100);
+        assert_eq!(stats.time_to_first_token, Some(Duration::from_millis(800)));
+        assert_eq!(stats.generating, Duration::from_millis(1000));
+        assert_eq!(stats.tokens_per_second(), Some(100.0));
+        assert_eq!(
+            stats.summary(),
+            "first token 0.8s · 5.0s total · 100 tok/s · 100 tokens"
+        );
+    }
+
+    #[test]
+    fn test_summary_without_output() {
+        let start = Instant::now();
+        let stats = TurnTimer::start(start).finish(start + Duration::from_millis(1500), 0);
+        assert_eq!(stats.tokens_per_second(), None);
+        assert_eq!(stats.summary(), "1.5s total · 0 tokens");
+    }
+}
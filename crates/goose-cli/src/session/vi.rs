@@ -0,0 +1,204 @@
+//! Vi editing of the prompt, turned on with `EDIT_MODE: vi`. rustyline provides the modes,
+//! motions, operators and the unnamed register; this adds the named registers `a` to `z`,
+//! used line by line as `"ayy`, `"add`, `"ap` and `"aP`, which keep their text across prompts.
+
+use rustyline::{Cmd, EditMode, InputMode, Movement};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// Reads the `EDIT_MODE` setting, `None` when it isn't a mode
+pub fn parse_edit_mode(value: &str) -> Option<EditMode> {
+    match value.trim().to_lowercase().as_str() {
+        "emacs" => Some(EditMode::Emacs),
+        "vi" | "vim" => Some(EditMode::Vi),
+        _ => None,
+    }
+}
+
+/// Where a `"` command is up to
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Pending {
+    #[default]
+    None,
+    /// `"` was typed, the register's name comes next
+    Name,
+    /// `"a`, waiting for the command
+    Register(char),
+    /// `"ay` or `"ad`, waiting for the second `y` or `d`
+    Operator(char, char),
+}
+
+#[derive(Debug, Default)]
+struct Registers {
+    pending: Pending,
+    contents: HashMap<char, String>,
+}
+
+static REGISTERS: LazyLock<Mutex<Registers>> = LazyLock::new(Default::default);
+
+/// The keys that make up register commands, to be bound at the prompt
+pub fn register_keys() -> impl Iterator<Item = char> {
+    std::iter::once('"')
+        .chain('a'..='z')
+        .chain(std::iter::once('P'))
+}
+
+/// Handles one of [`register_keys`] typed at the prompt. Anything that isn't part of a register
+/// command is left to rustyline.
+pub fn handle_key(key: char, ctx: &rustyline::EventContext) -> Option<Cmd> {
+    let mut registers = REGISTERS.lock().unwrap_or_else(|e| e.into_inner());
+    if ctx.mode() != EditMode::Vi || ctx.input_mode() != InputMode::Command {
+        registers.pending = Pending::None;
+        return None;
+    }
+    registers.key(key, ctx.line(), ctx.pos())
+}
+
+/// Ends a `"` command on a key that isn't one of [`register_keys`], as those never reach
+/// [`handle_key`]. The key itself is left to rustyline.
+pub fn handle_other_key() -> Option<Cmd> {
+    REGISTERS.lock().unwrap_or_else(|e| e.into_inner()).pending = Pending::None;
+    None
+}
+
+impl Registers {
+    fn key(&mut self, key: char, line: &str, pos: usize) -> Option<Cmd> {
+        let (cmd, pending) = match (self.pending, key) {
+            (Pending::None, '"') => (Some(Cmd::Noop), Pending::Name),
+            (Pending::None, _) => (None, Pending::None),
+            (Pending::Name, name) if name.is_ascii_lowercase() => {
+                (Some(Cmd::Noop), Pending::Register(name))
+            }
+            (Pending::Register(name), 'y' | 'd') => (Some(Cmd::Noop), Pending::Operator(name, key)),
+            (Pending::Operator(name, operator), key) if key == operator => {
+                self.contents
+                    .insert(name, current_line(line, pos).to_string());
+                let cmd = if operator == 'd' {
+                    Cmd::Kill(Movement::WholeLine)
+                } else {
+                    Cmd::Noop
+                };
+                (Some(cmd), Pending::None)
+            }
+            (Pending::Register(name), 'p' | 'P') => {
+                let text = self.contents.get(&name).cloned().unwrap_or_default();
+                (Some(put_line(&text, line, pos, key == 'p')), Pending::None)
+            }
+            // anything else ends the command and is typed as usual
+            _ => (None, Pending::None),
+        };
+        self.pending = pending;
+        cmd
+    }
+}
+
+/// The line of a multi-line buffer that the cursor is on
+fn current_line(line: &str, pos: usize) -> &str {
+    let before = line.get(..pos).unwrap_or(line);
+    let after = line.get(pos..).unwrap_or_default();
+    let start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let end = pos + after.find('\n').unwrap_or(after.len());
+    line.get(start..end).unwrap_or_default()
+}
+
+/// Puts `text` on a line of its own below the cursor's line, or above it for `P`, by replacing
+/// the part of the line between the cursor and where the text goes
+fn put_line(text: &str, line: &str, pos: usize, below: bool) -> Cmd {
+    let current = current_line(line, pos);
+    let start = line
+        .get(..pos)
+        .and_then(|before| before.rfind('\n'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let offset = pos - start;
+    if below {
+        let rest = current.get(offset..).unwrap_or_default();
+        Cmd::Replace(Movement::EndOfLine, Some(format!("{}\n{}", rest, text)))
+    } else {
+        let head = current.get(..offset).unwrap_or_default();
+        Cmd::Replace(
+            Movement::BeginningOfLine,
+            Some(format!("{}\n{}", text, head)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn type_keys(registers: &mut Registers, keys: &str, line: &str, pos: usize) -> Vec<Cmd> {
+        keys.chars()
+            .filter_map(|key| registers.key(key, line, pos))
+            .collect()
+    }
+
+    #[test_case("vi", Some(EditMode::Vi))]
+    #[test_case("Vim", Some(EditMode::Vi))]
+    #[test_case("emacs", Some(EditMode::Emacs))]
+    #[test_case("nano", None)]
+    fn test_parse_edit_mode(value: &str, expected: Option<EditMode>) {
+        assert_eq!(parse_edit_mode(value), expected);
+    }
+
+    #[test]
+    fn test_yank_and_put() {
+        let mut registers = Registers::default();
+        let line = "first\nsecond";
+        assert_eq!(
+            type_keys(&mut registers, "\"ayy", line, 2),
+            vec![Cmd::Noop; 4]
+        );
+        assert_eq!(registers.contents[&'a'], "first");
+        assert_eq!(
+            type_keys(&mut registers, "\"ap", line, 8),
+            vec![
+                Cmd::Noop,
+                Cmd::Noop,
+                Cmd::Replace(Movement::EndOfLine, Some("cond\nfirst".to_string()))
+            ]
+        );
+        assert_eq!(
+            type_keys(&mut registers, "\"aP", line, 8),
+            vec![
+                Cmd::Noop,
+                Cmd::Noop,
+                Cmd::Replace(Movement::BeginningOfLine, Some("first\nse".to_string()))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delete_into_register() {
+        let mut registers = Registers::default();
+        assert_eq!(
+            type_keys(&mut registers, "\"bdd", "one\ntwo", 5).last(),
+            Some(&Cmd::Kill(Movement::WholeLine))
+        );
+        assert_eq!(registers.contents[&'b'], "two");
+    }
+
+    #[test]
+    fn test_other_keys_pass_through() {
+        let mut registers = Registers::default();
+        assert_eq!(registers.key('d', "text", 0), None);
+        assert_eq!(registers.key('"', "text", 0), Some(Cmd::Noop));
+        assert_eq!(registers.key('a', "text", 0), Some(Cmd::Noop));
+        assert_eq!(registers.key('x', "text", 0), None);
+        assert_eq!(registers.pending, Pending::None);
+    }
+
+    #[test]
+    fn test_other_key_ends_the_command() {
+        let mut registers = REGISTERS.lock().unwrap_or_else(|e| e.into_inner());
+        registers.contents.insert('a', "saved".to_string());
+        type_keys(&mut registers, "\"a", "text", 0);
+        drop(registers);
+
+        assert_eq!(handle_other_key(), None);
+        let mut registers = REGISTERS.lock().unwrap_or_else(|e| e.into_inner());
+        assert_eq!(registers.pending, Pending::None);
+        assert_eq!(registers.key('p', "text", 0), None);
+    }
+}
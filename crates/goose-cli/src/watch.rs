@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use console::style;
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Most changed files named in the summary between runs
+const MAX_LISTED_FILES: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+struct FileState {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+/// Files changed since the last check, relative to the watched directory
+#[derive(Debug, Default, PartialEq)]
+pub struct Changes {
+    pub added: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+
+    /// e.g. `2 modified: docs/a.md, src/lib.rs · 1 removed: old.md`
+    pub fn summary(&self) -> String {
+        [
+            ("added", &self.added),
+            ("modified", &self.modified),
+            ("removed", &self.removed),
+        ]
+        .iter()
+        .filter(|(_, paths)| !paths.is_empty())
+        .map(|(label, paths)| {
+            let mut names: Vec<String> = paths
+                .iter()
+                .take(MAX_LISTED_FILES)
+                .map(|path| path.display().to_string())
+                .collect();
+            if paths.len() > MAX_LISTED_FILES {
+                names.push(format!("and {} more", paths.len() - MAX_LISTED_FILES));
+            }
+            format!("{} {}: {}", paths.len(), label, names.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+    }
+}
+
+/// Watches the files under a directory that match globs, for `goose run --watch`. Files are
+/// polled rather than subscribed to, and `.gitignore`d files are left out.
+pub struct Watcher {
+    root: PathBuf,
+    overrides: Override,
+    files: BTreeMap<PathBuf, FileState>,
+}
+
+impl Watcher {
+    pub fn new(root: &Path, patterns: &[String]) -> Result<Self> {
+        let mut builder = OverrideBuilder::new(root);
+        for pattern in patterns {
+            builder
+                .add(pattern)
+                .with_context(|| format!("Invalid --watch glob: {}", pattern))?;
+        }
+        let mut watcher = Self {
+            root: root.to_path_buf(),
+            overrides: builder.build()?,
+            files: BTreeMap::new(),
+        };
+        watcher.files = watcher.scan();
+        Ok(watcher)
+    }
+
+    fn scan(&self) -> BTreeMap<PathBuf, FileState> {
+        WalkBuilder::new(&self.root)
+            .overrides(self.overrides.clone())
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                let path = entry
+                    .path()
+                    .strip_prefix(&self.root)
+                    .unwrap_or(entry.path())
+                    .to_path_buf();
+                Some((
+                    path,
+                    FileState {
+                        modified: meta.modified().ok(),
+                        len: meta.len(),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// What changed since the last call.
+    pub fn poll(&mut self) -> Changes {
+        let files = self.scan();
+        let mut changes = Changes::default();
+        for (path, state) in &files {
+            match self.files.get(path) {
+                None => changes.added.push(path.clone()),
+                Some(previous) if previous != state => changes.modified.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = self
+            .files
+            .keys()
+            .filter(|path| !files.contains_key(*path))
+            .cloned()
+            .collect();
+        self.files = files;
+        changes
+    }
+
+    /// Waits for files to change. Changes arriving in quick succession, like a save that
+    /// touches several files, are returned together.
+    pub async fn wait_for_changes(&mut self) -> Changes {
+        let mut changes = Changes::default();
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let latest = self.poll();
+            if latest.is_empty() && !changes.is_empty() {
+                return changes;
+            }
+            changes.added.extend(latest.added);
+            changes.modified.extend(latest.modified);
+            changes.removed.extend(latest.removed);
+        }
+    }
+}
+
+pub fn render_waiting(patterns: &[String]) {
+    println!(
+        "\n{}",
+        style(format!(
+            "watching {} for changes · Ctrl+C to stop",
+            patterns.join(", ")
+        ))
+        .dim()
+    );
+}
+
+/// Separates the output of one run from the next, with what changed in between.
+pub fn render_separator(run: usize, changes: &Changes) {
+    let width = console::Term::stdout()
+        .size_checked()
+        .map(|(_, w)| w as usize)
+        .unwrap_or(80);
+    let label = format!(
+        " run {} · {} ",
+        run,
+        chrono::Local::now().format("%H:%M:%S")
+    );
+    let rule = "─".repeat(width.saturating_sub(label.chars().count() + 2) / 2);
+    println!(
+        "\n{}{}{}",
+        style(&rule).dim(),
+        style(label).cyan().bold(),
+        style(&rule).dim()
+    );
+    println!("  {}\n", style(changes.summary()).dim());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_reports_matching_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(dir.path().join("docs/a.md"), "a").unwrap();
+        std::fs::write(dir.path().join("docs/b.md"), "b").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "n").unwrap();
+        let mut watcher = Watcher::new(dir.path(), &["docs/*.md".to_string()]).unwrap();
+        assert!(watcher.poll().is_empty());
+
+        std::fs::write(dir.path().join("docs/a.md"), "changed").unwrap();
+        std::fs::remove_file(dir.path().join("docs/b.md")).unwrap();
+        std::fs::write(dir.path().join("docs/c.md"), "c").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not watched").unwrap();
+
+        let changes = watcher.poll();
+        assert_eq!(
+            changes,
+            Changes {
+                added: vec![PathBuf::from("docs/c.md")],
+                modified: vec![PathBuf::from("docs/a.md")],
+                removed: vec![PathBuf::from("docs/b.md")],
+            }
+        );
+        assert_eq!(
+            changes.summary(),
+            "1 added: docs/c.md · 1 modified: docs/a.md · 1 removed: docs/b.md"
+        );
+        assert!(watcher.poll().is_empty());
+    }
+
+    #[test]
+    fn test_summary_lists_a_limited_number_of_files() {
+        let changes = Changes {
+            modified: (0..10)
+                .map(|n| PathBuf::from(format!("{}.md", n)))
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            changes.summary(),
+            "10 modified: 0.md, 1.md, 2.md, 3.md, 4.md, 5.md, 6.md, 7.md, and 2 more"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Long-running shell commands, such as dev servers and watchers, started by the shell tool with
+//! `background` set. They are listed for the whole process rather than per server, so that the
+//! client running the developer extension in-process can list and stop them, and are killed
+//! with the server that started them.
+
+use super::shell::kill_process_group;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+use tempfile::NamedTempFile;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How much of the end of a job's log is read for its tail
+const TAIL_BYTES: u64 = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobStatus {
+    Running,
+    /// Exited by itself, with the exit code unless a signal ended it
+    Exited(Option<i32>),
+    /// Stopped by [`stop`] or [`stop_all`]
+    Stopped,
+}
+
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    pub id: u32,
+    pub command: String,
+    pub pid: Option<u32>,
+    pub started: Instant,
+    pub status: JobStatus,
+    /// Where the job's stdout and stderr go
+    pub log_path: PathBuf,
+}
+
+struct Job {
+    info: JobInfo,
+    owner: u32,
+    cancel: CancellationToken,
+    waiter: Option<JoinHandle<()>>,
+    /// Removed with the job
+    _log: NamedTempFile,
+}
+
+/// A job that is dropped while it still runs, because the server that started it went away,
+/// is killed with its child processes then and there
+impl Drop for Job {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.take() {
+            waiter.abort();
+        }
+        if self.info.status == JobStatus::Running {
+            if let Some(pid) = self.info.pid {
+                kill_process_group_now(pid);
+            }
+        }
+    }
+}
+
+static JOBS: LazyLock<Mutex<Vec<Job>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+static NEXT_OWNER: AtomicU32 = AtomicU32::new(1);
+
+/// The jobs of one developer server, shared by its clones. When the last one is dropped, the
+/// jobs the server started are stopped.
+#[derive(Debug)]
+pub struct JobOwner {
+    id: u32,
+}
+
+impl JobOwner {
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_OWNER.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for JobOwner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for JobOwner {
+    fn drop(&mut self) {
+        let owned: Vec<Job> = {
+            let mut jobs = lock();
+            let (owned, others) = std::mem::take(&mut *jobs)
+                .into_iter()
+                .partition(|job| job.owner == self.id);
+            *jobs = others;
+            owned
+        };
+        // dropped outside the lock, which their waiters may be waiting for
+        drop(owned);
+    }
+}
+
+/// Kills a job's process group at once, for when there is no runtime to wait on it
+fn kill_process_group_now(pid: u32) {
+    #[cfg(unix)]
+    unsafe {
+        libc::killpg(pid as i32, libc::SIGKILL);
+    }
+    #[cfg(windows)]
+    {
+        use crate::subprocess::SubprocessExt;
+        let _ = std::process::Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .set_no_window()
+            .output();
+    }
+}
+
+/// Starts a shell command as a job of `owner` with its output going to a log file, and returns
+/// at once
+pub fn start(
+    mut command: tokio::process::Command,
+    display: &str,
+    owner: &JobOwner,
+) -> std::io::Result<JobInfo> {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    // a new file with a random name, so nothing else can have put a file or link there first
+    let log = tempfile::Builder::new()
+        .prefix(&format!("goose-job-{}-", id))
+        .suffix(".log")
+        .tempfile()?;
+    let log_path = log.path().to_path_buf();
+    command
+        .stdout(log.as_file().try_clone()?)
+        .stderr(log.as_file().try_clone()?);
+
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    let info = JobInfo {
+        id,
+        command: display.to_string(),
+        pid,
+        started: Instant::now(),
+        status: JobStatus::Running,
+        log_path,
+    };
+
+    // held until the job is listed, so the waiter finds it however soon the command exits
+    let mut jobs = lock();
+    let waiter = tokio::spawn(async move {
+        let status = tokio::select! {
+            status = child.wait() => JobStatus::Exited(status.ok().and_then(|s| s.code())),
+            _ = token.cancelled() => {
+                if let Err(e) = kill_process_group(&mut child, pid).await {
+                    tracing::debug!("Failed to kill background job {}: {}", id, e);
+                }
+                JobStatus::Stopped
+            }
+        };
+        if let Some(job) = lock().iter_mut().find(|job| job.info.id == id) {
+            job.info.status = status;
+        }
+    });
+    jobs.push(Job {
+        info: info.clone(),
+        owner: owner.id,
+        cancel,
+        waiter: Some(waiter),
+        _log: log,
+    });
+    Ok(info)
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Vec<Job>> {
+    JOBS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Every job started in this process, oldest first
+pub fn list() -> Vec<JobInfo> {
+    lock().iter().map(|job| job.info.clone()).collect()
+}
+
+/// Stops a running job and its child processes. `None` if there is no job with that id.
+pub async fn stop(id: u32) -> Option<JobInfo> {
+    let waiter = {
+        let mut jobs = lock();
+        let job = jobs.iter_mut().find(|job| job.info.id == id)?;
+        job.cancel.cancel();
+        job.waiter.take()
+    };
+    if let Some(waiter) = waiter {
+        let _ = waiter.await;
+    }
+    lock()
+        .iter()
+        .find(|job| job.info.id == id)
+        .map(|job| job.info.clone())
+}
+
+/// Stops every job and removes their logs, for when the session ends
+pub async fn stop_all() {
+    let jobs: Vec<Job> = std::mem::take(&mut *lock());
+    for mut job in jobs {
+        job.cancel.cancel();
+        if let Some(waiter) = job.waiter.take() {
+            let _ = waiter.await;
+        }
+        // the waiter killed it, but no longer finds it in the list to say so
+        job.info.status = JobStatus::Stopped;
+    }
+}
+
+/// The last lines of a job's output
+pub fn tail(job: &JobInfo, lines: usize) -> String {
+    let mut text = String::new();
+    if let Ok(mut file) = std::fs::File::open(&job.log_path) {
+        let length = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let _ = file.seek(SeekFrom::Start(length.saturating_sub(TAIL_BYTES)));
+        let mut bytes = Vec::new();
+        let _ = file.read_to_end(&mut bytes);
+        text = String::from_utf8_lossy(&bytes).into_owned();
+    }
+    last_lines(&text, lines).to_string()
+}
+
+fn last_lines(text: &str, lines: usize) -> &str {
+    let text = text.trim_end();
+    let start = text
+        .rmatch_indices('\n')
+        .nth(lines.saturating_sub(1))
+        .map(|(index, _)| index + 1)
+        .unwrap_or(0);
+    text.get(start..).unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_jobs_are_killed_with_their_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("ticks");
+        let mut command = tokio::process::Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!(
+                "while true; do echo tick >> '{}'; sleep 0.05; done",
+                marker.display()
+            ))
+            .process_group(0);
+        let owner = JobOwner::new();
+        let job = start(command, "ticking", &owner).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        assert!(marker.exists());
+
+        drop(owner);
+        assert!(!list().iter().any(|listed| listed.id == job.id));
+        assert!(!job.log_path.exists());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let ticks = std::fs::read_to_string(&marker).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        assert_eq!(std::fs::read_to_string(&marker).unwrap(), ticks);
+    }
+
+    #[test]
+    fn test_last_lines() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(last_lines("a\nb\n", 5), "a\nb");
+        assert_eq!(last_lines("", 3), "");
+    }
+}
@@ -1,5 +1,6 @@
 pub mod analyze;
 mod editor_models;
+pub mod jobs;
 mod lang;
 pub mod paths;
 mod shell;
@@ -62,6 +62,7 @@ use crate::developer::{paths::get_shell_path_dirs, shell::ShellConfig};
 
 use super::analyze::{types::AnalyzeParams, CodeAnalyzer};
 use super::editor_models::{create_editor_model, EditorModel};
+use super::jobs;
 use super::shell::{configure_shell_command, expand_path, is_absolute_path, kill_process_group};
 use super::text_editor::{
     text_editor_insert, text_editor_replace, text_editor_undo, text_editor_view, text_editor_write,
@@ -116,6 +117,10 @@ pub struct TextEditorParams {
 pub struct ShellParams {
     /// The command string to execute in the shell
     pub command: String,
+    /// Start the command as a background job and return at once, for commands that keep
+    /// running such as dev servers and watchers
+    #[serde(default)]
+    pub background: bool,
 }
 
 /// Parameters for the image_processor tool
@@ -236,6 +241,8 @@ pub struct DeveloperServer {
     running_processes: Arc<RwLock<HashMap<String, CancellationToken>>>,
     bash_env_file: Option<PathBuf>,
     extend_path_with_shell: bool,
+    /// Stops the background jobs this server started once it and its clones are dropped
+    jobs: Arc<jobs::JobOwner>,
 }
 
 #[tool_handler(router = self.tool_router)]
@@ -403,8 +410,9 @@ impl ServerHandler for DeveloperServer {
         "#};
 
         let unix_specific = indoc! {r#"
-            If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
-            this tool does not run indefinitely.
+            If you need to run a long lived command such as a dev server or a watcher, set `background`
+            to true so that this tool does not run indefinitely. It returns a job id and a log file
+            holding the command's output; the job is stopped when the session ends.
 
             **Important**: Use ripgrep - `rg` - exclusively when you need to locate a file or a code reference,
             other solutions may produce too large output because of hidden files! For example *do not* use `find` or `ls -r`
@@ -612,6 +620,7 @@ impl DeveloperServer {
             running_processes: Arc::new(RwLock::new(HashMap::new())),
             extend_path_with_shell: false,
             bash_env_file: None,
+            jobs: Arc::new(jobs::JobOwner::new()),
         }
     }
 
@@ -880,11 +889,11 @@ impl DeveloperServer {
     /// of if the command succeeded or failed.
     ///
     /// Avoid commands that produce a large amount of output, and consider piping those outputs to files.
-    /// If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
-    /// this tool does not run indefinitely.
+    /// If you need to run a long lived command such as a dev server or a watcher, set `background`
+    /// to true so that this tool does not run indefinitely.
     #[tool(
         name = "shell",
-        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that this tool does not run indefinitely."
+        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. If you need to run a long lived command such as a dev server or a watcher, set background to true so that this tool does not run indefinitely; it returns a job id and a log file holding the command's output."
     )]
     pub async fn shell(
         &self,
@@ -902,6 +911,25 @@ impl DeveloperServer {
         // Validate the shell command
         self.validate_shell_command(command)?;
 
+        if params.background {
+            let shell_command = self
+                .build_shell_command(command, working_dir, session_id)
+                .await;
+            let job = jobs::start(shell_command, command, &self.jobs)
+                .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+            let log = job.log_path.display();
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Started background job {} (pid {}). Its output goes to {}; read it with e.g. \
+                 `tail -n 50 {}`. The job keeps running until it exits or the session ends.",
+                job.id,
+                job.pid
+                    .map(|pid| pid.to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                log,
+                log
+            ))]));
+        }
+
         let cancellation_token = CancellationToken::new();
         // Track the process using the request ID
         {
@@ -994,17 +1022,13 @@ impl DeveloperServer {
         Ok(())
     }
 
-    /// Execute a shell command and return the combined output.
-    ///
-    /// Streams output in real-time to the client using logging notifications.
-    async fn execute_shell_command(
+    /// The shell command with the environment and PATH that tool calls run with
+    async fn build_shell_command(
         &self,
         command: &str,
-        peer: &rmcp::service::Peer<RoleServer>,
-        cancellation_token: CancellationToken,
         working_dir: Option<PathBuf>,
         session_id: Option<String>,
-    ) -> Result<String, ErrorData> {
+    ) -> tokio::process::Command {
         let mut shell_config = ShellConfig::default();
         let shell_name = std::path::Path::new(&shell_config.executable)
             .file_name()
@@ -1038,6 +1062,24 @@ impl DeveloperServer {
             }
         }
 
+        command
+    }
+
+    /// Execute a shell command and return the combined output.
+    ///
+    /// Streams output in real-time to the client using logging notifications.
+    async fn execute_shell_command(
+        &self,
+        command: &str,
+        peer: &rmcp::service::Peer<RoleServer>,
+        cancellation_token: CancellationToken,
+        working_dir: Option<PathBuf>,
+        session_id: Option<String>,
+    ) -> Result<String, ErrorData> {
+        let mut command = self
+            .build_shell_command(command, working_dir, session_id)
+            .await;
+
         let mut child = command
             .spawn()
             .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
@@ -1589,6 +1631,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: "".to_string(),
+                        background: false,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1624,6 +1667,7 @@ mod tests {
             // Test PowerShell command
             let shell_params = Parameters(ShellParams {
                 command: "Get-ChildItem".to_string(),
+                background: false,
             });
 
             let result = server
@@ -2026,6 +2070,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", secret_file_path.to_str().unwrap()),
+                        background: false,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -2048,6 +2093,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", allowed_file_path.to_str().unwrap()),
+                        background: false,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3097,6 +3143,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        background: false,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3246,6 +3293,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        background: false,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3463,6 +3511,7 @@ mod tests {
                     .shell(
                         Parameters(ShellParams {
                             command: "sleep 30".to_string(),
+                            background: false,
                         }),
                         context,
                     )
@@ -3525,6 +3574,55 @@ mod tests {
         });
     }
 
+    #[test]
+    #[serial]
+    #[cfg(unix)] // Unix-specific test using sleep command
+    fn test_shell_background_job() {
+        run_shell_test(|| async {
+            let server = create_test_server();
+            let running_service = serve_directly(server.clone(), create_test_transport(), None);
+            let peer = running_service.peer().clone();
+
+            let result = timeout(
+                Duration::from_secs(5),
+                server.shell(
+                    Parameters(ShellParams {
+                        command: "echo started && sleep 30".to_string(),
+                        background: true,
+                    }),
+                    RequestContext {
+                        ct: Default::default(),
+                        id: NumberOrString::Number(124),
+                        meta: Default::default(),
+                        extensions: Default::default(),
+                        peer: peer.clone(),
+                    },
+                ),
+            )
+            .await
+            .expect("a background job should return at once")
+            .unwrap();
+            let text = result.content[0].as_text().unwrap().text.clone();
+            assert!(text.starts_with("Started background job"), "{}", text);
+
+            let job = jobs::list()
+                .into_iter()
+                .find(|job| job.command == "echo started && sleep 30")
+                .unwrap();
+            assert_eq!(job.status, jobs::JobStatus::Running);
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            assert_eq!(jobs::tail(&job, 5), "started");
+
+            let stopped = jobs::stop(job.id).await.unwrap();
+            assert_eq!(stopped.status, jobs::JobStatus::Stopped);
+            jobs::stop_all().await;
+            assert!(jobs::list().is_empty());
+            assert!(!job.log_path.exists());
+
+            cleanup_test_service(running_service, peer);
+        });
+    }
+
     #[test]
     #[serial]
     #[cfg(unix)] // Unix-specific test using shell commands
@@ -3551,6 +3649,7 @@ mod tests {
                     .shell(
                         Parameters(ShellParams {
                             command: "bash -c 'sleep 60 & wait'".to_string(),
+                            background: false,
                         }),
                         context,
                     )
@@ -3648,6 +3747,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: "echo 'Hello, World!'".to_string(),
+                        background: false,
                     }),
                     context,
                 )
@@ -2,6 +2,7 @@ use crate::configuration;
 use crate::state;
 use anyhow::Result;
 use axum::middleware;
+use goose::agents::sampling::{self, SamplingPolicy};
 use goose_server::auth::check_token;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
@@ -33,6 +34,9 @@ pub async fn run() -> Result<()> {
     let secret_key =
         std::env::var("GOOSE_SERVER__SECRET_KEY").unwrap_or_else(|_| "test".to_string());
 
+    // the desktop shows sampling approvals like any other elicitation
+    sampling::set_policy(SamplingPolicy::Ask);
+
     let app_state = state::AppState::new().await?;
 
     let cors = CorsLayer::new()
@@ -20,10 +20,10 @@ use goose::config::declarative_providers::{
     DeclarativeProviderConfig, LoadedProvider, ProviderEngine,
 };
 use goose::conversation::message::{
-    ActionRequired, ActionRequiredData, FrontendToolRequest, Message, MessageContent,
-    MessageMetadata, ReasoningContent, RedactedThinkingContent, SystemNotificationContent,
-    SystemNotificationType, ThinkingContent, TokenState, ToolConfirmationRequest, ToolRequest,
-    ToolResponse,
+    ActionRequired, ActionRequiredData, Citation, CitationsContent, FrontendToolRequest, Message,
+    MessageContent, MessageMetadata, ReasoningContent, RedactedThinkingContent,
+    SystemNotificationContent, SystemNotificationType, ThinkingContent, TokenState,
+    ToolConfirmationRequest, ToolRequest, ToolResponse,
 };
 
 use crate::routes::recipe_utils::RecipeManifest;
@@ -482,6 +482,8 @@ derive_utoipa!(Icon as IconSchema);
         ThinkingContent,
         RedactedThinkingContent,
         ReasoningContent,
+        CitationsContent,
+        Citation,
         FrontendToolRequest,
         ResourceContentsSchema,
         SystemNotificationType,
@@ -12,7 +12,10 @@ use uuid::Uuid;
 use super::container::Container;
 use super::final_output_tool::FinalOutputTool;
 use super::platform_tools;
-use super::tool_execution::{ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE, DECLINED_RESPONSE};
+use super::tool_execution::{
+    tool_timeout, with_elapsed, with_timeout, ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE,
+    DECLINED_RESPONSE,
+};
 use crate::action_required_manager::ActionRequiredManager;
 use crate::agents::extension::{ExtensionConfig, ExtensionResult, ToolInfo};
 use crate::agents::extension_manager::{
@@ -27,7 +30,8 @@ use crate::agents::types::{FrontendTool, SessionConfig, SharedProvider, ToolResu
 use crate::config::permission::PermissionManager;
 use crate::config::{get_enabled_extensions, Config, GooseMode};
 use crate::context_mgmt::{
-    check_if_compaction_needed, compact_messages, DEFAULT_COMPACTION_THRESHOLD,
+    check_if_compaction_needed, compact_messages, ToolOutputSummarizer,
+    DEFAULT_COMPACTION_THRESHOLD,
 };
 use crate::conversation::message::{
     ActionRequiredData, Message, MessageContent, ProviderMetadata, SystemNotificationType,
@@ -36,6 +40,8 @@ use crate::conversation::message::{
 use crate::conversation::tool_result_serde::call_tool_result;
 use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
 use crate::mcp_utils::ToolResult;
+use crate::network_inspector::NetworkInspector;
+use crate::otel::spans;
 use crate::permission::permission_inspector::PermissionInspector;
 use crate::permission::permission_judge::PermissionCheckResult;
 use crate::permission::PermissionConfirmation;
@@ -45,7 +51,8 @@ use crate::recipe::{Author, Recipe, Response, Settings};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::security::security_inspector::SecurityInspector;
 use crate::session::extension_data::{EnabledExtensionsState, ExtensionState};
-use crate::session::{Session, SessionManager};
+use crate::session::{PinnedState, Session, SessionManager};
+use crate::stale_file_inspector::StaleFileInspector;
 use crate::tool_inspection::ToolInspectionManager;
 use crate::tool_monitor::RepetitionInspector;
 use crate::utils::is_token_cancelled;
@@ -61,6 +68,9 @@ use tracing::{debug, error, info, instrument, warn};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
 const COMPACTION_THINKING_TEXT: &str = "goose is compacting the conversation...";
+/// Sent as the last message of a reply that ran out of turns
+pub const MAX_TURNS_REACHED_MESSAGE: &str =
+    "I've reached the maximum number of actions I can do without user input. Would you like me to continue?";
 
 /// Context needed for the reply function
 pub struct ReplyContext {
@@ -150,6 +160,7 @@ pub struct Agent {
     pub(super) retry_manager: RetryManager,
     pub(super) tool_inspection_manager: ToolInspectionManager,
     container: Mutex<Option<Container>>,
+    tool_output_summarizer: Mutex<Option<Arc<ToolOutputSummarizer>>>,
 }
 
 #[derive(Clone, Debug)]
@@ -248,6 +259,7 @@ impl Agent {
             retry_manager: RetryManager::new(),
             tool_inspection_manager: Self::create_tool_inspection_manager(permission_manager),
             container: Mutex::new(None),
+            tool_output_summarizer: Mutex::new(None),
         }
     }
 
@@ -270,6 +282,12 @@ impl Agent {
         // Add repetition inspector (lower priority - basic repetition checking)
         tool_inspection_manager.add_inspector(Box::new(RepetitionInspector::new(None)));
 
+        // Add stale file inspector (asks before editing files changed since the agent read them)
+        tool_inspection_manager.add_inspector(Box::new(StaleFileInspector::new()));
+
+        // Add network inspector (asks before connecting to hosts not allowed for the project)
+        tool_inspection_manager.add_inspector(Box::new(NetworkInspector::new()));
+
         tool_inspection_manager
     }
 
@@ -460,6 +478,11 @@ impl Agent {
         self.container.lock().await.clone()
     }
 
+    /// Summarize large tool outputs with another model before they reach the conversation
+    pub async fn set_tool_output_summarizer(&self, summarizer: Option<ToolOutputSummarizer>) {
+        *self.tool_output_summarizer.lock().await = summarizer.map(Arc::new);
+    }
+
     /// Check if a tool is a frontend tool
     pub async fn is_frontend_tool(&self, name: &str) -> bool {
         self.frontend_tools.lock().await.contains_key(name)
@@ -548,6 +571,8 @@ impl Agent {
                 None,
             )))
         } else {
+            // a token of its own, so a timeout stops only this call
+            let tool_cancel = cancellation_token.unwrap_or_default().child_token();
             // Clone the result to ensure no references to extension_manager are returned
             let result = self
                 .extension_manager
@@ -555,9 +580,15 @@ impl Agent {
                     &session.id,
                     tool_call.clone(),
                     Some(session.working_dir.as_path()),
-                    cancellation_token.unwrap_or_default(),
+                    tool_cancel.clone(),
                 )
-                .await;
+                .await
+                .map(|result| match tool_timeout(&tool_call.name) {
+                    Some(timeout) => {
+                        with_timeout(result, tool_call.name.to_string(), timeout, tool_cancel)
+                    }
+                    None => result,
+                });
             result.unwrap_or_else(|e| {
                 crate::posthog::emit_error(
                     "tool_execution_failed",
@@ -970,6 +1001,7 @@ impl Agent {
         .await?;
 
         let conversation_to_compact = conversation.clone();
+        let pinned = PinnedState::from_extension_data(&session.extension_data).unwrap_or_default();
 
         Ok(Box::pin(async_stream::try_stream! {
             let final_conversation = if !needs_auto_compact {
@@ -1004,6 +1036,7 @@ impl Agent {
                     self.provider().await?.as_ref(),
                     &session_config.id,
                     &conversation_to_compact,
+                    &pinned,
                     false,
                 )
                 .await
@@ -1078,9 +1111,11 @@ impl Agent {
         }
 
         let working_dir = session.working_dir.clone();
+        let pinned = PinnedState::from_extension_data(&session.extension_data).unwrap_or_default();
         Ok(Box::pin(async_stream::try_stream! {
             let reply_stream_span = tracing::info_span!(target: "goose::agents::agent", "reply_stream");
             let _stream_guard = reply_stream_span.enter();
+            let tool_output_summarizer = self.tool_output_summarizer.lock().await.clone();
             let mut turns_taken = 0u32;
             let max_turns = session_config.max_turns.unwrap_or(DEFAULT_MAX_TURNS);
             let mut compaction_attempts = 0;
@@ -1104,9 +1139,7 @@ impl Agent {
                 turns_taken += 1;
                 if turns_taken > max_turns {
                     yield AgentEvent::Message(
-                        Message::assistant().with_text(
-                            "I've reached the maximum number of actions I can do without user input. Would you like me to continue?"
-                        )
+                        Message::assistant().with_text(MAX_TURNS_REACHED_MESSAGE)
                     );
                     break;
                 }
@@ -1238,6 +1271,7 @@ impl Agent {
                                             &remaining_requests,
                                             conversation.messages(),
                                             goose_mode,
+                                            &session.working_dir,
                                         )
                                         .await?;
 
@@ -1293,14 +1327,36 @@ impl Agent {
                                         futures_lock.drain(..).collect::<Vec<_>>()
                                     };
 
+                                    let requests_by_id: HashMap<&str, &ToolRequest> = remaining_requests
+                                        .iter()
+                                        .map(|request| (request.id.as_str(), request))
+                                        .collect();
+                                    let tool_names: HashMap<&str, &str> = remaining_requests
+                                        .iter()
+                                        .filter_map(|request| {
+                                            let tool_call = request.tool_call.as_ref().ok()?;
+                                            Some((request.id.as_str(), tool_call.name.as_ref()))
+                                        })
+                                        .collect();
                                     let with_id = tool_futures
                                         .into_iter()
                                         .map(|(request_id, stream)| {
-                                            stream.map(move |item| (request_id.clone(), item))
+                                            // lives as long as the tool's stream, so it ends with the result
+                                            let span = spans::tool_call(
+                                                tool_names.get(request_id.as_str()).copied().unwrap_or_default(),
+                                                &request_id,
+                                            );
+                                            stream.map(move |item| {
+                                                if let ToolStreamItem::Result(result) = &item {
+                                                    spans::record_tool_result(&span, result);
+                                                }
+                                                (request_id.clone(), item)
+                                            })
                                         })
                                         .collect::<Vec<_>>();
 
                                     let mut combined = stream::select_all(with_id);
+                                    let tools_started = std::time::Instant::now();
                                     let mut all_install_successful = true;
 
                                     loop {
@@ -1320,7 +1376,8 @@ impl Agent {
                                                     Some((request_id, item)) => {
                                                         match item {
                                                             ToolStreamItem::Result(output) => {
-                                                                let output = call_tool_result::validate(output);
+                                                                let output = call_tool_result::validate(output)
+                                                                    .map(|result| with_elapsed(result, tools_started.elapsed()));
 
                                                                 if let Ok(ref call_result) = output {
                                                                     if let Some(ref meta) = call_result.meta {
@@ -1339,6 +1396,11 @@ impl Agent {
                                                                     }
                                                                 }
 
+                                                                if let Some(request) = requests_by_id.get(request_id.as_str()) {
+                                                                    let succeeded = matches!(&output, Ok(result) if result.is_error != Some(true));
+                                                                    self.tool_inspection_manager.record_result(request, succeeded);
+                                                                }
+
                                                                 if enable_extension_request_ids.contains(&request_id)
                                                                     && output.is_err()
                                                                 {
@@ -1423,7 +1485,22 @@ impl Agent {
                                         let final_response = tool_response_messages[idx]
                                                                 .lock().await.clone();
                                         yield AgentEvent::Message(final_response.clone());
-                                        messages_to_add.push(final_response);
+
+                                        // the user keeps the full output, the model gets the summary
+                                        let compressed = match (&tool_output_summarizer, &request.tool_call) {
+                                            (Some(summarizer), Ok(tool_call)) => {
+                                                summarizer.compress(&session_config.id, &tool_call.name, &final_response).await
+                                            }
+                                            _ => None,
+                                        };
+                                        match compressed {
+                                            Some((compressed, compression)) => {
+                                                yield AgentEvent::Message(compression.notification());
+                                                messages_to_add.push(final_response.user_only());
+                                                messages_to_add.push(compressed);
+                                            }
+                                            None => messages_to_add.push(final_response),
+                                        }
                                     }
                                 }
 
@@ -1462,6 +1539,7 @@ impl Agent {
                                 self.provider().await?.as_ref(),
                                 &session_config.id,
                                 &conversation,
+                                &pinned,
                                 false,
                             )
                             .await
@@ -1484,6 +1562,10 @@ impl Agent {
                         Err(ref provider_err) => {
                             crate::posthog::emit_error(provider_err.telemetry_type(), &provider_err.to_string());
                             error!("Error: {}", provider_err);
+                            // nothing of the turn has been kept yet, so the caller can send it again
+                            if messages_to_add.is_empty() && provider_err.is_retryable() {
+                                Err(provider_err.clone())?;
+                            }
                             yield AgentEvent::Message(
                                 Message::assistant().with_text(
                                     format!("Ran into this error: {provider_err}.\n\nPlease retry if you think this is a transient or recoverable error.")
@@ -1541,7 +1623,7 @@ impl Agent {
                     let matching: Vec<&mut Message> = updated_messages
                         .iter_mut()
                         .filter(|msg| {
-                            msg.id.is_some() && msg.content.iter().any(|c| match c {
+                            msg.id.is_some() && msg.is_agent_visible() && msg.content.iter().any(|c| match c {
                                 MessageContent::ToolRequest(req) => req.id == tool_id,
                                 MessageContent::ToolResponse(resp) => resp.id == tool_id,
                                 _ => false,
@@ -1995,6 +2077,14 @@ mod tests {
             inspector_names.contains(&"security"),
             "Tool inspection manager should contain security inspector"
         );
+        assert!(
+            inspector_names.contains(&"stale_file"),
+            "Tool inspection manager should contain stale file inspector"
+        );
+        assert!(
+            inspector_names.contains(&"network"),
+            "Tool inspection manager should contain network inspector"
+        );
 
         Ok(())
     }
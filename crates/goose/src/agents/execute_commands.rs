@@ -5,6 +5,7 @@ use anyhow::{anyhow, Result};
 use crate::context_mgmt::compact_messages;
 use crate::conversation::message::{Message, SystemNotificationType};
 use crate::recipe::build_recipe::build_recipe_from_template_with_positional_params;
+use crate::session::{ExtensionState, PinnedState};
 
 use super::Agent;
 
@@ -85,11 +86,13 @@ impl Agent {
         let conversation = session
             .conversation
             .ok_or_else(|| anyhow!("Session has no conversation"))?;
+        let pinned = PinnedState::from_extension_data(&session.extension_data).unwrap_or_default();
 
         let (compacted_conversation, usage) = compact_messages(
             self.provider().await?.as_ref(),
             session_id,
             &conversation,
+            &pinned,
             true, // is_manual_compact
         )
         .await?;
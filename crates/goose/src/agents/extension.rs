@@ -17,24 +17,57 @@ pub use crate::agents::platform_extensions::{
     PlatformExtensionContext, PlatformExtensionDef, PLATFORM_EXTENSIONS,
 };
 
+/// Lines of an extension's stderr kept when its process quits, counted from the end
+pub const STDERR_TAIL_LINES: usize = 40;
+
 #[derive(Error, Debug)]
-#[error("process quit before initialization: stderr = {stderr}")]
+#[error("process quit before initialization{}: stderr = {stderr}", exit_code_suffix(.exit_code))]
 pub struct ProcessExit {
     stderr: String,
+    exit_code: Option<i32>,
     #[source]
     source: ClientInitializeError,
 }
 
+fn exit_code_suffix(exit_code: &Option<i32>) -> String {
+    exit_code
+        .map(|code| format!(" with exit code {}", code))
+        .unwrap_or_default()
+}
+
+/// The last `lines` lines of `text`
+fn tail(text: &str, lines: usize) -> &str {
+    let text = text.trim_end();
+    let start = text
+        .rmatch_indices('\n')
+        .nth(lines.saturating_sub(1))
+        .map_or(0, |(index, _)| index + 1);
+    text.get(start..).unwrap_or(text)
+}
+
 impl ProcessExit {
-    pub fn new<T>(stderr: T, source: ClientInitializeError) -> Self
+    pub fn new<T>(stderr: T, exit_code: Option<i32>, source: ClientInitializeError) -> Self
     where
         T: Into<String>,
     {
+        let stderr = stderr.into();
         ProcessExit {
-            stderr: stderr.into(),
+            stderr: tail(&stderr, STDERR_TAIL_LINES).to_string(),
+            exit_code,
             source,
         }
     }
+
+    /// The end of what the process wrote to stderr, up to [`STDERR_TAIL_LINES`] lines
+    pub fn stderr_tail(&self) -> &str {
+        &self.stderr
+    }
+
+    /// The process's exit code if it had exited by the time initialization failed, with a
+    /// signal reported as 128 plus its number like shells do
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
 }
 
 /// Errors from Extension operation
@@ -825,4 +858,11 @@ available_tools: []
         cfg.set("MY_SECRET", &"secret_value", true).unwrap();
         assert_eq!(config.resolve(&cfg).await.unwrap(), expected);
     }
+
+    #[test_case("a\nb\nc\n", 2, "b\nc" ; "last lines")]
+    #[test_case("a\nb\n", 5, "a\nb" ; "shorter than the tail")]
+    #[test_case("", 5, "" ; "nothing written")]
+    fn test_stderr_tail(stderr: &str, lines: usize, expected: &str) {
+        assert_eq!(super::tail(stderr, lines), expected);
+    }
 }
@@ -4,18 +4,20 @@ use chrono::{DateTime, Utc};
 use futures::stream::{FuturesUnordered, StreamExt};
 use futures::{future, FutureExt};
 use once_cell::sync::Lazy;
-use rmcp::service::{ClientInitializeError, ServiceError};
+use rmcp::service::{ClientInitializeError, RxJsonRpcMessage, ServiceError, TxJsonRpcMessage};
 use rmcp::transport::streamable_http_client::{
     AuthRequiredError, StreamableHttpClientTransportConfig, StreamableHttpError,
 };
 use rmcp::transport::{
     ConfigureCommandExt, DynamicTransportError, StreamableHttpClientTransport, TokioChildProcess,
+    Transport,
 };
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use tempfile::{tempdir, TempDir};
 use tokio::io::AsyncReadExt;
@@ -47,6 +49,7 @@ use rmcp::model::{
     ResourceContents, ServerInfo, Tool,
 };
 use rmcp::transport::auth::AuthClient;
+use rmcp::RoleClient;
 use schemars::_private::NoSerialize;
 use serde_json::Value;
 
@@ -221,6 +224,68 @@ struct ResolvedTool {
     client: McpClientBox,
 }
 
+/// The stdio transport of an extension's process, which notes the process's exit code when
+/// rmcp drops it. That happens before the process is killed and reaped, so when the server quits
+/// during initialization the code is still there to report with its stderr.
+struct ExitCodeTransport {
+    inner: TokioChildProcess,
+    pid: Option<u32>,
+    exit_code: Arc<OnceLock<i32>>,
+}
+
+impl Transport<RoleClient> for ExitCodeTransport {
+    type Error = std::io::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.inner.send(item)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleClient>>> + Send {
+        self.inner.receive()
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
+
+impl Drop for ExitCodeTransport {
+    fn drop(&mut self) {
+        if let Some(code) = self.pid.and_then(exited_with) {
+            let _ = self.exit_code.set(code);
+        }
+    }
+}
+
+/// The exit code of the child process `pid` if it has exited, without reaping it so whoever
+/// owns the child can still wait for it
+#[cfg(unix)]
+fn exited_with(pid: u32) -> Option<i32> {
+    // SAFETY: waitid only writes to the zeroed siginfo_t, and with WNOHANG and WNOWAIT it
+    // neither blocks nor changes the child's state
+    unsafe {
+        let mut info: libc::siginfo_t = std::mem::zeroed();
+        let options = libc::WEXITED | libc::WNOHANG | libc::WNOWAIT;
+        if libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, options) != 0
+            || info.si_pid() == 0
+        {
+            return None;
+        }
+        match info.si_code {
+            libc::CLD_EXITED => Some(info.si_status()),
+            _ => Some(128 + info.si_status()),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn exited_with(_pid: u32) -> Option<i32> {
+    None
+}
+
 async fn child_process_client(
     mut command: Command,
     timeout: &Option<u64>,
@@ -261,6 +326,13 @@ async fn child_process_client(
         Ok::<String, std::io::Error>(String::from_utf8_lossy(&all_stderr).into())
     });
 
+    let exit_code = Arc::new(OnceLock::new());
+    let transport = ExitCodeTransport {
+        pid: transport.id(),
+        inner: transport,
+        exit_code: exit_code.clone(),
+    };
+
     let client_result = McpClient::connect_with_container(
         transport,
         Duration::from_secs(timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT)),
@@ -276,7 +348,9 @@ async fn child_process_client(
         Err(error) => {
             let error_task_out = stderr_task.await?;
             Err::<McpClient, ExtensionError>(match error_task_out {
-                Ok(stderr_content) => ProcessExit::new(stderr_content, error).into(),
+                Ok(stderr_content) => {
+                    ProcessExit::new(stderr_content, exit_code.get().copied(), error).into()
+                }
                 Err(e) => e.into(),
             })
         }
@@ -1,4 +1,5 @@
 use crate::action_required_manager::ActionRequiredManager;
+use crate::agents::sampling::{self, SamplingPolicy, SamplingPreview};
 use crate::agents::types::SharedProvider;
 use crate::session_context::{SESSION_ID_HEADER, WORKING_DIR_HEADER};
 use rmcp::model::{
@@ -221,6 +222,43 @@ impl ClientHandler for GooseClient {
         // Prefer explicit MCP metadata, then the active request scope.
         let session_id = self.resolve_session_id(&context.extensions).await;
 
+        let model_config = provider.get_model_config();
+        match sampling::policy() {
+            SamplingPolicy::Allow => {}
+            SamplingPolicy::Deny => {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_REQUEST,
+                    "Sampling requests are not allowed in this session",
+                    None,
+                ));
+            }
+            SamplingPolicy::Ask => {
+                let preview =
+                    SamplingPreview::new(&params, provider.get_name(), &model_config.model_name);
+                let response = ActionRequiredManager::global()
+                    .request_and_wait(
+                        "An extension wants to use the model".to_string(),
+                        preview.approval_schema(),
+                        Duration::from_secs(300),
+                    )
+                    .await
+                    .map_err(|e| {
+                        ErrorData::new(
+                            ErrorCode::INTERNAL_ERROR,
+                            format!("Sampling approval timed out or failed: {}", e),
+                            None,
+                        )
+                    })?;
+                if !sampling::is_approved(&response) {
+                    return Err(ErrorData::new(
+                        ErrorCode::INVALID_REQUEST,
+                        "The user declined the sampling request",
+                        None,
+                    ));
+                }
+            }
+        }
+
         let provider_ready_messages: Vec<crate::conversation::message::Message> = params
             .messages
             .iter()
@@ -245,7 +283,7 @@ impl ClientHandler for GooseClient {
         let (response, usage) = provider
             .complete_with_model(
                 session_id.as_deref(),
-                &provider.get_model_config(),
+                &model_config,
                 system_prompt,
                 &provider_ready_messages,
                 &[],
@@ -259,6 +297,19 @@ impl ClientHandler for GooseClient {
                 )
             })?;
 
+        let notification = sampling::sampling_result_notification(
+            provider.get_name(),
+            &usage.model,
+            usage.usage.output_tokens,
+        );
+        self.notification_handlers
+            .lock()
+            .await
+            .iter()
+            .for_each(|handler| {
+                let _ = handler.try_send(notification.clone());
+            });
+
         Ok(CreateMessageResult {
             model: usage.model,
             stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
@@ -14,6 +14,7 @@ pub mod platform_tools;
 pub mod prompt_manager;
 mod reply_parts;
 pub mod retry;
+pub mod sampling;
 mod schedule_tool;
 pub mod subagent_execution_tool;
 pub(crate) mod subagent_handler;
@@ -21,12 +22,16 @@ pub(crate) mod subagent_task_config;
 mod tool_execution;
 pub mod types;
 
-pub use agent::{Agent, AgentConfig, AgentEvent, ExtensionLoadResult, GoosePlatform};
+pub use agent::{
+    Agent, AgentConfig, AgentEvent, ExtensionLoadResult, GoosePlatform, MAX_TURNS_REACHED_MESSAGE,
+};
 pub use container::Container;
 pub use execute_commands::COMPACT_TRIGGERS;
-pub use extension::{ExtensionConfig, ExtensionError};
+pub use extension::{ExtensionConfig, ExtensionError, ProcessExit};
 pub use extension_manager::ExtensionManager;
 pub use prompt_manager::PromptManager;
+pub use sampling::{SamplingPolicy, SamplingPreview, SAMPLING_RESULT_TYPE};
 pub use subagent_handler::SUBAGENT_TOOL_REQUEST_TYPE;
 pub use subagent_task_config::TaskConfig;
+pub use tool_execution::{tool_elapsed, tool_timed_out, tool_timeout, TOOL_ELAPSED_MS_META_KEY};
 pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck};
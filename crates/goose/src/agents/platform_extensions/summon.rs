@@ -16,7 +16,7 @@ use crate::providers;
 use crate::recipe::build_recipe::build_recipe_from_template;
 use crate::recipe::local_recipes::load_local_recipe_file;
 use crate::recipe::{Recipe, Settings, RECIPE_FILE_EXTENSIONS};
-use crate::session::extension_data::EnabledExtensionsState;
+use crate::session::extension_data::{EnabledExtensionsState, SubagentRecord, SubagentsState};
 use crate::session::SessionType;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -1128,6 +1128,12 @@ impl SummonClient {
             )
             .await
             .map_err(|e| format!("Failed to create subagent session: {}", e))?;
+        self.record_subagent(
+            session_id,
+            &subagent_session.id,
+            truncate(&Self::get_task_description(&params), 40),
+        )
+        .await;
 
         let (notif_tx, notif_rx) = tokio::sync::mpsc::unbounded_channel::<ServerNotification>();
         Self::spawn_notification_bridge(
@@ -1502,6 +1508,19 @@ impl SummonClient {
         }
     }
 
+    /// Lists the subagent in the delegating session, so its transcript can be found later.
+    async fn record_subagent(&self, session_id: &str, subagent_id: &str, description: String) {
+        let subagent = SubagentRecord {
+            session_id: subagent_id.to_string(),
+            description,
+        };
+        if let Err(e) =
+            SubagentsState::record(&self.context.session_manager, session_id, subagent).await
+        {
+            warn!("Failed to record subagent {}: {}", subagent_id, e);
+        }
+    }
+
     fn get_task_description(params: &DelegateParams) -> String {
         if let Some(source) = &params.source {
             if let Some(instructions) = &params.instructions {
@@ -1564,6 +1583,8 @@ impl SummonClient {
             .create_session(working_dir, description.clone(), SessionType::SubAgent)
             .await
             .map_err(|e| format!("Failed to create subagent session: {}", e))?;
+        self.record_subagent(session_id, &subagent_session.id, description.clone())
+            .await;
 
         let task_id = subagent_session.id.clone();
 
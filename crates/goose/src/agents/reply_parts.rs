@@ -5,14 +5,16 @@ use std::sync::Arc;
 use async_stream::try_stream;
 use futures::stream::StreamExt;
 use serde_json::{json, Value};
-use tracing::debug;
+use tracing::{debug, Instrument};
 
 use super::super::agents::Agent;
 use crate::agents::platform_extensions::code_execution;
 use crate::conversation::message::{Message, MessageContent, ToolRequest};
 use crate::conversation::Conversation;
+use crate::otel::spans;
 use crate::providers::base::{stream_from_single_message, MessageStream, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
+use crate::providers::rate_budget;
 use crate::providers::toolshim::{
     augment_message_with_tool_calls, convert_tool_messages_to_text,
     modify_system_prompt_for_tool_json, OllamaInterpreter,
@@ -226,6 +228,16 @@ impl Agent {
         let tools = tools.to_owned();
         let toolshim_tools = toolshim_tools.to_owned();
         let provider = provider.clone();
+        let span = spans::provider_request(provider.get_name(), &config.model_name);
+
+        // subagents running side by side share the provider's rate limits
+        let budget_key = rate_budget::key(provider.get_name(), &config.model_name);
+        let reservation = rate_budget::acquire(
+            &budget_key,
+            session_id,
+            rate_budget::estimate_tokens(&system_prompt, messages_for_provider.messages()),
+        )
+        .await;
 
         // Capture errors during stream creation and return them as part of the stream
         // so they can be handled by the existing error handling logic in the agent
@@ -238,6 +250,7 @@ impl Agent {
                     messages_for_provider.messages(),
                     &tools,
                 )
+                .instrument(span.clone())
                 .await;
             debug!("WAITING_LLM_STREAM_END");
             result
@@ -250,6 +263,7 @@ impl Agent {
                     messages_for_provider.messages(),
                     &tools,
                 )
+                .instrument(span.clone())
                 .await;
             debug!("WAITING_LLM_END");
 
@@ -263,6 +277,8 @@ impl Agent {
         let mut stream = match stream_result {
             Ok(s) => s,
             Err(e) => {
+                spans::record_error(&span, &e);
+                rate_budget::record_error(&budget_key, &e);
                 let enhanced_error = enhance_model_error(e, &provider).await;
                 // Return a stream that immediately yields the error
                 // This allows the error to be caught by existing error handling in agent.rs
@@ -273,12 +289,25 @@ impl Agent {
         };
 
         Ok(Box::pin(try_stream! {
+            // ends once the usage or an error is in, before the agent acts on the response
+            let mut span = Some(span);
             while let Some(result) = stream.next().await {
-                let (mut message, usage) = result?;
+                let (mut message, usage) = result.inspect_err(|e| {
+                    rate_budget::record_error(&budget_key, e);
+                    if let Some(span) = span.take() {
+                        spans::record_error(&span, e);
+                    }
+                })?;
 
                 // Store the model information in the global store
                 if let Some(usage) = usage.as_ref() {
                     crate::providers::base::set_current_model(&usage.model);
+                    if let Some(output_tokens) = usage.usage.output_tokens {
+                        rate_budget::spend(&reservation, output_tokens.max(0) as u32);
+                    }
+                    if let Some(span) = span.take() {
+                        spans::record_usage(&span, usage);
+                    }
                 }
 
                 // Post-process / structure the response only if tool interpretation is enabled
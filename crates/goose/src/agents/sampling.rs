@@ -0,0 +1,240 @@
+use rmcp::model::{
+    CreateMessageRequestParams, LoggingLevel, LoggingMessageNotification,
+    LoggingMessageNotificationMethod, LoggingMessageNotificationParam, Role,
+    SamplingMessageContent, ServerNotification,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config::Config;
+
+/// Logging notification sent once a sampling request has been answered, with the provider and
+/// model that answered it
+pub const SAMPLING_RESULT_TYPE: &str = "sampling_result";
+
+/// Key of the approval schema that carries the request, so clients can show what an extension
+/// wants the model for instead of a bare yes/no form
+const PREVIEW_KEY: &str = "x-goose-sampling";
+
+/// The field of the approval form that holds the answer
+const APPROVED_FIELD: &str = "approved";
+
+/// A message of a sampling request as shown to the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingPreviewMessage {
+    pub role: String,
+    pub text: String,
+}
+
+/// What an extension asked the model for in a sampling/createMessage request, and who would
+/// answer it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingPreview {
+    pub system_prompt: Option<String>,
+    pub messages: Vec<SamplingPreviewMessage>,
+    /// Models the extension would like, most preferred first
+    pub model_hints: Vec<String>,
+    pub cost_priority: Option<f32>,
+    pub speed_priority: Option<f32>,
+    pub intelligence_priority: Option<f32>,
+    pub max_tokens: u32,
+    pub provider: String,
+    pub model: String,
+}
+
+fn content_text(content: &SamplingMessageContent) -> String {
+    match content {
+        SamplingMessageContent::Text(text) => text.text.clone(),
+        SamplingMessageContent::Image(image) => format!("[image {}]", image.mime_type),
+        SamplingMessageContent::Audio(audio) => format!("[audio {}]", audio.mime_type),
+        SamplingMessageContent::ToolUse(_) => "[tool call]".to_string(),
+        SamplingMessageContent::ToolResult(_) => "[tool result]".to_string(),
+    }
+}
+
+impl SamplingPreview {
+    pub fn new(params: &CreateMessageRequestParams, provider: &str, model: &str) -> Self {
+        let preferences = params.model_preferences.as_ref();
+        Self {
+            system_prompt: params.system_prompt.clone(),
+            messages: params
+                .messages
+                .iter()
+                .map(|message| SamplingPreviewMessage {
+                    role: match message.role {
+                        Role::User => "user".to_string(),
+                        Role::Assistant => "assistant".to_string(),
+                    },
+                    text: message
+                        .content
+                        .clone()
+                        .into_vec()
+                        .iter()
+                        .map(content_text)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                })
+                .collect(),
+            model_hints: preferences
+                .and_then(|p| p.hints.as_ref())
+                .into_iter()
+                .flatten()
+                .filter_map(|hint| hint.name.clone())
+                .collect(),
+            cost_priority: preferences.and_then(|p| p.cost_priority),
+            speed_priority: preferences.and_then(|p| p.speed_priority),
+            intelligence_priority: preferences.and_then(|p| p.intelligence_priority),
+            max_tokens: params.max_tokens,
+            provider: provider.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    /// The elicitation schema that asks to approve the request, with the request itself along
+    /// for clients that can show it
+    pub fn approval_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                APPROVED_FIELD: {
+                    "type": "boolean",
+                    "title": "Let the extension use the model?",
+                    "default": false,
+                },
+            },
+            "required": [APPROVED_FIELD],
+            PREVIEW_KEY: self,
+        })
+    }
+
+    /// The preview of an elicitation schema that asks to approve a sampling request
+    pub fn from_schema(schema: &Value) -> Option<Self> {
+        serde_json::from_value(schema.get(PREVIEW_KEY)?.clone()).ok()
+    }
+}
+
+/// The elicitation answer to an approval request
+pub fn approval_response(approved: bool) -> Value {
+    serde_json::json!({ APPROVED_FIELD: approved })
+}
+
+/// Whether the elicitation answer approved the request
+pub fn is_approved(response: &Value) -> bool {
+    response
+        .get(APPROVED_FIELD)
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// How requests from extensions to use the model are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingPolicy {
+    /// Turned down. The default, so a client that can't ask anyone never lets them through
+    /// unseen.
+    Deny = 0,
+    /// Each request asks the client to approve it with an elicitation
+    Ask = 1,
+    /// Let through without asking
+    Allow = 2,
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(SamplingPolicy::Deny as u8);
+
+/// Sets how this process handles sampling requests. Clients call this for the mode they run
+/// in; ones that never do deny every request.
+pub fn set_policy(policy: SamplingPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// How to handle a sampling request: as set with [`set_policy`], unless
+/// `GOOSE_SAMPLING_APPROVAL` is turned off, which lets every request through
+pub fn policy() -> SamplingPolicy {
+    let approval = Config::global()
+        .get_param::<bool>("GOOSE_SAMPLING_APPROVAL")
+        .unwrap_or(true);
+    if !approval {
+        return SamplingPolicy::Allow;
+    }
+    match POLICY.load(Ordering::Relaxed) {
+        1 => SamplingPolicy::Ask,
+        2 => SamplingPolicy::Allow,
+        _ => SamplingPolicy::Deny,
+    }
+}
+
+pub fn sampling_result_notification(
+    provider: &str,
+    model: &str,
+    output_tokens: Option<i32>,
+) -> ServerNotification {
+    ServerNotification::LoggingMessageNotification(LoggingMessageNotification {
+        method: LoggingMessageNotificationMethod,
+        params: LoggingMessageNotificationParam {
+            level: LoggingLevel::Info,
+            logger: Some("sampling".to_string()),
+            data: serde_json::json!({
+                "type": SAMPLING_RESULT_TYPE,
+                "provider": provider,
+                "model": model,
+                "output_tokens": output_tokens,
+            }),
+        },
+        extensions: Default::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{ModelHint, ModelPreferences, SamplingMessage};
+
+    fn params() -> CreateMessageRequestParams {
+        CreateMessageRequestParams {
+            meta: None,
+            task: None,
+            messages: vec![
+                SamplingMessage::new(Role::User, SamplingMessageContent::text("Summarize this")),
+                SamplingMessage::new(Role::Assistant, SamplingMessageContent::text("Sure")),
+            ],
+            model_preferences: Some(ModelPreferences {
+                hints: Some(vec![ModelHint {
+                    name: Some("claude".to_string()),
+                }]),
+                cost_priority: Some(0.8),
+                speed_priority: None,
+                intelligence_priority: None,
+            }),
+            system_prompt: Some("Be brief".to_string()),
+            include_context: None,
+            temperature: None,
+            max_tokens: 200,
+            stop_sequences: None,
+            metadata: None,
+            tools: None,
+            tool_choice: None,
+        }
+    }
+
+    #[test]
+    fn test_preview_round_trips_through_schema() {
+        let preview = SamplingPreview::new(&params(), "anthropic", "claude-sonnet-4");
+        assert_eq!(preview.model_hints, vec!["claude"]);
+        assert_eq!(preview.messages[0].text, "Summarize this");
+        assert_eq!(preview.messages[1].role, "assistant");
+
+        let schema = preview.approval_schema();
+        assert_eq!(SamplingPreview::from_schema(&schema), Some(preview));
+        assert_eq!(
+            SamplingPreview::from_schema(&serde_json::json!({"type": "object"})),
+            None
+        );
+    }
+
+    #[test]
+    fn test_approval_response() {
+        assert!(is_approved(&approval_response(true)));
+        assert!(!is_approved(&approval_response(false)));
+        assert!(!is_approved(&serde_json::json!({})));
+    }
+}
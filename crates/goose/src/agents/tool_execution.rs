@@ -1,17 +1,20 @@
 use std::collections::HashMap;
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_stream::try_stream;
 use futures::stream::{self, BoxStream};
-use futures::{Stream, StreamExt};
+use futures::{FutureExt, Stream, StreamExt};
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
-use crate::config::permission::PermissionLevel;
+use crate::config::permission::{extension_principal, PermissionLevel};
+use crate::config::Config;
 use crate::mcp_utils::ToolResult;
+use crate::permission::permission_confirmation::{PermissionConfirmation, PrincipalType};
 use crate::permission::Permission;
-use rmcp::model::{Content, ServerNotification};
+use rmcp::model::{CallToolResult, Content, ErrorCode, ErrorData, Meta, ServerNotification};
 
 // ToolCallResult combines the result of a tool call with an optional notification stream that
 // can be used to receive notifications from the tool.
@@ -33,12 +36,110 @@ use super::agent::{tool_stream, ToolStream};
 use crate::agents::Agent;
 use crate::conversation::message::{Message, ToolRequest};
 use crate::session::Session;
-use crate::tool_inspection::get_security_finding_id_from_results;
+use crate::stale_file_inspector::STALE_FILE_RETRY_RESPONSE;
+use crate::tool_inspection::{get_security_finding_id_from_results, is_stale_file_finding};
 
 pub const DECLINED_RESPONSE: &str = "The user has declined to run this tool. \
     DO NOT attempt to call this tool again. \
     If there are no alternative methods to proceed, clearly explain the situation and STOP.";
 
+/// Key in a tool result's `_meta` recording how long the call took, in milliseconds. Tool calls
+/// of one turn run concurrently, so clients can't tell this from when the results arrive.
+pub const TOOL_ELAPSED_MS_META_KEY: &str = "goose_elapsed_ms";
+
+pub(crate) fn with_elapsed(mut result: CallToolResult, elapsed: Duration) -> CallToolResult {
+    let meta = result.meta.get_or_insert_with(Meta::new);
+    meta.0.insert(
+        TOOL_ELAPSED_MS_META_KEY.to_string(),
+        serde_json::Value::from(elapsed.as_millis() as u64),
+    );
+    result
+}
+
+/// How long a tool call took, when the agent recorded it.
+pub fn tool_elapsed(result: &CallToolResult) -> Option<Duration> {
+    result
+        .meta
+        .as_ref()?
+        .0
+        .get(TOOL_ELAPSED_MS_META_KEY)?
+        .as_u64()
+        .map(Duration::from_millis)
+}
+
+/// Config key mapping tool names to how many seconds a call may run before it is stopped, e.g.
+/// `{"shell": 120, "text_editor": 10}`. Names without an extension prefix apply to the tool of
+/// that name in every extension; `developer__shell` would only match the developer's shell.
+pub const TOOL_TIMEOUTS_CONFIG_KEY: &str = "GOOSE_TOOL_TIMEOUTS";
+
+/// Key in the `data` of a timeout error giving the limit that was hit, in seconds.
+pub const TOOL_TIMEOUT_SECS_DATA_KEY: &str = "goose_timeout_secs";
+
+/// How long `tool_name` may run, if a timeout is configured for it.
+pub fn tool_timeout(tool_name: &str) -> Option<Duration> {
+    let timeouts = Config::global()
+        .get_param::<HashMap<String, u64>>(TOOL_TIMEOUTS_CONFIG_KEY)
+        .ok()?;
+    timeout_for(&timeouts, tool_name)
+}
+
+fn timeout_for(timeouts: &HashMap<String, u64>, tool_name: &str) -> Option<Duration> {
+    timeouts
+        .get(tool_name)
+        .or_else(|| {
+            let (_, tool) = tool_name.split_once("__")?;
+            timeouts.get(tool)
+        })
+        .filter(|secs| **secs > 0)
+        .map(|secs| Duration::from_secs(*secs))
+}
+
+/// The limit a tool call hit, when `error` says it was stopped for running too long.
+pub fn tool_timed_out(error: &ErrorData) -> Option<Duration> {
+    error
+        .data
+        .as_ref()?
+        .get(TOOL_TIMEOUT_SECS_DATA_KEY)?
+        .as_u64()
+        .map(Duration::from_secs)
+}
+
+/// Fails the call with a timeout error once it has run for `timeout`, cancelling `cancel` so
+/// the extension can stop the work.
+pub(crate) fn with_timeout(
+    result: ToolCallResult,
+    tool_name: String,
+    timeout: Duration,
+    cancel: CancellationToken,
+) -> ToolCallResult {
+    let call = result.result;
+    ToolCallResult {
+        notification_stream: result.notification_stream,
+        result: Box::new(
+            async move {
+                match tokio::time::timeout(timeout, call).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        cancel.cancel();
+                        Err(ErrorData::new(
+                            ErrorCode::INTERNAL_ERROR,
+                            format!(
+                                "Tool {} timed out after {}s and was stopped",
+                                tool_name,
+                                timeout.as_secs()
+                            ),
+                            Some(serde_json::json!({
+                                TOOL_TIMEOUT_SECS_DATA_KEY: timeout.as_secs()
+                            })),
+                        ))
+                    }
+                }
+            }
+            .boxed(),
+        ),
+    }
+}
+
 pub const CHAT_MODE_TOOL_SKIPPED_RESPONSE: &str = "Let the user know the tool call was skipped in goose chat mode. \
                                         DO NOT apologize for skipping the tool call. DO NOT say sorry. \
                                         Provide an explanation of what the tool call would do, structured as a \
@@ -59,18 +160,13 @@ impl Agent {
         inspection_results: &'a [crate::tool_inspection::InspectionResult],
     ) -> BoxStream<'a, anyhow::Result<Message>> {
         try_stream! {
+        // Ask about every call up front, so clients can present parallel calls as one batch
         for request in tool_requests.iter() {
             if let Ok(tool_call) = request.tool_call.clone() {
-                // Find the corresponding inspection result for this tool request
-                let security_message = inspection_results.iter()
-                    .find(|result| result.tool_request_id == request.id)
-                    .and_then(|result| {
-                        if let crate::tool_inspection::InspectionAction::RequireApproval(Some(message)) = &result.action {
-                            Some(message.clone())
-                        } else {
-                            None
-                        }
-                    });
+                let security_message = crate::tool_inspection::approval_message_from_results(
+                    &request.id,
+                    inspection_results,
+                );
 
                 let confirmation = Message::assistant()
                     .with_action_required(
@@ -81,65 +177,97 @@ impl Agent {
                     )
                     .user_only();
                 yield confirmation;
+            }
+        }
 
-                let mut rx = self.confirmation_rx.lock().await;
-                while let Some((req_id, confirmation)) = rx.recv().await {
-                    if req_id == request.id {
-                        // Log user decision if this was a security alert
-                        if let Some(finding_id) = get_security_finding_id_from_results(&request.id, inspection_results) {
-                            tracing::info!(
-                                monotonic_counter.goose.prompt_injection_user_decisions = 1,
-                                decision = ?confirmation.permission,
-                                finding_id = %finding_id,
-                                tool_request_id = %request.id,
-                                "Prompt injection detection: user decision on command injection finding"
-                            );
+        // Decisions can arrive in any order; keep the ones for later requests until we get there
+        let mut early_decisions: HashMap<String, PermissionConfirmation> = HashMap::new();
+        for request in tool_requests.iter() {
+            if let Ok(tool_call) = request.tool_call.clone() {
+                let mut decision = early_decisions.remove(&request.id);
+                if decision.is_none() {
+                    let mut rx = self.confirmation_rx.lock().await;
+                    while let Some((req_id, confirmation)) = rx.recv().await {
+                        if req_id == request.id {
+                            decision = Some(confirmation);
+                            break;
                         }
+                        early_decisions.insert(req_id, confirmation);
+                    }
+                }
+
+                if let Some(confirmation) = decision {
+                    // Log user decision if this was a security alert
+                    if let Some(finding_id) = get_security_finding_id_from_results(&request.id, inspection_results) {
+                        tracing::info!(
+                            monotonic_counter.goose.prompt_injection_user_decisions = 1,
+                            decision = ?confirmation.permission,
+                            finding_id = %finding_id,
+                            tool_request_id = %request.id,
+                            "Prompt injection detection: user decision on command injection finding"
+                        );
+                    }
+
+                    // "Always" decisions are remembered for the tool, or for every tool of its
+                    // extension when the user chose the extension as the principal
+                    let principal_name = match confirmation.principal_type {
+                        PrincipalType::Extension => tool_call
+                            .name
+                            .split_once("__")
+                            .map(|(extension_name, _)| extension_principal(extension_name))
+                            .unwrap_or_else(|| tool_call.name.to_string()),
+                        PrincipalType::Tool => tool_call.name.to_string(),
+                    };
 
-                        if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
-                            let (req_id, tool_result) = self.dispatch_tool_call(tool_call.clone(), request.id.clone(), cancellation_token.clone(), session).await;
-                            let mut futures = tool_futures.lock().await;
-
-                            futures.push((req_id, match tool_result {
-                                Ok(result) => tool_stream(
-                                    result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
-                                    result.result,
-                                ),
-                                Err(e) => tool_stream(
-                                    Box::new(stream::empty()),
-                                    futures::future::ready(Err(e)),
-                                ),
-                            }));
-
-                            // Update the shared permission manager when user selects "Always Allow"
-                            if confirmation.permission == Permission::AlwaysAllow {
-                                self.tool_inspection_manager
-                                    .update_permission_manager(&tool_call.name, PermissionLevel::AlwaysAllow)
-                                    .await;
-                            }
+                    if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
+                        let (req_id, tool_result) = self.dispatch_tool_call(tool_call.clone(), request.id.clone(), cancellation_token.clone(), session).await;
+                        let mut futures = tool_futures.lock().await;
+
+                        futures.push((req_id, match tool_result {
+                            Ok(result) => tool_stream(
+                                result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
+                                result.result,
+                            ),
+                            Err(e) => tool_stream(
+                                Box::new(stream::empty()),
+                                futures::future::ready(Err(e)),
+                            ),
+                        }));
+
+                        // Update the shared permission manager when user selects "Always Allow"
+                        if confirmation.permission == Permission::AlwaysAllow {
+                            self.tool_inspection_manager
+                                .update_permission_manager(&principal_name, PermissionLevel::AlwaysAllow)
+                                .await;
+                        }
+                    } else {
+                        // User declined - update the specific response message for this request.
+                        // Declining an edit to a file that changed on disk asks for it to be
+                        // read again and redone rather than dropped
+                        let declined_response = if is_stale_file_finding(&request.id, inspection_results) {
+                            STALE_FILE_RETRY_RESPONSE
                         } else {
-                            // User declined - update the specific response message for this request
-                            if let Some(response_msg) = request_to_response_map.get(&request.id) {
-                                let mut response = response_msg.lock().await;
-                                *response = response.clone().with_tool_response_with_metadata(
-                                    request.id.clone(),
-                                    Ok(rmcp::model::CallToolResult {
-                                        content: vec![Content::text(DECLINED_RESPONSE)],
-                                        structured_content: None,
-                                        is_error: Some(true),
-                                        meta: None,
-                                    }),
-                                    request.metadata.as_ref(),
-                                );
-                            }
-
-                            if confirmation.permission == Permission::AlwaysDeny {
-                                self.tool_inspection_manager
-                                    .update_permission_manager(&tool_call.name, PermissionLevel::NeverAllow)
-                                    .await;
-                            }
+                            DECLINED_RESPONSE
+                        };
+                        if let Some(response_msg) = request_to_response_map.get(&request.id) {
+                            let mut response = response_msg.lock().await;
+                            *response = response.clone().with_tool_response_with_metadata(
+                                request.id.clone(),
+                                Ok(rmcp::model::CallToolResult {
+                                    content: vec![Content::text(declined_response)],
+                                    structured_content: None,
+                                    is_error: Some(true),
+                                    meta: None,
+                                }),
+                                request.metadata.as_ref(),
+                            );
+                        }
+
+                        if confirmation.permission == Permission::AlwaysDeny {
+                            self.tool_inspection_manager
+                                .update_permission_manager(&principal_name, PermissionLevel::NeverAllow)
+                                .await;
                         }
-                        break; // Exit the loop once the matching `req_id` is found
                     }
                 }
             }
@@ -175,3 +303,46 @@ impl Agent {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("developer__shell", Some(120) ; "bare tool name")]
+    #[test_case("developer__text_editor", Some(5) ; "prefixed name wins")]
+    #[test_case("other__text_editor", Some(10) ; "bare name in another extension")]
+    #[test_case("developer__analyze", None ; "not configured")]
+    #[test_case("slow__sleep", None ; "zero means no timeout")]
+    fn test_timeout_for(tool_name: &str, expected: Option<u64>) {
+        let timeouts = HashMap::from([
+            ("shell".to_string(), 120),
+            ("text_editor".to_string(), 10),
+            ("developer__text_editor".to_string(), 5),
+            ("sleep".to_string(), 0),
+        ]);
+        assert_eq!(
+            timeout_for(&timeouts, tool_name),
+            expected.map(Duration::from_secs)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout_stops_the_call() {
+        let cancel = CancellationToken::new();
+        let result = with_timeout(
+            ToolCallResult {
+                result: Box::new(futures::future::pending::<ToolResult<CallToolResult>>().boxed()),
+                notification_stream: None,
+            },
+            "developer__shell".to_string(),
+            Duration::from_secs(1),
+            cancel.clone(),
+        );
+
+        let error = result.result.await.unwrap_err();
+        assert!(cancel.is_cancelled());
+        assert_eq!(tool_timed_out(&error), Some(Duration::from_secs(1)));
+        assert!(error.message.contains("timed out after 1s"));
+    }
+}
@@ -823,6 +823,40 @@ impl Config {
         Ok(())
     }
 
+    /// Set a secret in the system keyring only, for secrets that must not end up in a file.
+    ///
+    /// Unlike [`Config::set_secret`], this does not fall back to file storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::FallbackToFileStorage` if secrets are kept in a file because the
+    /// keyring is disabled or unavailable, and a ConfigError if the keyring can't be written.
+    pub fn set_keyring_secret<V>(&self, key: &str, value: &V) -> Result<(), ConfigError>
+    where
+        V: Serialize,
+    {
+        let _guard = self.guard.lock().unwrap();
+
+        let SecretStorage::Keyring { service } = &self.secrets else {
+            return Err(ConfigError::FallbackToFileStorage);
+        };
+        let mut values = self.all_secrets()?;
+        // reading may have found the keyring unavailable and fallen back to the file
+        if env::var("GOOSE_DISABLE_KEYRING").is_ok() {
+            return Err(ConfigError::FallbackToFileStorage);
+        }
+        values.insert(key.to_string(), serde_json::to_value(value)?);
+
+        let json_value = serde_json::to_string(&values)?;
+        Self::get_keyring_entry(service)
+            .and_then(|entry| entry.set_password(&json_value))
+            .map_err(|e| ConfigError::KeyringError(e.to_string()))?;
+
+        self.invalidate_secrets_cache();
+
+        Ok(())
+    }
+
     /// Delete a secret from the system keyring.
     ///
     /// This will remove the specified key from the JSON object in the system keyring.
@@ -899,7 +933,7 @@ impl Config {
         Ok(())
     }
 
-    fn invalidate_secrets_cache(&self) {
+    pub(crate) fn invalidate_secrets_cache(&self) {
         let mut cache = self.secrets_cache.lock().unwrap();
         *cache = None;
     }
@@ -1148,6 +1182,18 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_keyring_secret_is_not_written_to_file_storage() -> Result<(), ConfigError> {
+        let config = new_test_config();
+
+        let result = config.set_keyring_secret("encryption_key", &"secret".to_string());
+        assert!(matches!(result, Err(ConfigError::FallbackToFileStorage)));
+        let stored: Result<String, ConfigError> = config.get_secret("encryption_key");
+        assert!(matches!(stored, Err(ConfigError::NotFound(_))));
+
+        Ok(())
+    }
+
     #[test]
     fn test_multiple_secrets() -> Result<(), ConfigError> {
         let config = new_test_config();
@@ -28,11 +28,19 @@ pub struct PermissionConfig {
     pub never_allow: Vec<String>,  // List of tools that are never allowed
 }
 
+/// Principal name under which a permission for every tool of an extension is stored.
+pub fn extension_principal(extension_name: &str) -> String {
+    format!("{}__*", extension_name)
+}
+
 /// PermissionManager manages permission configurations for various tools.
 #[derive(Debug)]
 pub struct PermissionManager {
     config_path: PathBuf,
     permission_map: RwLock<HashMap<String, PermissionConfig>>,
+    /// Set for the current process only, e.g. from a project profile. Checked before the
+    /// user's own permissions and never written to disk.
+    project_permissions: RwLock<HashMap<String, PermissionLevel>>,
 }
 
 // Constants representing specific permission categories
@@ -54,6 +62,7 @@ impl PermissionManager {
         PermissionManager {
             config_path: permission_path,
             permission_map: RwLock::new(permission_map),
+            project_permissions: RwLock::new(HashMap::new()),
         }
     }
 
@@ -73,9 +82,26 @@ impl PermissionManager {
 
     /// Retrieves the user permission level for a specific tool.
     pub fn get_user_permission(&self, principal_name: &str) -> Option<PermissionLevel> {
+        if let Some(level) = self.project_permissions.read().unwrap().get(principal_name) {
+            return Some(level.clone());
+        }
         self.get_permission(USER_PERMISSION, principal_name)
     }
 
+    /// Replaces the permissions that apply on top of the user's own for this process only.
+    pub fn set_project_permissions(&self, permissions: HashMap<String, PermissionLevel>) {
+        *self.project_permissions.write().unwrap() = permissions;
+    }
+
+    /// Retrieves the user permission level for a tool, falling back to a permission granted
+    /// to the whole extension the tool belongs to.
+    pub fn get_user_permission_for_tool(&self, tool_name: &str) -> Option<PermissionLevel> {
+        self.get_user_permission(tool_name).or_else(|| {
+            let (extension_name, _) = tool_name.split_once("__")?;
+            self.get_user_permission(&extension_principal(extension_name))
+        })
+    }
+
     /// Retrieves the smart approve permission level for a specific tool.
     pub fn get_smart_approve_permission(&self, principal_name: &str) -> Option<PermissionLevel> {
         self.get_permission(SMART_APPROVE_PERMISSION, principal_name)
@@ -293,4 +319,58 @@ mod tests {
             .always_allow
             .contains(&"nonprefix__tool2".to_string()));
     }
+
+    #[test]
+    fn test_extension_permission_applies_to_its_tools() {
+        let (manager, _temp_dir) = create_test_permission_manager();
+        manager.update_user_permission(
+            &extension_principal("developer"),
+            PermissionLevel::AlwaysAllow,
+        );
+        manager.update_user_permission("developer__shell", PermissionLevel::NeverAllow);
+
+        assert_eq!(
+            manager.get_user_permission_for_tool("developer__text_editor"),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+        // a tool-level decision wins over the extension-level one
+        assert_eq!(
+            manager.get_user_permission_for_tool("developer__shell"),
+            Some(PermissionLevel::NeverAllow)
+        );
+        assert_eq!(
+            manager.get_user_permission_for_tool("memory__remember"),
+            None
+        );
+
+        manager.remove_extension("developer");
+        assert_eq!(
+            manager.get_user_permission_for_tool("developer__text_editor"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_project_permissions_take_precedence_in_memory() {
+        let (manager, temp_dir) = create_test_permission_manager();
+        manager.update_user_permission("developer__shell", PermissionLevel::AlwaysAllow);
+        manager.set_project_permissions(HashMap::from([
+            ("developer__shell".to_string(), PermissionLevel::AskBefore),
+            (extension_principal("github"), PermissionLevel::NeverAllow),
+        ]));
+
+        assert_eq!(
+            manager.get_user_permission_for_tool("developer__shell"),
+            Some(PermissionLevel::AskBefore)
+        );
+        assert_eq!(
+            manager.get_user_permission_for_tool("github__create_issue"),
+            Some(PermissionLevel::NeverAllow)
+        );
+        let saved = PermissionManager::new(temp_dir.path().to_path_buf());
+        assert_eq!(
+            saved.get_user_permission("developer__shell"),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+    }
 }
@@ -4,16 +4,23 @@ use crate::conversation::{merge_consecutive_messages, Conversation};
 use crate::prompt_template::render_template;
 use crate::providers::base::{Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
+use crate::session::{Pin, PinnedState, MAX_PINNED_FILE_BYTES};
 use crate::{config::Config, token_counter::create_token_counter};
 use anyhow::Result;
 use indoc::indoc;
 use rmcp::model::Role;
 use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::task::JoinHandle;
 use tracing::info;
 use tracing::log::warn;
 
+mod tool_output;
+pub use tool_output::{ToolOutputCompression, ToolOutputSummarizer, DEFAULT_TOOL_OUTPUT_THRESHOLD};
+
 pub const DEFAULT_COMPACTION_THRESHOLD: f64 = 0.8;
 
 const CONVERSATION_CONTINUATION_TEXT: &str =
@@ -31,6 +38,10 @@ const MANUAL_COMPACT_CONTINUATION_TEXT: &str =
 Do not mention that you read a summary or that conversation summarization occurred.
 Just continue the conversation naturally based on the summarized context.";
 
+/// Opens the message that carries pinned messages and files through compaction
+pub const PINNED_CONTEXT_TEXT: &str =
+    "The user pinned the following so it is kept in full through compaction. Treat it as still current:";
+
 #[derive(Serialize)]
 struct SummarizeContext {
     messages: String,
@@ -46,6 +57,7 @@ struct SummarizeContext {
 /// * `provider` - The provider to use for summarization
 /// * `session_id` - The session to use for summarization
 /// * `conversation` - The current conversation history
+/// * `pinned` - Messages and files the user pinned, carried over word for word
 /// * `manual_compact` - If true, this is a manual compaction (don't preserve user message)
 ///
 /// # Returns
@@ -56,6 +68,7 @@ pub async fn compact_messages(
     provider: &dyn Provider,
     session_id: &str,
     conversation: &Conversation,
+    pinned: &PinnedState,
     manual_compact: bool,
 ) -> Result<(Conversation, ProviderUsage)> {
     info!("Performing message compaction");
@@ -139,6 +152,14 @@ pub async fn compact_messages(
         final_messages.push(updated_msg);
     }
 
+    // The summary is a user message too, so the pins go in it rather than a message of their own
+    let mut summary_message = summary_message;
+    if let Some(pinned_text) = pinned_text(pinned) {
+        summary_message
+            .content
+            .insert(0, MessageContent::text(pinned_text));
+    }
+
     let summary_msg = summary_message.with_metadata(MessageMetadata::agent_only());
 
     let mut continuation_messages = vec![summary_msg];
@@ -171,6 +192,58 @@ pub async fn compact_messages(
     ))
 }
 
+/// The pinned messages and the current contents of pinned files, to go ahead of the summary
+fn pinned_text(pinned: &PinnedState) -> Option<String> {
+    if pinned.is_empty() {
+        return None;
+    }
+    let sections: Vec<String> = pinned
+        .pins
+        .iter()
+        .map(|pin| match pin {
+            Pin::Message { text, .. } => text.clone(),
+            Pin::File { path } => match read_pinned_file(path) {
+                Ok((contents, false)) => {
+                    format!("Contents of {}:\n```\n{}\n```", path.display(), contents)
+                }
+                Ok((contents, true)) => format!(
+                    "Start of {} (the file is longer than the {} bytes kept):\n```\n{}\n```",
+                    path.display(),
+                    MAX_PINNED_FILE_BYTES,
+                    contents
+                ),
+                Err(e) => format!("{} was pinned but could not be read: {}", path.display(), e),
+            },
+        })
+        .collect();
+    Some(format!(
+        "{}\n\n{}",
+        PINNED_CONTEXT_TEXT,
+        sections.join("\n\n---\n\n")
+    ))
+}
+
+/// Up to [`MAX_PINNED_FILE_BYTES`] of a pinned file, and whether there was more
+fn read_pinned_file(path: &Path) -> std::io::Result<(String, bool)> {
+    let mut bytes = Vec::new();
+    File::open(path)?
+        .take(MAX_PINNED_FILE_BYTES + 1)
+        .read_to_end(&mut bytes)?;
+    let truncated = bytes.len() as u64 > MAX_PINNED_FILE_BYTES;
+    bytes.truncate(MAX_PINNED_FILE_BYTES as usize);
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok((text, truncated)),
+        // the cut can land in the middle of a character
+        Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            Ok((String::from_utf8_lossy(&bytes).into_owned(), true))
+        }
+        Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+    }
+}
+
 /// Check if messages exceed the auto-compaction threshold
 pub async fn check_if_compaction_needed(
     provider: &dyn Provider,
@@ -396,7 +469,7 @@ fn format_message_for_compacting(msg: &Message) -> String {
             MessageContent::SystemNotification(notification) => {
                 Some(format!("system_notification: {}", notification.msg))
             }
-            MessageContent::Reasoning(_) => None,
+            MessageContent::Reasoning(_) | MessageContent::Citations(_) => None,
         })
         .collect();
 
@@ -633,17 +706,75 @@ mod tests {
         ];
 
         let conversation = Conversation::new_unvalidated(basic_conversation);
+        let (compacted_conversation, _usage) = compact_messages(
+            &provider,
+            "test-session-id",
+            &conversation,
+            &PinnedState::default(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let agent_conversation = compacted_conversation.agent_visible_messages();
+
+        let _ = Conversation::new(agent_conversation)
+            .expect("compaction should produce a valid conversation");
+    }
+
+    #[tokio::test]
+    async fn test_keeps_pinned_messages() {
+        let response_message = Message::assistant().with_text("<mock summary>");
+        let provider = MockProvider::new(response_message, 1);
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("never touch the vendor directory"),
+            Message::assistant().with_text("understood"),
+        ]);
+        let pinned = PinnedState {
+            pins: vec![Pin::Message {
+                id: None,
+                text: "never touch the vendor directory".to_string(),
+            }],
+        };
+
         let (compacted_conversation, _usage) =
-            compact_messages(&provider, "test-session-id", &conversation, false)
+            compact_messages(&provider, "test-session-id", &conversation, &pinned, true)
                 .await
                 .unwrap();
 
         let agent_conversation = compacted_conversation.agent_visible_messages();
-
+        let summary = &agent_conversation[0];
+        assert_eq!(summary.role, Role::User);
+        assert!(summary.content[0]
+            .as_text()
+            .unwrap()
+            .ends_with("never touch the vendor directory"));
+        assert!(summary.as_concat_text().contains("<mock summary>"));
         let _ = Conversation::new(agent_conversation)
             .expect("compaction should produce a valid conversation");
     }
 
+    #[test]
+    fn test_pinned_file_is_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.txt");
+        // the odd first byte puts the cut in the middle of a two-byte character
+        let contents = format!("a{}", "é".repeat(MAX_PINNED_FILE_BYTES as usize));
+        std::fs::write(&path, &contents).unwrap();
+
+        let (text, truncated) = read_pinned_file(&path).unwrap();
+        assert!(truncated);
+        assert_eq!(text.len() as u64, MAX_PINNED_FILE_BYTES - 1);
+        assert!(contents.starts_with(&text));
+
+        let pinned = PinnedState {
+            pins: vec![Pin::File { path }],
+        };
+        assert!(pinned_text(&pinned)
+            .unwrap()
+            .contains("the file is longer than"));
+    }
+
     #[tokio::test]
     async fn test_progressive_removal_on_context_exceeded() {
         let response_message = Message::assistant().with_text("<mock summary>");
@@ -674,7 +805,14 @@ mod tests {
         }
 
         let conversation = Conversation::new_unvalidated(messages);
-        let result = compact_messages(&provider, "test-session-id", &conversation, false).await;
+        let result = compact_messages(
+            &provider,
+            "test-session-id",
+            &conversation,
+            &PinnedState::default(),
+            false,
+        )
+        .await;
 
         assert!(
             result.is_ok(),
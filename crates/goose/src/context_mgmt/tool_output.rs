@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use indoc::indoc;
+use rmcp::model::{CallToolResult, Content, RawContent, ResourceContents};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::conversation::message::{
+    Message, MessageContent, SystemNotificationContent, SystemNotificationType,
+};
+use crate::providers::base::Provider;
+use crate::token_counter::create_token_counter;
+
+/// Tool output longer than this many tokens is summarized, unless configured otherwise with
+/// `GOOSE_TOOL_SUMMARIZER_THRESHOLD`
+pub const DEFAULT_TOOL_OUTPUT_THRESHOLD: usize = 2000;
+
+/// Marks the notification that reports a compressed tool output
+const COMPRESSION_NOTIFICATION: &str = "tool_output_compressed";
+
+const SUMMARIZER_SYSTEM_PROMPT: &str = indoc! {r#"
+    You condense the output of a tool call for an AI agent that will read your summary
+    instead of the full output.
+
+    Keep everything the agent is likely to act on: errors and warnings with their file names
+    and line numbers, failing test names, paths, identifiers, versions, counts and final
+    results. Drop repetition, progress output and boilerplate. Quote short key lines verbatim.
+    Reply with the summary only.
+"#};
+
+/// Summarizes large tool outputs with a cheaper model before they are added to the
+/// conversation. The user still sees the full output; the main model only sees the summary.
+pub struct ToolOutputSummarizer {
+    provider: Arc<dyn Provider>,
+    threshold: usize,
+}
+
+/// How much a tool's output shrank, reported to the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolOutputCompression {
+    pub tool_name: String,
+    pub original_tokens: usize,
+    pub tokens: usize,
+}
+
+impl ToolOutputCompression {
+    /// e.g. `shell output compressed 14k → 1.2k tokens`
+    pub fn describe(&self) -> String {
+        let tool = self
+            .tool_name
+            .rsplit("__")
+            .next()
+            .unwrap_or(&self.tool_name);
+        format!(
+            "{} output compressed {} → {} tokens",
+            tool,
+            short_count(self.original_tokens),
+            short_count(self.tokens)
+        )
+    }
+
+    /// A notification for the user, which frontends can recognize with
+    /// [`ToolOutputCompression::from_notification`].
+    pub fn notification(&self) -> Message {
+        let mut data = serde_json::to_value(self).unwrap_or_default();
+        if let Some(object) = data.as_object_mut() {
+            object.insert("type".to_string(), COMPRESSION_NOTIFICATION.into());
+        }
+        Message::assistant().with_system_notification_with_data(
+            SystemNotificationType::InlineMessage,
+            self.describe(),
+            data,
+        )
+    }
+
+    pub fn from_notification(notification: &SystemNotificationContent) -> Option<Self> {
+        let data = notification.data.as_ref()?;
+        if data.get("type")?.as_str()? != COMPRESSION_NOTIFICATION {
+            return None;
+        }
+        serde_json::from_value(data.clone()).ok()
+    }
+}
+
+/// `800`, `1.2k`, `14k`
+fn short_count(count: usize) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=9_999 => {
+            let tenths = (count + 50) / 100;
+            if tenths % 10 == 0 {
+                format!("{}k", tenths / 10)
+            } else {
+                format!("{}.{}k", tenths / 10, tenths % 10)
+            }
+        }
+        _ => format!("{}k", (count + 500) / 1_000),
+    }
+}
+
+/// The text of a tool result, or `None` if it has content a summary would lose, like images
+fn output_text(result: &CallToolResult) -> Option<String> {
+    let parts: Option<Vec<&str>> = result
+        .content
+        .iter()
+        .map(|content| match &content.raw {
+            RawContent::Text(text) => Some(text.text.as_str()),
+            RawContent::Resource(resource) => match &resource.resource {
+                ResourceContents::TextResourceContents { text, .. } => Some(text.as_str()),
+                ResourceContents::BlobResourceContents { .. } => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let text = parts?.join("\n");
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// The successful tool result in a tool response message, and where it is in the message
+fn tool_result(message: &Message) -> Option<(usize, &CallToolResult)> {
+    message
+        .content
+        .iter()
+        .enumerate()
+        .find_map(|(index, content)| match content {
+            MessageContent::ToolResponse(response) => {
+                Some((index, response.tool_result.as_ref().ok()?))
+            }
+            _ => None,
+        })
+}
+
+impl ToolOutputSummarizer {
+    pub fn new(provider: Arc<dyn Provider>, threshold: usize) -> Self {
+        Self {
+            provider,
+            threshold,
+        }
+    }
+
+    /// The summarized version of a tool response message, to be added for the agent in place
+    /// of the original, when its output is over the threshold. `None` leaves the response as
+    /// it is, including when summarizing fails.
+    pub async fn compress(
+        &self,
+        session_id: &str,
+        tool_name: &str,
+        response: &Message,
+    ) -> Option<(Message, ToolOutputCompression)> {
+        let (index, result) = tool_result(response)?;
+        let text = output_text(result)?;
+
+        let counter = create_token_counter()
+            .await
+            .inspect_err(|e| warn!("Failed to create token counter: {}", e))
+            .ok()?;
+        let original_tokens = counter.count_tokens(&text);
+        if original_tokens <= self.threshold {
+            return None;
+        }
+
+        let request =
+            Message::user().with_text(format!("Output of the {} tool:\n\n{}", tool_name, text));
+        let summary = match self
+            .provider
+            .complete(session_id, SUMMARIZER_SYSTEM_PROMPT, &[request], &[])
+            .await
+        {
+            Ok((message, _)) => message.as_concat_text(),
+            Err(e) => {
+                warn!("Failed to summarize the output of {}: {}", tool_name, e);
+                return None;
+            }
+        };
+        let tokens = counter.count_tokens(&summary);
+        if summary.trim().is_empty() || tokens >= original_tokens {
+            return None;
+        }
+
+        let mut compressed = response.clone().agent_only().with_generated_id();
+        if let Some(MessageContent::ToolResponse(response)) = compressed.content.get_mut(index) {
+            response.tool_result = Ok(CallToolResult {
+                content: vec![Content::text(format!(
+                    "[Summary of {} tokens of output]\n{}",
+                    original_tokens, summary
+                ))],
+                structured_content: None,
+                is_error: result.is_error,
+                meta: None,
+            });
+        }
+        Some((
+            compressed,
+            ToolOutputCompression {
+                tool_name: tool_name.to_string(),
+                original_tokens,
+                tokens,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(800, "800")]
+    #[test_case(1_234, "1.2k")]
+    #[test_case(2_000, "2k")]
+    #[test_case(14_321, "14k")]
+    fn test_short_count(count: usize, expected: &str) {
+        assert_eq!(short_count(count), expected);
+    }
+
+    #[test]
+    fn test_compression_notification_round_trips() {
+        let compression = ToolOutputCompression {
+            tool_name: "developer__shell".to_string(),
+            original_tokens: 14_000,
+            tokens: 1_200,
+        };
+        assert_eq!(
+            compression.describe(),
+            "shell output compressed 14k → 1.2k tokens"
+        );
+
+        let notification = compression.notification();
+        let Some(MessageContent::SystemNotification(content)) = notification.content.first() else {
+            panic!("expected a system notification");
+        };
+        assert_eq!(
+            ToolOutputCompression::from_notification(content),
+            Some(compression)
+        );
+        assert!(!notification.is_agent_visible());
+    }
+
+    #[test]
+    fn test_output_text_skips_binary_content() {
+        let text = CallToolResult::success(vec![Content::text("a"), Content::text("b")]);
+        assert_eq!(output_text(&text), Some("a\nb".to_string()));
+
+        let image = CallToolResult::success(vec![
+            Content::text("chart"),
+            Content::image("aGk=", "image/png"),
+        ]);
+        assert_eq!(output_text(&image), None);
+    }
+}
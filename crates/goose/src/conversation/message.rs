@@ -180,6 +180,27 @@ pub struct ReasoningContent {
     pub text: String,
 }
 
+/// A source the provider says the response draws on, such as a web search result or a
+/// passage of a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The part of the source the response relies on, when the provider quotes it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cited_text: Option<String>,
+}
+
+/// Citations or grounding returned with a response. They are shown to the user and not sent
+/// back to the provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CitationsContent {
+    pub citations: Vec<Citation>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 /// Content passed inside a message, which can be both simple content and tool content
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -195,6 +216,7 @@ pub enum MessageContent {
     RedactedThinking(RedactedThinkingContent),
     SystemNotification(SystemNotificationContent),
     Reasoning(ReasoningContent),
+    Citations(CitationsContent),
 }
 
 impl fmt::Display for MessageContent {
@@ -237,6 +259,7 @@ impl fmt::Display for MessageContent {
                 write!(f, "[SystemNotification: {}]", r.msg)
             }
             MessageContent::Reasoning(r) => write!(f, "[Reasoning: {}]", r.text),
+            MessageContent::Citations(c) => write!(f, "[Citations: {}]", c.citations.len()),
         }
     }
 }
@@ -454,6 +477,10 @@ impl MessageContent {
         MessageContent::Reasoning(ReasoningContent { text: text.into() })
     }
 
+    pub fn citations(citations: Vec<Citation>) -> Self {
+        MessageContent::Citations(CitationsContent { citations })
+    }
+
     pub fn as_system_notification(&self) -> Option<&SystemNotificationContent> {
         if let MessageContent::SystemNotification(ref notification) = self {
             Some(notification)
@@ -533,6 +560,14 @@ impl MessageContent {
             _ => None,
         }
     }
+
+    /// Get the citations if this is a CitationsContent variant
+    pub fn as_citations(&self) -> Option<&CitationsContent> {
+        match self {
+            MessageContent::Citations(citations) => Some(citations),
+            _ => None,
+        }
+    }
 }
 
 impl From<Content> for MessageContent {
@@ -830,6 +865,15 @@ impl Message {
         self.with_content(MessageContent::redacted_thinking(data))
     }
 
+    /// Add the citations returned with a response, if there are any
+    pub fn with_citations(self, citations: Vec<Citation>) -> Self {
+        if citations.is_empty() {
+            self
+        } else {
+            self.with_content(MessageContent::citations(citations))
+        }
+    }
+
     /// Get the concatenated text content of the message, separated by newlines
     pub fn as_concat_text(&self) -> String {
         self.content
@@ -1037,6 +1081,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_citations_serialization() {
+        let message = Message::assistant()
+            .with_text("Rust 1.0 shipped in 2015.")
+            .with_citations(vec![Citation {
+                title: Some("Announcing Rust 1.0".to_string()),
+                url: Some("https://blog.rust-lang.org/2015/05/15/Rust-1.0.html".to_string()),
+                cited_text: None,
+            }])
+            .with_citations(vec![]);
+        assert_eq!(message.content.len(), 2);
+
+        let value = serde_json::to_value(&message).unwrap();
+        let citations = &value["content"][1];
+        assert_eq!(citations["type"], "citations");
+        assert_eq!(citations["citations"][0]["title"], "Announcing Rust 1.0");
+        assert!(citations["citations"][0].get("citedText").is_none());
+
+        let parsed: Message = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.content, message.content);
+    }
+
     #[test]
     fn test_error_serialization() {
         let message = Message::assistant().with_tool_request(
@@ -11,6 +11,7 @@ pub mod hints;
 pub mod logging;
 pub mod mcp_utils;
 pub mod model;
+pub mod network_inspector;
 pub mod oauth;
 pub mod otel;
 pub mod permission;
@@ -25,6 +26,7 @@ pub mod security;
 pub mod session;
 pub mod session_context;
 pub mod slash_commands;
+pub mod stale_file_inspector;
 pub mod subprocess;
 pub mod token_counter;
 pub mod tool_inspection;
@@ -0,0 +1,357 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use rmcp::model::CallToolRequestParams;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::paths::Paths;
+use crate::config::{Config, GooseMode};
+use crate::conversation::message::{Message, ToolRequest};
+use crate::tool_inspection::{InspectionAction, InspectionResult, ToolInspector};
+use crate::utils::project_root;
+
+const SHELL_TOOL: &str = "developer__shell";
+
+/// Shell commands that download from the hosts in their arguments
+const NETWORK_COMMANDS: [&str; 2] = ["curl", "wget"];
+
+/// Options of curl and wget whose value is a file, header or data rather than somewhere to
+/// connect to
+const VALUE_OPTIONS: [&str; 16] = [
+    "-o",
+    "--output",
+    "-O",
+    "--output-document",
+    "-d",
+    "--data",
+    "-H",
+    "--header",
+    "-F",
+    "--form",
+    "-T",
+    "--upload-file",
+    "-u",
+    "--user",
+    "-P",
+    "--directory-prefix",
+];
+
+/// Where the hosts allowed for each project are kept, outside the projects themselves so a
+/// repository can't allow hosts for whoever opens it
+const ALLOWLIST_FILE: &str = "network_allowlist.yaml";
+
+/// First line of the approval prompt for a tool call that connects to hosts not allowed yet,
+/// which clients can look for to offer allowing the hosts for the project
+pub const NETWORK_ALERT: &str = "🌐 Network access";
+
+/// Marks each host listed in the prompt
+const HOST_BULLET: &str = "  - ";
+
+/// Whether an approval prompt is for a tool call that connects to hosts not allowed yet
+pub fn is_network_alert(prompt: &str) -> bool {
+    prompt.starts_with(NETWORK_ALERT)
+}
+
+/// The hosts a network prompt asks about
+pub fn network_alert_hosts(prompt: &str) -> Vec<String> {
+    prompt
+        .lines()
+        .filter_map(|line| line.strip_prefix(HOST_BULLET))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The host of `word` if it is a URL, or with `bare` also a host name like `example.com/path`
+/// as curl and wget accept
+fn host_of(word: &str, bare: bool) -> Option<String> {
+    let word = word.trim_matches(['"', '\'']);
+    if let Some((scheme, _)) = word.split_once("://") {
+        if !matches!(scheme, "http" | "https" | "ftp") {
+            return None;
+        }
+        return url::Url::parse(word)
+            .ok()?
+            .host_str()
+            .map(str::to_ascii_lowercase);
+    }
+    if !bare || word.starts_with(['-', '/', '.', '$']) {
+        return None;
+    }
+    let host = word.split(['/', ':', '?']).next()?;
+    let looks_like_host = host.contains('.')
+        && !host.ends_with('.')
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    looks_like_host.then(|| host.to_ascii_lowercase())
+}
+
+/// Hosts that curl or wget reach in a shell command, e.g. both in
+/// `curl -s https://a.example/x && wget b.example/y`
+fn shell_hosts(command: &str) -> Vec<String> {
+    command
+        .split(['\n', ';', '|', '&'])
+        .filter_map(|segment| {
+            let mut words = segment.split_whitespace();
+            let program = words.next()?.rsplit('/').next()?;
+            NETWORK_COMMANDS.contains(&program).then_some(words)
+        })
+        .flat_map(|words| {
+            let mut previous = "";
+            words.filter_map(move |word| {
+                // full URLs always count, as `curl -O https://...` takes no value
+                let bare = !VALUE_OPTIONS.contains(&previous);
+                previous = word;
+                host_of(word, bare)
+            })
+        })
+        .collect()
+}
+
+/// The hosts a tool call will connect to: the URLs passed to fetch-like tools, and the
+/// downloads of shell commands
+fn call_hosts(call: &CallToolRequestParams) -> BTreeSet<String> {
+    let Some(args) = &call.arguments else {
+        return BTreeSet::new();
+    };
+    if call.name == SHELL_TOOL {
+        return args
+            .get("command")
+            .and_then(Value::as_str)
+            .map(shell_hosts)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+    }
+    args.values()
+        .flat_map(|value| match value {
+            Value::Array(items) => items.iter().collect(),
+            value => vec![value],
+        })
+        .filter_map(Value::as_str)
+        .filter(|text| !text.contains(char::is_whitespace))
+        .filter_map(|text| host_of(text, false))
+        .collect()
+}
+
+/// The allowlist's key for the project containing `dir`
+fn project_key(dir: &Path) -> String {
+    project_root(dir).display().to_string()
+}
+
+fn load_allowlist(file: &Path) -> BTreeMap<String, BTreeSet<String>> {
+    std::fs::read_to_string(file)
+        .ok()
+        .and_then(|text| serde_yaml::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn add_to_allowlist(file: &Path, project: &str, hosts: &[String]) -> Result<()> {
+    let mut allowlist = load_allowlist(file);
+    allowlist
+        .entry(project.to_string())
+        .or_default()
+        .extend(hosts.iter().cloned());
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(file, serde_yaml::to_string(&allowlist)?)?;
+    Ok(())
+}
+
+/// Allows tools to connect to `hosts` without asking, in the project containing `working_dir`
+pub fn allow_hosts(working_dir: &Path, hosts: &[String]) -> Result<()> {
+    add_to_allowlist(
+        &Paths::in_config_dir(ALLOWLIST_FILE),
+        &project_key(working_dir),
+        hosts,
+    )
+}
+
+fn alert(tool_name: &str, hosts: &BTreeSet<String>) -> String {
+    let list: Vec<String> = hosts
+        .iter()
+        .map(|host| format!("{}{}", HOST_BULLET, host))
+        .collect();
+    format!(
+        "{}\n\n{} will connect to:\n{}",
+        NETWORK_ALERT,
+        tool_name,
+        list.join("\n")
+    )
+}
+
+/// With `GOOSE_NETWORK_PERMISSIONS` on, asks before tool calls that connect to hosts, like
+/// fetch tools given a URL or shell commands running curl or wget, unless every host is on
+/// the project's allowlist. Hosts are allowed per project, so a host trusted for one
+/// repository isn't for the next.
+pub struct NetworkInspector {
+    allowlist: PathBuf,
+}
+
+impl NetworkInspector {
+    pub fn new() -> Self {
+        Self {
+            allowlist: Paths::in_config_dir(ALLOWLIST_FILE),
+        }
+    }
+}
+
+impl Default for NetworkInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolInspector for NetworkInspector {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn inspect(
+        &self,
+        tool_requests: &[ToolRequest],
+        _messages: &[Message],
+        _goose_mode: GooseMode,
+        working_dir: &Path,
+    ) -> Result<Vec<InspectionResult>> {
+        let allowed = load_allowlist(&self.allowlist)
+            .remove(&project_key(working_dir))
+            .unwrap_or_default();
+        let mut results = Vec::new();
+        for request in tool_requests {
+            let Ok(call) = &request.tool_call else {
+                continue;
+            };
+            let hosts: BTreeSet<String> = call_hosts(call)
+                .into_iter()
+                .filter(|host| !allowed.contains(host))
+                .collect();
+            if hosts.is_empty() {
+                continue;
+            }
+            let names: Vec<&str> = hosts.iter().map(String::as_str).collect();
+            results.push(InspectionResult {
+                tool_request_id: request.id.clone(),
+                action: InspectionAction::RequireApproval(Some(alert(&call.name, &hosts))),
+                reason: format!("connects to {}", names.join(", ")),
+                confidence: 1.0,
+                inspector_name: self.name().to_string(),
+                finding_id: None,
+            });
+        }
+        Ok(results)
+    }
+
+    fn is_enabled(&self) -> bool {
+        Config::global()
+            .get_param::<bool>("GOOSE_NETWORK_PERMISSIONS")
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn call(name: &str, args: Value) -> CallToolRequestParams {
+        CallToolRequestParams {
+            meta: None,
+            task: None,
+            name: name.to_string().into(),
+            arguments: args.as_object().cloned(),
+        }
+    }
+
+    #[test_case("curl -s https://api.example.com/v1 | jq .", &["api.example.com"] ; "curl url")]
+    #[test_case("wget example.org/file.tar.gz && tar xf file.tar.gz", &["example.org"] ; "wget bare host")]
+    #[test_case("/usr/bin/curl -o out.json 'http://Host.Example:8080/x'", &["host.example"] ; "quoted url with port")]
+    #[test_case("cat README.md; echo https://example.com", &[] ; "no download")]
+    #[test_case("curl -d @data.json localhost:3000", &[] ; "no dotted host")]
+    fn test_shell_hosts(command: &str, expected: &[&str]) {
+        assert_eq!(shell_hosts(command), expected);
+    }
+
+    #[test_case("fetch__fetch", serde_json::json!({"url": "https://docs.rs/tokio"}), &["docs.rs"] ; "fetch tool")]
+    #[test_case("browser__open", serde_json::json!({"urls": ["https://a.dev", "https://b.dev/x"]}), &["a.dev", "b.dev"] ; "list of urls")]
+    #[test_case("developer__text_editor", serde_json::json!({"command": "write", "file_text": "see https://a.dev for more"}), &[] ; "url inside text")]
+    #[test_case("developer__shell", serde_json::json!({"command": "curl https://a.dev"}), &["a.dev"] ; "shell")]
+    fn test_call_hosts(name: &str, args: Value, expected: &[&str]) {
+        let hosts: Vec<String> = call_hosts(&call(name, args)).into_iter().collect();
+        assert_eq!(hosts, expected);
+    }
+
+    #[test]
+    fn test_alert_lists_hosts() {
+        let hosts = BTreeSet::from(["a.dev".to_string(), "b.dev".to_string()]);
+        let prompt = alert("fetch__fetch", &hosts);
+        assert!(is_network_alert(&prompt));
+        assert_eq!(network_alert_hosts(&prompt), vec!["a.dev", "b.dev"]);
+    }
+
+    #[tokio::test]
+    async fn test_hosts_are_allowed_for_the_session_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        let other = dir.path().join("other");
+        std::fs::create_dir_all(project.join(".git")).unwrap();
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        let inspector = NetworkInspector {
+            allowlist: dir.path().join(ALLOWLIST_FILE),
+        };
+        add_to_allowlist(
+            &inspector.allowlist,
+            &project_key(&project),
+            &["a.dev".to_string()],
+        )
+        .unwrap();
+
+        let request = ToolRequest {
+            id: "1".to_string(),
+            tool_call: Ok(call(
+                "fetch__fetch",
+                serde_json::json!({"url": "https://a.dev/docs"}),
+            )),
+            metadata: None,
+            tool_meta: None,
+        };
+        let requests = std::slice::from_ref(&request);
+        let in_project = inspector
+            .inspect(requests, &[], GooseMode::Approve, &project.join("src"))
+            .await
+            .unwrap();
+        assert!(in_project.is_empty());
+        let elsewhere = inspector
+            .inspect(requests, &[], GooseMode::Approve, &other)
+            .await
+            .unwrap();
+        assert_eq!(elsewhere.len(), 1);
+    }
+
+    #[test]
+    fn test_allowlist_is_per_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(ALLOWLIST_FILE);
+        add_to_allowlist(&file, "/work/one", &["a.dev".to_string()]).unwrap();
+        add_to_allowlist(&file, "/work/one", &["b.dev".to_string()]).unwrap();
+        add_to_allowlist(&file, "/work/two", &["c.dev".to_string()]).unwrap();
+
+        let allowlist = load_allowlist(&file);
+        assert_eq!(
+            allowlist["/work/one"],
+            BTreeSet::from(["a.dev".to_string(), "b.dev".to_string()])
+        );
+        assert_eq!(
+            allowlist["/work/two"],
+            BTreeSet::from(["c.dev".to_string()])
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Spans for a session, its turns, and the provider requests and tool calls within a turn.
+//! They are named and attributed after the OpenTelemetry GenAI semantic conventions so the
+//! traces group sensibly in observability backends. They are exported once
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` or the `otel_exporter_otlp_endpoint` setting is set (see
+//! [`super::otlp`]); otherwise they are ordinary tracing spans.
+
+use std::fmt::Display;
+
+use rmcp::model::CallToolResult;
+use tracing::field::Empty;
+use tracing::Span;
+
+use crate::mcp_utils::ToolResult;
+use crate::providers::base::ProviderUsage;
+
+/// The root of a session's trace. Every turn of the session is a child of it.
+pub fn session(session_id: &str, provider: &str, model: &str) -> Span {
+    tracing::info_span!(
+        target: "goose::otel",
+        parent: None,
+        "session",
+        otel.name = "session",
+        gen_ai.conversation.id = session_id,
+        gen_ai.provider.name = provider,
+        gen_ai.request.model = model,
+    )
+}
+
+/// One user message and everything the agent did to answer it
+pub fn turn(session: &Span, number: usize) -> Span {
+    tracing::info_span!(
+        target: "goose::otel",
+        parent: session,
+        "turn",
+        otel.name = %format!("turn {}", number),
+        turn.number = number,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+        otel.status_code = Empty,
+        otel.status_description = Empty,
+    )
+}
+
+/// One request to the model, until its usage is reported
+pub fn provider_request(provider: &str, model: &str) -> Span {
+    tracing::info_span!(
+        target: "goose::otel",
+        "provider_request",
+        otel.name = %format!("chat {}", model),
+        otel.kind = "client",
+        gen_ai.operation.name = "chat",
+        gen_ai.provider.name = provider,
+        gen_ai.request.model = model,
+        gen_ai.response.model = Empty,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+        otel.status_code = Empty,
+        otel.status_description = Empty,
+    )
+}
+
+/// One tool call, while the agent waits for its result
+pub fn tool_call(tool_name: &str, call_id: &str) -> Span {
+    tracing::info_span!(
+        target: "goose::otel",
+        "tool_call",
+        otel.name = %format!("execute_tool {}", tool_name),
+        gen_ai.operation.name = "execute_tool",
+        gen_ai.tool.name = tool_name,
+        gen_ai.tool.call.id = call_id,
+        otel.status_code = Empty,
+        otel.status_description = Empty,
+    )
+}
+
+pub fn record_tokens(span: &Span, input_tokens: Option<u64>, output_tokens: Option<u64>) {
+    if let Some(tokens) = input_tokens {
+        span.record("gen_ai.usage.input_tokens", tokens);
+    }
+    if let Some(tokens) = output_tokens {
+        span.record("gen_ai.usage.output_tokens", tokens);
+    }
+}
+
+pub fn record_usage(span: &Span, usage: &ProviderUsage) {
+    span.record("gen_ai.response.model", usage.model.as_str());
+    let tokens = |count: Option<i32>| count.and_then(|count| u64::try_from(count).ok());
+    record_tokens(
+        span,
+        tokens(usage.usage.input_tokens),
+        tokens(usage.usage.output_tokens),
+    );
+}
+
+pub fn record_error(span: &Span, error: impl Display) {
+    span.record("otel.status_code", "ERROR");
+    span.record("otel.status_description", tracing::field::display(error));
+}
+
+/// Marks the span failed when the tool failed or reported an error result
+pub fn record_tool_result(span: &Span, result: &ToolResult<CallToolResult>) {
+    match result {
+        Ok(result) if result.is_error == Some(true) => record_error(span, "tool returned an error"),
+        Ok(_) => {}
+        Err(e) => record_error(span, &e.message),
+    }
+}
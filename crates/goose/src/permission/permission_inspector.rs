@@ -7,6 +7,7 @@ use crate::tool_inspection::{InspectionAction, InspectionResult, ToolInspector};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 
 /// Permission Inspector that handles tool permission checking
@@ -108,6 +109,7 @@ impl ToolInspector for PermissionInspector {
         tool_requests: &[ToolRequest],
         _messages: &[Message],
         goose_mode: GooseMode,
+        _working_dir: &Path,
     ) -> Result<Vec<InspectionResult>> {
         let mut results = Vec::new();
         let permission_manager = &self.permission_manager;
@@ -121,7 +123,9 @@ impl ToolInspector for PermissionInspector {
                     GooseMode::Auto => InspectionAction::Allow,
                     GooseMode::Approve | GooseMode::SmartApprove => {
                         // 1. Check user-defined permission first
-                        if let Some(level) = permission_manager.get_user_permission(tool_name) {
+                        if let Some(level) =
+                            permission_manager.get_user_permission_for_tool(tool_name)
+                        {
                             match level {
                                 PermissionLevel::AlwaysAllow => InspectionAction::Allow,
                                 PermissionLevel::NeverAllow => InspectionAction::Deny,
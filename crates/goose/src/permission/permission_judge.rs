@@ -196,7 +196,9 @@ pub async fn check_tool_permissions(
                 }
 
                 // 1. Check user-defined permission
-                if let Some(level) = permission_manager.get_user_permission(&tool_call.name) {
+                if let Some(level) =
+                    permission_manager.get_user_permission_for_tool(&tool_call.name)
+                {
                     match level {
                         PermissionLevel::AlwaysAllow => approved.push(request.clone()),
                         PermissionLevel::AskBefore => needs_approval.push(request.clone()),
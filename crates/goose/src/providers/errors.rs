@@ -45,6 +45,14 @@ impl ProviderError {
             ProviderError::NotImplemented(_) => "not_implemented",
         }
     }
+
+    /// Whether sending the same request again later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::RateLimitExceeded { .. } | ProviderError::ServerError(_)
+        )
+    }
 }
 
 impl From<anyhow::Error> for ProviderError {
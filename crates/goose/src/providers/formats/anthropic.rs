@@ -1,4 +1,4 @@
-use crate::conversation::message::{Message, MessageContent};
+use crate::conversation::message::{Citation, Message, MessageContent};
 use crate::model::ModelConfig;
 use crate::providers::base::Usage;
 use crate::providers::errors::ProviderError;
@@ -29,6 +29,8 @@ const TOOL_USE_ID_FIELD: &str = "tool_use_id";
 const IS_ERROR_FIELD: &str = "is_error";
 const SIGNATURE_FIELD: &str = "signature";
 const DATA_FIELD: &str = "data";
+const CITATIONS_FIELD: &str = "citations";
+const CITATIONS_DELTA_TYPE: &str = "citations_delta";
 
 /// Convert internal Message format to Anthropic's API message specification
 pub fn format_messages(messages: &[Message]) -> Vec<Value> {
@@ -128,6 +130,9 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                     // Reasoning content is for OpenAI-compatible APIs (e.g., DeepSeek)
                     // Anthropic doesn't use this format, so skip it
                 }
+                MessageContent::Citations(_) => {
+                    // Citations are shown to the user, not sent back
+                }
             }
         }
 
@@ -231,6 +236,7 @@ pub fn response_to_message(response: &Value) -> Result<Message> {
         .ok_or_else(|| anyhow!("Invalid response format: missing content array"))?;
 
     let mut message = Message::assistant();
+    let mut citations = Vec::new();
 
     for block in content_blocks {
         match block.get(TYPE_FIELD).and_then(|t| t.as_str()) {
@@ -238,6 +244,10 @@ pub fn response_to_message(response: &Value) -> Result<Message> {
                 if let Some(text) = block.get(TEXT_TYPE).and_then(|t| t.as_str()) {
                     message = message.with_text(text.to_string());
                 }
+                let block_citations = block.get(CITATIONS_FIELD).and_then(|c| c.as_array());
+                for citation in block_citations.into_iter().flatten() {
+                    add_citation(&mut citations, citation);
+                }
             }
             Some(TOOL_USE_TYPE) => {
                 let id = block
@@ -284,7 +294,26 @@ pub fn response_to_message(response: &Value) -> Result<Message> {
         }
     }
 
-    Ok(message)
+    Ok(message.with_citations(citations))
+}
+
+/// Adds a citation of a text block, which points at a web search result, a search result or a
+/// passage of a document, unless it is already there
+fn add_citation(citations: &mut Vec<Citation>, citation: &Value) {
+    let field = |name: &str| {
+        citation
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let citation = Citation {
+        title: field("title").or_else(|| field("document_title")),
+        url: field("url").or_else(|| field("source").filter(|s| s.starts_with("http"))),
+        cited_text: field("cited_text"),
+    };
+    if (citation.title.is_some() || citation.url.is_some()) && !citations.contains(&citation) {
+        citations.push(citation);
+    }
 }
 
 /// Extract usage information from Anthropic's API response
@@ -480,6 +509,7 @@ where
         let mut current_tool_id: Option<String> = None;
         let mut final_usage: Option<crate::providers::base::ProviderUsage> = None;
         let mut message_id: Option<String> = None;
+        let mut citations = Vec::new();
 
         while let Some(line_result) = stream.next().await {
             let line = line_result?;
@@ -559,6 +589,10 @@ where
                                 message.id = message_id.clone();
                                 yield (Some(message), None);
                             }
+                        } else if delta.get("type") == Some(&json!(CITATIONS_DELTA_TYPE)) {
+                            if let Some(citation) = delta.get("citation") {
+                                add_citation(&mut citations, citation);
+                            }
                         } else if delta.get("type") == Some(&json!("input_json_delta")) {
                             // Tool input delta
                             if let Some(tool_id) = &current_tool_id {
@@ -683,6 +717,12 @@ where
             }
         }
 
+        if !citations.is_empty() {
+            let mut message = Message::assistant().with_citations(citations);
+            message.id = message_id.clone();
+            yield (Some(message), None);
+        }
+
         // Yield final usage information if available
         if let Some(usage) = final_usage {
             yield (None, Some(usage));
@@ -779,6 +819,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_citations() -> Result<()> {
+        let response = json!({
+            "content": [
+                {
+                    "type": "text",
+                    "text": "Rust 1.0 shipped in May 2015.",
+                    "citations": [
+                        {
+                            "type": "web_search_result_location",
+                            "url": "https://blog.rust-lang.org/2015/05/15/Rust-1.0.html",
+                            "title": "Announcing Rust 1.0",
+                            "cited_text": "We are very proud to announce the 1.0 release of Rust"
+                        },
+                        {
+                            "type": "char_location",
+                            "document_title": "Release notes",
+                            "cited_text": "Version 1.0.0 (2015-05-15)"
+                        }
+                    ]
+                },
+                {
+                    "type": "text",
+                    "text": " It was stable from the start.",
+                    "citations": null
+                }
+            ]
+        });
+
+        let message = response_to_message(&response)?;
+        assert_eq!(message.content.len(), 3);
+        let citations = message.content[2].as_citations().unwrap();
+        assert_eq!(
+            citations.citations[0].url.as_deref(),
+            Some("https://blog.rust-lang.org/2015/05/15/Rust-1.0.html")
+        );
+        assert_eq!(
+            citations.citations[1].title.as_deref(),
+            Some("Release notes")
+        );
+        assert_eq!(citations.citations[1].url, None);
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_thinking_response() -> Result<()> {
         let response = json!({
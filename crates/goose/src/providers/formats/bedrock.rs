@@ -118,6 +118,10 @@ pub fn to_bedrock_message_content(content: &MessageContent) -> Result<bedrock::C
             // Bedrock doesn't use this format, so skip
             bedrock::ContentBlock::Text("".to_string())
         }
+        MessageContent::Citations(_) => {
+            // Citations are shown to the user, not sent back
+            bedrock::ContentBlock::Text("".to_string())
+        }
     })
 }
 
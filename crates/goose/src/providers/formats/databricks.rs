@@ -209,6 +209,7 @@ fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<Data
                     // Reasoning content is for OpenAI-compatible APIs (e.g., DeepSeek)
                     // Databricks doesn't use this format, so skip
                 }
+                MessageContent::Citations(_) => {}
             }
         }
 
@@ -10,7 +10,7 @@ use serde::Serialize;
 use std::borrow::Cow;
 use uuid::Uuid;
 
-use crate::conversation::message::{Message, MessageContent, ProviderMetadata};
+use crate::conversation::message::{Citation, Message, MessageContent, ProviderMetadata};
 use serde_json::{json, Map, Value};
 use std::ops::Deref;
 
@@ -334,7 +334,33 @@ pub fn response_to_message(response: Value) -> Result<Message> {
             content.push(msg_content);
         }
     }
-    Ok(Message::new(role, created, content))
+    Ok(Message::new(role, created, content).with_citations(grounding_citations(&response)))
+}
+
+/// The web pages a response grounded with Google Search draws on
+fn grounding_citations(response: &Value) -> Vec<Citation> {
+    let chunks = response
+        .get("candidates")
+        .and_then(|v| v.as_array())
+        .and_then(|c| c.first())
+        .and_then(|c| c.get("groundingMetadata"))
+        .and_then(|g| g.get("groundingChunks"))
+        .and_then(|g| g.as_array());
+    let mut citations: Vec<Citation> = Vec::new();
+    for web in chunks.into_iter().flatten().filter_map(|c| c.get("web")) {
+        let citation = Citation {
+            title: web
+                .get("title")
+                .and_then(|t| t.as_str())
+                .map(str::to_string),
+            url: web.get("uri").and_then(|u| u.as_str()).map(str::to_string),
+            cited_text: None,
+        };
+        if citation.url.is_some() && !citations.contains(&citation) {
+            citations.push(citation);
+        }
+    }
+    citations
 }
 
 /// Extract usage information from Google's API response
@@ -382,6 +408,7 @@ where
         let mut last_signature: Option<String> = None;
         let stream_id = Uuid::new_v4().to_string();
         let mut incomplete_data: Option<String> = None;
+        let mut citations = Vec::new();
 
         while let Some(line_result) = stream.next().await {
             let line = line_result?;
@@ -456,6 +483,12 @@ where
                 }
             }
 
+            // the grounding comes with the last chunks, each with all of it so far
+            let chunk_citations = grounding_citations(&chunk);
+            if !chunk_citations.is_empty() {
+                citations = chunk_citations;
+            }
+
             let parts = chunk
                 .get("candidates")
                 .and_then(|v| v.as_array())
@@ -480,6 +513,13 @@ where
             }
         }
 
+        if !citations.is_empty() {
+            let message = Message::assistant()
+                .with_citations(citations)
+                .with_id(stream_id.clone());
+            yield (Some(message), None);
+        }
+
         if let Some(usage) = final_usage {
             yield (None, Some(usage));
         }
@@ -871,6 +911,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_response_to_message_with_grounding() {
+        let response = json!({
+            "candidates": [{
+                "content": {
+                    "parts": [{
+                        "text": "Rust 1.0 shipped in May 2015."
+                    }]
+                },
+                "groundingMetadata": {
+                    "webSearchQueries": ["rust 1.0 release date"],
+                    "groundingChunks": [
+                        {"web": {"uri": "https://blog.rust-lang.org/2015/05/15/Rust-1.0.html", "title": "rust-lang.org"}},
+                        {"web": {"uri": "https://blog.rust-lang.org/2015/05/15/Rust-1.0.html", "title": "rust-lang.org"}},
+                        {"retrievedContext": {"uri": "gs://bucket/notes.txt"}}
+                    ]
+                }
+            }]
+        });
+        let message = response_to_message(response).unwrap();
+        assert_eq!(message.content.len(), 2);
+        let citations = &message.content[1].as_citations().unwrap().citations;
+        assert_eq!(citations.len(), 1);
+        assert_eq!(citations[0].title.as_deref(), Some("rust-lang.org"));
+    }
+
     #[test]
     fn test_response_to_message_with_invalid_function_name() {
         let response = json!({
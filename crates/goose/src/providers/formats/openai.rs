@@ -1,4 +1,4 @@
-use crate::conversation::message::{Message, MessageContent, ProviderMetadata};
+use crate::conversation::message::{Citation, Message, MessageContent, ProviderMetadata};
 use crate::mcp_utils::extract_text_from_resource;
 use crate::model::ModelConfig;
 use crate::providers::base::{ProviderUsage, Usage};
@@ -54,6 +54,7 @@ struct Delta {
     tool_calls: Option<Vec<DeltaToolCall>>,
     reasoning_details: Option<Vec<Value>>,
     reasoning_content: Option<String>,
+    annotations: Option<Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -70,6 +71,10 @@ struct StreamingChunk {
     id: Option<String>,
     usage: Option<Value>,
     model: Option<String>,
+    /// Read only when they are lists, as are `search_results` and the delta's `annotations`, so
+    /// a provider that sends something else doesn't fail the whole chunk
+    citations: Option<Value>,
+    search_results: Option<Value>,
 }
 
 pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<Value> {
@@ -112,7 +117,7 @@ pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<
                     // Redacted thinking blocks are not directly used in OpenAI format
                     continue;
                 }
-                MessageContent::SystemNotification(_) => {
+                MessageContent::SystemNotification(_) | MessageContent::Citations(_) => {
                     continue;
                 }
                 MessageContent::Reasoning(r) => {
@@ -435,6 +440,36 @@ pub fn response_to_message(response: &Value) -> anyhow::Result<Message> {
         }
     }
 
+    let mut citations = Vec::new();
+    for annotation in original
+        .get("annotations")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+    {
+        add_annotation_citation(&mut citations, annotation);
+    }
+    for result in response
+        .get("search_results")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+    {
+        add_search_result_citation(&mut citations, result);
+    }
+    for url in response
+        .get("citations")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|url| url.as_str())
+    {
+        add_citation(&mut citations, None, url);
+    }
+    if !citations.is_empty() {
+        content.push(MessageContent::citations(citations));
+    }
+
     Ok(Message::new(
         Role::Assistant,
         chrono::Utc::now().timestamp(),
@@ -442,6 +477,43 @@ pub fn response_to_message(response: &Value) -> anyhow::Result<Message> {
     ))
 }
 
+/// Adds a cited URL, or the title of one already cited without it
+fn add_citation(citations: &mut Vec<Citation>, title: Option<&str>, url: &str) {
+    match citations
+        .iter_mut()
+        .find(|citation| citation.url.as_deref() == Some(url))
+    {
+        Some(citation) => {
+            if citation.title.is_none() {
+                citation.title = title.map(str::to_string);
+            }
+        }
+        None => citations.push(Citation {
+            title: title.map(str::to_string),
+            url: Some(url.to_string()),
+            cited_text: None,
+        }),
+    }
+}
+
+/// A `url_citation` annotation of the message, as OpenAI and OpenRouter send for web search
+fn add_annotation_citation(citations: &mut Vec<Citation>, annotation: &Value) {
+    if annotation.get("type").and_then(|t| t.as_str()) != Some("url_citation") {
+        return;
+    }
+    let source = annotation.get("url_citation").unwrap_or(annotation);
+    if let Some(url) = source.get("url").and_then(|u| u.as_str()) {
+        add_citation(citations, source.get("title").and_then(|t| t.as_str()), url);
+    }
+}
+
+/// One of the `search_results` Perplexity sends next to its list of cited URLs
+fn add_search_result_citation(citations: &mut Vec<Citation>, result: &Value) {
+    if let Some(url) = result.get("url").and_then(|u| u.as_str()) {
+        add_citation(citations, result.get("title").and_then(|t| t.as_str()), url);
+    }
+}
+
 pub fn get_usage(usage: &Value) -> Usage {
     let input_tokens = usage
         .get("prompt_tokens")
@@ -539,6 +611,8 @@ where
 
         let mut accumulated_reasoning: Vec<Value> = Vec::new();
         let mut accumulated_reasoning_content = String::new();
+        let mut citations: Vec<Citation> = Vec::new();
+        let mut last_id: Option<String> = None;
 
         'outer: while let Some(response) = stream.next().await {
             if response.as_ref().is_ok_and(|s| s == "data: [DONE]") {
@@ -562,6 +636,25 @@ where
                 if let Some(rc) = &chunk.choices[0].delta.reasoning_content {
                     accumulated_reasoning_content.push_str(rc);
                 }
+                let annotations = chunk.choices[0]
+                    .delta
+                    .annotations
+                    .iter()
+                    .filter_map(Value::as_array);
+                for annotation in annotations.flatten() {
+                    add_annotation_citation(&mut citations, annotation);
+                }
+            }
+            let search_results = chunk.search_results.iter().filter_map(Value::as_array);
+            for result in search_results.flatten() {
+                add_search_result_citation(&mut citations, result);
+            }
+            let urls = chunk.citations.iter().filter_map(Value::as_array).flatten();
+            for url in urls.filter_map(|url| url.as_str()) {
+                add_citation(&mut citations, None, url);
+            }
+            if chunk.id.is_some() {
+                last_id = chunk.id.clone();
             }
 
             let mut usage = extract_usage_with_output_tokens(&chunk);
@@ -748,6 +841,19 @@ where
                 yield (None, usage)
             }
         }
+
+        // the sources come with the text, so they are shown once it is all in
+        if !citations.is_empty() {
+            let mut msg = Message::new(
+                Role::Assistant,
+                chrono::Utc::now().timestamp(),
+                vec![MessageContent::citations(citations)],
+            );
+            if let Some(id) = last_id {
+                msg = msg.with_id(id);
+            }
+            yield (Some(msg), None)
+        }
     }
 }
 
@@ -1204,6 +1310,95 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_response_to_message_citations() -> anyhow::Result<()> {
+        let response = json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": "Rust 1.0 shipped in May 2015 [1].",
+                    "annotations": [{
+                        "type": "url_citation",
+                        "url_citation": {
+                            "url": "https://blog.rust-lang.org/2015/05/15/Rust-1.0.html",
+                            "title": "Announcing Rust 1.0"
+                        }
+                    }]
+                }
+            }],
+            "citations": [
+                "https://blog.rust-lang.org/2015/05/15/Rust-1.0.html",
+                "https://en.wikipedia.org/wiki/Rust_(programming_language)"
+            ],
+            "search_results": [{
+                "title": "Rust (programming language)",
+                "url": "https://en.wikipedia.org/wiki/Rust_(programming_language)"
+            }]
+        });
+
+        let message = response_to_message(&response)?;
+        assert_eq!(message.content.len(), 2);
+        let citations = &message.content[1].as_citations().unwrap().citations;
+        let titles: Vec<_> = citations.iter().map(|c| c.title.as_deref()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                Some("Announcing Rust 1.0"),
+                Some("Rust (programming language)")
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamed_citations_follow_the_text() -> anyhow::Result<()> {
+        let response_lines = r#"
+data: {"id":"pplx-1","model":"sonar","citations":["https://docs.rs"],"choices":[{"index":0,"delta":{"role":"assistant","content":"See docs.rs [1]."},"finish_reason":null}]}
+data: {"id":"pplx-1","model":"sonar","citations":["https://docs.rs"],"choices":[{"index":0,"delta":{"content":""},"finish_reason":"stop"}]}
+data: [DONE]
+"#;
+        let lines: Vec<String> = response_lines.lines().map(|s| s.to_string()).collect();
+        let messages = response_to_streaming_message(tokio_stream::iter(lines.into_iter().map(Ok)));
+        pin!(messages);
+
+        let mut last = None;
+        while let Some(Ok((message, _))) = messages.next().await {
+            last = message.or(last);
+        }
+        let last = last.unwrap();
+        assert_eq!(last.id.as_deref(), Some("pplx-1"));
+        let citations = &last.content[0].as_citations().unwrap().citations;
+        assert_eq!(citations.len(), 1);
+        assert_eq!(citations[0].url.as_deref(), Some("https://docs.rs"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamed_citations_that_are_not_lists_are_ignored() -> anyhow::Result<()> {
+        let response_lines = r#"
+data: {"id":"chunk-1","model":"m","citations":"none","search_results":{},"choices":[{"index":0,"delta":{"role":"assistant","content":"Hello","annotations":{}},"finish_reason":null}]}
+data: {"id":"chunk-1","model":"m","choices":[{"index":0,"delta":{"content":""},"finish_reason":"stop"}]}
+data: [DONE]
+"#;
+        let lines: Vec<String> = response_lines.lines().map(|s| s.to_string()).collect();
+        let messages = response_to_streaming_message(tokio_stream::iter(lines.into_iter().map(Ok)));
+        pin!(messages);
+
+        let mut text = String::new();
+        while let Some(item) = messages.next().await {
+            let (message, _) = item?;
+            if let Some(message) = message {
+                text.push_str(&message.as_concat_text());
+                assert!(message.content.iter().all(|c| c.as_citations().is_none()));
+            }
+        }
+        assert_eq!(text, "Hello");
+
+        Ok(())
+    }
+
     #[test]
     fn test_response_to_message_valid_toolrequest() -> anyhow::Result<()> {
         let response: Value = serde_json::from_str(OPENAI_TOOL_USE_RESPONSE)?;
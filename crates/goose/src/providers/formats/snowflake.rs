@@ -69,6 +69,9 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                     // Reasoning content is for OpenAI-compatible APIs (e.g., DeepSeek)
                     // Snowflake doesn't use this format, so skip
                 }
+                MessageContent::Citations(_) => {
+                    // Citations are shown to the user, not sent back
+                }
             }
         }
 
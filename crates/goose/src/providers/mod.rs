@@ -30,6 +30,7 @@ pub mod openai_compatible;
 pub mod openrouter;
 pub mod provider_registry;
 pub mod provider_test;
+pub mod rate_budget;
 mod retry;
 pub mod sagemaker_tgi;
 pub mod snowflake;
@@ -0,0 +1,278 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::config::Config;
+use crate::conversation::message::Message;
+use crate::providers::errors::ProviderError;
+
+/// Provider rate limits are counted over the last minute
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// How long calls hold off after a rate limit error that doesn't say when to retry
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Rough size of a token, for estimating a request before it is sent
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Requests and tokens a provider allows per minute, set with `GOOSE_RATE_LIMIT_RPM` and
+/// `GOOSE_RATE_LIMIT_TPM`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+impl Limits {
+    fn from_config() -> Self {
+        let config = Config::global();
+        let limit = |key: &str| config.get_param::<u32>(key).ok().filter(|n| *n > 0);
+        Self {
+            requests_per_minute: limit("GOOSE_RATE_LIMIT_RPM"),
+            tokens_per_minute: limit("GOOSE_RATE_LIMIT_TPM"),
+        }
+    }
+}
+
+/// A call counted in a budget, with the tokens it took so far
+struct Call {
+    id: u64,
+    at: Instant,
+    tokens: u32,
+}
+
+/// The calls made to one provider and model in the last minute, and how long to hold off
+/// after it reported a rate limit
+struct Budget {
+    limits: Limits,
+    calls: VecDeque<Call>,
+    blocked_until: Option<Instant>,
+}
+
+impl Budget {
+    fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            calls: VecDeque::new(),
+            blocked_until: None,
+        }
+    }
+
+    /// How long a call of `tokens` has to wait at `now` to stay under the limits, or `None`
+    /// when it can go ahead
+    fn wait(&mut self, now: Instant, tokens: u32) -> Option<Duration> {
+        while self
+            .calls
+            .front()
+            .is_some_and(|call| now.duration_since(call.at) >= WINDOW)
+        {
+            self.calls.pop_front();
+        }
+
+        let mut until = self.blocked_until.filter(|until| *until > now);
+        if let Some(rpm) = self.limits.requests_per_minute {
+            let rpm = rpm as usize;
+            if self.calls.len() >= rpm {
+                // the call that has to leave the window to make room for one more
+                let at = self.calls[self.calls.len() - rpm].at;
+                until = until.max(Some(at + WINDOW));
+            }
+        }
+        if let Some(tpm) = self.limits.tokens_per_minute {
+            let mut used: u64 = self.calls.iter().map(|call| call.tokens as u64).sum();
+            // a call bigger than the whole budget goes once the window is empty
+            let tokens = tokens.min(tpm) as u64;
+            for call in &self.calls {
+                if used + tokens <= tpm as u64 {
+                    break;
+                }
+                used -= call.tokens as u64;
+                until = until.max(Some(call.at + WINDOW));
+            }
+        }
+        until.map(|until| until - now)
+    }
+}
+
+static BUDGETS: LazyLock<Mutex<HashMap<String, Budget>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_CALL_ID: AtomicU64 = AtomicU64::new(0);
+
+static WAITING: LazyLock<watch::Sender<BTreeSet<String>>> =
+    LazyLock::new(|| watch::channel(BTreeSet::new()).0);
+
+/// The sessions waiting for room in a rate limit window, updated as they start and stop
+/// waiting, so clients can show subagents that are held back
+pub fn subscribe() -> watch::Receiver<BTreeSet<String>> {
+    WAITING.subscribe()
+}
+
+/// Takes a session off the waiting list when its call goes ahead or is cancelled
+struct Waiting<'a>(&'a str);
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        WAITING.send_modify(|waiting| {
+            waiting.remove(self.0);
+        });
+    }
+}
+
+/// A call let through by [`acquire`], for [`spend`] to add what the provider reports to that
+/// call rather than to whichever call to the same model came last
+#[derive(Debug)]
+pub struct Reservation {
+    key: String,
+    id: u64,
+}
+
+/// The budget calls to `model` of `provider` share, across every session in the process
+pub fn key(provider: &str, model: &str) -> String {
+    format!("{}/{}", provider, model)
+}
+
+/// About how many tokens a request with this prompt and these messages takes
+pub fn estimate_tokens(system_prompt: &str, messages: &[Message]) -> u32 {
+    let bytes: usize = system_prompt.len()
+        + messages
+            .iter()
+            .flat_map(|message| &message.content)
+            .map(|content| content.to_string().len())
+            .sum::<usize>();
+    u32::try_from(bytes / BYTES_PER_TOKEN).unwrap_or(u32::MAX)
+}
+
+/// Waits until a call of about `tokens` tokens fits in the budget for `key`, then counts it.
+/// Subagents running side by side against the same provider queue here instead of all
+/// running into its rate limit, and only one of them retries when the window reopens.
+pub async fn acquire(key: &str, session_id: &str, tokens: u32) -> Reservation {
+    let mut waiting = None;
+    loop {
+        let wait = {
+            let mut budgets = BUDGETS.lock().unwrap();
+            let budget = budgets
+                .entry(key.to_string())
+                .or_insert_with(|| Budget::new(Limits::from_config()));
+            let now = Instant::now();
+            let Some(wait) = budget.wait(now, tokens) else {
+                let id = NEXT_CALL_ID.fetch_add(1, Ordering::Relaxed);
+                budget.calls.push_back(Call {
+                    id,
+                    at: now,
+                    tokens,
+                });
+                return Reservation {
+                    key: key.to_string(),
+                    id,
+                };
+            };
+            wait
+        };
+        if waiting.is_none() {
+            WAITING.send_modify(|waiting| {
+                waiting.insert(session_id.to_string());
+            });
+            waiting = Some(Waiting(session_id));
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Counts tokens the provider reported for a call beyond the estimate, such as the response's
+pub fn spend(reservation: &Reservation, tokens: u32) {
+    let mut budgets = BUDGETS.lock().unwrap();
+    if let Some(call) = budgets.get_mut(&reservation.key).and_then(|budget| {
+        budget
+            .calls
+            .iter_mut()
+            .find(|call| call.id == reservation.id)
+    }) {
+        call.tokens = call.tokens.saturating_add(tokens);
+    }
+}
+
+/// Holds off every call for `key` when the provider says its rate limit was hit
+pub fn record_error(key: &str, error: &ProviderError) {
+    let ProviderError::RateLimitExceeded { retry_delay, .. } = error else {
+        return;
+    };
+    let until = Instant::now() + retry_delay.unwrap_or(DEFAULT_BACKOFF);
+    let mut budgets = BUDGETS.lock().unwrap();
+    let budget = budgets
+        .entry(key.to_string())
+        .or_insert_with(|| Budget::new(Limits::from_config()));
+    budget.blocked_until = budget.blocked_until.max(Some(until));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn budget(requests_per_minute: Option<u32>, tokens_per_minute: Option<u32>) -> Budget {
+        Budget::new(Limits {
+            requests_per_minute,
+            tokens_per_minute,
+        })
+    }
+
+    #[test_case(Some(2), None, 10, Some(40) ; "requests wait for the oldest to leave the window")]
+    #[test_case(Some(3), None, 10, None ; "requests under the limit")]
+    #[test_case(None, Some(1000), 500, Some(40) ; "tokens wait for enough to leave the window")]
+    #[test_case(None, Some(1000), 100, None ; "tokens under the limit")]
+    #[test_case(None, Some(1000), 5000, Some(50) ; "call bigger than the budget waits for an empty window")]
+    #[test_case(None, None, 10, None ; "no limits")]
+    fn test_wait(rpm: Option<u32>, tpm: Option<u32>, tokens: u32, expected_secs: Option<u64>) {
+        let start = Instant::now();
+        let mut budget = budget(rpm, tpm);
+        for (id, at) in [start, start + Duration::from_secs(10)]
+            .into_iter()
+            .enumerate()
+        {
+            budget.calls.push_back(Call {
+                id: id as u64,
+                at,
+                tokens: 400,
+            });
+        }
+        let now = start + Duration::from_secs(20);
+        assert_eq!(
+            budget.wait(now, tokens),
+            expected_secs.map(Duration::from_secs)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spend_counts_towards_the_reserved_call() {
+        let key = key("test", "spend");
+        let first = acquire(&key, "session-1", 10).await;
+        let _second = acquire(&key, "session-2", 20).await;
+        spend(&first, 5);
+
+        let budgets = BUDGETS.lock().unwrap();
+        let tokens: Vec<u32> = budgets[&key].calls.iter().map(|call| call.tokens).collect();
+        assert_eq!(tokens, vec![15, 20]);
+    }
+
+    #[test]
+    fn test_rate_limit_error_holds_off_calls() {
+        let key = key("test", "rate-limited");
+        record_error(
+            &key,
+            &ProviderError::RateLimitExceeded {
+                details: "slow down".to_string(),
+                retry_delay: Some(Duration::from_secs(30)),
+            },
+        );
+        let mut budgets = BUDGETS.lock().unwrap();
+        let wait = budgets
+            .get_mut(&key)
+            .unwrap()
+            .wait(Instant::now(), 1)
+            .unwrap();
+        assert!(wait > Duration::from_secs(25) && wait <= Duration::from_secs(30));
+    }
+}
@@ -8,7 +8,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Local, Utc};
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use tokio_cron_scheduler::{job::JobId, Job, JobScheduler as TokioJobScheduler};
 use tokio_util::sync::CancellationToken;
 
@@ -28,6 +28,9 @@ use crate::session::{Session, SessionManager};
 type RunningTasksMap = HashMap<String, CancellationToken>;
 type JobsMap = HashMap<String, (JobId, ScheduledJob)>;
 
+/// Runs reported to subscribers that haven't caught up yet before the oldest are dropped
+const RUN_REPORTS: usize = 16;
+
 pub fn get_default_scheduler_storage_path() -> Result<PathBuf, io::Error> {
     let data_dir = Paths::data_dir();
     fs::create_dir_all(&data_dir)?;
@@ -116,6 +119,15 @@ pub struct ScheduledJob {
     pub process_start_time: Option<DateTime<Utc>>,
 }
 
+/// How one run of a scheduled job went, reported to [`Scheduler::subscribe_runs`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobRun {
+    pub job_id: String,
+    /// The session holding the run's transcript, if it got as far as creating one
+    pub session_id: Option<String>,
+    pub error: Option<String>,
+}
+
 async fn persist_jobs(
     storage_path: &Path,
     jobs: &Arc<Mutex<JobsMap>>,
@@ -136,6 +148,7 @@ pub struct Scheduler {
     storage_path: PathBuf,
     running_tasks: Arc<Mutex<RunningTasksMap>>,
     session_manager: Arc<SessionManager>,
+    runs: broadcast::Sender<JobRun>,
 }
 
 impl Scheduler {
@@ -156,6 +169,7 @@ impl Scheduler {
             storage_path,
             running_tasks,
             session_manager,
+            runs: broadcast::channel(RUN_REPORTS).0,
         });
 
         arc_self.load_jobs_from_storage().await;
@@ -168,11 +182,17 @@ impl Scheduler {
         Ok(arc_self)
     }
 
+    /// Reports every run of a job from now on, as it finishes
+    pub fn subscribe_runs(&self) -> broadcast::Receiver<JobRun> {
+        self.runs.subscribe()
+    }
+
     fn create_cron_task(&self, job: ScheduledJob) -> Result<Job, SchedulerError> {
         let job_for_task = job.clone();
         let jobs_arc = self.jobs.clone();
         let storage_path = self.storage_path.clone();
         let running_tasks_arc = self.running_tasks.clone();
+        let runs = self.runs.clone();
 
         let cron_parts: Vec<&str> = job.cron.split_whitespace().collect();
         let cron = match cron_parts.len() {
@@ -203,6 +223,7 @@ impl Scheduler {
             let local_storage_path = storage_path.clone();
             let job_to_execute = job_for_task.clone();
             let running_tasks = running_tasks_arc.clone();
+            let runs = runs.clone();
 
             Box::pin(async move {
                 let should_execute = {
@@ -250,14 +271,14 @@ impl Scheduler {
                     tasks.remove(&task_job_id);
                 }
 
-                {
+                let session_id = {
                     let mut jobs_guard = current_jobs_arc.lock().await;
-                    if let Some((_, job)) = jobs_guard.get_mut(&task_job_id) {
+                    jobs_guard.get_mut(&task_job_id).and_then(|(_, job)| {
                         job.currently_running = false;
-                        job.current_session_id = None;
                         job.process_start_time = None;
-                    }
-                }
+                        job.current_session_id.take()
+                    })
+                };
 
                 if let Err(e) = persist_jobs(&local_storage_path, &current_jobs_arc).await {
                     tracing::error!("Failed to persist job completion: {}", e);
@@ -270,6 +291,12 @@ impl Scheduler {
                         crate::posthog::emit_error("scheduler_job_failed", &e.to_string());
                     }
                 }
+                // nobody may be watching
+                let _ = runs.send(JobRun {
+                    job_id: task_job_id,
+                    session_id,
+                    error: result.err().map(|e| e.to_string()),
+                });
             })
         })
         .map_err(|e| SchedulerError::CronParseError(e.to_string()))
@@ -563,18 +590,23 @@ impl Scheduler {
             tasks.remove(sched_id);
         }
 
-        {
+        let session_id = {
             let mut jobs_guard = self.jobs.lock().await;
-            if let Some((_, job)) = jobs_guard.get_mut(sched_id) {
+            jobs_guard.get_mut(sched_id).and_then(|(_, job)| {
                 job.currently_running = false;
-                job.current_session_id = None;
                 job.process_start_time = None;
                 job.last_run = Some(Utc::now());
-            }
-        }
+                job.current_session_id.take()
+            })
+        };
 
         persist_jobs(&self.storage_path, &self.jobs).await?;
 
+        let _ = self.runs.send(JobRun {
+            job_id: sched_id.to_string(),
+            session_id,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
         match result {
             Ok(session_id) => Ok(session_id),
             Err(e) => Err(SchedulerError::AnyhowError(anyhow!(
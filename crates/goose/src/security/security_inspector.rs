@@ -1,5 +1,6 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use std::path::Path;
 
 use crate::config::GooseMode;
 use crate::conversation::message::{Message, ToolRequest};
@@ -61,6 +62,7 @@ impl ToolInspector for SecurityInspector {
         tool_requests: &[ToolRequest],
         messages: &[Message],
         _goose_mode: GooseMode,
+        _working_dir: &Path,
     ) -> Result<Vec<InspectionResult>> {
         let security_results = self
             .security_manager
@@ -117,7 +119,7 @@ mod tests {
         }];
 
         let results = inspector
-            .inspect(&tool_requests, &[], GooseMode::Approve)
+            .inspect(&tool_requests, &[], GooseMode::Approve, Path::new("."))
             .await
             .unwrap();
 
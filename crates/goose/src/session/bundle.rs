@@ -0,0 +1,370 @@
+//! Self-contained archives of a session, its subagents' sessions and files for reading along
+//! with them, such as a transcript, that can be imported on another machine.
+
+use crate::session::diagnostics::SystemInfo;
+use crate::session::extension_data::{ExtensionState, TodoState};
+use crate::session::{Session, SessionManager, SubagentsState};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Bumped when a bundle changes in a way older versions can't import
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SESSION_FILE: &str = "session.json";
+const SUBAGENTS_DIR: &str = "subagents";
+const FILES_DIR: &str = "files";
+/// Entries larger than this aren't read, so a bundle from elsewhere can't exhaust memory
+const MAX_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+/// The extension states an imported session keeps. The rest are left out, as the bundle may
+/// come from anyone: its enabled extensions would run their commands on resume and its pinned
+/// files would be read and sent to the provider. The `cli_` states are the CLI's tags, model
+/// and cost figures.
+const IMPORTED_STATES: [&str; 5] = [
+    TodoState::EXTENSION_NAME,
+    SubagentsState::EXTENSION_NAME,
+    "cli_tags",
+    "cli_metadata",
+    "cli_cost",
+];
+
+/// What a bundle holds and where it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub session_id: String,
+    pub session_name: String,
+    /// Whether secrets in tool calls and output were masked
+    pub redacted: bool,
+    pub system: SystemInfo,
+    /// The session ids of the subagents whose sessions are in the bundle
+    pub subagents: Vec<String>,
+    /// The names of the files for reading, under `files/`
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionBundle {
+    pub manifest: BundleManifest,
+    pub session: Session,
+    pub subagents: Vec<Session>,
+    /// Files for reading along with the sessions, by name
+    pub files: Vec<(String, String)>,
+}
+
+impl SessionBundle {
+    pub fn new(
+        session: Session,
+        subagents: Vec<Session>,
+        files: Vec<(String, String)>,
+        redacted: bool,
+    ) -> Self {
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            created_at: Utc::now(),
+            session_id: session.id.clone(),
+            session_name: session.name.clone(),
+            redacted,
+            system: SystemInfo::collect(),
+            subagents: subagents.iter().map(|s| s.id.clone()).collect(),
+            files: files.iter().map(|(name, _)| name.clone()).collect(),
+        };
+        Self {
+            manifest,
+            session,
+            subagents,
+            files,
+        }
+    }
+
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        {
+            let mut zip = ZipWriter::new(Cursor::new(&mut buffer));
+            let options =
+                FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+            zip.start_file(MANIFEST_FILE, options)?;
+            zip.write_all(serde_json::to_string_pretty(&self.manifest)?.as_bytes())?;
+
+            zip.start_file(SESSION_FILE, options)?;
+            zip.write_all(serde_json::to_string_pretty(&self.session)?.as_bytes())?;
+
+            for subagent in &self.subagents {
+                zip.start_file(format!("{}/{}.json", SUBAGENTS_DIR, subagent.id), options)?;
+                zip.write_all(serde_json::to_string_pretty(subagent)?.as_bytes())?;
+            }
+
+            for (name, content) in &self.files {
+                zip.start_file(format!("{}/{}", FILES_DIR, name), options)?;
+                zip.write_all(content.as_bytes())?;
+            }
+
+            zip.finish()?;
+        }
+        Ok(buffer)
+    }
+
+    pub fn from_zip(data: &[u8]) -> Result<Self> {
+        let mut archive = ZipArchive::new(Cursor::new(data)).context("Not a session bundle")?;
+        let manifest: BundleManifest = serde_json::from_str(
+            &read_entry(&mut archive, MANIFEST_FILE).context("Not a session bundle")?,
+        )?;
+        if manifest.format_version > BUNDLE_FORMAT_VERSION {
+            bail!(
+                "The bundle was made by a newer version of goose ({}); update to import it",
+                manifest.system.app_version
+            );
+        }
+
+        let session = serde_json::from_str(&read_entry(&mut archive, SESSION_FILE)?)?;
+        let subagents = manifest
+            .subagents
+            .iter()
+            .map(|id| {
+                let json = read_entry(&mut archive, &format!("{}/{}.json", SUBAGENTS_DIR, id))?;
+                Ok(serde_json::from_str(&json)?)
+            })
+            .collect::<Result<Vec<Session>>>()?;
+        let files = manifest
+            .files
+            .iter()
+            .map(|name| {
+                let content = read_entry(&mut archive, &format!("{}/{}", FILES_DIR, name))?;
+                Ok((name.clone(), content))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            manifest,
+            session,
+            subagents,
+            files,
+        })
+    }
+
+    /// Stores the bundle's sessions under new ids and returns the imported session. The
+    /// subagents go first so the session's record of them can point at their new ids.
+    pub async fn import(&self, session_manager: &SessionManager) -> Result<Session> {
+        let mut new_ids = HashMap::new();
+        for subagent in &self.subagents {
+            let imported = session_manager
+                .import_session(&serde_json::to_string(&without_unsafe_states(subagent))?)
+                .await
+                .with_context(|| format!("Failed to import subagent {}", subagent.id))?;
+            new_ids.insert(subagent.id.clone(), imported.id);
+        }
+
+        let mut session = without_unsafe_states(&self.session);
+        remap_subagents(&mut session, &new_ids)?;
+        session_manager
+            .import_session(&serde_json::to_string(&session)?)
+            .await
+    }
+
+    /// Writes the bundled files into `dir` and returns their paths. Names that aren't a plain
+    /// file name, like `../notes.md`, are skipped, as the bundle may come from anyone.
+    pub fn write_files(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for (name, content) in &self.files {
+            if Path::new(name).file_name() != Some(OsStr::new(name)) {
+                tracing::warn!("Skipping bundled file with an unsafe name: {}", name);
+                continue;
+            }
+            std::fs::create_dir_all(dir)?;
+            let path = dir.join(name);
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// The session with only the extension states in [`IMPORTED_STATES`]
+fn without_unsafe_states(session: &Session) -> Session {
+    let mut session = session.clone();
+    session.extension_data.extension_states.retain(|key, _| {
+        key.rsplit_once('.')
+            .is_some_and(|(name, _)| IMPORTED_STATES.contains(&name))
+    });
+    session
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<String> {
+    let entry = archive
+        .by_name(name)
+        .with_context(|| format!("The bundle has no {}", name))?;
+    read_limited(entry, MAX_ENTRY_BYTES).with_context(|| format!("Failed to read {}", name))
+}
+
+fn read_limited(reader: impl Read, limit: u64) -> Result<String> {
+    let mut content = String::new();
+    reader.take(limit + 1).read_to_string(&mut content)?;
+    if content.len() as u64 > limit {
+        bail!("it is larger than {} bytes", limit);
+    }
+    Ok(content)
+}
+
+/// Points the session's subagent records at the ids their sessions were imported under. A
+/// subagent that wasn't bundled keeps its old id.
+fn remap_subagents(session: &mut Session, new_ids: &HashMap<String, String>) -> Result<()> {
+    let Some(mut state) = SubagentsState::from_extension_data(&session.extension_data) else {
+        return Ok(());
+    };
+    for subagent in &mut state.subagents {
+        if let Some(new_id) = new_ids.get(&subagent.session_id) {
+            subagent.session_id = new_id.clone();
+        }
+    }
+    state.to_extension_data(&mut session.extension_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::message::Message;
+    use crate::session::{EnabledExtensionsState, Pin, PinnedState, SessionType, SubagentRecord};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_bundle_round_trip() {
+        let source_dir = TempDir::new().unwrap();
+        let source = SessionManager::new(source_dir.path().to_path_buf());
+        let parent = source
+            .create_session(
+                "/tmp".into(),
+                "fix the build".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        source
+            .add_message(&parent.id, &Message::user().with_text("why does it fail?"))
+            .await
+            .unwrap();
+        let subagent = source
+            .create_session(
+                "/tmp".into(),
+                "read the logs".to_string(),
+                SessionType::SubAgent,
+            )
+            .await
+            .unwrap();
+        SubagentsState::record(
+            &source,
+            &parent.id,
+            SubagentRecord {
+                session_id: subagent.id.clone(),
+                description: "read the logs".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        let mut extension_data = source
+            .get_session(&parent.id, false)
+            .await
+            .unwrap()
+            .extension_data;
+        PinnedState {
+            pins: vec![Pin::File {
+                path: "/home/someone/.ssh/id_ed25519".into(),
+            }],
+        }
+        .to_extension_data(&mut extension_data)
+        .unwrap();
+        EnabledExtensionsState::new(vec![])
+            .to_extension_data(&mut extension_data)
+            .unwrap();
+        source
+            .update(&parent.id)
+            .extension_data(extension_data)
+            .apply()
+            .await
+            .unwrap();
+
+        let bundle = SessionBundle::new(
+            source.get_session(&parent.id, true).await.unwrap(),
+            vec![source.get_session(&subagent.id, true).await.unwrap()],
+            vec![("transcript.md".to_string(), "# fix the build\n".to_string())],
+            true,
+        );
+        let read = SessionBundle::from_zip(&bundle.to_zip().unwrap()).unwrap();
+        assert_eq!(read.manifest.session_id, parent.id);
+        assert!(read.manifest.redacted);
+        assert_eq!(read.files, bundle.files);
+
+        let files_dir = TempDir::new().unwrap();
+        let written = read.write_files(files_dir.path()).unwrap();
+        assert_eq!(written, vec![files_dir.path().join("transcript.md")]);
+        assert_eq!(
+            std::fs::read_to_string(&written[0]).unwrap(),
+            "# fix the build\n"
+        );
+
+        let target_dir = TempDir::new().unwrap();
+        let target = SessionManager::new(target_dir.path().to_path_buf());
+        let imported = read.import(&target).await.unwrap();
+        assert_eq!(imported.name, "fix the build");
+        assert_eq!(imported.conversation.unwrap().messages().len(), 1);
+
+        assert!(PinnedState::from_extension_data(&imported.extension_data).is_none());
+        assert!(EnabledExtensionsState::from_extension_data(&imported.extension_data).is_none());
+        let state = SubagentsState::from_extension_data(&imported.extension_data).unwrap();
+        let imported_subagent = target
+            .get_session(&state.subagents[0].session_id, false)
+            .await
+            .unwrap();
+        assert_eq!(imported_subagent.name, "read the logs");
+    }
+
+    #[test]
+    fn test_read_limited() {
+        assert_eq!(read_limited(&b"hello"[..], 5).unwrap(), "hello");
+        assert!(read_limited(&b"hello!"[..], 5).is_err());
+    }
+
+    #[test]
+    fn test_write_files_skips_unsafe_names() {
+        let session = Session::default();
+        let bundle = SessionBundle {
+            manifest: SessionBundle::new(session.clone(), vec![], vec![], false).manifest,
+            session,
+            subagents: vec![],
+            files: vec![
+                ("../escape.md".to_string(), "no".to_string()),
+                ("/tmp/absolute.md".to_string(), "no".to_string()),
+                ("notes.md".to_string(), "yes".to_string()),
+            ],
+        };
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("files");
+        assert_eq!(
+            bundle.write_files(&nested).unwrap(),
+            vec![nested.join("notes.md")]
+        );
+        assert!(!dir.path().join("escape.md").exists());
+    }
+
+    #[test]
+    fn test_from_zip_rejects_other_archives() {
+        let mut buffer = Vec::new();
+        {
+            let mut zip = ZipWriter::new(Cursor::new(&mut buffer));
+            zip.start_file("notes.txt", FileOptions::default()).unwrap();
+            zip.write_all(b"hello").unwrap();
+            zip.finish().unwrap();
+        }
+        assert!(SessionBundle::from_zip(&buffer).is_err());
+        assert!(SessionBundle::from_zip(b"not a zip").is_err());
+    }
+}
@@ -135,7 +135,10 @@ impl<'a> ChatHistorySearch<'a> {
             FROM messages m
             INNER JOIN sessions s ON m.session_id = s.id
             WHERE EXISTS (
-                SELECT 1 FROM json_each(m.content_json) 
+                -- encrypted messages aren't JSON and can't be searched
+                SELECT 1 FROM json_each(
+                    CASE WHEN json_valid(m.content_json) THEN m.content_json ELSE '[]' END
+                )
                 WHERE json_extract(value, '$.type') = 'text' 
                 AND (
         "#,
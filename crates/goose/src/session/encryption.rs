@@ -0,0 +1,253 @@
+//! Encryption of stored session transcripts. When `GOOSE_SESSION_ENCRYPTION` is on, the content
+//! of every message written to the session database is sealed with AES-256-GCM under a key kept
+//! with goose's other secrets in the OS keychain. Messages are opened again when they are read,
+//! so resuming and exporting work as before. Messages written while encryption was off stay
+//! readable, and messages written while it was on stay readable after it is turned off, as long
+//! as the key is still in the keychain.
+//!
+//! Only message content is encrypted; session names, token counts and message metadata are not.
+//! Chat history search can't look inside encrypted messages.
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use fs2::FileExt;
+use once_cell::sync::OnceCell;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fs::{self, OpenOptions};
+
+use crate::config::paths::Paths;
+use crate::config::{Config, ConfigError};
+
+/// Config flag that turns encryption of newly written messages on
+pub const SESSION_ENCRYPTION: &str = "GOOSE_SESSION_ENCRYPTION";
+
+/// Secret holding the base64 encoded key, created the first time it is needed
+const KEY_SECRET: &str = "GOOSE_SESSION_ENCRYPTION_KEY";
+
+/// Marks an encrypted value, followed by the base64 encoded nonce and ciphertext
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+const KEY_LEN: usize = 32;
+
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Seals and opens values with one AES-256-GCM key
+pub struct SessionCipher {
+    key: LessSafeKey,
+}
+
+impl SessionCipher {
+    pub fn new(key: &[u8]) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, key)
+            .map_err(|_| anyhow!("Session encryption keys must be {} bytes", KEY_LEN))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+        })
+    }
+
+    pub fn generate_key() -> Result<[u8; KEY_LEN]> {
+        let mut key = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| anyhow!("Failed to generate a session encryption key"))?;
+        Ok(key)
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("Failed to generate a nonce"))?;
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .map_err(|_| anyhow!("Failed to encrypt session content"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&sealed);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(payload)))
+    }
+
+    pub fn decrypt(&self, stored: &str) -> Result<String> {
+        let encoded = stored
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or_else(|| anyhow!("Session content is not encrypted"))?;
+        let mut payload = BASE64
+            .decode(encoded)
+            .context("Encrypted session content is corrupt")?;
+        if payload.len() < NONCE_LEN {
+            return Err(anyhow!("Encrypted session content is corrupt"));
+        }
+        let mut sealed = payload.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&payload)
+            .map_err(|_| anyhow!("Encrypted session content is corrupt"))?;
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| {
+                anyhow!(
+                    "Failed to decrypt session content; the session encryption key does not match"
+                )
+            })?;
+        Ok(String::from_utf8(plaintext.to_vec())?)
+    }
+}
+
+fn decode_key(encoded: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(encoded.trim())
+        .context("The session encryption key in the keychain is not valid base64")
+}
+
+/// Generates and stores the key the first time encryption is used. Processes starting at the
+/// same time take turns through a lock file, and the key is read back after storing it, so they
+/// all end up encrypting with the key that is in the keychain.
+fn create_key(config: &Config) -> Result<Vec<u8>> {
+    let lock_path = Paths::in_state_dir("session-encryption.lock");
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    lock.lock_exclusive()
+        .context("Failed to lock the session encryption key")?;
+
+    // another process may have stored a key while this one waited for the lock
+    config.invalidate_secrets_cache();
+    if let Ok(encoded) = config.get_secret::<String>(KEY_SECRET) {
+        return decode_key(&encoded);
+    }
+
+    let key = SessionCipher::generate_key()?;
+    match config.set_keyring_secret(KEY_SECRET, &BASE64.encode(key)) {
+        Ok(()) => {}
+        Err(ConfigError::FallbackToFileStorage) => {
+            return Err(anyhow!(
+                "Session encryption needs the OS keychain to keep its key, but secrets are kept \
+                 in a file because the keychain is disabled or unavailable. Make the keychain \
+                 available or turn off {}",
+                SESSION_ENCRYPTION
+            ))
+        }
+        Err(e) => return Err(e).context("Failed to store the session encryption key"),
+    }
+    let stored = config
+        .get_secret::<String>(KEY_SECRET)
+        .context("Failed to read back the session encryption key")?;
+    decode_key(&stored)
+}
+
+/// Applies the configured encryption to message content on its way into and out of the session
+/// database. The key is only fetched from the keychain once something needs it.
+pub struct SessionEncryption {
+    enabled: bool,
+    cipher: OnceCell<SessionCipher>,
+}
+
+impl SessionEncryption {
+    pub fn from_config() -> Self {
+        Self {
+            enabled: Config::global()
+                .get_param(SESSION_ENCRYPTION)
+                .unwrap_or(false),
+            cipher: OnceCell::new(),
+        }
+    }
+
+    /// Encryption with a given key instead of the keychain's
+    pub fn with_cipher(enabled: bool, cipher: SessionCipher) -> Self {
+        Self {
+            enabled,
+            cipher: OnceCell::with_value(cipher),
+        }
+    }
+
+    fn cipher(&self) -> Result<&SessionCipher> {
+        self.cipher.get_or_try_init(|| {
+            let config = Config::global();
+            let key = match config.get_secret::<String>(KEY_SECRET) {
+                Ok(encoded) => decode_key(&encoded)?,
+                Err(ConfigError::NotFound(_)) if self.enabled => create_key(config)?,
+                Err(ConfigError::NotFound(_)) => {
+                    return Err(anyhow!(
+                        "Session content is encrypted but {} is not in the keychain",
+                        KEY_SECRET
+                    ))
+                }
+                Err(e) => return Err(e).context("Failed to read the session encryption key"),
+            };
+            SessionCipher::new(&key)
+        })
+    }
+
+    /// The value to store for `content`: encrypted when encryption is on, as it is otherwise
+    pub fn seal(&self, content: String) -> Result<String> {
+        if !self.enabled {
+            return Ok(content);
+        }
+        self.cipher()?.encrypt(&content)
+    }
+
+    /// The content of a stored value, decrypting it if it was stored encrypted
+    pub fn open(&self, stored: String) -> Result<String> {
+        if !is_encrypted(&stored) {
+            return Ok(stored);
+        }
+        self.cipher()?.decrypt(&stored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> SessionCipher {
+        SessionCipher::new(&SessionCipher::generate_key().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_round_trips_with_a_fresh_nonce() {
+        let cipher = cipher();
+        let first = cipher.encrypt("[{\"type\":\"text\"}]").unwrap();
+        let second = cipher.encrypt("[{\"type\":\"text\"}]").unwrap();
+        assert!(is_encrypted(&first));
+        assert_ne!(first, second);
+        assert_eq!(cipher.decrypt(&first).unwrap(), "[{\"type\":\"text\"}]");
+    }
+
+    #[test]
+    fn test_decrypt_fails_with_another_key_or_tampering() {
+        let sealed = cipher().encrypt("secret").unwrap();
+        assert!(cipher().decrypt(&sealed).is_err());
+        assert!(cipher().decrypt("enc:v1:AAAA").is_err());
+        assert!(cipher().decrypt("not encrypted").is_err());
+    }
+
+    #[test]
+    fn test_plain_content_passes_through() {
+        let disabled = SessionEncryption::with_cipher(false, cipher());
+        assert_eq!(disabled.seal("plain".to_string()).unwrap(), "plain");
+        assert_eq!(disabled.open("plain".to_string()).unwrap(), "plain");
+
+        let enabled = SessionEncryption::with_cipher(true, cipher());
+        let sealed = enabled.seal("plain".to_string()).unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(enabled.open(sealed).unwrap(), "plain");
+        assert_eq!(enabled.open("old".to_string()).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_keys_must_be_32_bytes() {
+        assert!(SessionCipher::new(&[0u8; 16]).is_err());
+    }
+}
@@ -9,6 +9,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use utoipa::ToSchema;
 
 /// Extension data containing all extension states
@@ -99,6 +100,106 @@ impl TodoState {
     }
 }
 
+/// Largest file that can be pinned. A pinned file goes into the conversation every time it is
+/// compacted, so only this much of a file that has grown since is included.
+pub const MAX_PINNED_FILE_BYTES: u64 = 64 * 1024;
+
+/// Messages and files pinned by the user, which compaction keeps word for word instead of
+/// summarizing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PinnedState {
+    pub pins: Vec<Pin>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Pin {
+    /// A message from the conversation, with its text as it was when pinned
+    Message { id: Option<String>, text: String },
+    /// A file, whose contents are read again each time the conversation is compacted
+    File { path: PathBuf },
+}
+
+impl ExtensionState for PinnedState {
+    const EXTENSION_NAME: &'static str = "pinned";
+    const VERSION: &'static str = "v0";
+}
+
+impl PinnedState {
+    /// Adds a pin unless it is already there. Returns whether it was added.
+    pub fn add(&mut self, pin: Pin) -> bool {
+        if self.pins.contains(&pin) {
+            return false;
+        }
+        self.pins.push(pin);
+        true
+    }
+
+    /// Removes the pin at a 1-based position, as listed by `/pin`
+    pub fn remove(&mut self, number: usize) -> Option<Pin> {
+        (1..=self.pins.len())
+            .contains(&number)
+            .then(|| self.pins.remove(number - 1))
+    }
+
+    pub fn is_message_pinned(&self, message_id: &str) -> bool {
+        self.pins
+            .iter()
+            .any(|pin| matches!(pin, Pin::Message { id: Some(id), .. } if id == message_id))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+}
+
+/// Subagents the session delegated tasks to. Each one's conversation is kept as a session of
+/// its own, under the subagent's id.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubagentsState {
+    pub subagents: Vec<SubagentRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubagentRecord {
+    pub session_id: String,
+    pub description: String,
+}
+
+impl ExtensionState for SubagentsState {
+    const EXTENSION_NAME: &'static str = "subagents";
+    const VERSION: &'static str = "v0";
+}
+
+impl SubagentsState {
+    /// The subagent with this session id, or whose id ends in `_<id>` as shown in the short form.
+    pub fn find(&self, id: &str) -> Option<&SubagentRecord> {
+        self.subagents.iter().find(|subagent| {
+            subagent.session_id == id
+                || subagent
+                    .session_id
+                    .strip_suffix(id)
+                    .is_some_and(|prefix| prefix.ends_with('_'))
+        })
+    }
+
+    /// Records a subagent under the session that delegated to it. Delegations can run in
+    /// parallel, so the state is updated in place rather than written back whole.
+    pub async fn record(
+        session_manager: &SessionManager,
+        session_id: &str,
+        subagent: SubagentRecord,
+    ) -> Result<()> {
+        session_manager
+            .update_extension_data(session_id, |extension_data| {
+                let mut state = Self::from_extension_data(extension_data).unwrap_or_default();
+                state.subagents.push(subagent);
+                state.to_extension_data(extension_data)
+            })
+            .await
+    }
+}
+
 /// Enabled extensions state implementation for storing which extensions are active
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnabledExtensionsState {
@@ -196,6 +297,26 @@ mod tests {
         );
     }
 
+    #[test_case("20250101_3", Some("20250101_3") ; "full id")]
+    #[test_case("3", Some("20250101_3") ; "short id")]
+    #[test_case("13", None ; "only a suffix of the number")]
+    #[test_case("4", None ; "unknown")]
+    fn test_find_subagent(id: &str, expected: Option<&str>) {
+        let state = SubagentsState {
+            subagents: ["20250101_3", "20250101_113"]
+                .iter()
+                .map(|id| SubagentRecord {
+                    session_id: id.to_string(),
+                    description: "task".to_string(),
+                })
+                .collect(),
+        };
+        assert_eq!(
+            state.find(id).map(|subagent| subagent.session_id.as_str()),
+            expected
+        );
+    }
+
     #[test]
     fn test_extension_data_basic_operations() {
         let mut extension_data = ExtensionData::new();
@@ -299,4 +420,34 @@ mod tests {
             .iter()
             .any(|name| name == "definitely_not_real_platform_extension"));
     }
+
+    #[test]
+    fn test_pinned_state() {
+        let mut pinned = PinnedState::default();
+        let message = Pin::Message {
+            id: Some("msg_1".to_string()),
+            text: "Always use tabs".to_string(),
+        };
+        assert!(pinned.add(message.clone()));
+        assert!(!pinned.add(message));
+        assert!(pinned.add(Pin::File {
+            path: PathBuf::from("NOTES.md"),
+        }));
+        assert!(pinned.is_message_pinned("msg_1"));
+        assert!(!pinned.is_message_pinned("msg_2"));
+
+        let mut extension_data = ExtensionData::new();
+        pinned.to_extension_data(&mut extension_data).unwrap();
+        assert_eq!(
+            extension_data.get_extension_state("pinned", "v0"),
+            Some(&json!({"pins": [
+                {"kind": "message", "id": "msg_1", "text": "Always use tabs"},
+                {"kind": "file", "path": "NOTES.md"},
+            ]}))
+        );
+
+        assert_eq!(pinned.remove(3), None);
+        assert!(matches!(pinned.remove(1), Some(Pin::Message { .. })));
+        assert!(!pinned.is_message_pinned("msg_1"));
+    }
 }
@@ -1,11 +1,17 @@
+pub mod bundle;
 mod chat_history_search;
 mod diagnostics;
+pub mod encryption;
 pub mod extension_data;
 mod legacy;
 pub mod session_manager;
 
+pub use bundle::{BundleManifest, SessionBundle};
 pub use diagnostics::{generate_diagnostics, get_system_info, SystemInfo};
-pub use extension_data::{EnabledExtensionsState, ExtensionData, ExtensionState, TodoState};
+pub use extension_data::{
+    EnabledExtensionsState, ExtensionData, ExtensionState, Pin, PinnedState, SubagentRecord,
+    SubagentsState, TodoState, MAX_PINNED_FILE_BYTES,
+};
 pub use session_manager::{
     Session, SessionInsights, SessionManager, SessionType, SessionUpdateBuilder,
 };
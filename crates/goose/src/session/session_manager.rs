@@ -4,6 +4,7 @@ use crate::conversation::Conversation;
 use crate::model::ModelConfig;
 use crate::providers::base::{Provider, MSG_COUNT_FOR_SESSION_NAME_GENERATION};
 use crate::recipe::Recipe;
+use crate::session::encryption::SessionEncryption;
 use crate::session::extension_data::ExtensionData;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -364,6 +365,15 @@ impl SessionManager {
             .await
     }
 
+    /// Changes the session's extension data in a single transaction, so updates of different
+    /// extension states made at the same time don't overwrite each other.
+    pub async fn update_extension_data<F>(&self, id: &str, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut ExtensionData) -> Result<()>,
+    {
+        self.storage.update_extension_data(id, f).await
+    }
+
     pub async fn update_message_metadata<F>(id: &str, message_id: &str, f: F) -> Result<()>
     where
         F: FnOnce(
@@ -381,6 +391,7 @@ pub struct SessionStorage {
     pool: Pool<Sqlite>,
     initialized: tokio::sync::OnceCell<()>,
     session_dir: PathBuf,
+    encryption: SessionEncryption,
 }
 
 fn role_to_string(role: &Role) -> &'static str {
@@ -504,6 +515,7 @@ impl SessionStorage {
             pool: Self::create_pool(&db_path),
             initialized: tokio::sync::OnceCell::new(),
             session_dir,
+            encryption: SessionEncryption::from_config(),
         }
     }
 
@@ -521,7 +533,9 @@ impl SessionStorage {
                     Self::run_migrations(&self.pool).await?;
                 } else {
                     Self::create_schema(&self.pool).await?;
-                    if let Err(e) = Self::import_legacy(&self.pool, &self.session_dir).await {
+                    if let Err(e) =
+                        Self::import_legacy(&self.pool, &self.session_dir, &self.encryption).await
+                    {
                         warn!("Failed to import some legacy sessions: {}", e);
                     }
                 }
@@ -620,7 +634,11 @@ impl SessionStorage {
         Ok(())
     }
 
-    async fn import_legacy(pool: &Pool<Sqlite>, session_dir: &PathBuf) -> Result<()> {
+    async fn import_legacy(
+        pool: &Pool<Sqlite>,
+        session_dir: &PathBuf,
+        encryption: &SessionEncryption,
+    ) -> Result<()> {
         use crate::session::legacy;
 
         let sessions = match legacy::list_sessions(session_dir) {
@@ -640,16 +658,18 @@ impl SessionStorage {
 
         for (session_name, session_path) in sessions {
             match legacy::load_session(&session_name, &session_path) {
-                Ok(session) => match Self::import_legacy_session(pool, &session).await {
-                    Ok(_) => {
-                        imported_count += 1;
-                        info!("  ✓ Imported: {}", session_name);
-                    }
-                    Err(e) => {
-                        failed_count += 1;
-                        info!("  ✗ Failed to import {}: {}", session_name, e);
+                Ok(session) => {
+                    match Self::import_legacy_session(pool, &session, encryption).await {
+                        Ok(_) => {
+                            imported_count += 1;
+                            info!("  ✓ Imported: {}", session_name);
+                        }
+                        Err(e) => {
+                            failed_count += 1;
+                            info!("  ✗ Failed to import {}: {}", session_name, e);
+                        }
                     }
-                },
+                }
                 Err(e) => {
                     failed_count += 1;
                     info!("  ✗ Failed to load {}: {}", session_name, e);
@@ -664,7 +684,11 @@ impl SessionStorage {
         Ok(())
     }
 
-    async fn import_legacy_session(pool: &Pool<Sqlite>, session: &Session) -> Result<()> {
+    async fn import_legacy_session(
+        pool: &Pool<Sqlite>,
+        session: &Session,
+        encryption: &SessionEncryption,
+    ) -> Result<()> {
         let mut tx = pool.begin().await?;
 
         let recipe_json = match &session.recipe {
@@ -718,7 +742,7 @@ impl SessionStorage {
         tx.commit().await?;
 
         if let Some(conversation) = &session.conversation {
-            Self::replace_conversation_inner(pool, &session.id, conversation).await?;
+            Self::replace_conversation_inner(pool, encryption, &session.id, conversation).await?;
         }
         Ok(())
     }
@@ -1098,7 +1122,7 @@ impl SessionStorage {
                 _ => continue,
             };
 
-            let content = serde_json::from_str(&content_json)?;
+            let content = serde_json::from_str(&self.encryption.open(content_json)?)?;
             let metadata = metadata_json
                 .and_then(|json| serde_json::from_str(&json).ok())
                 .unwrap_or_default();
@@ -1134,7 +1158,7 @@ impl SessionStorage {
         .bind(message_id)
         .bind(session_id)
         .bind(role_to_string(&message.role))
-        .bind(serde_json::to_string(&message.content)?)
+        .bind(self.encryption.seal(serde_json::to_string(&message.content)?)?)
         .bind(message.created)
         .bind(metadata_json)
         .execute(&mut *tx)
@@ -1151,6 +1175,7 @@ impl SessionStorage {
 
     async fn replace_conversation_inner(
         pool: &Pool<Sqlite>,
+        encryption: &SessionEncryption,
         session_id: &str,
         conversation: &Conversation,
     ) -> Result<()> {
@@ -1178,7 +1203,7 @@ impl SessionStorage {
             .bind(message_id)
             .bind(session_id)
             .bind(role_to_string(&message.role))
-            .bind(serde_json::to_string(&message.content)?)
+            .bind(encryption.seal(serde_json::to_string(&message.content)?)?)
             .bind(message.created)
             .bind(metadata_json)
             .execute(&mut *tx)
@@ -1195,7 +1220,7 @@ impl SessionStorage {
         conversation: &Conversation,
     ) -> Result<()> {
         let pool = self.pool().await?;
-        Self::replace_conversation_inner(pool, session_id, conversation).await
+        Self::replace_conversation_inner(pool, &self.encryption, session_id, conversation).await
     }
 
     async fn list_sessions_by_types(&self, types: &[SessionType]) -> Result<Vec<Session>> {
@@ -1404,6 +1429,39 @@ impl SessionStorage {
         .await
     }
 
+    async fn update_extension_data<F>(&self, session_id: &str, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut ExtensionData) -> Result<()>,
+    {
+        let pool = self.pool().await?;
+        let mut tx = pool.begin().await?;
+
+        // writing before reading takes the write lock, so no other update reads the extension
+        // data until this one has committed
+        sqlx::query("UPDATE sessions SET updated_at = datetime('now') WHERE id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+        let extension_data_json =
+            sqlx::query_scalar::<_, String>("SELECT extension_data FROM sessions WHERE id = ?")
+                .bind(session_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        let mut extension_data: ExtensionData = serde_json::from_str(&extension_data_json)?;
+        f(&mut extension_data)?;
+
+        sqlx::query("UPDATE sessions SET extension_data = ? WHERE id = ?")
+            .bind(serde_json::to_string(&extension_data)?)
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     async fn update_message_metadata<F>(
         &self,
         session_id: &str,
@@ -1539,6 +1597,49 @@ mod tests {
         assert_eq!(insights.total_tokens, expected_tokens as i64);
     }
 
+    #[tokio::test]
+    async fn test_concurrent_extension_data_updates_are_all_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let session_manager = Arc::new(SessionManager::new(temp_dir.path().to_path_buf()));
+        let session = session_manager
+            .create_session(
+                PathBuf::from("/tmp/extension_data"),
+                "Extension data".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+
+        let handles: Vec<_> = (0..NUM_CONCURRENT_SESSIONS)
+            .map(|i| {
+                let sm = Arc::clone(&session_manager);
+                let id = session.id.clone();
+                tokio::spawn(async move {
+                    sm.update_extension_data(&id, |data| {
+                        data.set_extension_state(&format!("state{}", i), "v0", i.into());
+                        Ok(())
+                    })
+                    .await
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let extension_data = session_manager
+            .get_session(&session.id, false)
+            .await
+            .unwrap()
+            .extension_data;
+        for i in 0..NUM_CONCURRENT_SESSIONS {
+            assert_eq!(
+                extension_data.get_extension_state(&format!("state{}", i), "v0"),
+                Some(&serde_json::Value::from(i))
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_export_import_roundtrip() {
         const DESCRIPTION: &str = "Original session";
@@ -1614,6 +1715,50 @@ mod tests {
         assert_eq!(conversation.messages()[1].role, Role::Assistant);
     }
 
+    #[tokio::test]
+    async fn test_encrypted_messages_are_stored_sealed_and_read_back() {
+        use crate::session::encryption::{is_encrypted, SessionCipher};
+
+        let temp_dir = TempDir::new().unwrap();
+        let key = SessionCipher::generate_key().unwrap();
+        let mut storage = SessionStorage::new(temp_dir.path().to_path_buf());
+        storage.encryption =
+            SessionEncryption::with_cipher(true, SessionCipher::new(&key).unwrap());
+        let sm = SessionManager {
+            storage: Arc::new(storage),
+        };
+
+        let session = sm
+            .create_session(
+                PathBuf::from("/tmp/test"),
+                "secret".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        sm.add_message(&session.id, &Message::user().with_text("api key sk-123"))
+            .await
+            .unwrap();
+
+        let pool = sm.storage.pool().await.unwrap();
+        let stored: String =
+            sqlx::query_scalar("SELECT content_json FROM messages WHERE session_id = ?")
+                .bind(&session.id)
+                .fetch_one(pool)
+                .await
+                .unwrap();
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("sk-123"));
+
+        let exported = sm.export_session(&session.id).await.unwrap();
+        assert!(exported.contains("api key sk-123"));
+        let loaded = sm.get_session(&session.id, true).await.unwrap();
+        assert_eq!(
+            loaded.conversation.unwrap().messages()[0].as_concat_text(),
+            "api key sk-123"
+        );
+    }
+
     #[tokio::test]
     async fn test_import_session_with_description_field() {
         const OLD_FORMAT_JSON: &str = r#"{
@@ -0,0 +1,314 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use rmcp::model::CallToolRequestParams;
+use serde_json::Value;
+use similar::TextDiff;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::GooseMode;
+use crate::conversation::message::{Message, ToolRequest};
+use crate::tool_inspection::{InspectionAction, InspectionResult, ToolInspector};
+
+const TEXT_EDITOR_TOOL: &str = "developer__text_editor";
+const SHELL_TOOL: &str = "developer__shell";
+
+/// Text editor commands that change the file based on what the agent read of it
+const EDITING_COMMANDS: [&str; 3] = ["write", "str_replace", "insert"];
+
+/// Files larger than this aren't remembered, so edits to them aren't checked
+const MAX_TRACKED_BYTES: u64 = 2 * 1024 * 1024;
+
+/// First line of the approval prompt for an edit to a file that changed on disk, which clients
+/// can look for to offer re-reading the file instead of only allowing or denying the edit
+pub const STALE_FILE_ALERT: &str = "⚠️ File changed on disk";
+
+/// Headings of the two diffs in the prompt: the changes on disk and the changes of the edit,
+/// each against the version the agent last read
+pub const STALE_FILE_DISK_HEADING: &str = "Changed on disk:";
+pub const STALE_FILE_EDIT_HEADING: &str = "goose's edit:";
+
+/// What the agent is told when the user chooses to have it read the file again
+pub const STALE_FILE_RETRY_RESPONSE: &str = "The file changed on disk since you last read it, \
+                                             so the edit was not applied. View the file again \
+                                             and redo the edit against what it contains now.";
+
+/// Whether an approval prompt is for an edit to a file that changed on disk
+pub fn is_stale_file_alert(prompt: &str) -> bool {
+    prompt.starts_with(STALE_FILE_ALERT)
+}
+
+/// Asks before the text editor changes a file that was changed on disk since the agent last
+/// viewed or edited it, e.g. by the user in their editor, so the edit doesn't silently
+/// overwrite their work or fail on text that has moved. The prompt shows both sides against
+/// the version the agent knows: what changed on disk and what the edit would change.
+#[derive(Default)]
+pub struct StaleFileInspector {
+    /// Each file's content as it was when the agent last used the text editor on it
+    seen: Mutex<HashMap<PathBuf, Vec<u8>>>,
+}
+
+fn text_editor_call(request: &ToolRequest) -> Option<(&CallToolRequestParams, &str, PathBuf)> {
+    let call = request.tool_call.as_ref().ok()?;
+    if call.name != TEXT_EDITOR_TOOL {
+        return None;
+    }
+    let args = call.arguments.as_ref()?;
+    let command = args.get("command")?.as_str()?;
+    let path = PathBuf::from(args.get("path")?.as_str()?);
+    Some((call, command, path))
+}
+
+fn is_shell_call(request: &ToolRequest) -> bool {
+    request
+        .tool_call
+        .as_ref()
+        .is_ok_and(|call| call.name == SHELL_TOOL)
+}
+
+fn read_tracked(path: &Path) -> Option<Vec<u8>> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_TRACKED_BYTES {
+        return None;
+    }
+    std::fs::read(path).ok()
+}
+
+/// The file as the edit would leave it when applied to `base`, if that can be worked out
+/// from the call's arguments
+fn proposed_text(call: &CallToolRequestParams, command: &str, base: &str) -> Option<String> {
+    let args = call.arguments.as_ref()?;
+    let str_arg = |key: &str| args.get(key).and_then(Value::as_str);
+    match command {
+        "write" => str_arg("file_text").map(str::to_string),
+        "str_replace" => {
+            let (old, new) = (str_arg("old_str")?, str_arg("new_str")?);
+            (base.matches(old).count() == 1).then(|| base.replacen(old, new, 1))
+        }
+        "insert" => {
+            let line = usize::try_from(args.get("insert_line")?.as_i64()?).ok()?;
+            let mut lines: Vec<&str> = base.lines().collect();
+            if line > lines.len() {
+                return None;
+            }
+            lines.insert(line, str_arg("new_str")?);
+            Some(lines.join("\n") + "\n")
+        }
+        _ => None,
+    }
+}
+
+fn unified_diff(before: &str, after: &str, path: &str) -> String {
+    TextDiff::from_lines(before, after)
+        .unified_diff()
+        .context_radius(2)
+        .header(&format!("{} (last read)", path), &format!("{} (new)", path))
+        .to_string()
+}
+
+/// The prompt for an edit to `path`, with the changes made on disk since `base` was read and
+/// the changes the edit makes to it
+fn alert(
+    call: &CallToolRequestParams,
+    command: &str,
+    path: &str,
+    base: &str,
+    disk: &str,
+) -> String {
+    let edit = match call.arguments.as_ref().and_then(|args| args.get("diff")) {
+        Some(diff) => diff.as_str().unwrap_or_default().to_string(),
+        None => match proposed_text(call, command, base) {
+            Some(proposed) => unified_diff(base, &proposed, path),
+            None => "(can't be applied to the version it was written for)".to_string(),
+        },
+    };
+    format!(
+        "{}\n\n{} changed since goose last read it.\n\n{}\n{}\n{}\n{}",
+        STALE_FILE_ALERT,
+        path,
+        STALE_FILE_DISK_HEADING,
+        unified_diff(base, disk, path),
+        STALE_FILE_EDIT_HEADING,
+        edit
+    )
+}
+
+impl StaleFileInspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ToolInspector for StaleFileInspector {
+    fn name(&self) -> &'static str {
+        "stale_file"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn inspect(
+        &self,
+        tool_requests: &[ToolRequest],
+        _messages: &[Message],
+        _goose_mode: GooseMode,
+        _working_dir: &Path,
+    ) -> Result<Vec<InspectionResult>> {
+        let seen = self.seen.lock().unwrap();
+        let mut results = Vec::new();
+        for request in tool_requests {
+            let Some((call, command, path)) = text_editor_call(request) else {
+                continue;
+            };
+            if !EDITING_COMMANDS.contains(&command) {
+                continue;
+            }
+            let Some(base) = seen.get(&path) else {
+                continue;
+            };
+            let disk = std::fs::read(&path).ok();
+            if disk.as_ref() == Some(base) {
+                continue;
+            }
+            let base = String::from_utf8_lossy(base);
+            let disk = disk
+                .map(|disk| String::from_utf8_lossy(&disk).into_owned())
+                .unwrap_or_default();
+            let path = path.display().to_string();
+            results.push(InspectionResult {
+                tool_request_id: request.id.clone(),
+                action: InspectionAction::RequireApproval(Some(alert(
+                    call, command, &path, &base, &disk,
+                ))),
+                reason: format!("{} changed on disk since it was last read", path),
+                confidence: 1.0,
+                inspector_name: self.name().to_string(),
+                finding_id: None,
+            });
+        }
+        Ok(results)
+    }
+
+    fn record_result(&self, request: &ToolRequest, succeeded: bool) {
+        if !succeeded {
+            return;
+        }
+        let mut seen = self.seen.lock().unwrap();
+        let paths: Vec<PathBuf> = match text_editor_call(request) {
+            Some((_, _, path)) => vec![path],
+            // files the agent changed with commands of its own, like a formatter, don't count
+            // as changed behind its back
+            None if is_shell_call(request) => seen.keys().cloned().collect(),
+            None => return,
+        };
+        for path in paths {
+            match read_tracked(&path) {
+                Some(content) => seen.insert(path, content),
+                None => seen.remove(&path),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::object;
+    use test_case::test_case;
+
+    fn editor_request(id: &str, args: Value) -> ToolRequest {
+        ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(CallToolRequestParams {
+                meta: None,
+                task: None,
+                name: TEXT_EDITOR_TOOL.into(),
+                arguments: args.as_object().cloned(),
+            }),
+            metadata: None,
+            tool_meta: None,
+        }
+    }
+
+    #[test_case("str_replace", object!({"old_str": "b\n", "new_str": "B\n"}), Some("a\nB\nc\n") ; "replace")]
+    #[test_case("str_replace", object!({"old_str": "x", "new_str": "y"}), None ; "replace missing")]
+    #[test_case("insert", object!({"insert_line": 1, "new_str": "ab"}), Some("a\nab\nb\nc\n") ; "insert")]
+    #[test_case("write", object!({"file_text": "new\n"}), Some("new\n") ; "write")]
+    fn test_proposed_text(command: &str, args: rmcp::model::JsonObject, expected: Option<&str>) {
+        let call = CallToolRequestParams {
+            meta: None,
+            task: None,
+            name: TEXT_EDITOR_TOOL.into(),
+            arguments: Some(args),
+        };
+        assert_eq!(
+            proposed_text(&call, command, "a\nb\nc\n").as_deref(),
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_after_the_file_changed_needs_approval() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        let path = file.to_str().unwrap();
+        std::fs::write(&file, "fn main() {\n    old();\n}\n").unwrap();
+        let inspector = StaleFileInspector::new();
+        inspector.record_result(
+            &editor_request("1", serde_json::json!({"command": "view", "path": path})),
+            true,
+        );
+
+        let edit = editor_request(
+            "2",
+            serde_json::json!({
+                "command": "str_replace",
+                "path": path,
+                "old_str": "old();",
+                "new_str": "new();"
+            }),
+        );
+        let results = inspector
+            .inspect(
+                std::slice::from_ref(&edit),
+                &[],
+                GooseMode::Auto,
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+
+        std::fs::write(&file, "fn main() {\n    old();\n    theirs();\n}\n").unwrap();
+        let results = inspector
+            .inspect(
+                std::slice::from_ref(&edit),
+                &[],
+                GooseMode::Auto,
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        let InspectionAction::RequireApproval(Some(prompt)) = &results[0].action else {
+            panic!("expected approval, got {:?}", results[0].action);
+        };
+        assert!(is_stale_file_alert(prompt));
+        assert!(prompt.contains("+    theirs();"));
+        assert!(prompt.contains("-    old();\n+    new();"));
+
+        inspector.record_result(&edit, true);
+        let results = inspector
+            .inspect(
+                std::slice::from_ref(&edit),
+                &[],
+                GooseMode::Auto,
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+}
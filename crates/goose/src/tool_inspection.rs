@@ -1,6 +1,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::config::GooseMode;
 use crate::conversation::message::{Message, ToolRequest};
@@ -35,12 +36,14 @@ pub trait ToolInspector: Send + Sync {
     /// Name of this inspector (for logging/debugging)
     fn name(&self) -> &'static str;
 
-    /// Inspect tool requests and return results
+    /// Inspect tool requests and return results. `working_dir` is the session's, which the
+    /// tools run in.
     async fn inspect(
         &self,
         tool_requests: &[ToolRequest],
         messages: &[Message],
         goose_mode: GooseMode,
+        working_dir: &Path,
     ) -> Result<Vec<InspectionResult>>;
 
     /// Whether this inspector is enabled
@@ -48,6 +51,10 @@ pub trait ToolInspector: Send + Sync {
         true
     }
 
+    /// Called with each tool call that ran once its result is in, for inspectors that keep
+    /// track of what earlier calls did
+    fn record_result(&self, _tool_request: &ToolRequest, _succeeded: bool) {}
+
     /// Allow downcasting to concrete types
     fn as_any(&self) -> &dyn std::any::Any;
 }
@@ -76,6 +83,7 @@ impl ToolInspectionManager {
         tool_requests: &[ToolRequest],
         messages: &[Message],
         goose_mode: GooseMode,
+        working_dir: &Path,
     ) -> Result<Vec<InspectionResult>> {
         let mut all_results = Vec::new();
 
@@ -90,7 +98,10 @@ impl ToolInspectionManager {
                 "Running tool inspector"
             );
 
-            match inspector.inspect(tool_requests, messages, goose_mode).await {
+            match inspector
+                .inspect(tool_requests, messages, goose_mode, working_dir)
+                .await
+            {
                 Ok(results) => {
                     tracing::debug!(
                         inspector_name = inspector.name(),
@@ -113,6 +124,15 @@ impl ToolInspectionManager {
         Ok(all_results)
     }
 
+    /// Tell every inspector how a tool call that ran turned out
+    pub fn record_result(&self, tool_request: &ToolRequest, succeeded: bool) {
+        for inspector in &self.inspectors {
+            if inspector.is_enabled() {
+                inspector.record_result(tool_request, succeeded);
+            }
+        }
+    }
+
     /// Get list of registered inspector names
     pub fn inspector_names(&self) -> Vec<&'static str> {
         self.inspectors.iter().map(|i| i.name()).collect()
@@ -266,6 +286,34 @@ pub fn get_security_finding_id_from_results(
         .and_then(|result| result.finding_id.clone())
 }
 
+/// What the user should be told when asked to approve this tool request: the messages of every
+/// inspector that asked with one, such as a stale file or network warning. Inspectors that
+/// only require approval, like the permission inspector, don't hide them whatever the order.
+pub fn approval_message_from_results(
+    tool_request_id: &str,
+    inspection_results: &[InspectionResult],
+) -> Option<String> {
+    let messages: Vec<&str> = inspection_results
+        .iter()
+        .filter(|result| result.tool_request_id == tool_request_id)
+        .filter_map(|result| match &result.action {
+            InspectionAction::RequireApproval(Some(message)) => Some(message.as_str()),
+            _ => None,
+        })
+        .collect();
+    (!messages.is_empty()).then(|| messages.join("\n\n"))
+}
+
+/// Whether the stale file inspector asked about this tool request
+pub fn is_stale_file_finding(
+    tool_request_id: &str,
+    inspection_results: &[InspectionResult],
+) -> bool {
+    inspection_results.iter().any(|result| {
+        result.tool_request_id == tool_request_id && result.inspector_name == "stale_file"
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,4 +357,33 @@ mod tests {
         assert_eq!(updated_result.denied.len(), 1);
         assert_eq!(updated_result.denied[0].id, "req_1");
     }
+
+    #[test]
+    fn test_approval_message_is_not_hidden_by_plain_approval() {
+        let result = |inspector: &str, request: &str, message: Option<&str>| InspectionResult {
+            tool_request_id: request.to_string(),
+            action: InspectionAction::RequireApproval(message.map(str::to_string)),
+            reason: String::new(),
+            confidence: 1.0,
+            inspector_name: inspector.to_string(),
+            finding_id: None,
+        };
+        let results = vec![
+            result("permission", "req_1", None),
+            result("stale_file", "req_1", Some("file changed")),
+            result("network", "req_1", Some("connects to a.dev")),
+            result("network", "req_2", Some("connects to b.dev")),
+            result("permission", "req_3", None),
+        ];
+
+        assert_eq!(
+            approval_message_from_results("req_1", &results).as_deref(),
+            Some("file changed\n\nconnects to a.dev")
+        );
+        assert_eq!(
+            approval_message_from_results("req_2", &results).as_deref(),
+            Some("connects to b.dev")
+        );
+        assert_eq!(approval_message_from_results("req_3", &results), None);
+    }
 }
@@ -6,6 +6,7 @@ use async_trait::async_trait;
 use rmcp::model::CallToolRequestParams;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
 
 // Helper struct for internal tracking
 #[derive(Debug, Clone)]
@@ -101,6 +102,7 @@ impl ToolInspector for RepetitionInspector {
         tool_requests: &[ToolRequest],
         _messages: &[Message],
         _goose_mode: GooseMode,
+        _working_dir: &Path,
     ) -> Result<Vec<InspectionResult>> {
         let mut results = Vec::new();
 
@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use tokio_util::sync::CancellationToken;
 use unicode_normalization::UnicodeNormalization;
 
@@ -41,6 +42,14 @@ pub fn safe_truncate(s: &str, max_chars: usize) -> String {
     }
 }
 
+/// The repository root containing `dir`, or `dir` itself outside a git repository
+pub fn project_root(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
 pub fn is_token_cancelled(cancellation_token: &Option<CancellationToken>) -> bool {
     cancellation_token
         .as_ref()
@@ -509,6 +509,146 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod approval_prompt_tests {
+        use super::*;
+        use async_trait::async_trait;
+        use goose::agents::{AgentConfig, SessionConfig};
+        use goose::config::permission::PermissionManager;
+        use goose::config::GooseMode;
+        use goose::conversation::message::{ActionRequiredData, Message, MessageContent};
+        use goose::model::ModelConfig;
+        use goose::network_inspector::is_network_alert;
+        use goose::permission::permission_confirmation::PrincipalType;
+        use goose::permission::{Permission, PermissionConfirmation};
+        use goose::providers::base::{Provider, ProviderUsage, Usage};
+        use goose::providers::errors::ProviderError;
+        use goose::session::session_manager::SessionType;
+        use goose::session::SessionManager;
+        use rmcp::model::{CallToolRequestParams, Tool};
+        use rmcp::object;
+        use tempfile::TempDir;
+
+        /// Asks to fetch a URL on every turn
+        struct FetchProvider;
+
+        #[async_trait]
+        impl Provider for FetchProvider {
+            async fn complete(
+                &self,
+                _session_id: &str,
+                _system_prompt: &str,
+                _messages: &[Message],
+                _tools: &[Tool],
+            ) -> Result<(Message, ProviderUsage), ProviderError> {
+                let tool_call = CallToolRequestParams {
+                    meta: None,
+                    task: None,
+                    name: "fetch__fetch".into(),
+                    arguments: Some(object!({"url": "https://prompt-check.example/docs"})),
+                };
+                Ok((
+                    Message::assistant().with_tool_request("fetch_1", Ok(tool_call)),
+                    ProviderUsage::new(
+                        "mock-model".to_string(),
+                        Usage::new(Some(10), Some(5), Some(15)),
+                    ),
+                ))
+            }
+
+            async fn complete_with_model(
+                &self,
+                session_id: Option<&str>,
+                _model_config: &ModelConfig,
+                system_prompt: &str,
+                messages: &[Message],
+                tools: &[Tool],
+            ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+                self.complete(session_id.unwrap_or(""), system_prompt, messages, tools)
+                    .await
+            }
+
+            fn get_model_config(&self) -> ModelConfig {
+                ModelConfig::new("mock-model").unwrap()
+            }
+
+            fn get_name(&self) -> &str {
+                "mock-fetch"
+            }
+        }
+
+        #[tokio::test]
+        async fn test_network_warning_shows_when_permission_also_asks() -> Result<()> {
+            let _guard = env_lock::lock_env([("GOOSE_NETWORK_PERMISSIONS", Some("true"))]);
+            let temp_dir = TempDir::new()?;
+            let data_dir = temp_dir.path().to_path_buf();
+            // approve mode makes the permission inspector ask too, with no message of its own
+            let agent = Agent::with_config(AgentConfig::new(
+                Arc::new(SessionManager::new(data_dir.clone())),
+                Arc::new(PermissionManager::new(data_dir)),
+                None,
+                GooseMode::Approve,
+                true,
+                GoosePlatform::GooseCli,
+            ));
+            let session = agent
+                .config
+                .session_manager
+                .create_session(
+                    temp_dir.path().to_path_buf(),
+                    "approval-prompt-test".to_string(),
+                    SessionType::Hidden,
+                )
+                .await?;
+            agent
+                .update_provider(Arc::new(FetchProvider), &session.id)
+                .await?;
+
+            let session_config = SessionConfig {
+                id: session.id,
+                schedule_id: None,
+                max_turns: Some(1),
+                retry_config: None,
+            };
+            let reply_stream = agent
+                .reply(
+                    Message::user().with_text("read the docs"),
+                    session_config,
+                    None,
+                )
+                .await?;
+            tokio::pin!(reply_stream);
+
+            let mut prompts = Vec::new();
+            while let Some(event) = reply_stream.next().await {
+                let AgentEvent::Message(message) = event? else {
+                    continue;
+                };
+                let Some(MessageContent::ActionRequired(action)) = message.content.first() else {
+                    continue;
+                };
+                if let ActionRequiredData::ToolConfirmation { id, prompt, .. } = &action.data {
+                    prompts.push(prompt.clone());
+                    agent
+                        .handle_confirmation(
+                            id.clone(),
+                            PermissionConfirmation {
+                                principal_type: PrincipalType::Tool,
+                                permission: Permission::DenyOnce,
+                            },
+                        )
+                        .await;
+                }
+            }
+
+            assert_eq!(prompts.len(), 1);
+            let prompt = prompts[0].as_deref().expect("the prompt should warn");
+            assert!(is_network_alert(prompt), "unexpected prompt: {}", prompt);
+            assert!(prompt.contains("prompt-check.example"));
+            Ok(())
+        }
+    }
+
     #[cfg(test)]
     mod extension_manager_tests {
         use super::*;
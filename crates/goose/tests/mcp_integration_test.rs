@@ -12,6 +12,7 @@ use tokio_util::sync::CancellationToken;
 
 use goose::agents::extension::{Envs, ExtensionConfig};
 use goose::agents::extension_manager::{ExtensionManager, ExtensionManagerCapabilities};
+use goose::agents::sampling::{self, SamplingPolicy};
 use goose::agents::GoosePlatform;
 use goose::model::ModelConfig;
 
@@ -180,6 +181,8 @@ async fn test_replayed_session(
     required_envs: Vec<&str>,
 ) {
     std::env::set_var("GOOSE_MCP_CLIENT_VERSION", "0.0.0");
+    // the everything server's sampling tool is answered by the mock provider
+    sampling::set_policy(SamplingPolicy::Allow);
 
     // Setup test file for developer extension tests
     let test_file_path = "/tmp/goose_test/goose.txt";
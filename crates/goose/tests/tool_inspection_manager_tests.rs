@@ -5,6 +5,7 @@ use goose::conversation::message::{Message, ToolRequest};
 use goose::tool_inspection::{
     InspectionAction, InspectionResult, ToolInspectionManager, ToolInspector,
 };
+use std::path::Path;
 
 struct MockInspectorOk {
     name: &'static str,
@@ -28,6 +29,7 @@ impl ToolInspector for MockInspectorOk {
         _tool_requests: &[ToolRequest],
         _messages: &[Message],
         _goose_mode: GooseMode,
+        _working_dir: &Path,
     ) -> Result<Vec<InspectionResult>> {
         Ok(self.results.clone())
     }
@@ -46,6 +48,7 @@ impl ToolInspector for MockInspectorErr {
         _tool_requests: &[ToolRequest],
         _messages: &[Message],
         _goose_mode: GooseMode,
+        _working_dir: &Path,
     ) -> Result<Vec<InspectionResult>> {
         Err(anyhow!("simulated failure"))
     }
@@ -86,7 +89,12 @@ async fn test_inspect_tools_aggregates_and_handles_errors() {
 
     // Act
     let results = manager
-        .inspect_tools(&tool_requests, &messages, GooseMode::Approve)
+        .inspect_tools(
+            &tool_requests,
+            &messages,
+            GooseMode::Approve,
+            Path::new("."),
+        )
         .await
         .expect("inspect_tools should not fail when one inspector errors");
 